@@ -1,4 +1,4 @@
-use db::models::{AccessToken, Scopes, TokenIssuer};
+use db::models::{AccessToken, Scopes, TokenIssuer, UserSession};
 
 use chrono::Duration;
 use jwt::{decode, encode, errors, Header, TokenData, Validation};
@@ -43,4 +43,22 @@ impl TokenIssuer for DefaultTokenIssuer {
             AccessToken::new_limited_scope(user_id, self.token_issuer.to_string(), expires.num_minutes(), scopes);
         encode(&Header::default(), &access_token_claims, self.token_secret.as_bytes())
     }
+
+    fn issue_for_session(&self, session: &UserSession, expires: Duration) -> Result<String, errors::Error> {
+        let access_token_claims =
+            AccessToken::new(session.user_id, self.token_issuer.to_string(), expires.num_minutes())
+                .for_session(session.id, None);
+        encode(&Header::default(), &access_token_claims, self.token_secret.as_bytes())
+    }
+
+    fn issue_refresh_for_session(&self, session: &UserSession, expires: Duration) -> Result<String, errors::Error> {
+        let refresh_token_claims = AccessToken::new_limited_scope(
+            session.user_id,
+            self.token_issuer.to_string(),
+            expires.num_minutes(),
+            vec![Scopes::TokenRefresh],
+        )
+        .for_session(session.id, Some(session.refresh_token_id));
+        encode(&Header::default(), &refresh_token_claims, self.token_secret.as_bytes())
+    }
 }
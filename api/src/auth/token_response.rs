@@ -1,13 +1,14 @@
 use crate::errors::ApiError;
+use crate::models::RequestInfo;
 use actix_web::Error;
 use actix_web::HttpRequest;
 use actix_web::HttpResponse;
 use actix_web::Responder;
 use chrono::Duration;
-use db::models::{Scopes, TokenIssuer, User};
+use db::models::{TokenIssuer, User, UserSession};
+use diesel::PgConnection;
 use futures::future::{err, ok, Ready};
 use serde_json;
-use uuid::Uuid;
 
 #[derive(Serialize, Deserialize)]
 pub struct TokenResponse {
@@ -35,21 +36,44 @@ impl TokenResponse {
         }
     }
 
-    pub fn create_from_user(token_issuer: &dyn TokenIssuer, expires: Duration, user: &User) -> Result<Self, ApiError> {
+    /// Starts a new session for the user and issues its tokens
+    pub fn create_from_user(
+        token_issuer: &dyn TokenIssuer,
+        expires: Duration,
+        user: &User,
+        request_info: &RequestInfo,
+        conn: &PgConnection,
+    ) -> Result<Self, ApiError> {
+        let session = UserSession::create(
+            user.id,
+            request_info.user_agent.clone(),
+            request_info.ip_address.clone(),
+        )
+        .commit(conn)?;
+        TokenResponse::create_for_session(token_issuer, expires, &session)
+    }
+
+    pub fn create_for_session(
+        token_issuer: &dyn TokenIssuer,
+        expires: Duration,
+        session: &UserSession,
+    ) -> Result<Self, ApiError> {
         Ok(TokenResponse {
-            access_token: token_issuer.issue(user.id, expires)?,
-            refresh_token: token_issuer.issue_with_limited_scopes(user.id, vec![Scopes::TokenRefresh], expires * 60)?,
+            access_token: token_issuer.issue_for_session(session, expires)?,
+            refresh_token: token_issuer.issue_refresh_for_session(session, expires * 60)?,
         })
     }
 
-    pub fn create_from_refresh_token(
+    /// Tokens for a user who must enable two-factor authentication before accessing their
+    /// organizations, they carry no scopes and cannot be refreshed
+    pub fn create_for_two_factor_enrollment(
         token_issuer: &dyn TokenIssuer,
         expires: Duration,
-        user_id: Uuid,
+        user: &User,
     ) -> Result<Self, ApiError> {
         Ok(TokenResponse {
-            access_token: token_issuer.issue(user_id, expires)?,
-            refresh_token: token_issuer.issue_with_limited_scopes(user_id, vec![Scopes::TokenRefresh], expires * 60)?,
+            access_token: token_issuer.issue_with_limited_scopes(user.id, vec![], expires)?,
+            refresh_token: token_issuer.issue_with_limited_scopes(user.id, vec![], expires)?,
         })
     }
 }
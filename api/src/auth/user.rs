@@ -2,7 +2,7 @@ use crate::errors::*;
 use crate::extractors::OptionalUser;
 use actix_web::{HttpRequest, Result};
 use db::models::User as DbUser;
-use db::models::{scopes, Event, EventUser, Order, Organization, Roles, Scopes};
+use db::models::{scopes, ApiKey, Event, EventUser, Order, Organization, Roles, Scopes};
 use db::prelude::errors::EnumParseError;
 use db::prelude::Optional;
 use diesel::PgConnection;
@@ -23,6 +23,10 @@ pub struct User {
     pub method: String,
     pub global_scopes_only: bool,
     pub is_public_user: bool,
+    /// Organization API key the request was authenticated with, limiting access to its scopes
+    pub api_key: Option<ApiKey>,
+    /// Session the access token was issued for
+    pub session_id: Option<Uuid>,
 }
 
 impl User {
@@ -40,6 +44,8 @@ impl User {
             method: request.method().to_string(),
             global_scopes_only: false,
             is_public_user,
+            api_key: None,
+            session_id: None,
         };
         if let Some(scopes) = limited_scopes {
             result.global_scopes = scopes;
@@ -51,6 +57,16 @@ impl User {
         Ok(result)
     }
 
+    pub fn with_api_key(mut self, api_key: Option<ApiKey>) -> User {
+        self.api_key = api_key;
+        self
+    }
+
+    pub fn with_session_id(mut self, session_id: Option<Uuid>) -> User {
+        self.session_id = session_id;
+        self
+    }
+
     pub fn id(&self) -> Uuid {
         self.user.id
     }
@@ -67,6 +83,23 @@ impl User {
         connection: Option<&PgConnection>,
         log_on_failure: bool,
     ) -> Result<bool, ApiError> {
+        if let Some(ref api_key) = self.api_key {
+            // API keys only act within their own organization and never beyond the scopes they were
+            // issued with, the issuing user must also still hold the scope for the key to be honoured
+            let key_allows_access =
+                organization.map(|o| o.id == api_key.organization_id).unwrap_or(false) && api_key.has_scope(scope);
+            if !key_allows_access {
+                if log_on_failure {
+                    let mut logging_data = HashMap::new();
+                    logging_data.insert("accessed_scope", json!(scope.to_string()));
+                    logging_data.insert("api_key_id", json!(api_key.id));
+                    logging_data.insert("api_key_scopes", json!(api_key.scopes));
+                    self.log_unauthorized_access_attempt(logging_data);
+                }
+                return Ok(false);
+            }
+        }
+
         if self.global_scopes_only {
             if self.global_scopes.contains(&scope.to_string()) {
                 return Ok(true);
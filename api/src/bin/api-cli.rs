@@ -45,6 +45,10 @@ pub fn main() {
     (@subcommand backpopulate_temporary_user_data =>
       (name: "backpopulate-temporary-user-data")
       (about: "Backpopulate temporary user data")    )
+    (@subcommand backpopulate_domain_event_organizations =>
+      (name: "backpopulate-domain-event-organizations")
+      (about: "Backpopulate the organization of domain events recorded before organizations were tracked")
+      (@arg batch_size: -b --batch_size +takes_value "Number of domain events updated per batch, defaults to 1000"))
     (@subcommand   schedule_missing_domain_actions =>
       (name: "schedule-missing-domain-actions")
       (about: "Creates any missing reoccurring domain actions")
@@ -77,6 +81,9 @@ pub fn main() {
             regenerate_interaction_records(args.value_of("organization"), database)
         }
         ("backpopulate-temporary-user-data", Some(_)) => backpopulate_temporary_user_data(database),
+        ("backpopulate-domain-event-organizations", Some(args)) => {
+            backpopulate_domain_event_organizations(args.value_of("batch_size"), database)
+        }
         ("schedule-missing-domain-actions", Some(args)) => {
             schedule_missing_domain_actions(args.is_present("holds"), config, database)
         }
@@ -186,6 +193,22 @@ fn backpopulate_temporary_user_data(database: Database) {
     }
 }
 
+fn backpopulate_domain_event_organizations(batch_size: Option<&str>, database: Database) {
+    info!("Backpopulating domain event organizations");
+    let batch_size = batch_size.map(|b| b.parse::<i64>().unwrap()).unwrap_or(1000);
+    let connection = database.get_connection().expect("Expected connection to establish");
+    let connection = connection.get();
+
+    // Each batch is committed on its own so the domain events table is never locked for long
+    let mut after_seq = 0;
+    while let Some(last_seq) = DomainEvent::backpopulate_organization_ids(after_seq, batch_size, connection)
+        .expect("Expected to backpopulate domain event organizations")
+    {
+        after_seq = last_seq;
+        info!("Backpopulated domain event organizations up to seq {}", after_seq);
+    }
+}
+
 fn regenerate_interaction_records(org_id: Option<&str>, database: Database) {
     info!("Regenerating interaction records");
     let connection = database.get_connection().expect("Expected connection to establish");
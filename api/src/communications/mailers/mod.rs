@@ -5,9 +5,11 @@ use url::form_urlencoded::byte_serialize;
 
 pub mod orders;
 pub mod organization_invites;
+pub mod payment_plans;
 pub mod reports;
 pub mod tickets;
 pub mod user;
+pub mod waitlist;
 
 pub fn insert_event_template_data(
     template_data: &mut TemplateData,
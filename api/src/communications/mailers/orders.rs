@@ -56,8 +56,34 @@ pub fn confirmation_email(
                     ));
                 }
             }
+            OrderItemTypes::ResaleTickets => {
+                item_breakdown.push_str(&generate_item_row(
+                    &oi.description,
+                    oi.quantity,
+                    oi.unit_price_in_cents,
+                    false,
+                ));
+            }
             // Do nothing, included above with ticket for display
             OrderItemTypes::Discount => (),
+            // Inclusive taxes are already part of the item price
+            OrderItemTypes::Tax if oi.unit_price_in_cents == 0 => (),
+            OrderItemTypes::Tax => {
+                item_breakdown.push_str(&generate_item_row(
+                    &oi.description,
+                    oi.quantity,
+                    oi.unit_price_in_cents,
+                    false,
+                ));
+                if oi.refunded_quantity > 0 {
+                    item_breakdown.push_str(&generate_item_row(
+                        "Refunded",
+                        oi.refunded_quantity,
+                        oi.unit_price_in_cents,
+                        true,
+                    ));
+                }
+            }
             _ => {
                 //Accumulate fees
                 total_initial_fees += oi.quantity * oi.unit_price_in_cents;
@@ -115,8 +141,25 @@ pub fn confirmation_email(
     template_data.insert("total_breakdown".to_string(), total_breakdown);
     template_data.insert("tickets_link".to_string(), format!("{}/hub", config.front_end_url));
 
+    // Orders for a single organization's events use that organization's template
+    let organization_ids = display_order
+        .items
+        .iter()
+        .map(|i| i.event_id)
+        .unique()
+        .map(|event_id| Event::find(event_id, conn).map(|e| e.organization_id))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .unique()
+        .collect_vec();
+    let organization_id = if organization_ids.len() == 1 {
+        Some(organization_ids[0])
+    } else {
+        None
+    };
+
     // TODO: Perhaps move this to an event subscription
-    Ok(Communication::new(
+    let mut communication = Communication::new(
         CommunicationType::EmailTemplate,
         title,
         None,
@@ -126,7 +169,9 @@ pub fn confirmation_email(
         Some(vec![template_data]),
         Some(vec!["purchase".to_string()]),
         None,
-    ))
+    );
+    communication.apply_template(CommunicationTemplateNames::PurchaseCompleted, organization_id, conn)?;
+    Ok(communication)
 }
 
 fn generate_item_row(description: &str, quantity: i64, unit_price_in_cents: i64, refund: bool) -> String {
@@ -228,7 +273,7 @@ pub fn refund_email(
     template_data.insert("tickets_link".to_string(), format!("{}/orders", config.front_end_url));
 
     // TODO: Perhaps move this to an event subscription
-    Communication::new(
+    let mut communication = Communication::new(
         CommunicationType::EmailTemplate,
         title,
         None,
@@ -238,8 +283,9 @@ pub fn refund_email(
         Some(vec![template_data]),
         Some(vec!["refund"]),
         None,
-    )
-    .queue(conn)?;
+    );
+    communication.apply_template(CommunicationTemplateNames::Refund, None, conn)?;
+    communication.queue(conn)?;
 
     Ok(())
 }
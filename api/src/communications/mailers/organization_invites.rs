@@ -25,7 +25,7 @@ pub fn invite_user_to_organization_email(
     template_data.insert("name".to_string(), recipient_name.into());
     template_data.insert("org".to_string(), org.name.clone());
     template_data.insert("invite_link_accept".to_string(), invite_link_accept);
-    Communication::new(
+    let mut communication = Communication::new(
         CommunicationType::EmailTemplate,
         title,
         None,
@@ -35,8 +35,9 @@ pub fn invite_user_to_organization_email(
         Some(vec![template_data]),
         Some(vec!["org_invites".to_string()]),
         None,
-    )
-    .queue(conn)?;
+    );
+    communication.apply_template(CommunicationTemplateNames::OrgInvite, Some(org.id), conn)?;
+    communication.queue(conn)?;
 
     Ok(())
 }
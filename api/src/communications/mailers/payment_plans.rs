@@ -0,0 +1,81 @@
+use crate::config::Config;
+use crate::errors::*;
+use crate::SITE_NAME;
+use chrono::NaiveDateTime;
+use db::models::*;
+use diesel::PgConnection;
+
+pub fn installment_failed(
+    config: &Config,
+    email: String,
+    user: &User,
+    installment: &PaymentPlanInstallment,
+    retry_at: NaiveDateTime,
+    conn: &PgConnection,
+) -> Result<(), ApiError> {
+    let update_link = format!("{}/account/payment-methods", config.front_end_url);
+
+    let source = CommAddress::from(config.communication_default_source_email.clone());
+    let destinations = CommAddress::from(email);
+    let title = format!("{} could not collect your installment payment", SITE_NAME);
+    let template_id = config.email_templates.payment_plan_installment_failed.to_string();
+    let mut template_data = TemplateData::new();
+    template_data.insert("name".to_string(), user.full_name());
+    template_data.insert("update_link".to_string(), update_link);
+    template_data.insert(
+        "installment_number".to_string(),
+        installment.installment_number.to_string(),
+    );
+    template_data.insert(
+        "amount".to_string(),
+        format!("{:.2}", installment.amount_in_cents as f64 / 100.0),
+    );
+    template_data.insert("retry_date".to_string(), retry_at.format("%e %B %Y").to_string());
+    let mut communication = Communication::new(
+        CommunicationType::EmailTemplate,
+        title,
+        None,
+        Some(source),
+        destinations,
+        Some(template_id),
+        Some(vec![template_data]),
+        Some(vec!["payment_plan_installment_failed", "payment_plans"]),
+        None,
+    );
+    communication.apply_template(CommunicationTemplateNames::PaymentPlanInstallmentFailed, None, conn)?;
+    communication.queue(conn)?;
+
+    Ok(())
+}
+
+pub fn cancelled(
+    config: &Config,
+    email: String,
+    user: &User,
+    order: &Order,
+    conn: &PgConnection,
+) -> Result<(), ApiError> {
+    let source = CommAddress::from(config.communication_default_source_email.clone());
+    let destinations = CommAddress::from(email);
+    let title = format!("Your {} order has been cancelled", SITE_NAME);
+    let template_id = config.email_templates.payment_plan_cancelled.to_string();
+    let mut template_data = TemplateData::new();
+    template_data.insert("name".to_string(), user.full_name());
+    template_data.insert("order_id".to_string(), order.id.to_string());
+    template_data.insert("order_number".to_string(), order.order_number());
+    let mut communication = Communication::new(
+        CommunicationType::EmailTemplate,
+        title,
+        None,
+        Some(source),
+        destinations,
+        Some(template_id),
+        Some(vec![template_data]),
+        Some(vec!["payment_plan_cancelled", "payment_plans"]),
+        None,
+    );
+    communication.apply_template(CommunicationTemplateNames::PaymentPlanCancelled, None, conn)?;
+    communication.queue(conn)?;
+
+    Ok(())
+}
@@ -34,7 +34,7 @@ pub fn send_tickets(
             .to_string()
         })
         .join(",");
-    Communication::new(
+    let mut communication = Communication::new(
         CommunicationType::EmailTemplate,
         title,
         None,
@@ -46,8 +46,9 @@ pub fn send_tickets(
         Some(
             map!("event_id".to_string() => json!(event_ids), "days_until_event".to_string() => json!(days_until_event)),
         ),
-    )
-    .queue(conn)?;
+    );
+    communication.apply_template(CommunicationTemplateNames::TransferTickets, None, conn)?;
+    communication.queue(conn)?;
 
     Ok(())
 }
@@ -87,7 +88,7 @@ pub fn transfer_drip_reminder(
     template_data.insert("transfer_id".to_string(), transfer.id.to_string());
     insert_event_template_data(&mut template_data, event, conn)?;
 
-    Communication::new(
+    let mut communication = Communication::new(
         CommunicationType::EmailTemplate,
         title,
         None,
@@ -97,8 +98,13 @@ pub fn transfer_drip_reminder(
         Some(vec![template_data]),
         Some(vec!["transfer", "transfer_receiver", "transfer_drip"]),
         None,
-    )
-    .queue(conn)?;
+    );
+    communication.apply_template(
+        CommunicationTemplateNames::TransferDripReminder,
+        Some(event.organization_id),
+        conn,
+    )?;
+    communication.queue(conn)?;
 
     Ok(())
 }
@@ -126,7 +132,7 @@ pub fn transfer_sent_receipt(
         template_data.insert("transfer_id".to_string(), transfer.id.to_string());
         insert_event_template_data(&mut template_data, event, conn)?;
 
-        Communication::new(
+        let mut communication = Communication::new(
             CommunicationType::EmailTemplate,
             title,
             None,
@@ -136,8 +142,13 @@ pub fn transfer_sent_receipt(
             Some(vec![template_data]),
             Some(vec!["transfer", "transfer_sender", "transfer_confirmation"]),
             None,
-        )
-        .queue(conn)?;
+        );
+        communication.apply_template(
+            CommunicationTemplateNames::TransferSentReceipt,
+            Some(event.organization_id),
+            conn,
+        )?;
+        communication.queue(conn)?;
     }
     Ok(())
 }
@@ -160,7 +171,7 @@ pub fn transfer_cancelled_receipt(
         transfer.transfer_address.clone().unwrap_or("".to_string()),
     );
     template_data.insert("transfer_id".to_string(), transfer.id.to_string());
-    Communication::new(
+    let mut communication = Communication::new(
         CommunicationType::EmailTemplate,
         title,
         None,
@@ -170,8 +181,98 @@ pub fn transfer_cancelled_receipt(
         Some(vec![template_data]),
         Some(vec!["transfer", "transfer_receiver", "transfer_cancellation"]),
         None,
-    )
-    .queue(conn)?;
+    );
+    communication.apply_template(CommunicationTemplateNames::TransferCancelledReceipt, None, conn)?;
+    communication.queue(conn)?;
+
+    Ok(())
+}
+
+pub fn transfer_expiry_reminder(
+    email: String,
+    transfer: &Transfer,
+    event: &Event,
+    config: &Config,
+    conn: &PgConnection,
+) -> Result<(), ApiError> {
+    let source = CommAddress::from(config.communication_default_source_email.clone());
+    let destinations = CommAddress::from(email);
+    let title = format!("{}: Ticket transfer expiring", SITE_NAME);
+    let user = User::find(transfer.source_user_id, conn)?;
+    let template_id = config.sendgrid_template_bn_transfer_tickets_drip_source.clone();
+    let transfer_cancel_url = format!("{}/my-events?event_id={}", config.front_end_url.clone(), event.id,);
+
+    let mut template_data = TemplateData::new();
+    template_data.insert("header".to_string(), transfer.expiry_drip_header(true)?);
+    template_data.insert("sender_name".to_string(), Transfer::sender_name(&user));
+    template_data.insert(
+        "receiver_address".to_string(),
+        transfer.transfer_address.clone().unwrap_or("".to_string()),
+    );
+    template_data.insert("transfer_cancel_url".to_string(), transfer_cancel_url);
+    template_data.insert("transfer_id".to_string(), transfer.id.to_string());
+    insert_event_template_data(&mut template_data, event, conn)?;
+
+    let mut communication = Communication::new(
+        CommunicationType::EmailTemplate,
+        title,
+        None,
+        Some(source),
+        destinations,
+        Some(template_id),
+        Some(vec![template_data]),
+        Some(vec!["transfer", "transfer_sender", "transfer_drip", "transfer_expiry"]),
+        None,
+    );
+    communication.apply_template(
+        CommunicationTemplateNames::TransferExpiryReminder,
+        Some(event.organization_id),
+        conn,
+    )?;
+    communication.queue(conn)?;
+
+    Ok(())
+}
+
+pub fn transfer_returned_receipt(
+    config: &Config,
+    email: String,
+    from_user: &User,
+    transfer: &Transfer,
+    conn: &PgConnection,
+) -> Result<(), ApiError> {
+    let source = CommAddress::from(config.communication_default_source_email.clone());
+    let destinations = CommAddress::from(email);
+    let (title, tag) = match transfer.status {
+        TransferStatus::Declined => (format!("{}: Ticket transfer declined", SITE_NAME), "transfer_declined"),
+        _ => (format!("{}: Ticket transfer expired", SITE_NAME), "transfer_expiry"),
+    };
+    let template_id = config.sendgrid_template_bn_transfer_tickets_returned.clone();
+    let mut template_data = TemplateData::new();
+    template_data.insert("sender_name".to_string(), Transfer::sender_name(&from_user));
+    template_data.insert(
+        "receiver_address".to_string(),
+        transfer.transfer_address.clone().unwrap_or("".to_string()),
+    );
+    template_data.insert("transfer_id".to_string(), transfer.id.to_string());
+    template_data.insert("transfer_status".to_string(), transfer.status.to_string());
+    template_data.insert(
+        "num_tickets".to_string(),
+        transfer.transfer_ticket_count(conn)?.to_string(),
+    );
+    let mut communication = Communication::new(
+        CommunicationType::EmailTemplate,
+        title,
+        None,
+        Some(source),
+        destinations,
+        Some(template_id),
+        Some(vec![template_data]),
+        Some(vec!["transfer", "transfer_sender", tag]),
+        None,
+    );
+    communication.apply_template(CommunicationTemplateNames::TransferReturnedReceipt, None, conn)?;
+    communication.queue(conn)?;
 
     Ok(())
 }
@@ -194,7 +295,7 @@ pub fn transfer_cancelled(
         transfer.transfer_address.clone().unwrap_or("".to_string()),
     );
     template_data.insert("transfer_id".to_string(), transfer.id.to_string());
-    Communication::new(
+    let mut communication = Communication::new(
         CommunicationType::EmailTemplate,
         title,
         None,
@@ -204,8 +305,9 @@ pub fn transfer_cancelled(
         Some(vec![template_data]),
         Some(vec!["transfer", "transfer_receiver", "transfer_cancellation"]),
         None,
-    )
-    .queue(conn)?;
+    );
+    communication.apply_template(CommunicationTemplateNames::TransferCancelled, None, conn)?;
+    communication.queue(conn)?;
 
     Ok(())
 }
@@ -19,7 +19,7 @@ pub fn user_registered(
     let template_id = config.sendgrid_template_bn_user_registered.clone();
     let mut template_data = TemplateData::new();
     template_data.insert("name".to_string(), user_first_name.clone());
-    Communication::new(
+    let mut communication = Communication::new(
         CommunicationType::EmailTemplate,
         title,
         None,
@@ -29,13 +29,14 @@ pub fn user_registered(
         Some(vec![template_data]),
         Some(vec!["user_registered", "account"]),
         None,
-    )
-    .queue(conn)?;
+    );
+    communication.apply_template(CommunicationTemplateNames::UserRegistered, None, conn)?;
+    communication.queue(conn)?;
 
     Ok(())
 }
 
-pub fn password_reset_email(config: &Config, user: &User) -> Communication {
+pub fn password_reset_email(config: &Config, user: &User, conn: &PgConnection) -> Result<Communication, ApiError> {
     let password_reset_link = format!(
         "{}/password-reset?token={}",
         config.front_end_url.clone(),
@@ -49,7 +50,7 @@ pub fn password_reset_email(config: &Config, user: &User) -> Communication {
     let mut template_data = TemplateData::new();
     template_data.insert("name".to_string(), user.full_name());
     template_data.insert("password_reset_link".to_string(), password_reset_link);
-    Communication::new(
+    let mut communication = Communication::new(
         CommunicationType::EmailTemplate,
         title,
         None,
@@ -59,7 +60,9 @@ pub fn password_reset_email(config: &Config, user: &User) -> Communication {
         Some(vec![template_data]),
         Some(vec!["password_reset", "account"]),
         None,
-    )
+    );
+    communication.apply_template(CommunicationTemplateNames::PasswordReset, None, conn)?;
+    Ok(communication)
 }
 
 pub fn invite_user_email(config: &Config, user: &User, conn: &PgConnection) -> Result<(), ApiError> {
@@ -77,7 +80,7 @@ pub fn invite_user_email(config: &Config, user: &User, conn: &PgConnection) -> R
     let mut template_data = TemplateData::new();
     template_data.insert("name".to_string(), user.full_name());
     template_data.insert("invite_link".to_string(), invite_link);
-    Communication::new(
+    let mut communication = Communication::new(
         CommunicationType::EmailTemplate,
         title,
         None,
@@ -87,8 +90,9 @@ pub fn invite_user_email(config: &Config, user: &User, conn: &PgConnection) -> R
         Some(vec![template_data]),
         Some(vec!["user_invite", "account"]),
         None,
-    )
-    .queue(conn)?;
+    );
+    communication.apply_template(CommunicationTemplateNames::UserInvite, None, conn)?;
+    communication.queue(conn)?;
 
     Ok(())
 }
@@ -116,7 +120,7 @@ pub fn user_registered_magic_link(
     let mut template_data = TemplateData::new();
     template_data.insert("download_link".to_string(), link);
     template_data.insert("refresh_token".to_string(), refresh_token);
-    Communication::new(
+    let mut communication = Communication::new(
         CommunicationType::EmailTemplate,
         title,
         None,
@@ -126,8 +130,53 @@ pub fn user_registered_magic_link(
         Some(vec![template_data]),
         Some(vec!["user_invite", "account"]),
         None,
-    )
-    .queue(conn)?;
+    );
+    communication.apply_template(CommunicationTemplateNames::UserRegisteredMagicLink, None, conn)?;
+    communication.queue(conn)?;
+
+    Ok(())
+}
+
+pub fn payment_method_expiring(
+    config: &Config,
+    email: String,
+    user: &User,
+    payment_method: &PaymentMethod,
+    conn: &PgConnection,
+) -> Result<(), ApiError> {
+    let update_link = format!("{}/account/payment-methods", config.front_end_url);
+
+    let source = CommAddress::from(config.communication_default_source_email.clone());
+    let destinations = CommAddress::from(email);
+    let title = format!("Your {} card is about to expire", SITE_NAME);
+    let template_id = config.email_templates.payment_method_expiring.to_string();
+    let mut template_data = TemplateData::new();
+    template_data.insert("name".to_string(), user.full_name());
+    template_data.insert("update_link".to_string(), update_link);
+    template_data.insert(
+        "card_brand".to_string(),
+        payment_method.card_brand.clone().unwrap_or("".to_string()),
+    );
+    template_data.insert(
+        "card_last_four".to_string(),
+        payment_method.card_last_four.clone().unwrap_or("".to_string()),
+    );
+    if let (Some(month), Some(year)) = (payment_method.card_expiry_month, payment_method.card_expiry_year) {
+        template_data.insert("card_expiry".to_string(), format!("{:02}/{}", month, year));
+    }
+    let mut communication = Communication::new(
+        CommunicationType::EmailTemplate,
+        title,
+        None,
+        Some(source),
+        destinations,
+        Some(template_id),
+        Some(vec![template_data]),
+        Some(vec!["payment_method_expiring", "account"]),
+        None,
+    );
+    communication.apply_template(CommunicationTemplateNames::PaymentMethodExpiring, None, conn)?;
+    communication.queue(conn)?;
 
     Ok(())
 }
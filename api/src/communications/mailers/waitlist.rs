@@ -0,0 +1,55 @@
+use crate::communications::mailers::insert_event_template_data;
+use crate::config::Config;
+use crate::errors::*;
+use db::models::*;
+use diesel::pg::PgConnection;
+
+pub fn waitlist_offer(
+    config: &Config,
+    email: String,
+    entry: &WaitlistEntry,
+    hold: &Hold,
+    ticket_type: &TicketType,
+    event: &Event,
+    conn: &PgConnection,
+) -> Result<(), ApiError> {
+    let claim_link = format!(
+        "{}/{}/tickets?code={}",
+        config.front_end_url,
+        event.slug(conn)?,
+        hold.redemption_code.clone().unwrap_or("".to_string())
+    );
+
+    let source = CommAddress::from(config.communication_default_source_email.clone());
+    let destinations = CommAddress::from(email);
+    let title = format!("Tickets are available for {}", event.name);
+    let template_id = config.email_templates.waitlist_offer.to_string();
+    let mut template_data = TemplateData::new();
+    template_data.insert("claim_link".to_string(), claim_link);
+    template_data.insert("ticket_type_name".to_string(), ticket_type.name.clone());
+    template_data.insert("quantity".to_string(), entry.quantity.to_string());
+    if let Some(offer_expires_at) = entry.offer_expires_at {
+        template_data.insert("offer_expires_at".to_string(), offer_expires_at.to_string());
+    }
+    insert_event_template_data(&mut template_data, event, conn)?;
+
+    let mut communication = Communication::new(
+        CommunicationType::EmailTemplate,
+        title,
+        None,
+        Some(source),
+        destinations,
+        Some(template_id),
+        Some(vec![template_data]),
+        Some(vec!["waitlist".to_string()]),
+        None,
+    );
+    communication.apply_template(
+        CommunicationTemplateNames::WaitlistOffer,
+        Some(event.organization_id),
+        conn,
+    )?;
+    communication.queue(conn)?;
+
+    Ok(())
+}
@@ -2,10 +2,12 @@ use crate::auth::default_token_issuer::DefaultTokenIssuer;
 use crate::errors::{ApiError, ApplicationError};
 use crate::SITE_NAME;
 use chrono::Duration;
-use db::models::{EmailProvider, Environment};
+use db::models::{DomainActionTypes, EmailProvider, Environment, PaymentProviders};
 use db::utils::errors::EnumParseError;
 use dotenv::dotenv;
 use itertools::Itertools;
+use std::cmp;
+use std::collections::HashMap;
 use std::env;
 use std::fmt;
 use std::str;
@@ -44,6 +46,9 @@ pub struct Config {
     pub block_external_comms: bool,
     pub primary_currency: String,
     pub stripe_secret_key: String,
+    pub stripe_webhook_secret: Option<String>,
+    pub payment_processors: HashMap<PaymentProviders, PaymentProcessorConfig>,
+    pub mock_payment_processor_enabled: bool,
     pub token_issuer: Box<DefaultTokenIssuer>,
     pub tari_client: Box<dyn TariClient + Send + Sync>,
     pub communication_default_source_email: String,
@@ -58,6 +63,7 @@ pub struct Config {
     pub sendgrid_template_bn_transfer_tickets_receipt: String,
     pub sendgrid_template_bn_transfer_tickets_drip_source: String,
     pub sendgrid_template_bn_transfer_tickets_drip_destination: String,
+    pub sendgrid_template_bn_transfer_tickets_returned: String,
     pub sendgrid_template_bn_user_invite: String,
     pub settlement_period_in_days: Option<u32>,
     pub spotify_auth_token: Option<String>,
@@ -71,11 +77,13 @@ pub struct Config {
     pub branch_io_timeout: u64,
     pub max_instances_per_ticket_type: i64,
     pub connection_pool: ConnectionPoolConfig,
+    pub domain_actions: DomainActionSettings,
     pub ssr_trigger_header: String,
     pub ssr_trigger_value: String,
     pub customer_io: CustomerIoSettings,
     pub sharetribe: SharetribeConfig,
     pub product_context: ProductContext,
+    pub scanner_manifest_secret: String,
 }
 
 #[derive(Clone)]
@@ -91,6 +99,31 @@ pub struct ConnectionPoolConfig {
     pub max: u32,
 }
 
+/// Limits applied when processing domain actions. Per type values are set with a comma separated
+/// list of `<DomainActionTypes>:<value>`, e.g. `DOMAIN_ACTION_CONCURRENCY=SendAutomaticReportEmails:1`
+#[derive(Clone)]
+pub struct DomainActionSettings {
+    /// Wake the monitor with Postgres LISTEN/NOTIFY instead of only polling
+    pub listen_for_notifications: bool,
+    pub max_concurrency: usize,
+    pub default_timeout: u64,
+    pub concurrency: HashMap<DomainActionTypes, usize>,
+    pub timeouts: HashMap<DomainActionTypes, u64>,
+}
+
+impl DomainActionSettings {
+    pub fn concurrency_for(&self, domain_action_type: DomainActionTypes) -> usize {
+        *self
+            .concurrency
+            .get(&domain_action_type)
+            .unwrap_or(&self.max_concurrency)
+    }
+
+    pub fn timeout_for(&self, domain_action_type: DomainActionTypes) -> u64 {
+        *self.timeouts.get(&domain_action_type).unwrap_or(&self.default_timeout)
+    }
+}
+
 #[derive(Clone)]
 pub struct CubeJs {
     pub secret: String,
@@ -102,14 +135,27 @@ pub struct SharetribeConfig {
     pub client_secret: String,
 }
 
+/// Card processor enabled by setting `<PROVIDER>_API_KEY`, `<PROVIDER>_BASE_URL` and
+/// `<PROVIDER>_MERCHANT_ACCOUNT`, e.g. `ADYEN_API_KEY`
+#[derive(Clone)]
+pub struct PaymentProcessorConfig {
+    pub api_key: String,
+    pub base_url: String,
+    pub merchant_account: String,
+}
+
 #[derive(Clone)]
 pub struct EmailTemplates {
     pub custom_broadcast: EmailTemplate,
     pub org_invite: EmailTemplate,
     pub password_reset: EmailTemplate,
+    pub payment_method_expiring: EmailTemplate,
+    pub payment_plan_cancelled: EmailTemplate,
+    pub payment_plan_installment_failed: EmailTemplate,
     pub ticket_count_report: EmailTemplate,
     pub resend_download_link: EmailTemplate,
     pub user_registered_magic_link: EmailTemplate,
+    pub waitlist_offer: EmailTemplate,
 }
 
 #[derive(Clone, Deserialize, Serialize)]
@@ -180,9 +226,13 @@ const EMAIL_ONLY_REGISTRATION_ALLOWED: &str = "EMAIL_ONLY_REGISTRATION_ALLOWED";
 const EMAIL_TEMPLATES_CUSTOM_BROADCAST: &str = "EMAIL_TEMPLATES_CUSTOM_BROADCAST";
 const EMAIL_TEMPLATES_ORG_INVITE: &str = "EMAIL_TEMPLATES_ORG_INVITE";
 const EMAIL_TEMPLATES_PASSWORD_RESET: &str = "EMAIL_TEMPLATES_PASSWORD_RESET";
+const EMAIL_TEMPLATES_PAYMENT_METHOD_EXPIRING: &str = "EMAIL_TEMPLATES_PAYMENT_METHOD_EXPIRING";
+const EMAIL_TEMPLATES_PAYMENT_PLAN_CANCELLED: &str = "EMAIL_TEMPLATES_PAYMENT_PLAN_CANCELLED";
+const EMAIL_TEMPLATES_PAYMENT_PLAN_INSTALLMENT_FAILED: &str = "EMAIL_TEMPLATES_PAYMENT_PLAN_INSTALLMENT_FAILED";
 const EMAIL_TEMPLATES_TICKET_COUNT_REPORT: &str = "EMAIL_TEMPLATES_TICKET_COUNT_REPORT";
 const EMAIL_TEMPLATES_RESEND_DOWNLOAD_LINK: &str = "EMAIL_TEMPLATES_RESEND_DOWNLOAD_LINK";
 const EMAIL_TEMPLATES_USER_REGISTERED_MAGIC_LINK: &str = "EMAIL_TEMPLATES_USER_REGISTERED_MAGIC_LINK";
+const EMAIL_TEMPLATES_WAITLIST_OFFER: &str = "EMAIL_TEMPLATES_WAITLIST_OFFER";
 const ENVIRONMENT: &str = "ENVIRONMENT";
 const FACEBOOK_APP_ID: &str = "FACEBOOK_APP_ID";
 const FACEBOOK_APP_SECRET: &str = "FACEBOOK_APP_SECRET";
@@ -193,6 +243,10 @@ const API_BASE_URL: &str = "API_BASE_URL";
 const GOOGLE_RECAPTCHA_SECRET_KEY: &str = "GOOGLE_RECAPTCHA_SECRET_KEY";
 const PRIMARY_CURRENCY: &str = "PRIMARY_CURRENCY";
 const STRIPE_SECRET_KEY: &str = "STRIPE_SECRET_KEY";
+const STRIPE_WEBHOOK_SECRET: &str = "STRIPE_WEBHOOK_SECRET";
+// Processors configured with the generic `<PROVIDER>_*` settings
+const CONFIGURABLE_PAYMENT_PROCESSORS: &[PaymentProviders] = &[PaymentProviders::Adyen];
+const MOCK_PAYMENT_PROCESSOR_ENABLED: &str = "MOCK_PAYMENT_PROCESSOR_ENABLED";
 const STATIC_FILE_PATH: &str = "STATIC_FILE_PATH";
 const TARI_URL: &str = "TARI_URL";
 const TEST_DATABASE_URL: &str = "TEST_DATABASE_URL";
@@ -221,6 +275,7 @@ const SENDGRID_TEMPLATE_BN_CANCEL_TRANSFER_TICKETS_RECEIPT: &str =
 const SENDGRID_TEMPLATE_BN_CANCEL_TRANSFER_TICKETS: &str = "SENDGRID_TEMPLATE_BN_CANCEL_TRANSFER_TICKETS";
 const SENDGRID_TEMPLATE_BN_TRANSFER_TICKETS_RECEIPT: &str = "SENDGRID_TEMPLATE_BN_TRANSFER_TICKETS_RECEIPT";
 const SENDGRID_TEMPLATE_BN_TRANSFER_TICKETS: &str = "SENDGRID_TEMPLATE_BN_TRANSFER_TICKETS";
+const SENDGRID_TEMPLATE_BN_TRANSFER_TICKETS_RETURNED: &str = "SENDGRID_TEMPLATE_BN_TRANSFER_TICKETS_RETURNED";
 const SENDGRID_TEMPLATE_BN_USER_INVITE: &str = "SENDGRID_TEMPLATE_BN_USER_INVITE";
 
 // Settlement period settings
@@ -243,6 +298,14 @@ const MAX_INSTANCES_PER_TICKET_TYPE: &str = "MAX_INSTANCES_PER_TICKET_TYPE";
 const CONNECTION_POOL_MIN: &str = "CONNECTION_POOL_MIN";
 const CONNECTION_POOL_MAX: &str = "CONNECTION_POOL_MAX";
 
+// Domain action processing limits
+// Disable when connecting through a pooler that does not support LISTEN, e.g. pgbouncer in transaction mode
+const DOMAIN_ACTION_LISTEN_ENABLED: &str = "DOMAIN_ACTION_LISTEN_ENABLED";
+const DOMAIN_ACTION_MAX_CONCURRENCY: &str = "DOMAIN_ACTION_MAX_CONCURRENCY";
+const DOMAIN_ACTION_DEFAULT_TIMEOUT: &str = "DOMAIN_ACTION_DEFAULT_TIMEOUT";
+const DOMAIN_ACTION_CONCURRENCY: &str = "DOMAIN_ACTION_CONCURRENCY";
+const DOMAIN_ACTION_TIMEOUTS: &str = "DOMAIN_ACTION_TIMEOUTS";
+
 const SSR_TRIGGER_HEADER: &str = "SSR_TRIGGER_HEADER";
 const SSR_TRIGGER_VALUE: &str = "SSR_TRIGGER_VALUE";
 
@@ -251,10 +314,39 @@ const SHARETRIBE_CLIENT_SECRET: &str = "SHARETRIBE_CLIENT_SECRET";
 
 const PRODUCT_CONTEXT: &str = "PRODUCT_CONTEXT";
 
+// Signs the ticket manifests downloaded by scanners for offline redemption
+const SCANNER_MANIFEST_SECRET: &str = "SCANNER_MANIFEST_SECRET";
+
 fn get_env_var(var: &str) -> String {
     env::var(var).unwrap_or_else(|_| panic!("{} must be defined", var))
 }
 
+fn get_domain_action_limits<T: FromStr + Copy>(
+    var: &str,
+    defaults: &[(DomainActionTypes, T)],
+) -> HashMap<DomainActionTypes, T> {
+    let mut limits: HashMap<DomainActionTypes, T> = defaults.iter().cloned().collect();
+    if let Ok(value) = env::var(var) {
+        for limit in value.split(',').map(|l| l.trim()).filter(|l| !l.is_empty()) {
+            let split: Vec<&str> = limit.split(':').collect_vec();
+            if split.len() != 2 {
+                panic!(
+                    "{} was not in the correct format: '<domain_action_type>:<value>,...'",
+                    var
+                );
+            }
+            let domain_action_type = split[0]
+                .parse()
+                .unwrap_or_else(|_| panic!("Not a valid domain action type for {}", var));
+            let value = split[1]
+                .parse()
+                .unwrap_or_else(|_| panic!("Not a valid value for {}", var));
+            limits.insert(domain_action_type, value);
+        }
+    }
+    limits
+}
+
 impl Config {
     pub fn parse_environment() -> Result<Environment, EnumParseError> {
         if let Ok(environment_value) = env::var(&ENVIRONMENT) {
@@ -336,6 +428,41 @@ impl Config {
 
         let primary_currency = env::var(&PRIMARY_CURRENCY).unwrap_or_else(|_| "usd".to_string());
         let stripe_secret_key = env::var(&STRIPE_SECRET_KEY).unwrap_or_else(|_| "<stripe not enabled>".to_string());
+        // Signing secret of the webhook endpoint, webhooks are rejected when it is not set
+        let stripe_webhook_secret = match environment {
+            Environment::Test => Some("whsec_test".to_string()),
+            _ => env::var(&STRIPE_WEBHOOK_SECRET).ok(),
+        };
+
+        let payment_processors = CONFIGURABLE_PAYMENT_PROCESSORS
+            .iter()
+            .filter_map(|provider| {
+                let prefix = provider.to_string().to_uppercase();
+                env::var(format!("{}_API_KEY", prefix)).ok().map(|api_key| {
+                    (
+                        *provider,
+                        PaymentProcessorConfig {
+                            api_key,
+                            base_url: get_env_var(&format!("{}_BASE_URL", prefix)),
+                            merchant_account: get_env_var(&format!("{}_MERCHANT_ACCOUNT", prefix)),
+                        },
+                    )
+                })
+            })
+            .collect();
+        // The mock processor charges nothing so it is never available in production
+        let mock_payment_processor_enabled = match environment {
+            Environment::Test => true,
+            Environment::Production => false,
+            _ => env::var(&MOCK_PAYMENT_PROCESSOR_ENABLED)
+                .map(|s| {
+                    s.parse().expect(&format!(
+                        "{} is not a valid boolean value",
+                        MOCK_PAYMENT_PROCESSOR_ENABLED
+                    ))
+                })
+                .unwrap_or(false),
+        };
 
         let token_issuer = Box::new(DefaultTokenIssuer::new(
             get_env_var(TOKEN_SECRET),
@@ -393,9 +520,15 @@ impl Config {
             custom_broadcast: get_env_var(EMAIL_TEMPLATES_CUSTOM_BROADCAST).parse().unwrap(),
             org_invite: get_env_var(EMAIL_TEMPLATES_ORG_INVITE).parse().unwrap(),
             password_reset: get_env_var(EMAIL_TEMPLATES_PASSWORD_RESET).parse().unwrap(),
+            payment_method_expiring: get_env_var(EMAIL_TEMPLATES_PAYMENT_METHOD_EXPIRING).parse().unwrap(),
+            payment_plan_cancelled: get_env_var(EMAIL_TEMPLATES_PAYMENT_PLAN_CANCELLED).parse().unwrap(),
+            payment_plan_installment_failed: get_env_var(EMAIL_TEMPLATES_PAYMENT_PLAN_INSTALLMENT_FAILED)
+                .parse()
+                .unwrap(),
             ticket_count_report: get_env_var(EMAIL_TEMPLATES_TICKET_COUNT_REPORT).parse().unwrap(),
             resend_download_link: get_env_var(EMAIL_TEMPLATES_RESEND_DOWNLOAD_LINK).parse().unwrap(),
             user_registered_magic_link: get_env_var(EMAIL_TEMPLATES_USER_REGISTERED_MAGIC_LINK).parse().unwrap(),
+            waitlist_offer: get_env_var(EMAIL_TEMPLATES_WAITLIST_OFFER).parse().unwrap(),
         };
 
         let customer_io_base_url = get_env_var(CUSTOMER_IO_BASE_URL);
@@ -425,6 +558,8 @@ impl Config {
         let sendgrid_template_bn_cancel_transfer_tickets = get_env_var(SENDGRID_TEMPLATE_BN_CANCEL_TRANSFER_TICKETS);
         let sendgrid_template_bn_cancel_transfer_tickets_receipt =
             get_env_var(SENDGRID_TEMPLATE_BN_CANCEL_TRANSFER_TICKETS_RECEIPT);
+        let sendgrid_template_bn_transfer_tickets_returned = env::var(&SENDGRID_TEMPLATE_BN_TRANSFER_TICKETS_RETURNED)
+            .unwrap_or_else(|_| sendgrid_template_bn_cancel_transfer_tickets_receipt.clone());
         let sendgrid_template_bn_user_invite = get_env_var(SENDGRID_TEMPLATE_BN_USER_INVITE);
 
         // Force settlement period in days to 1 for testing
@@ -472,6 +607,44 @@ impl Config {
                 .unwrap_or(20),
         };
 
+        // Bulk jobs are limited so that they cannot occupy every worker
+        let domain_actions = DomainActionSettings {
+            listen_for_notifications: env::var(DOMAIN_ACTION_LISTEN_ENABLED)
+                .map(|s| s.parse().expect("Not a valid boolean for DOMAIN_ACTION_LISTEN_ENABLED"))
+                .unwrap_or(true),
+            max_concurrency: env::var(DOMAIN_ACTION_MAX_CONCURRENCY)
+                .map(|s| {
+                    s.parse()
+                        .expect("Not a valid integer for DOMAIN_ACTION_MAX_CONCURRENCY")
+                })
+                .unwrap_or(cmp::max(1, connection_pool.max / 2) as usize),
+            default_timeout: env::var(DOMAIN_ACTION_DEFAULT_TIMEOUT)
+                .map(|s| {
+                    s.parse()
+                        .expect("Not a valid integer for DOMAIN_ACTION_DEFAULT_TIMEOUT")
+                })
+                .unwrap_or(55),
+            concurrency: get_domain_action_limits(
+                DOMAIN_ACTION_CONCURRENCY,
+                &[
+                    (DomainActionTypes::ProcessSettlementReport, 1),
+                    (DomainActionTypes::RetargetAbandonedOrders, 1),
+                    (DomainActionTypes::SendAutomaticReportEmails, 1),
+                    (DomainActionTypes::SubmitSitemapToSearchEngines, 1),
+                    (DomainActionTypes::UpdateGenres, 1),
+                ],
+            ),
+            timeouts: get_domain_action_limits(
+                DOMAIN_ACTION_TIMEOUTS,
+                &[
+                    (DomainActionTypes::BroadcastPushNotification, 300),
+                    (DomainActionTypes::ProcessSettlementReport, 300),
+                    (DomainActionTypes::RetargetAbandonedOrders, 300),
+                    (DomainActionTypes::SendAutomaticReportEmails, 300),
+                ],
+            ),
+        };
+
         let ssr_trigger_header = env::var(&SSR_TRIGGER_HEADER).unwrap_or("x-ssr".to_string());
         let ssr_trigger_value = env::var(&SSR_TRIGGER_VALUE).unwrap_or("facebook".to_string());
 
@@ -481,6 +654,8 @@ impl Config {
             client_secret: get_env_var(SHARETRIBE_CLIENT_SECRET),
         };
 
+        let scanner_manifest_secret = get_env_var(SCANNER_MANIFEST_SECRET);
+
         let product_context = match env::var(&PRODUCT_CONTEXT)
             .unwrap_or("bigneon".to_owned())
             .to_lowercase()
@@ -527,6 +702,9 @@ impl Config {
             block_external_comms,
             primary_currency,
             stripe_secret_key,
+            stripe_webhook_secret,
+            payment_processors,
+            mock_payment_processor_enabled,
             token_issuer,
             front_end_url,
             tari_client,
@@ -541,6 +719,7 @@ impl Config {
             sendgrid_template_bn_transfer_tickets,
             sendgrid_template_bn_transfer_tickets_receipt,
             sendgrid_template_bn_transfer_tickets_drip_destination,
+            sendgrid_template_bn_transfer_tickets_returned,
             sendgrid_template_bn_transfer_tickets_drip_source,
             sendgrid_template_bn_user_invite,
             settlement_period_in_days,
@@ -554,10 +733,12 @@ impl Config {
             branch_io_timeout,
             max_instances_per_ticket_type,
             connection_pool,
+            domain_actions,
             ssr_trigger_header,
             ssr_trigger_value,
             sharetribe,
             product_context,
+            scanner_manifest_secret,
         }
     }
 }
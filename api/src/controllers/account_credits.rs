@@ -0,0 +1,14 @@
+use crate::auth::user::User as AuthUser;
+use crate::database::Connection;
+use crate::errors::*;
+use actix_web::HttpResponse;
+use db::prelude::*;
+
+pub async fn index((connection, user): (Connection, AuthUser)) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let account_credits = AccountCredit::find_for_user(user.id(), connection)?
+        .iter()
+        .map(|account_credit| account_credit.for_display(connection))
+        .collect::<Result<Vec<DisplayAccountCredit>, DatabaseError>>()?;
+    Ok(HttpResponse::Ok().json(&account_credits))
+}
@@ -0,0 +1,29 @@
+use crate::auth::user::User as AuthUser;
+use crate::controllers::communication_templates::{
+    self, PreviewCommunicationTemplateRequest, SaveCommunicationTemplateRequest,
+};
+use crate::database::Connection;
+use crate::errors::*;
+use crate::extractors::*;
+use actix_web::HttpResponse;
+use db::prelude::*;
+
+/// Platform default templates, used by organizations without their own template
+pub async fn index((connection, user): (Connection, AuthUser)) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    user.requires_scope(Scopes::OrgAdmin)?;
+    let templates = CommunicationTemplate::find_for_organization(None, connection)?;
+    Ok(HttpResponse::Ok().json(&templates))
+}
+
+pub async fn create(
+    (connection, json, user): (Connection, Json<SaveCommunicationTemplateRequest>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    communication_templates::save(None, json.into_inner(), &user, connection.get())
+}
+
+pub async fn preview(
+    (connection, json, user): (Connection, Json<PreviewCommunicationTemplateRequest>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    communication_templates::render_preview(None, json.into_inner(), &user, connection.get())
+}
@@ -0,0 +1,48 @@
+use crate::auth::user::User as AuthUser;
+use crate::database::Connection;
+use crate::errors::*;
+use crate::models::PathParameters;
+use actix_web::{
+    web::{Path, Query},
+    HttpResponse,
+};
+use db::prelude::*;
+
+pub async fn metrics((connection, user): (Connection, AuthUser)) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    user.requires_scope(Scopes::OrgAdmin)?;
+    let metrics = DomainAction::queue_metrics(connection)?;
+    Ok(HttpResponse::Ok().json(metrics))
+}
+
+/// Actions that errored or exceeded their retries, filtered with the `domain_action_type` tag
+pub async fn dead_letter(
+    (connection, query, user): (Connection, Query<PagingParameters>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    user.requires_scope(Scopes::OrgAdmin)?;
+    let domain_action_type = match query.get_tag_as_str("domain_action_type") {
+        Some(domain_action_type) => Some(domain_action_type.parse()?),
+        None => None,
+    };
+    let actions = DomainAction::find_dead_letters(domain_action_type, query.page(), query.limit(), connection)?;
+    Ok(HttpResponse::Ok().json(actions))
+}
+
+pub async fn requeue(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    user.requires_scope(Scopes::OrgAdmin)?;
+    let action = DomainAction::find(parameters.id, connection)?.requeue(connection)?;
+    Ok(HttpResponse::Ok().json(action))
+}
+
+pub async fn cancel(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    user.requires_scope(Scopes::OrgAdmin)?;
+    let action = DomainAction::find(parameters.id, connection)?.cancel(connection)?;
+    Ok(HttpResponse::Ok().json(action))
+}
@@ -1,2 +1,4 @@
 pub mod admin;
+pub mod communication_templates;
+pub mod domain_actions;
 pub mod reports;
@@ -0,0 +1,80 @@
+use crate::auth::user::User as AuthUser;
+use crate::database::Connection;
+use crate::errors::*;
+use crate::extractors::*;
+use crate::helpers::application;
+use crate::models::PathParameters;
+use actix_web::{
+    web::{Path, Query},
+    HttpResponse,
+};
+use chrono::NaiveDateTime;
+use db::prelude::*;
+
+#[derive(Deserialize, Serialize)]
+pub struct NewApiKeyRequest {
+    pub name: String,
+    pub scopes: Vec<Scopes>,
+    pub expires_at: Option<NaiveDateTime>,
+}
+
+pub async fn index(
+    (connection, parameters, query_parameters, user): (
+        Connection,
+        Path<PathParameters>,
+        Query<PagingParameters>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let organization = Organization::find(parameters.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgAdminUsers, &organization, connection)?;
+    let api_keys = ApiKey::find_for_organization(organization.id, connection)?;
+    Ok(HttpResponse::Ok().json(&Payload::from_data(
+        api_keys,
+        query_parameters.page(),
+        query_parameters.limit(),
+        None,
+    )))
+}
+
+pub async fn create(
+    (connection, parameters, json, user): (Connection, Path<PathParameters>, Json<NewApiKeyRequest>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let organization = Organization::find(parameters.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgAdminUsers, &organization, connection)?;
+    if user.api_key.is_some() {
+        return application::forbidden("API keys cannot be used to issue API keys");
+    }
+
+    let json = json.into_inner();
+    let issued_api_key =
+        ApiKey::create(organization.id, user.id(), json.name, json.scopes, json.expires_at).commit(connection)?;
+    Ok(HttpResponse::Created().json(&issued_api_key))
+}
+
+pub async fn rotate(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let api_key = ApiKey::find(parameters.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgAdminUsers, &api_key.organization(connection)?, connection)?;
+    if user.api_key.is_some() {
+        return application::forbidden("API keys cannot be used to rotate API keys");
+    }
+
+    let issued_api_key = api_key.rotate(Some(user.id()), connection)?;
+    Ok(HttpResponse::Ok().json(&issued_api_key))
+}
+
+pub async fn destroy(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let api_key = ApiKey::find(parameters.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgAdminUsers, &api_key.organization(connection)?, connection)?;
+
+    let api_key = api_key.revoke(Some(user.id()), connection)?;
+    Ok(HttpResponse::Ok().json(&api_key))
+}
@@ -8,9 +8,11 @@ use crate::models::*;
 use crate::server::{AppState, GetAppState};
 use crate::utils::google_recaptcha;
 use actix_web::{web::Data, HttpRequest, HttpResponse};
+use chrono::prelude::*;
 use db::prelude::*;
 use diesel::PgConnection;
 use log::Level::Info;
+use serde_json::Value;
 use std::collections::HashMap;
 use uuid::Uuid;
 
@@ -21,6 +23,10 @@ pub struct LoginRequest {
     #[serde(rename = "g-recaptcha-response")]
     #[serde(default, deserialize_with = "deserialize_unless_blank")]
     captcha_response: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    totp_code: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    recovery_code: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -34,8 +40,20 @@ impl LoginRequest {
             email: String::from(email),
             password: String::from(password),
             captcha_response: None,
+            totp_code: None,
+            recovery_code: None,
         }
     }
+
+    pub fn with_totp_code(mut self, totp_code: &str) -> Self {
+        self.totp_code = Some(totp_code.to_string());
+        self
+    }
+
+    pub fn with_recovery_code(mut self, recovery_code: &str) -> Self {
+        self.recovery_code = Some(recovery_code.to_string());
+        self
+    }
 }
 
 impl RefreshRequest {
@@ -87,18 +105,111 @@ pub async fn token(
         }
     };
 
+    if UserLoginThrottle::locked_until(user.id, connection.get())?.is_some() {
+        return application::unauthorized_with_message(
+            "Too many failed login attempts, please try again later",
+            None,
+            Some(login_log_data),
+        );
+    }
+
     if !user.check_password(&login_request.password) {
+        record_failed_login(&user, &connection)?;
         return application::unauthorized_with_message(login_failure_messaging, None, Some(login_log_data));
     }
 
-    user.login_domain_event(json!(request_info), connection.get())?;
-    jlog!(Info, "User logged in via email and password", {"id": user.id, "email": user.email.clone()});
-    let response = TokenResponse::create_from_user(&*state.config.token_issuer, state.config.jwt_expiry_time, &user)?;
-    Ok(response)
+    let auth_method = match verify_second_factor(
+        &user,
+        login_request.totp_code.as_ref(),
+        login_request.recovery_code.as_ref(),
+        &state,
+        &connection,
+        login_log_data,
+    )? {
+        Some(second_factor) => format!("password_{}", second_factor),
+        None => "password".to_string(),
+    };
+    UserLoginThrottle::reset(user.id, connection.get())?;
+
+    let mut login_data = json!(&request_info);
+    login_data["auth_method"] = json!(auth_method);
+    user.login_domain_event(login_data, connection.get())?;
+
+    jlog!(Info, "User logged in via email and password", {"id": user.id, "email": user.email.clone(), "auth_method": auth_method});
+    create_login_token_response(&user, &state, &request_info, connection.get())
+}
+
+/// Checks the second factor of a user who has confirmed two-factor authentication, returning which
+/// factor was used or `None` when the user has not set it up. Every way of signing in must call
+/// this once the first factor has been checked.
+pub(crate) fn verify_second_factor(
+    user: &User,
+    totp_code: Option<&String>,
+    recovery_code: Option<&String>,
+    state: &AppState,
+    connection: &Connection,
+    login_log_data: HashMap<&'static str, Value>,
+) -> Result<Option<&'static str>, ApiError> {
+    let credential = match UserTotpCredential::find_confirmed_for_user(user.id, connection.get())? {
+        Some(credential) => credential,
+        None => return Ok(None),
+    };
+
+    let (verified, second_factor) = match (totp_code, recovery_code) {
+        (Some(totp_code), _) => (
+            credential.verify(totp_code, &state.config.api_keys_encryption_key, connection.get())?,
+            "totp",
+        ),
+        (None, Some(recovery_code)) => (
+            UserRecoveryCode::redeem(user.id, recovery_code, connection.get())?,
+            "recovery_code",
+        ),
+        (None, None) => {
+            return application::unauthorized_with_message("Two-factor code required", None, Some(login_log_data));
+        }
+    };
+    if !verified {
+        record_failed_login(user, connection)?;
+        return application::unauthorized_with_message("Two-factor code invalid", None, Some(login_log_data));
+    }
+    Ok(Some(second_factor))
+}
+
+/// Issues the tokens for a completed sign in, users whose organizations require two-factor
+/// authentication they have not set up only receive a token for enrolling
+pub(crate) fn create_login_token_response(
+    user: &User,
+    state: &AppState,
+    request_info: &RequestInfo,
+    conn: &PgConnection,
+) -> Result<TokenResponse, ApiError> {
+    if user.two_factor_enrollment_required(conn)? {
+        jlog!(Info, "User logged in pending two-factor enrollment", {"id": user.id, "email": user.email.clone()});
+        return TokenResponse::create_for_two_factor_enrollment(
+            &*state.config.token_issuer,
+            state.config.jwt_expiry_time,
+            user,
+        );
+    }
+
+    TokenResponse::create_from_user(
+        &*state.config.token_issuer,
+        state.config.jwt_expiry_time,
+        user,
+        request_info,
+        conn,
+    )
+}
+
+// Failed attempts are kept by committing the request's transaction even though it fails
+fn record_failed_login(user: &User, connection: &Connection) -> Result<(), ApiError> {
+    UserLoginThrottle::record_failure(user.id, connection.get())?;
+    connection.commit_on_error();
+    Ok(())
 }
 
 pub async fn token_refresh(
-    (state, connection, refresh_request): (Data<AppState>, Connection, Json<RefreshRequest>),
+    (state, connection, refresh_request, request_info): (Data<AppState>, Connection, Json<RefreshRequest>, RequestInfo),
 ) -> Result<HttpResponse, ApiError> {
     let mut validation = Validation::default();
     validation.validate_exp = false;
@@ -132,8 +243,41 @@ pub async fn token_refresh(
         return application::unauthorized_with_message("Token can not be used to refresh", None, None);
     }
 
+    let session = match (token.claims.session_id, token.claims.refresh_token_id) {
+        (Some(session_id), Some(refresh_token_id)) => {
+            let session = UserSession::find(session_id, conn)?;
+            if session.user_id != user.id {
+                return application::unauthorized_with_message("Token no longer valid", None, None);
+            }
+            match session.rotate_refresh_token(refresh_token_id, conn)? {
+                Some(session) => session,
+                None => {
+                    // Keep the revocation of a session whose refresh token was reused
+                    connection.commit_on_error();
+                    return application::unauthorized_with_message("Token no longer valid", None, None);
+                }
+            }
+        }
+        // Magic links, temporary user links and refresh tokens issued before sessions were tracked
+        // can each start a single session before they expire
+        _ => {
+            if token.claims.exp < Utc::now().timestamp() as u64
+                || UserSession::token_used(&refresh_request.refresh_token, conn)?
+            {
+                return application::unauthorized_with_message("Token no longer valid", None, None);
+            }
+            UserSession::create(
+                user.id,
+                request_info.user_agent.clone(),
+                request_info.ip_address.clone(),
+            )
+            .started_by_token(&refresh_request.refresh_token)
+            .commit(conn)?
+        }
+    };
+
     let response =
-        TokenResponse::create_from_refresh_token(&*state.config.token_issuer, state.config.jwt_expiry_time, user.id)?;
+        TokenResponse::create_for_session(&*state.config.token_issuer, state.config.jwt_expiry_time, &session)?;
 
     Ok(HttpResponse::Ok().json(response))
 }
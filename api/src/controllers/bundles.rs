@@ -0,0 +1,138 @@
+use crate::auth::user::User as AuthUser;
+use crate::database::Connection;
+use crate::errors::*;
+use crate::extractors::*;
+use crate::helpers::application;
+use crate::models::{BundleTicketTypePathParameters, PathParameters};
+use actix_web::{
+    web::{Path, Query},
+    HttpResponse,
+};
+use db::prelude::*;
+use uuid::Uuid;
+
+#[derive(Deserialize, Serialize)]
+pub struct NewBundleRequest {
+    pub name: String,
+    pub currency: Option<String>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct BundleTicketTypeRequest {
+    pub ticket_type_id: Uuid,
+    pub quantity: i32,
+    pub unit_price_in_cents: i64,
+}
+
+pub async fn index(
+    (connection, parameters, query_parameters, user): (
+        Connection,
+        Path<PathParameters>,
+        Query<PagingParameters>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let organization = Organization::find(parameters.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgReadEvents, &organization, connection)?;
+    let bundles = Bundle::find_for_organization(organization.id, connection)?;
+    Ok(HttpResponse::Ok().json(&Payload::from_data(
+        bundles,
+        query_parameters.page(),
+        query_parameters.limit(),
+        None,
+    )))
+}
+
+pub async fn create(
+    (connection, parameters, json, user): (Connection, Path<PathParameters>, Json<NewBundleRequest>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let organization = Organization::find(parameters.id, connection)?;
+    user.requires_scope_for_organization(Scopes::EventWrite, &organization, connection)?;
+
+    let json = json.into_inner();
+    let bundle = Bundle::create(
+        organization.id,
+        json.name,
+        json.currency.unwrap_or_else(|| organization.currency.clone()),
+    )
+    .commit(Some(user.id()), connection)?;
+
+    Ok(HttpResponse::Created().json(&bundle))
+}
+
+pub async fn show((connection, parameters): (Connection, Path<PathParameters>)) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let bundle = Bundle::find(parameters.id, connection)?;
+    if bundle.deleted_at.is_some() {
+        return application::not_found();
+    }
+    Ok(HttpResponse::Ok().json(&bundle.for_display(connection)?))
+}
+
+pub async fn update(
+    (connection, parameters, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<BundleEditableAttributes>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let bundle = Bundle::find(parameters.id, connection)?;
+    user.requires_scope_for_organization(Scopes::EventWrite, &bundle.organization(connection)?, connection)?;
+
+    let bundle = bundle.update(json.into_inner(), Some(user.id()), connection)?;
+    Ok(HttpResponse::Ok().json(&bundle))
+}
+
+pub async fn destroy(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let bundle = Bundle::find(parameters.id, connection)?;
+    user.requires_scope_for_organization(Scopes::EventWrite, &bundle.organization(connection)?, connection)?;
+
+    bundle.destroy(Some(user.id()), connection)?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+pub async fn add_ticket_type(
+    (connection, parameters, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<BundleTicketTypeRequest>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let bundle = Bundle::find(parameters.id, connection)?;
+    let event = TicketType::find(json.ticket_type_id, connection)?.event(connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::EventWrite,
+        &bundle.organization(connection)?,
+        &event,
+        connection,
+    )?;
+
+    bundle.add_ticket_type(
+        json.ticket_type_id,
+        json.quantity,
+        json.unit_price_in_cents,
+        Some(user.id()),
+        connection,
+    )?;
+    Ok(HttpResponse::Ok().json(&bundle.for_display(connection)?))
+}
+
+pub async fn remove_ticket_type(
+    (connection, parameters, user): (Connection, Path<BundleTicketTypePathParameters>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let bundle = Bundle::find(parameters.id, connection)?;
+    user.requires_scope_for_organization(Scopes::EventWrite, &bundle.organization(connection)?, connection)?;
+
+    bundle.remove_ticket_type(parameters.ticket_type_id, Some(user.id()), connection)?;
+    Ok(HttpResponse::Ok().json(&bundle.for_display(connection)?))
+}
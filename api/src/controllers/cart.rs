@@ -90,6 +90,69 @@ pub async fn update_cart(
     Ok(HttpResponse::Ok().json(Order::find(cart.id, connection)?.for_display(None, user.id(), connection)?))
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct ReserveSeatsRequest {
+    pub ticket_type_id: Uuid,
+    pub seat_ids: Vec<Uuid>,
+}
+
+pub async fn reserve_seats(
+    (connection, json, user, request_info): (Connection, Json<ReserveSeatsRequest>, User, RequestInfo),
+) -> Result<HttpResponse, ApiError> {
+    let json = json.into_inner();
+    jlog!(Debug, "Reserve seats", {"request": json, "user_id": user.id()});
+    let connection = connection.get();
+
+    if !Dbticket_types::is_event_available_for_sale(&json.ticket_type_id, connection)? {
+        return Ok(HttpResponse::BadRequest().json(json!({"error": "Event has not been published.".to_string()})));
+    }
+
+    let mut cart = Order::find_or_create_cart(&user.user, connection)?;
+    cart.reserve_seats(user.id(), json.ticket_type_id, &json.seat_ids, connection)?;
+    cart.set_browser_data(request_info.user_agent.clone(), false, connection)?;
+
+    Ok(HttpResponse::Ok().json(Order::find(cart.id, connection)?.for_display(None, user.id(), connection)?))
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct UpdateBundleRequest {
+    pub bundle_id: Uuid,
+    pub quantity: u32,
+}
+
+pub async fn update_bundle(
+    (connection, json, user, request_info): (Connection, Json<UpdateBundleRequest>, User, RequestInfo),
+) -> Result<HttpResponse, ApiError> {
+    let json = json.into_inner();
+    jlog!(Debug, "Update bundle in cart", {"request": json, "user_id": user.id()});
+    let connection = connection.get();
+
+    let mut cart = Order::find_or_create_cart(&user.user, connection)?;
+    cart.update_bundle_quantity(user.id(), json.bundle_id, json.quantity, connection)?;
+    cart.set_browser_data(request_info.user_agent.clone(), false, connection)?;
+
+    Ok(HttpResponse::Ok().json(Order::find(cart.id, connection)?.for_display(None, user.id(), connection)?))
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AddListingRequest {
+    pub listing_id: Uuid,
+}
+
+pub async fn add_listing(
+    (connection, json, user, request_info): (Connection, Json<AddListingRequest>, User, RequestInfo),
+) -> Result<HttpResponse, ApiError> {
+    let json = json.into_inner();
+    jlog!(Debug, "Add listing to cart", {"request": json, "user_id": user.id()});
+    let connection = connection.get();
+
+    let mut cart = Order::find_or_create_cart(&user.user, connection)?;
+    cart.add_listing(user.id(), json.listing_id, connection)?;
+    cart.set_browser_data(request_info.user_agent.clone(), false, connection)?;
+
+    Ok(HttpResponse::Ok().json(Order::find(cart.id, connection)?.for_display(None, user.id(), connection)?))
+}
+
 pub async fn duplicate(
     (connection, path, user): (Connection, Path<PathParameters>, User),
 ) -> Result<HttpResponse, ApiError> {
@@ -110,6 +173,7 @@ pub async fn destroy((connection, user): (Connection, User)) -> Result<HttpRespo
 
     // Find the current cart of the user, if it exists.
     let mut cart = Order::find_or_create_cart(&user.user, connection)?;
+    cart.clear_cart(user.id(), connection)?;
     cart.update_quantities(user.id(), &[], false, true, connection)?;
 
     Ok(HttpResponse::Ok().json(Order::find(cart.id, connection)?.for_display(None, user.id(), connection)?))
@@ -173,6 +237,11 @@ pub async fn show((connection, user): (Connection, User)) -> Result<HttpResponse
 pub struct CheckoutCartRequest {
     pub method: PaymentRequest,
     pub tracking_data: Option<serde_json::Value>,
+    // Gift cards and account credit are applied before the payment method is charged any remaining amount
+    #[serde(default)]
+    pub gift_card_codes: Vec<String>,
+    #[serde(default)]
+    pub use_account_credit: bool,
 }
 
 #[derive(Deserialize)]
@@ -196,6 +265,9 @@ pub enum PaymentRequest {
         provider: PaymentProviders,
         save_payment_method: bool,
         set_default: bool,
+        // Pay in installments, the card is saved to charge the remaining installments
+        #[serde(default)]
+        payment_plan: bool,
     },
     Provider {
         provider: PaymentProviders,
@@ -203,6 +275,11 @@ pub enum PaymentRequest {
     PaymentMethod {
         #[serde(default, deserialize_with = "deserialize_unless_blank")]
         provider: Option<PaymentProviders>,
+        // Saved card to charge, the default payment method is used when neither this nor provider are given
+        #[serde(default)]
+        payment_method_id: Option<Uuid>,
+        #[serde(default)]
+        payment_plan: bool,
     },
     // Only for 0 amount carts
     Free,
@@ -275,6 +352,49 @@ pub async fn checkout(
         }
     }
 
+    // Orders are charged in the currency of their items, empty orders fall back to the platform currency
+    let currency = match order.currency(connection.get())? {
+        Some(currency) => currency.to_lowercase(),
+        None => state.config.primary_currency.clone(),
+    };
+
+    if !req.gift_card_codes.is_empty() || req.use_account_credit {
+        info!("CART: Applying stored value");
+        // Stored value applied by an earlier checkout attempt stays on the order until it expires
+        let applied_references: Vec<String> = order
+            .payments(connection.get())?
+            .into_iter()
+            .filter(|p| p.status == PaymentStatus::Completed)
+            .filter_map(|p| p.external_reference)
+            .collect();
+        for code in &req.gift_card_codes {
+            let gift_card = match GiftCard::find_by_code(code, connection.get()).optional()? {
+                Some(gift_card) => gift_card,
+                None => return application::unprocessable("Gift card code is not valid"),
+            };
+            if order.amount_due(connection.get())? == 0 {
+                break;
+            } else if applied_references.contains(&gift_card.id.to_string()) {
+                continue;
+            }
+            order.add_gift_card_payment(&gift_card, user.id(), connection.get())?;
+        }
+        let account_credit_applied = order
+            .payments(connection.get())?
+            .iter()
+            .any(|p| p.status == PaymentStatus::Completed && p.payment_method == PaymentMethods::AccountCredit);
+        if req.use_account_credit && !account_credit_applied && order.amount_due(connection.get())? > 0 {
+            order.add_account_credit_payment(user.id(), connection.get())?;
+        }
+
+        // Fully covered orders are complete without charging the payment method
+        if order.status == OrderStatus::Paid {
+            let mut order = Order::find(order.id, connection.get())?;
+            order.set_browser_data(request_info.user_agent.clone(), true, connection.get())?;
+            return Ok(HttpResponse::Ok().json(json!(order.for_display(None, user.id(), connection.get())?)));
+        }
+    }
+
     let payment_response = match &req.method {
         PaymentRequest::Free => {
             info!("CART: Received checkout for free cart");
@@ -304,12 +424,33 @@ pub async fn checkout(
                 &request_info,
             )?
         }
-        PaymentRequest::PaymentMethod { provider } => {
+        PaymentRequest::PaymentMethod {
+            provider,
+            payment_method_id,
+            payment_plan,
+        } => {
             info!("CART: Received provider payment");
-            let provider: PaymentProviders = match provider {
-                Some(provider) => provider.clone(),
-                None => match user.user.default_payment_method(connection.get()).optional()? {
-                    Some(payment_method) => payment_method.name,
+            let payment_method = match (payment_method_id, provider) {
+                (Some(payment_method_id), _) => {
+                    let payment_method = match PaymentMethod::find(*payment_method_id, connection.get()).optional()? {
+                        Some(payment_method) => payment_method,
+                        None => return application::not_found(),
+                    };
+                    if payment_method.user_id != user.id() {
+                        return application::forbidden("This payment method does not belong to you");
+                    }
+                    payment_method
+                }
+                (None, Some(provider)) => match user.user.payment_method(*provider, connection.get()).optional()? {
+                    Some(payment_method) => payment_method,
+                    None => {
+                        return application::unprocessable(
+                            "Could not complete this cart because stored provider does not exist",
+                        );
+                    }
+                },
+                (None, None) => match user.user.default_payment_method(connection.get()).optional()? {
+                    Some(payment_method) => payment_method,
                     None => {
                         return application::unprocessable(
                             "Could not complete this cart because user has no default payment method",
@@ -323,11 +464,12 @@ pub async fn checkout(
                 &mut order,
                 None,
                 &user,
-                &state.config.primary_currency,
-                provider.clone(),
-                true,
+                &currency,
+                payment_method.name,
+                Some(payment_method),
                 false,
                 false,
+                *payment_plan,
                 &state.service_locator,
                 &state.config,
                 &request_info,
@@ -340,8 +482,9 @@ pub async fn checkout(
                 &mut order,
                 None,
                 &user,
-                &state.config.primary_currency,
+                &currency,
                 *provider,
+                None,
                 false,
                 false,
                 false,
@@ -356,17 +499,19 @@ pub async fn checkout(
             provider,
             save_payment_method,
             set_default,
+            payment_plan,
         } => {
             checkout_payment_processor(
                 &connection,
                 &mut order,
                 Some(&token),
                 &user,
-                &state.config.primary_currency,
+                &currency,
                 *provider,
-                false,
+                None,
                 *save_payment_method,
                 *set_default,
+                *payment_plan,
                 &state.service_locator,
                 &state.config,
                 &request_info,
@@ -386,7 +531,7 @@ fn checkout_free(
     let conn = conn.get();
     if order.status != OrderStatus::Draft {
         return application::unprocessable("Could not complete this cart because it is not in the correct status");
-    } else if order.calculate_total(conn)? > 0 {
+    } else if order.amount_due(conn)? > 0 {
         // TODO: make this line cleaner
         return application::unprocessable(
             "Could not use free payment method this cart because it has a total greater than zero",
@@ -456,7 +601,7 @@ fn checkout_external(
         order.create_note(note, user.id(), conn)?;
     }
     order.set_behalf_of_user(guest, user.id(), conn)?;
-    let total = order.calculate_total(conn)?;
+    let total = order.amount_due(conn)?;
 
     if total == 0 {
         order.add_free_payment(true, user.id(), conn)?;
@@ -476,9 +621,10 @@ async fn checkout_payment_processor(
     auth_user: &User,
     currency: &str,
     provider: PaymentProviders,
-    use_stored_payment: bool,
+    stored_payment_method: Option<PaymentMethod>,
     save_payment_method: bool,
     set_default: bool,
+    payment_plan: bool,
     service_locator: &ServiceLocator,
     config: &Config,
     request_info: &RequestInfo,
@@ -490,7 +636,7 @@ async fn checkout_payment_processor(
         return application::forbidden("This cart does not belong to you");
     } else if order.status != OrderStatus::Draft {
         return application::unprocessable("Could not complete this cart because it is not in the correct status");
-    } else if order.calculate_total(connection)? == 0 {
+    } else if order.amount_due(connection)? == 0 {
         return application::unprocessable("Could not complete this cart; only paid orders require payment processing");
     }
 
@@ -502,23 +648,25 @@ async fn checkout_payment_processor(
     };
 
     let event = events.remove(0);
+    let organization = event.organization(connection)?;
+    if !organization.allowed_payment_providers.contains(&provider) {
+        return application::unprocessable(
+            "Could not complete this cart because the payment provider is not available",
+        );
+    }
 
-    let client = service_locator.create_payment_processor(provider, &event.organization(connection)?)?;
+    let client = service_locator.create_payment_processor(provider, &organization)?;
     match client.behavior() {
         PaymentProcessorBehavior::RedirectToPaymentPage(behavior) => {
-            return redirect_to_payment_page(&*behavior, &auth_user.user, order, conn.get(), config).await;
+            if payment_plan {
+                return application::unprocessable("Payment plans are not supported by this payment provider");
+            }
+            return redirect_to_payment_page(&*behavior, &auth_user.user, order, currency, conn.get(), config).await;
         }
         PaymentProcessorBehavior::AuthThenComplete(behavior) => {
-            let token = if use_stored_payment {
+            let (token, payment_method) = if let Some(payment_method) = stored_payment_method {
                 info!("CART: Using stored payment");
-                match auth_user.user.payment_method(provider, connection).optional()? {
-                    Some(payment_method) => payment_method.provider,
-                    None => {
-                        return application::unprocessable(
-                            "Could not complete this cart because stored provider does not exist",
-                        );
-                    }
-                }
+                (payment_method.provider.clone(), Some(payment_method))
             } else {
                 info!("CART: Not using stored payment");
                 let token = match token {
@@ -528,50 +676,49 @@ async fn checkout_payment_processor(
                     }
                 };
 
-                if save_payment_method {
+                if save_payment_method || payment_plan {
                     info!("CART: User has requested to save the payment method");
-                    match auth_user.user.payment_method(provider, connection).optional()? {
-                        Some(payment_method) => {
-                            let behavior = match client.behavior() {
-                                    PaymentProcessorBehavior::AuthThenComplete(b) => b,
-                                    _ => return application::unprocessable(
-                                        "Could not complete this cart using saved payment methods is not supported for this payment processor",
-                                    )
-                                };
-                            let client_response = behavior
-                                .update_repeat_token(&payment_method.provider, token, SITE_NAME)
-                                .await?;
-                            let payment_method_parameters = PaymentMethodEditableAttributes {
-                                provider_data: Some(client_response.to_json()?),
-                            };
-                            payment_method.update(&payment_method_parameters, auth_user.id(), connection)?;
-
-                            payment_method.provider
-                        }
-                        None => {
-                            let behavior = match client.behavior() {
-                                    PaymentProcessorBehavior::AuthThenComplete(b) => b,
-                                    _ => return application::unprocessable(
-                                        "Could not complete this cart using saved payment methods is not supported for this payment processor",
-                                    )
-                                };
-                            let repeat_token = behavior.create_token_for_repeat_charges(token, SITE_NAME).await?;
-                            let _payment_method = PaymentMethod::create(
-                                auth_user.id(),
-                                provider,
-                                set_default,
-                                repeat_token.token.clone(),
-                                repeat_token.to_json()?,
-                            )
-                            .commit(auth_user.id(), connection)?;
-                            repeat_token.token
-                        }
+                    // Each saved card gets its own repeat charge token so earlier cards remain usable
+                    let repeat_token = behavior.create_token_for_repeat_charges(token, SITE_NAME).await?;
+                    let is_default =
+                        set_default || auth_user.user.default_payment_method(connection).optional()?.is_none();
+                    let mut payment_method = PaymentMethod::create(
+                        auth_user.id(),
+                        provider,
+                        is_default,
+                        repeat_token.token.clone(),
+                        repeat_token.to_json()?,
+                    );
+                    if let Some(card) = repeat_token.card {
+                        payment_method =
+                            payment_method.with_card(card.brand, card.last_four, card.expiry_month, card.expiry_year);
                     }
+                    let payment_method = payment_method.commit(auth_user.id(), connection)?;
+                    (repeat_token.token, Some(payment_method))
                 } else {
-                    token.to_string()
+                    (token.to_string(), None)
                 }
             };
 
+            // The payment method is always saved above when paying in installments
+            if let (true, Some(payment_method)) = (payment_plan, payment_method) {
+                info!("CART: Creating payment plan");
+                let payment_plan =
+                    PaymentPlan::create_for_order(order, &payment_method, Some(auth_user.id()), connection)?;
+                return charge_first_installment(
+                    &*behavior,
+                    token,
+                    currency,
+                    order,
+                    &payment_plan,
+                    auth_user,
+                    conn,
+                    &*client,
+                    request_info,
+                )
+                .await;
+            }
+
             return auth_then_complete(
                 &*behavior,
                 token,
@@ -580,6 +727,7 @@ async fn checkout_payment_processor(
                 auth_user,
                 conn,
                 &*client,
+                config,
                 request_info,
             )
             .await;
@@ -595,11 +743,17 @@ async fn auth_then_complete(
     auth_user: &User,
     conn: &Connection,
     payment_processor: &dyn PaymentProcessor,
+    config: &Config,
     request_info: &RequestInfo,
 ) -> Result<HttpResponse, ApiError> {
     let connection = conn.get();
     info!("CART: Auth'ing to payment provider");
-    let amount = order.calculate_total(connection)?;
+    let amount = order.amount_due(connection)?;
+    let nonce = random_alpha_string(12);
+    let return_url = format!(
+        "{}/payments/callback/{}/{}?success=true",
+        &config.api_base_url, nonce, order.id,
+    );
     let auth_result = client
         .auth(
             &token,
@@ -607,9 +761,32 @@ async fn auth_then_complete(
             currency,
             SITE_NAME,
             order.purchase_metadata(connection)?,
+            Some(&return_url),
         )
         .await?;
 
+    if let Some(challenge) = auth_result.challenge.clone() {
+        // The charge is completed from the payment callback once the shopper passes the challenge
+        info!("CART: Payment provider requires card authentication");
+        order.add_checkout_url(
+            auth_user.id(),
+            challenge.redirect_url.clone(),
+            challenge.expires_at,
+            connection,
+        )?;
+        order.add_challenged_credit_card_payment(
+            auth_user.id(),
+            amount,
+            client.payment_provider(),
+            auth_result.id.clone(),
+            nonce,
+            auth_result.to_json()?,
+            connection,
+        )?;
+        let order = Order::find(order.id, connection)?;
+        return Ok(HttpResponse::Ok().json(json!(order.for_display(None, auth_user.id(), connection)?)));
+    }
+
     info!("CART: Saving payment to order");
     let payment = match order.add_credit_card_payment(
         auth_user.id(),
@@ -634,7 +811,64 @@ async fn auth_then_complete(
     let charge_result = client.complete_authed_charge(&auth_result.id).await?;
     info!("CART: Completing payment on order");
     info!("charge_result:{:?}", charge_result);
-    match payment.mark_complete(charge_result.to_json()?, Some(auth_user.id()), connection) {
+    let result = if charge_result.capture_pending {
+        // Tickets are held until the provider confirms the capture
+        payment.mark_pending_ipn(Some(auth_user.id()), connection)
+    } else {
+        payment.mark_complete(charge_result.to_json()?, Some(auth_user.id()), connection)
+    };
+    match result {
+        Ok(_) => {
+            let mut order = Order::find(order.id, connection)?;
+            order.set_browser_data(request_info.user_agent.clone(), true, connection)?;
+            Ok(HttpResponse::Ok().json(json!(order.for_display(None, auth_user.id(), connection)?)))
+        }
+        Err(e) => {
+            payment_processor.refund(&auth_result.id).await?;
+            Err(e.into())
+        }
+    }
+}
+
+async fn charge_first_installment(
+    client: &dyn AuthThenCompletePaymentBehavior,
+    token: String,
+    currency: &str,
+    order: &mut Order,
+    payment_plan: &PaymentPlan,
+    auth_user: &User,
+    conn: &Connection,
+    payment_processor: &dyn PaymentProcessor,
+    request_info: &RequestInfo,
+) -> Result<HttpResponse, ApiError> {
+    let connection = conn.get();
+    let installment = match payment_plan.installments(connection)?.into_iter().next() {
+        Some(installment) => installment,
+        None => return application::internal_server_error("Payment plan has no installments"),
+    };
+
+    info!("CART: Charging first installment with payment provider");
+    let auth_result = client
+        .auth(
+            &token,
+            installment.amount_in_cents,
+            currency,
+            SITE_NAME,
+            order.purchase_metadata(connection)?,
+            None,
+        )
+        .await?;
+    // Installments are recorded as paid once the processor accepts the capture
+    let charge_result = client.complete_authed_charge(&auth_result.id).await?;
+
+    info!("CART: Saving installment payment to order");
+    match installment.mark_paid(
+        client.payment_provider(),
+        charge_result.id.clone(),
+        charge_result.to_json()?,
+        Some(auth_user.id()),
+        connection,
+    ) {
         Ok(_) => {
             let mut order = Order::find(order.id, connection)?;
             order.set_browser_data(request_info.user_agent.clone(), true, connection)?;
@@ -651,6 +885,7 @@ async fn redirect_to_payment_page(
     client: &dyn RedirectToPaymentPageBehavior,
     user: &DbUser,
     order: &mut Order,
+    currency: &str,
     conn: &PgConnection,
     config: &Config,
 ) -> Result<HttpResponse, ApiError> {
@@ -658,7 +893,7 @@ async fn redirect_to_payment_page(
         return application::unprocessable("User must have an email to check out");
     }
 
-    let amount = order.calculate_total(conn)?;
+    let amount = order.amount_due(conn)?;
 
     let email = user.email.as_ref().unwrap().to_string();
 
@@ -668,6 +903,7 @@ async fn redirect_to_payment_page(
     let response = client
         .create_payment_request(
             amount as f64 / 100_f64,
+            currency,
             email,
             order.id,
             ipn,
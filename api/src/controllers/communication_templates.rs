@@ -0,0 +1,180 @@
+use crate::auth::user::User as AuthUser;
+use crate::database::Connection;
+use crate::errors::*;
+use crate::extractors::*;
+use crate::models::PathParameters;
+use actix_web::{web::Path, HttpResponse};
+use db::prelude::*;
+use diesel::PgConnection;
+use uuid::Uuid;
+
+#[derive(Deserialize, Serialize)]
+pub struct SaveCommunicationTemplateRequest {
+    pub template_name: CommunicationTemplateNames,
+    pub communication_type: CommunicationType,
+    pub subject: String,
+    pub body: String,
+}
+
+/// Previews the given content, or the saved template when no content is given
+#[derive(Deserialize, Serialize)]
+pub struct PreviewCommunicationTemplateRequest {
+    pub template_name: CommunicationTemplateNames,
+    pub communication_type: CommunicationType,
+    pub subject: Option<String>,
+    pub body: Option<String>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct RollbackCommunicationTemplateRequest {
+    pub version: i32,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct CommunicationTemplatePreviewResponse {
+    pub subject: String,
+    pub body: String,
+    pub variables: Vec<String>,
+    pub sample_data: TemplateData,
+}
+
+/// Organization templates need the organization admin scope, platform defaults need it globally
+fn authorize(organization_id: Option<Uuid>, user: &AuthUser, conn: &PgConnection) -> Result<(), ApiError> {
+    match organization_id {
+        Some(organization_id) => {
+            let organization = Organization::find(organization_id, conn)?;
+            user.requires_scope_for_organization(Scopes::OrgAdmin, &organization, conn)?;
+        }
+        None => user.requires_scope(Scopes::OrgAdmin)?,
+    }
+    Ok(())
+}
+
+pub(crate) fn save(
+    organization_id: Option<Uuid>,
+    request: SaveCommunicationTemplateRequest,
+    user: &AuthUser,
+    conn: &PgConnection,
+) -> Result<HttpResponse, ApiError> {
+    authorize(organization_id, user, conn)?;
+    let template = CommunicationTemplate::create(
+        organization_id,
+        request.template_name,
+        request.communication_type,
+        request.subject,
+        request.body,
+    )
+    .commit(Some(user.id()), conn)?;
+    Ok(HttpResponse::Ok().json(&template))
+}
+
+pub(crate) fn render_preview(
+    organization_id: Option<Uuid>,
+    request: PreviewCommunicationTemplateRequest,
+    user: &AuthUser,
+    conn: &PgConnection,
+) -> Result<HttpResponse, ApiError> {
+    authorize(organization_id, user, conn)?;
+    let (subject, body) = match (request.subject, request.body) {
+        (Some(subject), Some(body)) => (subject, body),
+        (subject, body) => {
+            let template = CommunicationTemplate::find_active(
+                organization_id,
+                request.template_name,
+                request.communication_type,
+                conn,
+            )?
+            .ok_or_else(|| ApplicationError::unprocessable("No communication template has been saved"))?;
+            (subject.unwrap_or(template.subject), body.unwrap_or(template.body))
+        }
+    };
+
+    let sample_data = request.template_name.sample_data();
+    let rendered = CommunicationTemplate::render_content(request.communication_type, &subject, &body, &sample_data);
+    Ok(HttpResponse::Ok().json(&CommunicationTemplatePreviewResponse {
+        subject: rendered.subject,
+        body: rendered.body,
+        variables: request
+            .template_name
+            .variables()
+            .into_iter()
+            .map(|v| v.to_string())
+            .collect(),
+        sample_data,
+    }))
+}
+
+pub async fn index(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    authorize(Some(parameters.id), &user, connection)?;
+    let templates = CommunicationTemplate::find_for_organization(Some(parameters.id), connection)?;
+    Ok(HttpResponse::Ok().json(&templates))
+}
+
+/// Saves the organization's template, changes to an existing template are stored as a new version
+pub async fn create(
+    (connection, parameters, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<SaveCommunicationTemplateRequest>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    save(Some(parameters.id), json.into_inner(), &user, connection.get())
+}
+
+pub async fn preview(
+    (connection, parameters, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<PreviewCommunicationTemplateRequest>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    render_preview(Some(parameters.id), json.into_inner(), &user, connection.get())
+}
+
+pub async fn show(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let template = CommunicationTemplate::find(parameters.id, connection)?;
+    authorize(template.organization_id, &user, connection)?;
+    Ok(HttpResponse::Ok().json(&template))
+}
+
+pub async fn versions(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let template = CommunicationTemplate::find(parameters.id, connection)?;
+    authorize(template.organization_id, &user, connection)?;
+    Ok(HttpResponse::Ok().json(&template.versions(connection)?))
+}
+
+pub async fn rollback(
+    (connection, parameters, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<RollbackCommunicationTemplateRequest>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let template = CommunicationTemplate::find(parameters.id, connection)?;
+    authorize(template.organization_id, &user, connection)?;
+    let template = template.rollback(json.version, Some(user.id()), connection)?;
+    Ok(HttpResponse::Ok().json(&template))
+}
+
+pub async fn destroy(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let template = CommunicationTemplate::find(parameters.id, connection)?;
+    authorize(template.organization_id, &user, connection)?;
+    template.destroy(Some(user.id()), connection)?;
+    Ok(HttpResponse::Ok().finish())
+}
@@ -0,0 +1,172 @@
+use crate::auth::user::User as AuthUser;
+use crate::controllers::ticket_types;
+use crate::database::Connection;
+use crate::domain_events::executors::UpdateGenresPayload;
+use crate::errors::*;
+use crate::extractors::*;
+use crate::models::PathParameters;
+use crate::server::AppState;
+use actix_web::{
+    web::{Data, Path, Query},
+    HttpResponse,
+};
+use chrono::NaiveDateTime;
+use db::prelude::*;
+use diesel::PgConnection;
+use uuid::Uuid;
+
+#[derive(Deserialize, Serialize)]
+pub struct NewEventSeriesRequest {
+    pub template_event_id: Uuid,
+    pub name: String,
+    pub recurrence_type: RecurrenceTypes,
+    #[serde(default = "NewEventSeriesRequest::default_recurrence_interval")]
+    pub recurrence_interval: i32,
+    pub recurrence_until: Option<NaiveDateTime>,
+    #[serde(default)]
+    pub custom_dates: Vec<NaiveDateTime>,
+}
+
+impl NewEventSeriesRequest {
+    fn default_recurrence_interval() -> i32 {
+        1
+    }
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct EventSeriesResponse {
+    pub event_series: EventSeries,
+    pub generated_events: Vec<Event>,
+}
+
+pub async fn index(
+    (connection, parameters, query_parameters, user): (
+        Connection,
+        Path<PathParameters>,
+        Query<PagingParameters>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let organization = Organization::find(parameters.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgReadEvents, &organization, connection)?;
+    let event_series = EventSeries::find_for_organization(organization.id, connection)?;
+    Ok(HttpResponse::Ok().json(&Payload::from_data(
+        event_series,
+        query_parameters.page(),
+        query_parameters.limit(),
+        None,
+    )))
+}
+
+pub async fn create(
+    (connection, parameters, json, user, state): (
+        Connection,
+        Path<PathParameters>,
+        Json<NewEventSeriesRequest>,
+        AuthUser,
+        Data<AppState>,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let organization = Organization::find(parameters.id, connection)?;
+    let json = json.into_inner();
+    let template_event = Event::find(json.template_event_id, connection)?;
+    user.requires_scope_for_organization_event(Scopes::EventClone, &organization, &template_event, connection)?;
+
+    let event_series = EventSeries::create(
+        organization.id,
+        json.template_event_id,
+        json.name,
+        json.recurrence_type,
+        json.recurrence_interval,
+        json.recurrence_until,
+        json.custom_dates,
+    )
+    .commit(Some(user.id()), connection)?;
+    let generated_events = generate_occurrences(&event_series, &user, &state, connection)?;
+
+    Ok(HttpResponse::Created().json(&EventSeriesResponse {
+        event_series,
+        generated_events,
+    }))
+}
+
+pub async fn show((connection, parameters): (Connection, Path<PathParameters>)) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let event_series = EventSeries::find(parameters.id, connection)?;
+    Ok(HttpResponse::Ok().json(&event_series.for_display(connection)?))
+}
+
+pub async fn update(
+    (connection, parameters, json, user, state): (
+        Connection,
+        Path<PathParameters>,
+        Json<EventSeriesEditableAttributes>,
+        AuthUser,
+        Data<AppState>,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let event_series = EventSeries::find(parameters.id, connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::EventClone,
+        &event_series.organization(connection)?,
+        &event_series.template_event(connection)?,
+        connection,
+    )?;
+
+    let event_series = event_series.update(json.into_inner(), Some(user.id()), connection)?;
+    let generated_events = generate_occurrences(&event_series, &user, &state, connection)?;
+
+    Ok(HttpResponse::Ok().json(&EventSeriesResponse {
+        event_series,
+        generated_events,
+    }))
+}
+
+pub async fn update_events(
+    (connection, parameters, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<EventEditableAttributes>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let event_series = EventSeries::find(parameters.id, connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::EventWrite,
+        &event_series.organization(connection)?,
+        &event_series.template_event(connection)?,
+        connection,
+    )?;
+
+    let events = event_series.update_future_occurrences(json.into_inner(), Some(user.id()), connection)?;
+    Ok(HttpResponse::Ok().json(&events))
+}
+
+fn generate_occurrences(
+    event_series: &EventSeries,
+    user: &AuthUser,
+    state: &Data<AppState>,
+    connection: &PgConnection,
+) -> Result<Vec<Event>, ApiError> {
+    let events = event_series.generate_occurrences(Some(user.id()), connection)?;
+    for event in &events {
+        let ticket_types = event.ticket_types(false, None, connection)?;
+        ticket_types::create_ticket_type_blockchain_assets(event, &ticket_types, state, connection)?;
+
+        DomainAction::create(
+            None,
+            DomainActionTypes::UpdateGenres,
+            None,
+            json!(UpdateGenresPayload { user_id: user.id() }),
+            Some(Tables::Events),
+            Some(event.id),
+        )
+        .commit(connection)?;
+    }
+
+    Ok(events)
+}
@@ -699,6 +699,9 @@ pub async fn redeem_ticket(
             Ok(HttpResponse::BadRequest()
                 .json(json!({"error": "Ticket has pending transfer in progress.".to_string()})))
         }
+        RedeemResults::TicketAwaitingPayment => {
+            Ok(HttpResponse::BadRequest().json(json!({"error": "Ticket has not been fully paid.".to_string()})))
+        }
         RedeemResults::TicketAlreadyRedeemed => Ok(HttpResponse::Conflict().json(json!({
         "error": "Ticket has already been redeemed.".to_string(),
         "redeemed_by": redeemable.redeemed_by,
@@ -710,6 +713,94 @@ pub async fn redeem_ticket(
     }
 }
 
+#[derive(Deserialize, Serialize, Debug)]
+pub struct ScannerManifestParameters {
+    pub changes_since: Option<NaiveDateTime>,
+}
+
+pub async fn scanner_manifest(
+    (connection, parameters, query, auth_user, state): (
+        Connection,
+        Path<PathParameters>,
+        Query<ScannerManifestParameters>,
+        AuthUser,
+        Data<AppState>,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let event = Event::find(parameters.id, connection)?;
+    let organization = event.organization(connection)?;
+    auth_user.requires_scope_for_organization_event(Scopes::RedeemTicket, &organization, &event, connection)?;
+
+    let manifest = ScannerManifest::create(
+        event.id,
+        query.changes_since,
+        &state.config.scanner_manifest_secret,
+        connection,
+    )?;
+    Ok(HttpResponse::Ok().json(manifest))
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct OfflineRedemptionsRequest {
+    pub redemptions: Vec<OfflineRedemption>,
+}
+
+pub async fn redeem_offline_tickets(
+    (connection, parameters, redemption_parameters, auth_user, state, cache_database): (
+        Connection,
+        Path<PathParameters>,
+        Json<OfflineRedemptionsRequest>,
+        AuthUser,
+        Data<AppState>,
+        CacheDatabase,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let event = Event::find(parameters.id, connection)?;
+    let organization = event.organization(connection)?;
+    auth_user.requires_scope_for_organization_event(Scopes::RedeemTicket, &organization, &event, connection)?;
+
+    let results = TicketInstance::redeem_offline_tickets(
+        event.id,
+        &redemption_parameters.redemptions,
+        auth_user.id(),
+        connection,
+    )?;
+
+    for result in results.iter().filter(|r| r.status == OfflineRedemptionStatus::Redeemed) {
+        let ticket = TicketInstance::find(result.ticket_id, connection)?;
+        //Redeem ticket on chain, the ticket holder has already been let in so redemption is not rolled back
+        //when the asset has not been assigned on the blockchain
+        let asset = Asset::find(ticket.asset_id, connection)?;
+        if let Some(ref blockchain_asset_id) = asset.blockchain_asset_id {
+            let wallet = Wallet::find(ticket.wallet_id, connection)?;
+            state.config.tari_client.modify_asset_redeem_token(
+                &wallet.secret_key,
+                &wallet.public_key,
+                blockchain_asset_id,
+                vec![ticket.token_id as u64],
+            )?;
+        }
+
+        // Publish redeem event for redis pubsub
+        cache_database.inner.clone().and_then(|conn| {
+            caching::publish(
+                conn,
+                RedisPubSubChannel::TicketRedemptions,
+                messages::TicketRedemption {
+                    ticket_id: ticket.id,
+                    event_id: event.id,
+                    redeemer_id: auth_user.id(),
+                },
+            )
+            .ok()
+        });
+    }
+
+    Ok(HttpResponse::Ok().json(results))
+}
+
 pub async fn show_from_organizations(
     (connection, path, paging, user): (Connection, Path<PathParameters>, Query<PagingParameters>, AuthUser),
 ) -> Result<WebPayload<EventSummaryResult>, ApiError> {
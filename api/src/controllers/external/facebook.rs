@@ -1,10 +1,10 @@
 use crate::auth::user::User as AuthUser;
-use crate::auth::TokenResponse;
+use crate::controllers::auth;
 use crate::database::Connection;
 use crate::errors::*;
 use crate::extractors::*;
 use crate::helpers::application;
-use crate::models::FacebookWebLoginToken;
+use crate::models::{FacebookWebLoginToken, RequestInfo};
 use crate::server::AppState;
 use actix_web::{web::Data, HttpResponse};
 use db::prelude::*;
@@ -16,6 +16,7 @@ use itertools::Itertools;
 use log::Level::Debug;
 use reqwest;
 use serde_json;
+use std::collections::HashMap;
 use uuid::Uuid;
 use validator::ValidationErrors;
 
@@ -30,10 +31,17 @@ struct FacebookGraphResponse {
 
 // TODO: Not covered by tests
 pub async fn web_login(
-    (state, connection, auth_token, auth_user): (Data<AppState>, Connection, Json<FacebookWebLoginToken>, OptionalUser),
+    (state, connection, auth_token, auth_user, request_info): (
+        Data<AppState>,
+        Connection,
+        Json<FacebookWebLoginToken>,
+        OptionalUser,
+        RequestInfo,
+    ),
 ) -> Result<HttpResponse, ApiError> {
     let url = format!("{}/me?fields=id,email,first_name,last_name", FACEBOOK_GRAPH_URL);
-    let connection = connection.get();
+    let db_connection = connection;
+    let connection = db_connection.get();
     let client = reqwest::Client::new();
     let response = client
         .get(&url)
@@ -62,11 +70,7 @@ pub async fn web_login(
             vec![],
             connection,
         )?;
-        let response = TokenResponse::create_from_user(
-            &*state.config.token_issuer,
-            state.config.jwt_expiry_time,
-            &auth_user.user,
-        )?;
+        let response = auth::create_login_token_response(&auth_user.user, &state, &request_info, connection)?;
         return Ok(HttpResponse::Ok().json(response));
     }
 
@@ -130,7 +134,17 @@ pub async fn web_login(
             }
         }
     };
-    let response = TokenResponse::create_from_user(&*state.config.token_issuer, state.config.jwt_expiry_time, &user)?;
+
+    // Facebook only stands in for the password, users with two-factor authentication still need it
+    auth::verify_second_factor(
+        &user,
+        auth_token.totp_code.as_ref(),
+        auth_token.recovery_code.as_ref(),
+        &state,
+        &db_connection,
+        HashMap::new(),
+    )?;
+    let response = auth::create_login_token_response(&user, &state, &request_info, connection)?;
     Ok(HttpResponse::Ok().json(response))
 }
 
@@ -1 +1,2 @@
 pub mod facebook;
+pub mod oidc;
@@ -0,0 +1,196 @@
+use crate::auth::user::User as AuthUser;
+use crate::auth::TokenResponse;
+use crate::controllers::auth;
+use crate::database::Connection;
+use crate::errors::*;
+use crate::extractors::*;
+use crate::helpers::application;
+use crate::models::RequestInfo;
+use crate::server::AppState;
+use crate::utils::oidc;
+use actix_web::{web::Data, HttpResponse};
+use db::prelude::*;
+use log::Level::Info;
+use std::collections::HashMap;
+use url::Url;
+use uuid::Uuid;
+
+#[derive(Deserialize, Serialize)]
+pub struct OidcAuthorizeRequest {
+    pub organization_id: Uuid,
+    /// Front end page the provider returns the user to with the code and state
+    pub redirect_uri: String,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct OidcAuthorizeResponse {
+    pub authorization_url: String,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct OidcCallbackRequest {
+    pub code: String,
+    pub state: String,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub totp_code: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub recovery_code: Option<String>,
+}
+
+/// The provider sends the code and state to the redirect URI so it must be on the front end's
+/// own origin, a prefix match would also accept hosts such as `front_end_url.attacker.com`
+fn is_front_end_url(redirect_uri: &str, front_end_url: &str) -> bool {
+    match (Url::parse(redirect_uri), Url::parse(front_end_url)) {
+        (Ok(redirect_uri), Ok(front_end_url)) => {
+            redirect_uri.scheme() == front_end_url.scheme()
+                && redirect_uri.host_str().is_some()
+                && redirect_uri.host_str() == front_end_url.host_str()
+                && redirect_uri.port_or_known_default() == front_end_url.port_or_known_default()
+                && redirect_uri.username().is_empty()
+                && redirect_uri.password().is_none()
+        }
+        _ => false,
+    }
+}
+
+/// Starts a login through the organization's identity provider, a signed in user starts linking
+/// the identity to their account instead
+pub async fn authorize(
+    (state, connection, json, auth_user): (Data<AppState>, Connection, Json<OidcAuthorizeRequest>, OptionalUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let configuration = OrganizationSsoConfiguration::find_for_organization(json.organization_id, connection)?;
+    if !configuration.enabled {
+        return application::unprocessable("Single sign-on is not enabled for this organization");
+    }
+    if !is_front_end_url(&json.redirect_uri, &state.config.front_end_url) {
+        return application::unprocessable("Redirect URI is not allowed");
+    }
+
+    let metadata = oidc::discover(&configuration.issuer_url).await?;
+    let login_request =
+        SsoLoginRequest::create(configuration.id, json.redirect_uri.clone(), auth_user.id()).commit(connection)?;
+    let authorization_url = oidc::authorization_url(&metadata, &configuration, &login_request)?;
+    Ok(HttpResponse::Ok().json(OidcAuthorizeResponse { authorization_url }))
+}
+
+/// Completes a login started with `authorize`. Staff without an account are provisioned and
+/// added to the organization, a signed in user links the identity to their account instead.
+pub async fn callback(
+    (state, connection, json, auth_user, request_info): (
+        Data<AppState>,
+        Connection,
+        Json<OidcCallbackRequest>,
+        OptionalUser,
+        RequestInfo,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let db_connection = connection;
+    let connection = db_connection.get();
+    let login_request = match SsoLoginRequest::consume(&json.state, connection)? {
+        Some(login_request) => login_request,
+        None => {
+            return application::unauthorized_with_message(
+                "Single sign-on request is invalid or has expired",
+                None,
+                None,
+            );
+        }
+    };
+    let auth_user = auth_user.into_inner();
+    // Identities are only linked for the user who started the request, otherwise a signed in user
+    // could be made to complete someone else's login and link that identity to their account
+    if let Some(ref auth_user) = auth_user {
+        if login_request.user_id != Some(auth_user.id()) {
+            return application::unauthorized_with_message(
+                "Single sign-on request was not started by this user",
+                Some(auth_user.clone()),
+                None,
+            );
+        }
+    }
+
+    let configuration = login_request.organization_sso_configuration(connection)?;
+    if !configuration.enabled {
+        return application::unprocessable("Single sign-on is not enabled for this organization");
+    }
+
+    let client_secret = configuration.client_secret(&state.config.api_keys_encryption_key)?;
+    let metadata = oidc::discover(&configuration.issuer_url).await?;
+    let token_response =
+        oidc::exchange_code(&metadata, &configuration, &client_secret, &login_request, &json.code).await?;
+    let keys = oidc::fetch_keys(&metadata).await?;
+    let claims = oidc::validate_id_token(
+        &token_response.id_token,
+        &keys,
+        &configuration,
+        &client_secret,
+        &login_request.nonce,
+    )?;
+    let identity = oidc::identity(&claims, &configuration)?;
+
+    let response = sign_in(
+        &state,
+        &db_connection,
+        &configuration,
+        &identity,
+        token_response.access_token,
+        auth_user,
+        &json,
+        &request_info,
+    )?;
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Signs in the user for an identity the provider has vouched for. The provider only stands in for
+/// the password, so users who set up two-factor authentication still need their second factor.
+pub fn sign_in(
+    state: &AppState,
+    connection: &Connection,
+    configuration: &OrganizationSsoConfiguration,
+    identity: &SsoIdentity,
+    access_token: String,
+    auth_user: Option<AuthUser>,
+    json: &OidcCallbackRequest,
+    request_info: &RequestInfo,
+) -> Result<TokenResponse, ApiError> {
+    let conn = connection.get();
+    let (user, auth_method) = match auth_user {
+        // The second factor was checked when the signed in user's session started
+        Some(auth_user) => (
+            configuration.link_user(&auth_user.user, identity, access_token, conn)?,
+            "oidc".to_string(),
+        ),
+        None => {
+            let user = configuration.provision_user(identity, access_token, conn)?;
+            if UserLoginThrottle::locked_until(user.id, conn)?.is_some() {
+                return application::unauthorized_with_message(
+                    "Too many failed login attempts, please try again later",
+                    None,
+                    None,
+                );
+            }
+            let auth_method = match auth::verify_second_factor(
+                &user,
+                json.totp_code.as_ref(),
+                json.recovery_code.as_ref(),
+                state,
+                connection,
+                HashMap::new(),
+            )? {
+                Some(second_factor) => format!("oidc_{}", second_factor),
+                None => "oidc".to_string(),
+            };
+            UserLoginThrottle::reset(user.id, conn)?;
+            (user, auth_method)
+        }
+    };
+
+    let mut login_data = json!(request_info);
+    login_data["auth_method"] = json!(auth_method);
+    login_data["organization_id"] = json!(configuration.organization_id);
+    user.login_domain_event(login_data, conn)?;
+
+    jlog!(Info, "User logged in via single sign-on", {"id": user.id, "organization_id": configuration.organization_id, "auth_method": auth_method});
+    auth::create_login_token_response(&user, state, request_info, conn)
+}
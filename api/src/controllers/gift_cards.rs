@@ -0,0 +1,97 @@
+use crate::auth::user::User as AuthUser;
+use crate::database::Connection;
+use crate::errors::*;
+use crate::extractors::*;
+use crate::helpers::application;
+use crate::models::PathParameters;
+use actix_web::{
+    web::{Path, Query},
+    HttpResponse,
+};
+use chrono::NaiveDateTime;
+use db::prelude::*;
+
+#[derive(Deserialize, Serialize)]
+pub struct NewGiftCardRequest {
+    pub initial_balance_in_cents: i64,
+    /// Generated when not provided
+    pub code: Option<String>,
+    /// Defaults to the organization's currency
+    pub currency: Option<String>,
+    pub expires_at: Option<NaiveDateTime>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct GiftCardBalanceRequest {
+    pub code: String,
+}
+
+pub async fn index(
+    (connection, parameters, query_parameters, user): (
+        Connection,
+        Path<PathParameters>,
+        Query<PagingParameters>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let organization = Organization::find(parameters.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgAdmin, &organization, connection)?;
+    let gift_cards = GiftCard::find_for_organization(organization.id, connection)?;
+    Ok(HttpResponse::Ok().json(&Payload::from_data(
+        gift_cards,
+        query_parameters.page(),
+        query_parameters.limit(),
+        None,
+    )))
+}
+
+pub async fn create(
+    (connection, parameters, json, user): (Connection, Path<PathParameters>, Json<NewGiftCardRequest>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let organization = Organization::find(parameters.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgAdmin, &organization, connection)?;
+
+    let json = json.into_inner();
+    let gift_card = GiftCard::create(
+        organization.id,
+        json.code,
+        json.initial_balance_in_cents,
+        json.currency.unwrap_or(organization.currency),
+        json.expires_at,
+        user.id(),
+    )
+    .commit(connection)?;
+    Ok(HttpResponse::Created().json(&gift_card.for_display(connection)?))
+}
+
+pub async fn show(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let gift_card = GiftCard::find(parameters.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgAdmin, &gift_card.organization(connection)?, connection)?;
+    Ok(HttpResponse::Ok().json(&gift_card.for_display(connection)?))
+}
+
+pub async fn destroy(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let gift_card = GiftCard::find(parameters.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgAdmin, &gift_card.organization(connection)?, connection)?;
+
+    let gift_card = gift_card.deactivate(user.id(), connection)?;
+    Ok(HttpResponse::Ok().json(&gift_card.for_display(connection)?))
+}
+
+pub async fn balance(
+    (connection, json, _user): (Connection, Json<GiftCardBalanceRequest>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    match GiftCard::find_by_code(&json.code, connection).optional()? {
+        Some(gift_card) => Ok(HttpResponse::Ok().json(&gift_card.balance_for_display())),
+        None => application::not_found(),
+    }
+}
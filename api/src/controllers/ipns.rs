@@ -1,13 +1,18 @@
 use crate::database::Connection;
 use crate::errors::ApiError;
 use crate::extractors::Json;
-use actix_web::HttpResponse;
+use crate::helpers::application;
+use crate::server::AppState;
+use actix_web::{web::Data, HttpRequest, HttpResponse};
 use db::prelude::*;
 use db::utils::dates::IntoDateBuilder;
 use globee::GlobeeIpnRequest;
-use log::Level::Debug;
+use log::Level::{Debug, Warn};
+use stripe::WebhookEvent;
 use uuid::Uuid;
 
+const STRIPE_SIGNATURE_HEADER: &str = "Stripe-Signature";
+
 pub async fn globee((data, conn): (Json<GlobeeIpnRequest>, Connection)) -> Result<HttpResponse, ApiError> {
     let data = data.into_inner();
     jlog!(Debug, "Globee IPN received", { "data": &data });
@@ -33,3 +38,37 @@ pub async fn globee((data, conn): (Json<GlobeeIpnRequest>, Connection)) -> Resul
 
     Ok(HttpResponse::Ok().finish())
 }
+
+pub async fn stripe(
+    (request, payload, state, conn): (HttpRequest, String, Data<AppState>, Connection),
+) -> Result<HttpResponse, ApiError> {
+    let webhook_secret = match state.config.stripe_webhook_secret {
+        Some(ref webhook_secret) => webhook_secret,
+        None => return application::not_found(),
+    };
+    let signature_header = request
+        .headers()
+        .get(STRIPE_SIGNATURE_HEADER)
+        .and_then(|header| header.to_str().ok())
+        .unwrap_or("");
+    let event = match WebhookEvent::construct(&payload, signature_header, webhook_secret) {
+        Ok(event) => event,
+        Err(error) => {
+            jlog!(Warn, "Stripe webhook rejected", { "error": error.to_string() });
+            return application::bad_request("Webhook signature is invalid");
+        }
+    };
+    jlog!(Debug, "Stripe webhook received", { "id": &event.id, "type": &event.event_type });
+
+    // Like IPNs the event is only recorded here and processed by a domain action so that it can be
+    // retried. Stripe retries deliveries it considers failed so repeated events are ignored.
+    PaymentProviderEvent::create(
+        PaymentProviders::Stripe,
+        event.id.clone(),
+        event.event_type.clone(),
+        serde_json::from_str(&payload)?,
+    )
+    .commit(conn.get())?;
+
+    Ok(HttpResponse::Ok().finish())
+}
@@ -9,8 +9,7 @@ use actix_web::{
     web::{Data, Path},
     HttpResponse,
 };
-use db::models::Listing;
-use db::models::{Scopes, TicketInstance};
+use db::models::{Event, Listing, Scopes};
 use uuid::Uuid;
 
 pub async fn create(
@@ -19,21 +18,21 @@ pub async fn create(
     let conn = conn.get();
     user.requires_scope(Scopes::ListingWrite)?;
     let data = data.into_inner();
-    let listing = Listing::create(data.title.clone(), user.id(), data.asking_price_in_cents).commit(conn)?;
+    let mut listing = Listing::create(data.title.clone(), user.id(), data.asking_price_in_cents).commit(conn)?;
     let wallet = user.user.default_wallet(conn)?;
     for item in data.items {
-        TicketInstance::add_to_listing(
-            Some(user.id()),
-            wallet.id,
-            listing.id,
-            item.ticket_type_id,
-            item.quantity,
-            conn,
-        )?;
+        listing.add_tickets(Some(user.id()), wallet.id, item.ticket_type_id, item.quantity, conn)?;
     }
     Ok(HttpResponse::Ok().json(json!({"id": listing.id})))
 }
 
+pub async fn index_for_user((user, conn): (User, Connection)) -> Result<HttpResponse, ApiError> {
+    let conn = conn.get();
+    user.requires_scope(Scopes::ListingWrite)?;
+    let listings = Listing::find_for_user(user.id(), conn)?;
+    Ok(HttpResponse::Ok().json(&listings))
+}
+
 pub async fn publish(
     (path, user, conn, state): (Path<PathParameters>, User, Connection, Data<AppState>),
 ) -> Result<HttpResponse, ApiError> {
@@ -43,22 +42,39 @@ pub async fn publish(
     if listing.user_id != user.id() {
         return application::forbidden("You cannot publish this listing because you are not the owner");
     }
-    let marketplace_account = user.user.marketplace_account(conn)?;
-    let marketplace_account = if marketplace_account.is_none() {
-        return application::unprocessable(
-            "User does not have a marketplace account. First create a marketplace account and then try again",
-        );
-    } else {
-        marketplace_account.unwrap()
-    };
+    match user.user.marketplace_account(conn)? {
+        // Send to market place
+        Some(marketplace_account) => {
+            let marketplace_api = state.service_locator.create_marketplace_api()?;
+            let m_listing = marketplace_api.publish_listing(&listing, &marketplace_account)?;
+            listing.set_published(m_listing, conn)?;
+        }
+        // Otherwise list on the platform's own resale marketplace
+        None => {
+            listing.publish(Some(user.id()), conn)?;
+        }
+    }
+    Ok(HttpResponse::Ok().finish())
+}
 
-    // Send to market place
-    let marketplace_api = state.service_locator.create_marketplace_api()?;
-    let m_listing = marketplace_api.publish_listing(&listing, &marketplace_account)?;
-    listing.set_published(m_listing, conn)?;
+pub async fn destroy((path, user, conn): (Path<PathParameters>, User, Connection)) -> Result<HttpResponse, ApiError> {
+    let conn = conn.get();
+    user.requires_scope(Scopes::ListingWrite)?;
+    let listing = Listing::find(path.id, conn)?;
+    if listing.user_id != user.id() {
+        return application::forbidden("You cannot cancel this listing because you are not the owner");
+    }
+    listing.cancel(Some(user.id()), conn)?;
     Ok(HttpResponse::Ok().finish())
 }
 
+pub async fn index((path, conn): (Path<PathParameters>, Connection)) -> Result<HttpResponse, ApiError> {
+    let conn = conn.get();
+    let event = Event::find(path.id, conn)?;
+    let listings = Listing::find_published_for_event(event.id, conn)?;
+    Ok(HttpResponse::Ok().json(&listings))
+}
+
 #[derive(Deserialize)]
 pub struct CreateListingRequest {
     pub title: String,
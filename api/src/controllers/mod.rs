@@ -1,25 +1,35 @@
+pub mod account_credits;
 pub mod admin;
 pub mod analytics;
 pub mod announcements;
+pub mod api_keys;
 pub mod artists;
 pub mod auth;
 pub mod broadcasts;
+pub mod bundles;
 pub mod cart;
 pub mod codes;
 pub mod collection_items;
 pub mod collections;
+pub mod communication_templates;
 pub mod comps;
 pub mod event_report_subscribers;
+pub mod event_series;
 pub mod events;
 pub mod external;
 pub mod genres;
+pub mod gift_cards;
 pub mod holds;
 pub mod ipns;
 pub mod listings;
 pub mod notes;
 pub mod orders;
+pub mod organization_events_stream;
 pub mod organization_invites;
+pub mod organization_roles;
+pub mod organization_sso_configurations;
 pub mod organization_venues;
+pub mod organization_webhooks;
 pub mod organizations;
 pub mod password_resets;
 pub mod payment_methods;
@@ -35,10 +45,14 @@ pub mod sitemap_gen;
 pub mod slugs;
 pub mod stages;
 pub mod status;
+pub mod tax_rules;
 pub mod ticket_types;
 pub mod tickets;
 pub mod transfers;
+pub mod two_factor;
 pub mod user_invites;
+pub mod user_sessions;
 pub mod users;
 pub mod venues;
+pub mod waitlist_entries;
 pub mod websockets;
@@ -167,6 +167,9 @@ pub struct RefundAttributes {
     pub reason: Option<String>,
     #[serde(default = "default_as_false")]
     pub manual_override: bool,
+    /// Issue the refunded amount as account credit instead of returning it to the original payments
+    #[serde(default = "default_as_false")]
+    pub refund_as_account_credit: bool,
 }
 
 #[derive(Deserialize, Serialize)]
@@ -190,6 +193,7 @@ pub async fn refund(
     let reason = refund_attributes.reason;
     let items = refund_attributes.items;
     let manual_override = refund_attributes.manual_override;
+    let refund_as_account_credit = refund_attributes.refund_as_account_credit;
     let mut order = Order::find(path.id, connection)?;
 
     if order.status != OrderStatus::Paid {
@@ -315,7 +319,15 @@ pub async fn refund(
 
             let amount_to_refund = cmp::min(refund_due - amount_refunded, remaining_balance);
             let mut refund_data = None;
-            if !manual_override && payment.payment_method == PaymentMethods::CreditCard {
+            if refund_as_account_credit {
+                refund_data = Some(json!({ "refunded_as": PaymentMethods::AccountCredit }));
+            } else if !manual_override
+                && (payment.payment_method == PaymentMethods::GiftCard
+                    || payment.payment_method == PaymentMethods::AccountCredit)
+            {
+                let ledger_entry = payment.refund_to_stored_value(&refund, amount_to_refund, user.id(), connection)?;
+                refund_data = Some(json!(ledger_entry));
+            } else if !manual_override && payment.payment_method == PaymentMethods::CreditCard {
                 let mut organizations = order.organizations(connection)?;
                 if organizations.len() != 1 {
                     return Err(application::internal_server_error::<HttpResponse>(
@@ -344,10 +356,19 @@ pub async fn refund(
                 };
             }
             payment.log_refund(user.id(), &refund, amount_to_refund, refund_data, connection)?;
-            *refund_breakdown.entry(payment.payment_method).or_insert(0) += amount_to_refund;
+            let refunded_as = if refund_as_account_credit {
+                PaymentMethods::AccountCredit
+            } else {
+                payment.payment_method
+            };
+            *refund_breakdown.entry(refunded_as).or_insert(0) += amount_to_refund;
             amount_refunded += amount_to_refund;
         }
 
+        if refund_as_account_credit && amount_refunded > 0 {
+            AccountCredit::issue_for_refund(&order, &refund, amount_refunded, user.id(), connection)?;
+        }
+
         if amount_refunded < refund_due {
             return Err(application::internal_server_error::<HttpResponse>(&format!(
                 "Unable to refund amount owed {} refunded, {} due",
@@ -454,6 +475,29 @@ fn is_authorized_to_refund(
     Ok(authorized_to_refund_items)
 }
 
+pub async fn payment_plan(
+    (conn, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, ApiError> {
+    let conn = conn.get();
+    let order = Order::find(path.id, conn)?;
+    if order.user_id == user.id() {
+        user.requires_scope(Scopes::OrderReadOwn)?;
+    } else {
+        let mut has_access = false;
+        for organization in order.organizations(conn)? {
+            if user.has_scope_for_organization(Scopes::OrderRead, &organization, conn)? {
+                has_access = true;
+            }
+        }
+        if !has_access {
+            return application::forbidden("You do not have access to this order");
+        }
+    }
+
+    let payment_plan = PaymentPlan::find_for_order(order.id, conn)?;
+    Ok(HttpResponse::Ok().json(payment_plan.for_display(conn)?))
+}
+
 pub async fn tickets((conn, path, user): (Connection, Path<PathParameters>, User)) -> Result<HttpResponse, ApiError> {
     let conn = conn.get();
     let order = Order::find(path.id, conn)?;
@@ -0,0 +1,267 @@
+use crate::auth::user::User as AuthUser;
+use crate::database::{Database, ReadonlyConnection};
+use crate::errors::*;
+use crate::models::PathParameters;
+use crate::server::AppState;
+use actix_web::{
+    http::header,
+    web::{self, Data, Path, Query},
+    HttpRequest, HttpResponse,
+};
+use bytes::Bytes;
+use chrono::NaiveDateTime;
+use db::models::User as DbUser;
+use db::prelude::*;
+use diesel::PgConnection;
+use futures::stream;
+use log::Level::Warn;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+const DEFAULT_LIMIT: u32 = 100;
+const MAX_LIMIT: u32 = 1000;
+const LIVE_POLL_INTERVAL_SECONDS: u64 = 2;
+/// Access is only checked when a live stream opens so it is checked again on this interval to close
+/// streams for users who have since been removed from the organization
+const LIVE_ACCESS_CHECK_INTERVAL_SECONDS: u64 = 60;
+
+#[derive(Default, Deserialize, Serialize)]
+pub struct DomainEventStreamParameters {
+    pub after_seq: Option<i64>,
+    /// Comma separated list of domain event types
+    pub event_types: Option<String>,
+    pub main_table: Option<Tables>,
+    pub start_utc: Option<NaiveDateTime>,
+    pub end_utc: Option<NaiveDateTime>,
+    pub limit: Option<u32>,
+}
+
+impl DomainEventStreamParameters {
+    fn filter(&self) -> Result<DomainEventStreamFilter, ApiError> {
+        let event_types = match self.event_types {
+            Some(ref event_types) => {
+                let mut parsed = Vec::new();
+                for event_type in event_types.split(',').map(|e| e.trim()).filter(|e| !e.is_empty()) {
+                    parsed.push(event_type.parse::<DomainEventTypes>().map_err(|_| {
+                        ApplicationError::bad_request(&format!("Unknown domain event type: {}", event_type))
+                    })?);
+                }
+                Some(parsed)
+            }
+            None => None,
+        };
+
+        Ok(DomainEventStreamFilter {
+            event_types,
+            main_table: self.main_table,
+            start_utc: self.start_utc,
+            end_utc: self.end_utc,
+        })
+    }
+
+    fn limit(&self) -> u32 {
+        self.limit.unwrap_or(DEFAULT_LIMIT).max(1).min(MAX_LIMIT)
+    }
+}
+
+struct LiveStreamState {
+    database: Database,
+    user: AuthUser,
+    access_checked_at: Instant,
+    organization_id: Uuid,
+    filter: DomainEventStreamFilter,
+    limit: u32,
+    after_seq: i64,
+    caught_up: bool,
+}
+
+/// Returns the organization's domain events after the `after_seq` cursor in sequence order.
+/// `next_seq` is passed back as `after_seq` to continue reading.
+pub async fn index(
+    (connection, path, query, user): (
+        ReadonlyConnection,
+        Path<PathParameters>,
+        Query<DomainEventStreamParameters>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgWrite, &organization, connection)?;
+
+    let page = DomainEvent::find_for_organization_after_seq(
+        organization.id,
+        query.after_seq.unwrap_or(0),
+        &query.filter()?,
+        query.limit(),
+        connection,
+    )?;
+    Ok(HttpResponse::Ok().json(&page))
+}
+
+/// Tails the organization's domain events as server-sent events. Each message id is the event seq
+/// so clients resume with the `Last-Event-ID` header when reconnecting.
+pub async fn live(
+    (connection, path, query, user, request, state): (
+        ReadonlyConnection,
+        Path<PathParameters>,
+        Query<DomainEventStreamParameters>,
+        AuthUser,
+        HttpRequest,
+        Data<AppState>,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let organization = {
+        let connection = connection.get();
+        let organization = Organization::find(path.id, connection)?;
+        user.requires_scope_for_organization(Scopes::OrgWrite, &organization, connection)?;
+        organization
+    };
+
+    let last_event_id = match request.headers().get("Last-Event-ID") {
+        Some(value) => Some(
+            value
+                .to_str()
+                .ok()
+                .and_then(|v| v.parse::<i64>().ok())
+                .ok_or_else(|| ApplicationError::bad_request("Last-Event-ID must be a domain event seq"))?,
+        ),
+        None => None,
+    };
+
+    let live_state = LiveStreamState {
+        database: state.database_ro.clone(),
+        user,
+        access_checked_at: Instant::now(),
+        organization_id: organization.id,
+        filter: query.filter()?,
+        limit: query.limit(),
+        after_seq: last_event_id.or(query.after_seq).unwrap_or(0),
+        caught_up: false,
+    };
+
+    let events = stream::unfold(live_state, |live_state| async move {
+        if live_state.caught_up {
+            tokio::time::delay_for(Duration::from_secs(LIVE_POLL_INTERVAL_SECONDS)).await;
+        }
+        // Diesel calls block so pages are loaded on the blocking thread pool
+        match web::block(move || Ok::<_, ()>(next_live_message(live_state))).await {
+            Ok((live_state, Some(message))) => Some((Ok::<Bytes, ApiError>(Bytes::from(message)), live_state)),
+            Ok((_, None)) | Err(_) => None,
+        }
+    });
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .header(header::CACHE_CONTROL, "no-cache")
+        .streaming(Box::pin(events)))
+}
+
+/// Loads the next page for a live stream, a comment is sent instead when there is nothing new so
+/// that proxies keep the connection open. No message is returned once the user has lost access to
+/// the organization, ending the stream.
+fn next_live_message(mut live_state: LiveStreamState) -> (LiveStreamState, Option<String>) {
+    let connection = match live_state.database.get_ro_connection() {
+        Ok(connection) => connection,
+        Err(e) => {
+            jlog!(Warn, "bigneon::domain_events", "Could not load domain events for live stream", {
+                "organization_id": live_state.organization_id,
+                "error": e.to_string()
+            });
+            live_state.caught_up = true;
+            return (live_state, Some(": keep-alive\n\n".to_string()));
+        }
+    };
+    let connection = connection.get();
+
+    if live_state.access_checked_at.elapsed() >= Duration::from_secs(LIVE_ACCESS_CHECK_INTERVAL_SECONDS) {
+        let organization_id = live_state.organization_id;
+        match refresh_live_access(&mut live_state.user, organization_id, connection) {
+            Ok(true) => live_state.access_checked_at = Instant::now(),
+            Ok(false) => {
+                jlog!(Warn, "bigneon::domain_events", "Closing live stream after access was removed", {
+                    "organization_id": organization_id,
+                    "user_id": live_state.user.id()
+                });
+                return (live_state, None);
+            }
+            // The check is retried on the next poll, access is not assumed lost on a read failure
+            Err(e) => {
+                jlog!(Warn, "bigneon::domain_events", "Could not check access for live stream", {
+                    "organization_id": organization_id,
+                    "error": e.to_string()
+                });
+            }
+        }
+    }
+
+    let result = DomainEvent::find_for_organization_after_seq(
+        live_state.organization_id,
+        live_state.after_seq,
+        &live_state.filter,
+        live_state.limit,
+        connection,
+    );
+
+    let message = match result {
+        Ok(page) => {
+            live_state.after_seq = page.next_seq;
+            live_state.caught_up = !page.has_more;
+            if page.data.is_empty() {
+                ": keep-alive\n\n".to_string()
+            } else {
+                page.data
+                    .iter()
+                    .map(|domain_event| {
+                        format!(
+                            "id: {}\nevent: {}\ndata: {}\n\n",
+                            domain_event.seq,
+                            domain_event.event_type,
+                            json!(domain_event)
+                        )
+                    })
+                    .collect()
+            }
+        }
+        Err(e) => {
+            jlog!(Warn, "bigneon::domain_events", "Could not load domain events for live stream", {
+                "organization_id": live_state.organization_id,
+                "error": e.to_string()
+            });
+            live_state.caught_up = true;
+            ": keep-alive\n\n".to_string()
+        }
+    };
+    (live_state, Some(message))
+}
+
+/// Reloads the user, session and API key the stream was opened with and checks the user can still
+/// read the organization's events
+fn refresh_live_access(user: &mut AuthUser, organization_id: Uuid, conn: &PgConnection) -> Result<bool, ApiError> {
+    if let Some(session_id) = user.session_id {
+        if !UserSession::find(session_id, conn)?.is_active() {
+            return Ok(false);
+        }
+    }
+    if let Some(ref api_key) = user.api_key {
+        let api_key = ApiKey::find(api_key.id, conn)?;
+        if !api_key.is_active() {
+            return Ok(false);
+        }
+        user.api_key = Some(api_key);
+    }
+
+    let db_user = DbUser::find(user.id(), conn)?;
+    if db_user.deleted_at.is_some() {
+        return Ok(false);
+    }
+    if !user.global_scopes_only {
+        user.global_scopes = db_user.get_global_scopes().into_iter().map(|s| s.to_string()).collect();
+    }
+    user.user = db_user;
+
+    let organization = Organization::find(organization_id, conn)?;
+    Ok(user
+        .requires_scope_for_organization(Scopes::OrgWrite, &organization, conn)
+        .is_ok())
+}
@@ -0,0 +1,113 @@
+use crate::auth::user::User as AuthUser;
+use crate::database::Connection;
+use crate::errors::*;
+use crate::extractors::*;
+use crate::models::{OrganizationUserPathParameters, PathParameters};
+use actix_web::{
+    web::{Path, Query},
+    HttpResponse,
+};
+use db::prelude::*;
+use uuid::Uuid;
+
+#[derive(Deserialize, Serialize)]
+pub struct NewOrganizationRoleRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub scopes: Vec<Scopes>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct OrganizationUserRolesRequest {
+    pub organization_role_ids: Vec<Uuid>,
+}
+
+pub async fn index(
+    (connection, parameters, query_parameters, user): (
+        Connection,
+        Path<PathParameters>,
+        Query<PagingParameters>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let organization = Organization::find(parameters.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgUsers, &organization, connection)?;
+    let organization_roles = OrganizationRole::find_for_organization(organization.id, connection)?;
+    Ok(HttpResponse::Ok().json(&Payload::from_data(
+        organization_roles,
+        query_parameters.page(),
+        query_parameters.limit(),
+        None,
+    )))
+}
+
+pub async fn create(
+    (connection, parameters, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<NewOrganizationRoleRequest>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let organization = Organization::find(parameters.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgAdminUsers, &organization, connection)?;
+
+    let json = json.into_inner();
+    let organization_role = OrganizationRole::create(organization.id, json.name, json.description, json.scopes)
+        .commit(Some(user.id()), connection)?;
+    Ok(HttpResponse::Created().json(&organization_role))
+}
+
+pub async fn update(
+    (connection, parameters, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<OrganizationRoleEditableAttributes>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let organization_role = OrganizationRole::find(parameters.id, connection)?;
+    user.requires_scope_for_organization(
+        Scopes::OrgAdminUsers,
+        &organization_role.organization(connection)?,
+        connection,
+    )?;
+
+    let organization_role = organization_role.update(json.into_inner(), Some(user.id()), connection)?;
+    Ok(HttpResponse::Ok().json(&organization_role))
+}
+
+pub async fn destroy(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let organization_role = OrganizationRole::find(parameters.id, connection)?;
+    user.requires_scope_for_organization(
+        Scopes::OrgAdminUsers,
+        &organization_role.organization(connection)?,
+        connection,
+    )?;
+
+    organization_role.destroy(Some(user.id()), connection)?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+pub async fn update_user_roles(
+    (connection, parameters, json, user): (
+        Connection,
+        Path<OrganizationUserPathParameters>,
+        Json<OrganizationUserRolesRequest>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let organization = Organization::find(parameters.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgAdminUsers, &organization, connection)?;
+
+    let organization_user = OrganizationUser::find_by_user_id(parameters.user_id, organization.id, connection)?
+        .set_organization_roles(json.into_inner().organization_role_ids, Some(user.id()), connection)?;
+    Ok(HttpResponse::Ok().json(&organization_user))
+}
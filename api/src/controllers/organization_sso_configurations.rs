@@ -0,0 +1,87 @@
+use crate::auth::user::User as AuthUser;
+use crate::database::Connection;
+use crate::errors::*;
+use crate::extractors::*;
+use crate::models::PathParameters;
+use crate::server::AppState;
+use actix_web::{
+    web::{Data, Path},
+    HttpResponse,
+};
+use db::prelude::*;
+use std::collections::HashMap;
+
+#[derive(Deserialize, Serialize)]
+pub struct NewOrganizationSsoConfigurationRequest {
+    pub issuer_url: String,
+    pub client_id: String,
+    pub client_secret: String,
+    #[serde(default = "default_role")]
+    pub default_role: Roles,
+    pub role_claim: Option<String>,
+    #[serde(default)]
+    pub role_mappings: HashMap<String, Roles>,
+}
+
+fn default_role() -> Roles {
+    Roles::OrgMember
+}
+
+pub async fn show(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let organization = Organization::find(parameters.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgAdminUsers, &organization, connection)?;
+    let configuration = OrganizationSsoConfiguration::find_for_organization(organization.id, connection)?;
+    Ok(HttpResponse::Ok().json(&configuration))
+}
+
+pub async fn create(
+    (connection, parameters, json, user, state): (
+        Connection,
+        Path<PathParameters>,
+        Json<NewOrganizationSsoConfigurationRequest>,
+        AuthUser,
+        Data<AppState>,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let organization = Organization::find(parameters.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgAdminUsers, &organization, connection)?;
+
+    let json = json.into_inner();
+    let configuration = OrganizationSsoConfiguration::create(
+        organization.id,
+        json.issuer_url,
+        json.client_id,
+        json.client_secret,
+        json.default_role,
+        json.role_claim,
+        json.role_mappings,
+    )
+    .commit(&state.config.api_keys_encryption_key, Some(user.id()), connection)?;
+    Ok(HttpResponse::Created().json(&configuration))
+}
+
+pub async fn update(
+    (connection, parameters, json, user, state): (
+        Connection,
+        Path<PathParameters>,
+        Json<OrganizationSsoConfigurationEditableAttributes>,
+        AuthUser,
+        Data<AppState>,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let organization = Organization::find(parameters.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgAdminUsers, &organization, connection)?;
+
+    let configuration = OrganizationSsoConfiguration::find_for_organization(organization.id, connection)?.update(
+        json.into_inner(),
+        &state.config.api_keys_encryption_key,
+        Some(user.id()),
+        connection,
+    )?;
+    Ok(HttpResponse::Ok().json(&configuration))
+}
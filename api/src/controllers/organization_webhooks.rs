@@ -0,0 +1,130 @@
+use crate::auth::user::User as AuthUser;
+use crate::database::Connection;
+use crate::errors::*;
+use crate::extractors::*;
+use crate::models::PathParameters;
+use crate::server::AppState;
+use actix_web::{
+    web::{Data, Path, Query},
+    HttpResponse,
+};
+use db::prelude::*;
+use db::utils::webhook_signatures;
+use diesel::PgConnection;
+use uuid::Uuid;
+
+#[derive(Deserialize, Serialize)]
+pub struct NewOrganizationWebhookRequest {
+    pub webhook_url: String,
+    pub event_types: Vec<DomainEventTypes>,
+}
+
+/// The signing secret is only returned when the webhook is created
+#[derive(Deserialize, Serialize)]
+pub struct CreatedOrganizationWebhookResponse {
+    #[serde(flatten)]
+    pub webhook: DomainEventPublisher,
+    pub signing_secret: String,
+}
+
+/// Webhooks are domain event publishers registered by an organization and signed for delivery
+fn find_webhook(id: Uuid, user: &AuthUser, conn: &PgConnection) -> Result<DomainEventPublisher, ApiError> {
+    let webhook = DomainEventPublisher::find(id, conn)?;
+    let organization_id = match webhook.organization_id {
+        Some(organization_id) if webhook.is_signed() && webhook.deleted_at.is_none() => organization_id,
+        _ => return Err(NotFoundError {}.into()),
+    };
+    let organization = Organization::find(organization_id, conn)?;
+    user.requires_scope_for_organization(Scopes::OrgWrite, &organization, conn)?;
+    Ok(webhook)
+}
+
+pub async fn index(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let organization = Organization::find(parameters.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgWrite, &organization, connection)?;
+    let webhooks = DomainEventPublisher::find_for_organization(organization.id, connection)?;
+    Ok(HttpResponse::Ok().json(&webhooks))
+}
+
+pub async fn create(
+    (connection, parameters, json, user, state): (
+        Connection,
+        Path<PathParameters>,
+        Json<NewOrganizationWebhookRequest>,
+        AuthUser,
+        Data<AppState>,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let organization = Organization::find(parameters.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgWrite, &organization, connection)?;
+
+    let json = json.into_inner();
+    let signing_secret = webhook_signatures::generate_secret();
+    let webhook = DomainEventPublisher::create_for_organization(
+        organization.id,
+        json.event_types,
+        json.webhook_url,
+        &signing_secret,
+        &state.config.api_keys_encryption_key,
+    )?
+    .commit(connection)?;
+    Ok(HttpResponse::Created().json(&CreatedOrganizationWebhookResponse {
+        webhook,
+        signing_secret,
+    }))
+}
+
+pub async fn update(
+    (connection, parameters, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<DomainEventPublisherEditableAttributes>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let webhook = find_webhook(parameters.id, &user, connection)?.update(&json.into_inner(), connection)?;
+    Ok(HttpResponse::Ok().json(&webhook))
+}
+
+pub async fn destroy(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    find_webhook(parameters.id, &user, connection)?.delete(connection)?;
+    Ok(HttpResponse::Ok().json(json!({})))
+}
+
+pub async fn deliveries(
+    (connection, parameters, query_parameters, user): (
+        Connection,
+        Path<PathParameters>,
+        Query<PagingParameters>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let webhook = find_webhook(parameters.id, &user, connection)?;
+    let deliveries = WebhookDelivery::find_for_publisher(
+        webhook.id,
+        query_parameters.page(),
+        query_parameters.limit(),
+        connection,
+    )?;
+    Ok(HttpResponse::Ok().json(&deliveries))
+}
+
+pub async fn replay_delivery(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let delivery = WebhookDelivery::find(parameters.id, connection)?;
+    find_webhook(delivery.domain_event_publisher_id, &user, connection)?;
+
+    let delivery = delivery.replay(Some(user.id()), connection)?;
+    Ok(HttpResponse::Ok().json(&delivery.for_display(connection)?))
+}
@@ -62,6 +62,10 @@ pub struct NewOrganizationRequest {
     pub cc_fee_percent: Option<f32>,
     pub max_instances_per_ticket_type: Option<i64>,
     pub settlement_type: Option<SettlementTypes>,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub currency: Option<String>,
+    pub transfer_expiry_hours: Option<i32>,
+    pub resale_fee_percent: Option<f32>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -155,6 +159,9 @@ pub async fn create(
             None => state.config.max_instances_per_ticket_type,
         }),
         settlement_type: new_organization.settlement_type,
+        currency: new_organization.currency.clone(),
+        transfer_expiry_hours: new_organization.transfer_expiry_hours,
+        resale_fee_percent: new_organization.resale_fee_percent,
     };
 
     let mut organization = new_organization_with_fee_schedule.commit(
@@ -184,9 +191,15 @@ pub async fn update(
     let mut organization = Organization::find(parameters.id, conn)?;
     let organization_update = organization_parameters.into_inner();
 
+    if organization_update.require_two_factor.is_some() {
+        user.requires_scope_for_organization(Scopes::OrgAdminUsers, &organization, conn)?;
+    }
     if organization_update.settlement_type.is_some() {
         user.requires_scope_for_organization(Scopes::OrgModifySettlementType, &organization, conn)?;
-    } else if organization_update.max_instances_per_ticket_type.is_some() {
+    } else if organization_update.max_instances_per_ticket_type.is_some()
+        || organization_update.resale_fee_percent.is_some()
+        || organization_update.payment_plan_max_failed_attempts.is_some()
+    {
         user.requires_scope_for_organization(Scopes::OrgAdmin, &organization, conn)?;
     } else {
         user.requires_scope_for_organization(Scopes::OrgWrite, &organization, conn)?;
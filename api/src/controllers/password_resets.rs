@@ -1,14 +1,16 @@
-use crate::auth::TokenResponse;
 use crate::communications::mailers;
+use crate::controllers::auth;
 use crate::database::Connection;
 use crate::errors::*;
 use crate::extractors::*;
 use crate::helpers::application;
+use crate::models::RequestInfo;
 use crate::server::AppState;
 use actix_web::{web::Data, HttpResponse};
 use db::models::concerns::users::password_resetable::*;
-use db::models::User;
+use db::models::{deserialize_unless_blank, User};
 use db::utils::errors::Optional;
+use std::collections::HashMap;
 use uuid::Uuid;
 
 #[derive(Deserialize)]
@@ -20,6 +22,11 @@ pub struct CreatePasswordResetParameters {
 pub struct UpdatePasswordResetParameters {
     pub password_reset_token: Uuid,
     pub password: String,
+    /// Required when the user has two-factor authentication
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub totp_code: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub recovery_code: Option<String>,
 }
 
 pub async fn create(
@@ -40,23 +47,46 @@ pub async fn create(
         user = user.create_password_reset_token(connection)?;
     }
 
-    mailers::user::password_reset_email(&state.config, &user).queue(connection)?;
+    mailers::user::password_reset_email(&state.config, &user, connection)?.queue(connection)?;
 
     request_pending_response
 }
 
 pub async fn update(
-    (state, connection, parameters): (Data<AppState>, Connection, Json<UpdatePasswordResetParameters>),
+    (state, connection, parameters, request_info): (
+        Data<AppState>,
+        Connection,
+        Json<UpdatePasswordResetParameters>,
+        RequestInfo,
+    ),
 ) -> Result<HttpResponse, ApiError> {
+    // The reset link only proves access to the user's email, so their second factor is checked
+    // before the password changes to let a missing code be retried with the same link
+    if let Some(user) =
+        User::find_by_password_reset_token(&parameters.password_reset_token, connection.get()).optional()?
+    {
+        if user.has_valid_password_reset_token() {
+            auth::verify_second_factor(
+                &user,
+                parameters.totp_code.as_ref(),
+                parameters.recovery_code.as_ref(),
+                &state,
+                &connection,
+                HashMap::new(),
+            )?;
+        }
+    }
+
     let user =
         User::consume_password_reset_token(&parameters.password_reset_token, &parameters.password, connection.get())
             .optional()?;
 
     match user {
-        Some(user) => Ok(HttpResponse::Ok().json(&TokenResponse::create_from_user(
-            &*state.config.token_issuer,
-            state.config.jwt_expiry_time,
+        Some(user) => Ok(HttpResponse::Ok().json(&auth::create_login_token_response(
             &user,
+            &state,
+            &request_info,
+            connection.get(),
         )?)),
         None => application::unprocessable("Password has already been reset."),
     }
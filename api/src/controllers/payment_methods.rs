@@ -1,11 +1,39 @@
 use crate::auth::user::User;
 use crate::database::Connection;
 use crate::errors::*;
-use actix_web::HttpResponse;
-use db::models::ForDisplay;
+use crate::helpers::application;
+use crate::models::PathParameters;
+use actix_web::{web::Path, HttpResponse};
+use db::models::{ForDisplay, PaymentMethod};
 
 pub async fn index((connection, auth_user): (Connection, User)) -> Result<HttpResponse, ApiError> {
     let connection = connection.get();
     let payment_methods = &auth_user.user.payment_methods(connection).for_display()?;
     Ok(HttpResponse::Ok().json(payment_methods))
 }
+
+pub async fn destroy(
+    (connection, path, auth_user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let payment_method = PaymentMethod::find(path.id, connection)?;
+    if payment_method.user_id != auth_user.id() {
+        return application::forbidden("This payment method does not belong to you");
+    }
+
+    payment_method.destroy(auth_user.id(), connection)?;
+    Ok(HttpResponse::Ok().json(json!({})))
+}
+
+pub async fn set_default(
+    (connection, path, auth_user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let payment_method = PaymentMethod::find(path.id, connection)?;
+    if payment_method.user_id != auth_user.id() {
+        return application::forbidden("This payment method does not belong to you");
+    }
+
+    let payment_method = payment_method.set_default(auth_user.id(), connection)?;
+    Ok(HttpResponse::Ok().json(payment_method.for_display()?))
+}
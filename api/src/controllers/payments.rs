@@ -2,6 +2,7 @@ use crate::database::Connection;
 use crate::errors::*;
 use crate::extractors::OptionalUser;
 use crate::helpers::application;
+use crate::payments::PaymentProcessorBehavior;
 use crate::server::AppState;
 use actix_web::web::{Data, Path, Query};
 use actix_web::HttpResponse;
@@ -12,6 +13,9 @@ use uuid::Uuid;
 #[derive(Serialize, Deserialize, Debug)]
 pub struct QueryParams {
     pub success: bool,
+    /// Appended by card processors when the shopper returns from an issuer challenge
+    #[serde(rename = "redirectResult")]
+    pub redirect_result: Option<String>,
 }
 
 // The nonce is in the path so that it is not cached.
@@ -58,6 +62,11 @@ pub async fn callback(
         None => return application::not_found(),
     };
 
+    if query.success && payment.payment_method == PaymentMethods::CreditCard {
+        let redirect_result = query.into_inner().redirect_result.unwrap_or_default();
+        return complete_challenged_payment(payment, order, &redirect_result, &state, user.id(), &connection).await;
+    }
+
     // We specifically don't count this as a payment confirmation, that will be done via the IPN
     // Just redirect to page accordingly
 
@@ -96,3 +105,76 @@ pub async fn callback(
         ))
     }
 }
+
+/// Card payments challenged by the issuer are authorized and charged once the shopper returns
+async fn complete_challenged_payment(
+    payment: Payment,
+    order: Order,
+    redirect_result: &str,
+    state: &AppState,
+    current_user_id: Option<Uuid>,
+    connection: &Connection,
+) -> Result<HttpResponse, ApiError> {
+    let conn = connection.get();
+    let success_url = format!(
+        "{}/tickets/{}/tickets/success?order_id={}",
+        state.config.front_end_url,
+        order.event_slug(conn)?,
+        order.id
+    );
+    // The shopper may return more than once, e.g. when reloading the page
+    if payment.status != PaymentStatus::Requested {
+        return application::redirect(&success_url);
+    }
+
+    let mut organizations = order.organizations(conn)?;
+    if organizations.len() != 1 {
+        return application::internal_server_error("Challenged payments must be for a single organization");
+    }
+    let client = state
+        .service_locator
+        .create_payment_processor(payment.provider, &organizations.remove(0))?;
+    let behavior = match client.behavior() {
+        PaymentProcessorBehavior::AuthThenComplete(behavior) => behavior,
+        PaymentProcessorBehavior::RedirectToPaymentPage(_) => {
+            return application::internal_server_error("Payment provider does not support card authentication");
+        }
+    };
+
+    let external_reference = payment.external_reference.clone().unwrap_or_default();
+    let auth_result = match behavior.confirm_challenge(&external_reference, redirect_result).await {
+        Ok(auth_result) => auth_result,
+        Err(e) => {
+            jlog!(Debug, "Payments: card authentication failed", {"payment_id": payment.id, "order_id": order.id, "error": e.to_string()});
+            payment.mark_cancelled(json!({ "error": e.to_string() }), current_user_id, conn)?;
+            return application::redirect(&format!(
+                "{}/tickets/{}/tickets/confirmation",
+                state.config.front_end_url,
+                order.event_slug(conn)?
+            ));
+        }
+    };
+    let payment = match payment.mark_authorized(&auth_result.id, auth_result.to_json()?, current_user_id, conn) {
+        Ok(payment) => payment,
+        Err(e) => {
+            client.refund(&auth_result.id).await?;
+            return Err(e.into());
+        }
+    };
+
+    connection.commit_transaction()?;
+    connection.begin_transaction()?;
+
+    let charge_result = behavior.complete_authed_charge(&auth_result.id).await?;
+    let result = if charge_result.capture_pending {
+        payment.mark_pending_ipn(current_user_id, conn)
+    } else {
+        payment.mark_complete(charge_result.to_json()?, current_user_id, conn)
+    };
+    if let Err(e) = result {
+        client.refund(&auth_result.id).await?;
+        return Err(e.into());
+    }
+
+    application::redirect(&success_url)
+}
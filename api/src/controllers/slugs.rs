@@ -53,6 +53,11 @@ pub enum SlugResponse {
         events: Vec<EventVenueEntry>,
         meta: SlugMetaData,
     },
+    EventSeries {
+        event_series: DisplayEventSeries,
+        events: Vec<EventVenueEntry>,
+        meta: SlugMetaData,
+    },
 }
 
 pub async fn index(
@@ -250,6 +255,20 @@ pub async fn show(
                 meta,
             }
         }
+        SlugTypes::EventSeries => {
+            let event_series = EventSeries::find(slug.main_table_id, connection)?;
+            let events = EventVenueEntry::event_venues_from_events(
+                event_series.upcoming_events(connection)?,
+                user,
+                &state,
+                connection,
+            )?;
+            SlugResponse::EventSeries {
+                event_series: event_series.for_display(connection)?,
+                events,
+                meta,
+            }
+        }
         SlugTypes::CityGenre => {
             return application::not_found();
         }
@@ -85,6 +85,104 @@ pub async fn delete(
     Ok(HttpResponse::Ok().json(json!({})))
 }
 
+pub async fn seat_map((connection, parameters): (Connection, Path<PathParameters>)) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let stage = Stage::find(parameters.id, connection)?;
+
+    Ok(HttpResponse::Ok().json(&stage.seat_map(connection)?))
+}
+
+#[derive(Deserialize)]
+pub struct CreateStageSection {
+    pub name: String,
+}
+
+pub async fn create_section(
+    (connection, parameters, create_section, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<CreateStageSection>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let stage = Stage::find(parameters.id, connection)?;
+    let venue = Venue::find(stage.venue_id, connection)?;
+    check_access(&venue, &user, connection)?;
+
+    let section = StageSection::create(stage.id, create_section.name.clone()).commit(connection)?;
+    Ok(HttpResponse::Created().json(&section))
+}
+
+pub async fn update_section(
+    (connection, parameters, section_parameters, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<StageSectionEditableAttributes>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let section = StageSection::find(parameters.id, connection)?;
+    let venue = Venue::find(section.stage(connection)?.venue_id, connection)?;
+    check_access(&venue, &user, connection)?;
+
+    let updated_section = section.update(section_parameters.into_inner(), connection)?;
+    Ok(HttpResponse::Ok().json(updated_section))
+}
+
+pub async fn delete_section(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let section = StageSection::find(parameters.id, connection)?;
+    let venue = Venue::find(section.stage(connection)?.venue_id, connection)?;
+    check_access(&venue, &user, connection)?;
+
+    section.destroy(connection)?;
+    Ok(HttpResponse::Ok().json(json!({})))
+}
+
+#[derive(Deserialize)]
+pub struct CreateStageSeat {
+    pub row_name: String,
+    pub seat_number: String,
+}
+
+#[derive(Deserialize)]
+pub struct CreateStageSeats {
+    pub seats: Vec<CreateStageSeat>,
+}
+
+pub async fn create_seats(
+    (connection, parameters, create_seats, user): (Connection, Path<PathParameters>, Json<CreateStageSeats>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let section = StageSection::find(parameters.id, connection)?;
+    let venue = Venue::find(section.stage(connection)?.venue_id, connection)?;
+    check_access(&venue, &user, connection)?;
+
+    let new_seats: Vec<NewStageSeat> = create_seats
+        .seats
+        .iter()
+        .map(|seat| StageSeat::create(section.id, seat.row_name.clone(), seat.seat_number.clone()))
+        .collect();
+    let seats = StageSeat::create_multiple(&new_seats, connection)?;
+    Ok(HttpResponse::Created().json(&seats))
+}
+
+pub async fn delete_seat(
+    (connection, parameters, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let seat = StageSeat::find(parameters.id, connection)?;
+    let venue = Venue::find(seat.section(connection)?.stage(connection)?.venue_id, connection)?;
+    check_access(&venue, &user, connection)?;
+
+    seat.destroy(connection)?;
+    Ok(HttpResponse::Ok().json(json!({})))
+}
+
 fn check_access(venue: &Venue, user: &AuthUser, connection: &PgConnection) -> Result<(), ApiError> {
     let mut has_create_access = false;
     for organization in venue.organizations(connection)? {
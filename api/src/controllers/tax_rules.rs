@@ -0,0 +1,111 @@
+use crate::auth::user::User;
+use crate::database::Connection;
+use crate::errors::*;
+use crate::extractors::*;
+use crate::models::PathParameters;
+use actix_web::{
+    web::{Path, Query},
+    HttpResponse,
+};
+use db::models::*;
+
+#[derive(Deserialize, Serialize)]
+pub struct NewTaxRuleRequest {
+    pub name: String,
+    pub rate_percent: f32,
+    #[serde(default)]
+    pub applies_to_fees: bool,
+    #[serde(default)]
+    pub inclusive: bool,
+}
+
+pub async fn index_for_venue(
+    (connection, parameters, query_parameters, user): (Connection, Path<PathParameters>, Query<PagingParameters>, User),
+) -> Result<HttpResponse, ApiError> {
+    user.requires_scope(Scopes::TaxRuleWrite)?;
+    let connection = connection.get();
+    let venue = Venue::find(parameters.id, connection)?;
+    let tax_rules = TaxRule::find_for_venue(venue.id, connection)?;
+    Ok(HttpResponse::Ok().json(&Payload::from_data(
+        tax_rules,
+        query_parameters.page(),
+        query_parameters.limit(),
+        None,
+    )))
+}
+
+pub async fn index_for_region(
+    (connection, parameters, query_parameters, user): (Connection, Path<PathParameters>, Query<PagingParameters>, User),
+) -> Result<HttpResponse, ApiError> {
+    user.requires_scope(Scopes::TaxRuleWrite)?;
+    let connection = connection.get();
+    let region = Region::find(parameters.id, connection)?;
+    let tax_rules = TaxRule::find_for_region(region.id, connection)?;
+    Ok(HttpResponse::Ok().json(&Payload::from_data(
+        tax_rules,
+        query_parameters.page(),
+        query_parameters.limit(),
+        None,
+    )))
+}
+
+pub async fn create_for_venue(
+    (connection, parameters, json, user): (Connection, Path<PathParameters>, Json<NewTaxRuleRequest>, User),
+) -> Result<HttpResponse, ApiError> {
+    user.requires_scope(Scopes::TaxRuleWrite)?;
+    let connection = connection.get();
+    let venue = Venue::find(parameters.id, connection)?;
+    let tax_rule = TaxRule::create(
+        json.name.clone(),
+        Some(venue.id),
+        None,
+        json.rate_percent,
+        json.applies_to_fees,
+        json.inclusive,
+    )
+    .commit(connection)?;
+    Ok(HttpResponse::Created().json(&tax_rule))
+}
+
+pub async fn create_for_region(
+    (connection, parameters, json, user): (Connection, Path<PathParameters>, Json<NewTaxRuleRequest>, User),
+) -> Result<HttpResponse, ApiError> {
+    user.requires_scope(Scopes::TaxRuleWrite)?;
+    let connection = connection.get();
+    let region = Region::find(parameters.id, connection)?;
+    let tax_rule = TaxRule::create(
+        json.name.clone(),
+        None,
+        Some(region.id),
+        json.rate_percent,
+        json.applies_to_fees,
+        json.inclusive,
+    )
+    .commit(connection)?;
+    Ok(HttpResponse::Created().json(&tax_rule))
+}
+
+pub async fn update(
+    (connection, parameters, tax_rule_parameters, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<TaxRuleEditableAttributes>,
+        User,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    user.requires_scope(Scopes::TaxRuleWrite)?;
+    let connection = connection.get();
+    let tax_rule = TaxRule::find(parameters.id, connection)?;
+    let updated_tax_rule = tax_rule.update(tax_rule_parameters.into_inner(), connection)?;
+    Ok(HttpResponse::Ok().json(&updated_tax_rule))
+}
+
+pub async fn destroy(
+    (connection, parameters, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, ApiError> {
+    user.requires_scope(Scopes::TaxRuleWrite)?;
+    let connection = connection.get();
+    let tax_rule = TaxRule::find(parameters.id, connection)?;
+    tax_rule.destroy(connection)?;
+    Ok(HttpResponse::Ok().json(json!({})))
+}
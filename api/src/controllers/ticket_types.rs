@@ -27,6 +27,8 @@ pub struct CreateTicketPricingRequest {
     pub start_date: NaiveDateTime,
     pub end_date: NaiveDateTime,
     pub is_box_office_only: Option<bool>,
+    #[serde(default)]
+    pub sold_quantity_cap: Option<i64>,
 }
 
 #[derive(Clone, Deserialize)]
@@ -129,6 +131,8 @@ pub struct UpdateTicketPricingRequest {
     pub end_date: Option<NaiveDateTime>,
     pub price_in_cents: Option<i64>,
     pub is_box_office_only: Option<bool>,
+    #[serde(default, deserialize_with = "double_option::deserialize")]
+    pub sold_quantity_cap: Option<Option<i64>>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Default)]
@@ -158,6 +162,12 @@ pub struct UpdateTicketTypeRequest {
     #[serde(default)]
     pub app_sales_enabled: Option<bool>,
     pub rank: Option<i32>,
+    #[serde(default, deserialize_with = "double_option::deserialize")]
+    pub resale_price_cap_percent: Option<Option<i32>>,
+    #[serde(default, deserialize_with = "double_option::deserialize")]
+    pub installment_count: Option<Option<i32>>,
+    #[serde(default)]
+    pub installment_interval_days: Option<i32>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -364,6 +374,9 @@ pub async fn update(
         additional_fee_in_cents: data.additional_fee_in_cents,
         app_sales_enabled: data.app_sales_enabled,
         rank: data.rank,
+        resale_price_cap_percent: data.resale_price_cap_percent,
+        installment_count: data.installment_count,
+        installment_interval_days: data.installment_interval_days,
     };
     let updated_ticket_type = ticket_type.update(update_parameters, Some(user.id()), connection)?;
 
@@ -395,6 +408,7 @@ pub async fn update(
                     start_date: current_ticket_pricing.start_date,
                     end_date: current_ticket_pricing.end_date,
                     is_box_office_only: current_ticket_pricing.is_box_office_only,
+                    sold_quantity_cap: current_ticket_pricing.sold_quantity_cap,
                 };
                 let found_index = ticket_pricing
                     .iter()
@@ -422,6 +436,7 @@ pub async fn update(
                     end_date,
                     price_in_cents,
                     current_ticket_pricing.is_box_office_only.unwrap_or(false),
+                    current_ticket_pricing.sold_quantity_cap.unwrap_or(None),
                     None,
                     Some(user.id()),
                     connection,
@@ -442,6 +457,35 @@ pub async fn update(
     Ok(HttpResponse::Ok().json(result))
 }
 
+#[derive(Deserialize, Serialize)]
+pub struct AssignSeatsRequest {
+    pub seat_ids: Vec<Uuid>,
+}
+
+pub async fn assign_seats(
+    (connection, path, data, user): (
+        Connection,
+        Path<EventTicketPathParameters>,
+        Json<AssignSeatsRequest>,
+        User,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let event = Event::find(path.event_id, connection)?;
+    let organization = event.organization(connection)?;
+    user.requires_scope_for_organization_event(Scopes::TicketTypeWrite, &organization, &event, connection)?;
+
+    let ticket_type = TicketType::find(path.ticket_type_id, connection)?;
+    if ticket_type.event_id != event.id {
+        return application::not_found();
+    }
+
+    let tickets = ticket_type.assign_seats(&data.seat_ids, Some(user.id()), connection)?;
+    let seat_ids: Vec<Uuid> = tickets.iter().filter_map(|t| t.seat_id).collect();
+
+    Ok(HttpResponse::Ok().json(StageSeat::find_for_display(&seat_ids, connection)?))
+}
+
 fn nullify_tickets(
     state: Data<AppState>,
     organization: Organization,
@@ -579,6 +623,7 @@ fn create_ticket_types(
                 current_pricing_entry.end_date,
                 current_pricing_entry.price_in_cents,
                 current_pricing_entry.is_box_office_only.unwrap_or(false),
+                current_pricing_entry.sold_quantity_cap,
                 None,
                 Some(user.id()),
                 connection,
@@ -3,6 +3,7 @@ use crate::communications::{mailers, smsers};
 use crate::controllers::tickets::transfer_tickets_on_blockchain;
 use crate::database::Connection;
 use crate::errors::*;
+use crate::extractors::*;
 use crate::helpers::application;
 use crate::models::*;
 use crate::server::AppState;
@@ -15,6 +16,7 @@ use chrono::prelude::*;
 use db::models::{User as DbUser, *};
 use diesel::PgConnection;
 use itertools::Itertools;
+use uuid::Uuid;
 
 #[derive(Deserialize, Clone)]
 pub struct TransferFilters {
@@ -184,6 +186,60 @@ pub async fn cancel(
     Ok(HttpResponse::Ok().json(&transfer.for_display(connection)?))
 }
 
+#[derive(Deserialize, Serialize, Default)]
+pub struct DeclineTransferRequest {
+    /// Declines only these tickets leaving the rest of the transfer pending, all tickets when omitted
+    pub ticket_ids: Option<Vec<Uuid>>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct DeclineTransferResponse {
+    pub declined_transfer: DisplayTransfer,
+    pub remaining_transfer_authorization: Option<TransferAuthorization>,
+}
+
+pub async fn decline(
+    (connection, path, decline_request, auth_user, state): (
+        Connection,
+        Path<PathParameters>,
+        Json<DeclineTransferRequest>,
+        OptionalUser,
+        Data<AppState>,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    // As with accepting a transfer, having the transfer key allows the recipient to decline it
+    let transfer = Transfer::find_by_transfer_key(path.id, connection)?;
+    let declined_transfer = transfer.decline(
+        decline_request.into_inner().ticket_ids,
+        auth_user.into_inner().map(|u| u.id()),
+        connection,
+    )?;
+
+    // The remaining tickets have a new authorization as the number of tickets forms part of the signature
+    let remaining_transfer_authorization = if declined_transfer.id != transfer.id {
+        Some(Transfer::find(transfer.id, connection)?.into_authorization(connection)?)
+    } else {
+        None
+    };
+
+    let source_user = DbUser::find(declined_transfer.source_user_id, connection)?;
+    if let Some(source_email) = source_user.email.clone() {
+        mailers::tickets::transfer_returned_receipt(
+            &state.config,
+            source_email,
+            &source_user,
+            &declined_transfer,
+            connection,
+        )?;
+    }
+
+    Ok(HttpResponse::Ok().json(&DeclineTransferResponse {
+        declined_transfer: declined_transfer.for_display(connection)?,
+        remaining_transfer_authorization,
+    }))
+}
+
 fn check_transfer_cancel_access(transfer: &Transfer, user: &User, connection: &PgConnection) -> Result<(), ApiError> {
     if transfer.status == TransferStatus::Completed {
         if !user.has_scope(Scopes::TransferCancelAccepted)? {
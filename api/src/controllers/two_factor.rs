@@ -0,0 +1,95 @@
+use crate::auth::user::User as AuthUser;
+use crate::database::Connection;
+use crate::errors::*;
+use crate::extractors::*;
+use crate::helpers::application;
+use crate::server::AppState;
+use actix_web::{web::Data, HttpResponse};
+use db::prelude::*;
+use diesel::PgConnection;
+
+#[derive(Deserialize, Serialize)]
+pub struct TwoFactorCodeRequest {
+    /// Code from the user's authenticator app, or one of their recovery codes
+    pub code: String,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct RecoveryCodesResponse {
+    pub recovery_codes: Vec<String>,
+}
+
+pub async fn enroll(
+    (connection, user, state): (Connection, AuthUser, Data<AppState>),
+) -> Result<HttpResponse, ApiError> {
+    if user.api_key.is_some() {
+        return application::forbidden("Two-factor authentication cannot be managed using an API key");
+    }
+    let enrollment = UserTotpCredential::enroll(&user.user, &state.config.api_keys_encryption_key, connection.get())?;
+    Ok(HttpResponse::Created().json(&enrollment))
+}
+
+pub async fn confirm(
+    (connection, user, state, json): (Connection, AuthUser, Data<AppState>, Json<TwoFactorCodeRequest>),
+) -> Result<HttpResponse, ApiError> {
+    if user.api_key.is_some() {
+        return application::forbidden("Two-factor authentication cannot be managed using an API key");
+    }
+    let connection = connection.get();
+    let credential = UserTotpCredential::find_by_user_id(user.id(), connection)?;
+    let recovery_codes = credential.confirm(&json.code, &state.config.api_keys_encryption_key, connection)?;
+    Ok(HttpResponse::Ok().json(&RecoveryCodesResponse { recovery_codes }))
+}
+
+pub async fn regenerate_recovery_codes(
+    (connection, user, state, json): (Connection, AuthUser, Data<AppState>, Json<TwoFactorCodeRequest>),
+) -> Result<HttpResponse, ApiError> {
+    if user.api_key.is_some() {
+        return application::forbidden("Two-factor authentication cannot be managed using an API key");
+    }
+    let connection = connection.get();
+    let credential = find_verified_credential(&user, &json.code, &state, connection)?;
+    let recovery_codes = credential.regenerate_recovery_codes(connection)?;
+    Ok(HttpResponse::Ok().json(&RecoveryCodesResponse { recovery_codes }))
+}
+
+pub async fn disable(
+    (connection, user, state, json): (Connection, AuthUser, Data<AppState>, Json<TwoFactorCodeRequest>),
+) -> Result<HttpResponse, ApiError> {
+    if user.api_key.is_some() {
+        return application::forbidden("Two-factor authentication cannot be managed using an API key");
+    }
+    let connection = connection.get();
+    let credential = find_verified_credential(&user, &json.code, &state, connection)?;
+    credential.disable(Some(user.id()), connection)?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+// Changes to an enabled second factor require proof the user still holds it
+fn find_verified_credential(
+    user: &AuthUser,
+    code: &str,
+    state: &AppState,
+    connection: &PgConnection,
+) -> Result<UserTotpCredential, ApiError> {
+    let credential = match UserTotpCredential::find_confirmed_for_user(user.id(), connection)? {
+        Some(credential) => credential,
+        None => {
+            return Err(ApplicationError::new_with_type(
+                ApplicationErrorType::Unprocessable,
+                "Two-factor authentication is not enabled".to_string(),
+            )
+            .into())
+        }
+    };
+    if !credential.verify(code, &state.config.api_keys_encryption_key, connection)?
+        && !UserRecoveryCode::redeem(user.id(), code, connection)?
+    {
+        return Err(ApplicationError::new_with_type(
+            ApplicationErrorType::Unprocessable,
+            "Two-factor code is invalid".to_string(),
+        )
+        .into());
+    }
+    Ok(credential)
+}
@@ -0,0 +1,45 @@
+use crate::auth::user::User as AuthUser;
+use crate::database::Connection;
+use crate::errors::*;
+use crate::extractors::*;
+use crate::helpers::application;
+use crate::models::PathParameters;
+use actix_web::{web::Path, HttpResponse};
+use db::prelude::*;
+
+pub async fn index((connection, user): (Connection, AuthUser)) -> Result<HttpResponse, ApiError> {
+    let sessions: Vec<DisplayUserSession> = UserSession::find_active_for_user(user.id(), connection.get())?
+        .iter()
+        .map(|session| session.for_display(user.session_id))
+        .collect();
+    Ok(HttpResponse::Ok().json(&sessions))
+}
+
+pub async fn destroy(
+    (connection, path, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, ApiError> {
+    if user.api_key.is_some() {
+        return application::forbidden("Sessions cannot be managed using an API key");
+    }
+    let connection = connection.get();
+    let session = UserSession::find(path.id, connection)?;
+    if session.user_id != user.id() {
+        return application::unauthorized(Some(user), None);
+    }
+    session.revoke(SessionRevocationReasons::SignedOut, Some(user.id()), connection)?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Signs the user out of every session, including the one making the request
+pub async fn destroy_all((connection, user): (Connection, AuthUser)) -> Result<HttpResponse, ApiError> {
+    if user.api_key.is_some() {
+        return application::forbidden("Sessions cannot be managed using an API key");
+    }
+    UserSession::revoke_all_for_user(
+        user.id(),
+        SessionRevocationReasons::SignedOut,
+        Some(user.id()),
+        connection.get(),
+    )?;
+    Ok(HttpResponse::Ok().finish())
+}
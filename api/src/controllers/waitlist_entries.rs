@@ -0,0 +1,87 @@
+use crate::auth::user::User;
+use crate::database::Connection;
+use crate::errors::*;
+use crate::extractors::*;
+use crate::helpers::application;
+use crate::models::{EventTicketPathParameters, WebPayload};
+use actix_web::{
+    web::{Path, Query},
+    HttpResponse,
+};
+use db::prelude::*;
+use reqwest::StatusCode;
+
+#[derive(Deserialize, Serialize)]
+pub struct JoinWaitlistRequest {
+    pub quantity: u32,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct WaitlistFilterParameters {
+    pub status: Option<WaitlistEntryStatus>,
+}
+
+pub async fn index(
+    (connection, path, query, filter_query, user): (
+        Connection,
+        Path<EventTicketPathParameters>,
+        Query<PagingParameters>,
+        Query<WaitlistFilterParameters>,
+        User,
+    ),
+) -> Result<WebPayload<WaitlistEntry>, ApiError> {
+    let connection = connection.get();
+    let event = Event::find(path.event_id, connection)?;
+    let organization = event.organization(connection)?;
+    user.requires_scope_for_organization_event(Scopes::TicketTypeRead, &organization, &event, connection)?;
+
+    let ticket_type = TicketType::find(path.ticket_type_id, connection)?;
+    if ticket_type.event_id != event.id {
+        return Err(NotFoundError {}.into());
+    }
+
+    let payload = WaitlistEntry::find_for_ticket_type(
+        ticket_type.id,
+        filter_query.status,
+        query.page(),
+        query.limit(),
+        connection,
+    )?;
+    Ok(WebPayload::new(StatusCode::OK, payload))
+}
+
+pub async fn create(
+    (connection, path, json, user): (
+        Connection,
+        Path<EventTicketPathParameters>,
+        Json<JoinWaitlistRequest>,
+        User,
+    ),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let ticket_type = TicketType::find(path.ticket_type_id, connection)?;
+    if ticket_type.event_id != path.event_id {
+        return application::not_found();
+    }
+
+    let entry = WaitlistEntry::create(ticket_type.id, user.id(), json.quantity).commit(connection)?;
+    Ok(HttpResponse::Created().json(&entry))
+}
+
+pub async fn destroy(
+    (connection, path, user): (Connection, Path<EventTicketPathParameters>, User),
+) -> Result<HttpResponse, ApiError> {
+    let connection = connection.get();
+    let ticket_type = TicketType::find(path.ticket_type_id, connection)?;
+    if ticket_type.event_id != path.event_id {
+        return application::not_found();
+    }
+
+    match WaitlistEntry::find_open_for_user(ticket_type.id, user.id(), connection)? {
+        Some(entry) => {
+            entry.cancel(user.id(), connection)?;
+            Ok(HttpResponse::Ok().json(json!({})))
+        }
+        None => application::not_found(),
+    }
+}
@@ -7,16 +7,19 @@ use diesel::connection::TransactionManager;
 use diesel::Connection as DieselConnection;
 use diesel::PgConnection;
 use futures::future::{err, ok, Ready};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
 pub struct Connection {
     pub inner: Arc<ConnectionType>,
+    commit_on_error: Arc<AtomicBool>,
 }
 
 impl From<ConnectionType> for Connection {
     fn from(connection_type: ConnectionType) -> Self {
         Connection {
             inner: Arc::new(connection_type),
+            commit_on_error: Arc::new(AtomicBool::new(false)),
         }
     }
 }
@@ -46,12 +49,23 @@ impl Connection {
     pub fn rollback_transaction(&self) -> Result<(), diesel::result::Error> {
         self.get().transaction_manager().rollback_transaction(self.get())
     }
+
+    /// Keeps the request's changes when it responds with an error, for records such as failed
+    /// login attempts that must outlive the failure they describe
+    pub fn commit_on_error(&self) {
+        self.commit_on_error.store(true, Ordering::SeqCst);
+    }
+
+    pub fn should_commit_on_error(&self) -> bool {
+        self.commit_on_error.load(Ordering::SeqCst)
+    }
 }
 
 impl Clone for Connection {
     fn clone(&self) -> Self {
         Connection {
             inner: self.inner.clone(),
+            commit_on_error: self.commit_on_error.clone(),
         }
     }
 }
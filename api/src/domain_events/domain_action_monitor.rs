@@ -1,7 +1,9 @@
 use crate::config::Config;
 use crate::database::*;
 use crate::domain_events::errors::DomainActionError;
+use crate::domain_events::listener::{NotificationListener, DOMAIN_ACTIONS_CHANNEL, DOMAIN_EVENTS_CHANNEL};
 use crate::domain_events::routing::{DomainActionExecutor, DomainActionRouter};
+use crate::domain_events::scheduler::{DomainActionScheduler, DomainActionSlot};
 use crate::domain_events::webhook_publisher::WebhookPublisher;
 use crate::utils::ServiceLocator;
 use db::prelude::*;
@@ -9,10 +11,11 @@ use log::Level::*;
 use logging::*;
 use std::sync::mpsc;
 use std::sync::mpsc::{Receiver, Sender};
+use std::thread;
 use std::thread::JoinHandle;
 use std::time::Duration;
-use std::{cmp, thread};
 use tokio::time::timeout;
+use uuid::Uuid;
 
 pub struct DomainActionMonitor {
     config: Config,
@@ -33,19 +36,19 @@ impl DomainActionMonitor {
 
     pub async fn run_til_empty(&self) -> Result<(), DomainActionError> {
         let router = DomainActionMonitor::create_router(&self.config);
+        let scheduler = DomainActionScheduler::new(self.config.domain_actions.clone());
 
         loop {
             let mut num_processed = 0;
 
-            let futures = DomainActionMonitor::find_actions(
-                &self.database,
-                &router,
-                cmp::max(1, self.config.connection_pool.max / 2) as usize,
-            )?;
+            let futures = DomainActionMonitor::find_actions(&self.database, &router, &scheduler)?;
 
-            for (executor, domain_action, connection) in futures {
-                if let Err(err) = timeout(Duration::from_secs(55), executor.execute(domain_action, connection)).await {
+            for (executor, domain_action, connection, _slot) in futures {
+                let action_id = domain_action.id;
+                let action_timeout = scheduler.timeout_for(domain_action.domain_action_type);
+                if let Err(err) = timeout(action_timeout, executor.execute(domain_action, connection)).await {
                     jlog! {Error,"bigneon::domain_actions", "Action: failed", {"error": err.to_string()}};
+                    DomainActionMonitor::fail_timed_out_action(&self.database, action_id, action_timeout);
                 }
                 num_processed += 1;
             }
@@ -121,6 +124,11 @@ impl DomainActionMonitor {
             config.token_issuer.as_ref().clone(),
             service_locator.create_deep_linker()?,
         );
+        let mut listener = NotificationListener::new(
+            config.database_url.clone(),
+            DOMAIN_EVENTS_CHANNEL,
+            config.domain_actions.listen_for_notifications,
+        );
         loop {
             if rx.try_recv().is_ok() {
                 jlog!(Info, "bigneon::domain_actions", "Stopping events processor", {});
@@ -130,7 +138,7 @@ impl DomainActionMonitor {
             // Domain Monitor main loop
             if DomainActionMonitor::find_and_publish_events(&webhook_publisher, &database)? == 0 {
                 //                jlog!(Info, "bigneon::domain_events", "No events founds, sleeping", {});
-                thread::sleep(Duration::from_secs(interval));
+                listener.wait(Duration::from_secs(interval));
             }
         }
         Ok(())
@@ -143,11 +151,38 @@ impl DomainActionMonitor {
         router
     }
 
+    /// The executor's transaction is abandoned when it times out, so the failure is recorded on a
+    /// separate connection. Once the retries are exceeded the action ends up in the dead letter queue.
+    fn fail_timed_out_action(database: &Database, action_id: Uuid, action_timeout: Duration) {
+        let connection = match database.get_connection() {
+            Ok(connection) => connection,
+            Err(e) => {
+                jlog!(Error, "bigneon::domain_actions", "Could not mark timed out action as failed", {"domain_action_id": action_id, "error": e.to_string()});
+                return;
+            }
+        };
+        let connection = connection.get();
+        let result = DomainAction::find(action_id, connection).and_then(|action| {
+            action.set_failed(
+                &format!("Timed out after {} seconds", action_timeout.as_secs()),
+                connection,
+            )
+        });
+        if let Err(e) = result {
+            jlog!(Error, "bigneon::domain_actions", "Could not mark timed out action as failed", {"domain_action_id": action_id, "error": e.to_string()});
+        }
+    }
+
     fn find_actions<'a>(
         database: &Database,
         router: &'a DomainActionRouter,
-        limit: usize,
-    ) -> Result<Vec<(&'a dyn DomainActionExecutor, DomainAction, Connection)>, DomainActionError> {
+        scheduler: &DomainActionScheduler,
+    ) -> Result<Vec<(&'a dyn DomainActionExecutor, DomainAction, Connection, DomainActionSlot)>, DomainActionError>
+    {
+        let limit = scheduler.available();
+        if limit == 0 {
+            return Ok(vec![]);
+        }
         let connection = database.get_connection()?;
 
         let pending_actions = DomainAction::find_pending(None, connection.get())?;
@@ -166,13 +201,20 @@ impl DomainActionMonitor {
 
         let mut result = vec![];
 
-        // //Process actions
+        // Actions are ordered by priority, lower priority actions wait when the limits are reached
         let len = pending_actions.len();
         for (index, action) in pending_actions.into_iter().enumerate() {
-            if limit < index {
+            if result.len() >= limit {
                 break;
             }
-            jlog! {Info, &format!("Pending Action: {}", action.domain_action_type), {"id":action.id, "domain_action_type": action.domain_action_type}};
+            let slot = match scheduler.try_reserve(action.domain_action_type) {
+                Some(slot) => slot,
+                None => {
+                    jlog! {Trace, "bigneon::domain_actions", "Concurrency limit reached for action type", {"domain_action_type": action.domain_action_type, "in_flight": scheduler.in_flight(action.domain_action_type)}};
+                    continue;
+                }
+            };
+            jlog! {Info, &format!("Pending Action: {}", action.domain_action_type), {"id":action.id, "domain_action_type": action.domain_action_type, "priority": action.priority}};
             let connection = connection.get();
             let per_action_connection = match database.get_connection() {
                 Ok(conn) => conn,
@@ -189,7 +231,7 @@ impl DomainActionMonitor {
                 }
             };
 
-            match action.set_busy(60, connection) {
+            match action.set_busy(scheduler.busy_period_for(action.domain_action_type), connection) {
                 Ok(_) => {}
                 Err(e) => match e.error_code {
                     ErrorCode::ConcurrencyError => {
@@ -212,7 +254,7 @@ impl DomainActionMonitor {
 
             per_action_connection.begin_transaction()?;
             // let f = command.execute(action, per_action_connection);
-            result.push((command, action, per_action_connection));
+            result.push((command, action, per_action_connection, slot));
         }
 
         Ok(result)
@@ -226,6 +268,12 @@ impl DomainActionMonitor {
         rx: Receiver<()>,
     ) -> Result<(), DomainActionError> {
         let router = DomainActionMonitor::create_router(&conf);
+        let scheduler = DomainActionScheduler::new(conf.domain_actions.clone());
+        let mut listener = NotificationListener::new(
+            conf.database_url.clone(),
+            DOMAIN_ACTIONS_CHANNEL,
+            conf.domain_actions.listen_for_notifications,
+        );
 
         let mut builder = tokio::runtime::Builder::new();
         let runtime = builder.threaded_scheduler().enable_all().build()?; // Runtime::new()?;
@@ -237,21 +285,24 @@ impl DomainActionMonitor {
             }
             //Check for actions that are due to be processed
 
-            let actions = DomainActionMonitor::find_actions(
-                &database,
-                &router,
-                cmp::max(1, conf.connection_pool.max / 2) as usize,
-            )?;
+            let actions = DomainActionMonitor::find_actions(&database, &router, &scheduler)?;
 
             if actions.len() == 0 {
-                thread::sleep(Duration::from_secs(interval));
+                // Woken early when an action is inserted, scheduled actions are still found by polling
+                listener.wait(Duration::from_secs(interval));
             } else {
-                for (command, action, connection) in actions {
+                for (command, action, connection, slot) in actions {
+                    let action_id = action.id;
+                    let action_timeout = scheduler.timeout_for(action.domain_action_type);
                     let cmd = command.execute(action, connection);
-                    runtime.spawn(async {
-                        if let Err(e) = timeout(Duration::from_secs(55), cmd).await {
+                    let database = database.clone();
+                    runtime.spawn(async move {
+                        if let Err(e) = timeout(action_timeout, cmd).await {
                             jlog! {Error,"bigneon::domain_actions", "Action:  failed", {"error": e.to_string()}};
+                            DomainActionMonitor::fail_timed_out_action(&database, action_id, action_timeout);
                         }
+                        // Frees the slot for the next action of this type
+                        drop(slot);
                     });
                 }
             }
@@ -42,7 +42,9 @@ impl Future for ExecutorFuture {
                 "bigneon::domain_actions",
                     "Action succeeded",
                     { "domain_action_id": self.action.id,
+                    "domain_action_type": self.action.domain_action_type,
                     "started_at": self.started_at,
+                    "queue_latency_ms": (self.started_at - self.action.scheduled_at).num_milliseconds(),
                     "milliseconds_taken": (Utc::now().naive_utc() - self.started_at).num_milliseconds()
                     });
                 self.action.set_done(&self.conn.get())?;
@@ -55,7 +57,9 @@ impl Future for ExecutorFuture {
                 "bigneon::domain_actions",
                 "Action failed",
                  { "domain_action_id": self.action.id,
+                  "domain_action_type": self.action.domain_action_type,
                   "started_at": self.started_at,
+                  "queue_latency_ms": (self.started_at - self.action.scheduled_at).num_milliseconds(),
                   "milliseconds_taken": (Utc::now().naive_utc() - self.started_at).num_milliseconds(),
                   "error": &desc
                    });
@@ -0,0 +1,150 @@
+use crate::communications::mailers;
+use crate::config::Config;
+use crate::database::Connection;
+use crate::domain_events::executor_future::ExecutorFuture;
+use crate::domain_events::routing::DomainActionExecutor;
+use crate::errors::*;
+use crate::payments::PaymentProcessorBehavior;
+use crate::utils::ServiceLocator;
+use crate::SITE_NAME;
+use chrono::prelude::*;
+use chrono::Duration;
+use db::prelude::*;
+use futures::future::TryFutureExt;
+use log::Level::{Error, Info};
+
+#[derive(Clone)]
+pub struct ChargePaymentPlanInstallmentExecutor {
+    config: Config,
+}
+
+impl DomainActionExecutor for ChargePaymentPlanInstallmentExecutor {
+    fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
+        let fut = self.clone().perform_job(action.clone(), conn.clone())
+            .inspect_err({
+                let action = action.clone();
+                move |e| jlog!(Error, "Charge payment plan installment failed", {"action_id": action.id, "main_table_id": action.main_table_id, "error": e.to_string()})
+            });
+        ExecutorFuture::new(action, conn, Box::pin(fut))
+    }
+}
+
+impl ChargePaymentPlanInstallmentExecutor {
+    pub fn new(config: Config) -> ChargePaymentPlanInstallmentExecutor {
+        ChargePaymentPlanInstallmentExecutor { config }
+    }
+
+    pub async fn perform_job(self, action: DomainAction, conn: Connection) -> Result<(), ApiError> {
+        let connection = conn.get();
+        let installment_id = action
+            .main_table_id
+            .ok_or_else(|| ApplicationError::new("No installment id attached to domain action".to_string()))?;
+        let installment = PaymentPlanInstallment::find(installment_id, connection)?;
+        let plan = installment.payment_plan(connection)?;
+
+        // Installments paid or cancelled since the charge was scheduled are skipped
+        if installment.status != PaymentPlanInstallmentStatus::Pending || plan.status != PaymentPlanStatus::Active {
+            return Ok(());
+        }
+
+        let mut order = Order::find(plan.order_id, connection)?;
+        order.lock_version(connection)?;
+        let user = order.user(connection)?;
+
+        let payment_method = match plan.payment_method(connection)? {
+            Some(payment_method) => payment_method,
+            None => {
+                return self.record_failure(&installment, &user, &order, "No payment method on file", &conn);
+            }
+        };
+
+        let mut organizations = order.organizations(connection)?;
+        if organizations.len() != 1 {
+            return Err(ApplicationError::new(
+                "Orders containing more than one organization are not supported".to_string(),
+            )
+            .into());
+        }
+        let organization = organizations.remove(0);
+        let client = ServiceLocator::new(&self.config)?.create_payment_processor(payment_method.name, &organization)?;
+        let behavior = match client.behavior() {
+            PaymentProcessorBehavior::AuthThenComplete(behavior) => behavior,
+            PaymentProcessorBehavior::RedirectToPaymentPage(_) => {
+                return Err(ApplicationError::new(
+                    "Payment plans are not supported by this payment provider".to_string(),
+                )
+                .into());
+            }
+        };
+
+        let currency = match order.currency(connection)? {
+            Some(currency) => currency.to_lowercase(),
+            None => self.config.primary_currency.clone(),
+        };
+        let charge = async {
+            let auth_result = behavior
+                .auth(
+                    &payment_method.provider,
+                    installment.amount_in_cents,
+                    &currency,
+                    SITE_NAME,
+                    order.purchase_metadata(connection)?,
+                    None,
+                )
+                .await?;
+            let charge_result = behavior.complete_authed_charge(&auth_result.id).await?;
+            Ok::<_, ApiError>(charge_result)
+        };
+        let charge_result = match charge.await {
+            Ok(charge_result) => charge_result,
+            Err(e) => {
+                return self.record_failure(&installment, &user, &order, &e.to_string(), &conn);
+            }
+        };
+
+        jlog!(Info, "Payment plan installment charged", {"installment_id": installment.id, "payment_plan_id": plan.id, "charge_id": charge_result.id});
+        if let Err(e) = installment.mark_paid(
+            behavior.payment_provider(),
+            charge_result.id.clone(),
+            charge_result.to_json()?,
+            None,
+            connection,
+        ) {
+            client.refund(&charge_result.id).await?;
+            return Err(e.into());
+        }
+
+        Ok(())
+    }
+
+    fn record_failure(
+        &self,
+        installment: &PaymentPlanInstallment,
+        user: &User,
+        order: &Order,
+        reason: &str,
+        conn: &Connection,
+    ) -> Result<(), ApiError> {
+        let connection = conn.get();
+        let plan = installment.record_failed_attempt(reason, connection)?;
+        jlog!(Info, "Payment plan installment could not be charged", {"installment_id": installment.id, "payment_plan_id": plan.id, "reason": reason});
+
+        if let Some(email) = user.email.clone() {
+            if plan.status == PaymentPlanStatus::Cancelled {
+                mailers::payment_plans::cancelled(&self.config, email, user, order, connection)?;
+            } else {
+                let retry_at = Utc::now().naive_utc() + Duration::days(PAYMENT_PLAN_RETRY_INTERVAL_DAYS);
+                mailers::payment_plans::installment_failed(
+                    &self.config,
+                    email,
+                    user,
+                    installment,
+                    retry_at,
+                    connection,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,167 @@
+use crate::config::Config;
+use crate::database::Connection;
+use crate::domain_events::executor_future::ExecutorFuture;
+use crate::domain_events::routing::DomainActionExecutor;
+use crate::errors::*;
+use actix_web::error::BlockingError;
+use actix_web::web;
+use chrono::prelude::*;
+use db::prelude::*;
+use db::utils::{webhook_signatures, webhook_urls};
+use futures::future::{self, TryFutureExt};
+use hyper::body::HttpBody;
+use hyper::client::connect::dns::Name;
+use hyper::client::HttpConnector;
+use hyper::header::CONTENT_TYPE;
+use hyper::{Body, Client, Request};
+use hyper_tls::HttpsConnector;
+use log::Level::{Error, Info};
+use std::io;
+use std::net::IpAddr;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use std::vec;
+use tower_service::Service;
+
+const REQUEST_TIMEOUT_SECONDS: u64 = 10;
+// Only an excerpt of the response is kept so the rest of a large body is never read
+const MAX_RESPONSE_BODY_BYTES: usize = 4096;
+pub const WEBHOOK_DELIVERY_HEADER: &str = "X-BigNeon-Delivery";
+pub const WEBHOOK_SIGNATURE_HEADER: &str = "X-BigNeon-Signature";
+pub const WEBHOOK_TIMESTAMP_HEADER: &str = "X-BigNeon-Timestamp";
+
+#[derive(Clone)]
+pub struct DeliverWebhookExecutor {
+    config: Config,
+}
+
+impl DomainActionExecutor for DeliverWebhookExecutor {
+    fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
+        let fut = self.clone().perform_job(action.clone(), conn.clone())
+            .inspect_err({
+                let action = action.clone();
+                move |e| jlog!(Error, "Deliver webhook failed", {"action_id": action.id, "main_table_id": action.main_table_id, "error": e.to_string()})
+            });
+        ExecutorFuture::new(action, conn, Box::pin(fut))
+    }
+}
+
+impl DeliverWebhookExecutor {
+    pub fn new(config: Config) -> DeliverWebhookExecutor {
+        DeliverWebhookExecutor { config }
+    }
+
+    pub async fn perform_job(self, action: DomainAction, conn: Connection) -> Result<(), ApiError> {
+        let connection = conn.get();
+        let delivery_id = action
+            .main_table_id
+            .ok_or_else(|| ApplicationError::new("No webhook delivery id attached to domain action".to_string()))?;
+        let delivery = WebhookDelivery::find(delivery_id, connection)?;
+        let publisher = delivery.domain_event_publisher(connection)?;
+
+        // Deliveries to webhooks deleted since the attempt was scheduled are dropped
+        if delivery.status != WebhookDeliveryStatus::Pending || publisher.deleted_at.is_some() {
+            return Ok(());
+        }
+
+        let signing_secret = publisher
+            .signing_secret(&self.config.api_keys_encryption_key)?
+            .ok_or_else(|| ApplicationError::new("Webhook has no signing secret".to_string()))?;
+        let timestamp = Utc::now().timestamp();
+
+        // The URL was checked when saved but its host can since have been pointed at an internal address.
+        // Name resolution blocks so it is moved off the executor.
+        let webhook_url = publisher.webhook_url.clone();
+        let addresses = match web::block(move || {
+            webhook_urls::check(&webhook_url).and_then(|url| webhook_urls::resolve_public_addresses(&url))
+        })
+        .await
+        {
+            Ok(addresses) => addresses,
+            Err(e) => {
+                let message = match e {
+                    BlockingError::Error(e) => e.message(),
+                    BlockingError::Canceled => "Webhook URL could not be resolved",
+                };
+                let delivery = delivery.record_attempt(None, 0, None, Some(message.to_string()), connection)?;
+                jlog!(Info, "bigneon::domain_actions", "Webhook delivery blocked", {"webhook_delivery_id": delivery.id, "status": delivery.status, "error": message});
+                return Ok(());
+            }
+        };
+
+        let request = Request::post(&publisher.webhook_url)
+            .header(CONTENT_TYPE, "application/json")
+            .header(WEBHOOK_DELIVERY_HEADER, delivery.id.to_string())
+            .header(WEBHOOK_TIMESTAMP_HEADER, timestamp.to_string())
+            .header(
+                WEBHOOK_SIGNATURE_HEADER,
+                webhook_signatures::signature_header(&delivery.payload, &signing_secret, timestamp),
+            )
+            .body(Body::from(delivery.payload.clone()))
+            .map_err(|e| ApplicationError::new(e.to_string()))?;
+
+        let started_at = Instant::now();
+        let result = tokio::time::timeout(
+            Duration::from_secs(REQUEST_TIMEOUT_SECONDS),
+            send(request, addresses.into_iter().map(|address| address.ip()).collect()),
+        )
+        .await
+        .unwrap_or_else(|_| Err("Webhook request timed out".to_string()));
+        let latency_ms = started_at.elapsed().as_millis() as i64;
+
+        let delivery = match result {
+            Ok((status, body)) => delivery.record_attempt(Some(status), latency_ms, Some(&body), None, connection)?,
+            Err(error) => delivery.record_attempt(None, latency_ms, None, Some(error), connection)?,
+        };
+        jlog!(Info, "bigneon::domain_actions", "Webhook delivery attempted", {"webhook_delivery_id": delivery.id, "status": delivery.status, "attempt_count": delivery.attempt_count});
+
+        Ok(())
+    }
+}
+
+/// Sends the request to the addresses that were checked rather than resolving the host again, a host
+/// whose DNS changes after the check could otherwise send it to an internal address. Redirects are not
+/// followed as they could also lead to an internal address.
+async fn send(request: Request<Body>, addresses: Vec<IpAddr>) -> Result<(i32, String), String> {
+    let mut http = HttpConnector::new_with_resolver(CheckedAddresses(addresses));
+    http.enforce_http(false);
+    let tls = native_tls::TlsConnector::new().map_err(|e| e.to_string())?;
+    let mut https = HttpsConnector::from((http, tls.into()));
+    https.https_only(true);
+
+    let mut response = Client::builder()
+        .build::<_, Body>(https)
+        .request(request)
+        .await
+        .map_err(|e| e.to_string())?;
+    let status = response.status().as_u16() as i32;
+
+    let mut body = Vec::new();
+    while body.len() < MAX_RESPONSE_BODY_BYTES {
+        match response.body_mut().data().await {
+            Some(Ok(chunk)) => body.extend_from_slice(&chunk),
+            // The status is recorded even if the body cannot be read
+            Some(Err(_)) | None => break,
+        }
+    }
+    body.truncate(MAX_RESPONSE_BODY_BYTES);
+    Ok((status, String::from_utf8_lossy(&body).into_owned()))
+}
+
+/// Resolves the webhook host to the addresses it was checked against
+#[derive(Clone)]
+struct CheckedAddresses(Vec<IpAddr>);
+
+impl Service<Name> for CheckedAddresses {
+    type Response = vec::IntoIter<IpAddr>;
+    type Error = io::Error;
+    type Future = future::Ready<Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, _: Name) -> Self::Future {
+        future::ready(Ok(self.0.clone().into_iter()))
+    }
+}
@@ -0,0 +1,73 @@
+use crate::communications::mailers;
+use crate::config::Config;
+use crate::database::Connection;
+use crate::domain_events::executor_future::ExecutorFuture;
+use crate::domain_events::routing::DomainActionExecutor;
+use crate::errors::*;
+use chrono::prelude::*;
+use db::prelude::*;
+use futures::future;
+use log::Level::Error;
+
+pub struct ExpireTransferExecutor {
+    config: Config,
+}
+
+impl DomainActionExecutor for ExpireTransferExecutor {
+    fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
+        match self.perform_job(&action, &conn) {
+            Ok(_) => ExecutorFuture::new(action, conn, Box::pin(future::ok(()))),
+            Err(e) => {
+                jlog!(Error, "Expire transfer action failed", {"action_id": action.id, "main_table_id": action.main_table_id, "error": e.to_string()});
+                ExecutorFuture::new(action, conn, Box::pin(future::err(e)))
+            }
+        }
+    }
+}
+
+impl ExpireTransferExecutor {
+    pub fn new(config: Config) -> ExpireTransferExecutor {
+        ExpireTransferExecutor { config }
+    }
+
+    pub fn perform_job(&self, action: &DomainAction, conn: &Connection) -> Result<(), ApiError> {
+        let conn = conn.get();
+        let id = action
+            .main_table_id
+            .clone()
+            .ok_or(ApplicationError::new("No id supplied in the action".to_string()))?;
+
+        match action
+            .main_table
+            .clone()
+            .ok_or(ApplicationError::new("No table supplied in the action".to_string()))?
+        {
+            Tables::Transfers => {
+                let transfer = Transfer::find(id, conn)?;
+                // Accepted, cancelled or declined transfers have nothing to expire
+                if transfer.status != TransferStatus::Pending {
+                    return Ok(());
+                }
+                if let Some(expires_at) = transfer.expires_at {
+                    if expires_at > Utc::now().naive_utc() {
+                        return Err(ApplicationError::new("Transfer has not yet expired".to_string()).into());
+                    }
+                }
+
+                let transfer = transfer.expire(conn)?;
+                let source_user = User::find(transfer.source_user_id, conn)?;
+                if let Some(source_email) = source_user.email.clone() {
+                    mailers::tickets::transfer_returned_receipt(
+                        &self.config,
+                        source_email,
+                        &source_user,
+                        &transfer,
+                        conn,
+                    )?;
+                }
+            }
+            _ => return Err(ApplicationError::new("Table not supported".to_string()).into()),
+        }
+        Ok(())
+    }
+}
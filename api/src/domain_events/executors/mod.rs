@@ -1,27 +1,41 @@
 pub use self::broadcast_push_notification::*;
+pub use self::charge_payment_plan_installment::*;
+pub use self::deliver_webhook::*;
+pub use self::expire_transfer::*;
 pub use self::finalize_settlements::*;
 pub use self::process_payment_ipn::*;
+pub use self::process_payment_provider_event::*;
 pub use self::process_settlement_report::*;
 pub use self::process_transfer_drip_event::*;
+pub use self::process_waitlist::*;
 pub use self::regenerate_drip_actions::*;
 pub use self::release_hold_inventory::*;
 pub use self::retarget_abandoned_orders::*;
+pub use self::return_stored_value::*;
 pub use self::send_automatic_report_emails::*;
 pub use self::send_communication::*;
 pub use self::send_order_complete::*;
+pub use self::send_payment_method_expiry_notifications::*;
 pub use self::submit_sitemap_to_search_engines::*;
 pub use self::update_genres::*;
 
 mod broadcast_push_notification;
+mod charge_payment_plan_installment;
+mod deliver_webhook;
+mod expire_transfer;
 mod finalize_settlements;
 mod process_payment_ipn;
+mod process_payment_provider_event;
 mod process_settlement_report;
 mod process_transfer_drip_event;
+mod process_waitlist;
 mod regenerate_drip_actions;
 mod release_hold_inventory;
 mod retarget_abandoned_orders;
+mod return_stored_value;
 mod send_automatic_report_emails;
 mod send_communication;
 mod send_order_complete;
+mod send_payment_method_expiry_notifications;
 mod submit_sitemap_to_search_engines;
 mod update_genres;
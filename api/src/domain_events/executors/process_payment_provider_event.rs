@@ -0,0 +1,157 @@
+use crate::database::Connection;
+use crate::domain_events::executor_future::ExecutorFuture;
+use crate::domain_events::routing::DomainActionExecutor;
+use crate::errors::*;
+use crate::payments::mock::{MOCK_EVENT_CAPTURE_FAILED, MOCK_EVENT_CAPTURE_SUCCEEDED};
+use db::prelude::*;
+use diesel::PgConnection;
+use futures::future;
+use log::Level::{Debug, Error};
+use stripe::WebhookEvent;
+
+pub struct ProcessPaymentProviderEventExecutor {}
+
+impl DomainActionExecutor for ProcessPaymentProviderEventExecutor {
+    fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
+        match self.perform_job(&action, &conn) {
+            Ok(_) => ExecutorFuture::new(action, conn, Box::pin(future::ok(()))),
+            Err(e) => {
+                jlog!(Error, "Process payment provider event action failed", {"action_id": action.id, "main_table_id": action.main_table_id, "error": e.to_string()});
+                ExecutorFuture::new(action, conn, Box::pin(future::err(e)))
+            }
+        }
+    }
+}
+
+impl ProcessPaymentProviderEventExecutor {
+    pub fn new() -> ProcessPaymentProviderEventExecutor {
+        ProcessPaymentProviderEventExecutor {}
+    }
+
+    pub fn perform_job(&self, action: &DomainAction, conn: &Connection) -> Result<(), ApiError> {
+        let conn = conn.get();
+        let id = action
+            .main_table_id
+            .clone()
+            .ok_or(ApplicationError::new("No id supplied in the action".to_string()))?;
+        let event = PaymentProviderEvent::find(id, conn)?;
+        if event.processed_at.is_some() {
+            return Ok(());
+        }
+
+        match event.provider {
+            PaymentProviders::Stripe => {
+                let webhook_event: WebhookEvent = serde_json::from_value(event.payload.clone())?;
+                self.process_stripe_event(&webhook_event, conn)?;
+            }
+            PaymentProviders::Mock => self.process_mock_event(&event, conn)?,
+            _ => return Err(ApplicationError::new("Payment provider not supported".to_string()).into()),
+        }
+        event.mark_processed(conn)?;
+
+        Ok(())
+    }
+
+    fn process_stripe_event(&self, event: &WebhookEvent, conn: &PgConnection) -> Result<(), ApiError> {
+        let raw_data = event.data.object.clone();
+        match event.event_type.as_str() {
+            "charge.dispute.created" => {
+                let dispute = event.dispute()?;
+                if let Some(payment) = self.find_stripe_payment(event, &dispute.charge, conn)? {
+                    payment.record_dispute(&dispute.id, dispute.amount, raw_data, conn)?;
+                }
+            }
+            "charge.dispute.closed" => {
+                let dispute = event.dispute()?;
+                if let Some(payment) = self.find_stripe_payment(event, &dispute.charge, conn)? {
+                    payment.record_dispute_closed(&dispute.id, dispute.status == "won", raw_data, conn)?;
+                }
+            }
+            "charge.refunded" => {
+                let charge = event.charge()?;
+                let payment = self
+                    .find_stripe_payment(event, &charge.id, conn)?
+                    .filter(|payment| payment.status == PaymentStatus::Completed);
+                if let Some(payment) = payment {
+                    // Refunds made through the API have already been logged against the payment
+                    for refund in charge
+                        .refunds
+                        .data
+                        .iter()
+                        .filter(|refund| refund.status.as_ref().map(|s| s == "succeeded").unwrap_or(true))
+                    {
+                        payment.record_external_refund(
+                            &refund.id,
+                            refund.amount,
+                            refund.reason.clone(),
+                            json!(refund),
+                            conn,
+                        )?;
+                    }
+                }
+            }
+            "charge.captured" => {
+                let charge = event.charge()?;
+                if let Some(payment) = self.find_stripe_payment(event, &charge.id, conn)? {
+                    if payment.status == PaymentStatus::Authorized {
+                        payment.mark_complete(raw_data, None, conn)?;
+                    }
+                }
+            }
+            "charge.failed" | "charge.expired" => {
+                let charge = event.charge()?;
+                if let Some(payment) = self.find_stripe_payment(event, &charge.id, conn)? {
+                    // Only payments awaiting capture can still fail, captured payments are refunded instead
+                    if payment.status == PaymentStatus::Authorized || payment.status == PaymentStatus::Requested {
+                        payment.add_ipn(PaymentStatus::Cancelled, raw_data, None, conn)?;
+                    }
+                }
+            }
+            _ => {
+                jlog!(Debug, "Stripe webhook event type not handled", {"id": &event.id, "type": &event.event_type});
+            }
+        }
+
+        Ok(())
+    }
+
+    fn process_mock_event(&self, event: &PaymentProviderEvent, conn: &PgConnection) -> Result<(), ApiError> {
+        let charge = &event.payload["charge"];
+        let charge_id = match charge["id"].as_str() {
+            Some(charge_id) => charge_id,
+            None => return Err(ApplicationError::new("Mock event has no charge".to_string()).into()),
+        };
+        let payment = match Payment::find_by_external_reference(PaymentProviders::Mock, charge_id, conn).optional()? {
+            Some(payment) => payment,
+            None => return Ok(()),
+        };
+        // Delayed captures leave the payment pending until the outcome is delivered
+        let awaiting_capture =
+            payment.status == PaymentStatus::Authorized || payment.status == PaymentStatus::PendingIpn;
+        match event.event_type.as_str() {
+            MOCK_EVENT_CAPTURE_SUCCEEDED if awaiting_capture => payment.mark_complete(charge.clone(), None, conn)?,
+            MOCK_EVENT_CAPTURE_FAILED if awaiting_capture => {
+                payment.add_ipn(PaymentStatus::Cancelled, charge.clone(), None, conn)?
+            }
+            _ => {
+                jlog!(Debug, "Mock payment provider event not handled", {"id": &event.external_id, "type": &event.event_type});
+            }
+        }
+
+        Ok(())
+    }
+
+    fn find_stripe_payment(
+        &self,
+        event: &WebhookEvent,
+        charge_id: &str,
+        conn: &PgConnection,
+    ) -> Result<Option<Payment>, ApiError> {
+        let payment = Payment::find_by_external_reference(PaymentProviders::Stripe, charge_id, conn).optional()?;
+        if payment.is_none() {
+            // Charges made outside of the platform on the same Stripe account
+            jlog!(Debug, "Stripe webhook charge has no payment", {"id": &event.id, "type": &event.event_type, "charge_id": charge_id});
+        }
+        Ok(payment)
+    }
+}
@@ -64,14 +64,24 @@ impl ProcessTransferDripEventExecutor {
                     match payload.source_or_destination {
                         SourceOrDestination::Source => {
                             if let Some(source_email) = source_user.email.clone() {
-                                mailers::tickets::transfer_drip_reminder(
-                                    source_email,
-                                    &transfer,
-                                    &event,
-                                    SourceOrDestination::Source,
-                                    &self.config,
-                                    conn,
-                                )?;
+                                if payload.expiry_reminder {
+                                    mailers::tickets::transfer_expiry_reminder(
+                                        source_email,
+                                        &transfer,
+                                        &event,
+                                        &self.config,
+                                        conn,
+                                    )?;
+                                } else {
+                                    mailers::tickets::transfer_drip_reminder(
+                                        source_email,
+                                        &transfer,
+                                        &event,
+                                        SourceOrDestination::Source,
+                                        &self.config,
+                                        conn,
+                                    )?;
+                                }
                             }
                             transfer.log_drip_domain_event(SourceOrDestination::Source, conn)?;
                         }
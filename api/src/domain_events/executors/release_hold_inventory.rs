@@ -60,6 +60,7 @@ impl ReleaseHoldInventoryExecutor {
                         )
                         .commit(conn)?;
                     }
+                    WaitlistEntry::queue_processing(hold.ticket_type_id, None, conn)?;
                 }
             }
             _ => return Err(ApplicationError::new("Table not supported".to_string()).into()),
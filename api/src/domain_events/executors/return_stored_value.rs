@@ -0,0 +1,61 @@
+use crate::database::Connection;
+use crate::domain_events::executor_future::ExecutorFuture;
+use crate::domain_events::routing::DomainActionExecutor;
+use crate::errors::*;
+use db::prelude::*;
+use futures::future;
+use log::Level::{Error, Info};
+
+pub struct ReturnStoredValueExecutor {}
+
+impl DomainActionExecutor for ReturnStoredValueExecutor {
+    fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
+        match self.perform_job(&action, &conn) {
+            Ok(_) => ExecutorFuture::new(action, conn, Box::pin(future::ok(()))),
+            Err(e) => {
+                jlog!(Error, "Return stored value action failed", {"action_id": action.id, "main_table_id": action.main_table_id, "error": e.to_string()});
+                ExecutorFuture::new(action, conn, Box::pin(future::err(e)))
+            }
+        }
+    }
+}
+
+impl ReturnStoredValueExecutor {
+    pub fn new() -> ReturnStoredValueExecutor {
+        ReturnStoredValueExecutor {}
+    }
+
+    pub fn perform_job(&self, action: &DomainAction, conn: &Connection) -> Result<(), ApiError> {
+        let conn = conn.get();
+        let id = action
+            .main_table_id
+            .clone()
+            .ok_or(ApplicationError::new("No id supplied in the action".to_string()))?;
+
+        match action
+            .main_table
+            .clone()
+            .ok_or(ApplicationError::new("No table supplied in the action".to_string()))?
+        {
+            Tables::Orders => {
+                let mut order = Order::find(id, conn)?;
+                // Paid orders keep their stored value, cancelled payment plans like their collected installments
+                if order.status != OrderStatus::Draft && order.status != OrderStatus::PendingPayment {
+                    return Ok(());
+                }
+                // The cart was extended so check again when it next expires
+                if order.expires_at.is_some() && !order.is_expired() {
+                    order.schedule_stored_value_return(conn)?;
+                    return Ok(());
+                }
+
+                let ledger_entries = order.return_stored_value(None, conn)?;
+                if !ledger_entries.is_empty() {
+                    jlog!(Info, "Returned stored value for expired order", {"order_id": order.id, "ledger_entry_ids": ledger_entries.iter().map(|e| e.id).collect::<Vec<_>>()});
+                }
+            }
+            _ => return Err(ApplicationError::new("Table not supported".to_string()).into()),
+        }
+        Ok(())
+    }
+}
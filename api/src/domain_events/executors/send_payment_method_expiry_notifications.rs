@@ -0,0 +1,54 @@
+use crate::communications::mailers;
+use crate::config::Config;
+use crate::database::Connection;
+use crate::domain_events::executor_future::ExecutorFuture;
+use crate::domain_events::routing::DomainActionExecutor;
+use crate::errors::*;
+use db::prelude::*;
+use futures::future;
+use log::Level::Error;
+
+pub struct SendPaymentMethodExpiryNotificationsExecutor {
+    config: Config,
+}
+
+impl DomainActionExecutor for SendPaymentMethodExpiryNotificationsExecutor {
+    fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
+        match self.perform_job(&conn) {
+            Ok(_) => ExecutorFuture::new(action, conn, Box::pin(future::ok(()))),
+            Err(e) => {
+                jlog!(Error, "Send payment method expiry notifications failed", {"action_id": action.id, "main_table_id": action.main_table_id, "error": e.to_string()});
+                ExecutorFuture::new(action, conn, Box::pin(future::err(e)))
+            }
+        }
+    }
+}
+
+impl SendPaymentMethodExpiryNotificationsExecutor {
+    pub fn new(config: Config) -> SendPaymentMethodExpiryNotificationsExecutor {
+        SendPaymentMethodExpiryNotificationsExecutor { config }
+    }
+
+    pub fn perform_job(&self, conn: &Connection) -> Result<(), ApiError> {
+        let conn = conn.get();
+
+        for payment_method in PaymentMethod::find_default_pending_expiry_notification(conn)? {
+            let user = User::find(payment_method.user_id, conn)?;
+            let email = match user.email.clone() {
+                Some(email) => email,
+                None => continue,
+            };
+
+            mailers::user::payment_method_expiring(&self.config, email, &user, &payment_method, conn)?;
+            payment_method.mark_expiry_notification_sent(conn)?;
+        }
+
+        let next_action = PaymentMethod::create_next_expiry_notification_domain_action(conn);
+        match next_action {
+            Err(err) if err.error_code == ErrorCode::AlreadyScheduledError => Ok(()),
+            r => r,
+        }?;
+
+        Ok(())
+    }
+}
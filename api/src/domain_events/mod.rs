@@ -4,5 +4,7 @@ mod domain_action_monitor;
 mod errors;
 mod executor_future;
 pub mod executors;
+mod listener;
 mod routing;
+pub mod scheduler;
 pub mod webhook_publisher;
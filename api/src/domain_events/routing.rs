@@ -48,16 +48,25 @@ impl DomainActionRouter {
             match action_type {
                 Communication => Box::new(SendCommunicationExecutor::new(conf)),
                 BroadcastPushNotification => Box::new(BroadcastPushNotificationExecutor::new(&conf)),
+                ChargePaymentPlanInstallment => Box::new(ChargePaymentPlanInstallmentExecutor::new(conf)),
+                DeliverWebhook => Box::new(DeliverWebhookExecutor::new(conf)),
+                ExpireTransfer => Box::new(ExpireTransferExecutor::new(conf)),
                 FinalizeSettlements => Box::new(FinalizeSettlementsExecutor::new()),
                 PaymentProviderIPN => Box::new(ProcessPaymentIPNExecutor::new(&conf)),
+                ProcessPaymentProviderEvent => Box::new(ProcessPaymentProviderEventExecutor::new()),
                 RegenerateDripActions => Box::new(RegenerateDripActionsExecutor::new(conf)),
                 ReleaseHoldInventory => Box::new(ReleaseHoldInventoryExecutor::new()),
                 SendPurchaseCompletedCommunication => Box::new(SendOrderCompleteExecutor::new(conf)),
                 UpdateGenres => Box::new(UpdateGenresExecutor::new()),
                 ProcessSettlementReport => Box::new(ProcessSettlementReportExecutor::new(conf)),
                 ProcessTransferDrip => Box::new(ProcessTransferDripEventExecutor::new(conf)),
+                ProcessWaitlist => Box::new(ProcessWaitlistExecutor::new(conf)),
                 RetargetAbandonedOrders => Box::new(RetargetAbandonedOrdersExecutor::new()),
+                ReturnStoredValue => Box::new(ReturnStoredValueExecutor::new()),
                 SendAutomaticReportEmails => Box::new(SendAutomaticReportEmailsExecutor::new(conf)),
+                SendPaymentMethodExpiryNotifications => {
+                    Box::new(SendPaymentMethodExpiryNotificationsExecutor::new(conf))
+                }
                 SubmitSitemapToSearchEngines => Box::new(SubmitSitemapToSearchEnginesExecutor::new(
                     conf.api_base_url.clone(),
                     conf.block_external_comms,
@@ -73,18 +82,36 @@ impl DomainActionRouter {
         self.add_executor(BroadcastPushNotification, find_executor(BroadcastPushNotification))
             .expect("Configuration error");
 
+        self.add_executor(
+            ChargePaymentPlanInstallment,
+            find_executor(ChargePaymentPlanInstallment),
+        )
+        .expect("Configuration error");
+
+        self.add_executor(DeliverWebhook, find_executor(DeliverWebhook))
+            .expect("Configuration error");
+
+        self.add_executor(ExpireTransfer, find_executor(ExpireTransfer))
+            .expect("Configuration error");
+
         self.add_executor(FinalizeSettlements, find_executor(FinalizeSettlements))
             .expect("Configuration error");
 
         self.add_executor(PaymentProviderIPN, find_executor(PaymentProviderIPN))
             .expect("Configuration error");
 
+        self.add_executor(ProcessPaymentProviderEvent, find_executor(ProcessPaymentProviderEvent))
+            .expect("Configuration error");
+
         self.add_executor(ProcessSettlementReport, find_executor(ProcessSettlementReport))
             .expect("Configuration error");
 
         self.add_executor(ProcessTransferDrip, find_executor(ProcessTransferDrip))
             .expect("Configuration error");
 
+        self.add_executor(ProcessWaitlist, find_executor(ProcessWaitlist))
+            .expect("Configuration error");
+
         self.add_executor(RegenerateDripActions, find_executor(RegenerateDripActions))
             .expect("Configuration error");
 
@@ -94,12 +121,21 @@ impl DomainActionRouter {
         self.add_executor(RetargetAbandonedOrders, find_executor(RetargetAbandonedOrders))
             .expect("Configuration error");
 
+        self.add_executor(ReturnStoredValue, find_executor(ReturnStoredValue))
+            .expect("Configuration error");
+
         self.add_executor(UpdateGenres, find_executor(UpdateGenres))
             .expect("Configuration error");
 
         self.add_executor(SendAutomaticReportEmails, find_executor(SendAutomaticReportEmails))
             .expect("Configuration error");
 
+        self.add_executor(
+            SendPaymentMethodExpiryNotifications,
+            find_executor(SendPaymentMethodExpiryNotifications),
+        )
+        .expect("Configuration error");
+
         self.add_executor(
             SendPurchaseCompletedCommunication,
             find_executor(SendPurchaseCompletedCommunication),
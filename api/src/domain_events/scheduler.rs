@@ -0,0 +1,69 @@
+use crate::config::DomainActionSettings;
+use db::prelude::*;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Tracks the actions in flight so that no action type exceeds its concurrency limit and the
+/// monitor as a whole stays within the connection pool
+#[derive(Clone)]
+pub struct DomainActionScheduler {
+    settings: DomainActionSettings,
+    in_flight: Arc<Mutex<HashMap<DomainActionTypes, usize>>>,
+}
+
+/// Held while an action is being processed, the slot is released when dropped
+pub struct DomainActionSlot {
+    domain_action_type: DomainActionTypes,
+    in_flight: Arc<Mutex<HashMap<DomainActionTypes, usize>>>,
+}
+
+impl DomainActionScheduler {
+    pub fn new(settings: DomainActionSettings) -> DomainActionScheduler {
+        DomainActionScheduler {
+            settings,
+            in_flight: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub fn try_reserve(&self, domain_action_type: DomainActionTypes) -> Option<DomainActionSlot> {
+        let mut in_flight = self.in_flight.lock().unwrap();
+        let total: usize = in_flight.values().sum();
+        let count = in_flight.entry(domain_action_type).or_insert(0);
+        if total >= self.settings.max_concurrency || *count >= self.settings.concurrency_for(domain_action_type) {
+            return None;
+        }
+        *count += 1;
+        Some(DomainActionSlot {
+            domain_action_type,
+            in_flight: self.in_flight.clone(),
+        })
+    }
+
+    pub fn in_flight(&self, domain_action_type: DomainActionTypes) -> usize {
+        *self.in_flight.lock().unwrap().get(&domain_action_type).unwrap_or(&0)
+    }
+
+    pub fn available(&self) -> usize {
+        let total: usize = self.in_flight.lock().unwrap().values().sum();
+        self.settings.max_concurrency.saturating_sub(total)
+    }
+
+    pub fn timeout_for(&self, domain_action_type: DomainActionTypes) -> Duration {
+        Duration::from_secs(self.settings.timeout_for(domain_action_type))
+    }
+
+    /// Actions stay checked out a little longer than their timeout so that another process
+    /// does not pick them up while the timeout is being handled
+    pub fn busy_period_for(&self, domain_action_type: DomainActionTypes) -> i64 {
+        self.settings.timeout_for(domain_action_type) as i64 + 5
+    }
+}
+
+impl Drop for DomainActionSlot {
+    fn drop(&mut self) {
+        if let Some(count) = self.in_flight.lock().unwrap().get_mut(&self.domain_action_type) {
+            *count = count.saturating_sub(1);
+        }
+    }
+}
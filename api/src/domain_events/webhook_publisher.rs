@@ -9,6 +9,15 @@ use log::Level::Error;
 use serde_json::Value;
 use std::collections::HashMap;
 
+const ORGANIZATION_WEBHOOK_OMITTED_FIELDS: &[&str] = &[
+    "download_link",
+    "new_receive_tickets_url",
+    "receive_tickets_url",
+    "redemption_code",
+    "refresh_token",
+    "token",
+];
+
 pub struct WebhookPublisher {
     pub front_end_url: String,
     pub token_issuer: DefaultTokenIssuer,
@@ -30,6 +39,13 @@ impl WebhookPublisher {
         conn: &PgConnection,
     ) -> Result<(), DomainActionError> {
         for webhook_payload in self.create_webhook_payloads(&domain_event, conn)? {
+            if domain_event_publisher.is_signed() {
+                let payload = WebhookPublisher::organization_webhook_payload(domain_event, webhook_payload);
+                WebhookDelivery::create(domain_event_publisher.id, domain_event.id, payload.to_string())
+                    .commit(conn)?;
+                continue;
+            }
+
             let mut comms = Communication::new(
                 CommunicationType::Webhook,
                 "Domain Event Webhook".to_string(),
@@ -48,6 +64,24 @@ impl WebhookPublisher {
         Ok(())
     }
 
+    /// Organization webhooks receive the event wrapped in an envelope identifying it, without the
+    /// links and tokens that would give access to the customer's account or tickets
+    pub fn organization_webhook_payload(
+        domain_event: &DomainEvent,
+        mut webhook_payload: HashMap<String, serde_json::Value>,
+    ) -> serde_json::Value {
+        for field in ORGANIZATION_WEBHOOK_OMITTED_FIELDS {
+            webhook_payload.remove(*field);
+        }
+        json!({
+            "id": domain_event.id,
+            "event_type": domain_event.event_type,
+            "organization_id": domain_event.organization_id,
+            "created_at": domain_event.created_at.timestamp(),
+            "data": webhook_payload,
+        })
+    }
+
     pub fn create_webhook_payloads(
         &self,
         domain_event: &DomainEvent,
@@ -264,6 +298,8 @@ impl WebhookPublisher {
         let mut refunded_fees_total = 0;
         let mut discount_total = 0;
         let mut refunded_discount_total = 0;
+        let mut tax_total = 0;
+        let mut refunded_tax_total = 0;
         let mut j_items = Vec::<R>::new();
         for item in order.items(conn)? {
             let item_total = item.unit_price_in_cents * item.quantity;
@@ -278,7 +314,7 @@ impl WebhookPublisher {
             });
 
             match item.item_type {
-                OrderItemTypes::Tickets => {
+                OrderItemTypes::Tickets | OrderItemTypes::ResaleTickets => {
                     count = count + item.quantity - item.refunded_quantity;
                     sub_total = sub_total + item_total;
                     refunded_sub_total = refunded_sub_total + refunded_total;
@@ -291,6 +327,11 @@ impl WebhookPublisher {
                     fees_total = fees_total + item_total;
                     refunded_fees_total = refunded_fees_total + refunded_total;
                 }
+                OrderItemTypes::Tax => {
+                    // Includes taxes that are already part of the item price
+                    tax_total = tax_total + item.tax_in_cents * item.quantity;
+                    refunded_tax_total = refunded_tax_total + item.tax_in_cents * item.refunded_quantity;
+                }
             }
         }
 
@@ -302,6 +343,8 @@ impl WebhookPublisher {
         data.insert("refunded_fees_total".to_string(), json!(refunded_fees_total));
         data.insert("discount_total".to_string(), json!(discount_total));
         data.insert("refunded_discount_total".to_string(), json!(refunded_discount_total));
+        data.insert("tax_total".to_string(), json!(tax_total));
+        data.insert("refunded_tax_total".to_string(), json!(refunded_tax_total));
 
         data.insert(
             "user_id".to_string(),
@@ -17,6 +17,7 @@ use serde_json::Error as SerdeError;
 use sharetribe_flex::ShareTribeError;
 use std::error::Error;
 use std::fmt;
+use stripe::StripeError;
 use tari_client::TariError;
 use twilio::TwilioError;
 use url;
@@ -60,6 +61,7 @@ error_conversion!(reqwest::Error);
 error_conversion!(url::ParseError);
 error_conversion!(ToStrError);
 error_conversion!(ShareTribeError);
+error_conversion!(StripeError);
 
 impl fmt::Display for ApiError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -2,11 +2,34 @@ use crate::errors::{ApiError, ApplicationError, AuthError};
 use crate::jwt::{decode, Validation};
 use crate::server::GetAppState;
 use actix_web::HttpMessage;
-use db::models::AccessToken;
+use db::models::{AccessToken, ApiKey};
+
+/// Credentials presented in a bearer authorization header
+pub(crate) enum BearerCredentials {
+    AccessToken(AccessToken),
+    /// Plain text organization API key, verified against the database by the user extractor
+    ApiKey(String),
+}
+
+impl BearerCredentials {
+    pub fn user_id(&self) -> Option<uuid::Uuid> {
+        match self {
+            BearerCredentials::AccessToken(token) => token.get_id().ok(),
+            BearerCredentials::ApiKey(_) => None,
+        }
+    }
+
+    pub fn api_key_prefix(&self) -> Option<String> {
+        match self {
+            BearerCredentials::AccessToken(_) => None,
+            BearerCredentials::ApiKey(key) => Some(ApiKey::display_prefix(key)),
+        }
+    }
+}
 
 pub(crate) struct AccessTokenExtractor;
 impl AccessTokenExtractor {
-    pub fn from_request<R>(req: &R) -> Result<AccessToken, ApiError>
+    pub fn from_request<R>(req: &R) -> Result<BearerCredentials, ApiError>
     where
         R: HttpMessage + GetAppState,
     {
@@ -20,6 +43,7 @@ impl AccessTokenExtractor {
             }
 
             match parts.next() {
+                Some(api_key) if ApiKey::is_api_key(api_key) => Ok(BearerCredentials::ApiKey(api_key.to_string())),
                 Some(access_token) => {
                     let token = decode::<AccessToken>(
                         &access_token,
@@ -27,7 +51,7 @@ impl AccessTokenExtractor {
                         &Validation::default(),
                     )
                     .map_err(|_| AuthError::unauthorized("Invalid auth token"))?;
-                    Ok(token.claims)
+                    Ok(BearerCredentials::AccessToken(token.claims))
                 }
                 None => Err(AuthError::unauthorized("No access token provided").into()),
             }
@@ -9,11 +9,12 @@ impl FromRequest for RequestInfo {
     type Future = Ready<Result<RequestInfo, Error>>;
 
     fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
-        ok(match req.headers().get("User-Agent") {
-            Some(user_agent_header) => RequestInfo {
-                user_agent: user_agent_header.to_str().ok().map(|ua| ua.to_string()),
-            },
-            None => RequestInfo { user_agent: None },
-        })
+        let user_agent = req
+            .headers()
+            .get("User-Agent")
+            .and_then(|user_agent_header| user_agent_header.to_str().ok())
+            .map(|ua| ua.to_string());
+        let ip_address = req.connection_info().remote().map(|ip| ip.to_string());
+        ok(RequestInfo { user_agent, ip_address })
     }
 }
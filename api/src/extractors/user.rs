@@ -1,9 +1,9 @@
-use super::AccessTokenExtractor;
+use super::{AccessTokenExtractor, BearerCredentials};
 use crate::auth::user::User;
 use crate::errors::{ApiError, AuthError};
-use crate::middleware::RequestConnection;
+use crate::middleware::{ApiKeysAllowed, RequestConnection};
 use actix_web::{dev::Payload, FromRequest, HttpRequest};
-use db::models::User as DbUser;
+use db::models::{ApiKey, User as DbUser, UserSession};
 use futures::future::{err, ready, Ready};
 
 impl FromRequest for User {
@@ -12,8 +12,8 @@ impl FromRequest for User {
     type Future = Ready<Result<User, Self::Error>>;
 
     fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
-        let token = match AccessTokenExtractor::from_request(req) {
-            Ok(token) => token,
+        let credentials = match AccessTokenExtractor::from_request(req) {
+            Ok(credentials) => credentials,
             Err(e) => return err(e),
         };
 
@@ -22,11 +22,35 @@ impl FromRequest for User {
             Err(e) => return err(e),
         };
 
-        let user_id = match token.get_id() {
-            Ok(id) => id,
-            Err(_) => return err(AuthError::unauthorized("Invalid Token").into()),
+        let (user_id, limited_scopes, api_key, session_id) = match credentials {
+            BearerCredentials::AccessToken(token) => match token.get_id() {
+                Ok(id) => (id, token.scopes, None, token.session_id),
+                Err(_) => return err(AuthError::unauthorized("Invalid Token").into()),
+            },
+            BearerCredentials::ApiKey(key) => {
+                // Keys act as the issuing user so they are only honoured where scopes are checked
+                // against the key's organization, see `AllowApiKeys`
+                if req.extensions().get::<ApiKeysAllowed>().is_none() {
+                    return err(AuthError::unauthorized("API keys are not accepted for this endpoint").into());
+                }
+                let api_key = match ApiKey::find_active_by_key(&key, connection.get())
+                    .and_then(|api_key| api_key.record_usage(connection.get()))
+                {
+                    Ok(api_key) => api_key,
+                    Err(_) => return err(AuthError::unauthorized("Invalid API key").into()),
+                };
+                (api_key.user_id, None, Some(api_key), None)
+            }
         };
 
+        // Access tokens stop working as soon as the session they were issued for is revoked
+        if let Some(session_id) = session_id {
+            match UserSession::find(session_id, connection.get()) {
+                Ok(ref session) if session.is_active() && session.user_id == user_id => (),
+                _ => return err(AuthError::unauthorized("Session has been revoked").into()),
+            }
+        }
+
         let (user, is_public_user) = match DbUser::find_for_authentication(user_id, connection.get()) {
             Ok(user) => user,
             Err(_) => return err(AuthError::unauthorized("Invalid Token").into()),
@@ -36,7 +60,8 @@ impl FromRequest for User {
             err(AuthError::unauthorized("User account is disabled").into())
         } else {
             ready(
-                User::new(user, is_public_user, req, token.scopes)
+                User::new(user, is_public_user, req, limited_scopes)
+                    .map(|user| user.with_api_key(api_key).with_session_id(session_id))
                     .map_err(|_| AuthError::unauthorized("User has invalid role data").into()),
             )
         }
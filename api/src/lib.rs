@@ -38,7 +38,7 @@ pub mod extractors;
 pub mod helpers;
 pub mod middleware;
 pub mod models;
-mod payments;
+pub mod payments;
 mod routing;
 mod routing_collectibles;
 pub mod server;
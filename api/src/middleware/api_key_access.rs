@@ -0,0 +1,52 @@
+use actix_service::Service;
+use actix_web::{dev, error, HttpMessage};
+use futures::future::{ok, Ready};
+use std::task::{Context, Poll};
+
+/// Accepts organization API keys on a resource. The user extractor rejects API keys on any
+/// resource not wrapped with this, so it must only wrap handlers that check scopes against the
+/// organization they act on.
+pub struct AllowApiKeys;
+
+/// Request extension marking that the matched resource accepts API keys
+#[derive(Clone, Copy)]
+pub struct ApiKeysAllowed;
+
+impl<S, B> dev::Transform<S> for AllowApiKeys
+where
+    S: Service<Request = dev::ServiceRequest, Response = dev::ServiceResponse<B>, Error = error::Error>,
+{
+    type Request = S::Request;
+    type Response = S::Response;
+    type Error = S::Error;
+    type InitError = ();
+    type Transform = AllowApiKeysService<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(AllowApiKeysService { service })
+    }
+}
+
+pub struct AllowApiKeysService<S> {
+    service: S,
+}
+
+impl<S, B> Service for AllowApiKeysService<S>
+where
+    S: Service<Request = dev::ServiceRequest, Response = dev::ServiceResponse<B>, Error = error::Error>,
+{
+    type Request = S::Request;
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Self::Request) -> Self::Future {
+        request.extensions_mut().insert(ApiKeysAllowed);
+        self.service.call(request)
+    }
+}
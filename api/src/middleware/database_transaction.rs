@@ -33,10 +33,10 @@ impl DatabaseTransaction {
             let connection_object = connection.get();
 
             let transaction_response = match response.response().error() {
-                Some(_) => connection_object
+                Some(_) if !connection.should_commit_on_error() => connection_object
                     .transaction_manager()
                     .rollback_transaction(connection_object),
-                None => connection_object
+                _ => connection_object
                     .transaction_manager()
                     .commit_transaction(connection_object),
             };
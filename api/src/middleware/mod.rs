@@ -1,9 +1,11 @@
+pub use self::api_key_access::*;
 pub use self::api_logger::*;
 pub use self::app_version_header::*;
 pub use self::cache_resource::*;
 pub use self::database_transaction::*;
 pub use self::metatags::*;
 
+mod api_key_access;
 mod api_logger;
 mod app_version_header;
 mod cache_resource;
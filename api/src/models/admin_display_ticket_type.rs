@@ -28,6 +28,9 @@ pub struct AdminDisplayTicketType {
     pub app_sales_enabled: bool,
     pub web_sales_enabled: bool,
     pub box_office_sales_enabled: bool,
+    pub resale_price_cap_percent: Option<i32>,
+    pub installment_count: Option<i32>,
+    pub installment_interval_days: i32,
 }
 
 impl AdminDisplayTicketType {
@@ -80,6 +83,9 @@ impl AdminDisplayTicketType {
             app_sales_enabled: ticket_type.app_sales_enabled,
             web_sales_enabled: ticket_type.web_sales_enabled,
             box_office_sales_enabled: ticket_type.box_office_sales_enabled,
+            resale_price_cap_percent: ticket_type.resale_price_cap_percent,
+            installment_count: ticket_type.installment_count,
+            installment_interval_days: ticket_type.installment_interval_days,
         };
         Ok(result)
     }
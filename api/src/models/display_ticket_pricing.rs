@@ -12,6 +12,8 @@ pub struct DisplayTicketPricing {
     pub start_date: NaiveDateTime,
     pub end_date: NaiveDateTime,
     pub price_in_cents: i64,
+    pub currency: String,
+    pub sold_quantity_cap: Option<i64>,
     pub fee_in_cents: i64,
     pub discount_in_cents: i64,
     pub associated_with_active_orders: bool,
@@ -85,6 +87,8 @@ impl DisplayTicketPricing {
             start_date: ticket_pricing.start_date,
             end_date: ticket_pricing.end_date,
             price_in_cents: ticket_pricing.price_in_cents,
+            currency: ticket_pricing.currency.clone(),
+            sold_quantity_cap: ticket_pricing.sold_quantity_cap,
             fee_in_cents,
             discount_in_cents,
             associated_with_active_orders,
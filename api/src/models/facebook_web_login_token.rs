@@ -1,4 +1,5 @@
 use crate::utils::serializers::default_as_false;
+use db::models::deserialize_unless_blank;
 
 #[derive(Deserialize, Default)]
 pub struct FacebookWebLoginToken {
@@ -13,4 +14,9 @@ pub struct FacebookWebLoginToken {
     // the other fields
     #[serde(rename = "linkToUserId", default = "default_as_false")]
     pub link_to_user_id: bool,
+    // Second factor for users with two-factor authentication, also not a Facebook field
+    #[serde(rename = "totpCode", default, deserialize_with = "deserialize_unless_blank")]
+    pub totp_code: Option<String>,
+    #[serde(rename = "recoveryCode", default, deserialize_with = "deserialize_unless_blank")]
+    pub recovery_code: Option<String>,
 }
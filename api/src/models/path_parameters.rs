@@ -56,3 +56,9 @@ pub struct CompPathParameters {
     pub hold_id: Uuid,
     pub comp_id: Uuid,
 }
+
+#[derive(Deserialize)]
+pub struct BundleTicketTypePathParameters {
+    pub id: Uuid, // Bundle Id
+    pub ticket_type_id: Uuid,
+}
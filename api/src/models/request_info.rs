@@ -1,4 +1,5 @@
 #[derive(Debug, Deserialize, PartialEq, Serialize)]
 pub struct RequestInfo {
     pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
 }
@@ -19,6 +19,7 @@ pub struct UserDisplayTicketType {
     pub redemption_code: Option<String>,
     pub event_id: Uuid,
     pub rank: i32,
+    pub installment_count: Option<i32>,
 }
 
 impl UserDisplayTicketType {
@@ -60,6 +61,7 @@ impl UserDisplayTicketType {
             increment: ticket_type.increment,
             limit_per_person: ticket_type.limit_per_person as u32,
             rank: ticket_type.rank,
+            installment_count: ticket_type.installment_count,
         };
 
         if let Some(ref redemption_code) = redemption_code {
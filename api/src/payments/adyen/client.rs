@@ -0,0 +1,190 @@
+use crate::payments::PaymentProcessorError;
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::HashMap;
+
+const API_VERSION: &str = "v68";
+
+/// Minimal client for the Adyen Checkout API
+#[derive(Clone)]
+pub struct AdyenClient {
+    api_key: String,
+    base_url: String,
+    merchant_account: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Amount {
+    pub currency: String,
+    pub value: i64,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Action {
+    pub url: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentResponse {
+    pub psp_reference: Option<String>,
+    pub result_code: String,
+    pub refusal_reason: Option<String>,
+    pub action: Option<Action>,
+    #[serde(default)]
+    pub additional_data: HashMap<String, String>,
+    #[serde(skip)]
+    pub raw_data: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModificationResponse {
+    pub psp_reference: String,
+    pub status: String,
+    #[serde(skip)]
+    pub raw_data: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ErrorResponse {
+    message: Option<String>,
+}
+
+fn request_error(e: reqwest::Error) -> PaymentProcessorError {
+    PaymentProcessorError {
+        description: "Could not reach the payment processor".to_string(),
+        cause: Some(Box::new(e)),
+        validation_response: None,
+    }
+}
+
+fn parse_response<T: DeserializeOwned>(status: StatusCode, body: String) -> Result<(T, String), PaymentProcessorError> {
+    if !status.is_success() {
+        let message = serde_json::from_str::<ErrorResponse>(&body)
+            .ok()
+            .and_then(|e| e.message)
+            .unwrap_or_else(|| status.to_string());
+        return Err(PaymentProcessorError {
+            description: format!("Payment processor request failed: {}", message),
+            cause: None,
+            validation_response: None,
+        });
+    }
+    let response = serde_json::from_str(&body).map_err(|e| PaymentProcessorError {
+        description: "Could not read the payment processor response".to_string(),
+        cause: Some(Box::new(e)),
+        validation_response: None,
+    })?;
+    Ok((response, body))
+}
+
+impl AdyenClient {
+    pub fn new(api_key: String, base_url: String, merchant_account: String) -> AdyenClient {
+        AdyenClient {
+            api_key,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            merchant_account,
+        }
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/{}/{}", self.base_url, API_VERSION, path)
+    }
+
+    async fn post<T: DeserializeOwned>(&self, path: &str, body: Value) -> Result<(T, String), PaymentProcessorError> {
+        let response = reqwest::Client::new()
+            .post(&self.url(path))
+            .header("X-API-Key", self.api_key.as_str())
+            .json(&body)
+            .send()
+            .await
+            .map_err(request_error)?;
+        let status = response.status();
+        let body = response.text().await.map_err(request_error)?;
+        parse_response(status, body)
+    }
+
+    fn post_blocking<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: Value,
+    ) -> Result<(T, String), PaymentProcessorError> {
+        let response = reqwest::blocking::Client::new()
+            .post(&self.url(path))
+            .header("X-API-Key", self.api_key.as_str())
+            .json(&body)
+            .send()
+            .map_err(request_error)?;
+        let status = response.status();
+        let body = response.text().map_err(request_error)?;
+        parse_response(status, body)
+    }
+
+    fn payment_response((mut response, raw_data): (PaymentResponse, String)) -> PaymentResponse {
+        response.raw_data = raw_data;
+        response
+    }
+
+    fn modification_response((mut response, raw_data): (ModificationResponse, String)) -> ModificationResponse {
+        response.raw_data = raw_data;
+        response
+    }
+
+    pub async fn payments(&self, mut body: Value) -> Result<PaymentResponse, PaymentProcessorError> {
+        body["merchantAccount"] = json!(self.merchant_account);
+        Ok(AdyenClient::payment_response(self.post("payments", body).await?))
+    }
+
+    pub async fn payment_details(&self, redirect_result: &str) -> Result<PaymentResponse, PaymentProcessorError> {
+        let body = json!({ "details": { "redirectResult": redirect_result } });
+        Ok(AdyenClient::payment_response(
+            self.post("payments/details", body).await?,
+        ))
+    }
+
+    pub async fn capture(
+        &self,
+        psp_reference: &str,
+        amount: &Amount,
+    ) -> Result<ModificationResponse, PaymentProcessorError> {
+        let body = json!({ "merchantAccount": self.merchant_account, "amount": amount });
+        Ok(AdyenClient::modification_response(
+            self.post(&format!("payments/{}/captures", psp_reference), body).await?,
+        ))
+    }
+
+    /// Cancels the payment when it has not been captured yet, otherwise refunds it in full
+    pub async fn reversal(&self, psp_reference: &str) -> Result<ModificationResponse, PaymentProcessorError> {
+        let body = json!({ "merchantAccount": self.merchant_account });
+        Ok(AdyenClient::modification_response(
+            self.post(&format!("payments/{}/reversals", psp_reference), body)
+                .await?,
+        ))
+    }
+
+    pub async fn refund(
+        &self,
+        psp_reference: &str,
+        amount: &Amount,
+    ) -> Result<ModificationResponse, PaymentProcessorError> {
+        let body = json!({ "merchantAccount": self.merchant_account, "amount": amount });
+        Ok(AdyenClient::modification_response(
+            self.post(&format!("payments/{}/refunds", psp_reference), body).await?,
+        ))
+    }
+
+    pub fn refund_blocking(
+        &self,
+        psp_reference: &str,
+        amount: &Amount,
+    ) -> Result<ModificationResponse, PaymentProcessorError> {
+        let body = json!({ "merchantAccount": self.merchant_account, "amount": amount });
+        Ok(AdyenClient::modification_response(
+            self.post_blocking(&format!("payments/{}/refunds", psp_reference), body)?,
+        ))
+    }
+}
@@ -0,0 +1,301 @@
+use self::client::*;
+use crate::payments::*;
+use chrono::prelude::*;
+use chrono::Duration;
+use db::models::PaymentProviders;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+mod client;
+
+const CHALLENGE_EXPIRY_MINUTES: i64 = 15;
+// Adyen requires a currency when verifying a card with a zero value authorization
+const CARD_VERIFICATION_CURRENCY: &str = "USD";
+const REFERENCE_SEPARATOR: char = ':';
+const REPEAT_TOKEN_SEPARATOR: char = ':';
+
+/// Charges are referenced as `<pspReference>:<currency>` as Adyen needs the currency of the
+/// payment to refund it
+fn charge_reference(psp_reference: &str, currency: &str) -> String {
+    format!("{}{}{}", psp_reference, REFERENCE_SEPARATOR, currency.to_uppercase())
+}
+
+fn parse_charge_reference(reference: &str) -> Result<(&str, &str), PaymentProcessorError> {
+    let mut parts = reference.splitn(2, REFERENCE_SEPARATOR);
+    match (parts.next(), parts.next()) {
+        (Some(psp_reference), Some(currency)) => Ok((psp_reference, currency)),
+        _ => Err(PaymentProcessorError {
+            description: format!("Invalid charge reference: {}", reference),
+            cause: None,
+            validation_response: None,
+        }),
+    }
+}
+
+fn refusal_error(response: &PaymentResponse) -> PaymentProcessorError {
+    let reason = response.refusal_reason.clone().unwrap_or(response.result_code.clone());
+    let validation_response = match reason.as_str() {
+        "Expired Card" => "Card expired",
+        "CVC Declined" => "Incorrect CVC",
+        "Invalid Card Number" => "Invalid card number",
+        "Not enough balance" => "Balance insufficient",
+        "3D Not Authenticated" | "3DS Authentication Failed" => "Card authentication failed",
+        _ => "Card has been declined",
+    };
+
+    PaymentProcessorError {
+        description: format!("Payment was not authorised: {}", reason),
+        cause: None,
+        validation_response: Some(validation_response.to_string()),
+    }
+}
+
+fn not_supported(description: &str) -> PaymentProcessorError {
+    PaymentProcessorError {
+        description: description.to_string(),
+        cause: None,
+        validation_response: None,
+    }
+}
+
+pub struct AdyenPaymentProcessor {
+    client: AdyenClient,
+}
+
+impl AdyenPaymentProcessor {
+    pub fn new(api_key: String, base_url: String, merchant_account: String) -> AdyenPaymentProcessor {
+        AdyenPaymentProcessor {
+            client: AdyenClient::new(api_key, base_url, merchant_account),
+        }
+    }
+
+    fn refund_result(response: ModificationResponse) -> ChargeAuthResult {
+        ChargeAuthResult {
+            id: response.psp_reference,
+            raw: response.raw_data,
+            challenge: None,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl PaymentProcessor for AdyenPaymentProcessor {
+    fn behavior(&self) -> PaymentProcessorBehavior {
+        PaymentProcessorBehavior::AuthThenComplete(Box::new(AdyenPaymentBehavior {
+            client: self.client.clone(),
+        }))
+    }
+
+    async fn refund(&self, auth_token: &str) -> Result<ChargeAuthResult, PaymentProcessorError> {
+        let (psp_reference, _) = parse_charge_reference(auth_token)?;
+        Ok(AdyenPaymentProcessor::refund_result(
+            self.client.reversal(psp_reference).await?,
+        ))
+    }
+
+    async fn partial_refund(&self, auth_token: &str, amount: i64) -> Result<ChargeAuthResult, PaymentProcessorError> {
+        let (psp_reference, currency) = parse_charge_reference(auth_token)?;
+        let amount = Amount {
+            currency: currency.to_string(),
+            value: amount,
+        };
+        Ok(AdyenPaymentProcessor::refund_result(
+            self.client.refund(psp_reference, &amount).await?,
+        ))
+    }
+
+    fn partial_refund_blocking(
+        &self,
+        auth_token: &str,
+        amount: i64,
+    ) -> Result<ChargeAuthResult, PaymentProcessorError> {
+        let (psp_reference, currency) = parse_charge_reference(auth_token)?;
+        let amount = Amount {
+            currency: currency.to_string(),
+            value: amount,
+        };
+        Ok(AdyenPaymentProcessor::refund_result(
+            self.client.refund_blocking(psp_reference, &amount)?,
+        ))
+    }
+
+    async fn update_metadata(
+        &self,
+        _charge_id: &str,
+        _metadata: Vec<(String, String)>,
+    ) -> Result<UpdateMetadataResult, PaymentProcessorError> {
+        Err(not_supported("Updating metadata is not supported by this gateway"))
+    }
+}
+
+pub struct AdyenPaymentBehavior {
+    client: AdyenClient,
+}
+
+impl AdyenPaymentBehavior {
+    /// Tokens are either the payment method data collected by the Adyen web components or a
+    /// repeat charge token of the form `<shopperReference>:<storedPaymentMethodId>`
+    fn payment_method(token: &str) -> serde_json::Value {
+        match serde_json::from_str::<serde_json::Value>(token) {
+            Ok(payment_method) if payment_method.is_object() => json!({
+                "paymentMethod": payment_method,
+                "shopperInteraction": "Ecommerce",
+            }),
+            _ => {
+                let mut parts = token.splitn(2, REPEAT_TOKEN_SEPARATOR);
+                let shopper_reference = parts.next().unwrap_or("");
+                let stored_payment_method_id = parts.next().unwrap_or(token);
+                json!({
+                    "paymentMethod": { "type": "scheme", "storedPaymentMethodId": stored_payment_method_id },
+                    "shopperReference": shopper_reference,
+                    "shopperInteraction": "ContAuth",
+                    "recurringProcessingModel": "CardOnFile",
+                })
+            }
+        }
+    }
+
+    /// Challenged payments are only assigned a psp reference once the challenge is confirmed and
+    /// are referenced by the given reference until then
+    fn auth_result(
+        response: PaymentResponse,
+        reference: &str,
+        currency: &str,
+    ) -> Result<ChargeAuthResult, PaymentProcessorError> {
+        let psp_reference = response.psp_reference.clone().unwrap_or_else(|| reference.to_string());
+        match response.result_code.as_str() {
+            "Authorised" | "Received" | "Pending" => Ok(ChargeAuthResult {
+                id: charge_reference(&psp_reference, currency),
+                raw: response.raw_data,
+                challenge: None,
+            }),
+            "RedirectShopper" | "ChallengeShopper" | "IdentifyShopper" => {
+                let redirect_url = match response.action.as_ref().and_then(|a| a.url.clone()) {
+                    Some(url) => url,
+                    None => return Err(not_supported("Card authentication challenges must be redirects")),
+                };
+                Ok(ChargeAuthResult {
+                    id: charge_reference(&psp_reference, currency),
+                    raw: response.raw_data,
+                    challenge: Some(ChargeChallenge {
+                        redirect_url,
+                        expires_at: Utc::now().naive_utc() + Duration::minutes(CHALLENGE_EXPIRY_MINUTES),
+                    }),
+                })
+            }
+            _ => Err(refusal_error(&response)),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl AuthThenCompletePaymentBehavior for AdyenPaymentBehavior {
+    fn payment_provider(&self) -> PaymentProviders {
+        PaymentProviders::Adyen
+    }
+
+    async fn create_token_for_repeat_charges(
+        &self,
+        token: &str,
+        description: &str,
+    ) -> Result<RepeatChargeToken, PaymentProcessorError> {
+        let shopper_reference = Uuid::new_v4().to_string();
+        let mut body = AdyenPaymentBehavior::payment_method(token);
+        body["amount"] = json!(Amount {
+            currency: CARD_VERIFICATION_CURRENCY.to_string(),
+            value: 0,
+        });
+        body["reference"] = json!(shopper_reference);
+        body["shopperReference"] = json!(shopper_reference);
+        body["shopperStatement"] = json!(description);
+        body["storePaymentMethod"] = json!(true);
+        body["recurringProcessingModel"] = json!("CardOnFile");
+
+        let response = self.client.payments(body).await?;
+        if response.result_code != "Authorised" {
+            return Err(refusal_error(&response));
+        }
+        let stored_payment_method_id = match response.additional_data.get("recurring.recurringDetailReference") {
+            Some(id) => id.clone(),
+            None => return Err(not_supported("Payment processor did not store the card")),
+        };
+
+        let additional_data = &response.additional_data;
+        let expiry = additional_data.get("expiryDate").map(|e| {
+            e.split('/')
+                .map(|p| p.parse::<i32>().unwrap_or(0))
+                .collect::<Vec<i32>>()
+        });
+        let card = match (additional_data.get("cardSummary"), expiry) {
+            (Some(last_four), Some(expiry)) if expiry.len() == 2 => Some(CardDetails {
+                brand: additional_data
+                    .get("paymentMethod")
+                    .cloned()
+                    .unwrap_or_else(|| "card".to_string()),
+                last_four: last_four.clone(),
+                expiry_month: expiry[0],
+                expiry_year: expiry[1],
+            }),
+            _ => None,
+        };
+
+        Ok(RepeatChargeToken {
+            token: format!(
+                "{}{}{}",
+                shopper_reference, REPEAT_TOKEN_SEPARATOR, stored_payment_method_id
+            ),
+            card,
+            raw: response.raw_data,
+        })
+    }
+
+    async fn auth(
+        &self,
+        token: &str,
+        amount: i64,
+        currency: &str,
+        description: &str,
+        metadata: Vec<(String, String)>,
+        return_url: Option<&str>,
+    ) -> Result<ChargeAuthResult, PaymentProcessorError> {
+        let mut body = AdyenPaymentBehavior::payment_method(token);
+        body["amount"] = json!(Amount {
+            currency: currency.to_uppercase(),
+            value: amount,
+        });
+        let reference = Uuid::new_v4().to_string();
+        body["reference"] = json!(reference);
+        body["shopperStatement"] = json!(description);
+        body["metadata"] = json!(metadata.into_iter().collect::<HashMap<String, String>>());
+        if let Some(return_url) = return_url {
+            body["returnUrl"] = json!(return_url);
+            body["channel"] = json!("Web");
+        }
+
+        let response = self.client.payments(body).await?;
+        AdyenPaymentBehavior::auth_result(response, &reference, currency)
+    }
+
+    async fn confirm_challenge(
+        &self,
+        auth_token: &str,
+        redirect_result: &str,
+    ) -> Result<ChargeAuthResult, PaymentProcessorError> {
+        let (reference, currency) = parse_charge_reference(auth_token)?;
+        let response = self.client.payment_details(redirect_result).await?;
+        let result = AdyenPaymentBehavior::auth_result(response, reference, currency)?;
+        if result.challenge.is_some() {
+            return Err(not_supported("Card authentication requires further challenges"));
+        }
+        Ok(result)
+    }
+
+    async fn complete_authed_charge(&self, auth_token: &str) -> Result<ChargeResult, PaymentProcessorError> {
+        // Payments are captured with the authorisation, as configured on the merchant account
+        Ok(ChargeResult {
+            id: auth_token.to_string(),
+            raw: json!({ "pspReference": parse_charge_reference(auth_token)?.0 }).to_string(),
+            capture_pending: false,
+        })
+    }
+}
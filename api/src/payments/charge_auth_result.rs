@@ -1,6 +1,18 @@
+use chrono::NaiveDateTime;
+
 pub struct ChargeAuthResult {
     pub id: String,
     pub raw: String,
+    /// Set when the issuer requires the shopper to authenticate before the charge can complete
+    pub challenge: Option<ChargeChallenge>,
+}
+
+/// Issuer challenge (e.g. 3D Secure) the shopper is redirected to, they return to the return url
+/// given when authorizing the charge
+#[derive(Clone, Debug, Serialize)]
+pub struct ChargeChallenge {
+    pub redirect_url: String,
+    pub expires_at: NaiveDateTime,
 }
 
 impl ChargeAuthResult {
@@ -2,6 +2,8 @@
 pub struct ChargeResult {
     pub id: String,
     pub raw: String,
+    /// The processor accepted the capture but confirms its outcome later through a provider event
+    pub capture_pending: bool,
 }
 
 impl ChargeResult {
@@ -0,0 +1,367 @@
+use crate::payments::*;
+use chrono::prelude::*;
+use chrono::Duration;
+use db::models::{NewPaymentProviderEvent, PaymentProviderEvent, PaymentProviders};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use uuid::Uuid;
+
+/// Card token that is authorized and captured immediately
+pub const MOCK_TOKEN_SUCCESS: &str = "tok_mock_success";
+/// Card token declined by the issuer
+pub const MOCK_TOKEN_DECLINE: &str = "tok_mock_decline";
+/// Card token declined for insufficient funds
+pub const MOCK_TOKEN_INSUFFICIENT_FUNDS: &str = "tok_mock_insufficient_funds";
+/// Card token requiring a challenge that the shopper passes
+pub const MOCK_TOKEN_CHALLENGE: &str = "tok_mock_challenge";
+/// Card token requiring a challenge that the shopper fails
+pub const MOCK_TOKEN_CHALLENGE_FAILED: &str = "tok_mock_challenge_failed";
+/// Card token whose capture is only confirmed by a later provider event
+pub const MOCK_TOKEN_DELAYED_CAPTURE: &str = "tok_mock_delayed_capture";
+
+/// Results appended to the return url when the mock challenge sends the shopper back
+pub const MOCK_CHALLENGE_PASSED: &str = "mock_challenge_passed";
+pub const MOCK_CHALLENGE_FAILED: &str = "mock_challenge_failed";
+
+/// Provider event types confirming the outcome of a delayed capture
+pub const MOCK_EVENT_CAPTURE_SUCCEEDED: &str = "capture.succeeded";
+pub const MOCK_EVENT_CAPTURE_FAILED: &str = "capture.failed";
+
+const REPEAT_TOKEN_SEPARATOR: char = '|';
+const CHALLENGE_EXPIRY_MINUTES: i64 = 15;
+
+lazy_static! {
+    // Charges are shared by every mock processor in the process, the service locator creates a
+    // new processor for each request
+    static ref CHARGES: Mutex<HashMap<String, MockCharge>> = Mutex::new(HashMap::new());
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum MockScript {
+    Success,
+    Decline,
+    InsufficientFunds,
+    Challenge,
+    ChallengeFailed,
+    DelayedCapture,
+}
+
+impl MockScript {
+    fn for_token(token: &str) -> MockScript {
+        // Repeat charge tokens carry the card token they were created from
+        let card_token = token.rsplit(REPEAT_TOKEN_SEPARATOR).next().unwrap_or(token);
+        match card_token {
+            MOCK_TOKEN_DECLINE => MockScript::Decline,
+            MOCK_TOKEN_INSUFFICIENT_FUNDS => MockScript::InsufficientFunds,
+            MOCK_TOKEN_CHALLENGE => MockScript::Challenge,
+            MOCK_TOKEN_CHALLENGE_FAILED => MockScript::ChallengeFailed,
+            MOCK_TOKEN_DELAYED_CAPTURE => MockScript::DelayedCapture,
+            _ => MockScript::Success,
+        }
+    }
+
+    fn decline_reason(&self) -> Option<&'static str> {
+        match self {
+            MockScript::Decline => Some("Card has been declined"),
+            MockScript::InsufficientFunds => Some("Balance insufficient"),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum MockChargeStatus {
+    ChallengeRequired,
+    Authorized,
+    CapturePending,
+    Captured,
+    Cancelled,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct MockCharge {
+    id: String,
+    amount: i64,
+    currency: String,
+    description: String,
+    status: MockChargeStatus,
+    amount_refunded: i64,
+    delayed_capture: bool,
+    metadata: Vec<(String, String)>,
+}
+
+impl MockCharge {
+    fn to_raw(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|_| "{}".to_string())
+    }
+
+    fn auth_result(&self, challenge: Option<ChargeChallenge>) -> ChargeAuthResult {
+        ChargeAuthResult {
+            id: self.id.clone(),
+            raw: self.to_raw(),
+            challenge,
+        }
+    }
+}
+
+fn mock_error(description: &str, validation_response: Option<&str>) -> PaymentProcessorError {
+    PaymentProcessorError {
+        description: description.to_string(),
+        cause: None,
+        validation_response: validation_response.map(|v| v.to_string()),
+    }
+}
+
+fn mock_id(prefix: &str) -> String {
+    format!("{}_mock_{}", prefix, Uuid::new_v4().to_string().replace("-", ""))
+}
+
+fn update_charge<T>(
+    id: &str,
+    update: impl FnOnce(&mut MockCharge) -> Result<T, PaymentProcessorError>,
+) -> Result<T, PaymentProcessorError> {
+    let mut charges = CHARGES
+        .lock()
+        .map_err(|_| mock_error("Mock charges are unavailable", None))?;
+    match charges.get_mut(id) {
+        Some(charge) => update(charge),
+        None => Err(mock_error(&format!("No such charge: {}", id), None)),
+    }
+}
+
+/// In-process processor for development and tests. Outcomes are scripted by the card token
+/// charged, see the `MOCK_TOKEN_*` constants; any other token is charged successfully.
+pub struct MockPaymentProcessor {}
+
+impl MockPaymentProcessor {
+    pub fn new() -> MockPaymentProcessor {
+        MockPaymentProcessor {}
+    }
+
+    /// Confirms the outcome of a delayed capture, returning the provider event the processor would
+    /// deliver so that it can be processed like any other provider event
+    pub fn settle_delayed_capture(
+        charge_id: &str,
+        succeeded: bool,
+    ) -> Result<NewPaymentProviderEvent, PaymentProcessorError> {
+        let charge = update_charge(charge_id, |charge| {
+            if charge.status != MockChargeStatus::CapturePending {
+                return Err(mock_error("Charge does not have a pending capture", None));
+            }
+            charge.status = if succeeded {
+                MockChargeStatus::Captured
+            } else {
+                MockChargeStatus::Cancelled
+            };
+            Ok(charge.clone())
+        })?;
+
+        let event_type = if succeeded {
+            MOCK_EVENT_CAPTURE_SUCCEEDED
+        } else {
+            MOCK_EVENT_CAPTURE_FAILED
+        };
+        Ok(PaymentProviderEvent::create(
+            PaymentProviders::Mock,
+            mock_id("evt"),
+            event_type.to_string(),
+            json!({ "charge": charge }),
+        ))
+    }
+
+    fn refund_charge(auth_token: &str, amount: Option<i64>) -> Result<ChargeAuthResult, PaymentProcessorError> {
+        let charge = update_charge(auth_token, |charge| {
+            match charge.status {
+                // Refunding an uncaptured charge releases the authorization
+                MockChargeStatus::ChallengeRequired | MockChargeStatus::Authorized => {
+                    charge.status = MockChargeStatus::Cancelled;
+                }
+                MockChargeStatus::Captured | MockChargeStatus::CapturePending => {
+                    let amount = amount.unwrap_or(charge.amount - charge.amount_refunded);
+                    if amount <= 0 || charge.amount_refunded + amount > charge.amount {
+                        return Err(mock_error("Refund amount exceeds the amount charged", None));
+                    }
+                    charge.amount_refunded += amount;
+                }
+                MockChargeStatus::Cancelled => return Err(mock_error("Charge has been cancelled", None)),
+            }
+            Ok(charge.clone())
+        })?;
+
+        Ok(ChargeAuthResult {
+            id: mock_id("re"),
+            raw: charge.to_raw(),
+            challenge: None,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl PaymentProcessor for MockPaymentProcessor {
+    fn behavior(&self) -> PaymentProcessorBehavior {
+        PaymentProcessorBehavior::AuthThenComplete(Box::new(MockPaymentBehavior {}))
+    }
+
+    async fn refund(&self, auth_token: &str) -> Result<ChargeAuthResult, PaymentProcessorError> {
+        MockPaymentProcessor::refund_charge(auth_token, None)
+    }
+
+    async fn partial_refund(&self, auth_token: &str, amount: i64) -> Result<ChargeAuthResult, PaymentProcessorError> {
+        MockPaymentProcessor::refund_charge(auth_token, Some(amount))
+    }
+
+    fn partial_refund_blocking(
+        &self,
+        auth_token: &str,
+        amount: i64,
+    ) -> Result<ChargeAuthResult, PaymentProcessorError> {
+        MockPaymentProcessor::refund_charge(auth_token, Some(amount))
+    }
+
+    async fn update_metadata(
+        &self,
+        charge_id: &str,
+        metadata: Vec<(String, String)>,
+    ) -> Result<UpdateMetadataResult, PaymentProcessorError> {
+        let charge = update_charge(charge_id, |charge| {
+            charge.metadata = metadata;
+            Ok(charge.clone())
+        })?;
+        Ok(UpdateMetadataResult {
+            id: charge.id.clone(),
+            raw: charge.to_raw(),
+        })
+    }
+}
+
+pub struct MockPaymentBehavior {}
+
+#[async_trait::async_trait]
+impl AuthThenCompletePaymentBehavior for MockPaymentBehavior {
+    fn payment_provider(&self) -> PaymentProviders {
+        PaymentProviders::Mock
+    }
+
+    async fn create_token_for_repeat_charges(
+        &self,
+        token: &str,
+        description: &str,
+    ) -> Result<RepeatChargeToken, PaymentProcessorError> {
+        let script = MockScript::for_token(token);
+        if let Some(reason) = script.decline_reason() {
+            return Err(mock_error(reason, Some(reason)));
+        }
+
+        let repeat_token = format!("{}{}{}", mock_id("cus"), REPEAT_TOKEN_SEPARATOR, token);
+        let expiry_year = Utc::now().year() + 3;
+        Ok(RepeatChargeToken {
+            raw: json!({ "id": &repeat_token, "description": description }).to_string(),
+            token: repeat_token,
+            card: Some(CardDetails {
+                brand: "Visa".to_string(),
+                last_four: "4242".to_string(),
+                expiry_month: 12,
+                expiry_year,
+            }),
+        })
+    }
+
+    async fn auth(
+        &self,
+        token: &str,
+        amount: i64,
+        currency: &str,
+        description: &str,
+        metadata: Vec<(String, String)>,
+        return_url: Option<&str>,
+    ) -> Result<ChargeAuthResult, PaymentProcessorError> {
+        let script = MockScript::for_token(token);
+        if let Some(reason) = script.decline_reason() {
+            return Err(mock_error(reason, Some(reason)));
+        }
+
+        let challenge_result = match script {
+            MockScript::Challenge => Some(MOCK_CHALLENGE_PASSED),
+            MockScript::ChallengeFailed => Some(MOCK_CHALLENGE_FAILED),
+            _ => None,
+        };
+        let challenge = match (challenge_result, return_url) {
+            (Some(result), Some(return_url)) => {
+                let separator = if return_url.contains('?') { '&' } else { '?' };
+                Some(ChargeChallenge {
+                    redirect_url: format!("{}{}redirectResult={}", return_url, separator, result),
+                    expires_at: Utc::now().naive_utc() + Duration::minutes(CHALLENGE_EXPIRY_MINUTES),
+                })
+            }
+            // Charges made without the shopper present cannot be challenged
+            (Some(_), None) => {
+                return Err(mock_error(
+                    "Card requires authentication",
+                    Some("Card requires authentication"),
+                ))
+            }
+            (None, _) => None,
+        };
+
+        let charge = MockCharge {
+            id: mock_id("ch"),
+            amount,
+            currency: currency.to_string(),
+            description: description.to_string(),
+            status: if challenge.is_some() {
+                MockChargeStatus::ChallengeRequired
+            } else {
+                MockChargeStatus::Authorized
+            },
+            amount_refunded: 0,
+            delayed_capture: script == MockScript::DelayedCapture,
+            metadata,
+        };
+        let result = charge.auth_result(challenge);
+        CHARGES
+            .lock()
+            .map_err(|_| mock_error("Mock charges are unavailable", None))?
+            .insert(charge.id.clone(), charge);
+        Ok(result)
+    }
+
+    async fn confirm_challenge(
+        &self,
+        auth_token: &str,
+        redirect_result: &str,
+    ) -> Result<ChargeAuthResult, PaymentProcessorError> {
+        update_charge(auth_token, |charge| {
+            if charge.status != MockChargeStatus::ChallengeRequired {
+                return Err(mock_error("Charge is not awaiting a challenge", None));
+            }
+            if redirect_result != MOCK_CHALLENGE_PASSED {
+                charge.status = MockChargeStatus::Cancelled;
+                return Err(mock_error(
+                    "Card authentication failed",
+                    Some("Card authentication failed"),
+                ));
+            }
+            charge.status = MockChargeStatus::Authorized;
+            Ok(charge.auth_result(None))
+        })
+    }
+
+    async fn complete_authed_charge(&self, auth_token: &str) -> Result<ChargeResult, PaymentProcessorError> {
+        update_charge(auth_token, |charge| {
+            if charge.status != MockChargeStatus::Authorized {
+                return Err(mock_error("Charge is not authorized", None));
+            }
+            charge.status = if charge.delayed_capture {
+                MockChargeStatus::CapturePending
+            } else {
+                MockChargeStatus::Captured
+            };
+            Ok(ChargeResult {
+                id: charge.id.clone(),
+                raw: charge.to_raw(),
+                capture_pending: charge.delayed_capture,
+            })
+        })
+    }
+}
@@ -5,9 +5,11 @@ pub use self::payment_processor_error::*;
 pub use self::repeat_charge_token::*;
 pub use self::update_metadata_result::*;
 
+pub mod adyen;
 mod charge_auth_result;
 mod charge_result;
 pub mod globee;
+pub mod mock;
 pub mod payment_processor;
 mod payment_processor_error;
 mod repeat_charge_token;
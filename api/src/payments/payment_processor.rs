@@ -18,13 +18,6 @@ pub trait AuthThenCompletePaymentBehavior {
         description: &str,
     ) -> Result<RepeatChargeToken, PaymentProcessorError>;
 
-    async fn update_repeat_token(
-        &self,
-        repeat_token: &str,
-        token: &str,
-        description: &str,
-    ) -> Result<RepeatChargeToken, PaymentProcessorError>;
-
     async fn auth(
         &self,
         token: &str,
@@ -32,8 +25,23 @@ pub trait AuthThenCompletePaymentBehavior {
         currency: &str,
         description: &str,
         metadata: Vec<(String, String)>,
+        return_url: Option<&str>,
     ) -> Result<ChargeAuthResult, PaymentProcessorError>;
 
+    /// Finishes an auth that returned a challenge using the result the processor appended to the
+    /// return url when sending the shopper back
+    async fn confirm_challenge(
+        &self,
+        _auth_token: &str,
+        _redirect_result: &str,
+    ) -> Result<ChargeAuthResult, PaymentProcessorError> {
+        Err(PaymentProcessorError {
+            description: "Card authentication challenges are not supported by this gateway".to_string(),
+            cause: None,
+            validation_response: None,
+        })
+    }
+
     async fn complete_authed_charge(&self, auth_token: &str) -> Result<ChargeResult, PaymentProcessorError>;
 }
 
@@ -43,6 +51,7 @@ pub trait RedirectToPaymentPageBehavior {
     async fn create_payment_request(
         &self,
         total: f64,
+        currency: &str,
         email: String,
         order_id: Uuid,
         ipn_url: Option<String>,
@@ -1,8 +1,17 @@
 pub struct RepeatChargeToken {
     pub token: String,
+    pub card: Option<CardDetails>,
     pub raw: String,
 }
 
+/// Display details of the card a repeat charge token charges
+pub struct CardDetails {
+    pub brand: String,
+    pub last_four: String,
+    pub expiry_month: i32,
+    pub expiry_year: i32,
+}
+
 use serde_json::Error as SerdeError;
 impl RepeatChargeToken {
     pub fn to_json(&self) -> Result<serde_json::Value, SerdeError> {
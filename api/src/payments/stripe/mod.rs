@@ -75,6 +75,7 @@ impl PaymentProcessor for StripePaymentProcessor {
         Ok(self.client.refund(auth_token).await.map(|r| ChargeAuthResult {
             id: r.id,
             raw: r.raw_data,
+            challenge: None,
         })?)
     }
 
@@ -86,6 +87,7 @@ impl PaymentProcessor for StripePaymentProcessor {
             .map(|r| ChargeAuthResult {
                 id: r.id,
                 raw: r.raw_data,
+                challenge: None,
             })?)
     }
 
@@ -100,6 +102,7 @@ impl PaymentProcessor for StripePaymentProcessor {
             .map(|r| ChargeAuthResult {
                 id: r.id,
                 raw: r.raw_data,
+                challenge: None,
             })?)
     }
 }
@@ -114,30 +117,21 @@ impl<'a> AuthThenCompletePaymentBehavior for StripePaymentBehavior {
         token: &str,
         description: &str,
     ) -> Result<RepeatChargeToken, PaymentProcessorError> {
-        Ok(self
+        let customer = self
             .client
             .create_customer(description, token, Vec::<(String, String)>::new())
-            .await
-            .map(|r| RepeatChargeToken {
-                token: r.id,
-                raw: r.raw_data,
-            })?)
-    }
-
-    async fn update_repeat_token(
-        &self,
-        repeat_token: &str,
-        token: &str,
-        description: &str,
-    ) -> Result<RepeatChargeToken, PaymentProcessorError> {
-        Ok(self
-            .client
-            .update_customer(repeat_token, description, token, Vec::<(String, String)>::new())
-            .await
-            .map(|r| RepeatChargeToken {
-                token: r.id,
-                raw: r.raw_data,
-            })?)
+            .await?;
+        let card = customer.default_card()?.map(|card| CardDetails {
+            brand: card.brand,
+            last_four: card.last4,
+            expiry_month: card.exp_month as i32,
+            expiry_year: card.exp_year as i32,
+        });
+        Ok(RepeatChargeToken {
+            token: customer.id,
+            card,
+            raw: customer.raw_data,
+        })
     }
 
     async fn auth(
@@ -147,6 +141,7 @@ impl<'a> AuthThenCompletePaymentBehavior for StripePaymentBehavior {
         currency: &str,
         description: &str,
         metadata: Vec<(String, String)>,
+        _return_url: Option<&str>,
     ) -> Result<ChargeAuthResult, PaymentProcessorError> {
         Ok(self
             .client
@@ -155,6 +150,7 @@ impl<'a> AuthThenCompletePaymentBehavior for StripePaymentBehavior {
             .map(|r| ChargeAuthResult {
                 id: r.id,
                 raw: r.raw_data,
+                challenge: None,
             })?)
     }
 
@@ -162,6 +158,7 @@ impl<'a> AuthThenCompletePaymentBehavior for StripePaymentBehavior {
         Ok(self.client.complete(_auth_token).await.map(|r| ChargeResult {
             id: r.id,
             raw: r.raw_data,
+            capture_pending: false,
         })?)
     }
 }
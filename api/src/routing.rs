@@ -1,5 +1,5 @@
 use crate::controllers::*;
-use crate::middleware::{CacheResource, CacheUsersBy, OrganizationLoad};
+use crate::middleware::{AllowApiKeys, CacheResource, CacheUsersBy, OrganizationLoad};
 use actix_web::web;
 use db::models::Scopes;
 
@@ -7,6 +7,21 @@ pub fn routes(app: &mut web::ServiceConfig) {
     // Please try to keep in alphabetical order
 
     app.service(
+        web::resource("/admin/communication_templates/preview")
+            .route(web::post().to(admin::communication_templates::preview)),
+    )
+    .service(
+        web::resource("/admin/communication_templates")
+            .route(web::get().to(admin::communication_templates::index))
+            .route(web::post().to(admin::communication_templates::create)),
+    )
+    .service(
+        web::resource("/admin/domain_actions/dead_letter").route(web::get().to(admin::domain_actions::dead_letter)),
+    )
+    .service(web::resource("/admin/domain_actions/metrics").route(web::get().to(admin::domain_actions::metrics)))
+    .service(web::resource("/admin/domain_actions/{id}/cancel").route(web::post().to(admin::domain_actions::cancel)))
+    .service(web::resource("/admin/domain_actions/{id}/requeue").route(web::post().to(admin::domain_actions::requeue)))
+    .service(
         web::resource("/admin/stuck_domain_actions").route(web::get().to(admin::admin::admin_stuck_domain_actions)),
     )
     .service(web::resource("/admin/ticket_count").route(web::get().to(admin::admin::admin_ticket_count)))
@@ -25,6 +40,8 @@ pub fn routes(app: &mut web::ServiceConfig) {
             .route(web::get().to(announcements::index))
             .route(web::post().to(announcements::create)),
     )
+    .service(web::resource("/api_keys/{id}").route(web::delete().to(api_keys::destroy)))
+    .service(web::resource("/api_keys/{id}/rotate").route(web::post().to(api_keys::rotate)))
     .service(
         web::resource("/artists/search")
             .wrap(CacheResource::new(CacheUsersBy::AnonymousOnly))
@@ -52,6 +69,17 @@ pub fn routes(app: &mut web::ServiceConfig) {
             .route(web::delete().to(broadcasts::delete)),
     )
     .service(web::resource("/broadcasts/{id}/tracking_count").route(web::post().to(broadcasts::tracking_count)))
+    .service(
+        web::resource("/bundles/{id}")
+            .route(web::get().to(bundles::show))
+            .route(web::put().to(bundles::update))
+            .route(web::delete().to(bundles::destroy)),
+    )
+    .service(web::resource("/bundles/{id}/ticket_types").route(web::post().to(bundles::add_ticket_type)))
+    .service(
+        web::resource("/bundles/{id}/ticket_types/{ticket_type_id}")
+            .route(web::delete().to(bundles::remove_ticket_type)),
+    )
     .service(
         web::resource("/cart")
             .route(web::delete().to(cart::destroy))
@@ -60,8 +88,11 @@ pub fn routes(app: &mut web::ServiceConfig) {
             .route(web::get().to(cart::show)),
     )
     .service(web::resource("/cart/{id}/duplicate").route(web::post().to(cart::duplicate)))
+    .service(web::resource("/cart/bundles").route(web::post().to(cart::update_bundle)))
     .service(web::resource("/cart/clear_invalid_items").route(web::delete().to(cart::clear_invalid_items)))
     .service(web::resource("/cart/checkout").route(web::post().to(cart::checkout)))
+    .service(web::resource("/cart/listings").route(web::post().to(cart::add_listing)))
+    .service(web::resource("/cart/seats").route(web::post().to(cart::reserve_seats)))
     .service(web::resource("/codes/{id}/link").route(web::get().to(codes::link)))
     .service(
         web::resource("/codes/{id}")
@@ -69,6 +100,18 @@ pub fn routes(app: &mut web::ServiceConfig) {
             .route(web::put().to(codes::update))
             .route(web::delete().to(codes::destroy)),
     )
+    .service(
+        web::resource("/communication_templates/{id}/rollback")
+            .route(web::post().to(communication_templates::rollback)),
+    )
+    .service(
+        web::resource("/communication_templates/{id}/versions").route(web::get().to(communication_templates::versions)),
+    )
+    .service(
+        web::resource("/communication_templates/{id}")
+            .route(web::get().to(communication_templates::show))
+            .route(web::delete().to(communication_templates::destroy)),
+    )
     .service(
         web::resource("/comps/{id}")
             .route(web::get().to(comps::show))
@@ -76,6 +119,12 @@ pub fn routes(app: &mut web::ServiceConfig) {
             .route(web::delete().to(comps::destroy)),
     )
     .service(web::resource("/event_report_subscribers/{id}").route(web::delete().to(event_report_subscribers::destroy)))
+    .service(
+        web::resource("/event_series/{id}")
+            .route(web::get().to(event_series::show))
+            .route(web::put().to(event_series::update)),
+    )
+    .service(web::resource("/event_series/{id}/events").route(web::put().to(event_series::update_events)))
     .service(
         web::resource("/events")
         // In future it may be better to cache this for every user to save the database hit
@@ -102,13 +151,23 @@ pub fn routes(app: &mut web::ServiceConfig) {
     .service(web::resource("/events/{id}/clone").route(web::post().to(events::clone)))
     .service(
         web::resource("/events/{id}/codes")
+            .wrap(AllowApiKeys)
             .route(web::get().to(events::codes))
             .route(web::post().to(codes::create)),
     )
-    .service(web::resource("/events/{id}/dashboard").route(web::get().to(events::dashboard)))
-    .service(web::resource("/events/{id}/guests").route(web::get().to(events::guest_list)))
+    .service(
+        web::resource("/events/{id}/dashboard")
+            .wrap(AllowApiKeys)
+            .route(web::get().to(events::dashboard)),
+    )
+    .service(
+        web::resource("/events/{id}/guests")
+            .wrap(AllowApiKeys)
+            .route(web::get().to(events::guest_list)),
+    )
     .service(
         web::resource("/events/{id}/holds")
+            .wrap(AllowApiKeys)
             .route(web::post().to(holds::create))
             .route(web::get().to(events::holds)),
     )
@@ -118,7 +177,17 @@ pub fn routes(app: &mut web::ServiceConfig) {
             .route(web::post().to(events::add_interest))
             .route(web::delete().to(events::remove_interest)),
     )
-    .service(web::resource("/events/{id}/publish").route(web::post().to(events::publish)))
+    .service(web::resource("/events/{id}/listings").route(web::get().to(listings::index)))
+    .service(
+        web::resource("/events/{id}/offline_redemptions")
+            .wrap(AllowApiKeys)
+            .route(web::post().to(events::redeem_offline_tickets)),
+    )
+    .service(
+        web::resource("/events/{id}/publish")
+            .wrap(AllowApiKeys)
+            .route(web::post().to(events::publish)),
+    )
     .service(
         web::resource("/events/{id}/broadcasts")
             .route(web::post().to(broadcasts::create))
@@ -127,26 +196,59 @@ pub fn routes(app: &mut web::ServiceConfig) {
     )
     .service(web::resource("/events/{id}/links").route(web::post().to(events::create_link)))
     .service(web::resource("/events/{id}/rarities").route(web::post().to(rarities::create)))
-    .service(web::resource("/events/{id}/redeem/{ticket_instance_id}").route(web::post().to(events::redeem_ticket)))
-    .service(web::resource("/events/{id}/redeem").route(web::post().to(events::redeem_ticket)))
+    .service(
+        web::resource("/events/{id}/redeem/{ticket_instance_id}")
+            .wrap(AllowApiKeys)
+            .route(web::post().to(events::redeem_ticket)),
+    )
+    .service(
+        web::resource("/events/{id}/redeem")
+            .wrap(AllowApiKeys)
+            .route(web::post().to(events::redeem_ticket)),
+    )
     .service(
         web::resource("/events/{id}/report_subscribers")
             .route(web::get().to(event_report_subscribers::index))
             .route(web::post().to(event_report_subscribers::create)),
     )
+    .service(
+        web::resource("/events/{id}/scanner_manifest")
+            .wrap(AllowApiKeys)
+            .route(web::get().to(events::scanner_manifest)),
+    )
     .service(web::resource("/events/{id}/tickets").route(web::get().to(tickets::index)))
     .service(
         web::resource("/events/{id}/ticket_types")
+            .wrap(AllowApiKeys)
             .route(web::get().to(ticket_types::index))
             .route(web::post().to(ticket_types::create)),
     )
-    .service(web::resource("/events/{id}/ticket_types/multiple").route(web::post().to(ticket_types::create_multiple)))
+    .service(
+        web::resource("/events/{id}/ticket_types/multiple")
+            .wrap(AllowApiKeys)
+            .route(web::post().to(ticket_types::create_multiple)),
+    )
     .service(
         web::resource("/events/{event_id}/ticket_types/{ticket_type_id}")
+            .wrap(AllowApiKeys)
             .route(web::patch().to(ticket_types::update))
             .route(web::delete().to(ticket_types::cancel)),
     )
-    .service(web::resource("/events/{id}/unpublish").route(web::post().to(events::unpublish)))
+    .service(
+        web::resource("/events/{event_id}/ticket_types/{ticket_type_id}/seats")
+            .route(web::post().to(ticket_types::assign_seats)),
+    )
+    .service(
+        web::resource("/events/{event_id}/ticket_types/{ticket_type_id}/waitlist")
+            .route(web::get().to(waitlist_entries::index))
+            .route(web::post().to(waitlist_entries::create))
+            .route(web::delete().to(waitlist_entries::destroy)),
+    )
+    .service(
+        web::resource("/events/{id}/unpublish")
+            .wrap(AllowApiKeys)
+            .route(web::post().to(events::unpublish)),
+    )
     .service(web::resource("/events/{id}/users").route(web::get().to(events::users)))
     .service(web::resource("/events/{id}/users/invites").route(web::post().to(organization_invites::create_for_event)))
     .service(
@@ -159,14 +261,23 @@ pub fn routes(app: &mut web::ServiceConfig) {
     .service(web::resource("/external/facebook/web_login").route(web::post().to(external::facebook::web_login)))
     .service(web::resource("/external/facebook/scopes").route(web::get().to(external::facebook::scopes)))
     .service(web::resource("/external/facebook").route(web::delete().to(external::facebook::disconnect)))
+    .service(web::resource("/external/oidc/authorize").route(web::post().to(external::oidc::authorize)))
+    .service(web::resource("/external/oidc/callback").route(web::post().to(external::oidc::callback)))
     .service(
         web::resource("/genres")
             .wrap(CacheResource::new(CacheUsersBy::None))
             .route(web::get().to(genres::index)),
     )
+    .service(web::resource("/gift_cards/balance").route(web::post().to(gift_cards::balance)))
+    .service(
+        web::resource("/gift_cards/{id}")
+            .route(web::get().to(gift_cards::show))
+            .route(web::delete().to(gift_cards::destroy)),
+    )
     .service(web::resource("/invitations/{id}").route(web::get().to(organization_invites::view)))
     .service(web::resource("/invitations").route(web::post().to(organization_invites::accept_request)))
     .service(web::resource("/ipns/globee").route(web::post().to(ipns::globee)))
+    .service(web::resource("/ipns/stripe").route(web::post().to(ipns::stripe)))
     .service(
         web::resource("/holds/{id}/comps")
             .route(web::get().to(comps::index))
@@ -177,11 +288,17 @@ pub fn routes(app: &mut web::ServiceConfig) {
     .service(web::resource("/holds/{id}/link").route(web::get().to(holds::link)))
     .service(
         web::resource("/holds/{id}")
+            .wrap(AllowApiKeys)
             .route(web::patch().to(holds::update))
             .route(web::get().to(holds::show))
             .route(web::delete().to(holds::destroy)),
     )
-    .service(web::resource("/listings").route(web::post().to(listings::create)))
+    .service(
+        web::resource("/listings")
+            .route(web::get().to(listings::index_for_user))
+            .route(web::post().to(listings::create)),
+    )
+    .service(web::resource("/listings/{id}").route(web::delete().to(listings::destroy)))
     .service(web::resource("/listings/{id}/publish").route(web::post().to(listings::publish)))
     .service(web::resource("/notes/{id}").route(web::delete().to(notes::destroy)))
     .service(
@@ -192,6 +309,7 @@ pub fn routes(app: &mut web::ServiceConfig) {
     .service(web::resource("/orders").route(web::get().to(orders::index)))
     .service(web::resource("/orders/{id}/activity").route(web::get().to(orders::activity)))
     .service(web::resource("/orders/{id}/details").route(web::get().to(orders::details)))
+    .service(web::resource("/orders/{id}/payment_plan").route(web::get().to(orders::payment_plan)))
     .service(web::resource("/orders/{id}/refund").route(web::patch().to(orders::refund)))
     .service(web::resource("/orders/{id}/resend_confirmation").route(web::post().to(orders::resend_confirmation)))
     .service(
@@ -201,6 +319,11 @@ pub fn routes(app: &mut web::ServiceConfig) {
     .service(web::resource("/orders/{id}/tickets").route(web::get().to(orders::tickets)))
     .service(web::resource("/orders/{id}/transfers").route(web::get().to(transfers::index)))
     .service(web::resource("/orders/{id}").route(web::get().to(orders::show)))
+    .service(
+        web::resource("/organization_roles/{id}")
+            .route(web::put().to(organization_roles::update))
+            .route(web::delete().to(organization_roles::destroy)),
+    )
     .service(
         web::resource("/organization_venues/{id}")
             .route(web::get().to(organization_venues::show))
@@ -209,19 +332,43 @@ pub fn routes(app: &mut web::ServiceConfig) {
     .service(
         web::resource("/organizations/{id}/announcements").route(web::get().to(announcements::show_from_organization)),
     )
+    .service(
+        web::resource("/organizations/{id}/api_keys")
+            .route(web::get().to(api_keys::index))
+            .route(web::post().to(api_keys::create)),
+    )
     .service(
         web::resource("/organizations/{id}/artists")
             .route(web::get().to(artists::show_from_organizations))
             .route(web::post().to(organizations::add_artist)),
     )
-    .service(web::resource("/organizations/{id}/events").route(web::get().to(events::show_from_organizations)))
-    .service(web::resource("/organizations/{id}/export_event_data").route(web::get().to(events::export_event_data)))
+    .service(
+        web::resource("/organizations/{id}/bundles")
+            .route(web::get().to(bundles::index))
+            .route(web::post().to(bundles::create)),
+    )
+    .service(
+        web::resource("/organizations/{id}/event_series")
+            .route(web::get().to(event_series::index))
+            .route(web::post().to(event_series::create)),
+    )
+    .service(
+        web::resource("/organizations/{id}/events")
+            .wrap(AllowApiKeys)
+            .route(web::get().to(events::show_from_organizations)),
+    )
+    .service(
+        web::resource("/organizations/{id}/export_event_data")
+            .wrap(AllowApiKeys)
+            .route(web::get().to(events::export_event_data)),
+    )
     .service(
         web::resource("/organizations/{id}/fans/{user_id}/activity")
             .wrap(CacheResource::new(CacheUsersBy::OrganizationScopePresence(
                 OrganizationLoad::Path,
                 Scopes::OrgFans,
             )))
+            .wrap(AllowApiKeys)
             .route(web::get().to(users::activity)),
     )
     .service(
@@ -230,6 +377,7 @@ pub fn routes(app: &mut web::ServiceConfig) {
                 OrganizationLoad::Path,
                 Scopes::OrgFans,
             )))
+            .wrap(AllowApiKeys)
             .route(web::get().to(users::history)),
     )
     .service(
@@ -238,8 +386,28 @@ pub fn routes(app: &mut web::ServiceConfig) {
                 OrganizationLoad::Path,
                 Scopes::OrgFans,
             )))
+            .wrap(AllowApiKeys)
             .route(web::get().to(users::profile)),
     )
+    .service(
+        web::resource("/organizations/{id}/communication_templates/preview")
+            .route(web::post().to(communication_templates::preview)),
+    )
+    .service(
+        web::resource("/organizations/{id}/communication_templates")
+            .route(web::get().to(communication_templates::index))
+            .route(web::post().to(communication_templates::create)),
+    )
+    .service(
+        web::resource("/organizations/{id}/events_stream")
+            .wrap(AllowApiKeys)
+            .route(web::get().to(organization_events_stream::index)),
+    )
+    .service(
+        web::resource("/organizations/{id}/events_stream/live")
+            .wrap(AllowApiKeys)
+            .route(web::get().to(organization_events_stream::live)),
+    )
     .service(
         web::resource("/organizations/{id}/fee_schedule")
             .route(web::get().to(organizations::show_fee_schedule))
@@ -251,11 +419,17 @@ pub fn routes(app: &mut web::ServiceConfig) {
                 OrganizationLoad::Path,
                 Scopes::OrgFans,
             )))
+            .wrap(AllowApiKeys)
             .route(web::get().to(organizations::search_fans)),
     )
     .service(
         web::resource("/organizations/{id}/invites/{invite_id}").route(web::delete().to(organization_invites::destroy)),
     )
+    .service(
+        web::resource("/organizations/{id}/organization_roles")
+            .route(web::get().to(organization_roles::index))
+            .route(web::post().to(organization_roles::create)),
+    )
     .service(
         web::resource("/organizations/{id}/organization_venues")
             .route(web::get().to(organization_venues::organizations_index))
@@ -266,6 +440,22 @@ pub fn routes(app: &mut web::ServiceConfig) {
             .route(web::get().to(settlements::index))
             .route(web::post().to(settlements::create)),
     )
+    .service(
+        web::resource("/organizations/{id}/sso")
+            .route(web::get().to(organization_sso_configurations::show))
+            .route(web::post().to(organization_sso_configurations::create))
+            .route(web::put().to(organization_sso_configurations::update)),
+    )
+    .service(
+        web::resource("/organizations/{id}/gift_cards")
+            .route(web::get().to(gift_cards::index))
+            .route(web::post().to(gift_cards::create)),
+    )
+    .service(
+        web::resource("/organizations/{id}/webhooks")
+            .route(web::get().to(organization_webhooks::index))
+            .route(web::post().to(organization_webhooks::create)),
+    )
     .service(
         web::resource("/organizations/{id}/invites")
             .route(web::get().to(organization_invites::index))
@@ -278,6 +468,10 @@ pub fn routes(app: &mut web::ServiceConfig) {
             .route(web::get().to(organizations::list_organization_members)),
     )
     .service(web::resource("/organizations/{id}/users/{user_id}").route(web::delete().to(organizations::remove_user)))
+    .service(
+        web::resource("/organizations/{id}/users/{user_id}/organization_roles")
+            .route(web::put().to(organization_roles::update_user_roles)),
+    )
     .service(web::resource("/organizations/{id}/venues").route(web::get().to(venues::show_from_organizations)))
     .service(
         web::resource("/organizations/{id}")
@@ -296,6 +490,8 @@ pub fn routes(app: &mut web::ServiceConfig) {
     )
     .service(web::resource("/payments/callback/{nonce}/{id}").route(web::get().to(payments::callback)))
     .service(web::resource("/payment_methods").route(web::get().to(payment_methods::index)))
+    .service(web::resource("/payment_methods/{id}").route(web::delete().to(payment_methods::destroy)))
+    .service(web::resource("/payment_methods/{id}/default").route(web::put().to(payment_methods::set_default)))
     .service(web::resource("/redemption_codes/{code}").route(web::get().to(redemption_codes::show)))
     .service(
         web::resource("/regions/{id}")
@@ -309,6 +505,11 @@ pub fn routes(app: &mut web::ServiceConfig) {
             .route(web::get().to(regions::index))
             .route(web::post().to(regions::create)),
     )
+    .service(
+        web::resource("/regions/{id}/tax_rules")
+            .route(web::get().to(tax_rules::index_for_region))
+            .route(web::post().to(tax_rules::create_for_region)),
+    )
     .service(web::resource("/reports/{id}").route(web::get().to(reports::get_report)))
     .service(web::resource("/send_download_link").route(web::post().to(send_download_link::create)))
     .service(web::resource("/send_download_link/resend").route(web::post().to(send_download_link::resend)))
@@ -325,6 +526,15 @@ pub fn routes(app: &mut web::ServiceConfig) {
             .route(web::put().to(stages::update))
             .route(web::delete().to(stages::delete)),
     )
+    .service(web::resource("/stages/{id}/seat_map").route(web::get().to(stages::seat_map)))
+    .service(web::resource("/stages/{id}/sections").route(web::post().to(stages::create_section)))
+    .service(
+        web::resource("/stage_sections/{id}")
+            .route(web::patch().to(stages::update_section))
+            .route(web::delete().to(stages::delete_section)),
+    )
+    .service(web::resource("/stage_sections/{id}/seats").route(web::post().to(stages::create_seats)))
+    .service(web::resource("/stage_seats/{id}").route(web::delete().to(stages::delete_seat)))
     .service(web::resource("/settlement_adjustments/{id}").route(web::delete().to(settlement_adjustments::destroy)))
     .service(
         web::resource("/settlements/{id}/adjustments")
@@ -336,6 +546,11 @@ pub fn routes(app: &mut web::ServiceConfig) {
             .route(web::get().to(settlements::show))
             .route(web::delete().to(settlements::destroy)),
     )
+    .service(
+        web::resource("/tax_rules/{id}")
+            .route(web::put().to(tax_rules::update))
+            .route(web::delete().to(tax_rules::destroy)),
+    )
     .service(web::resource("/tickets/transfer").route(web::post().to(tickets::transfer_authorization)))
     .service(web::resource("/tickets/receive").route(web::post().to(tickets::receive_transfer)))
     .service(web::resource("/tickets/send").route(web::post().to(tickets::send_via_email_or_phone)))
@@ -346,6 +561,7 @@ pub fn routes(app: &mut web::ServiceConfig) {
     )
     .service(web::resource("/tickets").route(web::get().to(tickets::index)))
     .service(web::resource("/tickets/{id}/redeem").route(web::get().to(tickets::show_redeemable_ticket)))
+    .service(web::resource("/transfers/transfer_key/{id}/decline").route(web::post().to(transfers::decline)))
     .service(web::resource("/transfers/transfer_key/{id}").route(web::get().to(transfers::show_by_transfer_key)))
     .service(web::resource("/transfers/activity").route(web::get().to(transfers::activity)))
     .service(web::resource("/transfers/{id}").route(web::delete().to(transfers::cancel)))
@@ -372,7 +588,24 @@ pub fn routes(app: &mut web::ServiceConfig) {
     )
     .service(web::resource("/user_invites").route(web::post().to(user_invites::create)))
     .service(web::resource("/users/{id}/organizations").route(web::get().to(users::list_organizations)))
+    .service(web::resource("/users/me/account_credits").route(web::get().to(account_credits::index)))
     .service(web::resource("/users/me/marketplace_account").route(web::post().to(users::create_marketplace_account)))
+    .service(
+        web::resource("/users/me/sessions")
+            .route(web::get().to(user_sessions::index))
+            .route(web::delete().to(user_sessions::destroy_all)),
+    )
+    .service(web::resource("/users/me/sessions/{id}").route(web::delete().to(user_sessions::destroy)))
+    .service(
+        web::resource("/users/me/two_factor")
+            .route(web::post().to(two_factor::enroll))
+            .route(web::delete().to(two_factor::disable)),
+    )
+    .service(web::resource("/users/me/two_factor/confirm").route(web::post().to(two_factor::confirm)))
+    .service(
+        web::resource("/users/me/two_factor/recovery_codes")
+            .route(web::post().to(two_factor::regenerate_recovery_codes)),
+    )
     .service(
         web::resource("/venues/{id}/organization_venues")
             .route(web::get().to(organization_venues::venues_index))
@@ -383,6 +616,11 @@ pub fn routes(app: &mut web::ServiceConfig) {
             .route(web::post().to(stages::create))
             .route(web::get().to(stages::index)),
     )
+    .service(
+        web::resource("/venues/{id}/tax_rules")
+            .route(web::get().to(tax_rules::index_for_venue))
+            .route(web::post().to(tax_rules::create_for_venue)),
+    )
     .service(web::resource("/venues/{id}/toggle_privacy").route(web::put().to(venues::toggle_privacy)))
     .service(
         web::resource("/venues/{id}")
@@ -396,6 +634,15 @@ pub fn routes(app: &mut web::ServiceConfig) {
             .route(web::get().to(venues::index))
             .route(web::post().to(venues::create)),
     )
+    .service(
+        web::resource("/webhook_deliveries/{id}/replay").route(web::post().to(organization_webhooks::replay_delivery)),
+    )
+    .service(web::resource("/webhooks/{id}/deliveries").route(web::get().to(organization_webhooks::deliveries)))
+    .service(
+        web::resource("/webhooks/{id}")
+            .route(web::put().to(organization_webhooks::update))
+            .route(web::delete().to(organization_webhooks::destroy)),
+    )
     .service(
         web::resource("/sitemap.xml")
             .wrap(CacheResource::new(CacheUsersBy::None))
@@ -73,6 +73,19 @@ async fn send_email_template(
     communication: Communication,
     destination_addresses: &Vec<String>,
 ) -> Result<(), ApiError> {
+    // Stored communication templates are rendered before queueing and sent as content
+    if communication.template_id.is_none() && communication.body.is_some() {
+        return sendgrid::send_email_async(
+            &config.sendgrid_api_key,
+            communication.source.as_ref().unwrap().get_first().unwrap(),
+            destination_addresses.clone(),
+            communication.title,
+            communication.body,
+            communication.categories,
+            None,
+        )
+        .await;
+    }
     if communication.template_id.is_none() {
         return Err(ApplicationError::new(
             "Template ID must be specified when communication type is EmailTemplate".to_string(),
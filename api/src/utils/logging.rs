@@ -18,6 +18,7 @@ impl From<&RequestLogData> for LogMeta {
     fn from(data: &RequestLogData) -> Self {
         LogMeta(json!({
             "user_id": data.user,
+            "api_key": data.api_key,
             "ip_address": data.ip_address,
             "uri": data.uri,
             "method": data.method,
@@ -58,6 +59,8 @@ impl From<LogMeta> for Value {
 
 pub struct RequestLogData {
     pub user: Option<uuid::Uuid>,
+    /// Display prefix of the API key used to authenticate, if any
+    pub api_key: Option<String>,
     pub ip_address: Option<String>,
     pub method: String,
     pub user_agent: Option<String>,
@@ -67,10 +70,9 @@ pub struct RequestLogData {
 impl From<&dev::ServiceRequest> for RequestLogData {
     fn from(req: &dev::ServiceRequest) -> Self {
         let uri = req.uri().to_string();
-        let user = AccessTokenExtractor::from_request(req)
-            .ok()
-            .map(|token| token.get_id().ok())
-            .flatten();
+        let credentials = AccessTokenExtractor::from_request(req).ok();
+        let user = credentials.as_ref().and_then(|c| c.user_id());
+        let api_key = credentials.as_ref().and_then(|c| c.api_key_prefix());
         let ip_address = req.connection_info().remote().map(|i| i.to_string());
         let method = req.method().to_string();
         let user_agent = if let Some(ua) = req.headers().get(header::USER_AGENT) {
@@ -81,6 +83,7 @@ impl From<&dev::ServiceRequest> for RequestLogData {
         };
         Self {
             user,
+            api_key,
             ip_address,
             method,
             user_agent,
@@ -92,10 +95,9 @@ impl From<&dev::ServiceRequest> for RequestLogData {
 impl From<&HttpRequest> for RequestLogData {
     fn from(req: &HttpRequest) -> Self {
         let uri = req.uri().to_string();
-        let user = AccessTokenExtractor::from_request(req)
-            .ok()
-            .map(|token| token.get_id().ok())
-            .flatten();
+        let credentials = AccessTokenExtractor::from_request(req).ok();
+        let user = credentials.as_ref().and_then(|c| c.user_id());
+        let api_key = credentials.as_ref().and_then(|c| c.api_key_prefix());
         let ip_address = req.connection_info().remote().map(|i| i.to_string());
         let method = req.method().to_string();
         let user_agent = if let Some(ua) = req.headers().get(header::USER_AGENT) {
@@ -106,6 +108,7 @@ impl From<&HttpRequest> for RequestLogData {
         };
         Self {
             user,
+            api_key,
             ip_address,
             method,
             user_agent,
@@ -9,6 +9,7 @@ pub mod gen_sitemap;
 pub mod google_recaptcha;
 pub mod logging;
 pub mod marketplace_api;
+pub mod oidc;
 pub mod redis;
 pub mod sendgrid;
 pub mod serializers;
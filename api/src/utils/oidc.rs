@@ -0,0 +1,308 @@
+use crate::errors::*;
+use crate::jwt::{decode, decode_header, Algorithm, Validation};
+use db::models::{OrganizationSsoConfiguration, SsoIdentity, SsoLoginRequest};
+use db::utils::oidc::rsa_public_key_der;
+use reqwest;
+use serde_json::Value;
+use url::Url;
+
+const DISCOVERY_PATH: &str = "/.well-known/openid-configuration";
+const AUTHORIZATION_SCOPES: &str = "openid email profile";
+// Allow for clock drift between the provider and this server
+const ID_TOKEN_LEEWAY_SECONDS: i64 = 60;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ProviderMetadata {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub jwks_uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TokenEndpointResponse {
+    pub access_token: String,
+    pub id_token: String,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct JsonWebKeySet {
+    pub keys: Vec<JsonWebKey>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct JsonWebKey {
+    pub kty: String,
+    pub kid: Option<String>,
+    pub n: Option<String>,
+    pub e: Option<String>,
+}
+
+pub async fn discover(issuer_url: &str) -> Result<ProviderMetadata, ApiError> {
+    let metadata: ProviderMetadata = reqwest::Client::new()
+        .get(&format!("{}{}", issuer_url, DISCOVERY_PATH))
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    if metadata.issuer.trim_end_matches('/') != issuer_url {
+        return Err(
+            ApplicationError::unprocessable("Identity provider issuer does not match the configured issuer").into(),
+        );
+    }
+    Ok(metadata)
+}
+
+/// Provider URL the user is sent to, using PKCE so an intercepted code cannot be redeemed
+pub fn authorization_url(
+    metadata: &ProviderMetadata,
+    configuration: &OrganizationSsoConfiguration,
+    login_request: &SsoLoginRequest,
+) -> Result<String, ApiError> {
+    let code_challenge = login_request.code_challenge();
+    let url = Url::parse_with_params(
+        &metadata.authorization_endpoint,
+        &[
+            ("response_type", "code"),
+            ("client_id", configuration.client_id.as_str()),
+            ("redirect_uri", login_request.redirect_uri.as_str()),
+            ("scope", AUTHORIZATION_SCOPES),
+            ("state", login_request.state.as_str()),
+            ("nonce", login_request.nonce.as_str()),
+            ("code_challenge", code_challenge.as_str()),
+            ("code_challenge_method", "S256"),
+        ],
+    )?;
+    Ok(url.to_string())
+}
+
+pub async fn exchange_code(
+    metadata: &ProviderMetadata,
+    configuration: &OrganizationSsoConfiguration,
+    client_secret: &str,
+    login_request: &SsoLoginRequest,
+    code: &str,
+) -> Result<TokenEndpointResponse, ApiError> {
+    let params = [
+        ("grant_type", "authorization_code"),
+        ("code", code),
+        ("redirect_uri", login_request.redirect_uri.as_str()),
+        ("client_id", configuration.client_id.as_str()),
+        ("client_secret", client_secret),
+        ("code_verifier", login_request.code_verifier.as_str()),
+    ];
+    let response = reqwest::Client::new()
+        .post(&metadata.token_endpoint)
+        .form(&params)
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        warn!(
+            "Identity provider token exchange failed with status {}",
+            response.status()
+        );
+        return Err(AuthError::unauthorized("Identity provider rejected the authorization code").into());
+    }
+    Ok(response.json().await?)
+}
+
+pub async fn fetch_keys(metadata: &ProviderMetadata) -> Result<JsonWebKeySet, ApiError> {
+    Ok(reqwest::Client::new()
+        .get(&metadata.jwks_uri)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?)
+}
+
+/// Checks the signature, expiry, issuer, audience and nonce of an ID token returning its claims.
+/// RS256 tokens are verified with the provider's published keys, HS256 with the client secret.
+pub fn validate_id_token(
+    id_token: &str,
+    keys: &JsonWebKeySet,
+    configuration: &OrganizationSsoConfiguration,
+    client_secret: &str,
+    nonce: &str,
+) -> Result<Value, ApiError> {
+    let header = decode_header(id_token)?;
+    let key = match header.alg {
+        Algorithm::HS256 => client_secret.as_bytes().to_vec(),
+        Algorithm::RS256 => keys
+            .keys
+            .iter()
+            .filter(|key| key.kty == "RSA")
+            .find(|key| header.kid.is_none() || key.kid == header.kid)
+            .and_then(|key| match (&key.n, &key.e) {
+                (Some(n), Some(e)) => rsa_public_key_der(n, e),
+                _ => None,
+            })
+            .ok_or_else(|| AuthError::unauthorized("ID token signing key not found"))?,
+        _ => return Err(AuthError::unauthorized("ID token signing algorithm is not supported").into()),
+    };
+
+    let mut validation = Validation::new(header.alg);
+    validation.leeway = ID_TOKEN_LEEWAY_SECONDS;
+    let claims = decode::<Value>(id_token, &key, &validation)?.claims;
+
+    if claims["iss"].as_str().map(|iss| iss.trim_end_matches('/')) != Some(configuration.issuer_url.as_str()) {
+        return Err(AuthError::unauthorized("ID token issuer is invalid").into());
+    }
+    let audience_valid = match &claims["aud"] {
+        Value::String(aud) => aud == &configuration.client_id,
+        Value::Array(aud) => aud
+            .iter()
+            .any(|aud| aud.as_str() == Some(configuration.client_id.as_str())),
+        _ => false,
+    };
+    if !audience_valid {
+        return Err(AuthError::unauthorized("ID token audience is invalid").into());
+    }
+    if claims["nonce"].as_str() != Some(nonce) {
+        return Err(AuthError::unauthorized("ID token nonce is invalid").into());
+    }
+    Ok(claims)
+}
+
+/// Identity from validated ID token claims, unverified emails are ignored
+pub fn identity(claims: &Value, configuration: &OrganizationSsoConfiguration) -> Result<SsoIdentity, ApiError> {
+    let subject = claims["sub"]
+        .as_str()
+        .ok_or_else(|| AuthError::unauthorized("ID token subject is missing"))?;
+    let email = match claims["email_verified"] {
+        Value::Bool(false) => None,
+        _ => claims["email"].as_str().map(|email| email.to_string()),
+    };
+    let role_claim_values = match configuration.role_claim.as_ref().map(|role_claim| &claims[role_claim]) {
+        Some(Value::String(value)) => vec![value.to_string()],
+        Some(Value::Array(values)) => values
+            .iter()
+            .filter_map(|value| value.as_str().map(|value| value.to_string()))
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    Ok(SsoIdentity {
+        subject: subject.to_string(),
+        email,
+        first_name: claims["given_name"].as_str().map(|name| name.to_string()),
+        last_name: claims["family_name"].as_str().map(|name| name.to_string()),
+        role_claim_values,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::jwt::{encode, Header};
+    use chrono::Utc;
+    use db::models::Roles;
+    use uuid::Uuid;
+
+    fn configuration() -> OrganizationSsoConfiguration {
+        OrganizationSsoConfiguration {
+            id: Uuid::new_v4(),
+            organization_id: Uuid::new_v4(),
+            issuer_url: "https://login.example.com".to_string(),
+            client_id: "client-id".to_string(),
+            encrypted_client_secret: "".to_string(),
+            default_role: Roles::OrgMember,
+            role_claim: Some("groups".to_string()),
+            role_mappings: json!({}),
+            enabled: true,
+            created_at: Utc::now().naive_utc(),
+            updated_at: Utc::now().naive_utc(),
+        }
+    }
+
+    fn id_token(claims: Value) -> String {
+        encode(&Header::default(), &claims, b"client-secret").unwrap()
+    }
+
+    #[test]
+    fn validate_id_token_checks_claims() {
+        let configuration = configuration();
+        let keys = JsonWebKeySet::default();
+        let exp = Utc::now().timestamp() + 300;
+        let valid_claims = json!({
+            "iss": "https://login.example.com/",
+            "aud": ["client-id"],
+            "sub": "subject",
+            "nonce": "nonce",
+            "exp": exp
+        });
+
+        let claims = validate_id_token(
+            &id_token(valid_claims.clone()),
+            &keys,
+            &configuration,
+            "client-secret",
+            "nonce",
+        )
+        .unwrap();
+        assert_eq!(claims["sub"], "subject");
+
+        // Signed with a different secret
+        assert!(validate_id_token(
+            &id_token(valid_claims.clone()),
+            &keys,
+            &configuration,
+            "other-secret",
+            "nonce"
+        )
+        .is_err());
+
+        let mut claims = valid_claims.clone();
+        claims["nonce"] = json!("other-nonce");
+        assert!(validate_id_token(&id_token(claims), &keys, &configuration, "client-secret", "nonce").is_err());
+
+        let mut claims = valid_claims.clone();
+        claims["aud"] = json!("other-client");
+        assert!(validate_id_token(&id_token(claims), &keys, &configuration, "client-secret", "nonce").is_err());
+
+        let mut claims = valid_claims.clone();
+        claims["iss"] = json!("https://attacker.example.com");
+        assert!(validate_id_token(&id_token(claims), &keys, &configuration, "client-secret", "nonce").is_err());
+
+        let mut claims = valid_claims;
+        claims["exp"] = json!(Utc::now().timestamp() - 3600);
+        assert!(validate_id_token(&id_token(claims), &keys, &configuration, "client-secret", "nonce").is_err());
+    }
+
+    #[test]
+    fn identity_from_claims() {
+        let configuration = configuration();
+        let sso_identity = identity(
+            &json!({
+                "sub": "subject",
+                "email": "staff@example.com",
+                "given_name": "Staff",
+                "family_name": "Member",
+                "groups": ["box-office", "admins"]
+            }),
+            &configuration,
+        )
+        .unwrap();
+        assert_eq!(sso_identity.subject, "subject");
+        assert_eq!(sso_identity.email, Some("staff@example.com".to_string()));
+        assert_eq!(sso_identity.first_name, Some("Staff".to_string()));
+        assert_eq!(sso_identity.last_name, Some("Member".to_string()));
+        assert_eq!(
+            sso_identity.role_claim_values,
+            vec!["box-office".to_string(), "admins".to_string()]
+        );
+
+        // Unverified emails are ignored and single valued role claims are accepted
+        let sso_identity = identity(
+            &json!({"sub": "subject", "email": "staff@example.com", "email_verified": false, "groups": "admins"}),
+            &configuration,
+        )
+        .unwrap();
+        assert_eq!(sso_identity.email, None);
+        assert_eq!(sso_identity.role_claim_values, vec!["admins".to_string()]);
+
+        assert!(identity(&json!({"email": "staff@example.com"}), &configuration).is_err());
+    }
+}
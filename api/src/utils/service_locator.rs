@@ -1,6 +1,8 @@
-use crate::config::Config;
+use crate::config::{Config, PaymentProcessorConfig};
 use crate::errors::*;
+use crate::payments::adyen::AdyenPaymentProcessor;
 use crate::payments::globee::GlobeePaymentProcessor;
+use crate::payments::mock::MockPaymentProcessor;
 use crate::payments::stripe::StripePaymentProcessor;
 use crate::payments::PaymentProcessor;
 use crate::utils::deep_linker::BranchDeepLinker;
@@ -10,11 +12,14 @@ use crate::utils::sharetribe_marketplace_api::SharetribeMarketplaceApi;
 use db::prelude::*;
 use db::services::CountryLookup;
 use db::utils::errors::DatabaseError;
+use std::collections::HashMap;
 
 pub struct ServiceLocator {
     stripe_secret_key: String,
     globee_api_key: String,
     globee_base_url: String,
+    payment_processors: HashMap<PaymentProviders, PaymentProcessorConfig>,
+    mock_payment_processor_enabled: bool,
     branch_io_base_url: String,
     branch_io_branch_key: String,
     branch_io_timeout: u64,
@@ -38,6 +43,8 @@ impl ServiceLocator {
             stripe_secret_key: config.stripe_secret_key.clone(),
             globee_api_key: config.globee_api_key.clone(),
             globee_base_url: config.globee_base_url.clone(),
+            payment_processors: config.payment_processors.clone(),
+            mock_payment_processor_enabled: config.mock_payment_processor_enabled,
             branch_io_base_url: config.branch_io_base_url.clone(),
             branch_io_branch_key: config.branch_io_branch_key.clone(),
             branch_io_timeout: config.branch_io_timeout,
@@ -74,6 +81,20 @@ impl ServiceLocator {
                     self.globee_base_url.clone(),
                 )))
             }
+            PaymentProviders::Adyen => match self.payment_processors.get(&provider) {
+                Some(config) => Ok(Box::new(AdyenPaymentProcessor::new(
+                    config.api_key.clone(),
+                    config.base_url.clone(),
+                    config.merchant_account.clone(),
+                ))),
+                None => Err(ApplicationError::new(format!("Payment provider {} is not configured", provider)).into()),
+            },
+            PaymentProviders::Mock => {
+                if !self.mock_payment_processor_enabled {
+                    return Err(ApplicationError::new("Payment provider Mock is not enabled".into()).into());
+                }
+                Ok(Box::new(MockPaymentProcessor::new()))
+            }
             // External is not valid for service locator
             PaymentProviders::Free | PaymentProviders::External => {
                 return Err(ApplicationError::new("Unknown payment provider".into()).into());
@@ -99,6 +120,8 @@ impl ServiceLocator {
     pub fn is_refund_supported(provider: String) -> bool {
         match provider.to_lowercase().as_str() {
             "stripe" => true,
+            "adyen" => true,
+            "mock" => true,
             "globee" => false,
             "external" => false,
             _ => false,
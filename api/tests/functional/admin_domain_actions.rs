@@ -0,0 +1,142 @@
+use crate::support;
+use crate::support::database::TestDatabase;
+use crate::support::test_request::TestRequest;
+use actix_web::{
+    http::StatusCode,
+    web::{Path, Query},
+    FromRequest, HttpResponse,
+};
+use api::controllers::admin::domain_actions;
+use api::models::PathParameters;
+use db::prelude::*;
+use serde_json;
+
+#[actix_rt::test]
+async fn metrics() {
+    let database = TestDatabase::new();
+    let auth_user = support::create_auth_user(Roles::Admin, None, &database);
+    database
+        .create_domain_action()
+        .with_domain_action_type(DomainActionTypes::Communication)
+        .finish();
+    database
+        .create_domain_action()
+        .with_domain_action_type(DomainActionTypes::SendAutomaticReportEmails)
+        .with_status(DomainActionStatus::Errored)
+        .finish();
+
+    let response: HttpResponse = domain_actions::metrics((database.connection.clone().into(), auth_user))
+        .await
+        .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let metrics: Vec<DomainActionQueueMetrics> = serde_json::from_str(&body).unwrap();
+    let communication = metrics
+        .iter()
+        .find(|m| m.domain_action_type == DomainActionTypes::Communication)
+        .unwrap();
+    assert_eq!(communication.due_count, 1);
+    let reports = metrics
+        .iter()
+        .find(|m| m.domain_action_type == DomainActionTypes::SendAutomaticReportEmails)
+        .unwrap();
+    assert_eq!(reports.dead_letter_count, 1);
+}
+
+#[actix_rt::test]
+async fn metrics_org_owner() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+
+    let response: HttpResponse = domain_actions::metrics((database.connection.clone().into(), auth_user))
+        .await
+        .into();
+    support::expects_unauthorized(&response);
+}
+
+#[actix_rt::test]
+async fn dead_letter() {
+    let database = TestDatabase::new();
+    let auth_user = support::create_auth_user(Roles::Admin, None, &database);
+    let errored = database
+        .create_domain_action()
+        .with_domain_action_type(DomainActionTypes::SendAutomaticReportEmails)
+        .with_status(DomainActionStatus::Errored)
+        .finish();
+    database
+        .create_domain_action()
+        .with_domain_action_type(DomainActionTypes::Communication)
+        .with_status(DomainActionStatus::RetriesExceeded)
+        .finish();
+
+    let test_request =
+        TestRequest::create_with_uri("/admin/domain_actions/dead_letter?domain_action_type=SendAutomaticReportEmails");
+    let query_parameters = Query::<PagingParameters>::extract(&test_request.request).await.unwrap();
+    let response: HttpResponse =
+        domain_actions::dead_letter((database.connection.clone().into(), query_parameters, auth_user))
+            .await
+            .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let dead_letters: Payload<DomainAction> = serde_json::from_str(&body).unwrap();
+    assert_eq!(dead_letters.data.len(), 1);
+    assert_eq!(dead_letters.data[0].id, errored.id);
+}
+
+#[actix_rt::test]
+async fn requeue() {
+    let database = TestDatabase::new();
+    let auth_user = support::create_auth_user(Roles::Admin, None, &database);
+    let action = database
+        .create_domain_action()
+        .with_status(DomainActionStatus::RetriesExceeded)
+        .with_attempt_count(3)
+        .finish();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = action.id;
+    let response: HttpResponse = domain_actions::requeue((database.connection.clone().into(), path, auth_user))
+        .await
+        .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let action = DomainAction::find(action.id, database.connection.get()).unwrap();
+    assert_eq!(action.status, DomainActionStatus::Pending);
+    assert_eq!(action.attempt_count, 0);
+}
+
+#[actix_rt::test]
+async fn requeue_pending_action() {
+    let database = TestDatabase::new();
+    let auth_user = support::create_auth_user(Roles::Admin, None, &database);
+    let action = database.create_domain_action().finish();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = action.id;
+    let response: HttpResponse = domain_actions::requeue((database.connection.clone().into(), path, auth_user))
+        .await
+        .into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[actix_rt::test]
+async fn cancel() {
+    let database = TestDatabase::new();
+    let auth_user = support::create_auth_user(Roles::Admin, None, &database);
+    let action = database
+        .create_domain_action()
+        .with_status(DomainActionStatus::Errored)
+        .finish();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = action.id;
+    let response: HttpResponse = domain_actions::cancel((database.connection.clone().into(), path, auth_user))
+        .await
+        .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let action = DomainAction::find(action.id, database.connection.get()).unwrap();
+    assert_eq!(action.status, DomainActionStatus::Cancelled);
+}
@@ -0,0 +1,264 @@
+use crate::functional::base;
+use crate::support;
+use crate::support::database::TestDatabase;
+use crate::support::test_request::TestRequest;
+use actix_web::{dev::Payload, http::StatusCode, test, web::Path, FromRequest, HttpResponse};
+use api::auth::user::User as AuthUser;
+use api::controllers::api_keys;
+use api::middleware::ApiKeysAllowed;
+use api::models::PathParameters;
+use db::models::*;
+use serde_json;
+
+#[cfg(test)]
+mod index_tests {
+    use super::*;
+
+    #[actix_rt::test]
+    async fn index_org_member() {
+        base::api_keys::index(Roles::OrgMember, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn index_admin() {
+        base::api_keys::index(Roles::Admin, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn index_super() {
+        base::api_keys::index(Roles::Super, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn index_user() {
+        base::api_keys::index(Roles::User, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn index_org_owner() {
+        base::api_keys::index(Roles::OrgOwner, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn index_door_person() {
+        base::api_keys::index(Roles::DoorPerson, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn index_promoter() {
+        base::api_keys::index(Roles::Promoter, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn index_promoter_read_only() {
+        base::api_keys::index(Roles::PromoterReadOnly, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn index_org_admin() {
+        base::api_keys::index(Roles::OrgAdmin, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn index_box_office() {
+        base::api_keys::index(Roles::OrgBoxOffice, false).await;
+    }
+}
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+
+    #[actix_rt::test]
+    async fn create_org_member() {
+        base::api_keys::create(Roles::OrgMember, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_admin() {
+        base::api_keys::create(Roles::Admin, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_super() {
+        base::api_keys::create(Roles::Super, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_user() {
+        base::api_keys::create(Roles::User, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_org_owner() {
+        base::api_keys::create(Roles::OrgOwner, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_door_person() {
+        base::api_keys::create(Roles::DoorPerson, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_promoter() {
+        base::api_keys::create(Roles::Promoter, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_promoter_read_only() {
+        base::api_keys::create(Roles::PromoterReadOnly, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_org_admin() {
+        base::api_keys::create(Roles::OrgAdmin, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_box_office() {
+        base::api_keys::create(Roles::OrgBoxOffice, false).await;
+    }
+}
+
+#[actix_rt::test]
+async fn rotate() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::OrgOwner, Some(&organization), &database);
+    let issued_api_key = ApiKey::create(
+        organization.id,
+        user.id,
+        "Box office sync".to_string(),
+        vec![Scopes::EventWrite],
+        None,
+    )
+    .commit(connection)
+    .unwrap();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = issued_api_key.api_key.id;
+    let response: HttpResponse = api_keys::rotate((database.connection.clone().into(), path, auth_user))
+        .await
+        .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let rotated_api_key: IssuedApiKey = serde_json::from_str(&body).unwrap();
+    assert_eq!(rotated_api_key.api_key.id, issued_api_key.api_key.id);
+    assert!(ApiKey::find_active_by_key(&issued_api_key.key, connection).is_err());
+    assert!(ApiKey::find_active_by_key(&rotated_api_key.key, connection).is_ok());
+}
+
+#[actix_rt::test]
+async fn destroy() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::OrgOwner, Some(&organization), &database);
+    let issued_api_key = ApiKey::create(
+        organization.id,
+        user.id,
+        "Box office sync".to_string(),
+        vec![Scopes::EventWrite],
+        None,
+    )
+    .commit(connection)
+    .unwrap();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = issued_api_key.api_key.id;
+    let response: HttpResponse = api_keys::destroy((database.connection.clone().into(), path, auth_user))
+        .await
+        .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(ApiKey::find(issued_api_key.api_key.id, connection)
+        .unwrap()
+        .revoked_at
+        .is_some());
+    assert!(ApiKey::find_active_by_key(&issued_api_key.key, connection).is_err());
+}
+
+#[actix_rt::test]
+async fn api_key_scope_access() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let other_organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::OrgOwner, Some(&organization), &database);
+    other_organization
+        .add_user(user.id, vec![Roles::OrgOwner], Vec::new(), connection)
+        .unwrap();
+    let api_key = ApiKey::create(
+        organization.id,
+        user.id,
+        "Box office sync".to_string(),
+        vec![Scopes::EventWrite],
+        None,
+    )
+    .commit(connection)
+    .unwrap()
+    .api_key;
+
+    assert!(auth_user
+        .has_scope_for_organization(Scopes::OrgWrite, &organization, connection)
+        .unwrap());
+    assert!(auth_user
+        .has_scope_for_organization(Scopes::EventWrite, &other_organization, connection)
+        .unwrap());
+
+    // Requests made with the key are limited to its scopes and organization
+    let key_user = auth_user.with_api_key(Some(api_key));
+    assert!(key_user
+        .has_scope_for_organization(Scopes::EventWrite, &organization, connection)
+        .unwrap());
+    assert!(!key_user
+        .has_scope_for_organization(Scopes::OrgWrite, &organization, connection)
+        .unwrap());
+    assert!(!key_user
+        .has_scope_for_organization(Scopes::EventWrite, &other_organization, connection)
+        .unwrap());
+    assert!(!key_user.has_scope(Scopes::EventWrite).unwrap());
+
+    // Keys stop granting access once the issuer loses the scope
+    organization.remove_user(user.id, connection).unwrap();
+    assert!(!key_user
+        .has_scope_for_organization(Scopes::EventWrite, &organization, connection)
+        .unwrap());
+}
+
+#[actix_rt::test]
+async fn api_key_only_accepted_where_allowed() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database
+        .create_organization()
+        .with_member(&user, Roles::OrgOwner)
+        .finish();
+    let issued_api_key = ApiKey::create(
+        organization.id,
+        user.id,
+        "Box office sync".to_string(),
+        vec![Scopes::EventWrite],
+        None,
+    )
+    .commit(connection)
+    .unwrap();
+
+    let request = test::TestRequest::get()
+        .header("Authorization", format!("Bearer {}", issued_api_key.key))
+        .to_http_request();
+    request.extensions_mut().insert(database.connection.clone());
+    assert!(AuthUser::from_request(&request, &mut Payload::None).await.is_err());
+
+    // Resources wrapped with `AllowApiKeys` accept the key limited to its organization scopes
+    request.extensions_mut().insert(ApiKeysAllowed);
+    let auth_user = AuthUser::from_request(&request, &mut Payload::None).await.unwrap();
+    assert_eq!(auth_user.id(), user.id);
+    assert_eq!(auth_user.api_key.map(|k| k.id), Some(issued_api_key.api_key.id));
+}
@@ -8,9 +8,10 @@ use api::controllers::auth;
 use api::controllers::auth::{LoginRequest, RefreshRequest};
 use api::extractors::*;
 use api::models::*;
-use chrono::Duration;
+use chrono::{Duration, Utc};
 use db::models::TokenIssuer;
-use db::prelude::{AccessToken, Scopes};
+use db::prelude::*;
+use db::utils::totp;
 use serde_json;
 use uuid::Uuid;
 
@@ -33,7 +34,10 @@ async fn token() {
         test_request.request,
         database.connection.into(),
         json,
-        RequestInfo { user_agent: None },
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
     ))
     .await
     .unwrap();
@@ -60,7 +64,10 @@ async fn token_invalid_email() {
         test_request.request,
         database.connection.into(),
         json,
-        RequestInfo { user_agent: None },
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
     ))
     .await;
 
@@ -80,7 +87,10 @@ async fn token_incorrect_password() {
         test_request.request,
         database.connection.into(),
         json,
-        RequestInfo { user_agent: None },
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
     ))
     .await;
 
@@ -101,15 +111,131 @@ async fn token_refresh() {
         .unwrap();
     let json = Json(RefreshRequest::new(&refresh_token));
 
-    let response: HttpResponse = auth::token_refresh((state, database.connection.into(), json))
-        .await
-        .into();
+    let response: HttpResponse = auth::token_refresh((
+        state.clone(),
+        database.connection.clone().into(),
+        json,
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
+    ))
+    .await
+    .into();
 
     assert_eq!(response.status(), StatusCode::OK);
     let body = support::unwrap_body_to_string(&response).unwrap();
     let response: TokenResponse = serde_json::from_str(&body).unwrap();
     let access_token = token_issuer.decode(&response.access_token).unwrap();
     assert_eq!(access_token.claims.get_id().unwrap(), user.id);
+    assert!(access_token.claims.session_id.is_some());
+
+    // Tokens without a session can only start one
+    let json = Json(RefreshRequest::new(&refresh_token));
+    let response: HttpResponse = auth::token_refresh((
+        state,
+        database.connection.clone().into(),
+        json,
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
+    ))
+    .await
+    .into();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        UserSession::find_active_for_user(user.id, database.connection.get())
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[actix_rt::test]
+async fn token_refresh_expired_sessionless_token() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state().await;
+    let mut refresh_token_claims =
+        AccessToken::new_limited_scope(user.id, "iss".to_string(), 30, vec![Scopes::TokenRefresh]);
+    refresh_token_claims.exp = (Utc::now() - Duration::minutes(1)).timestamp() as u64;
+    let refresh_token = state.config.token_issuer.encode(&refresh_token_claims).unwrap();
+    let json = Json(RefreshRequest::new(&refresh_token));
+
+    let response: HttpResponse = auth::token_refresh((
+        state,
+        database.connection.clone().into(),
+        json,
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
+    ))
+    .await
+    .into();
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert!(UserSession::find_active_for_user(user.id, database.connection.get())
+        .unwrap()
+        .is_empty());
+}
+
+#[actix_rt::test]
+async fn token_refresh_rotates_session_refresh_token() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let session = UserSession::create(user.id, None, None).commit(connection).unwrap();
+
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state().await;
+    let token_issuer = state.config.token_issuer.clone();
+    let refresh_token = token_issuer
+        .issue_refresh_for_session(&session, Duration::minutes(30))
+        .unwrap();
+
+    let response: HttpResponse = auth::token_refresh((
+        state.clone(),
+        database.connection.clone().into(),
+        Json(RefreshRequest::new(&refresh_token)),
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
+    ))
+    .await
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let response: TokenResponse = serde_json::from_str(&body).unwrap();
+    let new_refresh_token = token_issuer.decode(&response.refresh_token).unwrap();
+    assert_eq!(new_refresh_token.claims.session_id, Some(session.id));
+    assert_ne!(
+        new_refresh_token.claims.refresh_token_id,
+        Some(session.refresh_token_id)
+    );
+
+    // Using the replaced refresh token again signs the session out
+    let response: HttpResponse = auth::token_refresh((
+        state,
+        database.connection.clone().into(),
+        Json(RefreshRequest::new(&refresh_token)),
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
+    ))
+    .await
+    .into();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let session = UserSession::find(session.id, connection).unwrap();
+    assert_eq!(
+        session.revoked_reason,
+        Some(SessionRevocationReasons::RefreshTokenReused)
+    );
 }
 
 #[actix_rt::test]
@@ -125,9 +251,17 @@ async fn token_refresh_invalid_refresh_token_secret() {
 
     let json = Json(RefreshRequest::new(&refresh_token));
 
-    let response: HttpResponse = auth::token_refresh((state, database.connection.into(), json))
-        .await
-        .into();
+    let response: HttpResponse = auth::token_refresh((
+        state,
+        database.connection.into(),
+        json,
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
+    ))
+    .await
+    .into();
 
     assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
     let body = support::unwrap_body_to_string(&response).unwrap();
@@ -144,9 +278,17 @@ async fn token_refresh_invalid_refresh_token() {
     let state = test_request.extract_state().await;
     let json = Json(RefreshRequest::new(&"not.a.real.token"));
 
-    let response: HttpResponse = auth::token_refresh((state, database.connection.into(), json))
-        .await
-        .into();
+    let response: HttpResponse = auth::token_refresh((
+        state,
+        database.connection.into(),
+        json,
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
+    ))
+    .await
+    .into();
 
     assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
     let body = support::unwrap_body_to_string(&response).unwrap();
@@ -168,9 +310,17 @@ async fn token_refresh_user_does_not_exist() {
     let refresh_token = state.config.token_issuer.encode(&refresh_token_claims).unwrap();
     let json = Json(RefreshRequest::new(&refresh_token));
 
-    let response: HttpResponse = auth::token_refresh((state, database.connection.into(), json))
-        .await
-        .into();
+    let response: HttpResponse = auth::token_refresh((
+        state,
+        database.connection.into(),
+        json,
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
+    ))
+    .await
+    .into();
 
     assert_eq!(response.status(), StatusCode::NOT_FOUND);
 }
@@ -194,9 +344,17 @@ async fn token_refresh_password_reset_since_issued() {
 
     let json = Json(RefreshRequest::new(&refresh_token));
 
-    let response: HttpResponse = auth::token_refresh((state, database.connection.into(), json))
-        .await
-        .into();
+    let response: HttpResponse = auth::token_refresh((
+        state,
+        database.connection.into(),
+        json,
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
+    ))
+    .await
+    .into();
 
     assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
     let body = support::unwrap_body_to_string(&response).unwrap();
@@ -222,9 +380,17 @@ async fn token_refreshed_after_password_change() {
     let refresh_token = token_issuer.encode(&refresh_token_claims).unwrap();
     let json = Json(RefreshRequest::new(&refresh_token));
 
-    let response: HttpResponse = auth::token_refresh((state, database.connection.into(), json))
-        .await
-        .into();
+    let response: HttpResponse = auth::token_refresh((
+        state,
+        database.connection.into(),
+        json,
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
+    ))
+    .await
+    .into();
 
     assert_eq!(response.status(), StatusCode::OK);
     let body = support::unwrap_body_to_string(&response).unwrap();
@@ -233,3 +399,240 @@ async fn token_refreshed_after_password_change() {
 
     assert_eq!(access_token.claims.get_id().unwrap(), user.id);
 }
+
+#[actix_rt::test]
+async fn token_with_two_factor() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database
+        .create_user()
+        .with_email("fake@localhost".to_string())
+        .with_password("strong_password".to_string())
+        .finish();
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state().await;
+    let encryption_key = &state.config.api_keys_encryption_key;
+    let enrollment = UserTotpCredential::enroll(&user, encryption_key, connection).unwrap();
+    let time_step = totp::time_step(Utc::now().timestamp());
+    let credential = UserTotpCredential::find_by_user_id(user.id, connection).unwrap();
+    credential
+        .confirm(
+            &totp::generate_code(&enrollment.secret, time_step).unwrap(),
+            encryption_key,
+            connection,
+        )
+        .unwrap();
+
+    // Password alone is no longer enough
+    let json = Json(LoginRequest::new("fake@localhost", "strong_password"));
+    let response = auth::token((
+        test_request.request,
+        database.connection.clone().into(),
+        json,
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
+    ))
+    .await;
+    assert_eq!("Two-factor code required", response.err().unwrap().to_string());
+
+    let test_request = TestRequest::create();
+    let json = Json(LoginRequest::new("fake@localhost", "strong_password").with_totp_code("000000"));
+    let response = auth::token((
+        test_request.request,
+        database.connection.clone().into(),
+        json,
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
+    ))
+    .await;
+    assert_eq!("Two-factor code invalid", response.err().unwrap().to_string());
+    let throttle = UserLoginThrottle::find_by_user_id(user.id, connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(throttle.failed_attempts, 1);
+
+    let test_request = TestRequest::create();
+    let code = totp::generate_code(&enrollment.secret, time_step + 1).unwrap();
+    let json = Json(LoginRequest::new("fake@localhost", "strong_password").with_totp_code(&code));
+    let response: TokenResponse = auth::token((
+        test_request.request,
+        database.connection.clone().into(),
+        json,
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
+    ))
+    .await
+    .unwrap();
+    let access_token = state.config.token_issuer.decode(&response.access_token).unwrap();
+    assert_eq!(access_token.claims.get_id().unwrap(), user.id);
+    assert!(access_token.claims.scopes.is_none());
+
+    // Successful login clears failed attempts and records how the user authenticated
+    let throttle = UserLoginThrottle::find_by_user_id(user.id, connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(throttle.failed_attempts, 0);
+    let domain_events = DomainEvent::find(
+        Tables::Users,
+        Some(user.id),
+        Some(DomainEventTypes::UserLogin),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(1, domain_events.len());
+    assert_eq!(
+        domain_events[0].event_data.as_ref().unwrap()["auth_method"],
+        json!("password_totp")
+    );
+}
+
+#[actix_rt::test]
+async fn token_with_recovery_code() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database
+        .create_user()
+        .with_email("fake@localhost".to_string())
+        .with_password("strong_password".to_string())
+        .finish();
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state().await;
+    let encryption_key = &state.config.api_keys_encryption_key;
+    let enrollment = UserTotpCredential::enroll(&user, encryption_key, connection).unwrap();
+    let credential = UserTotpCredential::find_by_user_id(user.id, connection).unwrap();
+    let recovery_codes = credential
+        .confirm(
+            &totp::generate_code(&enrollment.secret, totp::time_step(Utc::now().timestamp())).unwrap(),
+            encryption_key,
+            connection,
+        )
+        .unwrap();
+
+    let json = Json(LoginRequest::new("fake@localhost", "strong_password").with_recovery_code(&recovery_codes[0]));
+    let response: TokenResponse = auth::token((
+        test_request.request,
+        database.connection.clone().into(),
+        json,
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
+    ))
+    .await
+    .unwrap();
+    let access_token = state.config.token_issuer.decode(&response.access_token).unwrap();
+    assert_eq!(access_token.claims.get_id().unwrap(), user.id);
+    let domain_events = DomainEvent::find(
+        Tables::Users,
+        Some(user.id),
+        Some(DomainEventTypes::UserLogin),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(
+        domain_events[0].event_data.as_ref().unwrap()["auth_method"],
+        json!("password_recovery_code")
+    );
+
+    // Recovery codes are single use
+    let test_request = TestRequest::create();
+    let json = Json(LoginRequest::new("fake@localhost", "strong_password").with_recovery_code(&recovery_codes[0]));
+    let response = auth::token((
+        test_request.request,
+        database.connection.clone().into(),
+        json,
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
+    ))
+    .await;
+    assert_eq!("Two-factor code invalid", response.err().unwrap().to_string());
+}
+
+#[actix_rt::test]
+async fn token_locked_after_failed_attempts() {
+    let database = TestDatabase::new();
+    database
+        .create_user()
+        .with_email("fake@localhost".to_string())
+        .with_password("strong_password".to_string())
+        .finish();
+
+    for _ in 0..MAX_FAILED_LOGIN_ATTEMPTS {
+        let test_request = TestRequest::create();
+        let json = Json(LoginRequest::new("fake@localhost", "incorrect"));
+        let response = auth::token((
+            test_request.request,
+            database.connection.clone().into(),
+            json,
+            RequestInfo {
+                user_agent: None,
+                ip_address: None,
+            },
+        ))
+        .await;
+        assert_eq!("Email or password incorrect", response.err().unwrap().to_string());
+    }
+
+    // Correct password is refused while the user is locked out
+    let test_request = TestRequest::create();
+    let json = Json(LoginRequest::new("fake@localhost", "strong_password"));
+    let response = auth::token((
+        test_request.request,
+        database.connection.clone().into(),
+        json,
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
+    ))
+    .await;
+    assert_eq!(
+        "Too many failed login attempts, please try again later",
+        response.err().unwrap().to_string()
+    );
+}
+
+#[actix_rt::test]
+async fn token_two_factor_enrollment_required() {
+    let database = TestDatabase::new();
+    let user = database
+        .create_user()
+        .with_email("fake@localhost".to_string())
+        .with_password("strong_password".to_string())
+        .finish();
+    database
+        .create_organization()
+        .with_member(&user, Roles::OrgOwner)
+        .with_two_factor_required()
+        .finish();
+
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state().await;
+    let json = Json(LoginRequest::new("fake@localhost", "strong_password"));
+    let response: TokenResponse = auth::token((
+        test_request.request,
+        database.connection.clone().into(),
+        json,
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
+    ))
+    .await
+    .unwrap();
+
+    // Tokens only allow the user to enroll, they grant no scopes and cannot be refreshed
+    let access_token = state.config.token_issuer.decode(&response.access_token).unwrap();
+    assert_eq!(access_token.claims.get_id().unwrap(), user.id);
+    assert_eq!(access_token.claims.scopes, Some(Vec::<String>::new()));
+    let refresh_token = state.config.token_issuer.decode(&response.refresh_token).unwrap();
+    assert_eq!(refresh_token.claims.scopes, Some(Vec::<String>::new()));
+}
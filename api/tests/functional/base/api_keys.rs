@@ -0,0 +1,94 @@
+use crate::support;
+use crate::support::database::TestDatabase;
+use crate::support::test_request::TestRequest;
+use actix_web::{
+    http::StatusCode,
+    web::{Path, Query},
+    FromRequest, HttpResponse,
+};
+use api::controllers::api_keys::{self, NewApiKeyRequest};
+use api::extractors::*;
+use api::models::PathParameters;
+use db::models::*;
+use serde_json;
+
+pub async fn index(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database
+        .create_organization()
+        .with_member(&user, Roles::OrgOwner)
+        .finish();
+    let api_key = ApiKey::create(
+        organization.id,
+        user.id,
+        "Box office sync".to_string(),
+        vec![Scopes::EventWrite],
+        None,
+    )
+    .commit(connection)
+    .unwrap()
+    .api_key;
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = organization.id;
+    let query_parameters = Query::<PagingParameters>::extract(&test_request.request).await.unwrap();
+    let response: HttpResponse =
+        api_keys::index((database.connection.clone().into(), path, query_parameters, auth_user))
+            .await
+            .into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let payload: Payload<ApiKey> = serde_json::from_str(&body).unwrap();
+    assert_eq!(payload.data.len(), 1);
+    assert_eq!(payload.data[0].id, api_key.id);
+    // The key hash is never exposed
+    assert!(!body.contains(&api_key.key_hash));
+}
+
+pub async fn create(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = organization.id;
+    let json = Json(NewApiKeyRequest {
+        name: "Box office sync".to_string(),
+        scopes: vec![Scopes::EventWrite, Scopes::OrderRead],
+        expires_at: None,
+    });
+    let response: HttpResponse = api_keys::create((database.connection.clone().into(), path, json, auth_user))
+        .await
+        .into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let issued_api_key: IssuedApiKey = serde_json::from_str(&body).unwrap();
+    assert_eq!(issued_api_key.api_key.organization_id, organization.id);
+    assert_eq!(issued_api_key.api_key.user_id, user.id);
+    assert_eq!(
+        issued_api_key.api_key.scopes,
+        vec!["event:write".to_string(), "order:read".to_string()]
+    );
+    assert_eq!(
+        ApiKey::find_active_by_key(&issued_api_key.key, database.connection.get())
+            .unwrap()
+            .id,
+        issued_api_key.api_key.id
+    );
+}
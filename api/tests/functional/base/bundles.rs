@@ -0,0 +1,80 @@
+use crate::support;
+use crate::support::database::TestDatabase;
+use crate::support::test_request::TestRequest;
+use actix_web::{http::StatusCode, web::Path, FromRequest, HttpResponse};
+use api::controllers::bundles::{self, BundleTicketTypeRequest, NewBundleRequest};
+use api::extractors::*;
+use api::models::PathParameters;
+use db::models::*;
+use serde_json;
+
+pub async fn create(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = organization.id;
+    let json = Json(NewBundleRequest {
+        name: "Festival Pass".to_string(),
+        currency: None,
+    });
+    let response: HttpResponse = bundles::create((database.connection.clone().into(), path, json, auth_user))
+        .await
+        .into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let bundle: Bundle = serde_json::from_str(&body).unwrap();
+    assert_eq!(bundle.name, "Festival Pass");
+    assert_eq!(bundle.organization_id, organization.id);
+    assert_eq!(bundle.currency, organization.currency);
+}
+
+pub async fn add_ticket_type(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let bundle = Bundle::create(organization.id, "Festival Pass".to_string(), "USD".to_string())
+        .commit(None, connection)
+        .unwrap();
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = bundle.id;
+    let json = Json(BundleTicketTypeRequest {
+        ticket_type_id: ticket_type.id,
+        quantity: 2,
+        unit_price_in_cents: 1500,
+    });
+    let response: HttpResponse = bundles::add_ticket_type((database.connection.clone().into(), path, json, auth_user))
+        .await
+        .into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let display_bundle: DisplayBundle = serde_json::from_str(&body).unwrap();
+    assert_eq!(display_bundle.price_in_cents, 3000);
+    assert_eq!(display_bundle.ticket_types.len(), 1);
+    assert_eq!(display_bundle.ticket_types[0].ticket_type_id, ticket_type.id);
+    assert_eq!(display_bundle.ticket_types[0].event_id, event.id);
+}
@@ -30,6 +30,7 @@ pub async fn update_box_office_pricing(role: Roles, should_test_succeed: bool) {
             true,
             None,
             None,
+            None,
             connection,
         )
         .unwrap();
@@ -50,7 +51,10 @@ pub async fn update_box_office_pricing(role: Roles, should_test_succeed: bool) {
         database.connection.clone().into(),
         input,
         auth_user,
-        RequestInfo { user_agent: None },
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
     ))
     .await
     .into();
@@ -99,6 +103,7 @@ pub async fn replace_box_office_pricing(role: Roles, should_test_succeed: bool)
             true,
             None,
             None,
+            None,
             connection,
         )
         .unwrap();
@@ -145,7 +150,10 @@ pub async fn replace_box_office_pricing(role: Roles, should_test_succeed: bool)
         database.connection.clone().into(),
         input,
         auth_user,
-        RequestInfo { user_agent: None },
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
     ))
     .await
     .into();
@@ -0,0 +1,168 @@
+use crate::support;
+use crate::support::database::TestDatabase;
+use crate::support::test_request::TestRequest;
+use actix_web::{http::StatusCode, web::Path, FromRequest, HttpResponse};
+use api::controllers::communication_templates::{
+    self, CommunicationTemplatePreviewResponse, PreviewCommunicationTemplateRequest,
+    RollbackCommunicationTemplateRequest, SaveCommunicationTemplateRequest,
+};
+use api::extractors::*;
+use api::models::PathParameters;
+use db::prelude::*;
+use serde_json;
+
+fn create_template(organization: &Organization, database: &TestDatabase) -> CommunicationTemplate {
+    let connection = database.connection.get();
+    CommunicationTemplate::create(
+        Some(organization.id),
+        CommunicationTemplateNames::UserInvite,
+        CommunicationType::EmailTemplate,
+        "Invitation".to_string(),
+        "Hi {{name}}".to_string(),
+    )
+    .commit(None, connection)
+    .unwrap();
+    CommunicationTemplate::create(
+        Some(organization.id),
+        CommunicationTemplateNames::UserInvite,
+        CommunicationType::EmailTemplate,
+        "Your invitation".to_string(),
+        "Hi {{name}}, accept at {{invite_link}}".to_string(),
+    )
+    .commit(None, connection)
+    .unwrap()
+}
+
+pub async fn index(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+    let template = create_template(&organization, &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = organization.id;
+    let response: HttpResponse = communication_templates::index((database.connection.clone().into(), path, auth_user))
+        .await
+        .into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let templates: Vec<CommunicationTemplate> = serde_json::from_str(&body).unwrap();
+    assert_eq!(templates, vec![template]);
+}
+
+pub async fn create(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = organization.id;
+    let json = Json(SaveCommunicationTemplateRequest {
+        template_name: CommunicationTemplateNames::OrgInvite,
+        communication_type: CommunicationType::EmailTemplate,
+        subject: "Join {{org}}".to_string(),
+        body: "Hi {{name}}, accept at {{invite_link_accept}}".to_string(),
+    });
+    let response: HttpResponse =
+        communication_templates::create((database.connection.clone().into(), path, json, auth_user))
+            .await
+            .into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let template: CommunicationTemplate = serde_json::from_str(&body).unwrap();
+    assert_eq!(template.organization_id, Some(organization.id));
+    assert_eq!(template.version, 1);
+}
+
+pub async fn preview(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = organization.id;
+    let json = Json(PreviewCommunicationTemplateRequest {
+        template_name: CommunicationTemplateNames::OrgInvite,
+        communication_type: CommunicationType::EmailTemplate,
+        subject: Some("Join {{org}}".to_string()),
+        body: Some("Hi {{name}}".to_string()),
+    });
+    let response: HttpResponse =
+        communication_templates::preview((database.connection.clone().into(), path, json, auth_user))
+            .await
+            .into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let preview: CommunicationTemplatePreviewResponse = serde_json::from_str(&body).unwrap();
+    assert_eq!(preview.subject, "Join Sample Organization");
+    assert_eq!(preview.body, "Hi Jordan Smith");
+    assert!(preview.variables.contains(&"invite_link_accept".to_string()));
+}
+
+pub async fn versions(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+    let template = create_template(&organization, &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = template.id;
+    let response: HttpResponse =
+        communication_templates::versions((database.connection.clone().into(), path, auth_user))
+            .await
+            .into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let versions: Vec<CommunicationTemplateVersion> = serde_json::from_str(&body).unwrap();
+    assert_eq!(versions.iter().map(|v| v.version).collect::<Vec<i32>>(), vec![2, 1]);
+}
+
+pub async fn rollback(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+    let template = create_template(&organization, &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = template.id;
+    let json = Json(RollbackCommunicationTemplateRequest { version: 1 });
+    let response: HttpResponse =
+        communication_templates::rollback((database.connection.clone().into(), path, json, auth_user))
+            .await
+            .into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let template: CommunicationTemplate = serde_json::from_str(&body).unwrap();
+    assert_eq!(template.version, 3);
+    assert_eq!(template.subject, "Invitation");
+}
@@ -0,0 +1,115 @@
+use crate::support;
+use crate::support::database::TestDatabase;
+use crate::support::test_request::TestRequest;
+use actix_web::{http::StatusCode, web::Path, FromRequest, HttpResponse};
+use api::controllers::event_series::{self, EventSeriesResponse, NewEventSeriesRequest};
+use api::extractors::*;
+use api::models::PathParameters;
+use chrono::prelude::*;
+use chrono::Duration;
+use db::models::*;
+use db::utils::dates;
+use serde_json;
+
+pub async fn create(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let event_start = NaiveDateTime::from_timestamp(dates::now().add_days(7).finish().timestamp(), 0);
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_event_start(event_start)
+        .with_ticket_pricing()
+        .finish();
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = organization.id;
+    let json = Json(NewEventSeriesRequest {
+        template_event_id: event.id,
+        name: "Friday Night Comedy".to_string(),
+        recurrence_type: RecurrenceTypes::Weekly,
+        recurrence_interval: 1,
+        recurrence_until: Some(event_start + Duration::weeks(3)),
+        custom_dates: vec![],
+    });
+    let response: HttpResponse = event_series::create((
+        database.connection.clone().into(),
+        path,
+        json,
+        auth_user,
+        test_request.extract_state().await,
+    ))
+    .await
+    .into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let response: EventSeriesResponse = serde_json::from_str(&body).unwrap();
+    assert_eq!(response.event_series.name, "Friday Night Comedy");
+    assert_eq!(response.event_series.template_event_id, event.id);
+    assert_eq!(response.generated_events.len(), 3);
+    for (index, generated_event) in response.generated_events.iter().enumerate() {
+        assert_eq!(generated_event.event_series_id, Some(response.event_series.id));
+        assert_eq!(
+            generated_event.event_start,
+            Some(event_start + Duration::weeks(index as i64 + 1))
+        );
+    }
+}
+
+pub async fn update_events(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let event_start = NaiveDateTime::from_timestamp(dates::now().add_days(7).finish().timestamp(), 0);
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_event_start(event_start)
+        .finish();
+    let event_series = EventSeries::create(
+        organization.id,
+        event.id,
+        "Friday Night Comedy".to_string(),
+        RecurrenceTypes::Weekly,
+        1,
+        Some(event_start + Duration::weeks(1)),
+        vec![],
+    )
+    .commit(None, connection)
+    .unwrap();
+    event_series.generate_occurrences(None, connection).unwrap();
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = event_series.id;
+    let json = Json(EventEditableAttributes {
+        top_line_info: Some(Some("Two drink minimum".to_string())),
+        ..Default::default()
+    });
+    let response: HttpResponse =
+        event_series::update_events((database.connection.clone().into(), path, json, auth_user))
+            .await
+            .into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let events: Vec<Event> = serde_json::from_str(&body).unwrap();
+    assert_eq!(events.len(), 2);
+    for event in events {
+        assert_eq!(event.top_line_info, Some("Two drink minimum".to_string()));
+    }
+}
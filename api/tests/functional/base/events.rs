@@ -136,6 +136,60 @@ pub async fn redeem_ticket(role: Roles, should_test_succeed: bool) {
     } else {
         support::expects_unauthorized(&response);
     }
+
+    pub async fn redeem_offline_tickets(role: Roles, should_test_succeed: bool) {
+        let database = TestDatabase::new();
+        let conn = database.connection.get();
+        let user = database.create_user().finish();
+        let request = TestRequest::create_with_uri_custom_params("/", vec!["id"]);
+        let organization = database.create_organization().finish();
+        let event = database
+            .create_event()
+            .with_organization(&organization)
+            .with_ticket_pricing()
+            .finish();
+        let user2 = database.create_user().finish();
+        let ticket_type = event.ticket_types(true, None, conn).unwrap()[0].id;
+        let ticket = database.create_purchased_tickets(&user2, ticket_type, 2).remove(0);
+        let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+
+        let mut path = Path::<PathParameters>::extract(&request.request).await.unwrap();
+        path.id = event.id;
+        let scanned_at = NaiveDateTime::from_timestamp((Utc::now() - Duration::minutes(10)).timestamp(), 0);
+        let request_data = OfflineRedemptionsRequest {
+            redemptions: vec![OfflineRedemption {
+                ticket_id: ticket.id,
+                redeem_key: ticket.redeem_key.clone().unwrap(),
+                scanned_at,
+            }],
+        };
+
+        let response: HttpResponse = events::redeem_offline_tickets((
+            database.connection.clone().into(),
+            path,
+            Json(request_data),
+            auth_user,
+            request.extract_state().await,
+            CacheDatabase { inner: None },
+        ))
+        .await
+        .into();
+
+        if should_test_succeed {
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = support::unwrap_body_to_string(&response).unwrap();
+            let results: Vec<OfflineRedemptionResult> = serde_json::from_str(&body).unwrap();
+            assert_eq!(results.len(), 1);
+            assert_eq!(results[0].status, OfflineRedemptionStatus::Redeemed);
+
+            let ticket = TicketInstance::find(ticket.id, conn).unwrap();
+            assert_eq!(ticket.status, TicketInstanceStatus::Redeemed);
+            assert_eq!(ticket.redeemed_at, Some(scanned_at));
+            assert_eq!(ticket.check_in_source, Some(CheckInSource::Scanned));
+        } else {
+            support::expects_unauthorized(&response);
+        }
+    }
 }
 
 pub async fn export_event_data(role: Roles, should_test_succeed: bool, past_or_upcoming: Option<PastOrUpcoming>) {
@@ -252,6 +306,7 @@ pub async fn show_box_office_pricing(role: Roles, should_test_succeed: bool) {
             true,
             None,
             None,
+            None,
             conn,
         )
         .unwrap();
@@ -279,6 +334,7 @@ pub async fn show_box_office_pricing(role: Roles, should_test_succeed: bool) {
         OptionalUser(Some(auth_user)),
         RequestInfo {
             user_agent: Some("test".to_string()),
+            ip_address: None,
         },
     ))
     .await
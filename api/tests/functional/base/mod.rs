@@ -1,16 +1,24 @@
 pub mod announcements;
+pub mod api_keys;
 pub mod artists;
+pub mod bundles;
 pub mod cart;
 pub mod codes;
 pub mod collections;
+pub mod communication_templates;
 pub mod comps;
 pub mod event_report_subscribers;
+pub mod event_series;
 pub mod events;
 pub mod holds;
 pub mod notes;
 pub mod orders;
+pub mod organization_events_stream;
 pub mod organization_invites;
+pub mod organization_roles;
+pub mod organization_sso_configurations;
 pub mod organization_venues;
+pub mod organization_webhooks;
 pub mod organizations;
 pub mod regions;
 pub mod reports;
@@ -18,8 +26,10 @@ pub mod reports_admin;
 pub mod settlement_adjustments;
 pub mod settlements;
 pub mod stages;
+pub mod tax_rules;
 pub mod ticket_types;
 pub mod tickets;
 pub mod transfers;
 pub mod users;
 pub mod venues;
+pub mod waitlist_entries;
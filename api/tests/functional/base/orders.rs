@@ -432,6 +432,7 @@ pub async fn refund(role: Roles, manual_override: bool, should_succeed: bool) {
         items: refund_items,
         reason: None,
         manual_override,
+        refund_as_account_credit: false,
     });
 
     let test_request = TestRequest::create();
@@ -0,0 +1,93 @@
+use crate::support;
+use crate::support::database::TestDatabase;
+use crate::support::test_request::TestRequest;
+use actix_web::{
+    http::{header, StatusCode},
+    web::{Path, Query},
+    FromRequest, HttpResponse,
+};
+use api::controllers::organization_events_stream::{self, DomainEventStreamParameters};
+use api::models::PathParameters;
+use chrono::{Duration, Utc};
+use db::prelude::*;
+use serde_json;
+
+/// Streamed events are held back until they settle so test events are created in the past
+fn create_settled_domain_event(organization: &Organization, database: &TestDatabase) -> DomainEvent {
+    let mut domain_event = DomainEvent::create(
+        DomainEventTypes::OrganizationCreated,
+        "Organization created".to_string(),
+        Tables::Organizations,
+        Some(organization.id),
+        None,
+        None,
+    );
+    domain_event.created_at = Some(Utc::now().naive_utc() - Duration::minutes(1));
+    domain_event.commit(database.connection.get()).unwrap()
+}
+
+pub async fn index(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+    let domain_event = create_settled_domain_event(&organization, &database);
+    let domain_event2 = create_settled_domain_event(&organization, &database);
+
+    let test_request = TestRequest::create_with_uri(&format!(
+        "/organizations/{}/events_stream?after_seq={}",
+        organization.id, domain_event.seq
+    ));
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = organization.id;
+    let query = Query::<DomainEventStreamParameters>::extract(&test_request.request)
+        .await
+        .unwrap();
+    let response: HttpResponse =
+        organization_events_stream::index((database.connection.clone().into(), path, query, auth_user))
+            .await
+            .into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let page: DomainEventStreamPage = serde_json::from_str(&body).unwrap();
+    assert_eq!(page.data, vec![domain_event2.clone()]);
+    assert_eq!(page.next_seq, domain_event2.seq);
+    assert!(!page.has_more);
+}
+
+pub async fn live(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+
+    let test_request = TestRequest::create_with_uri(&format!("/organizations/{}/events_stream/live", organization.id));
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = organization.id;
+    let query = Query::<DomainEventStreamParameters>::extract(&test_request.request)
+        .await
+        .unwrap();
+    let response: HttpResponse = organization_events_stream::live((
+        database.connection.clone().into(),
+        path,
+        query,
+        auth_user,
+        test_request.request.clone(),
+        test_request.extract_state().await,
+    ))
+    .await
+    .into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get(header::CONTENT_TYPE).unwrap().to_str().unwrap(),
+        "text/event-stream"
+    );
+}
@@ -0,0 +1,87 @@
+use crate::support;
+use crate::support::database::TestDatabase;
+use crate::support::test_request::TestRequest;
+use actix_web::{http::StatusCode, web::Path, FromRequest, HttpResponse};
+use api::controllers::organization_roles::{self, NewOrganizationRoleRequest, OrganizationUserRolesRequest};
+use api::extractors::*;
+use api::models::{OrganizationUserPathParameters, PathParameters};
+use db::models::*;
+use serde_json;
+
+pub async fn create(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = organization.id;
+    let json = Json(NewOrganizationRoleRequest {
+        name: "Marketing".to_string(),
+        description: None,
+        scopes: vec![Scopes::OrgFans, Scopes::EventBroadcast],
+    });
+    let response: HttpResponse =
+        organization_roles::create((database.connection.clone().into(), path, json, auth_user))
+            .await
+            .into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let organization_role: OrganizationRole = serde_json::from_str(&body).unwrap();
+    assert_eq!(organization_role.name, "Marketing");
+    assert_eq!(organization_role.organization_id, organization.id);
+    assert_eq!(
+        organization_role.scopes,
+        vec!["org:fans".to_string(), "event:broadcast".to_string()]
+    );
+}
+
+pub async fn update_user_roles(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let member = database.create_user().finish();
+    let organization = database
+        .create_organization()
+        .with_member(&member, Roles::OrgMember)
+        .finish();
+    let organization_role = OrganizationRole::create(
+        organization.id,
+        "Finance read-only".to_string(),
+        None,
+        vec![Scopes::SettlementRead],
+    )
+    .commit(None, connection)
+    .unwrap();
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+
+    let test_request = TestRequest::create_with_uri_custom_params("/", vec!["id", "user_id"]);
+    let mut path = Path::<OrganizationUserPathParameters>::extract(&test_request.request)
+        .await
+        .unwrap();
+    path.id = organization.id;
+    path.user_id = member.id;
+    let json = Json(OrganizationUserRolesRequest {
+        organization_role_ids: vec![organization_role.id],
+    });
+    let response: HttpResponse =
+        organization_roles::update_user_roles((database.connection.clone().into(), path, json, auth_user))
+            .await
+            .into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(organization
+        .get_scopes_for_user(&member, connection)
+        .unwrap()
+        .contains(&Scopes::SettlementRead));
+}
@@ -0,0 +1,119 @@
+use crate::support;
+use crate::support::database::TestDatabase;
+use crate::support::test_request::TestRequest;
+use actix_web::{http::StatusCode, web::Path, FromRequest, HttpResponse};
+use api::controllers::organization_sso_configurations::{self, NewOrganizationSsoConfigurationRequest};
+use api::extractors::*;
+use api::models::PathParameters;
+use db::models::*;
+use serde_json;
+use std::collections::HashMap;
+
+fn create_configuration(
+    organization: &Organization,
+    encryption_key: &str,
+    database: &TestDatabase,
+) -> OrganizationSsoConfiguration {
+    OrganizationSsoConfiguration::create(
+        organization.id,
+        "https://login.example.com".to_string(),
+        "client-id".to_string(),
+        "client-secret".to_string(),
+        Roles::OrgMember,
+        None,
+        HashMap::new(),
+    )
+    .commit(encryption_key, None, database.connection.get())
+    .unwrap()
+}
+
+pub async fn show(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state().await;
+    let configuration = create_configuration(&organization, &state.config.api_keys_encryption_key, &database);
+
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = organization.id;
+    let response: HttpResponse =
+        organization_sso_configurations::show((database.connection.clone().into(), path, auth_user))
+            .await
+            .into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let found_configuration: OrganizationSsoConfiguration = serde_json::from_str(&body).unwrap();
+    assert_eq!(found_configuration.id, configuration.id);
+    // The client secret is never exposed
+    assert!(!body.contains(&configuration.encrypted_client_secret));
+}
+
+pub async fn create(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state().await;
+
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = organization.id;
+    let mut role_mappings = HashMap::new();
+    role_mappings.insert("box-office".to_string(), Roles::OrgBoxOffice);
+    let json = Json(NewOrganizationSsoConfigurationRequest {
+        issuer_url: "https://login.example.com".to_string(),
+        client_id: "client-id".to_string(),
+        client_secret: "client-secret".to_string(),
+        default_role: Roles::OrgMember,
+        role_claim: Some("groups".to_string()),
+        role_mappings,
+    });
+    let response: HttpResponse =
+        organization_sso_configurations::create((database.connection.clone().into(), path, json, auth_user, state))
+            .await
+            .into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let configuration =
+        OrganizationSsoConfiguration::find_for_organization(organization.id, database.connection.get()).unwrap();
+    assert_eq!(configuration.client_id, "client-id");
+    assert_eq!(configuration.role_for(&["box-office".to_string()]), Roles::OrgBoxOffice);
+}
+
+pub async fn update(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state().await;
+    create_configuration(&organization, &state.config.api_keys_encryption_key, &database);
+
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = organization.id;
+    let json = Json(OrganizationSsoConfigurationEditableAttributes {
+        enabled: Some(false),
+        ..Default::default()
+    });
+    let response: HttpResponse =
+        organization_sso_configurations::update((database.connection.clone().into(), path, json, auth_user, state))
+            .await
+            .into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let configuration =
+        OrganizationSsoConfiguration::find_for_organization(organization.id, database.connection.get()).unwrap();
+    assert!(!configuration.enabled);
+}
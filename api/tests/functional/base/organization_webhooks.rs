@@ -0,0 +1,240 @@
+use crate::support;
+use crate::support::database::TestDatabase;
+use crate::support::test_request::TestRequest;
+use actix_web::{
+    http::StatusCode,
+    web::{Path, Query},
+    FromRequest, HttpResponse,
+};
+use api::controllers::organization_webhooks::{
+    self, CreatedOrganizationWebhookResponse, NewOrganizationWebhookRequest,
+};
+use api::extractors::*;
+use api::models::PathParameters;
+use db::prelude::*;
+use serde_json;
+
+fn create_delivery(webhook: &DomainEventPublisher, failed: bool, database: &TestDatabase) -> WebhookDelivery {
+    let connection = database.connection.get();
+    let domain_event = DomainEvent::create(
+        DomainEventTypes::OrderCompleted,
+        "Order completed".to_string(),
+        Tables::Orders,
+        None,
+        None,
+        None,
+    )
+    .commit(connection)
+    .unwrap();
+    let mut delivery = WebhookDelivery::create(webhook.id, domain_event.id, "{}".to_string())
+        .commit(connection)
+        .unwrap();
+    if failed {
+        for _ in 0..WEBHOOK_DELIVERY_MAX_ATTEMPTS {
+            delivery = delivery
+                .record_attempt(Some(500), 50, Some("Internal Server Error"), None, connection)
+                .unwrap();
+        }
+    }
+    delivery
+}
+
+pub async fn index(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+    let webhook = database
+        .create_domain_event_publisher()
+        .with_organization(&organization)
+        .with_signing_secret("whsec_test")
+        .finish();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = organization.id;
+    let response: HttpResponse = organization_webhooks::index((database.connection.clone().into(), path, auth_user))
+        .await
+        .into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let webhooks: Vec<DomainEventPublisher> = serde_json::from_str(&body).unwrap();
+    assert_eq!(webhooks.len(), 1);
+    assert_eq!(webhooks[0].id, webhook.id);
+    // The signing secret is never exposed after creation
+    assert!(!body.contains(webhook.encrypted_signing_secret.as_ref().unwrap()));
+}
+
+pub async fn create(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state().await;
+    let encryption_key = state.config.api_keys_encryption_key.clone();
+
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = organization.id;
+    let json = Json(NewOrganizationWebhookRequest {
+        webhook_url: "https://example.com/webhook".to_string(),
+        event_types: vec![DomainEventTypes::OrderCompleted, DomainEventTypes::OrderRefund],
+    });
+    let response: HttpResponse =
+        organization_webhooks::create((database.connection.clone().into(), path, json, auth_user, state))
+            .await
+            .into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let created: CreatedOrganizationWebhookResponse = serde_json::from_str(&body).unwrap();
+    assert!(created.signing_secret.starts_with("whsec_"));
+
+    let webhook = DomainEventPublisher::find(created.webhook.id, database.connection.get()).unwrap();
+    assert_eq!(webhook.organization_id, Some(organization.id));
+    assert_eq!(webhook.webhook_url, "https://example.com/webhook".to_string());
+    assert_eq!(
+        webhook.event_types,
+        vec![DomainEventTypes::OrderCompleted, DomainEventTypes::OrderRefund]
+    );
+    assert_eq!(
+        webhook.signing_secret(&encryption_key).unwrap(),
+        Some(created.signing_secret)
+    );
+}
+
+pub async fn update(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+    let webhook = database
+        .create_domain_event_publisher()
+        .with_organization(&organization)
+        .with_signing_secret("whsec_test")
+        .finish();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = webhook.id;
+    let json = Json(DomainEventPublisherEditableAttributes {
+        webhook_url: Some("https://example.com/updated".to_string()),
+        import_historic_events: None,
+        event_types: Some(vec![DomainEventTypes::OrderRefund]),
+    });
+    let response: HttpResponse =
+        organization_webhooks::update((database.connection.clone().into(), path, json, auth_user))
+            .await
+            .into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let webhook = DomainEventPublisher::find(webhook.id, database.connection.get()).unwrap();
+    assert_eq!(webhook.webhook_url, "https://example.com/updated".to_string());
+    assert_eq!(webhook.event_types, vec![DomainEventTypes::OrderRefund]);
+}
+
+pub async fn destroy(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+    let webhook = database
+        .create_domain_event_publisher()
+        .with_organization(&organization)
+        .with_signing_secret("whsec_test")
+        .finish();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = webhook.id;
+    let response: HttpResponse = organization_webhooks::destroy((database.connection.clone().into(), path, auth_user))
+        .await
+        .into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let webhook = DomainEventPublisher::find(webhook.id, database.connection.get()).unwrap();
+    assert!(webhook.deleted_at.is_some());
+}
+
+pub async fn deliveries(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+    let webhook = database
+        .create_domain_event_publisher()
+        .with_organization(&organization)
+        .with_signing_secret("whsec_test")
+        .finish();
+    let delivery = create_delivery(&webhook, true, &database);
+
+    let test_request = TestRequest::create();
+    let query_parameters = Query::<PagingParameters>::extract(&test_request.request).await.unwrap();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = webhook.id;
+    let response: HttpResponse =
+        organization_webhooks::deliveries((database.connection.clone().into(), path, query_parameters, auth_user))
+            .await
+            .into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let deliveries: Payload<DisplayWebhookDelivery> = serde_json::from_str(&body).unwrap();
+    assert_eq!(deliveries.data.len(), 1);
+    assert_eq!(deliveries.data[0].id, delivery.id);
+    assert_eq!(deliveries.data[0].status, WebhookDeliveryStatus::Failed);
+    assert_eq!(
+        deliveries.data[0].attempts.len(),
+        WEBHOOK_DELIVERY_MAX_ATTEMPTS as usize
+    );
+    assert_eq!(deliveries.data[0].attempts[0].response_status, Some(500));
+    assert_eq!(
+        deliveries.data[0].attempts[0].response_body_excerpt,
+        Some("Internal Server Error".to_string())
+    );
+}
+
+pub async fn replay_delivery(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(role, Some(&organization), &database);
+    let webhook = database
+        .create_domain_event_publisher()
+        .with_organization(&organization)
+        .with_signing_secret("whsec_test")
+        .finish();
+    let delivery = create_delivery(&webhook, true, &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = delivery.id;
+    let response: HttpResponse =
+        organization_webhooks::replay_delivery((database.connection.clone().into(), path, auth_user))
+            .await
+            .into();
+
+    if !should_test_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let delivery = WebhookDelivery::find(delivery.id, database.connection.get()).unwrap();
+    assert_eq!(delivery.status, WebhookDeliveryStatus::Pending);
+    assert_eq!(delivery.attempt_count, 0);
+}
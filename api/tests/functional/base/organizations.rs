@@ -186,6 +186,9 @@ pub async fn create(role: Roles, should_test_succeed: bool) {
         globee_api_key: None,
         max_instances_per_ticket_type: Some(11000),
         settlement_type: None,
+        currency: None,
+        transfer_expiry_hours: None,
+        resale_fee_percent: None,
     });
 
     let test_request = TestRequest::create_with_uri("/organizations");
@@ -197,6 +197,7 @@ pub async fn box_office_sales_summary(role: Roles, should_succeed: bool) {
                     BoxOfficeSalesSummaryOperatorEventRow {
                         event_name: Some("Event1".to_string()),
                         event_date: Some(NaiveDate::from_ymd(2016, 7, 8).and_hms(9, 10, 11)),
+                        currency: "USD".to_string(),
                         number_of_tickets: 2,
                         face_value_in_cents: 150,
                         revenue_share_value_in_cents: 0,
@@ -205,6 +206,7 @@ pub async fn box_office_sales_summary(role: Roles, should_succeed: bool) {
                     BoxOfficeSalesSummaryOperatorEventRow {
                         event_name: Some("Event2".to_string()),
                         event_date: Some(NaiveDate::from_ymd(2017, 7, 8).and_hms(9, 10, 11)),
+                        currency: "USD".to_string(),
                         number_of_tickets: 2,
                         face_value_in_cents: 150,
                         revenue_share_value_in_cents: 0,
@@ -213,6 +215,7 @@ pub async fn box_office_sales_summary(role: Roles, should_succeed: bool) {
                 ],
                 payments: vec![BoxOfficeSalesSummaryPaymentRow {
                     payment_type: ExternalPaymentType::CreditCard,
+                    currency: "USD".to_string(),
                     quantity: 4,
                     total_sales_in_cents: 600,
                 }],
@@ -223,6 +226,7 @@ pub async fn box_office_sales_summary(role: Roles, should_succeed: bool) {
                 events: vec![BoxOfficeSalesSummaryOperatorEventRow {
                     event_name: Some("Event1".to_string()),
                     event_date: Some(NaiveDate::from_ymd(2016, 7, 8).and_hms(9, 10, 11)),
+                    currency: "USD".to_string(),
                     number_of_tickets: 2,
                     face_value_in_cents: 150,
                     revenue_share_value_in_cents: 0,
@@ -230,6 +234,7 @@ pub async fn box_office_sales_summary(role: Roles, should_succeed: bool) {
                 }],
                 payments: vec![BoxOfficeSalesSummaryPaymentRow {
                     payment_type: ExternalPaymentType::CreditCard,
+                    currency: "USD".to_string(),
                     quantity: 2,
                     total_sales_in_cents: 300,
                 }],
@@ -237,6 +242,7 @@ pub async fn box_office_sales_summary(role: Roles, should_succeed: bool) {
         ],
         payments: vec![BoxOfficeSalesSummaryPaymentRow {
             payment_type: ExternalPaymentType::CreditCard,
+            currency: "USD".to_string(),
             quantity: 6,
             total_sales_in_cents: 900,
         }],
@@ -484,6 +490,9 @@ fn build_transaction_report_row(
         client_fee_in_cents_total: fee_schedule_range.client_fee_in_cents * quantity,
         event_fee_client_in_cents: organization.client_event_fee_in_cents,
         event_fee_client_in_cents_total: organization.client_event_fee_in_cents,
+        tax_in_cents: 0,
+        tax_in_cents_total: 0,
+        currency: "USD".to_string(),
         fee_range_id: Some(fee_schedule_range.id),
         order_type: OrderTypes::Cart,
         payment_method: Some(PaymentMethods::CreditCard.to_string()),
@@ -5,7 +5,7 @@ use actix_web::{http::StatusCode, web::Path, FromRequest, HttpResponse};
 use api::controllers::stages;
 use api::extractors::*;
 use api::models::PathParameters;
-use db::models::{Roles, Stage, StageEditableAttributes};
+use db::models::{Roles, Stage, StageEditableAttributes, StageSeat, StageSection};
 use serde_json;
 
 pub async fn create(role: Roles, should_succeed: bool) {
@@ -64,3 +64,68 @@ pub async fn update(role: Roles, should_succeed: bool) {
     let updated_stage: Stage = serde_json::from_str(&body).unwrap();
     assert_eq!(updated_stage.name, new_name);
 }
+
+pub async fn create_section(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let venue = database.create_venue().finish();
+    let stage = database.create_stage().with_venue_id(venue.id).finish();
+    let name = "Balcony";
+
+    let user = support::create_auth_user(role, None, &database);
+    let json = Json(stages::CreateStageSection { name: name.to_string() });
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = stage.id;
+    let response: HttpResponse = stages::create_section((database.connection.into(), path, json, user))
+        .await
+        .into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let section: StageSection = serde_json::from_str(&body).unwrap();
+    assert_eq!(section.name, name);
+    assert_eq!(section.stage_id, stage.id);
+}
+
+pub async fn create_seats(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let venue = database.create_venue().finish();
+    let stage = database.create_stage().with_venue_id(venue.id).finish();
+    let section = database.create_stage_section().with_stage_id(stage.id).finish();
+
+    let user = support::create_auth_user(role, None, &database);
+    let json = Json(stages::CreateStageSeats {
+        seats: vec![
+            stages::CreateStageSeat {
+                row_name: "A".to_string(),
+                seat_number: "1".to_string(),
+            },
+            stages::CreateStageSeat {
+                row_name: "A".to_string(),
+                seat_number: "2".to_string(),
+            },
+        ],
+    });
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = section.id;
+    let response: HttpResponse = stages::create_seats((database.connection.into(), path, json, user))
+        .await
+        .into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let seats: Vec<StageSeat> = serde_json::from_str(&body).unwrap();
+    assert_eq!(seats.len(), 2);
+    assert!(seats.iter().all(|s| s.stage_section_id == section.id));
+}
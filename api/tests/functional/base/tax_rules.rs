@@ -0,0 +1,72 @@
+use crate::support;
+use crate::support::database::TestDatabase;
+use crate::support::test_request::TestRequest;
+use actix_web::{http::StatusCode, web::Path, FromRequest, HttpResponse};
+use api::controllers::tax_rules::{self, NewTaxRuleRequest};
+use api::extractors::*;
+use api::models::PathParameters;
+use db::models::{Roles, TaxRule, TaxRuleEditableAttributes};
+use serde_json;
+
+pub async fn create_for_venue(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let venue = database.create_venue().finish();
+    let name = "Sales Tax";
+
+    let user = support::create_auth_user(role, None, &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = venue.id;
+    let json = Json(NewTaxRuleRequest {
+        name: name.to_string(),
+        rate_percent: 8.5,
+        applies_to_fees: true,
+        inclusive: false,
+    });
+
+    let response: HttpResponse = tax_rules::create_for_venue((database.connection.into(), path, json, user))
+        .await
+        .into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let tax_rule: TaxRule = serde_json::from_str(&body).unwrap();
+    assert_eq!(tax_rule.name, name);
+    assert_eq!(tax_rule.venue_id, Some(venue.id));
+    assert_eq!(tax_rule.rate_percent, 8.5);
+    assert!(tax_rule.applies_to_fees);
+}
+
+pub async fn update(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let region = database.create_region().finish();
+    let tax_rule = TaxRule::create("VAT".to_string(), None, Some(region.id), 20.0, false, true)
+        .commit(connection)
+        .unwrap();
+
+    let user = support::create_auth_user(role, None, &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = tax_rule.id;
+
+    let mut attributes: TaxRuleEditableAttributes = Default::default();
+    attributes.rate_percent = Some(15.0);
+    let json = Json(attributes);
+
+    let response: HttpResponse = tax_rules::update((database.connection.clone().into(), path, json, user))
+        .await
+        .into();
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let updated_tax_rule: TaxRule = serde_json::from_str(&body).unwrap();
+    assert_eq!(updated_tax_rule.rate_percent, 15.0);
+}
@@ -55,6 +55,7 @@ pub async fn show_other_user_ticket(role: Roles, should_test_succeed: bool) {
             transfer_address: None,
             check_in_source: None,
             promo_image_url: None,
+            seat: None,
         };
 
         let expected_result = ShowTicketResponse {
@@ -123,6 +124,7 @@ pub async fn update(role: Roles, owns_ticket: bool, should_test_succeed: bool) {
             transfer_address: None,
             check_in_source: None,
             promo_image_url: None,
+            seat: None,
         };
 
         let expected_result = ShowTicketResponse {
@@ -0,0 +1,80 @@
+use crate::support;
+use crate::support::database::TestDatabase;
+use crate::support::test_request::TestRequest;
+use actix_web::{
+    http::StatusCode,
+    web::{Path, Query},
+    FromRequest,
+};
+use api::controllers::waitlist_entries::{self, *};
+use api::models::*;
+use db::models::*;
+use std::collections::HashMap;
+
+pub async fn index(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().with_event_fee().with_fees().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_a_specific_number_of_tickets(1)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    database
+        .create_order()
+        .for_event(&event)
+        .for_tickets(ticket_type.id)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let waiting_user = database.create_user().finish();
+    let entry = WaitlistEntry::create(ticket_type.id, waiting_user.id, 1)
+        .commit(connection)
+        .unwrap();
+    let auth_user = support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+
+    let test_request = TestRequest::create_with_uri_custom_params("/", vec!["event_id", "ticket_type_id"]);
+    let query_parameters = Query::<PagingParameters>::extract(&test_request.request).await.unwrap();
+    let filter_parameters = Query::<WaitlistFilterParameters>::extract(&test_request.request)
+        .await
+        .unwrap();
+    let mut path = Path::<EventTicketPathParameters>::extract(&test_request.request)
+        .await
+        .unwrap();
+    path.event_id = event.id;
+    path.ticket_type_id = ticket_type.id;
+
+    let response = waitlist_entries::index((
+        database.connection.clone().into(),
+        path,
+        query_parameters,
+        filter_parameters,
+        auth_user,
+    ))
+    .await;
+
+    if should_test_succeed {
+        let response = response.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let expected = Payload {
+            data: vec![entry],
+            paging: Paging {
+                page: 0,
+                limit: 100,
+                sort: "".to_string(),
+                dir: SortingDir::Asc,
+                total: 1,
+                tags: HashMap::new(),
+            },
+        };
+        assert_eq!(expected, *response.payload());
+    } else {
+        assert_eq!(
+            response.err().unwrap().to_string(),
+            "User does not have the required permissions"
+        );
+    }
+}
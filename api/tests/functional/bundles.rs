@@ -0,0 +1,156 @@
+use crate::functional::base;
+use crate::support;
+use crate::support::database::TestDatabase;
+use crate::support::test_request::TestRequest;
+use actix_web::{http::StatusCode, web::Path, FromRequest, HttpResponse};
+use api::controllers::bundles;
+use api::models::PathParameters;
+use db::models::*;
+use serde_json;
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+
+    #[actix_rt::test]
+    async fn create_org_member() {
+        base::bundles::create(Roles::OrgMember, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_admin() {
+        base::bundles::create(Roles::Admin, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_super() {
+        base::bundles::create(Roles::Super, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_user() {
+        base::bundles::create(Roles::User, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_org_owner() {
+        base::bundles::create(Roles::OrgOwner, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_door_person() {
+        base::bundles::create(Roles::DoorPerson, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_promoter() {
+        base::bundles::create(Roles::Promoter, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_promoter_read_only() {
+        base::bundles::create(Roles::PromoterReadOnly, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_org_admin() {
+        base::bundles::create(Roles::OrgAdmin, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_box_office() {
+        base::bundles::create(Roles::OrgBoxOffice, false).await;
+    }
+}
+
+#[cfg(test)]
+mod add_ticket_type_tests {
+    use super::*;
+
+    #[actix_rt::test]
+    async fn add_ticket_type_org_member() {
+        base::bundles::add_ticket_type(Roles::OrgMember, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn add_ticket_type_admin() {
+        base::bundles::add_ticket_type(Roles::Admin, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn add_ticket_type_super() {
+        base::bundles::add_ticket_type(Roles::Super, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn add_ticket_type_user() {
+        base::bundles::add_ticket_type(Roles::User, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn add_ticket_type_org_owner() {
+        base::bundles::add_ticket_type(Roles::OrgOwner, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn add_ticket_type_door_person() {
+        base::bundles::add_ticket_type(Roles::DoorPerson, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn add_ticket_type_promoter() {
+        base::bundles::add_ticket_type(Roles::Promoter, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn add_ticket_type_promoter_read_only() {
+        base::bundles::add_ticket_type(Roles::PromoterReadOnly, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn add_ticket_type_org_admin() {
+        base::bundles::add_ticket_type(Roles::OrgAdmin, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn add_ticket_type_box_office() {
+        base::bundles::add_ticket_type(Roles::OrgBoxOffice, false).await;
+    }
+}
+
+#[actix_rt::test]
+async fn show() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let bundle = Bundle::create(organization.id, "Festival Pass".to_string(), "USD".to_string())
+        .commit(None, connection)
+        .unwrap();
+    bundle
+        .add_ticket_type(ticket_type.id, 1, 1500, None, connection)
+        .unwrap();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = bundle.id;
+    let response: HttpResponse = bundles::show((database.connection.clone().into(), path)).await.into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let display_bundle: DisplayBundle = serde_json::from_str(&body).unwrap();
+    assert_eq!(display_bundle, bundle.for_display(connection).unwrap());
+
+    // Deleted bundles are no longer shown
+    bundle.destroy(None, connection).unwrap();
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = bundle.id;
+    let response: HttpResponse = bundles::show((database.connection.clone().into(), path)).await.into();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
@@ -10,9 +10,10 @@ use actix_web::{
 use api::controllers;
 use api::controllers::cart;
 use api::controllers::cart::*;
-use api::domain_events::executors::ProcessPaymentIPNExecutor;
+use api::domain_events::executors::{ProcessPaymentIPNExecutor, ProcessPaymentProviderEventExecutor};
 use api::extractors::*;
 use api::models::*;
+use api::payments::mock::*;
 use chrono::prelude::*;
 use chrono::Duration;
 use db::models::*;
@@ -379,7 +380,10 @@ async fn update() {
         database.connection.clone().into(),
         input,
         auth_user,
-        RequestInfo { user_agent: None },
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
     ))
     .await
     .unwrap();
@@ -398,6 +402,168 @@ async fn update() {
     assert_eq!(order_item.unit_price_in_cents, ticket_pricing.price_in_cents);
 }
 
+#[actix_rt::test]
+async fn reserve_seats() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let venue = database.create_venue().finish();
+    let stage = database.create_stage().with_venue_id(venue.id).finish();
+    let section = database
+        .create_stage_section()
+        .with_stage_id(stage.id)
+        .with_seats(2)
+        .finish();
+    let seat_ids: Vec<Uuid> = section.seats(connection).unwrap().iter().map(|s| s.id).collect();
+    let event = database
+        .create_event()
+        .with_venue(&venue)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    ticket_type.assign_seats(&seat_ids, None, connection).unwrap();
+
+    let user = database.create_user().finish();
+    let input = Json(cart::ReserveSeatsRequest {
+        ticket_type_id: ticket_type.id,
+        seat_ids: vec![seat_ids[0]],
+    });
+
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let response = cart::reserve_seats((
+        database.connection.clone().into(),
+        input,
+        auth_user,
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
+    ))
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let cart = Order::find_cart_for_user(user.id, &connection).unwrap().unwrap();
+    let items = cart.items(&connection).unwrap();
+    let order_item = items.iter().find(|i| i.ticket_type_id == Some(ticket_type.id)).unwrap();
+    assert_eq!(order_item.quantity, 1);
+    let tickets = TicketInstance::find_for_order_item(order_item.id, connection).unwrap();
+    assert_eq!(tickets.len(), 1);
+    assert_eq!(tickets[0].seat_id, Some(seat_ids[0]));
+}
+
+#[actix_rt::test]
+async fn add_listing() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let event = database.create_event().with_tickets().with_ticket_pricing().finish();
+    let seller = database.create_user().finish();
+    let user = database.create_user().finish();
+    database
+        .create_order()
+        .for_event(&event)
+        .for_user(&seller)
+        .quantity(2)
+        .is_paid()
+        .finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let wallet = seller.default_wallet(connection).unwrap();
+    let mut listing = Listing::create("Listing".to_string(), seller.id, 500)
+        .commit(connection)
+        .unwrap();
+    listing
+        .add_tickets(Some(seller.id), wallet.id, ticket_type.id, 2, connection)
+        .unwrap();
+    let listing = listing.publish(Some(seller.id), connection).unwrap();
+
+    let input = Json(cart::AddListingRequest { listing_id: listing.id });
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let response = cart::add_listing((
+        database.connection.clone().into(),
+        input,
+        auth_user,
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
+    ))
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let cart = Order::find_cart_for_user(user.id, &connection).unwrap().unwrap();
+    let items = cart.items(&connection).unwrap();
+    let order_item = items.iter().find(|i| i.listing_id == Some(listing.id)).unwrap();
+    assert_eq!(order_item.item_type, OrderItemTypes::ResaleTickets);
+    assert_eq!(order_item.quantity, 2);
+    assert_eq!(order_item.unit_price_in_cents, 500);
+    assert_eq!(cart.calculate_total(connection).unwrap(), 1000);
+    assert!(!listing.is_available(None, connection).unwrap());
+}
+
+#[actix_rt::test]
+async fn update_bundle() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let event2 = database
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let ticket_type2 = event2.ticket_types(true, None, connection).unwrap().remove(0);
+    let bundle = Bundle::create(organization.id, "Festival Pass".to_string(), "USD".to_string())
+        .commit(None, connection)
+        .unwrap();
+    bundle
+        .add_ticket_type(ticket_type.id, 1, 1500, None, connection)
+        .unwrap();
+    bundle
+        .add_ticket_type(ticket_type2.id, 1, 1000, None, connection)
+        .unwrap();
+
+    let user = database.create_user().finish();
+    let input = Json(cart::UpdateBundleRequest {
+        bundle_id: bundle.id,
+        quantity: 2,
+    });
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let response = cart::update_bundle((
+        database.connection.clone().into(),
+        input,
+        auth_user,
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
+    ))
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let cart = Order::find_cart_for_user(user.id, &connection).unwrap().unwrap();
+    let items = cart.items(&connection).unwrap();
+    let order_item = items.iter().find(|i| i.ticket_type_id == Some(ticket_type.id)).unwrap();
+    assert_eq!(order_item.bundle_id, Some(bundle.id));
+    assert_eq!(order_item.quantity, 2);
+    assert_eq!(order_item.unit_price_in_cents, 1500);
+    let order_item2 = items
+        .iter()
+        .find(|i| i.ticket_type_id == Some(ticket_type2.id))
+        .unwrap();
+    assert_eq!(order_item2.bundle_id, Some(bundle.id));
+    assert_eq!(order_item2.quantity, 2);
+    assert_eq!(order_item2.unit_price_in_cents, 1000);
+}
+
 #[actix_rt::test]
 async fn update_with_draft_event() {
     let database = TestDatabase::new();
@@ -427,7 +593,10 @@ async fn update_with_draft_event() {
         database.connection.clone().into(),
         input,
         auth_user,
-        RequestInfo { user_agent: None },
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
     ))
     .await
     .unwrap();
@@ -470,7 +639,10 @@ async fn update_multiple() {
         database.connection.clone().into(),
         input,
         auth_user,
-        RequestInfo { user_agent: None },
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
     ))
     .await
     .unwrap();
@@ -533,7 +705,10 @@ async fn add_with_increment() {
         database.connection.clone(),
         input,
         auth_user,
-        RequestInfo { user_agent: None },
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
     ))
     .await
     .unwrap();
@@ -582,7 +757,10 @@ async fn update_with_increment_failure_invalid_quantity() {
         database.connection.clone().into(),
         input,
         auth_user,
-        RequestInfo { user_agent: None },
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
     ))
     .await
     .into();
@@ -622,7 +800,10 @@ async fn update_with_existing_cart() {
         database.connection.clone().into(),
         input,
         auth_user,
-        RequestInfo { user_agent: None },
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
     ))
     .await
     .unwrap();
@@ -684,7 +865,10 @@ async fn reduce() {
         database.connection.clone().into(),
         input,
         auth_user,
-        RequestInfo { user_agent: None },
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
     ))
     .await
     .unwrap();
@@ -750,7 +934,10 @@ async fn remove() {
         database.connection.clone().into(),
         input,
         auth_user,
-        RequestInfo { user_agent: None },
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
     ))
     .await
     .unwrap();
@@ -818,7 +1005,10 @@ async fn remove_with_increment() {
         database.connection.clone().into(),
         input,
         auth_user,
-        RequestInfo { user_agent: None },
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
     ))
     .await
     .unwrap();
@@ -889,7 +1079,10 @@ async fn remove_with_increment_failure_invalid_quantity() {
         database.connection.clone().into(),
         input,
         auth_user,
-        RequestInfo { user_agent: None },
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
     ))
     .await
     .into();
@@ -916,6 +1109,8 @@ async fn checkout_external() {
 
     let input = Json(cart::CheckoutCartRequest {
         tracking_data: None,
+        gift_card_codes: vec![],
+        use_account_credit: false,
         method: PaymentRequest::External {
             reference: Some("TestRef".to_string()),
             external_payment_type: ExternalPaymentType::Voucher,
@@ -935,7 +1130,10 @@ async fn checkout_external() {
         input,
         user.clone(),
         request.extract_state().await,
-        RequestInfo { user_agent: None },
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
     ))
     .await
     .unwrap();
@@ -971,6 +1169,8 @@ async fn checkout_external_with_free_cart() {
 
     let input = Json(cart::CheckoutCartRequest {
         tracking_data: None,
+        gift_card_codes: vec![],
+        use_account_credit: false,
         method: PaymentRequest::External {
             reference: Some("TestRef".to_string()),
             external_payment_type: ExternalPaymentType::Cash,
@@ -990,7 +1190,10 @@ async fn checkout_external_with_free_cart() {
         input,
         user,
         request.extract_state().await,
-        RequestInfo { user_agent: None },
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
     ))
     .await
     .unwrap();
@@ -1008,6 +1211,152 @@ async fn checkout_external_with_free_cart() {
     assert_eq!(payment.provider, PaymentProviders::External);
 }
 
+#[actix_rt::test]
+async fn checkout_with_payment_method_of_other_user() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let event = database.create_event().with_tickets().with_ticket_pricing().finish();
+
+    let user = database.create_user().finish();
+    let order = database.create_cart().for_user(&user).for_event(&event).finish();
+    let payment_method = database.create_payment_method().make_default().finish();
+    let request = TestRequest::create();
+
+    let input = Json(cart::CheckoutCartRequest {
+        tracking_data: None,
+        gift_card_codes: vec![],
+        use_account_credit: false,
+        method: PaymentRequest::PaymentMethod {
+            provider: None,
+            payment_method_id: Some(payment_method.id),
+            payment_plan: false,
+        },
+    });
+
+    let user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let response: HttpResponse = cart::checkout((
+        database.connection.clone().into(),
+        input,
+        user,
+        request.extract_state().await,
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
+    ))
+    .await
+    .into();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let order = Order::find(order.id, connection).unwrap();
+    assert_eq!(order.status, OrderStatus::Draft);
+}
+
+#[actix_rt::test]
+async fn checkout_with_payment_plan_for_ineligible_ticket_type() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let event = database.create_event().with_tickets().with_ticket_pricing().finish();
+
+    let user = database.create_user().finish();
+    let order = database.create_cart().for_user(&user).for_event(&event).finish();
+    let payment_method = database
+        .create_payment_method()
+        .with_user(&user)
+        .make_default()
+        .finish();
+    let request = TestRequest::create();
+
+    let input = Json(cart::CheckoutCartRequest {
+        tracking_data: None,
+        gift_card_codes: vec![],
+        use_account_credit: false,
+        method: PaymentRequest::PaymentMethod {
+            provider: None,
+            payment_method_id: Some(payment_method.id),
+            payment_plan: true,
+        },
+    });
+
+    let user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let response: HttpResponse = cart::checkout((
+        database.connection.clone().into(),
+        input,
+        user,
+        request.extract_state().await,
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
+    ))
+    .await
+    .into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let order = Order::find(order.id, connection).unwrap();
+    assert_eq!(order.status, OrderStatus::Draft);
+    assert!(PaymentPlan::find_for_order(order.id, connection).is_err());
+}
+
+#[actix_rt::test]
+async fn checkout_with_gift_card() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+
+    let user = database.create_user().finish();
+    let order = database.create_cart().for_user(&user).for_event(&event).finish();
+    let total = order.calculate_total(connection).unwrap();
+    let gift_card = GiftCard::create(
+        organization.id,
+        None,
+        total + 500,
+        organization.currency.clone(),
+        None,
+        user.id,
+    )
+    .commit(connection)
+    .unwrap();
+    let request = TestRequest::create();
+
+    // Gift card covers the whole order so the payment method is not charged
+    let input = Json(cart::CheckoutCartRequest {
+        tracking_data: None,
+        gift_card_codes: vec![gift_card.code.to_lowercase()],
+        use_account_credit: false,
+        method: PaymentRequest::Free,
+    });
+
+    let user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let response: HttpResponse = cart::checkout((
+        database.connection.clone().into(),
+        input,
+        user,
+        request.extract_state().await,
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
+    ))
+    .await
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let order = Order::find(order.id, connection).unwrap();
+    assert_eq!(order.status, OrderStatus::Paid);
+    let payments = order.payments(connection).unwrap();
+    assert_eq!(payments.len(), 1);
+    assert_eq!(payments[0].payment_method, PaymentMethods::GiftCard);
+    assert_eq!(payments[0].amount, total);
+    assert_eq!(GiftCard::find(gift_card.id, connection).unwrap().balance_in_cents, 500);
+}
+
 #[actix_rt::test]
 async fn checkout_paid_fails_with_free_cart() {
     let database = TestDatabase::new();
@@ -1025,11 +1374,14 @@ async fn checkout_paid_fails_with_free_cart() {
 
     let input = Json(cart::CheckoutCartRequest {
         tracking_data: None,
+        gift_card_codes: vec![],
+        use_account_credit: false,
         method: PaymentRequest::Card {
             token: "abc".into(),
             provider: PaymentProviders::Stripe,
             save_payment_method: false,
             set_default: false,
+            payment_plan: false,
         },
     });
 
@@ -1041,7 +1393,10 @@ async fn checkout_paid_fails_with_free_cart() {
         input,
         user,
         request.extract_state().await,
-        RequestInfo { user_agent: None },
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
     ))
     .await
     .into();
@@ -1077,6 +1432,8 @@ async fn checkout_free() {
 
     let input = Json(cart::CheckoutCartRequest {
         tracking_data: None,
+        gift_card_codes: vec![],
+        use_account_credit: false,
         method: PaymentRequest::Free,
     });
 
@@ -1087,7 +1444,10 @@ async fn checkout_free() {
         input,
         user,
         request.extract_state().await,
-        RequestInfo { user_agent: None },
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
     ))
     .await
     .unwrap();
@@ -1118,6 +1478,8 @@ async fn checkout_free_for_paid_items() {
 
     let input = Json(cart::CheckoutCartRequest {
         tracking_data: None,
+        gift_card_codes: vec![],
+        use_account_credit: false,
         method: PaymentRequest::Free,
     });
 
@@ -1128,7 +1490,10 @@ async fn checkout_free_for_paid_items() {
         input,
         user,
         request.extract_state().await,
-        RequestInfo { user_agent: None },
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
     ))
     .await
     .into();
@@ -1227,6 +1592,8 @@ async fn checkout_fails_for_invalid_items() {
 
     let input = Json(cart::CheckoutCartRequest {
         tracking_data: None,
+        gift_card_codes: vec![],
+        use_account_credit: false,
         method: PaymentRequest::External {
             reference: Some("TestRef".to_string()),
             external_payment_type: ExternalPaymentType::Cash,
@@ -1246,7 +1613,10 @@ async fn checkout_fails_for_invalid_items() {
         input,
         user,
         request.extract_state().await,
-        RequestInfo { user_agent: None },
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
     ))
     .await
     .into();
@@ -1267,7 +1637,16 @@ async fn checkout_fails_for_invalid_items() {
 async fn checkout_provider_globee() {
     let database = TestDatabase::new();
     let conn = database.connection.get();
-    let event = database.create_event().with_tickets().with_ticket_pricing().finish();
+    let organization = database
+        .create_organization()
+        .with_allowed_payment_providers(vec![PaymentProviders::Globee])
+        .finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
 
     let user = database.create_user().finish();
 
@@ -1276,6 +1655,8 @@ async fn checkout_provider_globee() {
 
     let input = Json(cart::CheckoutCartRequest {
         tracking_data: None,
+        gift_card_codes: vec![],
+        use_account_credit: false,
         method: PaymentRequest::Provider {
             provider: PaymentProviders::Globee,
         },
@@ -1289,7 +1670,10 @@ async fn checkout_provider_globee() {
         input,
         user.clone(),
         request.extract_state().await,
-        RequestInfo { user_agent: None },
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
     ))
     .await
     .unwrap();
@@ -1384,3 +1768,173 @@ async fn checkout_provider_globee() {
     let order = Order::find(order.id, conn).unwrap();
     assert_eq!(order.status, OrderStatus::Paid);
 }
+
+async fn checkout_with_mock_card(database: &TestDatabase, user: &User, token: &str) -> HttpResponse {
+    let input = Json(cart::CheckoutCartRequest {
+        tracking_data: None,
+        gift_card_codes: vec![],
+        use_account_credit: false,
+        method: PaymentRequest::Card {
+            token: token.to_string(),
+            provider: PaymentProviders::Mock,
+            save_payment_method: false,
+            set_default: false,
+            payment_plan: false,
+        },
+    });
+    let user = support::create_auth_user_from_user(user, Roles::User, None, database);
+    cart::checkout((
+        database.connection.clone().into(),
+        input,
+        user,
+        TestRequest::create().extract_state().await,
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
+    ))
+    .await
+    .into()
+}
+
+fn create_mock_provider_cart(database: &TestDatabase) -> (User, Order) {
+    let organization = database
+        .create_organization()
+        .with_allowed_payment_providers(vec![PaymentProviders::Mock])
+        .finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let user = database.create_user().finish();
+    let order = database.create_cart().for_user(&user).for_event(&event).finish();
+    (user, order)
+}
+
+#[actix_rt::test]
+async fn checkout_provider_mock() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let (user, order) = create_mock_provider_cart(&database);
+
+    let response = checkout_with_mock_card(&database, &user, MOCK_TOKEN_SUCCESS).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let order = Order::find(order.id, connection).unwrap();
+    assert_eq!(order.status, OrderStatus::Paid);
+    let payments = order.payments(connection).unwrap();
+    assert_eq!(payments.len(), 1);
+    assert_eq!(payments[0].provider, PaymentProviders::Mock);
+    assert_eq!(payments[0].status, PaymentStatus::Completed);
+}
+
+#[actix_rt::test]
+async fn checkout_provider_mock_declined() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let (user, order) = create_mock_provider_cart(&database);
+
+    let response = checkout_with_mock_card(&database, &user, MOCK_TOKEN_DECLINE).await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let order = Order::find(order.id, connection).unwrap();
+    assert_eq!(order.status, OrderStatus::Draft);
+    assert!(order.payments(connection).unwrap().is_empty());
+}
+
+#[actix_rt::test]
+async fn checkout_provider_not_allowed_for_organization() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let event = database.create_event().with_tickets().with_ticket_pricing().finish();
+    let user = database.create_user().finish();
+    let order = database.create_cart().for_user(&user).for_event(&event).finish();
+
+    let response = checkout_with_mock_card(&database, &user, MOCK_TOKEN_SUCCESS).await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(Order::find(order.id, connection).unwrap().status, OrderStatus::Draft);
+}
+
+#[actix_rt::test]
+async fn checkout_provider_mock_with_challenge() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let (user, order) = create_mock_provider_cart(&database);
+
+    let response = checkout_with_mock_card(&database, &user, MOCK_TOKEN_CHALLENGE).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = unwrap_body_to_string(&response).unwrap();
+    let display_order: DisplayOrder = serde_json::from_str(body).unwrap();
+    assert_eq!(display_order.status, OrderStatus::Draft);
+
+    // Cart is kept until the shopper passes the challenge
+    let order = Order::find(order.id, connection).unwrap();
+    assert!(order.checkout_url.is_some());
+    let payment = order.payments(connection).unwrap().remove(0);
+    assert_eq!(payment.status, PaymentStatus::Requested);
+
+    let nonce = payment.url_nonce.clone().unwrap();
+    let url = format!(
+        "/payments/callback/{}/{}?success=true&redirectResult={}",
+        nonce, order.id, MOCK_CHALLENGE_PASSED
+    );
+    let request = TestRequest::create_with_uri_custom_params(&url, vec!["nonce", "id"]);
+    let query = Query::<controllers::payments::QueryParams>::extract(&request.request)
+        .await
+        .unwrap();
+    let mut path = Path::<controllers::payments::PathParams>::extract(&request.request)
+        .await
+        .unwrap();
+    path.nonce = nonce;
+    path.id = order.id;
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let response = controllers::payments::callback((
+        query,
+        path,
+        database.connection.clone().into(),
+        request.extract_state().await,
+        OptionalUser(Some(auth_user)),
+    ))
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::FOUND);
+
+    let order = Order::find(order.id, connection).unwrap();
+    assert_eq!(order.status, OrderStatus::Paid);
+    let payment = Payment::find(payment.id, connection).unwrap();
+    assert_eq!(payment.status, PaymentStatus::Completed);
+}
+
+#[actix_rt::test]
+async fn checkout_provider_mock_with_delayed_capture() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let (user, order) = create_mock_provider_cart(&database);
+
+    let response = checkout_with_mock_card(&database, &user, MOCK_TOKEN_DELAYED_CAPTURE).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let order = Order::find(order.id, connection).unwrap();
+    assert_eq!(order.status, OrderStatus::PendingPayment);
+    let payment = order.payments(connection).unwrap().remove(0);
+    assert_eq!(payment.status, PaymentStatus::PendingIpn);
+
+    MockPaymentProcessor::settle_delayed_capture(payment.external_reference.as_ref().unwrap(), true)
+        .unwrap()
+        .commit(connection)
+        .unwrap();
+    let domain_actions =
+        DomainAction::find_pending(Some(DomainActionTypes::ProcessPaymentProviderEvent), connection).unwrap();
+    assert_eq!(domain_actions.len(), 1);
+    ProcessPaymentProviderEventExecutor::new()
+        .perform_job(&domain_actions[0], &database.connection)
+        .unwrap();
+
+    let order = Order::find(order.id, connection).unwrap();
+    assert_eq!(order.status, OrderStatus::Paid);
+    let payment = Payment::find(payment.id, connection).unwrap();
+    assert_eq!(payment.status, PaymentStatus::Completed);
+}
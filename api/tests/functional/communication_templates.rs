@@ -0,0 +1,277 @@
+use crate::functional::base;
+use db::models::*;
+
+#[cfg(test)]
+mod index_tests {
+    use super::*;
+
+    #[actix_rt::test]
+    async fn index_org_member() {
+        base::communication_templates::index(Roles::OrgMember, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn index_admin() {
+        base::communication_templates::index(Roles::Admin, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn index_super() {
+        base::communication_templates::index(Roles::Super, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn index_user() {
+        base::communication_templates::index(Roles::User, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn index_org_owner() {
+        base::communication_templates::index(Roles::OrgOwner, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn index_door_person() {
+        base::communication_templates::index(Roles::DoorPerson, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn index_promoter() {
+        base::communication_templates::index(Roles::Promoter, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn index_promoter_read_only() {
+        base::communication_templates::index(Roles::PromoterReadOnly, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn index_org_admin() {
+        base::communication_templates::index(Roles::OrgAdmin, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn index_box_office() {
+        base::communication_templates::index(Roles::OrgBoxOffice, false).await;
+    }
+}
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+
+    #[actix_rt::test]
+    async fn create_org_member() {
+        base::communication_templates::create(Roles::OrgMember, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_admin() {
+        base::communication_templates::create(Roles::Admin, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_super() {
+        base::communication_templates::create(Roles::Super, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_user() {
+        base::communication_templates::create(Roles::User, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_org_owner() {
+        base::communication_templates::create(Roles::OrgOwner, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_door_person() {
+        base::communication_templates::create(Roles::DoorPerson, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_promoter() {
+        base::communication_templates::create(Roles::Promoter, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_promoter_read_only() {
+        base::communication_templates::create(Roles::PromoterReadOnly, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_org_admin() {
+        base::communication_templates::create(Roles::OrgAdmin, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_box_office() {
+        base::communication_templates::create(Roles::OrgBoxOffice, false).await;
+    }
+}
+
+#[cfg(test)]
+mod preview_tests {
+    use super::*;
+
+    #[actix_rt::test]
+    async fn preview_org_member() {
+        base::communication_templates::preview(Roles::OrgMember, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn preview_admin() {
+        base::communication_templates::preview(Roles::Admin, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn preview_super() {
+        base::communication_templates::preview(Roles::Super, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn preview_user() {
+        base::communication_templates::preview(Roles::User, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn preview_org_owner() {
+        base::communication_templates::preview(Roles::OrgOwner, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn preview_door_person() {
+        base::communication_templates::preview(Roles::DoorPerson, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn preview_promoter() {
+        base::communication_templates::preview(Roles::Promoter, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn preview_promoter_read_only() {
+        base::communication_templates::preview(Roles::PromoterReadOnly, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn preview_org_admin() {
+        base::communication_templates::preview(Roles::OrgAdmin, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn preview_box_office() {
+        base::communication_templates::preview(Roles::OrgBoxOffice, false).await;
+    }
+}
+
+#[cfg(test)]
+mod versions_tests {
+    use super::*;
+
+    #[actix_rt::test]
+    async fn versions_org_member() {
+        base::communication_templates::versions(Roles::OrgMember, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn versions_admin() {
+        base::communication_templates::versions(Roles::Admin, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn versions_super() {
+        base::communication_templates::versions(Roles::Super, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn versions_user() {
+        base::communication_templates::versions(Roles::User, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn versions_org_owner() {
+        base::communication_templates::versions(Roles::OrgOwner, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn versions_door_person() {
+        base::communication_templates::versions(Roles::DoorPerson, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn versions_promoter() {
+        base::communication_templates::versions(Roles::Promoter, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn versions_promoter_read_only() {
+        base::communication_templates::versions(Roles::PromoterReadOnly, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn versions_org_admin() {
+        base::communication_templates::versions(Roles::OrgAdmin, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn versions_box_office() {
+        base::communication_templates::versions(Roles::OrgBoxOffice, false).await;
+    }
+}
+
+#[cfg(test)]
+mod rollback_tests {
+    use super::*;
+
+    #[actix_rt::test]
+    async fn rollback_org_member() {
+        base::communication_templates::rollback(Roles::OrgMember, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn rollback_admin() {
+        base::communication_templates::rollback(Roles::Admin, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn rollback_super() {
+        base::communication_templates::rollback(Roles::Super, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn rollback_user() {
+        base::communication_templates::rollback(Roles::User, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn rollback_org_owner() {
+        base::communication_templates::rollback(Roles::OrgOwner, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn rollback_door_person() {
+        base::communication_templates::rollback(Roles::DoorPerson, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn rollback_promoter() {
+        base::communication_templates::rollback(Roles::Promoter, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn rollback_promoter_read_only() {
+        base::communication_templates::rollback(Roles::PromoterReadOnly, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn rollback_org_admin() {
+        base::communication_templates::rollback(Roles::OrgAdmin, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn rollback_box_office() {
+        base::communication_templates::rollback(Roles::OrgBoxOffice, false).await;
+    }
+}
@@ -0,0 +1,171 @@
+use crate::functional::base;
+use crate::support;
+use crate::support::database::TestDatabase;
+use crate::support::test_request::TestRequest;
+use actix_web::{http::StatusCode, web::Path, FromRequest, HttpResponse};
+use api::controllers::event_series;
+use api::models::PathParameters;
+use chrono::prelude::*;
+use chrono::Duration;
+use db::models::*;
+use db::utils::dates;
+use serde_json;
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+
+    #[actix_rt::test]
+    async fn create_org_member() {
+        base::event_series::create(Roles::OrgMember, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_admin() {
+        base::event_series::create(Roles::Admin, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_super() {
+        base::event_series::create(Roles::Super, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_user() {
+        base::event_series::create(Roles::User, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_org_owner() {
+        base::event_series::create(Roles::OrgOwner, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_door_person() {
+        base::event_series::create(Roles::DoorPerson, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_promoter() {
+        base::event_series::create(Roles::Promoter, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_promoter_read_only() {
+        base::event_series::create(Roles::PromoterReadOnly, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_org_admin() {
+        base::event_series::create(Roles::OrgAdmin, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_box_office() {
+        base::event_series::create(Roles::OrgBoxOffice, false).await;
+    }
+}
+
+#[cfg(test)]
+mod update_events_tests {
+    use super::*;
+
+    #[actix_rt::test]
+    async fn update_events_org_member() {
+        base::event_series::update_events(Roles::OrgMember, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn update_events_admin() {
+        base::event_series::update_events(Roles::Admin, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn update_events_super() {
+        base::event_series::update_events(Roles::Super, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn update_events_user() {
+        base::event_series::update_events(Roles::User, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn update_events_org_owner() {
+        base::event_series::update_events(Roles::OrgOwner, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn update_events_door_person() {
+        base::event_series::update_events(Roles::DoorPerson, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn update_events_promoter() {
+        base::event_series::update_events(Roles::Promoter, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn update_events_promoter_read_only() {
+        base::event_series::update_events(Roles::PromoterReadOnly, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn update_events_org_admin() {
+        base::event_series::update_events(Roles::OrgAdmin, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn update_events_box_office() {
+        base::event_series::update_events(Roles::OrgBoxOffice, false).await;
+    }
+}
+
+#[actix_rt::test]
+async fn show() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let venue = database.create_venue().finish();
+    let event_start = NaiveDateTime::from_timestamp(dates::now().add_days(7).finish().timestamp(), 0);
+    let event = database
+        .create_event()
+        .with_venue(&venue)
+        .with_event_start(event_start)
+        .with_status(EventStatus::Published)
+        .with_publish_date(dates::now().add_days(-1).finish())
+        .finish();
+    let event_series = EventSeries::create(
+        event.organization_id,
+        event.id,
+        "Friday Night Comedy".to_string(),
+        RecurrenceTypes::Weekly,
+        1,
+        Some(event_start + Duration::weeks(1)),
+        vec![],
+    )
+    .commit(None, connection)
+    .unwrap();
+    let occurrences = event_series.generate_occurrences(None, connection).unwrap();
+    occurrences[0].publish(None, connection).unwrap();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = event_series.id;
+    let response: HttpResponse = event_series::show((database.connection.clone().into(), path))
+        .await
+        .into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let display_event_series: DisplayEventSeries = serde_json::from_str(&body).unwrap();
+    assert_eq!(display_event_series, event_series.for_display(connection).unwrap());
+    assert_eq!(display_event_series.slug, Some("friday-night-comedy".to_string()));
+    assert_eq!(
+        display_event_series
+            .upcoming_dates
+            .iter()
+            .map(|d| d.event_id)
+            .collect::<Vec<_>>(),
+        vec![event.id, occurrences[0].id]
+    );
+}
@@ -445,6 +445,7 @@ async fn show() {
         OptionalUser(Some(auth_user)),
         RequestInfo {
             user_agent: Some("test".to_string()),
+            ip_address: None,
         },
     ))
     .await
@@ -508,6 +509,7 @@ async fn show_ended_event() {
         OptionalUser(Some(auth_user)),
         RequestInfo {
             user_agent: Some("test".to_string()),
+            ip_address: None,
         },
     ))
     .await
@@ -548,6 +550,7 @@ async fn show_future_published_no_preview() {
         OptionalUser(Some(auth_user.clone())),
         RequestInfo {
             user_agent: Some("test".to_string()),
+            ip_address: None,
         },
     ))
     .await
@@ -598,6 +601,7 @@ async fn show_from_slug() {
         OptionalUser(Some(auth_user.clone())),
         RequestInfo {
             user_agent: Some("test".to_string()),
+            ip_address: None,
         },
     ))
     .await
@@ -624,6 +628,7 @@ async fn show_from_slug() {
         OptionalUser(Some(auth_user.clone())),
         RequestInfo {
             user_agent: Some("test".to_string()),
+            ip_address: None,
         },
     ))
     .await
@@ -666,6 +671,7 @@ async fn show_from_slug() {
         OptionalUser(Some(auth_user.clone())),
         RequestInfo {
             user_agent: Some("test".to_string()),
+            ip_address: None,
         },
     ))
     .await
@@ -720,6 +726,7 @@ async fn show_future_published_with_preview() {
         OptionalUser(Some(auth_user.clone())),
         RequestInfo {
             user_agent: Some("test".to_string()),
+            ip_address: None,
         },
     ))
     .await
@@ -761,6 +768,7 @@ async fn show_deleted_event() {
         OptionalUser(Some(auth_user.clone())),
         RequestInfo {
             user_agent: Some("test".to_string()),
+            ip_address: None,
         },
     ))
     .await
@@ -834,6 +842,7 @@ async fn show_private() {
         OptionalUser(Some(auth_user.clone())),
         RequestInfo {
             user_agent: Some("test".to_string()),
+            ip_address: None,
         },
     ))
     .await
@@ -871,6 +880,7 @@ async fn show_private() {
         OptionalUser(Some(auth_org_user)),
         RequestInfo {
             user_agent: Some("test".to_string()),
+            ip_address: None,
         },
     ))
     .await
@@ -893,6 +903,7 @@ async fn show_private() {
         OptionalUser(Some(auth_user.clone())),
         RequestInfo {
             user_agent: Some("test".to_string()),
+            ip_address: None,
         },
     ))
     .await
@@ -917,6 +928,7 @@ async fn show_private() {
         OptionalUser(Some(auth_user)),
         RequestInfo {
             user_agent: Some("test".to_string()),
+            ip_address: None,
         },
     ))
     .await
@@ -995,6 +1007,7 @@ async fn show_with_cancelled_ticket_type() {
         OptionalUser(Some(auth_user)),
         RequestInfo {
             user_agent: Some("test".to_string()),
+            ip_address: None,
         },
     ))
     .await
@@ -1068,6 +1081,7 @@ async fn show_with_access_restricted_ticket_type_and_no_code() {
         OptionalUser(Some(auth_user)),
         RequestInfo {
             user_agent: Some("test".to_string()),
+            ip_address: None,
         },
     ))
     .await
@@ -1144,6 +1158,7 @@ async fn show_with_access_restricted_ticket_type_and_access_code() {
         OptionalUser(Some(auth_user)),
         RequestInfo {
             user_agent: Some("test".to_string()),
+            ip_address: None,
         },
     ))
     .await
@@ -1182,6 +1197,7 @@ async fn show_with_visibility_always_before_sale() {
         auth_user.into_optional(),
         RequestInfo {
             user_agent: Some("test".to_string()),
+            ip_address: None,
         },
     ))
     .await
@@ -1224,6 +1240,7 @@ async fn show_with_visibility_always_before_sale_pricing() {
         auth_user.into_optional(),
         RequestInfo {
             user_agent: Some("test".to_string()),
+            ip_address: None,
         },
     ))
     .await
@@ -1267,6 +1284,7 @@ async fn show_with_visibility_always_after_sale() {
         auth_user.into_optional(),
         RequestInfo {
             user_agent: Some("test".to_string()),
+            ip_address: None,
         },
     ))
     .await
@@ -1307,6 +1325,7 @@ async fn show_with_hidden_ticket_type() {
         auth_user.into_optional(),
         RequestInfo {
             user_agent: Some("test".to_string()),
+            ip_address: None,
         },
     ))
     .await
@@ -2179,6 +2198,48 @@ mod redeem_ticket {
     }
 }
 
+#[cfg(test)]
+mod redeem_offline_tickets {
+    use super::*;
+
+    #[actix_rt::test]
+    async fn redeem_offline_tickets_org_member() {
+        base::events::redeem_offline_tickets(Roles::OrgMember, true).await;
+    }
+    #[actix_rt::test]
+    async fn redeem_offline_tickets_admin() {
+        base::events::redeem_offline_tickets(Roles::Admin, true).await;
+    }
+    #[actix_rt::test]
+    async fn redeem_offline_tickets_user() {
+        base::events::redeem_offline_tickets(Roles::User, false).await;
+    }
+    #[actix_rt::test]
+    async fn redeem_offline_tickets_org_owner() {
+        base::events::redeem_offline_tickets(Roles::OrgOwner, true).await;
+    }
+    #[actix_rt::test]
+    async fn redeem_offline_tickets_door_person() {
+        base::events::redeem_offline_tickets(Roles::DoorPerson, true).await;
+    }
+    #[actix_rt::test]
+    async fn redeem_offline_tickets_promoter() {
+        base::events::redeem_offline_tickets(Roles::Promoter, false).await;
+    }
+    #[actix_rt::test]
+    async fn redeem_offline_tickets_promoter_read_only() {
+        base::events::redeem_offline_tickets(Roles::PromoterReadOnly, false).await;
+    }
+    #[actix_rt::test]
+    async fn redeem_offline_tickets_org_admin() {
+        base::events::redeem_offline_tickets(Roles::OrgAdmin, true).await;
+    }
+    #[actix_rt::test]
+    async fn redeem_offline_tickets_box_office() {
+        base::events::redeem_offline_tickets(Roles::OrgBoxOffice, true).await;
+    }
+}
+
 #[actix_rt::test]
 pub async fn delete_fails_has_ticket_in_cart() {
     let database = TestDatabase::new();
@@ -0,0 +1,166 @@
+use crate::support::database::TestDatabase;
+use crate::support::test_request::TestRequest;
+use actix_web::{http::StatusCode, test, HttpRequest, HttpResponse};
+use api::controllers::ipns;
+use api::domain_events::executors::ProcessPaymentProviderEventExecutor;
+use chrono::prelude::*;
+use db::models::*;
+
+const DISPUTE_CREATED: &str = include_str!("../../../stripe/fixtures/webhooks/charge.dispute.created.json");
+const REFUNDED: &str = include_str!("../../../stripe/fixtures/webhooks/charge.refunded.json");
+// Charge the fixture events were raised for
+const CHARGE_ID: &str = "ch_1GU3XbHb0zBq6YkKwL8kZt2n";
+
+fn signed_request(payload: &str, secret: &str) -> HttpRequest {
+    test::TestRequest::post()
+        .header(
+            "Stripe-Signature",
+            stripe::signature_header(payload, secret, Utc::now().timestamp()),
+        )
+        .to_http_request()
+}
+
+fn create_paid_order(database: &TestDatabase) -> (Organization, Order) {
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let mut order = database.create_order().for_event(&event).finish();
+    let total = order.calculate_total(connection).unwrap();
+    order
+        .add_credit_card_payment(
+            order.user_id,
+            total,
+            PaymentProviders::Stripe,
+            CHARGE_ID.to_string(),
+            PaymentStatus::Completed,
+            json!({ "id": CHARGE_ID }),
+            connection,
+        )
+        .unwrap();
+    (organization, order)
+}
+
+fn process_pending_events(database: &TestDatabase) {
+    let connection = database.connection.get();
+    let domain_actions =
+        DomainAction::find_pending(Some(DomainActionTypes::ProcessPaymentProviderEvent), connection).unwrap();
+    let executor = ProcessPaymentProviderEventExecutor::new();
+    for domain_action in domain_actions {
+        executor.perform_job(&domain_action, &database.connection).unwrap();
+    }
+}
+
+#[actix_rt::test]
+async fn stripe_dispute_created() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let (organization, order) = create_paid_order(&database);
+    let settlement = database.create_settlement().with_organization(&organization).finish();
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state().await;
+    let secret = state.config.stripe_webhook_secret.clone().unwrap();
+
+    let response: HttpResponse = ipns::stripe((
+        signed_request(DISPUTE_CREATED, &secret),
+        DISPUTE_CREATED.to_string(),
+        state,
+        database.connection.clone().into(),
+    ))
+    .await
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    process_pending_events(&database);
+
+    let adjustments = settlement.adjustments(connection).unwrap();
+    assert_eq!(adjustments.len(), 1);
+    assert_eq!(
+        adjustments[0].settlement_adjustment_type,
+        SettlementAdjustmentTypes::Chargeback
+    );
+    assert_eq!(adjustments[0].amount_in_cents, 5000);
+    assert_eq!(
+        adjustments[0].external_reference,
+        Some("dp_1GU3Y1Hb0zBq6YkKc7pZ1mQe".to_string())
+    );
+    let payment = Payment::find_by_external_reference(PaymentProviders::Stripe, CHARGE_ID, connection).unwrap();
+    assert_eq!(adjustments[0].payment_id, Some(payment.id));
+    assert_eq!(payment.order_id, order.id);
+
+    // Redelivery of the same event does not create another chargeback
+    let state = test_request.extract_state().await;
+    let response: HttpResponse = ipns::stripe((
+        signed_request(DISPUTE_CREATED, &secret),
+        DISPUTE_CREATED.to_string(),
+        state,
+        database.connection.clone().into(),
+    ))
+    .await
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        DomainAction::find_pending(Some(DomainActionTypes::ProcessPaymentProviderEvent), connection)
+            .unwrap()
+            .len(),
+        1
+    );
+    process_pending_events(&database);
+    assert_eq!(settlement.adjustments(connection).unwrap().len(), 1);
+}
+
+#[actix_rt::test]
+async fn stripe_refunded() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let (_, order) = create_paid_order(&database);
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state().await;
+    let secret = state.config.stripe_webhook_secret.clone().unwrap();
+
+    let response: HttpResponse = ipns::stripe((
+        signed_request(REFUNDED, &secret),
+        REFUNDED.to_string(),
+        state,
+        database.connection.clone().into(),
+    ))
+    .await
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    process_pending_events(&database);
+
+    let refund_payments: Vec<Payment> = order
+        .payments(connection)
+        .unwrap()
+        .into_iter()
+        .filter(|payment| payment.refund_id.is_some())
+        .collect();
+    assert_eq!(refund_payments.len(), 1);
+    assert_eq!(refund_payments[0].amount, -2500);
+    assert!(order.has_refunds(connection).unwrap());
+}
+
+#[actix_rt::test]
+async fn stripe_invalid_signature() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state().await;
+
+    let response: HttpResponse = ipns::stripe((
+        signed_request(DISPUTE_CREATED, "whsec_other"),
+        DISPUTE_CREATED.to_string(),
+        state,
+        database.connection.clone().into(),
+    ))
+    .await
+    .into();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert!(
+        DomainAction::find_pending(Some(DomainActionTypes::ProcessPaymentProviderEvent), connection)
+            .unwrap()
+            .is_empty()
+    );
+}
@@ -1,21 +1,32 @@
+mod admin_domain_actions;
 mod announcements;
+mod api_keys;
 mod artists;
 mod auth;
 mod base;
 mod broadcast;
+mod bundles;
 mod cart;
 mod codes;
 mod collection_items;
 mod collections;
+mod communication_templates;
 mod comps;
 mod event_report_subscribers;
+mod event_series;
 mod events;
 mod genres;
 mod holds;
+mod ipns;
 mod notes;
+mod oidc;
 mod orders;
+mod organization_events_stream;
 mod organization_invites;
+mod organization_roles;
+mod organization_sso_configurations;
 mod organization_venues;
+mod organization_webhooks;
 mod organizations;
 mod password_resets;
 mod payment_methods;
@@ -28,9 +39,13 @@ mod settlements;
 mod sitemap;
 mod slugs;
 mod stages;
+mod tax_rules;
 mod ticket_types;
 mod tickets;
 mod transfers;
+mod two_factor;
 mod user_invites;
+mod user_sessions;
 mod users;
 mod venues;
+mod waitlist_entries;
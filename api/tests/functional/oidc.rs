@@ -0,0 +1,259 @@
+use crate::support;
+use crate::support::database::TestDatabase;
+use crate::support::test_request::TestRequest;
+use actix_web::{http::StatusCode, HttpResponse};
+use api::controllers::external::oidc::{self, OidcAuthorizeRequest, OidcCallbackRequest};
+use api::extractors::*;
+use api::models::*;
+use chrono::Utc;
+use db::models::*;
+use db::utils::totp;
+use std::collections::HashMap;
+
+fn create_configuration(
+    organization: &Organization,
+    encryption_key: &str,
+    database: &TestDatabase,
+) -> OrganizationSsoConfiguration {
+    OrganizationSsoConfiguration::create(
+        organization.id,
+        "https://login.example.com".to_string(),
+        "client-id".to_string(),
+        "client-secret".to_string(),
+        Roles::OrgMember,
+        None,
+        HashMap::new(),
+    )
+    .commit(encryption_key, None, database.connection.get())
+    .unwrap()
+}
+
+#[actix_rt::test]
+async fn authorize_disabled_configuration() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state().await;
+    let configuration = create_configuration(&organization, &state.config.api_keys_encryption_key, &database);
+    configuration
+        .update(
+            OrganizationSsoConfigurationEditableAttributes {
+                enabled: Some(false),
+                ..Default::default()
+            },
+            &state.config.api_keys_encryption_key,
+            None,
+            database.connection.get(),
+        )
+        .unwrap();
+
+    let json = Json(OidcAuthorizeRequest {
+        organization_id: organization.id,
+        redirect_uri: format!("{}/sso/callback", state.config.front_end_url),
+    });
+    let response: HttpResponse = oidc::authorize((state, database.connection.clone().into(), json, OptionalUser(None)))
+        .await
+        .into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[actix_rt::test]
+async fn authorize_redirect_uri_not_allowed() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state().await;
+    create_configuration(&organization, &state.config.api_keys_encryption_key, &database);
+
+    let front_end_url = state.config.front_end_url.clone();
+    for redirect_uri in vec![
+        "https://attacker.example.com/sso/callback".to_string(),
+        format!("{}.attacker.example.com/sso/callback", front_end_url),
+        format!("{}@attacker.example.com/sso/callback", front_end_url),
+    ] {
+        let json = Json(OidcAuthorizeRequest {
+            organization_id: organization.id,
+            redirect_uri,
+        });
+        let response: HttpResponse = oidc::authorize((
+            state.clone(),
+            database.connection.clone().into(),
+            json,
+            OptionalUser(None),
+        ))
+        .await
+        .into();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body = support::unwrap_body_to_string(&response).unwrap();
+        assert_eq!(body, json!({"error": "Redirect URI is not allowed"}).to_string());
+    }
+}
+
+#[actix_rt::test]
+async fn callback_unknown_state() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state().await;
+    let configuration = create_configuration(&organization, &state.config.api_keys_encryption_key, &database);
+    let login_request = SsoLoginRequest::create(
+        configuration.id,
+        format!("{}/sso/callback", state.config.front_end_url),
+        None,
+    )
+    .commit(database.connection.get())
+    .unwrap();
+    SsoLoginRequest::consume(&login_request.state, database.connection.get()).unwrap();
+
+    // A request that has already been used cannot complete another login
+    let json = Json(OidcCallbackRequest {
+        code: "code".to_string(),
+        state: login_request.state.clone(),
+        totp_code: None,
+        recovery_code: None,
+    });
+    let response: HttpResponse = oidc::callback((
+        state,
+        database.connection.clone().into(),
+        json,
+        OptionalUser(None),
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
+    ))
+    .await
+    .into();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    assert_eq!(
+        body,
+        json!({"error": "Single sign-on request is invalid or has expired"}).to_string()
+    );
+}
+
+#[actix_rt::test]
+async fn callback_started_by_another_user() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state().await;
+    let configuration = create_configuration(&organization, &state.config.api_keys_encryption_key, &database);
+    let login_request = SsoLoginRequest::create(
+        configuration.id,
+        format!("{}/sso/callback", state.config.front_end_url),
+        None,
+    )
+    .commit(database.connection.get())
+    .unwrap();
+    let auth_user = support::create_auth_user(Roles::User, None, &database);
+
+    // A signed in user cannot complete a request started by someone else, that would link the
+    // other person's identity to their account
+    let json = Json(OidcCallbackRequest {
+        code: "code".to_string(),
+        state: login_request.state.clone(),
+        totp_code: None,
+        recovery_code: None,
+    });
+    let response: HttpResponse = oidc::callback((
+        state,
+        database.connection.clone().into(),
+        json,
+        auth_user.into_optional(),
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
+    ))
+    .await
+    .into();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    assert_eq!(
+        body,
+        json!({"error": "Single sign-on request was not started by this user"}).to_string()
+    );
+}
+
+#[actix_rt::test]
+async fn sign_in_with_two_factor() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let user = database.create_user().finish();
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state().await;
+    let encryption_key = &state.config.api_keys_encryption_key;
+    let configuration = create_configuration(&organization, encryption_key, &database);
+    let identity = SsoIdentity {
+        subject: "subject".to_string(),
+        email: user.email.clone(),
+        first_name: None,
+        last_name: None,
+        role_claim_values: Vec::new(),
+    };
+    configuration
+        .link_user(&user, &identity, "access-token".to_string(), connection)
+        .unwrap();
+    let enrollment = UserTotpCredential::enroll(&user, encryption_key, connection).unwrap();
+    let time_step = totp::time_step(Utc::now().timestamp());
+    UserTotpCredential::find_by_user_id(user.id, connection)
+        .unwrap()
+        .confirm(
+            &totp::generate_code(&enrollment.secret, time_step).unwrap(),
+            encryption_key,
+            connection,
+        )
+        .unwrap();
+    let request_info = RequestInfo {
+        user_agent: None,
+        ip_address: None,
+    };
+    let mut json = OidcCallbackRequest {
+        code: "code".to_string(),
+        state: "state".to_string(),
+        totp_code: None,
+        recovery_code: None,
+    };
+
+    // Signing in through the identity provider does not get past the second factor
+    let response = oidc::sign_in(
+        &state,
+        &database.connection,
+        &configuration,
+        &identity,
+        "access-token".to_string(),
+        None,
+        &json,
+        &request_info,
+    );
+    assert_eq!("Two-factor code required", response.err().unwrap().to_string());
+
+    json.totp_code = Some("000000".to_string());
+    let response = oidc::sign_in(
+        &state,
+        &database.connection,
+        &configuration,
+        &identity,
+        "access-token".to_string(),
+        None,
+        &json,
+        &request_info,
+    );
+    assert_eq!("Two-factor code invalid", response.err().unwrap().to_string());
+
+    json.totp_code = Some(totp::generate_code(&enrollment.secret, time_step + 1).unwrap());
+    let response = oidc::sign_in(
+        &state,
+        &database.connection,
+        &configuration,
+        &identity,
+        "access-token".to_string(),
+        None,
+        &json,
+        &request_info,
+    )
+    .unwrap();
+    assert!(!response.access_token.is_empty());
+}
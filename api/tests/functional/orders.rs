@@ -568,6 +568,7 @@ pub async fn refund_for_non_refundable_tickets() {
         items: refund_items,
         reason: None,
         manual_override: false,
+        refund_as_account_credit: false,
     });
 
     let test_request = TestRequest::create();
@@ -657,6 +658,7 @@ pub async fn refund_hold_ticket() {
         items: refund_items,
         reason: Some("Purchased by mistake".to_string()),
         manual_override: false,
+        refund_as_account_credit: false,
     });
 
     let test_request = TestRequest::create();
@@ -709,3 +711,67 @@ pub async fn refund_hold_ticket() {
     assert_eq!(ticket.status, TicketInstanceStatus::Reserved);
     assert_ne!(Some(order_item.id), ticket.order_item_id);
 }
+
+#[actix_rt::test]
+pub async fn refund_as_account_credit() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let user = database.create_user().finish();
+    let order = database
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(2)
+        .is_paid()
+        .finish();
+    let admin = database.create_user().finish();
+    let auth_user = support::create_auth_user_from_user(&admin, Roles::OrgOwner, Some(&organization), &database);
+
+    let items = order.items(&connection).unwrap();
+    let order_item = items.iter().find(|i| i.item_type == OrderItemTypes::Tickets).unwrap();
+    let ticket = &TicketInstance::find_for_order_item(order_item.id, connection).unwrap()[0];
+    let json = Json(RefundAttributes {
+        items: vec![RefundItemRequest {
+            order_item_id: order_item.id,
+            ticket_instance_id: Some(ticket.id),
+        }],
+        reason: None,
+        manual_override: false,
+        refund_as_account_credit: true,
+    });
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = order.id;
+    let response: HttpResponse = orders::refund((
+        database.connection.clone(),
+        path,
+        json,
+        auth_user,
+        test_request.extract_state().await,
+    ))
+    .await
+    .into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let refund_response: RefundResponse = serde_json::from_str(&body).unwrap();
+    let mut expected_refund_breakdown = HashMap::new();
+    expected_refund_breakdown.insert(PaymentMethods::AccountCredit, refund_response.amount_refunded);
+    assert_eq!(refund_response.refund_breakdown, expected_refund_breakdown);
+
+    // Purchaser holds the refunded amount as credit
+    let account_credits = AccountCredit::find_for_user(user.id, connection).unwrap();
+    assert_eq!(account_credits.len(), 1);
+    assert_eq!(account_credits[0].balance_in_cents, refund_response.amount_refunded);
+    let ledger_entries = account_credits[0].ledger_entries(connection).unwrap();
+    assert_eq!(ledger_entries[0].entry_type, StoredValueLedgerEntryTypes::Issued);
+    assert_eq!(ledger_entries[0].order_id, Some(order.id));
+}
@@ -0,0 +1,195 @@
+use crate::functional::base;
+use crate::support;
+use crate::support::database::TestDatabase;
+use crate::support::test_request::TestRequest;
+use actix_web::{
+    http::StatusCode,
+    web::{Path, Query},
+    FromRequest, HttpResponse,
+};
+use api::controllers::organization_events_stream::{self, DomainEventStreamParameters};
+use api::models::PathParameters;
+use chrono::{Duration, Utc};
+use db::models::*;
+use serde_json;
+
+#[cfg(test)]
+mod index_tests {
+    use super::*;
+
+    #[actix_rt::test]
+    async fn index_org_member() {
+        base::organization_events_stream::index(Roles::OrgMember, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn index_admin() {
+        base::organization_events_stream::index(Roles::Admin, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn index_super() {
+        base::organization_events_stream::index(Roles::Super, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn index_user() {
+        base::organization_events_stream::index(Roles::User, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn index_org_owner() {
+        base::organization_events_stream::index(Roles::OrgOwner, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn index_door_person() {
+        base::organization_events_stream::index(Roles::DoorPerson, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn index_promoter() {
+        base::organization_events_stream::index(Roles::Promoter, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn index_promoter_read_only() {
+        base::organization_events_stream::index(Roles::PromoterReadOnly, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn index_org_admin() {
+        base::organization_events_stream::index(Roles::OrgAdmin, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn index_box_office() {
+        base::organization_events_stream::index(Roles::OrgBoxOffice, false).await;
+    }
+}
+
+#[cfg(test)]
+mod live_tests {
+    use super::*;
+
+    #[actix_rt::test]
+    async fn live_org_member() {
+        base::organization_events_stream::live(Roles::OrgMember, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn live_admin() {
+        base::organization_events_stream::live(Roles::Admin, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn live_super() {
+        base::organization_events_stream::live(Roles::Super, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn live_user() {
+        base::organization_events_stream::live(Roles::User, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn live_org_owner() {
+        base::organization_events_stream::live(Roles::OrgOwner, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn live_door_person() {
+        base::organization_events_stream::live(Roles::DoorPerson, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn live_promoter() {
+        base::organization_events_stream::live(Roles::Promoter, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn live_promoter_read_only() {
+        base::organization_events_stream::live(Roles::PromoterReadOnly, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn live_org_admin() {
+        base::organization_events_stream::live(Roles::OrgAdmin, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn live_box_office() {
+        base::organization_events_stream::live(Roles::OrgBoxOffice, false).await;
+    }
+}
+
+#[actix_rt::test]
+async fn index_filtered_by_event_type() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let event = database.create_event().with_organization(&organization).finish();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+    let connection = database.connection.get();
+    let settled_at = Utc::now().naive_utc() - Duration::minutes(1);
+
+    let mut domain_event = DomainEvent::create(
+        DomainEventTypes::OrganizationCreated,
+        "Organization created".to_string(),
+        Tables::Organizations,
+        Some(organization.id),
+        None,
+        None,
+    );
+    domain_event.created_at = Some(settled_at);
+    domain_event.commit(connection).unwrap();
+    let mut domain_event = DomainEvent::create(
+        DomainEventTypes::EventUpdated,
+        "Event updated".to_string(),
+        Tables::Events,
+        Some(event.id),
+        None,
+        None,
+    );
+    domain_event.created_at = Some(settled_at);
+    let event_domain_event = domain_event.commit(connection).unwrap();
+
+    let test_request = TestRequest::create_with_uri(&format!(
+        "/organizations/{}/events_stream?event_types=EventUpdated,EventCreated",
+        organization.id
+    ));
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = organization.id;
+    let query = Query::<DomainEventStreamParameters>::extract(&test_request.request)
+        .await
+        .unwrap();
+    let response: HttpResponse =
+        organization_events_stream::index((database.connection.clone().into(), path, query, auth_user))
+            .await
+            .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let page: DomainEventStreamPage = serde_json::from_str(&body).unwrap();
+    assert_eq!(page.data, vec![event_domain_event]);
+}
+
+#[actix_rt::test]
+async fn index_with_unknown_event_type() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request = TestRequest::create_with_uri(&format!(
+        "/organizations/{}/events_stream?event_types=NotAnEventType",
+        organization.id
+    ));
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = organization.id;
+    let query = Query::<DomainEventStreamParameters>::extract(&test_request.request)
+        .await
+        .unwrap();
+    let response: HttpResponse =
+        organization_events_stream::index((database.connection.clone().into(), path, query, auth_user))
+            .await
+            .into();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
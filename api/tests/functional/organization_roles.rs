@@ -0,0 +1,176 @@
+use crate::functional::base;
+use crate::support;
+use crate::support::database::TestDatabase;
+use crate::support::test_request::TestRequest;
+use actix_web::{http::StatusCode, web::Path, FromRequest, HttpResponse};
+use api::controllers::organization_roles;
+use api::extractors::*;
+use api::models::PathParameters;
+use db::models::*;
+use serde_json;
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+
+    #[actix_rt::test]
+    async fn create_org_member() {
+        base::organization_roles::create(Roles::OrgMember, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_admin() {
+        base::organization_roles::create(Roles::Admin, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_super() {
+        base::organization_roles::create(Roles::Super, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_user() {
+        base::organization_roles::create(Roles::User, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_org_owner() {
+        base::organization_roles::create(Roles::OrgOwner, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_door_person() {
+        base::organization_roles::create(Roles::DoorPerson, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_promoter() {
+        base::organization_roles::create(Roles::Promoter, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_promoter_read_only() {
+        base::organization_roles::create(Roles::PromoterReadOnly, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_org_admin() {
+        base::organization_roles::create(Roles::OrgAdmin, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_box_office() {
+        base::organization_roles::create(Roles::OrgBoxOffice, false).await;
+    }
+}
+
+#[cfg(test)]
+mod update_user_roles_tests {
+    use super::*;
+
+    #[actix_rt::test]
+    async fn update_user_roles_org_member() {
+        base::organization_roles::update_user_roles(Roles::OrgMember, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn update_user_roles_admin() {
+        base::organization_roles::update_user_roles(Roles::Admin, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn update_user_roles_super() {
+        base::organization_roles::update_user_roles(Roles::Super, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn update_user_roles_user() {
+        base::organization_roles::update_user_roles(Roles::User, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn update_user_roles_org_owner() {
+        base::organization_roles::update_user_roles(Roles::OrgOwner, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn update_user_roles_door_person() {
+        base::organization_roles::update_user_roles(Roles::DoorPerson, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn update_user_roles_promoter() {
+        base::organization_roles::update_user_roles(Roles::Promoter, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn update_user_roles_promoter_read_only() {
+        base::organization_roles::update_user_roles(Roles::PromoterReadOnly, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn update_user_roles_org_admin() {
+        base::organization_roles::update_user_roles(Roles::OrgAdmin, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn update_user_roles_box_office() {
+        base::organization_roles::update_user_roles(Roles::OrgBoxOffice, false).await;
+    }
+}
+
+#[actix_rt::test]
+async fn update() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::OrgOwner, Some(&organization), &database);
+    let organization_role =
+        OrganizationRole::create(organization.id, "Marketing".to_string(), None, vec![Scopes::OrgFans])
+            .commit(None, connection)
+            .unwrap();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = organization_role.id;
+    let json = Json(OrganizationRoleEditableAttributes {
+        scopes: Some(vec![Scopes::OrgFans, Scopes::OrgReports]),
+        ..Default::default()
+    });
+    let response: HttpResponse =
+        organization_roles::update((database.connection.clone().into(), path, json, auth_user))
+            .await
+            .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let organization_role: OrganizationRole = serde_json::from_str(&body).unwrap();
+    assert_eq!(
+        organization_role.scopes,
+        vec!["org:fans".to_string(), "org:reports".to_string()]
+    );
+}
+
+#[actix_rt::test]
+async fn destroy() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::OrgOwner, Some(&organization), &database);
+    let organization_role =
+        OrganizationRole::create(organization.id, "Marketing".to_string(), None, vec![Scopes::OrgFans])
+            .commit(None, connection)
+            .unwrap();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = organization_role.id;
+    let response: HttpResponse = organization_roles::destroy((database.connection.clone().into(), path, auth_user))
+        .await
+        .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(OrganizationRole::find_for_organization(organization.id, connection)
+        .unwrap()
+        .is_empty());
+}
@@ -0,0 +1,167 @@
+use crate::functional::base;
+use db::models::*;
+
+#[cfg(test)]
+mod show_tests {
+    use super::*;
+
+    #[actix_rt::test]
+    async fn show_org_member() {
+        base::organization_sso_configurations::show(Roles::OrgMember, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn show_admin() {
+        base::organization_sso_configurations::show(Roles::Admin, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn show_super() {
+        base::organization_sso_configurations::show(Roles::Super, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn show_user() {
+        base::organization_sso_configurations::show(Roles::User, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn show_org_owner() {
+        base::organization_sso_configurations::show(Roles::OrgOwner, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn show_door_person() {
+        base::organization_sso_configurations::show(Roles::DoorPerson, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn show_promoter() {
+        base::organization_sso_configurations::show(Roles::Promoter, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn show_promoter_read_only() {
+        base::organization_sso_configurations::show(Roles::PromoterReadOnly, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn show_org_admin() {
+        base::organization_sso_configurations::show(Roles::OrgAdmin, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn show_box_office() {
+        base::organization_sso_configurations::show(Roles::OrgBoxOffice, false).await;
+    }
+}
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+
+    #[actix_rt::test]
+    async fn create_org_member() {
+        base::organization_sso_configurations::create(Roles::OrgMember, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_admin() {
+        base::organization_sso_configurations::create(Roles::Admin, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_super() {
+        base::organization_sso_configurations::create(Roles::Super, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_user() {
+        base::organization_sso_configurations::create(Roles::User, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_org_owner() {
+        base::organization_sso_configurations::create(Roles::OrgOwner, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_door_person() {
+        base::organization_sso_configurations::create(Roles::DoorPerson, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_promoter() {
+        base::organization_sso_configurations::create(Roles::Promoter, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_promoter_read_only() {
+        base::organization_sso_configurations::create(Roles::PromoterReadOnly, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_org_admin() {
+        base::organization_sso_configurations::create(Roles::OrgAdmin, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_box_office() {
+        base::organization_sso_configurations::create(Roles::OrgBoxOffice, false).await;
+    }
+}
+
+#[cfg(test)]
+mod update_tests {
+    use super::*;
+
+    #[actix_rt::test]
+    async fn update_org_member() {
+        base::organization_sso_configurations::update(Roles::OrgMember, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn update_admin() {
+        base::organization_sso_configurations::update(Roles::Admin, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn update_super() {
+        base::organization_sso_configurations::update(Roles::Super, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn update_user() {
+        base::organization_sso_configurations::update(Roles::User, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn update_org_owner() {
+        base::organization_sso_configurations::update(Roles::OrgOwner, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn update_door_person() {
+        base::organization_sso_configurations::update(Roles::DoorPerson, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn update_promoter() {
+        base::organization_sso_configurations::update(Roles::Promoter, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn update_promoter_read_only() {
+        base::organization_sso_configurations::update(Roles::PromoterReadOnly, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn update_org_admin() {
+        base::organization_sso_configurations::update(Roles::OrgAdmin, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn update_box_office() {
+        base::organization_sso_configurations::update(Roles::OrgBoxOffice, false).await;
+    }
+}
@@ -0,0 +1,332 @@
+use crate::functional::base;
+use db::models::*;
+
+#[cfg(test)]
+mod index_tests {
+    use super::*;
+
+    #[actix_rt::test]
+    async fn index_org_member() {
+        base::organization_webhooks::index(Roles::OrgMember, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn index_admin() {
+        base::organization_webhooks::index(Roles::Admin, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn index_super() {
+        base::organization_webhooks::index(Roles::Super, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn index_user() {
+        base::organization_webhooks::index(Roles::User, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn index_org_owner() {
+        base::organization_webhooks::index(Roles::OrgOwner, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn index_door_person() {
+        base::organization_webhooks::index(Roles::DoorPerson, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn index_promoter() {
+        base::organization_webhooks::index(Roles::Promoter, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn index_promoter_read_only() {
+        base::organization_webhooks::index(Roles::PromoterReadOnly, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn index_org_admin() {
+        base::organization_webhooks::index(Roles::OrgAdmin, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn index_box_office() {
+        base::organization_webhooks::index(Roles::OrgBoxOffice, false).await;
+    }
+}
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+
+    #[actix_rt::test]
+    async fn create_org_member() {
+        base::organization_webhooks::create(Roles::OrgMember, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_admin() {
+        base::organization_webhooks::create(Roles::Admin, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_super() {
+        base::organization_webhooks::create(Roles::Super, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_user() {
+        base::organization_webhooks::create(Roles::User, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_org_owner() {
+        base::organization_webhooks::create(Roles::OrgOwner, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_door_person() {
+        base::organization_webhooks::create(Roles::DoorPerson, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_promoter() {
+        base::organization_webhooks::create(Roles::Promoter, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_promoter_read_only() {
+        base::organization_webhooks::create(Roles::PromoterReadOnly, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_org_admin() {
+        base::organization_webhooks::create(Roles::OrgAdmin, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn create_box_office() {
+        base::organization_webhooks::create(Roles::OrgBoxOffice, false).await;
+    }
+}
+
+#[cfg(test)]
+mod update_tests {
+    use super::*;
+
+    #[actix_rt::test]
+    async fn update_org_member() {
+        base::organization_webhooks::update(Roles::OrgMember, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn update_admin() {
+        base::organization_webhooks::update(Roles::Admin, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn update_super() {
+        base::organization_webhooks::update(Roles::Super, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn update_user() {
+        base::organization_webhooks::update(Roles::User, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn update_org_owner() {
+        base::organization_webhooks::update(Roles::OrgOwner, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn update_door_person() {
+        base::organization_webhooks::update(Roles::DoorPerson, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn update_promoter() {
+        base::organization_webhooks::update(Roles::Promoter, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn update_promoter_read_only() {
+        base::organization_webhooks::update(Roles::PromoterReadOnly, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn update_org_admin() {
+        base::organization_webhooks::update(Roles::OrgAdmin, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn update_box_office() {
+        base::organization_webhooks::update(Roles::OrgBoxOffice, false).await;
+    }
+}
+
+#[cfg(test)]
+mod destroy_tests {
+    use super::*;
+
+    #[actix_rt::test]
+    async fn destroy_org_member() {
+        base::organization_webhooks::destroy(Roles::OrgMember, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn destroy_admin() {
+        base::organization_webhooks::destroy(Roles::Admin, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn destroy_super() {
+        base::organization_webhooks::destroy(Roles::Super, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn destroy_user() {
+        base::organization_webhooks::destroy(Roles::User, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn destroy_org_owner() {
+        base::organization_webhooks::destroy(Roles::OrgOwner, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn destroy_door_person() {
+        base::organization_webhooks::destroy(Roles::DoorPerson, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn destroy_promoter() {
+        base::organization_webhooks::destroy(Roles::Promoter, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn destroy_promoter_read_only() {
+        base::organization_webhooks::destroy(Roles::PromoterReadOnly, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn destroy_org_admin() {
+        base::organization_webhooks::destroy(Roles::OrgAdmin, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn destroy_box_office() {
+        base::organization_webhooks::destroy(Roles::OrgBoxOffice, false).await;
+    }
+}
+
+#[cfg(test)]
+mod deliveries_tests {
+    use super::*;
+
+    #[actix_rt::test]
+    async fn deliveries_org_member() {
+        base::organization_webhooks::deliveries(Roles::OrgMember, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn deliveries_admin() {
+        base::organization_webhooks::deliveries(Roles::Admin, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn deliveries_super() {
+        base::organization_webhooks::deliveries(Roles::Super, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn deliveries_user() {
+        base::organization_webhooks::deliveries(Roles::User, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn deliveries_org_owner() {
+        base::organization_webhooks::deliveries(Roles::OrgOwner, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn deliveries_door_person() {
+        base::organization_webhooks::deliveries(Roles::DoorPerson, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn deliveries_promoter() {
+        base::organization_webhooks::deliveries(Roles::Promoter, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn deliveries_promoter_read_only() {
+        base::organization_webhooks::deliveries(Roles::PromoterReadOnly, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn deliveries_org_admin() {
+        base::organization_webhooks::deliveries(Roles::OrgAdmin, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn deliveries_box_office() {
+        base::organization_webhooks::deliveries(Roles::OrgBoxOffice, false).await;
+    }
+}
+
+#[cfg(test)]
+mod replay_delivery_tests {
+    use super::*;
+
+    #[actix_rt::test]
+    async fn replay_delivery_org_member() {
+        base::organization_webhooks::replay_delivery(Roles::OrgMember, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn replay_delivery_admin() {
+        base::organization_webhooks::replay_delivery(Roles::Admin, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn replay_delivery_super() {
+        base::organization_webhooks::replay_delivery(Roles::Super, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn replay_delivery_user() {
+        base::organization_webhooks::replay_delivery(Roles::User, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn replay_delivery_org_owner() {
+        base::organization_webhooks::replay_delivery(Roles::OrgOwner, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn replay_delivery_door_person() {
+        base::organization_webhooks::replay_delivery(Roles::DoorPerson, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn replay_delivery_promoter() {
+        base::organization_webhooks::replay_delivery(Roles::Promoter, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn replay_delivery_promoter_read_only() {
+        base::organization_webhooks::replay_delivery(Roles::PromoterReadOnly, false).await;
+    }
+
+    #[actix_rt::test]
+    async fn replay_delivery_org_admin() {
+        base::organization_webhooks::replay_delivery(Roles::OrgAdmin, true).await;
+    }
+
+    #[actix_rt::test]
+    async fn replay_delivery_box_office() {
+        base::organization_webhooks::replay_delivery(Roles::OrgBoxOffice, false).await;
+    }
+}
@@ -7,10 +7,12 @@ use api::auth::TokenResponse;
 use api::controllers::password_resets::{self, CreatePasswordResetParameters, UpdatePasswordResetParameters};
 use api::database::Connection as ApiConnection;
 use api::extractors::*;
+use api::models::RequestInfo;
 use chrono::{Duration, Utc};
 use db::models::concerns::users::password_resetable::*;
 use db::models::TokenIssuer;
-use db::models::User;
+use db::models::{User, UserTotpCredential};
+use db::utils::totp;
 use diesel;
 use diesel::prelude::*;
 use serde_json;
@@ -78,11 +80,23 @@ async fn update() {
     let json = Json(UpdatePasswordResetParameters {
         password_reset_token: user.password_reset_token.unwrap(),
         password: new_password.to_string(),
+        totp_code: None,
+        recovery_code: None,
     });
 
     let token_issuer = state.config.token_issuer.clone();
 
-    let response: HttpResponse = password_resets::update((state, connection_object, json)).await.into();
+    let response: HttpResponse = password_resets::update((
+        state,
+        connection_object,
+        json,
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
+    ))
+    .await
+    .into();
 
     let user = User::find(user.id, database.connection.get()).unwrap();
     assert!(user.password_reset_token.is_none());
@@ -125,8 +139,20 @@ async fn update_expired_token() {
     let json = Json(UpdatePasswordResetParameters {
         password_reset_token: token,
         password: new_password.to_string(),
+        totp_code: None,
+        recovery_code: None,
     });
-    let response: HttpResponse = password_resets::update((state, connection_object, json)).await.into();
+    let response: HttpResponse = password_resets::update((
+        state,
+        connection_object,
+        json,
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
+    ))
+    .await
+    .into();
 
     let user = User::find(user.id, database.connection.get()).unwrap();
     assert_eq!(user.password_reset_token.unwrap(), token);
@@ -151,8 +177,20 @@ async fn update_incorrect_token() {
     let json = Json(UpdatePasswordResetParameters {
         password_reset_token: Uuid::new_v4(),
         password: new_password.to_string(),
+        totp_code: None,
+        recovery_code: None,
     });
-    let response: HttpResponse = password_resets::update((state, connection_object, json)).await.into();
+    let response: HttpResponse = password_resets::update((
+        state,
+        connection_object,
+        json,
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
+    ))
+    .await
+    .into();
 
     let user = User::find(user.id, database.connection.get()).unwrap();
     assert_eq!(user.password_reset_token.unwrap(), token);
@@ -161,3 +199,66 @@ async fn update_incorrect_token() {
 
     assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
 }
+
+#[actix_rt::test]
+async fn update_with_two_factor() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let user = user.create_password_reset_token(connection).unwrap();
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state().await;
+    let encryption_key = &state.config.api_keys_encryption_key;
+    let enrollment = UserTotpCredential::enroll(&user, encryption_key, connection).unwrap();
+    let time_step = totp::time_step(Utc::now().timestamp());
+    UserTotpCredential::find_by_user_id(user.id, connection)
+        .unwrap()
+        .confirm(
+            &totp::generate_code(&enrollment.secret, time_step).unwrap(),
+            encryption_key,
+            connection,
+        )
+        .unwrap();
+
+    // Access to the user's email alone does not get past their second factor
+    let json = Json(UpdatePasswordResetParameters {
+        password_reset_token: user.password_reset_token.unwrap(),
+        password: "newPassword".to_string(),
+        totp_code: None,
+        recovery_code: None,
+    });
+    let response = password_resets::update((
+        state.clone(),
+        database.connection.clone().into(),
+        json,
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
+    ))
+    .await;
+    assert_eq!("Two-factor code required", response.err().unwrap().to_string());
+    let unchanged_user = User::find(user.id, connection).unwrap();
+    assert!(!unchanged_user.check_password("newPassword"));
+    assert!(unchanged_user.password_reset_token.is_some());
+
+    let json = Json(UpdatePasswordResetParameters {
+        password_reset_token: user.password_reset_token.unwrap(),
+        password: "newPassword".to_string(),
+        totp_code: Some(totp::generate_code(&enrollment.secret, time_step + 1).unwrap()),
+        recovery_code: None,
+    });
+    let response: HttpResponse = password_resets::update((
+        state,
+        database.connection.clone().into(),
+        json,
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
+    ))
+    .await
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(User::find(user.id, connection).unwrap().check_password("newPassword"));
+}
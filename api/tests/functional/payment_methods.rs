@@ -1,8 +1,10 @@
 use crate::support;
 use crate::support::database::TestDatabase;
-use actix_web::{http::StatusCode, HttpResponse};
+use crate::support::test_request::TestRequest;
+use actix_web::{http::StatusCode, web::Path, FromRequest, HttpResponse};
 use api::controllers::payment_methods;
-use db::models::{DisplayPaymentMethod, PaymentProviders, Roles};
+use api::models::PathParameters;
+use db::models::{DisplayPaymentMethod, ForDisplay, PaymentMethod, PaymentProviders, Roles};
 use serde_json;
 
 #[actix_rt::test]
@@ -31,3 +33,82 @@ async fn index() {
     let body = support::unwrap_body_to_string(&response).unwrap();
     assert_eq!(body, payment_methods_expected_json);
 }
+
+#[actix_rt::test]
+async fn destroy() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let payment_method = database
+        .create_payment_method()
+        .with_user(&user)
+        .make_default()
+        .finish();
+    let payment_method2 = database.create_payment_method().with_user(&user).finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = payment_method.id;
+    let response: HttpResponse = payment_methods::destroy((database.connection.clone().into(), path, auth_user))
+        .await
+        .into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(PaymentMethod::find(payment_method.id, connection).is_err());
+    assert_eq!(user.default_payment_method(connection).unwrap().id, payment_method2.id);
+}
+
+#[actix_rt::test]
+async fn destroy_payment_method_of_other_user() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let payment_method = database.create_payment_method().make_default().finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = payment_method.id;
+    let response: HttpResponse = payment_methods::destroy((database.connection.clone().into(), path, auth_user))
+        .await
+        .into();
+
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert!(PaymentMethod::find(payment_method.id, connection).is_ok());
+}
+
+#[actix_rt::test]
+async fn set_default() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let payment_method = database
+        .create_payment_method()
+        .with_user(&user)
+        .make_default()
+        .finish();
+    let payment_method2 = database
+        .create_payment_method()
+        .with_user(&user)
+        .with_card_expiry(8, 2024)
+        .finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = payment_method2.id;
+    let response: HttpResponse = payment_methods::set_default((database.connection.clone().into(), path, auth_user))
+        .await
+        .into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let payment_method2 = PaymentMethod::find(payment_method2.id, connection).unwrap();
+    assert!(payment_method2.is_default);
+    assert!(!PaymentMethod::find(payment_method.id, connection).unwrap().is_default);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    assert_eq!(
+        body,
+        serde_json::to_string(&payment_method2.for_display().unwrap()).unwrap()
+    );
+}
@@ -59,6 +59,7 @@ async fn show_event() {
         OptionalUser(Some(auth_user.clone())),
         RequestInfo {
             user_agent: Some("test".to_string()),
+            ip_address: None,
         },
     ))
     .await
@@ -122,6 +123,7 @@ async fn show_redirect_to_primary_slug() {
             OptionalUser(Some(auth_user.clone())),
             RequestInfo {
                 user_agent: Some("test".to_string()),
+                ip_address: None,
             },
         ))
         .await
@@ -187,6 +189,7 @@ async fn show_venue() {
         OptionalUser(Some(auth_user.clone())),
         RequestInfo {
             user_agent: Some("test".to_string()),
+            ip_address: None,
         },
     ))
     .await
@@ -223,6 +226,7 @@ async fn show_venue() {
         OptionalUser(Some(auth_user.clone())),
         RequestInfo {
             user_agent: Some("test".to_string()),
+            ip_address: None,
         },
     ))
     .await
@@ -292,6 +296,7 @@ async fn show_organization() {
         OptionalUser(Some(auth_user.clone())),
         RequestInfo {
             user_agent: Some("test".to_string()),
+            ip_address: None,
         },
     ))
     .await
@@ -328,6 +333,7 @@ async fn show_organization() {
         OptionalUser(Some(auth_user.clone())),
         RequestInfo {
             user_agent: Some("test".to_string()),
+            ip_address: None,
         },
     ))
     .await
@@ -406,6 +412,7 @@ async fn show_city() {
         OptionalUser(Some(auth_user.clone())),
         RequestInfo {
             user_agent: Some("test".to_string()),
+            ip_address: None,
         },
     ))
     .await
@@ -450,6 +457,7 @@ async fn show_city() {
         OptionalUser(Some(auth_user.clone())),
         RequestInfo {
             user_agent: Some("test".to_string()),
+            ip_address: None,
         },
     ))
     .await
@@ -542,6 +550,7 @@ async fn show_genre() {
         OptionalUser(Some(auth_user.clone())),
         RequestInfo {
             user_agent: Some("test".to_string()),
+            ip_address: None,
         },
     ))
     .await
@@ -82,3 +82,85 @@ mod update_tests {
         base::stages::update(Roles::OrgBoxOffice, false).await;
     }
 }
+
+#[cfg(test)]
+mod create_section_tests {
+    use super::*;
+    #[actix_rt::test]
+    async fn create_section_org_member() {
+        base::stages::create_section(Roles::OrgMember, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_section_admin() {
+        base::stages::create_section(Roles::Admin, true).await;
+    }
+    #[actix_rt::test]
+    async fn create_section_user() {
+        base::stages::create_section(Roles::User, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_section_org_owner() {
+        base::stages::create_section(Roles::OrgOwner, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_section_door_person() {
+        base::stages::create_section(Roles::DoorPerson, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_section_promoter() {
+        base::stages::create_section(Roles::Promoter, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_section_promoter_read_only() {
+        base::stages::create_section(Roles::PromoterReadOnly, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_section_org_admin() {
+        base::stages::create_section(Roles::OrgAdmin, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_section_box_office() {
+        base::stages::create_section(Roles::OrgBoxOffice, false).await;
+    }
+}
+
+#[cfg(test)]
+mod create_seats_tests {
+    use super::*;
+    #[actix_rt::test]
+    async fn create_seats_org_member() {
+        base::stages::create_seats(Roles::OrgMember, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_seats_admin() {
+        base::stages::create_seats(Roles::Admin, true).await;
+    }
+    #[actix_rt::test]
+    async fn create_seats_user() {
+        base::stages::create_seats(Roles::User, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_seats_org_owner() {
+        base::stages::create_seats(Roles::OrgOwner, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_seats_door_person() {
+        base::stages::create_seats(Roles::DoorPerson, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_seats_promoter() {
+        base::stages::create_seats(Roles::Promoter, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_seats_promoter_read_only() {
+        base::stages::create_seats(Roles::PromoterReadOnly, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_seats_org_admin() {
+        base::stages::create_seats(Roles::OrgAdmin, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_seats_box_office() {
+        base::stages::create_seats(Roles::OrgBoxOffice, false).await;
+    }
+}
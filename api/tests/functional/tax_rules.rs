@@ -0,0 +1,84 @@
+use crate::functional::base;
+use db::models::*;
+
+#[cfg(test)]
+mod create_for_venue_tests {
+    use super::*;
+    #[actix_rt::test]
+    async fn create_for_venue_org_member() {
+        base::tax_rules::create_for_venue(Roles::OrgMember, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_for_venue_admin() {
+        base::tax_rules::create_for_venue(Roles::Admin, true).await;
+    }
+    #[actix_rt::test]
+    async fn create_for_venue_user() {
+        base::tax_rules::create_for_venue(Roles::User, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_for_venue_org_owner() {
+        base::tax_rules::create_for_venue(Roles::OrgOwner, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_for_venue_door_person() {
+        base::tax_rules::create_for_venue(Roles::DoorPerson, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_for_venue_promoter() {
+        base::tax_rules::create_for_venue(Roles::Promoter, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_for_venue_promoter_read_only() {
+        base::tax_rules::create_for_venue(Roles::PromoterReadOnly, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_for_venue_org_admin() {
+        base::tax_rules::create_for_venue(Roles::OrgAdmin, false).await;
+    }
+    #[actix_rt::test]
+    async fn create_for_venue_box_office() {
+        base::tax_rules::create_for_venue(Roles::OrgBoxOffice, false).await;
+    }
+}
+
+#[cfg(test)]
+mod update_tests {
+    use super::*;
+    #[actix_rt::test]
+    async fn update_org_member() {
+        base::tax_rules::update(Roles::OrgMember, false).await;
+    }
+    #[actix_rt::test]
+    async fn update_admin() {
+        base::tax_rules::update(Roles::Admin, true).await;
+    }
+    #[actix_rt::test]
+    async fn update_user() {
+        base::tax_rules::update(Roles::User, false).await;
+    }
+    #[actix_rt::test]
+    async fn update_org_owner() {
+        base::tax_rules::update(Roles::OrgOwner, false).await;
+    }
+    #[actix_rt::test]
+    async fn update_door_person() {
+        base::tax_rules::update(Roles::DoorPerson, false).await;
+    }
+    #[actix_rt::test]
+    async fn update_promoter() {
+        base::tax_rules::update(Roles::Promoter, false).await;
+    }
+    #[actix_rt::test]
+    async fn update_promoter_read_only() {
+        base::tax_rules::update(Roles::PromoterReadOnly, false).await;
+    }
+    #[actix_rt::test]
+    async fn update_org_admin() {
+        base::tax_rules::update(Roles::OrgAdmin, false).await;
+    }
+    #[actix_rt::test]
+    async fn update_box_office() {
+        base::tax_rules::update(Roles::OrgBoxOffice, false).await;
+    }
+}
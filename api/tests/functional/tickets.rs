@@ -140,6 +140,7 @@ pub async fn index() {
         transfer_address: None,
         check_in_source: None,
         promo_image_url: None,
+        seat: None,
     };
     assert_eq!(vec![expected_ticket.clone()], found_data.data);
     // Test without specified event
@@ -171,6 +172,7 @@ pub async fn index() {
         transfer_address: None,
         check_in_source: None,
         promo_image_url: None,
+        seat: None,
     };
     assert_eq!(
         vec![
@@ -294,6 +296,7 @@ pub async fn show() {
         transfer_address: None,
         check_in_source: None,
         promo_image_url: None,
+        seat: None,
     };
 
     let expected_result = ShowTicketResponse {
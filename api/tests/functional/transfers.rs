@@ -9,6 +9,7 @@ use actix_web::{
 };
 use api::controllers::transfers::{self, *};
 use api::errors::ApiError;
+use api::extractors::*;
 use api::models::*;
 use chrono::prelude::*;
 use db::prelude::*;
@@ -332,6 +333,52 @@ pub async fn show_by_transfer_key() {
     assert_eq!(found_transfer.status, TransferStatus::Pending);
 }
 
+#[actix_rt::test]
+async fn decline() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    database.create_order().for_user(&user).quantity(2).is_paid().finish();
+    let tickets = TicketInstance::find_for_user(user.id, connection).unwrap();
+    let transfer = TicketInstance::create_transfer(
+        &user,
+        &[tickets[0].id, tickets[1].id],
+        Some("example@tari.com"),
+        Some(TransferMessageType::Email),
+        false,
+        connection,
+    )
+    .unwrap();
+
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state().await;
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = transfer.transfer_key;
+    let response: HttpResponse = transfers::decline((
+        database.connection.clone().into(),
+        path,
+        Json(DeclineTransferRequest {
+            ticket_ids: Some(vec![tickets[1].id]),
+        }),
+        OptionalUser(None),
+        state,
+    ))
+    .await
+    .into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let decline_response: DeclineTransferResponse = serde_json::from_str(&body).unwrap();
+    assert_eq!(decline_response.declined_transfer.status, TransferStatus::Declined);
+    assert_eq!(decline_response.declined_transfer.ticket_ids, vec![tickets[1].id]);
+    let remaining_transfer_authorization = decline_response.remaining_transfer_authorization.unwrap();
+    assert_eq!(remaining_transfer_authorization.transfer_key, transfer.transfer_key);
+    assert_eq!(remaining_transfer_authorization.num_tickets, 1);
+
+    let transfer = Transfer::find(transfer.id, connection).unwrap();
+    assert_eq!(transfer.status, TransferStatus::Pending);
+}
+
 #[actix_rt::test]
 async fn index() {
     let database = TestDatabase::new();
@@ -0,0 +1,98 @@
+use crate::support;
+use crate::support::database::TestDatabase;
+use crate::support::test_request::TestRequest;
+use actix_web::{http::StatusCode, HttpResponse};
+use api::controllers::two_factor::{self, RecoveryCodesResponse, TwoFactorCodeRequest};
+use api::extractors::*;
+use chrono::Utc;
+use db::models::*;
+use db::utils::totp;
+use serde_json;
+
+#[actix_rt::test]
+async fn enroll() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state().await;
+    let response: HttpResponse = two_factor::enroll((database.connection.clone().into(), auth_user, state))
+        .await
+        .into();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let enrollment: TotpEnrollment = serde_json::from_str(&body).unwrap();
+    assert!(enrollment.provisioning_uri.contains(&enrollment.secret));
+    let credential = UserTotpCredential::find_by_user_id(user.id, connection).unwrap();
+    assert!(credential.confirmed_at.is_none());
+}
+
+#[actix_rt::test]
+async fn confirm() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state().await;
+    let enrollment = UserTotpCredential::enroll(&user, &state.config.api_keys_encryption_key, connection).unwrap();
+
+    let json = Json(TwoFactorCodeRequest {
+        code: totp::generate_code(&enrollment.secret, totp::time_step(Utc::now().timestamp())).unwrap(),
+    });
+    let response: HttpResponse = two_factor::confirm((database.connection.clone().into(), auth_user, state, json))
+        .await
+        .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let recovery_codes: RecoveryCodesResponse = serde_json::from_str(&body).unwrap();
+    assert_eq!(recovery_codes.recovery_codes.len(), 10);
+    assert!(UserTotpCredential::find_confirmed_for_user(user.id, connection)
+        .unwrap()
+        .is_some());
+}
+
+#[actix_rt::test]
+async fn disable() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state().await;
+    let encryption_key = &state.config.api_keys_encryption_key;
+    let enrollment = UserTotpCredential::enroll(&user, encryption_key, connection).unwrap();
+    let credential = UserTotpCredential::find_by_user_id(user.id, connection).unwrap();
+    let recovery_codes = credential
+        .confirm(
+            &totp::generate_code(&enrollment.secret, totp::time_step(Utc::now().timestamp())).unwrap(),
+            encryption_key,
+            connection,
+        )
+        .unwrap();
+
+    // Proof of the second factor is required
+    let json = Json(TwoFactorCodeRequest {
+        code: "000000".to_string(),
+    });
+    let response: HttpResponse = two_factor::disable((
+        database.connection.clone().into(),
+        auth_user.clone(),
+        state.clone(),
+        json,
+    ))
+    .await
+    .into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let json = Json(TwoFactorCodeRequest {
+        code: recovery_codes[0].clone(),
+    });
+    let response: HttpResponse = two_factor::disable((database.connection.clone().into(), auth_user, state, json))
+        .await
+        .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(UserTotpCredential::find_by_user_id(user.id, connection).is_err());
+}
@@ -0,0 +1,102 @@
+use crate::support;
+use crate::support::database::TestDatabase;
+use crate::support::test_request::TestRequest;
+use actix_web::{http::StatusCode, web::Path, FromRequest, HttpResponse};
+use api::controllers::user_sessions;
+use api::models::PathParameters;
+use db::models::*;
+use serde_json;
+
+#[actix_rt::test]
+async fn index() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let session = UserSession::create(user.id, Some("Mozilla/5.0".to_string()), None)
+        .commit(connection)
+        .unwrap();
+    let session2 = UserSession::create(user.id, None, None).commit(connection).unwrap();
+    let revoked_session = UserSession::create(user.id, None, None).commit(connection).unwrap();
+    revoked_session
+        .revoke(SessionRevocationReasons::SignedOut, Some(user.id), connection)
+        .unwrap();
+    let auth_user =
+        support::create_auth_user_from_user(&user, Roles::User, None, &database).with_session_id(Some(session.id));
+
+    let response: HttpResponse = user_sessions::index((database.connection.clone().into(), auth_user))
+        .await
+        .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let sessions: Vec<DisplayUserSession> = serde_json::from_str(&body).unwrap();
+    assert_eq!(sessions.len(), 2);
+    let current_session = sessions.iter().find(|s| s.id == session.id).unwrap();
+    assert!(current_session.current);
+    assert_eq!(current_session.user_agent, Some("Mozilla/5.0".to_string()));
+    assert!(!sessions.iter().find(|s| s.id == session2.id).unwrap().current);
+}
+
+#[actix_rt::test]
+async fn destroy() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let session = UserSession::create(user.id, None, None).commit(connection).unwrap();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = session.id;
+    let response: HttpResponse = user_sessions::destroy((database.connection.clone().into(), path, auth_user))
+        .await
+        .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let session = UserSession::find(session.id, connection).unwrap();
+    assert!(!session.is_active());
+    assert_eq!(session.revoked_reason, Some(SessionRevocationReasons::SignedOut));
+}
+
+#[actix_rt::test]
+async fn destroy_other_users_session() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let other_user = database.create_user().finish();
+    let session = UserSession::create(other_user.id, None, None)
+        .commit(connection)
+        .unwrap();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).await.unwrap();
+    path.id = session.id;
+    let response: HttpResponse = user_sessions::destroy((database.connection.clone().into(), path, auth_user))
+        .await
+        .into();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert!(UserSession::find(session.id, connection).unwrap().is_active());
+}
+
+#[actix_rt::test]
+async fn destroy_all() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let other_user = database.create_user().finish();
+    let session = UserSession::create(user.id, None, None).commit(connection).unwrap();
+    UserSession::create(user.id, None, None).commit(connection).unwrap();
+    let other_session = UserSession::create(other_user.id, None, None)
+        .commit(connection)
+        .unwrap();
+    let auth_user =
+        support::create_auth_user_from_user(&user, Roles::User, None, &database).with_session_id(Some(session.id));
+
+    let response: HttpResponse = user_sessions::destroy_all((database.connection.clone().into(), auth_user))
+        .await
+        .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(UserSession::find_active_for_user(user.id, connection)
+        .unwrap()
+        .is_empty());
+    assert!(UserSession::find(other_session.id, connection).unwrap().is_active());
+}
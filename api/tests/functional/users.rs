@@ -461,7 +461,10 @@ async fn register_succeeds_with_login() {
         request.request,
         database.connection.into(),
         json,
-        RequestInfo { user_agent: None },
+        RequestInfo {
+            user_agent: None,
+            ip_address: None,
+        },
     ))
     .await
     .into();
@@ -0,0 +1,146 @@
+use crate::functional::base;
+use crate::support;
+use crate::support::database::TestDatabase;
+use crate::support::test_request::TestRequest;
+use actix_web::{http::StatusCode, web::Path, FromRequest, HttpResponse};
+use api::controllers::waitlist_entries::{self, *};
+use api::extractors::*;
+use api::models::*;
+use db::prelude::*;
+
+#[cfg(test)]
+mod index_tests {
+    use super::*;
+    #[actix_rt::test]
+    async fn index_org_member() {
+        base::waitlist_entries::index(Roles::OrgMember, true).await;
+    }
+    #[actix_rt::test]
+    async fn index_admin() {
+        base::waitlist_entries::index(Roles::Admin, true).await;
+    }
+    #[actix_rt::test]
+    async fn index_user() {
+        base::waitlist_entries::index(Roles::User, false).await;
+    }
+    #[actix_rt::test]
+    async fn index_org_owner() {
+        base::waitlist_entries::index(Roles::OrgOwner, true).await;
+    }
+    #[actix_rt::test]
+    async fn index_door_person() {
+        base::waitlist_entries::index(Roles::DoorPerson, false).await;
+    }
+    #[actix_rt::test]
+    async fn index_promoter() {
+        base::waitlist_entries::index(Roles::Promoter, true).await;
+    }
+    #[actix_rt::test]
+    async fn index_promoter_read_only() {
+        base::waitlist_entries::index(Roles::PromoterReadOnly, true).await;
+    }
+    #[actix_rt::test]
+    async fn index_org_admin() {
+        base::waitlist_entries::index(Roles::OrgAdmin, true).await;
+    }
+    #[actix_rt::test]
+    async fn index_box_office() {
+        base::waitlist_entries::index(Roles::OrgBoxOffice, false).await;
+    }
+}
+
+#[actix_rt::test]
+async fn create() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let event = database
+        .create_event()
+        .with_a_specific_number_of_tickets(1)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let test_request = TestRequest::create_with_uri_custom_params("/", vec!["event_id", "ticket_type_id"]);
+    let mut path = Path::<EventTicketPathParameters>::extract(&test_request.request)
+        .await
+        .unwrap();
+    path.event_id = event.id;
+    path.ticket_type_id = ticket_type.id;
+
+    // Tickets are still available
+    let response: HttpResponse = waitlist_entries::create((
+        database.connection.clone().into(),
+        path,
+        Json(JoinWaitlistRequest { quantity: 1 }),
+        auth_user.clone(),
+    ))
+    .await
+    .into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    database
+        .create_order()
+        .for_event(&event)
+        .for_tickets(ticket_type.id)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let mut path = Path::<EventTicketPathParameters>::extract(&test_request.request)
+        .await
+        .unwrap();
+    path.event_id = event.id;
+    path.ticket_type_id = ticket_type.id;
+    let response: HttpResponse = waitlist_entries::create((
+        database.connection.clone().into(),
+        path,
+        Json(JoinWaitlistRequest { quantity: 1 }),
+        auth_user,
+    ))
+    .await
+    .into();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let entry: WaitlistEntry = serde_json::from_str(&body).unwrap();
+    assert_eq!(entry.user_id, user.id);
+    assert_eq!(entry.status, WaitlistEntryStatus::Waiting);
+}
+
+#[actix_rt::test]
+async fn destroy() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let event = database
+        .create_event()
+        .with_a_specific_number_of_tickets(1)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    database
+        .create_order()
+        .for_event(&event)
+        .for_tickets(ticket_type.id)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let entry = WaitlistEntry::create(ticket_type.id, user.id, 1)
+        .commit(connection)
+        .unwrap();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let test_request = TestRequest::create_with_uri_custom_params("/", vec!["event_id", "ticket_type_id"]);
+    let mut path = Path::<EventTicketPathParameters>::extract(&test_request.request)
+        .await
+        .unwrap();
+    path.event_id = event.id;
+    path.ticket_type_id = ticket_type.id;
+
+    let response: HttpResponse = waitlist_entries::destroy((database.connection.clone().into(), path, auth_user))
+        .await
+        .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let entry = WaitlistEntry::find(entry.id, connection).unwrap();
+    assert_eq!(entry.status, WaitlistEntryStatus::Cancelled);
+}
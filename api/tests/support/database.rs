@@ -137,6 +137,10 @@ impl TestDatabase {
         StageBuilder::new(self.connection.get())
     }
 
+    pub fn create_stage_section(&self) -> StageSectionBuilder {
+        StageSectionBuilder::new(self.connection.get())
+    }
+
     pub fn create_settlement_entry(&self) -> SettlementEntryBuilder {
         SettlementEntryBuilder::new(self.connection.get())
     }
@@ -0,0 +1,63 @@
+use api::config::DomainActionSettings;
+use api::domain_events::scheduler::DomainActionScheduler;
+use db::prelude::*;
+use std::collections::HashMap;
+use std::time::Duration;
+
+fn settings() -> DomainActionSettings {
+    let mut concurrency = HashMap::new();
+    concurrency.insert(DomainActionTypes::SendAutomaticReportEmails, 1);
+    let mut timeouts = HashMap::new();
+    timeouts.insert(DomainActionTypes::SendAutomaticReportEmails, 300);
+    DomainActionSettings {
+        listen_for_notifications: false,
+        max_concurrency: 3,
+        default_timeout: 55,
+        concurrency,
+        timeouts,
+    }
+}
+
+#[test]
+fn try_reserve() {
+    let scheduler = DomainActionScheduler::new(settings());
+
+    let report_slot = scheduler.try_reserve(DomainActionTypes::SendAutomaticReportEmails);
+    assert!(report_slot.is_some());
+    assert_eq!(scheduler.in_flight(DomainActionTypes::SendAutomaticReportEmails), 1);
+    // Limited to one report run at a time
+    assert!(scheduler
+        .try_reserve(DomainActionTypes::SendAutomaticReportEmails)
+        .is_none());
+
+    // Other types are not blocked by the report run
+    let communication_slot = scheduler.try_reserve(DomainActionTypes::Communication);
+    let communication_slot2 = scheduler.try_reserve(DomainActionTypes::Communication);
+    assert!(communication_slot.is_some());
+    assert!(communication_slot2.is_some());
+    assert_eq!(scheduler.available(), 0);
+    // Overall limit reached
+    assert!(scheduler.try_reserve(DomainActionTypes::Communication).is_none());
+
+    // Dropping a slot frees it up again
+    drop(report_slot);
+    assert_eq!(scheduler.in_flight(DomainActionTypes::SendAutomaticReportEmails), 0);
+    assert_eq!(scheduler.available(), 1);
+    assert!(scheduler
+        .try_reserve(DomainActionTypes::SendAutomaticReportEmails)
+        .is_some());
+}
+
+#[test]
+fn timeout_for() {
+    let scheduler = DomainActionScheduler::new(settings());
+    assert_eq!(
+        scheduler.timeout_for(DomainActionTypes::SendAutomaticReportEmails),
+        Duration::from_secs(300)
+    );
+    assert_eq!(
+        scheduler.timeout_for(DomainActionTypes::Communication),
+        Duration::from_secs(55)
+    );
+    assert_eq!(scheduler.busy_period_for(DomainActionTypes::Communication), 60);
+}
@@ -348,3 +348,63 @@ where
 {
     serde_json::from_value::<T>(payload.get(&key.to_string()).unwrap().clone()).unwrap()
 }
+
+#[test]
+fn publish_to_organization_webhook() {
+    let project = TestDatabase::new();
+    let connection = project.connection.get();
+    let organization = project.create_organization().finish();
+    let user = project.create_user().finish();
+    let config = Config::new(Environment::Test);
+    let publisher = WebhookPublisher::new(
+        "http://localhost:5432".to_string(),
+        DefaultTokenIssuer::new("asdf".into(), "asdf".into()),
+        Box::new(BranchDeepLinker::new(
+            config.branch_io_base_url.clone(),
+            config.branch_io_branch_key.clone(),
+            500,
+        )),
+    );
+    let webhook = project
+        .create_domain_event_publisher()
+        .with_organization(&organization)
+        .with_event_types(vec![DomainEventTypes::PushNotificationTokenCreated])
+        .with_signing_secret("whsec_test")
+        .finish();
+
+    let push_token = PushNotificationToken::create(user.id, "source".to_string(), "token".to_string())
+        .commit(user.id, connection)
+        .unwrap();
+    let domain_event = DomainEvent::create(
+        DomainEventTypes::PushNotificationTokenCreated,
+        "Nothing to see here".to_string(),
+        Tables::PushNotificationTokens,
+        Some(push_token.id),
+        None,
+        None,
+    )
+    .commit(connection)
+    .unwrap();
+    publisher.publish(&webhook, &domain_event, connection).unwrap();
+
+    // Signed webhooks are delivered directly rather than queued as communications
+    let deliveries = WebhookDelivery::find_for_publisher(webhook.id, 0, 100, connection).unwrap();
+    assert_eq!(deliveries.data.len(), 1);
+    let delivery = &deliveries.data[0];
+    assert_eq!(delivery.domain_event_id, domain_event.id);
+    assert_eq!(delivery.status, WebhookDeliveryStatus::Pending);
+    let payload: serde_json::Value = serde_json::from_str(&delivery.payload).unwrap();
+    assert_eq!(payload["id"], json!(domain_event.id));
+    assert_eq!(
+        payload["event_type"],
+        json!(DomainEventTypes::PushNotificationTokenCreated)
+    );
+    assert_eq!(payload["data"]["user_id"], json!(user.id));
+    assert!(payload["data"].get("token").is_none());
+    assert_eq!(
+        DomainAction::find_pending(Some(DomainActionTypes::DeliverWebhook), connection)
+            .unwrap()
+            .len(),
+        1
+    );
+}
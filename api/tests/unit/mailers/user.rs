@@ -2,7 +2,7 @@ use crate::support::database::TestDatabase;
 use api::communications::mailers;
 use api::config::Config;
 use db::models::concerns::users::password_resetable::PasswordResetable;
-use db::models::{CommAddress, Environment};
+use db::models::{CommAddress, CommunicationTemplate, CommunicationTemplateNames, CommunicationType, Environment};
 
 #[test]
 fn password_reset_email() {
@@ -12,7 +12,7 @@ fn password_reset_email() {
     let user = database.create_user().finish();
     let user = user.create_password_reset_token(database.connection.get()).unwrap();
 
-    let password_reset_email = mailers::user::password_reset_email(&config, &user);
+    let password_reset_email = mailers::user::password_reset_email(&config, &user, database.connection.get()).unwrap();
     assert_eq!(
         password_reset_email.destinations,
         CommAddress::from(user.email.unwrap().to_string())
@@ -22,3 +22,35 @@ fn password_reset_email() {
         Some(CommAddress::from("noreply@bigneon.com".to_string()))
     );
 }
+
+#[test]
+fn password_reset_email_with_stored_template() {
+    let config = Config::new(Environment::Test);
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+
+    let user = database.create_user().with_first_name("Sam").finish();
+    let user = user.create_password_reset_token(connection).unwrap();
+    CommunicationTemplate::create(
+        None,
+        CommunicationTemplateNames::PasswordReset,
+        CommunicationType::EmailTemplate,
+        "Reset your password {{name}}".to_string(),
+        "<a href=\"{{password_reset_link}}\">Reset</a>".to_string(),
+    )
+    .commit(None, connection)
+    .unwrap();
+
+    let password_reset_email = mailers::user::password_reset_email(&config, &user, connection).unwrap();
+    assert_eq!(password_reset_email.template_id, None);
+    assert_eq!(
+        password_reset_email.title,
+        format!("Reset your password {}", user.full_name())
+    );
+    let body = password_reset_email.body.unwrap();
+    assert!(body.contains(&format!(
+        "{}/password-reset?token={}",
+        config.front_end_url,
+        user.password_reset_token.unwrap()
+    )));
+}
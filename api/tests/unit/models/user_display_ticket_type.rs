@@ -41,6 +41,7 @@ fn from_ticket_type() {
             true,
             None,
             None,
+            None,
             conn,
         )
         .unwrap();
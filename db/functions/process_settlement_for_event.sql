@@ -49,6 +49,8 @@ INNER JOIN orders o on oi.order_id = o.id
 WHERE oi.event_id = $2
 AND (oi.item_type <> 'EventFees' OR oi.client_fee_in_cents > 0)
 AND oi.item_type <> 'CreditCardFees'
+-- Taxes are refunded alongside their parent item
+AND oi.item_type <> 'Tax'
 AND (start_override IS NULL OR r.created_at >= start_override)
 AND ($3 IS NULL OR r.created_at >= $3)
 AND ($4 IS NULL OR r.created_at <= $4)
@@ -57,7 +59,7 @@ AND ri.amount > 0
 AND r.settlement_id IS NULL
 AND o.box_office_pricing IS FALSE;
 
-INSERT INTO settlement_entries (settlement_id, event_id, ticket_type_id, face_value_in_cents, revenue_share_value_in_cents, online_sold_quantity, fee_sold_quantity, total_sales_in_cents, settlement_entry_type)
+INSERT INTO settlement_entries (settlement_id, event_id, ticket_type_id, face_value_in_cents, revenue_share_value_in_cents, online_sold_quantity, fee_sold_quantity, total_sales_in_cents, settlement_entry_type, tax_in_cents, currency)
 SELECT -- Group result set by face price to prevent multiple records for holds that match code discounts
   entries.settlement_id,
   entries.event_id,
@@ -67,7 +69,10 @@ SELECT -- Group result set by face price to prevent multiple records for holds t
   SUM(online_sold_quantity),
   SUM(fee_sold_quantity),
   SUM(online_sold_quantity) * entries.face_value_in_cents + SUM(fee_sold_quantity) * entries.revenue_share_value_in_cents,
-  entries.settlement_entry_type
+  entries.settlement_entry_type,
+  -- Taxes charged on the face value (or event fee) of the sold items
+  SUM(CASE entries.settlement_entry_type WHEN 'EventFees' THEN fee_sold_quantity ELSE online_sold_quantity END * entries.tax_per_unit_in_cents),
+  entries.currency
 FROM (
   SELECT
     $1 as settlement_id,
@@ -100,7 +105,9 @@ FROM (
           CAST(SUM(COALESCE(oi_t_fees.quantity, 0)) AS BIGINT)
         END
     END as fee_sold_quantity,
-    CASE oi.item_type WHEN 'EventFees' THEN 'EventFees' ELSE 'TicketType' END as settlement_entry_type
+    CASE oi.item_type WHEN 'EventFees' THEN 'EventFees' ELSE 'TicketType' END as settlement_entry_type,
+    CAST(COALESCE(oi_tax.tax_in_cents, 0) AS BIGINT) as tax_per_unit_in_cents,
+    oi.currency
   FROM order_items oi
   INNER JOIN order_item_ids oi_ids ON oi.id = oi_ids.id
   INNER JOIN orders o ON oi.order_id = o.id
@@ -108,6 +115,14 @@ FROM (
   LEFT JOIN order_items oi_promo_code ON (oi_promo_code.item_type = 'Discount' AND oi.id = oi_promo_code.parent_id)
   LEFT JOIN order_items oi_t_fees ON oi_t_fees.parent_id = oi.id AND oi_t_fees.item_type = 'PerUnitFees'
   LEFT JOIN refund_items oi_t_fees_r ON oi_t_fees_r.order_item_id = oi_t_fees.id AND oi_t_fees_r.refund_id = oi_ids.refund_id
+  LEFT JOIN (
+    SELECT parent_id, SUM(tax_in_cents) as tax_in_cents
+    FROM order_items
+    WHERE item_type = 'Tax'
+    GROUP BY parent_id
+  ) oi_tax ON oi_tax.parent_id = oi.id
+  -- Resale tickets were settled at face value when first sold, only the resale fee is settled below
+  WHERE oi.item_type <> 'ResaleTickets'
   GROUP BY
     oi.item_type,
     oi.event_id,
@@ -117,7 +132,9 @@ FROM (
     oi_t_fees.client_fee_in_cents,
     oi_promo_code.unit_price_in_cents,
     oi_t_fees_r.quantity,
-    oi_r.quantity
+    oi_r.quantity,
+    oi_tax.tax_in_cents,
+    oi.currency
 ) entries
   GROUP BY
     entries.settlement_id,
@@ -125,7 +142,8 @@ FROM (
     entries.ticket_type_id,
     entries.face_value_in_cents,
     entries.revenue_share_value_in_cents,
-    entries.settlement_entry_type
+    entries.settlement_entry_type,
+    entries.currency
   -- Filter out any records where the sum of their quantities is 0
   -- Negative indicates a refund settlement adjustment, positive purchases
   HAVING
@@ -134,6 +152,49 @@ FROM (
     (SUM(fee_sold_quantity) <> 0 AND revenue_share_value_in_cents > 0)
 ;
 
+-- The resale price is paid out to the seller less the organization's resale fee, which is all that is settled for a
+-- resale. Resale items cannot be refunded so there are no adjustments.
+INSERT INTO settlement_entries (settlement_id, event_id, ticket_type_id, face_value_in_cents, revenue_share_value_in_cents, online_sold_quantity, fee_sold_quantity, total_sales_in_cents, settlement_entry_type, tax_in_cents, currency)
+SELECT $1, $2, NULL, 0, 0, 0, 0, CAST(SUM(l.resale_fee_in_cents) AS BIGINT), 'ResaleFees', 0, oi.currency
+FROM order_item_ids oi_ids
+JOIN order_items oi ON oi.id = oi_ids.id
+JOIN listings l ON l.id = oi.listing_id
+WHERE oi.item_type = 'ResaleTickets'
+AND oi_ids.refund_id IS NULL
+AND l.status = 'Sold'
+GROUP BY oi.currency
+HAVING SUM(l.resale_fee_in_cents) <> 0;
+
+-- Gift card value is collected by the organization when the card is issued. Tickets paid for with a gift card are
+-- recognised as revenue above when redeemed, the redeemed value is then deducted from the amount settled and
+-- added back when it is refunded to the card.
+INSERT INTO settlement_entries (settlement_id, event_id, ticket_type_id, face_value_in_cents, revenue_share_value_in_cents, online_sold_quantity, fee_sold_quantity, total_sales_in_cents, settlement_entry_type, tax_in_cents, currency)
+SELECT $1, $2, NULL, 0, 0, 0, 0, CAST(-SUM(p.amount) AS BIGINT), 'GiftCardRedemptions', 0, p.currency
+FROM payments p
+JOIN orders o ON o.id = p.order_id
+LEFT JOIN refunds r ON r.id = p.refund_id
+WHERE p.payment_method = 'GiftCard'
+AND (
+  (
+    p.status = 'Completed'
+    AND o.settlement_id IS NULL
+    AND o.id IN (
+      SELECT oi.order_id
+      FROM order_item_ids oi_ids
+      JOIN order_items oi ON oi.id = oi_ids.id
+      WHERE oi_ids.refund_id IS NULL
+    )
+  )
+  OR
+  (
+    p.status = 'Refunded'
+    AND r.settlement_id IS NULL
+    AND r.id IN (SELECT refund_id FROM order_item_ids WHERE refund_id IS NOT NULL)
+  )
+)
+GROUP BY p.currency
+HAVING SUM(p.amount) <> 0;
+
 -- Update associated orders as part of this settlement
 UPDATE orders SET settlement_id = $1
 FROM order_item_ids oi_ids
@@ -0,0 +1,7 @@
+DROP INDEX IF EXISTS index_ticket_instances_seat_id;
+
+ALTER TABLE ticket_instances
+    DROP COLUMN seat_id;
+
+DROP TABLE stage_seats;
+DROP TABLE stage_sections;
@@ -0,0 +1,27 @@
+CREATE TABLE stage_sections (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  stage_id uuid NOT NULL REFERENCES stages (id),
+  name TEXT NOT NULL,
+  created_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_stage_sections_stage_id ON stage_sections (stage_id);
+CREATE UNIQUE INDEX index_stage_sections_stage_id_name ON stage_sections (stage_id, name);
+
+CREATE TABLE stage_seats (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  stage_section_id uuid NOT NULL REFERENCES stage_sections (id),
+  row_name TEXT NOT NULL,
+  seat_number TEXT NOT NULL,
+  created_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_stage_seats_stage_section_id ON stage_seats (stage_section_id);
+CREATE UNIQUE INDEX index_stage_seats_stage_section_id_row_name_seat_number ON stage_seats (stage_section_id, row_name, seat_number);
+
+ALTER TABLE ticket_instances
+    ADD COLUMN seat_id uuid NULL REFERENCES stage_seats (id);
+
+CREATE INDEX index_ticket_instances_seat_id ON ticket_instances (seat_id);
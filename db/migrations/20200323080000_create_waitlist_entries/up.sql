@@ -0,0 +1,17 @@
+CREATE TABLE waitlist_entries (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  ticket_type_id uuid NOT NULL REFERENCES ticket_types (id),
+  user_id uuid NOT NULL REFERENCES users (id),
+  quantity BIGINT NOT NULL,
+  status TEXT NOT NULL DEFAULT 'Waiting',
+  hold_id uuid NULL REFERENCES holds (id),
+  offer_expires_at TIMESTAMP NULL,
+  created_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_waitlist_entries_ticket_type_id_status ON waitlist_entries (ticket_type_id, status);
+CREATE INDEX index_waitlist_entries_user_id ON waitlist_entries (user_id);
+CREATE INDEX index_waitlist_entries_hold_id ON waitlist_entries (hold_id);
+-- A user can only have one open place in line per ticket type
+CREATE UNIQUE INDEX index_waitlist_entries_ticket_type_id_user_id_open ON waitlist_entries (ticket_type_id, user_id) WHERE status IN ('Waiting', 'Offered');
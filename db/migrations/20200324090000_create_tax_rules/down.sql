@@ -0,0 +1,12 @@
+ALTER TABLE settlement_entries
+  DROP tax_in_cents;
+
+DROP INDEX IF EXISTS index_order_items_tax_rule_id;
+
+ALTER TABLE order_items
+  DROP tax_rule_id,
+  DROP tax_in_cents;
+
+DROP INDEX IF EXISTS index_tax_rules_region_id;
+DROP INDEX IF EXISTS index_tax_rules_venue_id;
+DROP TABLE IF EXISTS tax_rules;
@@ -0,0 +1,25 @@
+CREATE TABLE tax_rules (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  name TEXT NOT NULL,
+  venue_id uuid NULL REFERENCES venues (id),
+  region_id uuid NULL REFERENCES regions (id),
+  rate_percent REAL NOT NULL,
+  applies_to_fees BOOLEAN NOT NULL DEFAULT false,
+  inclusive BOOLEAN NOT NULL DEFAULT false,
+  deleted_at TIMESTAMP NULL,
+  created_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now(),
+  CONSTRAINT tax_rules_venue_or_region CHECK (venue_id IS NOT NULL OR region_id IS NOT NULL)
+);
+
+CREATE INDEX index_tax_rules_venue_id ON tax_rules (venue_id);
+CREATE INDEX index_tax_rules_region_id ON tax_rules (region_id);
+
+ALTER TABLE order_items
+  ADD tax_rule_id uuid NULL REFERENCES tax_rules (id),
+  ADD tax_in_cents BIGINT NOT NULL DEFAULT 0;
+
+CREATE INDEX index_order_items_tax_rule_id ON order_items (tax_rule_id);
+
+ALTER TABLE settlement_entries
+  ADD tax_in_cents BIGINT NOT NULL DEFAULT 0;
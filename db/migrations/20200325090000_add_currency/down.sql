@@ -0,0 +1,20 @@
+ALTER TABLE settlement_entries
+  DROP currency;
+
+ALTER TABLE settlements
+  DROP currency;
+
+ALTER TABLE payments
+  DROP currency;
+
+ALTER TABLE order_items
+  DROP currency;
+
+ALTER TABLE ticket_pricing
+  DROP currency;
+
+ALTER TABLE events
+  DROP currency;
+
+ALTER TABLE organizations
+  DROP currency;
@@ -0,0 +1,46 @@
+ALTER TABLE organizations
+  ADD currency TEXT NOT NULL DEFAULT 'USD';
+
+ALTER TABLE events
+  ADD currency TEXT NOT NULL DEFAULT 'USD';
+
+UPDATE events
+SET currency = o.currency
+FROM organizations o
+WHERE o.id = events.organization_id;
+
+ALTER TABLE ticket_pricing
+  ADD currency TEXT NOT NULL DEFAULT 'USD';
+
+UPDATE ticket_pricing
+SET currency = e.currency
+FROM ticket_types tt
+JOIN events e ON e.id = tt.event_id
+WHERE tt.id = ticket_pricing.ticket_type_id;
+
+ALTER TABLE order_items
+  ADD currency TEXT NOT NULL DEFAULT 'USD';
+
+UPDATE order_items
+SET currency = e.currency
+FROM events e
+WHERE e.id = order_items.event_id;
+
+ALTER TABLE payments
+  ADD currency TEXT NOT NULL DEFAULT 'USD';
+
+ALTER TABLE settlements
+  ADD currency TEXT NOT NULL DEFAULT 'USD';
+
+UPDATE settlements
+SET currency = o.currency
+FROM organizations o
+WHERE o.id = settlements.organization_id;
+
+ALTER TABLE settlement_entries
+  ADD currency TEXT NOT NULL DEFAULT 'USD';
+
+UPDATE settlement_entries
+SET currency = e.currency
+FROM events e
+WHERE e.id = settlement_entries.event_id;
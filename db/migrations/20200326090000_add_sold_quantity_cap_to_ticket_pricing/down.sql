@@ -0,0 +1,2 @@
+ALTER TABLE ticket_pricing
+  DROP sold_quantity_cap;
@@ -0,0 +1,2 @@
+ALTER TABLE ticket_pricing
+  ADD sold_quantity_cap BIGINT NULL CHECK (sold_quantity_cap > 0);
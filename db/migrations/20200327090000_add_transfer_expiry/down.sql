@@ -0,0 +1,7 @@
+DROP INDEX IF EXISTS index_transfers_status_expires_at;
+
+ALTER TABLE transfers
+  DROP expires_at;
+
+ALTER TABLE organizations
+  DROP transfer_expiry_hours;
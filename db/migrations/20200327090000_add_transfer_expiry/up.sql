@@ -0,0 +1,7 @@
+ALTER TABLE organizations
+  ADD transfer_expiry_hours INTEGER NULL CHECK (transfer_expiry_hours > 0);
+
+ALTER TABLE transfers
+  ADD expires_at TIMESTAMP NULL;
+
+CREATE INDEX index_transfers_status_expires_at ON transfers (status, expires_at);
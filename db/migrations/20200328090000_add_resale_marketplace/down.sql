@@ -0,0 +1,20 @@
+DROP INDEX IF EXISTS index_order_items_listing_id;
+
+ALTER TABLE order_items
+  DROP listing_id;
+
+DROP INDEX IF EXISTS index_listings_event_id_status;
+
+ALTER TABLE listings
+  DROP event_id,
+  DROP buyer_user_id,
+  DROP order_id,
+  DROP resale_fee_in_cents,
+  DROP payout_in_cents,
+  DROP sold_at;
+
+ALTER TABLE organizations
+  DROP resale_fee_percent;
+
+ALTER TABLE ticket_types
+  DROP resale_price_cap_percent;
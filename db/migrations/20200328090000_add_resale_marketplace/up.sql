@@ -0,0 +1,20 @@
+ALTER TABLE ticket_types
+  ADD resale_price_cap_percent INTEGER NULL CHECK (resale_price_cap_percent >= 0);
+
+ALTER TABLE organizations
+  ADD resale_fee_percent REAL NOT NULL DEFAULT 0 CHECK (resale_fee_percent >= 0 AND resale_fee_percent <= 100);
+
+ALTER TABLE listings
+  ADD event_id UUID NULL REFERENCES events (id),
+  ADD buyer_user_id UUID NULL REFERENCES users (id),
+  ADD order_id UUID NULL REFERENCES orders (id),
+  ADD resale_fee_in_cents BIGINT NULL,
+  ADD payout_in_cents BIGINT NULL,
+  ADD sold_at TIMESTAMP NULL;
+
+CREATE INDEX index_listings_event_id_status ON listings (event_id, status);
+
+ALTER TABLE order_items
+  ADD listing_id UUID NULL REFERENCES listings (id);
+
+CREATE INDEX index_order_items_listing_id ON order_items (listing_id);
@@ -0,0 +1,7 @@
+DROP INDEX IF EXISTS index_events_event_series_id;
+
+ALTER TABLE events
+  DROP COLUMN event_series_id;
+
+DROP INDEX IF EXISTS index_event_series_organization_id;
+DROP TABLE IF EXISTS event_series;
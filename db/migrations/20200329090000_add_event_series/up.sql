@@ -0,0 +1,20 @@
+CREATE TABLE event_series (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  organization_id UUID NOT NULL REFERENCES organizations (id),
+  template_event_id UUID NOT NULL REFERENCES events (id),
+  name TEXT NOT NULL,
+  recurrence_type TEXT NOT NULL,
+  recurrence_interval INTEGER NOT NULL DEFAULT 1 CHECK (recurrence_interval > 0),
+  recurrence_until TIMESTAMP NULL,
+  custom_dates TIMESTAMP[] NOT NULL DEFAULT '{}',
+  slug_id UUID NULL REFERENCES slugs (id),
+  created_at TIMESTAMP NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_event_series_organization_id ON event_series (organization_id);
+
+ALTER TABLE events
+  ADD event_series_id UUID NULL REFERENCES event_series (id);
+
+CREATE INDEX index_events_event_series_id ON events (event_series_id);
@@ -0,0 +1,6 @@
+ALTER TABLE organization_users
+  DROP COLUMN organization_role_ids;
+
+DROP INDEX IF EXISTS index_organization_roles_organization_id_name;
+DROP INDEX IF EXISTS index_organization_roles_organization_id;
+DROP TABLE IF EXISTS organization_roles;
@@ -0,0 +1,11 @@
+ALTER TABLE organizations
+  DROP COLUMN require_two_factor;
+
+DROP INDEX IF EXISTS index_user_login_throttles_user_id;
+DROP TABLE IF EXISTS user_login_throttles;
+
+DROP INDEX IF EXISTS index_user_recovery_codes_user_id;
+DROP TABLE IF EXISTS user_recovery_codes;
+
+DROP INDEX IF EXISTS index_user_totp_credentials_user_id;
+DROP TABLE IF EXISTS user_totp_credentials;
@@ -0,0 +1,37 @@
+CREATE TABLE user_totp_credentials (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  user_id UUID NOT NULL REFERENCES users (id),
+  encrypted_secret TEXT NOT NULL,
+  confirmed_at TIMESTAMP NULL,
+  last_used_time_step BIGINT NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_user_totp_credentials_user_id ON user_totp_credentials (user_id);
+
+CREATE TABLE user_recovery_codes (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  user_id UUID NOT NULL REFERENCES users (id),
+  code_hash TEXT NOT NULL,
+  used_at TIMESTAMP NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_user_recovery_codes_user_id ON user_recovery_codes (user_id);
+
+CREATE TABLE user_login_throttles (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  user_id UUID NOT NULL REFERENCES users (id),
+  failed_attempts INTEGER NOT NULL DEFAULT 0,
+  last_failed_at TIMESTAMP NULL,
+  locked_until TIMESTAMP NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_user_login_throttles_user_id ON user_login_throttles (user_id);
+
+ALTER TABLE organizations
+  ADD require_two_factor BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,14 @@
+CREATE TABLE user_sessions (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  user_id UUID NOT NULL REFERENCES users (id),
+  refresh_token_id UUID NOT NULL,
+  user_agent TEXT NULL,
+  ip_address TEXT NULL,
+  last_seen_at TIMESTAMP NOT NULL DEFAULT now(),
+  revoked_at TIMESTAMP NULL,
+  revoked_reason TEXT NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_user_sessions_user_id ON user_sessions (user_id);
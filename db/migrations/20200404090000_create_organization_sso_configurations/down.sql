@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS index_sso_login_requests_organization_sso_configuration_id;
+DROP INDEX IF EXISTS index_sso_login_requests_state;
+DROP TABLE IF EXISTS sso_login_requests;
+DROP INDEX IF EXISTS index_organization_sso_configurations_organization_id;
+DROP TABLE IF EXISTS organization_sso_configurations;
@@ -0,0 +1,31 @@
+CREATE TABLE organization_sso_configurations (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  organization_id UUID NOT NULL REFERENCES organizations (id),
+  issuer_url TEXT NOT NULL,
+  client_id TEXT NOT NULL,
+  encrypted_client_secret TEXT NOT NULL,
+  default_role TEXT NOT NULL,
+  role_claim TEXT NULL,
+  role_mappings JSONB NOT NULL DEFAULT '{}',
+  enabled BOOLEAN NOT NULL DEFAULT TRUE,
+  created_at TIMESTAMP NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_organization_sso_configurations_organization_id ON organization_sso_configurations (organization_id);
+
+CREATE TABLE sso_login_requests (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  organization_sso_configuration_id UUID NOT NULL REFERENCES organization_sso_configurations (id),
+  state TEXT NOT NULL,
+  nonce TEXT NOT NULL,
+  code_verifier TEXT NOT NULL,
+  redirect_uri TEXT NOT NULL,
+  expires_at TIMESTAMP NOT NULL,
+  consumed_at TIMESTAMP NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_sso_login_requests_state ON sso_login_requests (state);
+CREATE INDEX index_sso_login_requests_organization_sso_configuration_id ON sso_login_requests (organization_sso_configuration_id);
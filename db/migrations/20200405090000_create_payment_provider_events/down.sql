@@ -0,0 +1,9 @@
+DROP INDEX IF EXISTS index_settlement_adjustments_type_external_reference;
+DROP INDEX IF EXISTS index_settlement_adjustments_payment_id;
+
+ALTER TABLE settlement_adjustments
+  DROP payment_id,
+  DROP external_reference;
+
+DROP INDEX IF EXISTS index_payment_provider_events_provider_external_id;
+DROP TABLE IF EXISTS payment_provider_events;
@@ -0,0 +1,19 @@
+CREATE TABLE payment_provider_events (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  provider TEXT NOT NULL,
+  external_id TEXT NOT NULL,
+  event_type TEXT NOT NULL,
+  payload JSONB NOT NULL,
+  processed_at TIMESTAMP NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_payment_provider_events_provider_external_id ON payment_provider_events (provider, external_id);
+
+ALTER TABLE settlement_adjustments
+  ADD payment_id UUID NULL REFERENCES payments (id),
+  ADD external_reference TEXT NULL;
+
+CREATE INDEX index_settlement_adjustments_payment_id ON settlement_adjustments (payment_id);
+CREATE UNIQUE INDEX index_settlement_adjustments_type_external_reference ON settlement_adjustments (settlement_adjustment_type, external_reference) WHERE external_reference IS NOT NULL;
@@ -0,0 +1,20 @@
+DROP INDEX index_payment_methods_user_id_default;
+
+-- Only the most recently saved method per provider can be kept
+DELETE FROM payment_methods pm
+WHERE EXISTS (
+  SELECT 1
+  FROM payment_methods other
+  WHERE other.user_id = pm.user_id
+    AND other.name = pm.name
+    AND (other.created_at, other.id) > (pm.created_at, pm.id)
+);
+
+ALTER TABLE payment_methods
+  DROP card_brand,
+  DROP card_last_four,
+  DROP card_expiry_month,
+  DROP card_expiry_year,
+  DROP expiry_notification_sent_at;
+
+CREATE UNIQUE INDEX index_payment_methods_user_id_name on payment_methods(user_id, name);
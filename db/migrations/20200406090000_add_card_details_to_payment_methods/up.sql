@@ -0,0 +1,38 @@
+DROP INDEX index_payment_methods_user_id_name;
+
+ALTER TABLE payment_methods
+  ADD card_brand TEXT NULL,
+  ADD card_last_four TEXT NULL,
+  ADD card_expiry_month INT NULL,
+  ADD card_expiry_year INT NULL,
+  ADD expiry_notification_sent_at TIMESTAMP NULL;
+
+-- Card details of existing methods come from the customer stored when the method was saved
+UPDATE payment_methods pm
+SET card_brand = card.value ->> 'brand',
+    card_last_four = card.value ->> 'last4',
+    card_expiry_month = (card.value ->> 'exp_month')::INT,
+    card_expiry_year = (card.value ->> 'exp_year')::INT
+FROM payment_methods source,
+     json_array_elements(
+       CASE WHEN json_typeof(source.provider_data -> 'sources' -> 'data') = 'array'
+         THEN source.provider_data -> 'sources' -> 'data'
+       END
+     ) card
+WHERE pm.id = source.id
+  AND card.value ->> 'object' = 'card'
+  AND card.value ->> 'id' = source.provider_data ->> 'default_source';
+
+-- Users could previously have a default per provider, keep the most recently saved one
+UPDATE payment_methods pm
+SET is_default = false
+WHERE pm.is_default
+  AND EXISTS (
+    SELECT 1
+    FROM payment_methods other
+    WHERE other.user_id = pm.user_id
+      AND other.is_default
+      AND (other.created_at, other.id) > (pm.created_at, pm.id)
+  );
+
+CREATE UNIQUE INDEX index_payment_methods_user_id_default ON payment_methods (user_id) WHERE is_default;
@@ -0,0 +1,11 @@
+UPDATE orders SET status = 'Cancelled' WHERE status = 'PartiallyPaid';
+
+DROP TABLE IF EXISTS payment_plan_installments;
+DROP TABLE IF EXISTS payment_plans;
+
+ALTER TABLE organizations
+  DROP payment_plan_max_failed_attempts;
+
+ALTER TABLE ticket_types
+  DROP installment_count,
+  DROP installment_interval_days;
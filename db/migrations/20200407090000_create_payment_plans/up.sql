@@ -0,0 +1,38 @@
+ALTER TABLE ticket_types
+  ADD installment_count INTEGER NULL CHECK (installment_count >= 2),
+  ADD installment_interval_days INTEGER NOT NULL DEFAULT 30 CHECK (installment_interval_days >= 1);
+
+ALTER TABLE organizations
+  ADD payment_plan_max_failed_attempts INTEGER NOT NULL DEFAULT 3 CHECK (payment_plan_max_failed_attempts >= 1);
+
+CREATE TABLE payment_plans (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  order_id UUID NOT NULL REFERENCES orders (id),
+  payment_method_id UUID NULL REFERENCES payment_methods (id) ON DELETE SET NULL,
+  status TEXT NOT NULL,
+  installment_count INTEGER NOT NULL,
+  max_failed_attempts INTEGER NOT NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_payment_plans_order_id ON payment_plans (order_id);
+CREATE INDEX index_payment_plans_payment_method_id ON payment_plans (payment_method_id);
+
+CREATE TABLE payment_plan_installments (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  payment_plan_id UUID NOT NULL REFERENCES payment_plans (id) ON DELETE CASCADE,
+  installment_number INTEGER NOT NULL,
+  amount_in_cents BIGINT NOT NULL,
+  due_at TIMESTAMP NOT NULL,
+  status TEXT NOT NULL,
+  failed_attempts INTEGER NOT NULL DEFAULT 0,
+  last_failure_reason TEXT NULL,
+  payment_id UUID NULL REFERENCES payments (id),
+  paid_at TIMESTAMP NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_payment_plan_installments_payment_plan_id_installment_number ON payment_plan_installments (payment_plan_id, installment_number);
+CREATE INDEX index_payment_plan_installments_payment_id ON payment_plan_installments (payment_id);
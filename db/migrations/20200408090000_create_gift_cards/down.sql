@@ -0,0 +1,5 @@
+DELETE FROM settlement_entries WHERE settlement_entry_type = 'GiftCardRedemptions';
+
+DROP TABLE IF EXISTS stored_value_ledger_entries;
+DROP TABLE IF EXISTS account_credits;
+DROP TABLE IF EXISTS gift_cards;
@@ -0,0 +1,47 @@
+CREATE TABLE gift_cards (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  organization_id UUID NOT NULL REFERENCES organizations (id),
+  code TEXT NOT NULL,
+  initial_balance_in_cents BIGINT NOT NULL CHECK (initial_balance_in_cents > 0),
+  balance_in_cents BIGINT NOT NULL CHECK (balance_in_cents >= 0),
+  currency TEXT NOT NULL,
+  expires_at TIMESTAMP NULL,
+  deactivated_at TIMESTAMP NULL,
+  created_by UUID NOT NULL REFERENCES users (id),
+  created_at TIMESTAMP NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_gift_cards_code ON gift_cards (code);
+CREATE INDEX index_gift_cards_organization_id ON gift_cards (organization_id);
+
+CREATE TABLE account_credits (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  user_id UUID NOT NULL REFERENCES users (id),
+  currency TEXT NOT NULL,
+  balance_in_cents BIGINT NOT NULL DEFAULT 0 CHECK (balance_in_cents >= 0),
+  created_at TIMESTAMP NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_account_credits_user_id_currency ON account_credits (user_id, currency);
+
+CREATE TABLE stored_value_ledger_entries (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  gift_card_id UUID NULL REFERENCES gift_cards (id),
+  account_credit_id UUID NULL REFERENCES account_credits (id),
+  entry_type TEXT NOT NULL,
+  amount_in_cents BIGINT NOT NULL,
+  balance_after_in_cents BIGINT NOT NULL,
+  order_id UUID NULL REFERENCES orders (id),
+  payment_id UUID NULL REFERENCES payments (id),
+  refund_id UUID NULL REFERENCES refunds (id),
+  created_by UUID NULL REFERENCES users (id),
+  -- Entries made in the same transaction are ordered by when they were written
+  created_at TIMESTAMP NOT NULL DEFAULT clock_timestamp(),
+  CHECK ((gift_card_id IS NULL) <> (account_credit_id IS NULL))
+);
+
+CREATE INDEX index_stored_value_ledger_entries_gift_card_id ON stored_value_ledger_entries (gift_card_id);
+CREATE INDEX index_stored_value_ledger_entries_account_credit_id ON stored_value_ledger_entries (account_credit_id);
+CREATE INDEX index_stored_value_ledger_entries_order_id ON stored_value_ledger_entries (order_id);
@@ -0,0 +1,5 @@
+DROP TABLE IF EXISTS webhook_delivery_attempts;
+DROP TABLE IF EXISTS webhook_deliveries;
+
+ALTER TABLE domain_event_publishers
+  DROP encrypted_signing_secret;
@@ -0,0 +1,31 @@
+ALTER TABLE domain_event_publishers
+  ADD encrypted_signing_secret TEXT NULL;
+
+CREATE TABLE webhook_deliveries (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  domain_event_publisher_id UUID NOT NULL REFERENCES domain_event_publishers (id),
+  domain_event_id UUID NOT NULL REFERENCES domain_events (id),
+  status TEXT NOT NULL,
+  payload TEXT NOT NULL,
+  attempt_count BIGINT NOT NULL DEFAULT 0,
+  last_attempted_at TIMESTAMP NULL,
+  next_attempt_at TIMESTAMP NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_webhook_deliveries_domain_event_publisher_id ON webhook_deliveries (domain_event_publisher_id);
+CREATE INDEX index_webhook_deliveries_domain_event_id ON webhook_deliveries (domain_event_id);
+
+CREATE TABLE webhook_delivery_attempts (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  webhook_delivery_id UUID NOT NULL REFERENCES webhook_deliveries (id),
+  response_status INTEGER NULL,
+  latency_ms BIGINT NOT NULL,
+  response_body_excerpt TEXT NULL,
+  error TEXT NULL,
+  -- Attempts made in the same transaction are ordered by when they were written
+  created_at TIMESTAMP NOT NULL DEFAULT clock_timestamp()
+);
+
+CREATE INDEX index_webhook_delivery_attempts_webhook_delivery_id ON webhook_delivery_attempts (webhook_delivery_id);
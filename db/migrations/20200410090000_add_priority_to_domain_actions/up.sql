@@ -0,0 +1,16 @@
+ALTER TABLE domain_actions
+    ADD priority INT NOT NULL DEFAULT 50;
+
+-- Customer facing actions are picked up before bulk jobs
+UPDATE domain_actions
+SET priority = 100
+WHERE domain_action_type IN ('Communication', 'ExpireTransfer', 'PaymentProviderIPN', 'ProcessPaymentProviderEvent',
+                             'ReleaseHoldInventory', 'SendPurchaseCompletedCommunication');
+
+UPDATE domain_actions
+SET priority = 0
+WHERE domain_action_type IN ('BroadcastPushNotification', 'FinalizeSettlements', 'ProcessSettlementReport',
+                             'RegenerateDripActions', 'RetargetAbandonedOrders', 'SendAutomaticReportEmails',
+                             'SendPaymentMethodExpiryNotifications', 'SubmitSitemapToSearchEngines', 'UpdateGenres');
+
+CREATE INDEX index_domain_actions_status_priority_scheduled_at ON domain_actions (status, priority DESC, scheduled_at);
@@ -0,0 +1,6 @@
+DROP TRIGGER IF EXISTS domain_events_inserted_notify ON domain_events;
+DROP TRIGGER IF EXISTS domain_actions_pending_notify ON domain_actions;
+DROP TRIGGER IF EXISTS domain_actions_inserted_notify ON domain_actions;
+
+DROP FUNCTION IF EXISTS notify_domain_events();
+DROP FUNCTION IF EXISTS notify_domain_actions();
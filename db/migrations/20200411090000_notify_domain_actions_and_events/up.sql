@@ -0,0 +1,37 @@
+-- Wakes the domain action monitor when there is new work. Notifications are only delivered once the
+-- transaction commits and duplicates within a transaction are collapsed.
+CREATE OR REPLACE FUNCTION notify_domain_actions() RETURNS TRIGGER AS
+$$
+BEGIN
+    PERFORM pg_notify('domain_actions', '');
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION notify_domain_events() RETURNS TRIGGER AS
+$$
+BEGIN
+    PERFORM pg_notify('domain_events', '');
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER domain_actions_inserted_notify
+    AFTER INSERT
+    ON domain_actions
+    FOR EACH STATEMENT
+EXECUTE PROCEDURE notify_domain_actions();
+
+-- Requeued actions
+CREATE TRIGGER domain_actions_pending_notify
+    AFTER UPDATE OF status
+    ON domain_actions
+    FOR EACH ROW
+    WHEN (OLD.status <> 'Pending' AND NEW.status = 'Pending')
+EXECUTE PROCEDURE notify_domain_actions();
+
+CREATE TRIGGER domain_events_inserted_notify
+    AFTER INSERT
+    ON domain_events
+    FOR EACH STATEMENT
+EXECUTE PROCEDURE notify_domain_events();
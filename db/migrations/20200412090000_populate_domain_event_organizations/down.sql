@@ -0,0 +1,6 @@
+DROP INDEX IF EXISTS index_domain_events_organization_id_seq;
+
+DROP TRIGGER IF EXISTS domain_events_set_organization_id ON domain_events;
+
+DROP FUNCTION IF EXISTS set_domain_event_organization_id();
+DROP FUNCTION IF EXISTS domain_event_organization_id(TEXT, UUID);
@@ -0,0 +1,102 @@
+-- Resolves the organization a domain event belongs to from its main record. Events on records that
+-- span organizations, such as orders with tickets from several organizations, are left without one.
+CREATE OR REPLACE FUNCTION domain_event_organization_id(event_main_table TEXT, event_main_id UUID) RETURNS UUID AS
+$$
+SELECT CASE event_main_table
+           WHEN 'Organizations' THEN event_main_id
+           WHEN 'Announcements' THEN (SELECT organization_id FROM announcements WHERE id = event_main_id)
+           WHEN 'ApiKeys' THEN (SELECT organization_id FROM api_keys WHERE id = event_main_id)
+           WHEN 'Artists' THEN (SELECT organization_id FROM artists WHERE id = event_main_id)
+           WHEN 'Broadcasts' THEN (SELECT e.organization_id
+                                   FROM broadcasts b
+                                            JOIN events e ON e.id = b.event_id
+                                   WHERE b.id = event_main_id)
+           WHEN 'Bundles' THEN (SELECT organization_id FROM bundles WHERE id = event_main_id)
+           WHEN 'Codes' THEN (SELECT e.organization_id
+                              FROM codes c
+                                       JOIN events e ON e.id = c.event_id
+                              WHERE c.id = event_main_id)
+           WHEN 'DomainEventPublishers' THEN (SELECT organization_id FROM domain_event_publishers WHERE id = event_main_id)
+           WHEN 'Events' THEN (SELECT organization_id FROM events WHERE id = event_main_id)
+           WHEN 'EventArtists' THEN (SELECT e.organization_id
+                                     FROM event_artists ea
+                                              JOIN events e ON e.id = ea.event_id
+                                     WHERE ea.id = event_main_id)
+           WHEN 'EventReportSubscribers' THEN (SELECT e.organization_id
+                                               FROM event_report_subscribers ers
+                                                        JOIN events e ON e.id = ers.event_id
+                                               WHERE ers.id = event_main_id)
+           WHEN 'EventSeries' THEN (SELECT organization_id FROM event_series WHERE id = event_main_id)
+           WHEN 'FeeSchedules' THEN (SELECT organization_id FROM fee_schedules WHERE id = event_main_id)
+           WHEN 'GiftCards' THEN (SELECT organization_id FROM gift_cards WHERE id = event_main_id)
+           WHEN 'Holds' THEN (SELECT e.organization_id
+                              FROM holds h
+                                       JOIN events e ON e.id = h.event_id
+                              WHERE h.id = event_main_id)
+           WHEN 'Orders' THEN (SELECT (array_agg(DISTINCT e.organization_id))[1]
+                               FROM order_items oi
+                                        JOIN events e ON e.id = oi.event_id
+                               WHERE oi.order_id = event_main_id
+                               HAVING COUNT(DISTINCT e.organization_id) = 1)
+           WHEN 'OrganizationRoles' THEN (SELECT organization_id FROM organization_roles WHERE id = event_main_id)
+           WHEN 'OrganizationSsoConfigurations' THEN (SELECT organization_id
+                                                      FROM organization_sso_configurations
+                                                      WHERE id = event_main_id)
+           WHEN 'PaymentPlans' THEN (SELECT (array_agg(DISTINCT e.organization_id))[1]
+                                     FROM payment_plans pp
+                                              JOIN order_items oi ON oi.order_id = pp.order_id
+                                              JOIN events e ON e.id = oi.event_id
+                                     WHERE pp.id = event_main_id
+                                     HAVING COUNT(DISTINCT e.organization_id) = 1)
+           WHEN 'TicketInstances' THEN (SELECT e.organization_id
+                                        FROM ticket_instances ti
+                                                 JOIN assets a ON a.id = ti.asset_id
+                                                 JOIN ticket_types tt ON tt.id = a.ticket_type_id
+                                                 JOIN events e ON e.id = tt.event_id
+                                        WHERE ti.id = event_main_id)
+           WHEN 'TicketTypes' THEN (SELECT e.organization_id
+                                    FROM ticket_types tt
+                                             JOIN events e ON e.id = tt.event_id
+                                    WHERE tt.id = event_main_id)
+           WHEN 'Transfers' THEN (SELECT (array_agg(DISTINCT e.organization_id))[1]
+                                  FROM transfer_tickets tt2
+                                           JOIN ticket_instances ti ON ti.id = tt2.ticket_instance_id
+                                           JOIN assets a ON a.id = ti.asset_id
+                                           JOIN ticket_types tt ON tt.id = a.ticket_type_id
+                                           JOIN events e ON e.id = tt.event_id
+                                  WHERE tt2.transfer_id = event_main_id
+                                  HAVING COUNT(DISTINCT e.organization_id) = 1)
+           WHEN 'WaitlistEntries' THEN (SELECT e.organization_id
+                                        FROM waitlist_entries we
+                                                 JOIN ticket_types tt ON tt.id = we.ticket_type_id
+                                                 JOIN events e ON e.id = tt.event_id
+                                        WHERE we.id = event_main_id)
+           WHEN 'WebhookDeliveries' THEN (SELECT dep.organization_id
+                                          FROM webhook_deliveries wd
+                                                   JOIN domain_event_publishers dep
+                                                        ON dep.id = wd.domain_event_publisher_id
+                                          WHERE wd.id = event_main_id)
+           END;
+$$ LANGUAGE sql STABLE;
+
+CREATE OR REPLACE FUNCTION set_domain_event_organization_id() RETURNS TRIGGER AS
+$$
+BEGIN
+    IF NEW.organization_id IS NULL AND NEW.main_id IS NOT NULL THEN
+        NEW.organization_id := domain_event_organization_id(NEW.main_table, NEW.main_id);
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER domain_events_set_organization_id
+    BEFORE INSERT
+    ON domain_events
+    FOR EACH ROW
+EXECUTE PROCEDURE set_domain_event_organization_id();
+
+-- Existing events are backpopulated in batches outside of the migration with
+-- `api-cli backpopulate-domain-event-organizations`
+
+-- Organization event streams are read in seq order
+CREATE INDEX index_domain_events_organization_id_seq ON domain_events (organization_id, seq);
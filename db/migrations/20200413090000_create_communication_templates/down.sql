@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS communication_template_versions;
+DROP TABLE IF EXISTS communication_templates;
@@ -0,0 +1,27 @@
+CREATE TABLE communication_templates (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  -- Platform defaults have no organization
+  organization_id UUID NULL REFERENCES organizations (id),
+  template_name TEXT NOT NULL,
+  communication_type TEXT NOT NULL,
+  subject TEXT NOT NULL,
+  body TEXT NOT NULL,
+  version INTEGER NOT NULL DEFAULT 1,
+  created_at TIMESTAMP NOT NULL DEFAULT now(),
+  updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_communication_templates_organization_id_template_name ON communication_templates (organization_id, template_name, communication_type) WHERE organization_id IS NOT NULL;
+CREATE UNIQUE INDEX index_communication_templates_default_template_name ON communication_templates (template_name, communication_type) WHERE organization_id IS NULL;
+
+CREATE TABLE communication_template_versions (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+  communication_template_id UUID NOT NULL REFERENCES communication_templates (id) ON DELETE CASCADE,
+  version INTEGER NOT NULL,
+  subject TEXT NOT NULL,
+  body TEXT NOT NULL,
+  created_by UUID NULL REFERENCES users (id),
+  created_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_communication_template_versions_communication_template_id_version ON communication_template_versions (communication_template_id, version);
@@ -0,0 +1,2 @@
+ALTER TABLE sso_login_requests
+  DROP COLUMN user_id;
@@ -0,0 +1,2 @@
+ALTER TABLE sso_login_requests
+  ADD user_id UUID NULL REFERENCES users (id);
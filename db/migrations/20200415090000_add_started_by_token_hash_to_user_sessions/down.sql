@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS index_user_sessions_started_by_token_hash;
+
+ALTER TABLE user_sessions
+  DROP COLUMN started_by_token_hash;
@@ -0,0 +1,4 @@
+ALTER TABLE user_sessions
+  ADD started_by_token_hash TEXT NULL;
+
+CREATE UNIQUE INDEX index_user_sessions_started_by_token_hash ON user_sessions (started_by_token_hash);
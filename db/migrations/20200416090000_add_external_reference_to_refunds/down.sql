@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS index_refunds_external_reference;
+
+ALTER TABLE refunds
+  DROP COLUMN external_reference,
+  DROP COLUMN requires_item_review;
@@ -0,0 +1,6 @@
+ALTER TABLE refunds
+  ADD external_reference TEXT NULL,
+  ADD requires_item_review BOOLEAN NOT NULL DEFAULT 'F';
+
+-- Refunds made with the payment provider directly are only recorded once
+CREATE UNIQUE INDEX index_refunds_external_reference ON refunds (external_reference);
@@ -0,0 +1,186 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::account_credits;
+use utils::errors::*;
+use uuid::Uuid;
+
+/// Platform issued credit held by a user, one balance per currency. Credit is issued in place of
+/// returning funds to the original payment method when an order is refunded and can be spent on
+/// any later order in the same currency.
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(User)]
+#[table_name = "account_credits"]
+pub struct AccountCredit {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub currency: String,
+    pub balance_in_cents: i64,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DisplayAccountCredit {
+    pub id: Uuid,
+    pub currency: String,
+    pub balance_in_cents: i64,
+    pub ledger_entries: Vec<StoredValueLedgerEntry>,
+}
+
+impl AccountCredit {
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<AccountCredit, DatabaseError> {
+        account_credits::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find account credit")
+    }
+
+    pub fn find_for_user(user_id: Uuid, conn: &PgConnection) -> Result<Vec<AccountCredit>, DatabaseError> {
+        account_credits::table
+            .filter(account_credits::user_id.eq(user_id))
+            .order_by(account_credits::currency)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load account credit for user")
+    }
+
+    pub(crate) fn find_for_update(
+        user_id: Uuid,
+        currency: &str,
+        conn: &PgConnection,
+    ) -> Result<AccountCredit, DatabaseError> {
+        account_credits::table
+            .filter(account_credits::user_id.eq(user_id))
+            .filter(account_credits::currency.eq(currency.to_uppercase()))
+            .for_update()
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find account credit")
+    }
+
+    /// Adds credit to the user's balance, creating the balance for the currency if needed
+    pub fn issue(
+        user_id: Uuid,
+        currency: &str,
+        amount_in_cents: i64,
+        order_id: Option<Uuid>,
+        refund_id: Option<Uuid>,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<AccountCredit, DatabaseError> {
+        if amount_in_cents <= 0 {
+            return DatabaseError::business_process_error("Account credit must be greater than 0");
+        }
+
+        diesel::insert_into(account_credits::table)
+            .values((
+                account_credits::user_id.eq(user_id),
+                account_credits::currency.eq(currency.to_uppercase()),
+            ))
+            .on_conflict((account_credits::user_id, account_credits::currency))
+            .do_nothing()
+            .execute(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create account credit")?;
+        let account_credit = AccountCredit::find_for_update(user_id, currency, conn)?;
+
+        account_credit.change_balance(
+            StoredValueLedgerEntryTypes::Issued,
+            amount_in_cents,
+            order_id,
+            None,
+            refund_id,
+            current_user_id,
+            conn,
+        )?;
+
+        DomainEvent::create(
+            DomainEventTypes::AccountCreditIssued,
+            "Account credit issued".to_string(),
+            Tables::AccountCredits,
+            Some(account_credit.id),
+            current_user_id,
+            Some(json!({
+                "user_id": user_id,
+                "amount_in_cents": amount_in_cents,
+                "currency": account_credit.currency,
+                "order_id": order_id,
+                "refund_id": refund_id
+            })),
+        )
+        .commit(conn)?;
+
+        AccountCredit::find(account_credit.id, conn)
+    }
+
+    /// Issues credit to the order's purchaser as an alternative to refunding the original payments
+    pub fn issue_for_refund(
+        order: &Order,
+        refund: &Refund,
+        amount_in_cents: i64,
+        current_user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<AccountCredit, DatabaseError> {
+        let currency = match order.currency(conn)? {
+            Some(currency) => currency,
+            None => return DatabaseError::business_process_error("Order does not have a currency to issue credit in"),
+        };
+
+        AccountCredit::issue(
+            order.on_behalf_of_user_id.unwrap_or(order.user_id),
+            &currency,
+            amount_in_cents,
+            Some(order.id),
+            Some(refund.id),
+            Some(current_user_id),
+            conn,
+        )
+    }
+
+    pub fn ledger_entries(&self, conn: &PgConnection) -> Result<Vec<StoredValueLedgerEntry>, DatabaseError> {
+        StoredValueLedgerEntry::find_for_account_credit(self.id, conn)
+    }
+
+    pub fn for_display(&self, conn: &PgConnection) -> Result<DisplayAccountCredit, DatabaseError> {
+        Ok(DisplayAccountCredit {
+            id: self.id,
+            currency: self.currency.clone(),
+            balance_in_cents: self.balance_in_cents,
+            ledger_entries: self.ledger_entries(conn)?,
+        })
+    }
+
+    /// Applies the change to the stored balance and records it in the ledger
+    pub(crate) fn change_balance(
+        &self,
+        entry_type: StoredValueLedgerEntryTypes,
+        amount_in_cents: i64,
+        order_id: Option<Uuid>,
+        payment_id: Option<Uuid>,
+        refund_id: Option<Uuid>,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<StoredValueLedgerEntry, DatabaseError> {
+        let account_credit: AccountCredit =
+            diesel::update(account_credits::table.filter(account_credits::id.eq(self.id)))
+                .set((
+                    account_credits::balance_in_cents.eq(account_credits::balance_in_cents + amount_in_cents),
+                    account_credits::updated_at.eq(dsl::now),
+                ))
+                .get_result(conn)
+                .to_db_error(ErrorCode::UpdateError, "Could not update account credit balance")?;
+
+        NewStoredValueLedgerEntry {
+            gift_card_id: None,
+            account_credit_id: Some(self.id),
+            entry_type,
+            amount_in_cents,
+            balance_after_in_cents: account_credit.balance_in_cents,
+            order_id,
+            payment_id,
+            refund_id,
+            created_by: current_user_id,
+        }
+        .commit(conn)
+    }
+}
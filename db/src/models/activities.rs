@@ -784,6 +784,11 @@ impl ActivityItem {
             pub company_fee_in_cents: i64,
             pub client_fee_in_cents: i64,
             pub refunded_quantity: i64,
+            pub tax_rule_id: Option<Uuid>,
+            pub tax_in_cents: i64,
+            pub currency: String,
+            pub listing_id: Option<Uuid>,
+            pub bundle_id: Option<Uuid>,
         };
 
         let refund_ids: Vec<Uuid> = refund_data.iter().map(|r| r.refund_id).collect();
@@ -813,6 +818,11 @@ impl ActivityItem {
                 order_items::company_fee_in_cents,
                 order_items::client_fee_in_cents,
                 order_items::refunded_quantity,
+                order_items::tax_rule_id,
+                order_items::tax_in_cents,
+                order_items::currency,
+                order_items::listing_id,
+                order_items::bundle_id,
             ))
             .order_by(refunds::id)
             .load(conn)
@@ -848,6 +858,11 @@ impl ActivityItem {
                     company_fee_in_cents: item.company_fee_in_cents,
                     client_fee_in_cents: item.client_fee_in_cents,
                     refunded_quantity: item.refunded_quantity,
+                    tax_rule_id: item.tax_rule_id,
+                    tax_in_cents: item.tax_in_cents,
+                    currency: item.currency.clone(),
+                    listing_id: item.listing_id,
+                    bundle_id: item.bundle_id,
                 };
                 refund_items.push(RefundActivityItem {
                     id: item.id,
@@ -0,0 +1,263 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use hex;
+use models::*;
+use ring::digest;
+use schema::api_keys;
+use std::str::FromStr;
+use utils::errors::*;
+use utils::rand::random_alpha_string;
+use uuid::Uuid;
+
+/// Marks a bearer token as an API key rather than a JWT
+pub const API_KEY_PREFIX: &str = "bnk_";
+const API_KEY_SECRET_LENGTH: usize = 40;
+const API_KEY_DISPLAY_PREFIX_LENGTH: usize = 12;
+
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(Organization)]
+#[belongs_to(User)]
+#[table_name = "api_keys"]
+pub struct ApiKey {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    /// User who issued the key, requests made with the key act on their behalf
+    pub user_id: Uuid,
+    pub name: String,
+    /// Leading characters of the key, safe to display and log to identify it
+    pub key_prefix: String,
+    #[serde(default, skip_serializing)]
+    pub key_hash: String,
+    pub scopes: Vec<String>,
+    pub last_used_at: Option<NaiveDateTime>,
+    pub expires_at: Option<NaiveDateTime>,
+    pub revoked_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct NewApiKey {
+    pub organization_id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<NaiveDateTime>,
+}
+
+/// An API key along with its secret, only available when the key is created or rotated
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct IssuedApiKey {
+    pub api_key: ApiKey,
+    pub key: String,
+}
+
+impl NewApiKey {
+    pub fn commit(self, conn: &PgConnection) -> Result<IssuedApiKey, DatabaseError> {
+        self.validate_record(conn)?;
+
+        let key = ApiKey::generate_key();
+        let api_key: ApiKey = diesel::insert_into(api_keys::table)
+            .values((
+                api_keys::organization_id.eq(self.organization_id),
+                api_keys::user_id.eq(self.user_id),
+                api_keys::name.eq(&self.name),
+                api_keys::key_prefix.eq(ApiKey::display_prefix(&key)),
+                api_keys::key_hash.eq(ApiKey::hash_key(&key)),
+                api_keys::scopes.eq(&self.scopes),
+                api_keys::expires_at.eq(self.expires_at),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create API key")?;
+
+        DomainEvent::create(
+            DomainEventTypes::ApiKeyCreated,
+            format!("API key '{}' created", &api_key.name),
+            Tables::ApiKeys,
+            Some(api_key.id),
+            Some(self.user_id),
+            Some(json!({
+                "organization_id": self.organization_id,
+                "name": self.name,
+                "key_prefix": api_key.key_prefix,
+                "scopes": self.scopes,
+                "expires_at": self.expires_at
+            })),
+        )
+        .commit(conn)?;
+
+        Ok(IssuedApiKey { api_key, key })
+    }
+
+    fn validate_record(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        if self.name.trim().is_empty() {
+            return DatabaseError::validation_error("name", "Name cannot be blank");
+        }
+        if self.scopes.is_empty() {
+            return DatabaseError::validation_error("scopes", "At least one scope is required");
+        }
+        if let Some(expires_at) = self.expires_at {
+            if expires_at <= Utc::now().naive_utc() {
+                return DatabaseError::validation_error("expires_at", "Expiry must be in the future");
+            }
+        }
+
+        // A key can never grant more than its issuer holds for the organization
+        let organization = Organization::find(self.organization_id, conn)?;
+        let issuer_scopes = organization.get_scopes_for_user(&User::find(self.user_id, conn)?, conn)?;
+        for scope in &self.scopes {
+            match Scopes::from_str(scope) {
+                Ok(scope) if issuer_scopes.contains(&scope) => (),
+                Ok(_) => {
+                    return DatabaseError::validation_error("scopes", "Scope is not held by the issuing user");
+                }
+                Err(_) => return DatabaseError::validation_error("scopes", "Unknown scope"),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl ApiKey {
+    pub fn create(
+        organization_id: Uuid,
+        user_id: Uuid,
+        name: String,
+        scopes: Vec<Scopes>,
+        expires_at: Option<NaiveDateTime>,
+    ) -> NewApiKey {
+        NewApiKey {
+            organization_id,
+            user_id,
+            name,
+            scopes: scopes.into_iter().map(|s| s.to_string()).collect(),
+            expires_at,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<ApiKey, DatabaseError> {
+        api_keys::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find API key")
+    }
+
+    /// Finds the active key matching the plain text key presented by a client
+    pub fn find_active_by_key(key: &str, conn: &PgConnection) -> Result<ApiKey, DatabaseError> {
+        api_keys::table
+            .filter(api_keys::key_hash.eq(ApiKey::hash_key(key)))
+            .filter(api_keys::revoked_at.is_null())
+            .filter(
+                api_keys::expires_at
+                    .is_null()
+                    .or(api_keys::expires_at.gt(dsl::now.nullable())),
+            )
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find API key")
+    }
+
+    pub fn find_for_organization(organization_id: Uuid, conn: &PgConnection) -> Result<Vec<ApiKey>, DatabaseError> {
+        api_keys::table
+            .filter(api_keys::organization_id.eq(organization_id))
+            .order_by(api_keys::created_at.desc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load API keys for organization")
+    }
+
+    pub fn organization(&self, conn: &PgConnection) -> Result<Organization, DatabaseError> {
+        Organization::find(self.organization_id, conn)
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.revoked_at.is_none() && self.expires_at.map(|e| e > Utc::now().naive_utc()).unwrap_or(true)
+    }
+
+    pub fn has_scope(&self, scope: Scopes) -> bool {
+        self.scopes.contains(&scope.to_string())
+    }
+
+    pub fn record_usage(&self, conn: &PgConnection) -> Result<ApiKey, DatabaseError> {
+        diesel::update(self)
+            .set(api_keys::last_used_at.eq(dsl::now.nullable()))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not record API key usage")
+    }
+
+    /// Replaces the key's secret, the previous secret stops working immediately
+    pub fn rotate(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<IssuedApiKey, DatabaseError> {
+        if !self.is_active() {
+            return DatabaseError::business_process_error("Unable to rotate an inactive API key");
+        }
+
+        let key = ApiKey::generate_key();
+        let api_key: ApiKey = diesel::update(self)
+            .set((
+                api_keys::key_prefix.eq(ApiKey::display_prefix(&key)),
+                api_keys::key_hash.eq(ApiKey::hash_key(&key)),
+                api_keys::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not rotate API key")?;
+
+        DomainEvent::create(
+            DomainEventTypes::ApiKeyRotated,
+            format!("API key '{}' rotated", &self.name),
+            Tables::ApiKeys,
+            Some(self.id),
+            current_user_id,
+            Some(json!({
+                "previous_key_prefix": self.key_prefix,
+                "key_prefix": api_key.key_prefix
+            })),
+        )
+        .commit(conn)?;
+
+        Ok(IssuedApiKey { api_key, key })
+    }
+
+    pub fn revoke(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<ApiKey, DatabaseError> {
+        if self.revoked_at.is_some() {
+            return DatabaseError::business_process_error("API key has already been revoked");
+        }
+
+        let api_key: ApiKey = diesel::update(self)
+            .set((
+                api_keys::revoked_at.eq(dsl::now.nullable()),
+                api_keys::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not revoke API key")?;
+
+        DomainEvent::create(
+            DomainEventTypes::ApiKeyRevoked,
+            format!("API key '{}' revoked", &self.name),
+            Tables::ApiKeys,
+            Some(self.id),
+            current_user_id,
+            None,
+        )
+        .commit(conn)?;
+
+        Ok(api_key)
+    }
+
+    pub fn is_api_key(token: &str) -> bool {
+        token.starts_with(API_KEY_PREFIX)
+    }
+
+    /// Identifies a key in logs without revealing enough of it to be used
+    pub fn display_prefix(key: &str) -> String {
+        key.chars().take(API_KEY_DISPLAY_PREFIX_LENGTH).collect()
+    }
+
+    fn generate_key() -> String {
+        format!("{}{}", API_KEY_PREFIX, random_alpha_string(API_KEY_SECRET_LENGTH))
+    }
+
+    fn hash_key(key: &str) -> String {
+        hex::encode(digest::digest(&digest::SHA256, key.as_bytes()).as_ref())
+    }
+}
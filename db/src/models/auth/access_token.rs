@@ -13,6 +13,11 @@ pub struct AccessToken {
     #[serde(default)]
     pub scopes: Option<Vec<String>>,
     pub issued: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<Uuid>,
+    /// Only set on refresh tokens, must match the session's current refresh token id
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_token_id: Option<Uuid>,
 }
 
 impl AccessToken {
@@ -28,6 +33,8 @@ impl AccessToken {
             exp,
             scopes: None,
             issued,
+            session_id: None,
+            refresh_token_id: None,
         }
     }
 
@@ -42,9 +49,17 @@ impl AccessToken {
             exp,
             scopes: Some(scopes.into_iter().map(|s| s.to_string()).collect_vec()),
             issued,
+            session_id: None,
+            refresh_token_id: None,
         }
     }
 
+    pub fn for_session(mut self, session_id: Uuid, refresh_token_id: Option<Uuid>) -> Self {
+        self.session_id = Some(session_id);
+        self.refresh_token_id = refresh_token_id;
+        self
+    }
+
     pub fn get_id(&self) -> Result<Uuid, ParseError> {
         Ok(Uuid::parse_str(&self.sub)?)
     }
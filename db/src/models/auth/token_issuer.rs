@@ -1,7 +1,7 @@
 use chrono::Duration;
 use jsonwebtoken::errors::Error;
 use jsonwebtoken::TokenData;
-use models::Scopes;
+use models::{Scopes, UserSession};
 use prelude::AccessToken;
 use uuid::Uuid;
 
@@ -11,4 +11,6 @@ pub trait TokenIssuer {
     fn issue(&self, user_id: Uuid, expires: Duration) -> Result<String, Error>;
     fn issue_with_limited_scopes(&self, user_id: Uuid, scopes: Vec<Scopes>, expires: Duration)
         -> Result<String, Error>;
+    fn issue_for_session(&self, session: &UserSession, expires: Duration) -> Result<String, Error>;
+    fn issue_refresh_for_session(&self, session: &UserSession, expires: Duration) -> Result<String, Error>;
 }
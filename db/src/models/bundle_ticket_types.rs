@@ -0,0 +1,129 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::{Bundle, TicketType};
+use schema::bundle_ticket_types;
+use utils::errors::*;
+use uuid::Uuid;
+use validators;
+
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(Bundle)]
+#[belongs_to(TicketType)]
+#[table_name = "bundle_ticket_types"]
+pub struct BundleTicketType {
+    pub id: Uuid,
+    pub bundle_id: Uuid,
+    pub ticket_type_id: Uuid,
+    pub quantity: i32,
+    /// Revenue allocated to each ticket issued for this ticket type when the bundle is purchased
+    pub unit_price_in_cents: i64,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "bundle_ticket_types"]
+pub struct NewBundleTicketType {
+    pub bundle_id: Uuid,
+    pub ticket_type_id: Uuid,
+    pub quantity: i32,
+    pub unit_price_in_cents: i64,
+}
+
+impl NewBundleTicketType {
+    pub fn commit(&self, conn: &PgConnection) -> Result<BundleTicketType, DatabaseError> {
+        BundleTicketType::validate_amounts(self.quantity, self.unit_price_in_cents)?;
+
+        diesel::insert_into(bundle_ticket_types::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not add ticket type to bundle")
+    }
+}
+
+impl BundleTicketType {
+    pub fn create(
+        bundle_id: Uuid,
+        ticket_type_id: Uuid,
+        quantity: i32,
+        unit_price_in_cents: i64,
+    ) -> NewBundleTicketType {
+        NewBundleTicketType {
+            bundle_id,
+            ticket_type_id,
+            quantity,
+            unit_price_in_cents,
+        }
+    }
+
+    pub fn find_for_bundle(bundle_id: Uuid, conn: &PgConnection) -> Result<Vec<BundleTicketType>, DatabaseError> {
+        bundle_ticket_types::table
+            .filter(bundle_ticket_types::bundle_id.eq(bundle_id))
+            .order_by(bundle_ticket_types::created_at.asc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load ticket types for bundle")
+    }
+
+    pub fn find_by_bundle_id_ticket_type_id(
+        bundle_id: Uuid,
+        ticket_type_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<BundleTicketType, DatabaseError> {
+        bundle_ticket_types::table
+            .filter(bundle_ticket_types::bundle_id.eq(bundle_id))
+            .filter(bundle_ticket_types::ticket_type_id.eq(ticket_type_id))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find ticket type for bundle")
+    }
+
+    pub fn update(
+        &self,
+        quantity: i32,
+        unit_price_in_cents: i64,
+        conn: &PgConnection,
+    ) -> Result<BundleTicketType, DatabaseError> {
+        BundleTicketType::validate_amounts(quantity, unit_price_in_cents)?;
+
+        diesel::update(self)
+            .set((
+                bundle_ticket_types::quantity.eq(quantity),
+                bundle_ticket_types::unit_price_in_cents.eq(unit_price_in_cents),
+                bundle_ticket_types::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update ticket type for bundle")
+    }
+
+    pub fn destroy(self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        diesel::delete(&self)
+            .execute(conn)
+            .map(|_| ())
+            .to_db_error(ErrorCode::DeleteError, "Could not remove ticket type from bundle")
+    }
+
+    fn validate_amounts(quantity: i32, unit_price_in_cents: i64) -> Result<(), DatabaseError> {
+        let validation_errors = validators::append_validation_error(
+            Ok(()),
+            "quantity",
+            validators::validate_greater_than_or_equal(
+                quantity,
+                1,
+                "quantity_must_be_positive",
+                "Quantity must be at least 1",
+            ),
+        );
+        let validation_errors = validators::append_validation_error(
+            validation_errors,
+            "unit_price_in_cents",
+            validators::validate_greater_than_or_equal(
+                unit_price_in_cents,
+                0,
+                "unit_price_must_not_be_negative",
+                "Unit price cannot be negative",
+            ),
+        );
+        Ok(validation_errors?)
+    }
+}
@@ -0,0 +1,280 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use prelude::*;
+use schema::{bundle_ticket_types, bundles, events, ticket_types};
+use utils::errors::ErrorCode;
+use uuid::Uuid;
+
+#[derive(Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[table_name = "bundles"]
+pub struct Bundle {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub name: String,
+    pub currency: String,
+    pub deleted_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Deserialize, Insertable, Serialize)]
+#[table_name = "bundles"]
+pub struct NewBundle {
+    pub organization_id: Uuid,
+    pub name: String,
+    pub currency: String,
+}
+
+#[derive(AsChangeset, Clone, Default, Deserialize, Serialize)]
+#[table_name = "bundles"]
+pub struct BundleEditableAttributes {
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct DisplayBundle {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub name: String,
+    pub currency: String,
+    pub price_in_cents: i64,
+    pub ticket_types: Vec<DisplayBundleTicketType>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Queryable, Serialize)]
+pub struct DisplayBundleTicketType {
+    pub ticket_type_id: Uuid,
+    pub ticket_type_name: String,
+    pub event_id: Uuid,
+    pub event_name: String,
+    pub event_start: Option<NaiveDateTime>,
+    pub quantity: i32,
+    pub unit_price_in_cents: i64,
+}
+
+impl Bundle {
+    pub fn create(organization_id: Uuid, name: String, currency: String) -> NewBundle {
+        NewBundle {
+            organization_id,
+            name,
+            currency,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<Bundle, DatabaseError> {
+        bundles::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find bundle")
+    }
+
+    pub fn find_for_organization(organization_id: Uuid, conn: &PgConnection) -> Result<Vec<Bundle>, DatabaseError> {
+        bundles::table
+            .filter(bundles::organization_id.eq(organization_id))
+            .filter(bundles::deleted_at.is_null())
+            .order_by(bundles::name.asc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load bundles for organization")
+    }
+
+    pub fn organization(&self, conn: &PgConnection) -> Result<Organization, DatabaseError> {
+        Organization::find(self.organization_id, conn)
+    }
+
+    pub fn ticket_types(&self, conn: &PgConnection) -> Result<Vec<BundleTicketType>, DatabaseError> {
+        BundleTicketType::find_for_bundle(self.id, conn)
+    }
+
+    /// Package price of a single bundle, the sum of the revenue allocated to each ticket it issues
+    pub fn price_in_cents(&self, conn: &PgConnection) -> Result<i64, DatabaseError> {
+        Ok(self
+            .ticket_types(conn)?
+            .iter()
+            .map(|bt| bt.quantity as i64 * bt.unit_price_in_cents)
+            .sum())
+    }
+
+    /// Adds a ticket type to the bundle or, if it is already part of it, changes the quantity and
+    /// revenue allocated to it. The ticket type may belong to any event of the bundle's organization.
+    pub fn add_ticket_type(
+        &self,
+        ticket_type_id: Uuid,
+        quantity: i32,
+        unit_price_in_cents: i64,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<BundleTicketType, DatabaseError> {
+        if self.deleted_at.is_some() {
+            return DatabaseError::business_process_error("Unable to change a deleted bundle");
+        }
+
+        let event = TicketType::find(ticket_type_id, conn)?.event(conn)?;
+        if event.organization_id != self.organization_id {
+            return DatabaseError::validation_error(
+                "ticket_type_id",
+                "Ticket type must belong to an event of the bundle's organization",
+            );
+        }
+        if event.currency != self.currency {
+            return DatabaseError::validation_error(
+                "ticket_type_id",
+                "Ticket type must be priced in the bundle's currency",
+            );
+        }
+
+        let bundle_ticket_type = match BundleTicketType::find_by_bundle_id_ticket_type_id(self.id, ticket_type_id, conn)
+            .optional()?
+        {
+            Some(bundle_ticket_type) => bundle_ticket_type.update(quantity, unit_price_in_cents, conn)?,
+            None => BundleTicketType::create(self.id, ticket_type_id, quantity, unit_price_in_cents).commit(conn)?,
+        };
+
+        DomainEvent::create(
+            DomainEventTypes::BundleUpdated,
+            format!("Ticket type added to bundle '{}'", &self.name),
+            Tables::Bundles,
+            Some(self.id),
+            current_user_id,
+            Some(json!({
+                "ticket_type_id": ticket_type_id,
+                "quantity": quantity,
+                "unit_price_in_cents": unit_price_in_cents
+            })),
+        )
+        .commit(conn)?;
+
+        Ok(bundle_ticket_type)
+    }
+
+    pub fn remove_ticket_type(
+        &self,
+        ticket_type_id: Uuid,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        BundleTicketType::find_by_bundle_id_ticket_type_id(self.id, ticket_type_id, conn)?.destroy(conn)?;
+
+        DomainEvent::create(
+            DomainEventTypes::BundleUpdated,
+            format!("Ticket type removed from bundle '{}'", &self.name),
+            Tables::Bundles,
+            Some(self.id),
+            current_user_id,
+            Some(json!({ "ticket_type_id": ticket_type_id })),
+        )
+        .commit(conn)?;
+
+        Ok(())
+    }
+
+    pub fn update(
+        &self,
+        attributes: BundleEditableAttributes,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Bundle, DatabaseError> {
+        if let Some(ref name) = attributes.name {
+            Bundle::validate_name(name)?;
+        }
+
+        let result: Bundle = diesel::update(self)
+            .set((&attributes, bundles::updated_at.eq(dsl::now)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update bundle")?;
+
+        DomainEvent::create(
+            DomainEventTypes::BundleUpdated,
+            format!("Bundle '{}' updated", &result.name),
+            Tables::Bundles,
+            Some(self.id),
+            current_user_id,
+            Some(json!(attributes)),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    /// Removes the bundle from sale, tickets already purchased through it are unaffected
+    pub fn destroy(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<(), DatabaseError> {
+        diesel::update(self)
+            .set((bundles::deleted_at.eq(dsl::now), bundles::updated_at.eq(dsl::now)))
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not delete bundle")?;
+
+        DomainEvent::create(
+            DomainEventTypes::BundleDeleted,
+            format!("Bundle '{}' deleted", &self.name),
+            Tables::Bundles,
+            Some(self.id),
+            current_user_id,
+            None,
+        )
+        .commit(conn)?;
+
+        Ok(())
+    }
+
+    pub fn for_display(&self, conn: &PgConnection) -> Result<DisplayBundle, DatabaseError> {
+        let ticket_types: Vec<DisplayBundleTicketType> = bundle_ticket_types::table
+            .inner_join(ticket_types::table.inner_join(events::table))
+            .filter(bundle_ticket_types::bundle_id.eq(self.id))
+            .order_by(events::event_start.asc())
+            .then_order_by(ticket_types::rank.asc())
+            .select((
+                ticket_types::id,
+                ticket_types::name,
+                events::id,
+                events::name,
+                events::event_start,
+                bundle_ticket_types::quantity,
+                bundle_ticket_types::unit_price_in_cents,
+            ))
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load ticket types for bundle")?;
+
+        Ok(DisplayBundle {
+            id: self.id,
+            organization_id: self.organization_id,
+            name: self.name.clone(),
+            currency: self.currency.clone(),
+            price_in_cents: ticket_types
+                .iter()
+                .map(|tt| tt.quantity as i64 * tt.unit_price_in_cents)
+                .sum(),
+            ticket_types,
+        })
+    }
+
+    fn validate_name(name: &str) -> Result<(), DatabaseError> {
+        if name.trim().is_empty() {
+            return DatabaseError::validation_error("name", "Name cannot be blank");
+        }
+        Ok(())
+    }
+}
+
+impl NewBundle {
+    pub fn commit(self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<Bundle, DatabaseError> {
+        Bundle::validate_name(&self.name)?;
+
+        let bundle: Bundle = diesel::insert_into(bundles::table)
+            .values(&self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create bundle")?;
+
+        DomainEvent::create(
+            DomainEventTypes::BundleCreated,
+            format!("Bundle '{}' created", &bundle.name),
+            Tables::Bundles,
+            Some(bundle.id),
+            current_user_id,
+            Some(json!(self)),
+        )
+        .commit(conn)?;
+
+        Ok(bundle)
+    }
+}
@@ -88,6 +88,32 @@ impl Communication {
         }
     }
 
+    /// Uses the stored template for the organization, or the platform default, in place of the
+    /// provider template when one has been saved
+    pub fn apply_template(
+        &mut self,
+        template_name: CommunicationTemplateNames,
+        organization_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        // Stored templates are rendered once so per recipient data is left to the provider
+        let template_data = match self.template_data {
+            Some(ref template_data) if template_data.len() > 1 => return Ok(()),
+            Some(ref template_data) => template_data.first().cloned().unwrap_or_default(),
+            None => TemplateData::new(),
+        };
+
+        if let Some(template) =
+            CommunicationTemplate::find_active(organization_id, template_name, self.comm_type, conn)?
+        {
+            let rendered = template.render(&template_data);
+            self.title = rendered.subject;
+            self.body = Some(rendered.body);
+            self.template_id = None;
+        }
+        Ok(())
+    }
+
     pub fn queue(&self, connection: &PgConnection) -> Result<(), DatabaseError> {
         DomainAction::create(
             None,
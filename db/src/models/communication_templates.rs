@@ -0,0 +1,545 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::{communication_template_versions, communication_templates};
+use std::borrow::Cow;
+use utils::errors::*;
+use utils::templates;
+use uuid::Uuid;
+use validator::ValidationErrors;
+use validators::{self, *};
+
+/// Variables added by `insert_event_template_data` for mailers about a specific event
+const EVENT_TEMPLATE_VARIABLES: &'static [&'static str] = &[
+    "event_id",
+    "event_name",
+    "event_promo_url",
+    "event_age_limit",
+    "event_date",
+    "event_event_start",
+    "event_doors_open_time",
+    "artist_headliners",
+    "artist_other",
+    "venue_name",
+    "venue_address",
+    "venue_city",
+    "venue_state",
+    "venue_country",
+    "venue_postal_code",
+    "venue_phone",
+    "venue_promo_image_url",
+    "venue_map_link",
+    "organization_id",
+    "organization_name",
+];
+
+/// Copy for a communication stored in the database. Organizations can override the platform
+/// default (stored without an organization) and every change is kept as a version.
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(Organization)]
+#[table_name = "communication_templates"]
+pub struct CommunicationTemplate {
+    pub id: Uuid,
+    pub organization_id: Option<Uuid>,
+    pub template_name: CommunicationTemplateNames,
+    pub communication_type: CommunicationType,
+    pub subject: String,
+    pub body: String,
+    pub version: i32,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(CommunicationTemplate)]
+#[table_name = "communication_template_versions"]
+pub struct CommunicationTemplateVersion {
+    pub id: Uuid,
+    pub communication_template_id: Uuid,
+    pub version: i32,
+    pub subject: String,
+    pub body: String,
+    pub created_by: Option<Uuid>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Deserialize, Insertable, Serialize)]
+#[table_name = "communication_templates"]
+pub struct NewCommunicationTemplate {
+    pub organization_id: Option<Uuid>,
+    pub template_name: CommunicationTemplateNames,
+    pub communication_type: CommunicationType,
+    pub subject: String,
+    pub body: String,
+}
+
+#[derive(Insertable)]
+#[table_name = "communication_template_versions"]
+struct NewCommunicationTemplateVersion {
+    communication_template_id: Uuid,
+    version: i32,
+    subject: String,
+    body: String,
+    created_by: Option<Uuid>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct RenderedCommunicationTemplate {
+    pub subject: String,
+    pub body: String,
+}
+
+impl NewCommunicationTemplate {
+    /// Creates the template, or saves the content as the next version when the organization
+    /// already has a template with this name and type
+    pub fn commit(
+        self,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<CommunicationTemplate, DatabaseError> {
+        self.validate_record()?;
+
+        let existing = CommunicationTemplate::find_by_name(
+            self.organization_id,
+            self.template_name,
+            self.communication_type,
+            conn,
+        )?;
+        let template = match existing {
+            Some(existing) => {
+                if existing.subject == self.subject && existing.body == self.body {
+                    return Ok(existing);
+                }
+                existing.update_content(self.subject, self.body, conn)?
+            }
+            None => diesel::insert_into(communication_templates::table)
+                .values(&self)
+                .get_result(conn)
+                .to_db_error(ErrorCode::InsertError, "Could not create communication template")?,
+        };
+        template.record_version(current_user_id, conn)?;
+
+        template.create_domain_event(
+            DomainEventTypes::CommunicationTemplateUpdated,
+            "Communication template updated",
+            current_user_id,
+            conn,
+        )?;
+
+        Ok(template)
+    }
+
+    fn validate_record(&self) -> Result<(), DatabaseError> {
+        let mut validation_errors: Result<(), ValidationErrors> = Ok(());
+        if self.body.trim().is_empty() {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "body",
+                Err(create_validation_error("required", "Body is required")),
+            );
+        }
+        if self.communication_type == CommunicationType::EmailTemplate && self.subject.trim().is_empty() {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "subject",
+                Err(create_validation_error("required", "Subject is required for emails")),
+            );
+        }
+
+        let available = self.template_name.variables();
+        for (field, content) in vec![("subject", &self.subject), ("body", &self.body)] {
+            let unknown: Vec<String> = templates::variables(content)
+                .into_iter()
+                .filter(|v| !available.contains(&v.as_str()))
+                .collect();
+            if !unknown.is_empty() {
+                let mut validation_error = create_validation_error(
+                    "unknown_variables",
+                    "Template uses variables that are not sent with this communication",
+                );
+                validation_error.add_param(Cow::from("unknown_variables"), &unknown);
+                validation_errors =
+                    validators::append_validation_error(validation_errors, field, Err(validation_error));
+            }
+        }
+
+        Ok(validation_errors?)
+    }
+}
+
+impl CommunicationTemplate {
+    pub fn create(
+        organization_id: Option<Uuid>,
+        template_name: CommunicationTemplateNames,
+        communication_type: CommunicationType,
+        subject: String,
+        body: String,
+    ) -> NewCommunicationTemplate {
+        NewCommunicationTemplate {
+            organization_id,
+            template_name,
+            communication_type,
+            subject,
+            body,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<CommunicationTemplate, DatabaseError> {
+        communication_templates::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find communication template")
+    }
+
+    /// Templates stored for the organization, or the platform defaults when no organization is given
+    pub fn find_for_organization(
+        organization_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Vec<CommunicationTemplate>, DatabaseError> {
+        let query = match organization_id {
+            Some(organization_id) => communication_templates::table
+                .filter(communication_templates::organization_id.eq(organization_id))
+                .into_boxed(),
+            None => communication_templates::table
+                .filter(communication_templates::organization_id.is_null())
+                .into_boxed(),
+        };
+
+        query
+            .order_by((
+                communication_templates::template_name,
+                communication_templates::communication_type,
+            ))
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load communication templates")
+    }
+
+    pub fn find_by_name(
+        organization_id: Option<Uuid>,
+        template_name: CommunicationTemplateNames,
+        communication_type: CommunicationType,
+        conn: &PgConnection,
+    ) -> Result<Option<CommunicationTemplate>, DatabaseError> {
+        let query = communication_templates::table
+            .filter(communication_templates::template_name.eq(template_name))
+            .filter(communication_templates::communication_type.eq(communication_type))
+            .into_boxed();
+        let query = match organization_id {
+            Some(organization_id) => query.filter(communication_templates::organization_id.eq(organization_id)),
+            None => query.filter(communication_templates::organization_id.is_null()),
+        };
+
+        query
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Could not load communication template")
+    }
+
+    /// The template used when sending, the organization's own template takes precedence over
+    /// the platform default
+    pub fn find_active(
+        organization_id: Option<Uuid>,
+        template_name: CommunicationTemplateNames,
+        communication_type: CommunicationType,
+        conn: &PgConnection,
+    ) -> Result<Option<CommunicationTemplate>, DatabaseError> {
+        if organization_id.is_some() {
+            if let Some(template) =
+                CommunicationTemplate::find_by_name(organization_id, template_name, communication_type, conn)?
+            {
+                return Ok(Some(template));
+            }
+        }
+        CommunicationTemplate::find_by_name(None, template_name, communication_type, conn)
+    }
+
+    pub fn organization(&self, conn: &PgConnection) -> Result<Option<Organization>, DatabaseError> {
+        self.organization_id
+            .map(|organization_id| Organization::find(organization_id, conn))
+            .transpose()
+    }
+
+    /// Newest version first
+    pub fn versions(&self, conn: &PgConnection) -> Result<Vec<CommunicationTemplateVersion>, DatabaseError> {
+        communication_template_versions::table
+            .filter(communication_template_versions::communication_template_id.eq(self.id))
+            .order_by(communication_template_versions::version.desc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load communication template versions")
+    }
+
+    /// Restores the content of an earlier version. The restored content is saved as a new
+    /// version so the history is never rewritten.
+    pub fn rollback(
+        &self,
+        version: i32,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<CommunicationTemplate, DatabaseError> {
+        let target: Option<CommunicationTemplateVersion> = communication_template_versions::table
+            .filter(communication_template_versions::communication_template_id.eq(self.id))
+            .filter(communication_template_versions::version.eq(version))
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Could not load communication template version")?;
+        let target = match target {
+            Some(target) => target,
+            None => return DatabaseError::business_process_error("Communication template version does not exist"),
+        };
+        if target.version == self.version {
+            return DatabaseError::business_process_error("Communication template is already at this version");
+        }
+
+        let template = self.update_content(target.subject, target.body, conn)?;
+        template.record_version(current_user_id, conn)?;
+
+        DomainEvent::create(
+            DomainEventTypes::CommunicationTemplateRolledBack,
+            "Communication template rolled back".to_string(),
+            Tables::CommunicationTemplates,
+            Some(template.id),
+            current_user_id,
+            Some(json!({
+                "template_name": template.template_name,
+                "communication_type": template.communication_type,
+                "version": template.version,
+                "restored_version": version
+            })),
+        )
+        .with_organization_id(template.organization_id)
+        .commit(conn)?;
+
+        Ok(template)
+    }
+
+    /// Removes the template and its history, communications fall back to the platform default
+    pub fn destroy(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<(), DatabaseError> {
+        diesel::delete(self)
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not delete communication template")?;
+
+        self.create_domain_event(
+            DomainEventTypes::CommunicationTemplateDeleted,
+            "Communication template deleted",
+            current_user_id,
+            conn,
+        )
+    }
+
+    pub fn render(&self, template_data: &TemplateData) -> RenderedCommunicationTemplate {
+        CommunicationTemplate::render_content(self.communication_type, &self.subject, &self.body, template_data)
+    }
+
+    /// Email bodies are HTML so values are escaped unless inserted with `{{{variable}}}`
+    pub fn render_content(
+        communication_type: CommunicationType,
+        subject: &str,
+        body: &str,
+        template_data: &TemplateData,
+    ) -> RenderedCommunicationTemplate {
+        RenderedCommunicationTemplate {
+            subject: templates::render(subject, template_data, false),
+            body: templates::render(
+                body,
+                template_data,
+                communication_type == CommunicationType::EmailTemplate,
+            ),
+        }
+    }
+
+    fn update_content(
+        &self,
+        subject: String,
+        body: String,
+        conn: &PgConnection,
+    ) -> Result<CommunicationTemplate, DatabaseError> {
+        diesel::update(self)
+            .set((
+                communication_templates::subject.eq(subject),
+                communication_templates::body.eq(body),
+                communication_templates::version.eq(communication_templates::version + 1),
+                communication_templates::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update communication template")
+    }
+
+    fn record_version(
+        &self,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<CommunicationTemplateVersion, DatabaseError> {
+        diesel::insert_into(communication_template_versions::table)
+            .values(&NewCommunicationTemplateVersion {
+                communication_template_id: self.id,
+                version: self.version,
+                subject: self.subject.clone(),
+                body: self.body.clone(),
+                created_by: current_user_id,
+            })
+            .get_result(conn)
+            .to_db_error(
+                ErrorCode::InsertError,
+                "Could not create communication template version",
+            )
+    }
+
+    fn create_domain_event(
+        &self,
+        event_type: DomainEventTypes,
+        display_text: &str,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        DomainEvent::create(
+            event_type,
+            display_text.to_string(),
+            Tables::CommunicationTemplates,
+            Some(self.id),
+            current_user_id,
+            Some(json!({
+                "template_name": self.template_name,
+                "communication_type": self.communication_type,
+                "version": self.version
+            })),
+        )
+        .with_organization_id(self.organization_id)
+        .commit(conn)?;
+        Ok(())
+    }
+}
+
+impl CommunicationTemplateNames {
+    /// The template data each mailer sends for this communication
+    pub fn variables(&self) -> Vec<&'static str> {
+        let (variables, includes_event): (Vec<&'static str>, bool) = match self {
+            CommunicationTemplateNames::OrgInvite => (vec!["name", "org", "invite_link_accept"], false),
+            CommunicationTemplateNames::PasswordReset => (vec!["name", "password_reset_link"], false),
+            CommunicationTemplateNames::PaymentMethodExpiring => (
+                vec!["name", "update_link", "card_brand", "card_last_four", "card_expiry"],
+                false,
+            ),
+            CommunicationTemplateNames::PaymentPlanCancelled => (vec!["name", "order_id", "order_number"], false),
+            CommunicationTemplateNames::PaymentPlanInstallmentFailed => (
+                vec!["name", "update_link", "installment_number", "amount", "retry_date"],
+                false,
+            ),
+            CommunicationTemplateNames::PurchaseCompleted => (
+                vec![
+                    "name",
+                    "ticket_count",
+                    "total_initial_fees",
+                    "total_refunded_fees",
+                    "total_fees",
+                    "total_price",
+                    "item_breakdown",
+                    "total_breakdown",
+                    "tickets_link",
+                ],
+                false,
+            ),
+            CommunicationTemplateNames::Refund => (
+                vec![
+                    "name",
+                    "amount_refunded",
+                    "ticket_count",
+                    "total_fees",
+                    "total_price",
+                    "item_breakdown",
+                    "tickets_link",
+                ],
+                false,
+            ),
+            CommunicationTemplateNames::TransferCancelled | CommunicationTemplateNames::TransferCancelledReceipt => {
+                (vec!["sender_name", "receiver_address", "transfer_id"], false)
+            }
+            CommunicationTemplateNames::TransferDripReminder => (
+                vec![
+                    "header",
+                    "sender_name",
+                    "receiver_address",
+                    "transfer_accept_url",
+                    "transfer_cancel_url",
+                    "transfer_id",
+                ],
+                true,
+            ),
+            CommunicationTemplateNames::TransferExpiryReminder => (
+                vec![
+                    "header",
+                    "sender_name",
+                    "receiver_address",
+                    "transfer_cancel_url",
+                    "transfer_id",
+                ],
+                true,
+            ),
+            CommunicationTemplateNames::TransferReturnedReceipt => (
+                vec![
+                    "sender_name",
+                    "receiver_address",
+                    "transfer_id",
+                    "transfer_status",
+                    "num_tickets",
+                ],
+                false,
+            ),
+            CommunicationTemplateNames::TransferSentReceipt => (
+                vec!["sender_name", "receiver_address", "transfer_cancel_url", "transfer_id"],
+                true,
+            ),
+            CommunicationTemplateNames::TransferTickets => (vec!["sender_name", "receive_tickets_link"], false),
+            CommunicationTemplateNames::UserInvite => (vec!["name", "invite_link"], false),
+            CommunicationTemplateNames::UserRegistered => (vec!["name"], false),
+            CommunicationTemplateNames::UserRegisteredMagicLink => (vec!["download_link", "refresh_token"], false),
+            CommunicationTemplateNames::WaitlistOffer => (
+                vec!["claim_link", "ticket_type_name", "quantity", "offer_expires_at"],
+                true,
+            ),
+        };
+
+        let mut variables = variables;
+        if includes_event {
+            variables.extend_from_slice(EVENT_TEMPLATE_VARIABLES);
+        }
+        variables
+    }
+
+    /// Example values for previewing a template
+    pub fn sample_data(&self) -> TemplateData {
+        self.variables()
+            .into_iter()
+            .map(|variable| (variable.to_string(), sample_value(variable)))
+            .collect()
+    }
+}
+
+fn sample_value(variable: &str) -> String {
+    match variable {
+        "name" | "sender_name" => "Jordan Smith".to_string(),
+        "org" | "organization_name" => "Sample Organization".to_string(),
+        "receiver_address" => "recipient@example.com".to_string(),
+        "event_name" => "Sample Event".to_string(),
+        "venue_name" => "Sample Venue".to_string(),
+        "ticket_type_name" => "General Admission".to_string(),
+        "ticket_count" | "quantity" | "num_tickets" | "installment_number" => "2".to_string(),
+        "amount" | "amount_refunded" | "total_price" => "50.00".to_string(),
+        "total_fees" | "total_initial_fees" => "5.00".to_string(),
+        "total_refunded_fees" => "0.00".to_string(),
+        "order_number" => "A1B2C3D4".to_string(),
+        "transfer_status" => TransferStatus::Declined.to_string(),
+        "card_brand" => "Visa".to_string(),
+        "card_last_four" => "4242".to_string(),
+        "card_expiry" => "12/2030".to_string(),
+        "item_breakdown" | "total_breakdown" => r#"<table style="width:100%"><tbody></tbody></table>"#.to_string(),
+        "retry_date" | "event_date" => "Saturday, 1 January 2030".to_string(),
+        "offer_expires_at" => "2030-01-01 12:00:00".to_string(),
+        "event_event_start" => "8:00 PM UTC".to_string(),
+        "event_doors_open_time" => "7:00 PM UTC".to_string(),
+        "event_id" | "organization_id" | "order_id" | "transfer_id" => Uuid::nil().to_string(),
+        v if v.ends_with("_link") || v.ends_with("_url") => "https://example.com".to_string(),
+        v => format!("Sample {}", v.replace('_', " ")),
+    }
+}
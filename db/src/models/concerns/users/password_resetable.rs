@@ -2,7 +2,7 @@ use chrono::{NaiveDateTime, Utc};
 use diesel;
 use diesel::expression::dsl;
 use diesel::prelude::*;
-use models::User;
+use models::{SessionRevocationReasons, User, UserSession};
 use schema::users;
 use utils::errors::{DatabaseError, ErrorCode};
 use utils::passwords::PasswordHash;
@@ -35,6 +35,9 @@ impl PasswordResetable for User {
             let hash = PasswordHash::generate(password, None);
             let now = Utc::now().naive_utc();
 
+            // Anyone holding a session from before the reset is signed out
+            UserSession::revoke_all_for_user(user.id, SessionRevocationReasons::PasswordReset, Some(user.id), conn)?;
+
             DatabaseError::wrap(
                 ErrorCode::UpdateError,
                 "Could not save new password for user",
@@ -2,15 +2,26 @@ use chrono::{Duration, NaiveDateTime, Utc};
 use diesel;
 use diesel::expression::dsl;
 use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Double, Nullable, Text, Timestamp};
 use models::enums::*;
+use models::*;
 use schema::*;
 use serde_json;
 use utils::dates;
 use utils::dates::IntoDateBuilder;
 use utils::errors::*;
+use utils::pagination::*;
 use uuid::Uuid;
 
-#[derive(Clone, Debug, Serialize, PartialEq, Identifiable, Queryable, QueryableByName)]
+pub const DOMAIN_ACTION_PRIORITY_HIGH: i32 = 100;
+pub const DOMAIN_ACTION_PRIORITY_NORMAL: i32 = 50;
+pub const DOMAIN_ACTION_PRIORITY_LOW: i32 = 0;
+
+/// Actions that will not be attempted again unless they are requeued
+pub static DEAD_LETTER_DOMAIN_ACTION_STATUSES: &'static [DomainActionStatus] =
+    &[DomainActionStatus::Errored, DomainActionStatus::RetriesExceeded];
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Identifiable, Queryable, QueryableByName)]
 #[table_name = "domain_actions"]
 pub struct DomainAction {
     pub id: Uuid,
@@ -30,6 +41,27 @@ pub struct DomainAction {
     pub blocked_until: NaiveDateTime,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub priority: i32,
+}
+
+/// Queue depth and latency of a domain action type
+#[derive(Clone, Debug, Deserialize, PartialEq, QueryableByName, Serialize)]
+pub struct DomainActionQueueMetrics {
+    #[sql_type = "Text"]
+    pub domain_action_type: DomainActionTypes,
+    #[sql_type = "BigInt"]
+    pub pending_count: i64,
+    #[sql_type = "BigInt"]
+    pub due_count: i64,
+    #[sql_type = "BigInt"]
+    pub in_progress_count: i64,
+    #[sql_type = "BigInt"]
+    pub dead_letter_count: i64,
+    #[sql_type = "Nullable<Timestamp>"]
+    pub oldest_due_at: Option<NaiveDateTime>,
+    /// Average time between being scheduled and being picked up over the last hour
+    #[sql_type = "Nullable<Double>"]
+    pub average_start_latency_seconds: Option<f64>,
 }
 
 #[derive(AsChangeset, Deserialize)]
@@ -73,6 +105,7 @@ impl DomainAction {
             max_attempt_count: 3,
             status: DomainActionStatus::Pending,
             blocked_until: dates::now().add_seconds(-30).finish(),
+            priority: domain_action_type.default_priority(),
         }
     }
 
@@ -140,10 +173,64 @@ impl DomainAction {
         }
 
         query
+            .order_by((domain_actions::priority.desc(), domain_actions::scheduled_at.asc()))
             .load(conn)
             .to_db_error(ErrorCode::QueryError, "Error loading domain actions")
     }
 
+    /// Actions that errored or exceeded their retries, most recently failed first
+    pub fn find_dead_letters(
+        domain_action_type: Option<DomainActionTypes>,
+        page: u32,
+        limit: u32,
+        conn: &PgConnection,
+    ) -> Result<Payload<DomainAction>, DatabaseError> {
+        let mut query = domain_actions::table
+            .filter(domain_actions::status.eq_any(DEAD_LETTER_DOMAIN_ACTION_STATUSES.to_vec()))
+            .into_boxed();
+
+        if let Some(action_type) = domain_action_type {
+            query = query.filter(domain_actions::domain_action_type.eq(action_type));
+        }
+
+        let (actions, record_count): (Vec<DomainAction>, i64) = query
+            .order_by(domain_actions::updated_at.desc())
+            .paginate(page as i64)
+            .per_page(limit as i64)
+            .load_and_count_pages(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading dead letter domain actions")?;
+
+        Ok(Payload::from_data(actions, page, limit, Some(record_count as u64)))
+    }
+
+    pub fn queue_metrics(conn: &PgConnection) -> Result<Vec<DomainActionQueueMetrics>, DatabaseError> {
+        // Server time is used for the same reason as in `find_pending`
+        let query = r#"
+            SELECT domain_action_type,
+                COUNT(*) FILTER (WHERE status = 'Pending' AND expires_at > $1) AS pending_count,
+                COUNT(*) FILTER (
+                    WHERE status = 'Pending' AND expires_at > $1 AND scheduled_at <= $1 AND blocked_until <= $1
+                ) AS due_count,
+                COUNT(*) FILTER (WHERE status = 'Pending' AND blocked_until > $1) AS in_progress_count,
+                COUNT(*) FILTER (WHERE status IN ('Errored', 'RetriesExceeded')) AS dead_letter_count,
+                MIN(scheduled_at) FILTER (
+                    WHERE status = 'Pending' AND expires_at > $1 AND scheduled_at <= $1 AND blocked_until <= $1
+                ) AS oldest_due_at,
+                CAST(AVG(EXTRACT(EPOCH FROM last_attempted_at - scheduled_at)) FILTER (
+                    WHERE last_attempted_at >= $1 - INTERVAL '1 hour'
+                ) AS DOUBLE PRECISION) AS average_start_latency_seconds
+            FROM domain_actions
+            WHERE (status = 'Pending' AND expires_at > $1)
+            OR status IN ('Errored', 'RetriesExceeded')
+            OR last_attempted_at >= $1 - INTERVAL '1 hour'
+            GROUP BY domain_action_type
+            ORDER BY domain_action_type;"#;
+        diesel::sql_query(query)
+            .bind::<Timestamp, _>(Utc::now().naive_utc())
+            .get_results(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load domain action metrics")
+    }
+
     /// This method returns true if a pending/busy domain action
     /// exists for the given `domain_action_type`, `main_table` and `main_table_id`
     /// otherwise false.
@@ -184,6 +271,7 @@ impl DomainAction {
             .filter(domain_actions::blocked_until.le(dsl::now))
             .set((
                 domain_actions::blocked_until.eq(timeout),
+                domain_actions::last_attempted_at.eq(dsl::now.nullable()),
                 domain_actions::updated_at.eq(dsl::now),
             ))
             .get_result(conn)
@@ -196,6 +284,38 @@ impl DomainAction {
         }
     }
 
+    pub fn is_dead_letter(&self) -> bool {
+        DEAD_LETTER_DOMAIN_ACTION_STATUSES.contains(&self.status)
+    }
+
+    /// Gives a dead letter action a fresh set of attempts, starting now
+    pub fn requeue(&self, conn: &PgConnection) -> Result<DomainAction, DatabaseError> {
+        if !self.is_dead_letter() {
+            return DatabaseError::business_process_error("Only errored or failed domain actions can be requeued");
+        }
+
+        let now = dates::now();
+        diesel::update(self)
+            .set((
+                domain_actions::status.eq(DomainActionStatus::Pending),
+                domain_actions::attempt_count.eq(0),
+                domain_actions::scheduled_at.eq(now.clone().finish()),
+                domain_actions::expires_at.eq(now.clone().add_seconds(900).finish()),
+                domain_actions::blocked_until.eq(now.add_seconds(-30).finish()),
+                domain_actions::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not requeue Domain Action")
+    }
+
+    /// Cancels an action that is waiting to be processed or in the dead letter queue
+    pub fn cancel(&self, conn: &PgConnection) -> Result<DomainAction, DatabaseError> {
+        if self.status != DomainActionStatus::Pending && !self.is_dead_letter() {
+            return DatabaseError::business_process_error("Domain action has already completed");
+        }
+        self.set_cancelled(conn)
+    }
+
     pub fn set_cancelled(&self, conn: &PgConnection) -> Result<DomainAction, DatabaseError> {
         diesel::update(self)
             .set((
@@ -308,6 +428,7 @@ pub struct NewDomainAction {
     pub max_attempt_count: i64,
     pub status: DomainActionStatus,
     pub blocked_until: NaiveDateTime,
+    pub priority: i32,
 }
 
 impl NewDomainAction {
@@ -325,3 +446,32 @@ impl NewDomainAction {
         self.blocked_until = self.blocked_until + diff;
     }
 }
+
+impl DomainActionTypes {
+    /// Customer facing actions are processed ahead of bulk jobs when the queue is backed up
+    pub fn default_priority(&self) -> i32 {
+        use self::DomainActionTypes::*;
+        match self {
+            Communication
+            | ExpireTransfer
+            | PaymentProviderIPN
+            | ProcessPaymentProviderEvent
+            | ReleaseHoldInventory
+            | SendPurchaseCompletedCommunication => DOMAIN_ACTION_PRIORITY_HIGH,
+            ChargePaymentPlanInstallment
+            | DeliverWebhook
+            | ProcessTransferDrip
+            | ProcessWaitlist
+            | ReturnStoredValue => DOMAIN_ACTION_PRIORITY_NORMAL,
+            BroadcastPushNotification
+            | FinalizeSettlements
+            | ProcessSettlementReport
+            | RegenerateDripActions
+            | RetargetAbandonedOrders
+            | SendAutomaticReportEmails
+            | SendPaymentMethodExpiryNotifications
+            | SubmitSitemapToSearchEngines
+            | UpdateGenres => DOMAIN_ACTION_PRIORITY_LOW,
+        }
+    }
+}
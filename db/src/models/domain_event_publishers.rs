@@ -6,9 +6,12 @@ use models::*;
 use schema::{domain_event_published, domain_event_publishers};
 use serde_json::Value;
 use std::hash::{Hash, Hasher};
+use utils::encryption::*;
 use utils::errors::*;
+use utils::webhook_urls;
 use uuid::Uuid;
 use validator::Validate;
+use validators::{self, *};
 
 pub static SUPPORTED_DOMAIN_EVENT_TYPES_FOR_PUBLISHING: &'static [DomainEventTypes] = &[
     DomainEventTypes::TransferTicketStarted,
@@ -23,7 +26,7 @@ pub static SUPPORTED_DOMAIN_EVENT_TYPES_FOR_PUBLISHING: &'static [DomainEventTyp
     DomainEventTypes::PushNotificationTokenCreated,
 ];
 
-#[derive(Clone, Debug, Serialize, Identifiable, Queryable, QueryableByName)]
+#[derive(Clone, Debug, Deserialize, Serialize, Identifiable, Queryable, QueryableByName)]
 #[table_name = "domain_event_publishers"]
 pub struct DomainEventPublisher {
     pub id: Uuid,
@@ -38,6 +41,8 @@ pub struct DomainEventPublisher {
     pub adapter: Option<WebhookAdapters>,
     pub adapter_config: Option<Value>,
     pub blocked_until: NaiveDateTime,
+    #[serde(skip_serializing)]
+    pub encrypted_signing_secret: Option<String>,
 }
 
 impl Eq for DomainEventPublisher {}
@@ -60,6 +65,7 @@ pub struct DomainEventPublisherEditableAttributes {
     #[validate(url(message = "Webhook URL is invalid"))]
     pub webhook_url: Option<String>,
     pub import_historic_events: Option<bool>,
+    pub event_types: Option<Vec<DomainEventTypes>>,
 }
 
 impl DomainEventPublisher {
@@ -71,6 +77,74 @@ impl DomainEventPublisher {
             .to_db_error(ErrorCode::QueryError, "Could not load Domain Event Publishers")
     }
 
+    /// Webhooks registered by the organization itself, as opposed to publishers set up for
+    /// integrations by an administrator
+    pub fn find_for_organization(
+        organization_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<DomainEventPublisher>, DatabaseError> {
+        domain_event_publishers::table
+            .filter(domain_event_publishers::organization_id.eq(organization_id))
+            .filter(domain_event_publishers::encrypted_signing_secret.is_not_null())
+            .filter(domain_event_publishers::deleted_at.is_null())
+            .order_by(domain_event_publishers::created_at.asc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load webhooks")
+    }
+
+    pub fn is_signed(&self) -> bool {
+        self.encrypted_signing_secret.is_some()
+    }
+
+    pub fn signing_secret(&self, encryption_key: &str) -> Result<Option<String>, DatabaseError> {
+        match self.encrypted_signing_secret {
+            Some(ref encrypted_signing_secret) => Ok(Some(decrypt(encrypted_signing_secret, encryption_key)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn validate_signed_publisher(
+        webhook_url: Option<&String>,
+        event_types: Option<&Vec<DomainEventTypes>>,
+    ) -> Result<(), DatabaseError> {
+        let mut validation_errors = Ok(());
+        if let Some(webhook_url) = webhook_url {
+            if let Err(error) = webhook_urls::check(webhook_url) {
+                validation_errors = validators::append_validation_error(
+                    validation_errors,
+                    "webhook_url",
+                    Err(create_validation_error(error.code(), error.message())),
+                );
+            }
+        }
+        if let Some(event_types) = event_types {
+            if event_types.is_empty() {
+                validation_errors = validators::append_validation_error(
+                    validation_errors,
+                    "event_types",
+                    Err(create_validation_error(
+                        "event_types_empty",
+                        "At least one event type is required",
+                    )),
+                );
+            } else if event_types
+                .iter()
+                .any(|event_type| !SUPPORTED_DOMAIN_EVENT_TYPES_FOR_PUBLISHING.contains(event_type))
+            {
+                validation_errors = validators::append_validation_error(
+                    validation_errors,
+                    "event_types",
+                    Err(create_validation_error(
+                        "event_types_unsupported",
+                        "Event type is not available for webhooks",
+                    )),
+                );
+            }
+        }
+
+        Ok(validation_errors?)
+    }
+
     pub fn delete(self, conn: &PgConnection) -> Result<(), DatabaseError> {
         diesel::update(&self)
             .set((
@@ -80,6 +154,18 @@ impl DomainEventPublisher {
             .execute(conn)
             .to_db_error(ErrorCode::UpdateError, "Could not delete domain event publisher")?;
 
+        if self.is_signed() {
+            DomainEvent::create(
+                DomainEventTypes::WebhookDeleted,
+                "Webhook deleted".to_string(),
+                Tables::DomainEventPublishers,
+                Some(self.id),
+                None,
+                Some(json!({ "organization_id": self.organization_id, "webhook_url": self.webhook_url })),
+            )
+            .commit(conn)?;
+        }
+
         Ok(())
     }
 
@@ -144,9 +230,29 @@ impl DomainEventPublisher {
             webhook_url,
             adapter: None,
             adapter_config: None,
+            encrypted_signing_secret: None,
         }
     }
 
+    /// Webhook registered by an organization. Deliveries are signed with the secret, which is only
+    /// shown to the organization when the webhook is created.
+    pub fn create_for_organization(
+        organization_id: Uuid,
+        event_types: Vec<DomainEventTypes>,
+        webhook_url: String,
+        signing_secret: &str,
+        encryption_key: &str,
+    ) -> Result<NewDomainEventPublisher, DatabaseError> {
+        Ok(NewDomainEventPublisher {
+            organization_id: Some(organization_id),
+            event_types,
+            webhook_url,
+            adapter: None,
+            adapter_config: None,
+            encrypted_signing_secret: Some(encrypt(signing_secret, encryption_key)?),
+        })
+    }
+
     pub fn create_with_adapter(
         organization_id: Option<Uuid>,
         event_types: Vec<DomainEventTypes>,
@@ -159,6 +265,7 @@ impl DomainEventPublisher {
             webhook_url: "".to_string(),
             adapter: Some(adapter),
             adapter_config: Some(adapter_config),
+            encrypted_signing_secret: None,
         }
     }
 
@@ -177,10 +284,31 @@ impl DomainEventPublisher {
         attributes: &DomainEventPublisherEditableAttributes,
         conn: &PgConnection,
     ) -> Result<DomainEventPublisher, DatabaseError> {
-        diesel::update(self)
+        if self.is_signed() {
+            DomainEventPublisher::validate_signed_publisher(
+                attributes.webhook_url.as_ref(),
+                attributes.event_types.as_ref(),
+            )?;
+        }
+
+        let publisher: DomainEventPublisher = diesel::update(self)
             .set((attributes, domain_event_publishers::updated_at.eq(dsl::now)))
             .get_result(conn)
-            .to_db_error(ErrorCode::UpdateError, "Could not update domain event publisher")
+            .to_db_error(ErrorCode::UpdateError, "Could not update domain event publisher")?;
+
+        if self.is_signed() {
+            DomainEvent::create(
+                DomainEventTypes::WebhookUpdated,
+                "Webhook updated".to_string(),
+                Tables::DomainEventPublishers,
+                Some(self.id),
+                None,
+                Some(json!({ "webhook_url": publisher.webhook_url, "event_types": publisher.event_types })),
+            )
+            .commit(conn)?;
+        }
+
+        Ok(publisher)
     }
 
     pub fn acquire_lock(&mut self, timeout: i64, conn: &PgConnection) -> Result<(), DatabaseError> {
@@ -262,13 +390,33 @@ pub struct NewDomainEventPublisher {
     pub webhook_url: String,
     pub adapter: Option<WebhookAdapters>,
     pub adapter_config: Option<Value>,
+    pub encrypted_signing_secret: Option<String>,
 }
 
 impl NewDomainEventPublisher {
     pub fn commit(self, conn: &PgConnection) -> Result<DomainEventPublisher, DatabaseError> {
-        diesel::insert_into(domain_event_publishers::table)
+        let is_signed = self.encrypted_signing_secret.is_some();
+        if is_signed {
+            DomainEventPublisher::validate_signed_publisher(Some(&self.webhook_url), Some(&self.event_types))?;
+        }
+
+        let publisher: DomainEventPublisher = diesel::insert_into(domain_event_publishers::table)
             .values(self)
             .get_result(conn)
-            .to_db_error(ErrorCode::InsertError, "Could not insert domain event publisher")
+            .to_db_error(ErrorCode::InsertError, "Could not insert domain event publisher")?;
+
+        if is_signed {
+            DomainEvent::create(
+                DomainEventTypes::WebhookCreated,
+                "Webhook created".to_string(),
+                Tables::DomainEventPublishers,
+                Some(publisher.id),
+                None,
+                Some(json!({ "webhook_url": publisher.webhook_url, "event_types": publisher.event_types })),
+            )
+            .commit(conn)?;
+        }
+
+        Ok(publisher)
     }
 }
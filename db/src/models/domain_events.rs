@@ -1,6 +1,8 @@
 use chrono::prelude::*;
+use chrono::Duration;
 use diesel;
 use diesel::prelude::*;
+use diesel::sql_types::{Array, Uuid as dUuid};
 use log::Level::Info;
 use models::*;
 use schema::domain_events;
@@ -9,6 +11,11 @@ use std::cmp::Ordering;
 use utils::errors::*;
 use uuid::Uuid;
 
+/// Events are only streamed once they are this old. Sequence numbers are assigned on insert but
+/// transactions can commit out of order, so a cursor that ran ahead of a slower transaction would
+/// otherwise skip its events.
+pub const DOMAIN_EVENT_STREAM_SETTLE_SECONDS: i64 = 5;
+
 #[derive(Clone, Debug, PartialEq, Identifiable, Queryable, Serialize, Deserialize)]
 pub struct DomainEvent {
     pub id: Uuid,
@@ -64,6 +71,7 @@ impl DomainEvent {
             main_id,
             user_id,
             created_at: None,
+            organization_id: None,
         }
     }
 
@@ -78,6 +86,95 @@ impl DomainEvent {
             .to_db_error(ErrorCode::QueryError, "Could not load domain events after seq")
     }
 
+    /// Reads an organization's events in sequence order. Unlike `find_after_seq` this does not lock
+    /// the rows so it can be used for replay without interfering with the publisher.
+    pub fn find_for_organization_after_seq(
+        organization_id: Uuid,
+        after_seq: i64,
+        filter: &DomainEventStreamFilter,
+        limit: u32,
+        conn: &PgConnection,
+    ) -> Result<DomainEventStreamPage, DatabaseError> {
+        let settled_before = Utc::now().naive_utc() - Duration::seconds(DOMAIN_EVENT_STREAM_SETTLE_SECONDS);
+        let mut query = domain_events::table
+            .filter(domain_events::organization_id.eq(organization_id))
+            .filter(domain_events::seq.gt(after_seq))
+            .filter(domain_events::created_at.le(settled_before))
+            .into_boxed();
+
+        if let Some(ref event_types) = filter.event_types {
+            query = query.filter(domain_events::event_type.eq_any(event_types.clone()));
+        }
+        if let Some(main_table) = filter.main_table {
+            query = query.filter(domain_events::main_table.eq(main_table));
+        }
+        if let Some(start_utc) = filter.start_utc {
+            query = query.filter(domain_events::created_at.ge(start_utc));
+        }
+        if let Some(end_utc) = filter.end_utc {
+            query = query.filter(domain_events::created_at.le(end_utc));
+        }
+
+        // One extra row is loaded to tell whether another page follows
+        let mut data: Vec<DomainEvent> = query
+            .order_by(domain_events::seq.asc())
+            .limit(limit as i64 + 1)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load domain events for organization")?;
+
+        let has_more = data.len() > limit as usize;
+        data.truncate(limit as usize);
+        let next_seq = data.last().map(|e| e.seq).unwrap_or(after_seq);
+
+        Ok(DomainEventStreamPage {
+            data,
+            next_seq,
+            has_more,
+        })
+    }
+
+    /// Sets the organization of the next `batch_size` events after `after_seq` that were recorded
+    /// before events were assigned an organization on insert. Returns the last seq looked at to pass
+    /// back as `after_seq`, or `None` once there is nothing left so a live database can be backfilled
+    /// with short statements.
+    pub fn backpopulate_organization_ids(
+        after_seq: i64,
+        batch_size: i64,
+        conn: &PgConnection,
+    ) -> Result<Option<i64>, DatabaseError> {
+        let batch: Vec<(Uuid, i64)> = domain_events::table
+            .filter(domain_events::seq.gt(after_seq))
+            .filter(domain_events::organization_id.is_null())
+            .filter(domain_events::main_id.is_not_null())
+            .select((domain_events::id, domain_events::seq))
+            .order_by(domain_events::seq.asc())
+            .limit(batch_size)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load domain events to backpopulate")?;
+        let last_seq = match batch.last() {
+            Some((_, seq)) => *seq,
+            None => return Ok(None),
+        };
+        let ids: Vec<Uuid> = batch.into_iter().map(|(id, _)| id).collect();
+
+        diesel::sql_query(
+            r#"
+            UPDATE domain_events
+            SET organization_id = domain_event_organization_id(main_table, main_id)
+            WHERE id = ANY($1)
+              AND organization_id IS NULL;
+            "#,
+        )
+        .bind::<Array<dUuid>, _>(ids)
+        .execute(conn)
+        .to_db_error(
+            ErrorCode::UpdateError,
+            "Could not backpopulate domain event organizations",
+        )?;
+
+        Ok(Some(last_seq))
+    }
+
     pub fn find(
         main_table: Tables,
         main_id: Option<Uuid>,
@@ -159,6 +256,22 @@ impl DomainEvent {
     }
 }
 
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct DomainEventStreamFilter {
+    pub event_types: Option<Vec<DomainEventTypes>>,
+    pub main_table: Option<Tables>,
+    pub start_utc: Option<NaiveDateTime>,
+    pub end_utc: Option<NaiveDateTime>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct DomainEventStreamPage {
+    pub data: Vec<DomainEvent>,
+    /// Pass back as `after_seq` to continue from the last returned event
+    pub next_seq: i64,
+    pub has_more: bool,
+}
+
 #[derive(Insertable, Clone)]
 #[table_name = "domain_events"]
 pub struct NewDomainEvent {
@@ -169,9 +282,16 @@ pub struct NewDomainEvent {
     pub main_id: Option<Uuid>,
     pub user_id: Option<Uuid>,
     pub created_at: Option<NaiveDateTime>,
+    /// Resolved from the main record on insert when not set
+    pub organization_id: Option<Uuid>,
 }
 
 impl NewDomainEvent {
+    pub fn with_organization_id(mut self, organization_id: Option<Uuid>) -> NewDomainEvent {
+        self.organization_id = organization_id;
+        self
+    }
+
     pub fn commit(self, conn: &PgConnection) -> Result<DomainEvent, DatabaseError> {
         let result: DomainEvent = diesel::insert_into(domain_events::table)
             .values(&self)
@@ -100,13 +100,43 @@ define_enum! { CartItemStatus [CodeExpired, HoldExpired, TicketNullified, Ticket
 define_enum! { CheckInSource [GuestList, Scanned, LootBox] }
 define_enum! { CodeTypes [Access, Discount] }
 define_enum! { CommunicationChannelType [Email, Sms, Push, Webhook]}
+define_enum! { CommunicationTemplateNames [
+    OrgInvite,
+    PasswordReset,
+    PaymentMethodExpiring,
+    PaymentPlanCancelled,
+    PaymentPlanInstallmentFailed,
+    PurchaseCompleted,
+    Refund,
+    TransferCancelled,
+    TransferCancelledReceipt,
+    TransferDripReminder,
+    TransferExpiryReminder,
+    TransferReturnedReceipt,
+    TransferSentReceipt,
+    TransferTickets,
+    UserInvite,
+    UserRegistered,
+    UserRegisteredMagicLink,
+    WaitlistOffer
+]}
 define_enum! { CommunicationType [EmailTemplate, Sms, Push, Webhook]}
 define_enum! { DomainEventTypes [
+    AccountCreditIssued,
     AnnouncementCreated,
     AnnouncementDeleted,
+    ApiKeyCreated,
+    ApiKeyRevoked,
+    ApiKeyRotated,
+    BundleCreated,
+    BundleDeleted,
+    BundleUpdated,
     CodeCreated,
     CodeDeleted,
     CodeUpdated,
+    CommunicationTemplateDeleted,
+    CommunicationTemplateRolledBack,
+    CommunicationTemplateUpdated,
     EventArtistCreated,
     EventArtistAdded,
     EventCancelled,
@@ -117,12 +147,17 @@ define_enum! { DomainEventTypes [
     EventPublished,
     EventReportSubscriberCreated,
     EventReportSubscriberDeleted,
+    EventSeriesCreated,
+    EventSeriesOccurrencesGenerated,
+    EventSeriesUpdated,
     EventUpdated,
     EventUnpublished,
     ExternalLoginCreated,
     ExternalLoginDeleted,
     FeeScheduleCreated,
     GenresUpdated,
+    GiftCardCreated,
+    GiftCardDeactivated,
     HoldAutomaticallyReleased,
     HoldCreated,
     HoldDeleted,
@@ -136,21 +171,46 @@ define_enum! { DomainEventTypes [
     OrderStatusUpdated,
     OrderUpdated,
     OrganizationCreated,
+    OrganizationRoleCreated,
+    OrganizationRoleDeleted,
+    OrganizationRoleUpdated,
+    OrganizationSsoConfigurationCreated,
+    OrganizationSsoConfigurationUpdated,
+    OrganizationUserRolesUpdated,
+    OrganizationUserSsoProvisioned,
     NoteCreated,
     NoteDeleted,
     PaymentCancelled,
     PaymentCreated,
     PaymentCompleted,
+    PaymentDisputeClosed,
+    PaymentDisputeCreated,
     PaymentRefund,
     PaymentProviderIPN,
     PaymentMethodCreated,
+    PaymentMethodDefaultChanged,
+    PaymentMethodDeleted,
+    PaymentMethodExpiryNotificationSent,
     PaymentMethodUpdated,
+    PaymentPlanCancelled,
+    PaymentPlanCompleted,
+    PaymentPlanCreated,
+    PaymentPlanInstallmentFailed,
+    PaymentPlanInstallmentPaid,
     PaymentUpdated,
     UserCreated,
     UserDisabled,
     UserLogin,
+    UserLoginLocked,
     UserRegistration,
+    UserSessionRevoked,
+    UserTwoFactorDisabled,
+    UserTwoFactorEnabled,
+    UserTwoFactorRecoveryCodesRegenerated,
     UserUpdated,
+    ListingCancelled,
+    ListingPublished,
+    ListingSold,
     LostPassword,
     PurchaseCompleted,
     PushNotificationTokenCreated,
@@ -159,6 +219,8 @@ define_enum! { DomainEventTypes [
     TransferTicketDripDestinationSent,
     TransferTicketCancelled,
     TransferTicketCompleted,
+    TransferTicketDeclined,
+    TransferTicketExpired,
     TransferTicketStarted,
     TrackingDataUpdated,
     TemporaryUserCreated,
@@ -178,20 +240,34 @@ define_enum! { DomainEventTypes [
     TicketTypeCreated,
     TicketTypeSalesStarted,
     TicketTypeSoldOut,
-    TicketTypeUpdated
+    TicketTypeUpdated,
+    WaitlistEntryCancelled,
+    WaitlistEntryCreated,
+    WaitlistOfferSent,
+    WebhookCreated,
+    WebhookDeleted,
+    WebhookDeliveryReplayed,
+    WebhookUpdated
 ]}
 define_enum! { DomainActionTypes [
     BroadcastPushNotification,
+    ChargePaymentPlanInstallment,
     // Email/SMS/Push Communication
     Communication,
+    DeliverWebhook,
+    ExpireTransfer,
     FinalizeSettlements,
     PaymentProviderIPN,
+    ProcessPaymentProviderEvent,
     ProcessSettlementReport,
     ProcessTransferDrip,
+    ProcessWaitlist,
     RegenerateDripActions,
     ReleaseHoldInventory,
     RetargetAbandonedOrders,
+    ReturnStoredValue,
     SendAutomaticReportEmails,
+    SendPaymentMethodExpiryNotifications,
     SendPurchaseCompletedCommunication,
     SubmitSitemapToSearchEngines,
     UpdateGenres
@@ -210,39 +286,47 @@ define_enum! { ExternalPaymentType [Cash, CreditCard, Voucher]}
 define_enum! { FanSortField [FirstName, LastName, Email, Phone, OrganizationId, UserCreated, Orders, FirstOrder, LastOrder, Revenue, FirstInteracted, LastInteracted] }
 define_enum! { HistoryType [Purchase]}
 define_enum! { HoldTypes [Discount, Comp] }
-define_enum! { ListingStatus [Pending, Published] }
+define_enum! { ListingStatus [Pending, Published, Sold, Cancelled] }
 define_enum! { MarketplaceAccountStatus [ Pending, Linked ]}
-define_enum! { OrderStatus [Cancelled, Draft, Paid, PendingPayment] }
-define_enum! { OrderItemTypes [Tickets, PerUnitFees, EventFees, Discount, CreditCardFees]}
+define_enum! { OrderStatus [Cancelled, Draft, Paid, PartiallyPaid, PendingPayment] }
+define_enum! { OrderItemTypes [Tickets, PerUnitFees, EventFees, Discount, CreditCardFees, Tax, ResaleTickets]}
 define_enum! { OrderTypes [Cart, BackOffice] }
-define_enum! { PaymentMethods [CreditCard, External, Free, Provider] }
-define_enum! { PaymentProviders [External, Globee, Free, Stripe] }
+define_enum! { PaymentMethods [AccountCredit, CreditCard, External, Free, GiftCard, Provider] }
+define_enum! { PaymentProviders [External, Globee, Free, Stripe, Adyen, Mock] }
 define_enum! { PaymentStatus [Authorized, Completed, Requested, Refunded, Unpaid, PendingConfirmation, Cancelled, Draft, Unknown, PendingIpn] }
+define_enum! { OfflineRedemptionStatus [Redeemed, AlreadyRedeemed, TransferInProcess, AwaitingPayment, Invalid] }
+define_enum! { PaymentPlanInstallmentStatus [Cancelled, Paid, Pending] }
+define_enum! { PaymentPlanStatus [Active, Cancelled, Completed] }
 define_enum! { PastOrUpcoming [Past,Upcoming]}
 define_enum! { Platforms [Web, App, BoxOffice]}
+define_enum! { RecurrenceTypes [Weekly, Monthly, Custom]}
 define_enum! { ReportTypes [TicketCounts]}
 define_enum! { Roles [Admin, DoorPerson, OrgAdmin, OrgBoxOffice, OrgMember, OrgOwner, PrismIntegration, Promoter, PromoterReadOnly, User, Super] }
+define_enum! { SessionRevocationReasons [SignedOut, PasswordReset, UserDisabled, RefreshTokenReused] }
 define_enum! { SettlementStatus[PendingSettlement, FinalizedSettlement] }
 define_enum! { SettlementTypes [Rolling, PostEvent]}
 define_enum! { SettlementAdjustmentTypes [ManualCredit, ManualDeduction, Chargeback]}
-define_enum! { SettlementEntryTypes [EventFees, TicketType]}
-define_enum! { SlugTypes[ Event, Organization, Venue, City, Genre, CityGenre ] }
+define_enum! { SettlementEntryTypes [EventFees, GiftCardRedemptions, ResaleFees, TicketType]}
+define_enum! { SlugTypes[ Event, Organization, Venue, City, Genre, CityGenre, EventSeries ] }
 define_enum! { SortingDir[ Asc, Desc ] }
 define_enum! { SourceOrDestination [Destination,Source]}
+define_enum! { StoredValueLedgerEntryTypes [Deactivated, Issued, Redeemed, Refunded, Returned] }
 define_enum! { Tables [
-    Announcements, Artists, Broadcasts, Codes, DomainEventPublishers, Events, EventArtists, EventReportSubscribers, ExternalLogins, FeeSchedules,
-    Holds, Orders, Organizations, Notes, Payments, PaymentMethods, PushNotificationTokens, TemporaryUsers, TicketInstances, TicketTypes,
-    TicketPricing, Transfers, Users, Venues, Genres
+    AccountCredits, Announcements, ApiKeys, Artists, Broadcasts, Bundles, Codes, CommunicationTemplates, DomainEventPublishers, Events, EventArtists, EventSeries, EventReportSubscribers, ExternalLogins, FeeSchedules,
+    GiftCards, Holds, Listings, Orders, Organizations, OrganizationRoles, OrganizationSsoConfigurations, Notes, Payments, PaymentMethods, PaymentPlans, PaymentPlanInstallments, PaymentProviderEvents, PushNotificationTokens, TemporaryUsers, TicketInstances, TicketTypes,
+    TicketPricing, Transfers, Users, UserSessions, Venues, Genres, WaitlistEntries, WebhookDeliveries
 ] }
 define_enum! { TicketInstanceStatus [Available, Reserved, Purchased, Redeemed, Nullified]}
 define_enum! { TicketPricingStatus [Published, Deleted, Default] }
 define_enum! { TicketTypeEndDateType [DoorTime, EventEnd, EventStart, Manual] }
+define_enum! { WaitlistEntryStatus [Waiting, Offered, Claimed, Expired, Cancelled] }
 define_enum! { TicketTypeStatus [NoActivePricing, Published, SoldOut, OnSaleSoon, SaleEnded, Cancelled, Deleted] }
 define_enum! { TicketTypeType [ Token, LootBox ]}
 define_enum! { TicketTypeVisibility [ Always, Hidden, WhenAvailable ]}
 define_enum! { TransferMessageType [Email, Phone] }
-define_enum! { TransferStatus [Pending, Cancelled, Completed, EventEnded] }
+define_enum! { TransferStatus [Pending, Cancelled, Completed, EventEnded, Declined, Expired] }
 define_enum! { WebhookAdapters [CustomerIo]}
+define_enum! { WebhookDeliveryStatus [Failed, Pending, Succeeded] }
 
 impl Roles {
     pub fn get_event_limited_roles() -> Vec<Roles> {
@@ -0,0 +1,523 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use prelude::*;
+use schema::{event_series, events};
+use serde_with::rust::double_option;
+use std::collections::HashMap;
+use utils::errors::ErrorCode;
+use uuid::Uuid;
+use validator::ValidationErrors;
+use validators;
+
+/// Upper bound on the number of occurrences a single series can generate
+pub const MAX_EVENT_SERIES_OCCURRENCES: usize = 366;
+
+#[derive(Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[table_name = "event_series"]
+pub struct EventSeries {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub template_event_id: Uuid,
+    pub name: String,
+    pub recurrence_type: RecurrenceTypes,
+    pub recurrence_interval: i32,
+    pub recurrence_until: Option<NaiveDateTime>,
+    pub custom_dates: Vec<NaiveDateTime>,
+    pub slug_id: Option<Uuid>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Deserialize, Insertable, Serialize)]
+#[table_name = "event_series"]
+pub struct NewEventSeries {
+    pub organization_id: Uuid,
+    pub template_event_id: Uuid,
+    pub name: String,
+    pub recurrence_type: RecurrenceTypes,
+    pub recurrence_interval: i32,
+    pub recurrence_until: Option<NaiveDateTime>,
+    pub custom_dates: Vec<NaiveDateTime>,
+}
+
+#[derive(AsChangeset, Clone, Default, Deserialize, Serialize)]
+#[table_name = "event_series"]
+pub struct EventSeriesEditableAttributes {
+    pub name: Option<String>,
+    pub recurrence_type: Option<RecurrenceTypes>,
+    pub recurrence_interval: Option<i32>,
+    #[serde(default, deserialize_with = "double_option::deserialize")]
+    pub recurrence_until: Option<Option<NaiveDateTime>>,
+    pub custom_dates: Option<Vec<NaiveDateTime>>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct DisplayEventSeries {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub name: String,
+    pub slug: Option<String>,
+    pub recurrence_type: RecurrenceTypes,
+    pub recurrence_interval: i32,
+    pub recurrence_until: Option<NaiveDateTime>,
+    pub upcoming_dates: Vec<EventSeriesOccurrence>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct EventSeriesOccurrence {
+    pub event_id: Uuid,
+    pub name: String,
+    pub slug: Option<String>,
+    pub event_start: Option<NaiveDateTime>,
+    pub door_time: Option<NaiveDateTime>,
+    pub event_end: Option<NaiveDateTime>,
+}
+
+impl EventSeries {
+    pub fn create(
+        organization_id: Uuid,
+        template_event_id: Uuid,
+        name: String,
+        recurrence_type: RecurrenceTypes,
+        recurrence_interval: i32,
+        recurrence_until: Option<NaiveDateTime>,
+        custom_dates: Vec<NaiveDateTime>,
+    ) -> NewEventSeries {
+        NewEventSeries {
+            organization_id,
+            template_event_id,
+            name,
+            recurrence_type,
+            recurrence_interval,
+            recurrence_until,
+            custom_dates,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<EventSeries, DatabaseError> {
+        event_series::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find event series")
+    }
+
+    pub fn find_for_organization(
+        organization_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<EventSeries>, DatabaseError> {
+        event_series::table
+            .filter(event_series::organization_id.eq(organization_id))
+            .order_by(event_series::name.asc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load event series for organization")
+    }
+
+    pub fn organization(&self, conn: &PgConnection) -> Result<Organization, DatabaseError> {
+        Organization::find(self.organization_id, conn)
+    }
+
+    pub fn template_event(&self, conn: &PgConnection) -> Result<Event, DatabaseError> {
+        Event::find(self.template_event_id, conn)
+    }
+
+    /// All occurrences of the series that have not been deleted, including the template event
+    pub fn events(&self, conn: &PgConnection) -> Result<Vec<Event>, DatabaseError> {
+        events::table
+            .filter(events::event_series_id.eq(self.id))
+            .filter(events::deleted_at.is_null())
+            .order_by(events::event_start.asc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load events for series")
+    }
+
+    /// Published occurrences of the series which have not yet started
+    pub fn upcoming_events(&self, conn: &PgConnection) -> Result<Vec<Event>, DatabaseError> {
+        events::table
+            .filter(events::event_series_id.eq(self.id))
+            .filter(events::deleted_at.is_null())
+            .filter(events::cancelled_at.is_null())
+            .filter(events::status.eq(EventStatus::Published))
+            .filter(events::event_start.gt(dsl::now))
+            .order_by(events::event_start.asc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load upcoming events for series")
+    }
+
+    /// Start dates for each occurrence of the series following the template event's start.
+    /// Monthly occurrences fall on the same day of the month as the template, skipping months
+    /// which do not contain that day.
+    pub fn occurrence_dates(
+        recurrence_type: RecurrenceTypes,
+        recurrence_interval: i32,
+        recurrence_until: Option<NaiveDateTime>,
+        custom_dates: &[NaiveDateTime],
+        template_start: NaiveDateTime,
+    ) -> Vec<NaiveDateTime> {
+        let mut dates = vec![];
+        let interval = recurrence_interval.max(1);
+        match recurrence_type {
+            RecurrenceTypes::Custom => {
+                dates = custom_dates.iter().filter(|d| **d != template_start).cloned().collect();
+                dates.sort();
+                dates.dedup();
+            }
+            RecurrenceTypes::Weekly => {
+                if let Some(until) = recurrence_until {
+                    let mut next = template_start + Duration::weeks(interval as i64);
+                    // Validation rejects series exceeding the limit so there is no need to continue past it
+                    while next <= until && dates.len() <= MAX_EVENT_SERIES_OCCURRENCES {
+                        dates.push(next);
+                        next = next + Duration::weeks(interval as i64);
+                    }
+                }
+            }
+            RecurrenceTypes::Monthly => {
+                if let Some(until) = recurrence_until {
+                    let mut months = interval as u32;
+                    while dates.len() <= MAX_EVENT_SERIES_OCCURRENCES {
+                        let first_of_month = match template_start
+                            .with_day(1)
+                            .and_then(|first_of_month| EventSeries::add_months(first_of_month, months))
+                        {
+                            Some(first_of_month) => first_of_month,
+                            None => break,
+                        };
+                        if first_of_month > until {
+                            break;
+                        }
+                        if let Some(next) = EventSeries::add_months(template_start, months) {
+                            if next <= until {
+                                dates.push(next);
+                            }
+                        }
+                        months += interval as u32;
+                    }
+                }
+            }
+        }
+
+        dates
+    }
+
+    fn add_months(date: NaiveDateTime, months: u32) -> Option<NaiveDateTime> {
+        let total_months = date.month0() + months;
+        let year = date.year() + (total_months / 12) as i32;
+        let month = total_months % 12 + 1;
+        NaiveDate::from_ymd_opt(year, month, date.day()).map(|d| d.and_time(date.time()))
+    }
+
+    fn validate_recurrence(
+        recurrence_type: RecurrenceTypes,
+        recurrence_interval: i32,
+        recurrence_until: Option<NaiveDateTime>,
+        custom_dates: &[NaiveDateTime],
+        template_start: NaiveDateTime,
+    ) -> Result<(), DatabaseError> {
+        let mut validation_errors: Result<(), ValidationErrors> = validators::append_validation_error(
+            Ok(()),
+            "recurrence_interval",
+            validators::validate_greater_than_or_equal(
+                recurrence_interval,
+                1,
+                "recurrence_interval_must_be_positive",
+                "Recurrence interval must be at least 1",
+            ),
+        );
+
+        match recurrence_type {
+            RecurrenceTypes::Custom => {
+                if custom_dates.is_empty() {
+                    validation_errors = validators::append_validation_error(
+                        validation_errors,
+                        "custom_dates",
+                        Err(validators::create_validation_error(
+                            "required",
+                            "Custom series require at least one date",
+                        )),
+                    );
+                }
+            }
+            RecurrenceTypes::Weekly | RecurrenceTypes::Monthly => match recurrence_until {
+                Some(recurrence_until) => {
+                    if recurrence_until <= template_start {
+                        validation_errors = validators::append_validation_error(
+                            validation_errors,
+                            "recurrence_until",
+                            Err(validators::create_validation_error(
+                                "recurrence_until_before_template_start",
+                                "Recurrence end date must be after the template event start",
+                            )),
+                        );
+                    }
+                }
+                None => {
+                    validation_errors = validators::append_validation_error(
+                        validation_errors,
+                        "recurrence_until",
+                        Err(validators::create_validation_error(
+                            "required",
+                            "Recurrence end date is required for weekly and monthly series",
+                        )),
+                    );
+                }
+            },
+        }
+
+        if EventSeries::occurrence_dates(
+            recurrence_type,
+            recurrence_interval,
+            recurrence_until,
+            custom_dates,
+            template_start,
+        )
+        .len()
+            > MAX_EVENT_SERIES_OCCURRENCES
+        {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "recurrence_until",
+                Err(validators::create_validation_error(
+                    "too_many_occurrences",
+                    "Event series cannot contain more than 366 occurrences",
+                )),
+            );
+        }
+
+        Ok(validation_errors?)
+    }
+
+    pub fn update(
+        &self,
+        attributes: EventSeriesEditableAttributes,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<EventSeries, DatabaseError> {
+        let template_start = self.template_start(conn)?;
+        EventSeries::validate_recurrence(
+            attributes.recurrence_type.unwrap_or(self.recurrence_type),
+            attributes.recurrence_interval.unwrap_or(self.recurrence_interval),
+            attributes.recurrence_until.unwrap_or(self.recurrence_until),
+            attributes.custom_dates.as_ref().unwrap_or(&self.custom_dates),
+            template_start,
+        )?;
+
+        let result: EventSeries = diesel::update(self)
+            .set((&attributes, event_series::updated_at.eq(dsl::now)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update event series")?;
+
+        DomainEvent::create(
+            DomainEventTypes::EventSeriesUpdated,
+            format!("Event series '{}' updated", &result.name),
+            Tables::EventSeries,
+            Some(self.id),
+            current_user_id,
+            Some(json!(attributes)),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    fn template_start(&self, conn: &PgConnection) -> Result<NaiveDateTime, DatabaseError> {
+        match self.template_event(conn)?.event_start {
+            Some(event_start) => Ok(event_start),
+            None => DatabaseError::business_process_error("Series template event must have a start date"),
+        }
+    }
+
+    /// Creates any occurrences of the series which do not exist yet. Occurrences that were
+    /// previously generated and later deleted are not recreated.
+    pub fn generate_occurrences(
+        &self,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Vec<Event>, DatabaseError> {
+        let template_event = self.template_event(conn)?;
+        let template_start = self.template_start(conn)?;
+
+        let existing_starts: Vec<Option<NaiveDateTime>> = events::table
+            .filter(events::event_series_id.eq(self.id))
+            .select(events::event_start)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load events for series")?;
+
+        let mut new_events = vec![];
+        for event_start in EventSeries::occurrence_dates(
+            self.recurrence_type,
+            self.recurrence_interval,
+            self.recurrence_until,
+            &self.custom_dates,
+            template_start,
+        ) {
+            if existing_starts.contains(&Some(event_start)) {
+                continue;
+            }
+
+            new_events.push(template_event.clone_for_series(self.id, event_start, current_user_id, conn)?);
+        }
+
+        if !new_events.is_empty() {
+            DomainEvent::create(
+                DomainEventTypes::EventSeriesOccurrencesGenerated,
+                format!("{} occurrences generated for event series", new_events.len()),
+                Tables::EventSeries,
+                Some(self.id),
+                current_user_id,
+                Some(json!({ "event_ids": new_events.iter().map(|e| e.id).collect::<Vec<Uuid>>() })),
+            )
+            .commit(conn)?;
+        }
+
+        Ok(new_events)
+    }
+
+    /// Applies the attributes to every occurrence which has not yet started. Dates are specific
+    /// to each occurrence so are not propagated.
+    pub fn update_future_occurrences(
+        &self,
+        attributes: EventEditableAttributes,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Vec<Event>, DatabaseError> {
+        let mut attributes = attributes;
+        attributes.event_start = None;
+        attributes.event_end = None;
+        attributes.door_time = None;
+        attributes.publish_date = None;
+        attributes.redeem_date = None;
+        attributes.cancelled_at = None;
+        attributes.cloned_from_event_id = None;
+
+        let events: Vec<Event> = events::table
+            .filter(events::event_series_id.eq(self.id))
+            .filter(events::deleted_at.is_null())
+            .filter(events::cancelled_at.is_null())
+            .filter(events::event_start.gt(dsl::now))
+            .order_by(events::event_start.asc())
+            .for_update()
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load future events for series")?;
+
+        let mut updated_events = vec![];
+        for event in events {
+            updated_events.push(event.update(current_user_id, attributes.clone(), conn)?);
+        }
+
+        DomainEvent::create(
+            DomainEventTypes::EventSeriesUpdated,
+            "Future occurrences of event series updated".to_string(),
+            Tables::EventSeries,
+            Some(self.id),
+            current_user_id,
+            Some(json!({
+                "attributes": attributes,
+                "event_ids": updated_events.iter().map(|e| e.id).collect::<Vec<Uuid>>()
+            })),
+        )
+        .commit(conn)?;
+
+        Ok(updated_events)
+    }
+
+    pub fn for_display(&self, conn: &PgConnection) -> Result<DisplayEventSeries, DatabaseError> {
+        let slug = match self.slug_id {
+            Some(slug_id) => Some(Slug::find(slug_id, conn)?.slug),
+            None => None,
+        };
+
+        let upcoming_events = self.upcoming_events(conn)?;
+        let event_slugs: HashMap<Uuid, String> = Slug::load_primary_slugs(
+            &upcoming_events.iter().map(|e| e.id).collect::<Vec<Uuid>>(),
+            Tables::Events,
+            conn,
+        )?
+        .into_iter()
+        .map(|s| (s.id, s.slug))
+        .collect();
+
+        let upcoming_dates = upcoming_events
+            .into_iter()
+            .map(|event| EventSeriesOccurrence {
+                event_id: event.id,
+                slug: event.slug_id.and_then(|slug_id| event_slugs.get(&slug_id).cloned()),
+                name: event.name,
+                event_start: event.event_start,
+                door_time: event.door_time,
+                event_end: event.event_end,
+            })
+            .collect();
+
+        Ok(DisplayEventSeries {
+            id: self.id,
+            organization_id: self.organization_id,
+            name: self.name.clone(),
+            slug,
+            recurrence_type: self.recurrence_type,
+            recurrence_interval: self.recurrence_interval,
+            recurrence_until: self.recurrence_until,
+            upcoming_dates,
+        })
+    }
+}
+
+impl NewEventSeries {
+    pub fn commit(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<EventSeries, DatabaseError> {
+        let template_event = Event::find(self.template_event_id, conn)?;
+        if template_event.organization_id != self.organization_id {
+            return DatabaseError::business_process_error("Template event must belong to the series organization");
+        }
+        if template_event.event_series_id.is_some() {
+            return DatabaseError::business_process_error("Template event already belongs to an event series");
+        }
+        let template_start = match template_event.event_start {
+            Some(event_start) => event_start,
+            None => return DatabaseError::business_process_error("Series template event must have a start date"),
+        };
+        EventSeries::validate_recurrence(
+            self.recurrence_type,
+            self.recurrence_interval,
+            self.recurrence_until,
+            &self.custom_dates,
+            template_start,
+        )?;
+
+        let result: EventSeries = diesel::insert_into(event_series::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create event series")?;
+
+        let slug = Slug::generate_slug(
+            &SlugContext::EventSeries {
+                id: result.id,
+                name: result.name.clone(),
+            },
+            SlugTypes::EventSeries,
+            conn,
+        )?;
+        let result: EventSeries = diesel::update(&result)
+            .set((event_series::slug_id.eq(slug.id), event_series::updated_at.eq(dsl::now)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update event series slug")?;
+
+        diesel::update(&template_event)
+            .set((events::event_series_id.eq(result.id), events::updated_at.eq(dsl::now)))
+            .execute(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not add template event to series")?;
+
+        DomainEvent::create(
+            DomainEventTypes::EventSeriesCreated,
+            format!("Event series '{}' created", &result.name),
+            Tables::EventSeries,
+            Some(result.id),
+            current_user_id,
+            Some(json!(self)),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+}
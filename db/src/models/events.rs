@@ -16,7 +16,8 @@ use log::Level;
 use models::*;
 use schema::{
     artists, assets, event_artists, event_genres, events, genres, order_items, orders, organization_users,
-    organizations, payments, ticket_instances, ticket_types, transfer_tickets, transfers, users, venues, wallets,
+    organizations, payments, ticket_instances, ticket_pricing, ticket_types, transfer_tickets, transfers, users,
+    venues, wallets,
 };
 use serde_json::Value;
 use serde_with::rust::double_option;
@@ -26,6 +27,7 @@ use std::cmp::Ordering;
 use std::collections::HashMap;
 use utils::errors::*;
 use utils::pagination::*;
+use utils::rand::random_alpha_string;
 use utils::text;
 use uuid::Uuid;
 use validator::{Validate, ValidationErrors};
@@ -73,6 +75,8 @@ pub struct Event {
     pub facebook_event_id: Option<String>,
     pub settled_at: Option<NaiveDateTime>,
     pub cloned_from_event_id: Option<Uuid>,
+    pub currency: String,
+    pub event_series_id: Option<Uuid>,
 }
 
 impl PartialOrd for Event {
@@ -127,6 +131,9 @@ pub struct NewEvent {
     #[serde(default, deserialize_with = "deserialize_unless_blank")]
     pub facebook_event_id: Option<String>,
     pub cloned_from_event_id: Option<Uuid>,
+    /// Defaults to the organization's currency when not provided
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub currency: Option<String>,
 }
 
 pub enum TicketHoldersCountType {
@@ -152,8 +159,16 @@ impl NewEvent {
             None => (),
         }
 
+        let currency = match new_event.currency.take() {
+            Some(currency) => currency,
+            None => Organization::find(new_event.organization_id, conn)?.currency,
+        };
+        let validation_errors =
+            validators::append_validation_error(Ok(()), "event.currency", validate_currency(&currency));
+        new_event.currency = Some(currency);
+
         validators::append_validation_error(
-            Ok(()),
+            validation_errors,
             "event.event_end",
             validators::n_date_valid(
                 new_event.event_start,
@@ -207,7 +222,7 @@ impl NewEvent {
     }
 }
 
-#[derive(AsChangeset, Default, Deserialize, Validate, Serialize)]
+#[derive(AsChangeset, Clone, Default, Deserialize, Validate, Serialize)]
 #[table_name = "events"]
 pub struct EventEditableAttributes {
     pub name: Option<String>,
@@ -251,6 +266,8 @@ pub struct EventEditableAttributes {
     #[serde(default, deserialize_with = "double_option_deserialize_unless_blank")]
     pub facebook_event_id: Option<Option<String>>,
     pub cloned_from_event_id: Option<Option<Uuid>>,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub currency: Option<String>,
 }
 
 #[derive(Debug, Default, PartialEq, Serialize)]
@@ -281,6 +298,94 @@ impl Event {
         current_user_id: Option<Uuid>,
         conn: &PgConnection,
     ) -> Result<Event, DatabaseError> {
+        let (event, _) = self.clone_with_ticket_types(clone_fields, None, current_user_id, conn)?;
+        Ok(event)
+    }
+
+    /// Creates an occurrence of an event series from this (template) event starting at `event_start`.
+    /// Ticket type sale windows, pricing tiers and holds are copied with their dates shifted by the
+    /// difference between the template's start and `event_start`.
+    pub fn clone_for_series(
+        &self,
+        event_series_id: Uuid,
+        event_start: NaiveDateTime,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Event, DatabaseError> {
+        let template_start = match self.event_start {
+            Some(template_start) => template_start,
+            None => return DatabaseError::business_process_error("Series template event must have a start date"),
+        };
+        let offset = event_start.signed_duration_since(template_start);
+        let clone_fields = CloneFields {
+            name: self.name.clone(),
+            event_start,
+            event_end: self
+                .event_end
+                .map(|event_end| event_end + offset)
+                .unwrap_or(event_start + Duration::days(1)),
+        };
+
+        let (event, cloned_ticket_types) =
+            self.clone_with_ticket_types(&clone_fields, Some(offset), current_user_id, conn)?;
+        let event: Event = diesel::update(&event)
+            .set((
+                events::event_series_id.eq(event_series_id),
+                events::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not add event to series")?;
+
+        let mut ticket_type_ids = HashMap::new();
+        for (ticket_type, new_ticket_type) in cloned_ticket_types {
+            for ticket_pricing in ticket_type.valid_ticket_pricing(false, conn)? {
+                new_ticket_type.add_ticket_pricing(
+                    ticket_pricing.name,
+                    ticket_pricing.start_date + offset,
+                    ticket_pricing.end_date + offset,
+                    ticket_pricing.price_in_cents,
+                    ticket_pricing.is_box_office_only,
+                    ticket_pricing.sold_quantity_cap,
+                    None,
+                    current_user_id,
+                    conn,
+                )?;
+            }
+            new_ticket_type.validate_ticket_pricing(conn)?;
+            ticket_type_ids.insert(ticket_type.id, new_ticket_type.id);
+        }
+
+        for hold in Hold::find_for_event(self.id, false, conn)? {
+            let ticket_type_id = match ticket_type_ids.get(&hold.ticket_type_id) {
+                Some(ticket_type_id) => *ticket_type_id,
+                None => continue,
+            };
+            let (quantity, _) = hold.quantity(conn)?;
+            // Redemption codes are unique across events so each occurrence receives its own
+            let new_hold = Hold::create_hold(
+                hold.name.clone(),
+                event.id,
+                hold.redemption_code.as_ref().map(|_| random_alpha_string(10)),
+                hold.discount_in_cents.map(|discount| discount as u32),
+                hold.end_at.map(|end_at| end_at + offset),
+                hold.max_per_user.map(|max_per_user| max_per_user as u32),
+                hold.hold_type,
+                ticket_type_id,
+            )
+            .commit(current_user_id, conn)?;
+            new_hold.set_quantity(current_user_id, quantity, conn)?;
+        }
+
+        Ok(event)
+    }
+
+    fn clone_with_ticket_types(
+        &self,
+        clone_fields: &CloneFields,
+        date_offset: Option<Duration>,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<(Event, Vec<(TicketType, TicketType)>), DatabaseError> {
         let calculated_door_time = match self.door_time {
             Some(door_time) => match self.event_start {
                 Some(event_start) => {
@@ -313,6 +418,7 @@ impl Event {
         event.video_url = self.video_url.clone();
         event.is_external = self.is_external;
         event.external_url = self.external_url.clone();
+        event.currency = Some(self.currency.clone());
         let event = event.commit(current_user_id, conn)?;
 
         for event_artist in EventArtist::find_all_from_event(self.id, conn)? {
@@ -328,6 +434,7 @@ impl Event {
         }
 
         let org_wallet = Wallet::find_default_for_organization(event.organization_id, conn)?;
+        let mut cloned_ticket_types = vec![];
         for ticket_type in self.ticket_types(false, None, conn)? {
             // Skip any cancelled or deleted ticket type. Skip children (will be included below)
             if ticket_type.status == TicketTypeStatus::Cancelled
@@ -338,7 +445,15 @@ impl Event {
                 continue;
             }
 
-            event.clone_ticket_type(&org_wallet, None, &ticket_type, current_user_id, conn)?;
+            event.clone_ticket_type(
+                &org_wallet,
+                None,
+                &ticket_type,
+                date_offset,
+                &mut cloned_ticket_types,
+                current_user_id,
+                conn,
+            )?;
         }
 
         DomainEvent::create(
@@ -351,7 +466,7 @@ impl Event {
         )
         .commit(conn)?;
 
-        Ok(event)
+        Ok((event, cloned_ticket_types))
     }
 
     fn clone_ticket_type(
@@ -359,24 +474,36 @@ impl Event {
         org_wallet: &Wallet,
         parent_ticket_type: Option<&TicketType>,
         ticket_type: &TicketType,
+        date_offset: Option<Duration>,
+        cloned_ticket_types: &mut Vec<(TicketType, TicketType)>,
         current_user_id: Option<Uuid>,
         conn: &PgConnection,
     ) -> Result<TicketType, DatabaseError> {
+        let start_date = match (parent_ticket_type, date_offset, ticket_type.start_date) {
+            (Some(_), _, _) => None,
+            (None, Some(date_offset), Some(start_date)) => Some(start_date + date_offset),
+            _ => Some(times::zero()),
+        };
+        let (end_date, end_date_type) = match (date_offset, ticket_type.end_date) {
+            (Some(date_offset), Some(end_date)) if ticket_type.end_date_type == TicketTypeEndDateType::Manual => {
+                (Some(end_date + date_offset), TicketTypeEndDateType::Manual)
+            }
+            _ => (
+                None,
+                if ticket_type.end_date_type == TicketTypeEndDateType::Manual {
+                    TicketTypeEndDateType::EventEnd
+                } else {
+                    ticket_type.end_date_type
+                },
+            ),
+        };
         let new_ticket_type = self.add_ticket_type(
             ticket_type.name.clone(),
             ticket_type.description.clone(),
             ticket_type.valid_ticket_count(conn)?,
-            if parent_ticket_type.is_some() {
-                None
-            } else {
-                Some(times::zero())
-            },
-            None,
-            if ticket_type.end_date_type == TicketTypeEndDateType::Manual {
-                TicketTypeEndDateType::EventEnd
-            } else {
-                ticket_type.end_date_type
-            },
+            start_date,
+            end_date,
+            end_date_type,
             Some(org_wallet.id),
             Some(ticket_type.increment),
             ticket_type.limit_per_person,
@@ -408,11 +535,14 @@ impl Event {
                 org_wallet,
                 Some(&new_ticket_type),
                 &child_ticket_type,
+                date_offset,
+                cloned_ticket_types,
                 current_user_id,
                 conn,
             )?;
         }
 
+        cloned_ticket_types.push((ticket_type.clone(), new_ticket_type.clone()));
         Ok(new_ticket_type)
     }
 
@@ -690,9 +820,25 @@ impl Event {
             ),
         );
 
+        if let Some(ref currency) = attributes.currency {
+            validation_errors =
+                validators::append_validation_error(validation_errors, "event.currency", validate_currency(currency));
+        }
+
         let associated_with_active_orders = self.associated_with_active_orders(conn)?;
 
         if associated_with_active_orders {
+            if attributes.currency.is_some() && attributes.currency.as_ref() != Some(&self.currency) {
+                validation_errors = validators::append_validation_error(
+                    validation_errors,
+                    "event.currency",
+                    Err(create_validation_error(
+                        "cannot_change_currency_with_sales",
+                        "Event with sales cannot change currency.",
+                    )),
+                );
+            }
+
             if attributes.event_start != self.event_start {
                 if let Some(updated_date) = attributes.event_start {
                     if updated_date < Utc::now().naive_utc() {
@@ -778,6 +924,25 @@ impl Event {
             result.regenerate_drip_actions(conn)?;
         }
 
+        if result.currency != self.currency {
+            // Pricing is always charged in the event's currency
+            diesel::update(
+                ticket_pricing::table.filter(
+                    ticket_pricing::ticket_type_id.eq_any(
+                        ticket_types::table
+                            .filter(ticket_types::event_id.eq(self.id))
+                            .select(ticket_types::id),
+                    ),
+                ),
+            )
+            .set((
+                ticket_pricing::currency.eq(&result.currency),
+                ticket_pricing::updated_at.eq(dsl::now),
+            ))
+            .execute(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update ticket pricing currency")?;
+        }
+
         DomainEvent::create(
             DomainEventTypes::EventUpdated,
             format!("Event '{}' was updated", &self.name),
@@ -923,6 +1088,7 @@ impl Event {
                 json!(ProcessTransferDripPayload {
                     event_id: self.id,
                     source_or_destination: SourceOrDestination::Destination,
+                    expiry_reminder: false,
                 }),
                 Some(Tables::Events),
                 Some(self.id),
@@ -1783,6 +1949,7 @@ impl Event {
                 , sql::<Timestamp>("ticket_instances.updated_at AS updated_at")
                 , sql::<Nullable<Text>>("CASE WHEN ticket_instances.redeemed_by_user_id IS NOT NULL THEN (SELECT CONCAT(u2.first_name, ' ', u2.last_name) FROM users u2 WHERE u2.id = ticket_instances.redeemed_by_user_id) ELSE NULL END  AS redeemed_by")
                 , sql::<Nullable<Timestamp>>("ticket_instances.redeemed_at AS redeemed_at")
+                , sql::<Nullable<Text>>("(SELECT stage_sections.name || ' ' || stage_seats.row_name || '-' || stage_seats.seat_number FROM stage_seats INNER JOIN stage_sections ON stage_sections.id = stage_seats.stage_section_id WHERE stage_seats.id = ticket_instances.seat_id) AS seat")
             ))
             .paginate(paging.page as i64)
             .per_page(paging.limit as i64)
@@ -2287,6 +2454,7 @@ impl Event {
             event_type: self.event_type,
             slug,
             cloned_from_event_id: self.cloned_from_event_id,
+            currency: self.currency.clone(),
         })
     }
 }
@@ -2315,6 +2483,7 @@ pub struct DisplayEvent {
     pub genres: Vec<String>,
     pub slug: String,
     pub cloned_from_event_id: Option<Uuid>,
+    pub currency: String,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
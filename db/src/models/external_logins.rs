@@ -12,6 +12,8 @@ use utils::errors::*;
 use uuid::Uuid;
 
 pub const FACEBOOK_SITE: &str = "facebook.com";
+/// Logins through an organization's OpenID Connect provider, see `OrganizationSsoConfiguration`
+pub const OIDC_SITE: &str = "oidc";
 
 #[derive(Clone, Identifiable, Associations, Queryable, Serialize, Deserialize, PartialEq, Debug)]
 #[belongs_to(User, foreign_key = "user_id")]
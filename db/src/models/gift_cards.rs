@@ -0,0 +1,324 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::gift_cards;
+use utils::errors::*;
+use utils::rand::random_alpha_string;
+use uuid::Uuid;
+use validators::{self, *};
+
+const GIFT_CARD_CODE_LENGTH: usize = 16;
+const GIFT_CARD_CODE_MIN_LENGTH: usize = 8;
+
+/// Stored value issued by an organization and redeemable against orders for its events. The
+/// organization collects the value when the card is issued, so redemptions are deducted from
+/// settlements while the tickets they pay for are recognised as revenue.
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(Organization)]
+#[table_name = "gift_cards"]
+pub struct GiftCard {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub code: String,
+    pub initial_balance_in_cents: i64,
+    pub balance_in_cents: i64,
+    pub currency: String,
+    pub expires_at: Option<NaiveDateTime>,
+    pub deactivated_at: Option<NaiveDateTime>,
+    pub created_by: Uuid,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Deserialize, Insertable, Serialize)]
+#[table_name = "gift_cards"]
+pub struct NewGiftCard {
+    pub organization_id: Uuid,
+    pub code: String,
+    pub initial_balance_in_cents: i64,
+    pub balance_in_cents: i64,
+    pub currency: String,
+    pub expires_at: Option<NaiveDateTime>,
+    pub created_by: Uuid,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DisplayGiftCard {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub code: String,
+    pub initial_balance_in_cents: i64,
+    pub balance_in_cents: i64,
+    pub currency: String,
+    pub expires_at: Option<NaiveDateTime>,
+    pub deactivated_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub ledger_entries: Vec<StoredValueLedgerEntry>,
+}
+
+/// Balance of a gift card as shown to the customer holding the code
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DisplayGiftCardBalance {
+    pub balance_in_cents: i64,
+    pub currency: String,
+    pub expires_at: Option<NaiveDateTime>,
+    pub redeemable: bool,
+}
+
+impl NewGiftCard {
+    pub fn commit(self, conn: &PgConnection) -> Result<GiftCard, DatabaseError> {
+        self.validate_record(conn)?;
+
+        let gift_card: GiftCard = diesel::insert_into(gift_cards::table)
+            .values(&self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create gift card")?;
+
+        NewStoredValueLedgerEntry {
+            gift_card_id: Some(gift_card.id),
+            account_credit_id: None,
+            entry_type: StoredValueLedgerEntryTypes::Issued,
+            amount_in_cents: gift_card.initial_balance_in_cents,
+            balance_after_in_cents: gift_card.balance_in_cents,
+            order_id: None,
+            payment_id: None,
+            refund_id: None,
+            created_by: Some(self.created_by),
+        }
+        .commit(conn)?;
+
+        DomainEvent::create(
+            DomainEventTypes::GiftCardCreated,
+            "Gift card created".to_string(),
+            Tables::GiftCards,
+            Some(gift_card.id),
+            Some(self.created_by),
+            Some(json!({
+                "organization_id": self.organization_id,
+                "initial_balance_in_cents": self.initial_balance_in_cents,
+                "currency": self.currency,
+                "expires_at": self.expires_at
+            })),
+        )
+        .commit(conn)?;
+
+        Ok(gift_card)
+    }
+
+    fn validate_record(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        let mut validation_errors = validators::append_validation_error(
+            Ok(()),
+            "initial_balance_in_cents",
+            validators::validate_greater_than(
+                self.initial_balance_in_cents,
+                0,
+                "initial_balance_in_cents_lte_0",
+                "Gift card balance must be greater than 0",
+            ),
+        );
+        validation_errors =
+            validators::append_validation_error(validation_errors, "currency", validate_currency(&self.currency));
+        if self.code.len() < GIFT_CARD_CODE_MIN_LENGTH || !self.code.chars().all(|c| c.is_ascii_alphanumeric()) {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "code",
+                Err(create_validation_error(
+                    "code_invalid",
+                    "Gift card codes must be at least 8 letters or digits",
+                )),
+            );
+        } else if GiftCard::find_by_code(&self.code, conn).optional()?.is_some() {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "code",
+                Err(create_validation_error(
+                    "code_taken",
+                    "Gift card code is already in use",
+                )),
+            );
+        }
+        if let Some(expires_at) = self.expires_at {
+            if expires_at <= Utc::now().naive_utc() {
+                validation_errors = validators::append_validation_error(
+                    validation_errors,
+                    "expires_at",
+                    Err(create_validation_error(
+                        "expires_at_past",
+                        "Expiry must be in the future",
+                    )),
+                );
+            }
+        }
+
+        Ok(validation_errors?)
+    }
+}
+
+impl GiftCard {
+    /// Codes are case insensitive and generated when not provided
+    pub fn create(
+        organization_id: Uuid,
+        code: Option<String>,
+        initial_balance_in_cents: i64,
+        currency: String,
+        expires_at: Option<NaiveDateTime>,
+        created_by: Uuid,
+    ) -> NewGiftCard {
+        NewGiftCard {
+            organization_id,
+            code: GiftCard::normalize_code(&code.unwrap_or_else(|| random_alpha_string(GIFT_CARD_CODE_LENGTH))),
+            initial_balance_in_cents,
+            balance_in_cents: initial_balance_in_cents,
+            currency: currency.to_uppercase(),
+            expires_at,
+            created_by,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<GiftCard, DatabaseError> {
+        gift_cards::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find gift card")
+    }
+
+    pub(crate) fn find_for_update(id: Uuid, conn: &PgConnection) -> Result<GiftCard, DatabaseError> {
+        gift_cards::table
+            .filter(gift_cards::id.eq(id))
+            .for_update()
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find gift card")
+    }
+
+    pub fn find_by_code(code: &str, conn: &PgConnection) -> Result<GiftCard, DatabaseError> {
+        gift_cards::table
+            .filter(gift_cards::code.eq(GiftCard::normalize_code(code)))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find gift card")
+    }
+
+    pub fn find_for_organization(organization_id: Uuid, conn: &PgConnection) -> Result<Vec<GiftCard>, DatabaseError> {
+        gift_cards::table
+            .filter(gift_cards::organization_id.eq(organization_id))
+            .order_by(gift_cards::created_at.desc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load gift cards for organization")
+    }
+
+    pub fn organization(&self, conn: &PgConnection) -> Result<Organization, DatabaseError> {
+        Organization::find(self.organization_id, conn)
+    }
+
+    pub fn ledger_entries(&self, conn: &PgConnection) -> Result<Vec<StoredValueLedgerEntry>, DatabaseError> {
+        StoredValueLedgerEntry::find_for_gift_card(self.id, conn)
+    }
+
+    pub fn is_redeemable(&self) -> bool {
+        self.deactivated_at.is_none()
+            && self.balance_in_cents > 0
+            && self
+                .expires_at
+                .map(|expires_at| expires_at > Utc::now().naive_utc())
+                .unwrap_or(true)
+    }
+
+    pub fn for_display(&self, conn: &PgConnection) -> Result<DisplayGiftCard, DatabaseError> {
+        Ok(DisplayGiftCard {
+            id: self.id,
+            organization_id: self.organization_id,
+            code: self.code.clone(),
+            initial_balance_in_cents: self.initial_balance_in_cents,
+            balance_in_cents: self.balance_in_cents,
+            currency: self.currency.clone(),
+            expires_at: self.expires_at,
+            deactivated_at: self.deactivated_at,
+            created_at: self.created_at,
+            ledger_entries: self.ledger_entries(conn)?,
+        })
+    }
+
+    pub fn balance_for_display(&self) -> DisplayGiftCardBalance {
+        DisplayGiftCardBalance {
+            balance_in_cents: self.balance_in_cents,
+            currency: self.currency.clone(),
+            expires_at: self.expires_at,
+            redeemable: self.is_redeemable(),
+        }
+    }
+
+    /// Voids the card, writing off any remaining balance
+    pub fn deactivate(&self, current_user_id: Uuid, conn: &PgConnection) -> Result<GiftCard, DatabaseError> {
+        let gift_card = GiftCard::find_for_update(self.id, conn)?;
+        if gift_card.deactivated_at.is_some() {
+            return DatabaseError::business_process_error("Gift card has already been deactivated");
+        }
+
+        gift_card.change_balance(
+            StoredValueLedgerEntryTypes::Deactivated,
+            -gift_card.balance_in_cents,
+            None,
+            None,
+            None,
+            Some(current_user_id),
+            conn,
+        )?;
+        let gift_card: GiftCard = diesel::update(&gift_card)
+            .set((
+                gift_cards::deactivated_at.eq(dsl::now.nullable()),
+                gift_cards::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not deactivate gift card")?;
+
+        DomainEvent::create(
+            DomainEventTypes::GiftCardDeactivated,
+            "Gift card deactivated".to_string(),
+            Tables::GiftCards,
+            Some(gift_card.id),
+            Some(current_user_id),
+            Some(json!({ "written_off_in_cents": self.balance_in_cents })),
+        )
+        .commit(conn)?;
+
+        Ok(gift_card)
+    }
+
+    /// Applies the change to the stored balance and records it in the ledger
+    pub(crate) fn change_balance(
+        &self,
+        entry_type: StoredValueLedgerEntryTypes,
+        amount_in_cents: i64,
+        order_id: Option<Uuid>,
+        payment_id: Option<Uuid>,
+        refund_id: Option<Uuid>,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<StoredValueLedgerEntry, DatabaseError> {
+        let gift_card: GiftCard = diesel::update(gift_cards::table.filter(gift_cards::id.eq(self.id)))
+            .set((
+                gift_cards::balance_in_cents.eq(gift_cards::balance_in_cents + amount_in_cents),
+                gift_cards::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update gift card balance")?;
+
+        NewStoredValueLedgerEntry {
+            gift_card_id: Some(self.id),
+            account_credit_id: None,
+            entry_type,
+            amount_in_cents,
+            balance_after_in_cents: gift_card.balance_in_cents,
+            order_id,
+            payment_id,
+            refund_id,
+            created_by: current_user_id,
+        }
+        .commit(conn)
+    }
+
+    fn normalize_code(code: &str) -> String {
+        code.trim().to_uppercase()
+    }
+}
@@ -16,5 +16,16 @@ pub fn schedule_domain_actions(conn: &PgConnection) -> Result<(), DatabaseError>
         Settlement::create_next_finalize_settlements_domain_action(conn)?;
     }
 
+    if DomainAction::upcoming_domain_action(
+        None,
+        None,
+        DomainActionTypes::SendPaymentMethodExpiryNotifications,
+        conn,
+    )?
+    .is_none()
+    {
+        PaymentMethod::create_next_expiry_notification_domain_action(conn)?;
+    }
+
     Ok(())
 }
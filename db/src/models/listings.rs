@@ -1,12 +1,14 @@
 use chrono::NaiveDateTime;
-use diesel::dsl;
+use diesel;
+use diesel::dsl::{self, exists, select};
 use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Nullable, Text, Uuid as dUuid};
 use prelude::*;
 use schema::*;
 use utils::errors::ErrorCode;
 use uuid::Uuid;
 
-#[derive(Queryable, Identifiable)]
+#[derive(Clone, Debug, Identifiable, PartialEq, Queryable, Serialize)]
 #[table_name = "listings"]
 pub struct Listing {
     pub id: Uuid,
@@ -18,6 +20,26 @@ pub struct Listing {
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
     pub deleted_at: Option<NaiveDateTime>,
+    pub event_id: Option<Uuid>,
+    pub buyer_user_id: Option<Uuid>,
+    pub order_id: Option<Uuid>,
+    pub resale_fee_in_cents: Option<i64>,
+    pub payout_in_cents: Option<i64>,
+    pub sold_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, QueryableByName, Serialize)]
+pub struct DisplayListing {
+    #[sql_type = "dUuid"]
+    pub id: Uuid,
+    #[sql_type = "Text"]
+    pub title: String,
+    #[sql_type = "Nullable<dUuid>"]
+    pub event_id: Option<Uuid>,
+    #[sql_type = "BigInt"]
+    pub asking_price_in_cents: i64,
+    #[sql_type = "BigInt"]
+    pub quantity: i64,
 }
 
 impl Listing {
@@ -36,6 +58,178 @@ impl Listing {
             .to_db_error(ErrorCode::QueryError, "Could not find listing")
     }
 
+    pub(crate) fn find_for_update(id: Uuid, conn: &PgConnection) -> Result<Listing, DatabaseError> {
+        listings::table
+            .filter(listings::id.eq(id))
+            .for_update()
+            .get_result(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not lock listing")
+    }
+
+    pub fn find_for_user(user_id: Uuid, conn: &PgConnection) -> Result<Vec<Listing>, DatabaseError> {
+        listings::table
+            .filter(listings::user_id.eq(user_id))
+            .filter(listings::deleted_at.is_null())
+            .order_by(listings::created_at.desc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load listings for user")
+    }
+
+    /// Published listings for an event that are not currently claimed by a buyer's cart
+    pub fn find_published_for_event(event_id: Uuid, conn: &PgConnection) -> Result<Vec<DisplayListing>, DatabaseError> {
+        let query = include_str!("../queries/find_published_listings_for_event.sql");
+        diesel::sql_query(query)
+            .bind::<dUuid, _>(event_id)
+            .get_results(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load listings for event")
+    }
+
+    pub fn tickets(&self, conn: &PgConnection) -> Result<Vec<TicketInstance>, DatabaseError> {
+        ticket_instances::table
+            .filter(ticket_instances::listing_id.eq(self.id))
+            .order_by(ticket_instances::id)
+            .select(ticket_instances::all_columns)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load tickets for listing")
+    }
+
+    /// Adds tickets from the owner's wallet to the listing. A listing may only contain tickets
+    /// for a single event and the asking price must respect each ticket type's resale price cap.
+    pub fn add_tickets(
+        &mut self,
+        current_user_id: Option<Uuid>,
+        owner_wallet_id: Uuid,
+        ticket_type_id: Uuid,
+        quantity: u32,
+        conn: &PgConnection,
+    ) -> Result<Vec<TicketInstance>, DatabaseError> {
+        if self.status != ListingStatus::Pending {
+            return DatabaseError::business_process_error("Tickets can only be added to a pending listing");
+        }
+
+        let ticket_type = TicketType::find(ticket_type_id, conn)?;
+        match self.event_id {
+            Some(event_id) if event_id != ticket_type.event_id => {
+                return DatabaseError::validation_error(
+                    "ticket_type_id",
+                    "Listings can only contain tickets for a single event",
+                );
+            }
+            Some(_) => (),
+            None => {
+                *self = diesel::update(listings::table.filter(listings::id.eq(self.id)))
+                    .set((
+                        listings::event_id.eq(ticket_type.event_id),
+                        listings::updated_at.eq(dsl::now),
+                    ))
+                    .get_result(conn)
+                    .to_db_error(ErrorCode::UpdateError, "Could not update listing")?;
+            }
+        }
+
+        let tickets = TicketInstance::add_to_listing(
+            current_user_id,
+            owner_wallet_id,
+            self.id,
+            ticket_type_id,
+            quantity,
+            conn,
+        )?;
+        self.validate_asking_price(conn)?;
+
+        Ok(tickets)
+    }
+
+    /// Confirms the asking price does not exceed the organizer's resale price cap for any ticket
+    /// on the listing. The cap is a percentage of the face value originally paid for the ticket.
+    pub fn validate_asking_price(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        if self.asking_price_in_cents < 0 {
+            return DatabaseError::validation_error("asking_price_in_cents", "Asking price cannot be negative");
+        }
+
+        let prices: Vec<(Option<i64>, i64, Option<i32>)> = ticket_instances::table
+            .inner_join(assets::table.inner_join(ticket_types::table))
+            .left_join(order_items::table)
+            .filter(ticket_instances::listing_id.eq(self.id))
+            .select((
+                order_items::unit_price_in_cents.nullable(),
+                ticket_types::price_in_cents,
+                ticket_types::resale_price_cap_percent,
+            ))
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load face values for listing")?;
+
+        for (paid_price_in_cents, price_in_cents, resale_price_cap_percent) in prices {
+            if let Some(resale_price_cap_percent) = resale_price_cap_percent {
+                let face_value_in_cents = paid_price_in_cents.unwrap_or(price_in_cents);
+                let max_price_in_cents = face_value_in_cents * resale_price_cap_percent as i64 / 100;
+                if self.asking_price_in_cents > max_price_in_cents {
+                    return DatabaseError::validation_error(
+                        "asking_price_in_cents",
+                        "Asking price exceeds the resale price cap set by the organizer",
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// A listing can be added to a cart while it is published and not claimed by another order
+    pub fn is_available(&self, order_id: Option<Uuid>, conn: &PgConnection) -> Result<bool, DatabaseError> {
+        if self.status != ListingStatus::Published {
+            return Ok(false);
+        }
+
+        let claimed = select(exists(
+            order_items::table
+                .inner_join(orders::table)
+                .filter(order_items::listing_id.eq(self.id))
+                .filter(orders::id.ne(order_id.unwrap_or(Uuid::nil())))
+                .filter(
+                    orders::status
+                        .eq_any(vec![OrderStatus::Paid, OrderStatus::PendingPayment])
+                        .or(orders::status
+                            .eq(OrderStatus::Draft)
+                            .and(orders::expires_at.gt(dsl::now.nullable()))),
+                ),
+        ))
+        .get_result(conn)
+        .to_db_error(ErrorCode::QueryError, "Could not check if listing is claimed")?;
+
+        Ok(!claimed)
+    }
+
+    /// Publishes the listing on the platform's own resale marketplace
+    pub fn publish(self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<Listing, DatabaseError> {
+        if self.status != ListingStatus::Pending {
+            return DatabaseError::business_process_error("Only pending listings can be published");
+        }
+        if self.tickets(conn)?.is_empty() {
+            return DatabaseError::business_process_error("Cannot publish a listing without tickets");
+        }
+
+        let listing: Listing = diesel::update(&self)
+            .set((
+                listings::status.eq(ListingStatus::Published),
+                listings::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not publish listing")?;
+
+        DomainEvent::create(
+            DomainEventTypes::ListingPublished,
+            "Listing published".to_string(),
+            Tables::Listings,
+            Some(listing.id),
+            current_user_id,
+            Some(json!({ "asking_price_in_cents": listing.asking_price_in_cents })),
+        )
+        .commit(conn)?;
+
+        Ok(listing)
+    }
+
     pub fn set_published(self, marketplace_id: String, conn: &PgConnection) -> Result<Listing, DatabaseError> {
         diesel::update(&self)
             .set((
@@ -46,6 +240,135 @@ impl Listing {
             .get_result(conn)
             .to_db_error(ErrorCode::UpdateError, "Could not publish listing")
     }
+
+    /// Withdraws the listing and returns its tickets to the owner
+    pub fn cancel(self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<Listing, DatabaseError> {
+        let listing = Listing::find_for_update(self.id, conn)?;
+        if listing.status != ListingStatus::Pending && listing.status != ListingStatus::Published {
+            return DatabaseError::business_process_error("Listing can no longer be cancelled");
+        }
+
+        listing.release_tickets(current_user_id, conn)?;
+        let listing: Listing = diesel::update(&listing)
+            .set((
+                listings::status.eq(ListingStatus::Cancelled),
+                listings::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not cancel listing")?;
+
+        DomainEvent::create(
+            DomainEventTypes::ListingCancelled,
+            "Listing cancelled".to_string(),
+            Tables::Listings,
+            Some(listing.id),
+            current_user_id,
+            None,
+        )
+        .commit(conn)?;
+
+        Ok(listing)
+    }
+
+    /// Completes the sale of a listing once the buyer's order is paid. The tickets are transferred
+    /// to the buyer and the seller's payout is recorded net of the organization's resale fee.
+    pub(crate) fn complete_sale(
+        order_item: &OrderItem,
+        buyer_user_id: Uuid,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Listing, DatabaseError> {
+        let listing_id = match order_item.listing_id {
+            Some(listing_id) => listing_id,
+            None => return DatabaseError::business_process_error("Order item is not for a resale listing"),
+        };
+        let listing = Listing::find_for_update(listing_id, conn)?;
+        if listing.status != ListingStatus::Published {
+            return DatabaseError::business_process_error("Listing is no longer available for purchase");
+        }
+
+        let tickets = listing.release_tickets(current_user_id, conn)?;
+        if tickets.len() as i64 != order_item.quantity {
+            return DatabaseError::business_process_error("Listing tickets do not match the purchased quantity");
+        }
+        let ticket_ids: Vec<Uuid> = tickets.iter().map(|t| t.id).collect();
+
+        let seller = User::find(listing.user_id, conn)?;
+        let buyer = User::find(buyer_user_id, conn)?;
+        let transfer = TicketInstance::direct_transfer(
+            &seller,
+            &ticket_ids,
+            &buyer.email.clone().unwrap_or_default(),
+            TransferMessageType::Email,
+            buyer.id,
+            conn,
+        )?;
+
+        let organization = Organization::find(order_item.event(conn)?.organization_id, conn)?;
+        let sale_total_in_cents = order_item.unit_price_in_cents * order_item.quantity;
+        let resale_fee_in_cents = organization.resale_fee_in_cents(sale_total_in_cents);
+        let payout_in_cents = sale_total_in_cents - resale_fee_in_cents;
+
+        let listing: Listing = diesel::update(&listing)
+            .set((
+                listings::status.eq(ListingStatus::Sold),
+                listings::buyer_user_id.eq(buyer.id),
+                listings::order_id.eq(order_item.order_id),
+                listings::resale_fee_in_cents.eq(resale_fee_in_cents),
+                listings::payout_in_cents.eq(payout_in_cents),
+                listings::sold_at.eq(dsl::now.nullable()),
+                listings::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not mark listing as sold")?;
+
+        DomainEvent::create(
+            DomainEventTypes::ListingSold,
+            "Listing sold".to_string(),
+            Tables::Listings,
+            Some(listing.id),
+            current_user_id,
+            Some(json!({
+                "order_id": order_item.order_id,
+                "buyer_user_id": buyer.id,
+                "transfer_id": transfer.id,
+                "resale_fee_in_cents": resale_fee_in_cents,
+                "payout_in_cents": payout_in_cents
+            })),
+        )
+        .commit(conn)?;
+
+        Ok(listing)
+    }
+
+    fn release_tickets(
+        &self,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Vec<TicketInstance>, DatabaseError> {
+        let tickets: Vec<TicketInstance> =
+            diesel::update(ticket_instances::table.filter(ticket_instances::listing_id.eq(self.id)))
+                .set((
+                    ticket_instances::listing_id.eq(None::<Uuid>),
+                    ticket_instances::updated_at.eq(dsl::now),
+                ))
+                .get_results(conn)
+                .to_db_error(ErrorCode::UpdateError, "Could not release tickets from the listing")?;
+
+        for ticket in tickets.iter() {
+            DomainEvent::create(
+                DomainEventTypes::TicketInstanceReleasedFromListing,
+                "Ticket released from listing".to_string(),
+                Tables::TicketInstances,
+                Some(ticket.id),
+                current_user_id,
+                Some(json!({ "listing_id": self.id })),
+            )
+            .commit(conn)?;
+        }
+
+        Ok(tickets)
+    }
 }
 
 #[derive(Insertable)]
@@ -1,14 +1,19 @@
+pub use self::account_credits::*;
 pub use self::activities::*;
 pub use self::announcement_engagements::*;
 pub use self::announcements::*;
+pub use self::api_keys::*;
 pub use self::artists::*;
 pub use self::assets::*;
 pub use self::auth::*;
 pub use self::broadcasts::*;
+pub use self::bundle_ticket_types::*;
+pub use self::bundles::*;
 pub use self::codes::*;
 pub use self::collection_items::*;
 pub use self::collections::*;
 pub use self::communication::*;
+pub use self::communication_templates::*;
 pub use self::domain_actions::*;
 pub use self::domain_event_publishers::*;
 pub use self::domain_events::*;
@@ -16,6 +21,7 @@ pub use self::enums::*;
 pub use self::event_artists::*;
 pub use self::event_interest::*;
 pub use self::event_report_subscribers::*;
+pub use self::event_series::*;
 pub use self::event_users::*;
 pub use self::events::*;
 pub use self::external_logins::FACEBOOK_SITE;
@@ -25,6 +31,7 @@ pub use self::fee_schedule_ranges::*;
 pub use self::fee_schedules::*;
 pub use self::for_display::*;
 pub use self::genres::*;
+pub use self::gift_cards::*;
 pub use self::global::*;
 pub use self::history_item::*;
 pub use self::holds::*;
@@ -36,11 +43,15 @@ pub use self::order_items::*;
 pub use self::orders::*;
 pub use self::organization_interactions::*;
 pub use self::organization_invites::*;
+pub use self::organization_roles::*;
+pub use self::organization_sso_configurations::*;
 pub use self::organization_users::*;
 pub use self::organization_venues::*;
 pub use self::organizations::*;
 pub use self::paging::*;
 pub use self::payment_methods::*;
+pub use self::payment_plans::*;
+pub use self::payment_provider_events::*;
 pub use self::payments::*;
 pub use self::platforms::*;
 pub use self::push_notification_tokens::*;
@@ -51,12 +62,18 @@ pub use self::refunded_tickets::*;
 pub use self::refunds::*;
 pub use self::regions::*;
 pub use self::reports::*;
+pub use self::scanner_manifests::*;
 pub use self::scopes::*;
 pub use self::settlement_adjustments::*;
 pub use self::settlement_entries::*;
 pub use self::settlements::*;
 pub use self::slugs::*;
+pub use self::sso_login_requests::*;
+pub use self::stage_seats::*;
+pub use self::stage_sections::*;
 pub use self::stages::*;
+pub use self::stored_value_ledger_entries::*;
+pub use self::tax_rules::*;
 pub use self::temporary_users::*;
 pub use self::ticket_instances::RedeemResults;
 pub use self::ticket_instances::*;
@@ -65,27 +82,38 @@ pub use self::ticket_type_codes::*;
 pub use self::ticket_types::*;
 pub use self::transfer_tickets::*;
 pub use self::transfers::*;
+pub use self::user_login_throttles::*;
+pub use self::user_recovery_codes::*;
+pub use self::user_sessions::*;
+pub use self::user_totp_credentials::*;
 pub use self::users::*;
 pub use self::venues::*;
+pub use self::waitlist_entries::*;
 pub use self::wallets::*;
+pub use self::webhook_deliveries::*;
 
 use serde::{Deserialize, Deserializer};
 use serde_json::Value;
 
 pub mod concerns;
 
+mod account_credits;
 mod activities;
 pub mod analytics;
 mod announcement_engagements;
 mod announcements;
+mod api_keys;
 mod artists;
 mod assets;
 mod auth;
 mod broadcasts;
+mod bundle_ticket_types;
+mod bundles;
 mod codes;
 mod collection_items;
 mod collections;
 mod communication;
+mod communication_templates;
 mod domain_actions;
 mod domain_event_publishers;
 mod domain_events;
@@ -93,6 +121,7 @@ pub mod enums;
 mod event_artists;
 mod event_interest;
 mod event_report_subscribers;
+mod event_series;
 mod event_users;
 mod events;
 mod external_logins;
@@ -101,6 +130,7 @@ mod fee_schedule_ranges;
 mod fee_schedules;
 mod for_display;
 mod genres;
+mod gift_cards;
 pub mod global;
 mod history_item;
 mod holds;
@@ -112,11 +142,15 @@ mod order_items;
 mod orders;
 mod organization_interactions;
 mod organization_invites;
+mod organization_roles;
+mod organization_sso_configurations;
 mod organization_users;
 mod organization_venues;
 mod organizations;
 mod paging;
 mod payment_methods;
+mod payment_plans;
+mod payment_provider_events;
 mod payments;
 mod platforms;
 mod push_notification_tokens;
@@ -127,12 +161,18 @@ mod refunded_tickets;
 mod refunds;
 mod regions;
 mod reports;
+mod scanner_manifests;
 pub mod scopes;
 mod settlement_adjustments;
 mod settlement_entries;
 mod settlements;
 mod slugs;
+mod sso_login_requests;
+mod stage_seats;
+mod stage_sections;
 mod stages;
+mod stored_value_ledger_entries;
+mod tax_rules;
 mod temporary_users;
 mod ticket_instances;
 mod ticket_pricing;
@@ -140,9 +180,15 @@ mod ticket_type_codes;
 mod ticket_types;
 mod transfer_tickets;
 mod transfers;
+mod user_login_throttles;
+mod user_recovery_codes;
+mod user_sessions;
+mod user_totp_credentials;
 mod users;
 mod venues;
+mod waitlist_entries;
 mod wallets;
+mod webhook_deliveries;
 
 pub fn deserialize_unless_blank<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
 where
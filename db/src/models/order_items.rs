@@ -5,7 +5,7 @@ use diesel::prelude::*;
 use diesel::sql_types::{Array, BigInt, Nullable, Text, Uuid as dUuid};
 use itertools::Itertools;
 use models::*;
-use schema::{codes, events, order_items, ticket_instances, ticket_types};
+use schema::{codes, events, order_items, tax_rules, ticket_instances, ticket_types};
 use std::borrow::Cow;
 use std::cmp;
 use std::collections::HashMap;
@@ -39,6 +39,11 @@ pub struct OrderItem {
     pub company_fee_in_cents: i64,
     pub client_fee_in_cents: i64,
     pub refunded_quantity: i64,
+    pub tax_rule_id: Option<Uuid>,
+    pub tax_in_cents: i64,
+    pub currency: String,
+    pub listing_id: Option<Uuid>,
+    pub bundle_id: Option<Uuid>,
 }
 
 impl OrderItem {
@@ -67,6 +72,26 @@ impl OrderItem {
             .to_db_error(ErrorCode::QueryError, "Could not retrieve order item discount")
     }
 
+    pub fn find_tax_items(&self, conn: &PgConnection) -> Result<Vec<OrderItem>, DatabaseError> {
+        order_items::table
+            .filter(order_items::parent_id.eq(self.id))
+            .filter(order_items::item_type.eq(OrderItemTypes::Tax))
+            .order_by(order_items::created_at.asc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not retrieve order item taxes")
+    }
+
+    pub fn tax_rule(&self, conn: &PgConnection) -> Result<Option<TaxRule>, DatabaseError> {
+        match self.tax_rule_id {
+            Some(tax_rule_id) => tax_rules::table
+                .filter(tax_rules::id.eq(tax_rule_id))
+                .first(conn)
+                .optional()
+                .to_db_error(ErrorCode::QueryError, "Could not retrieve tax rule"),
+            None => Ok(None),
+        }
+    }
+
     pub fn description(&self, conn: &PgConnection) -> Result<String, DatabaseError> {
         use models::OrderItemTypes::*;
         let res = match self.item_type {
@@ -80,6 +105,11 @@ impl OrderItem {
             }
             Discount => "Discount".to_string(),
             CreditCardFees => "Credit Card Fees".to_string(),
+            ResaleTickets => format!("Resale - {}", self.event(conn)?.name),
+            Tax => match self.tax_rule(conn)? {
+                Some(tax_rule) => tax_rule.name,
+                None => "Tax".to_string(),
+            },
             _ => {
                 let ticket_type = self.ticket_type(conn)?;
                 match ticket_type {
@@ -122,6 +152,11 @@ impl OrderItem {
             }
         }
 
+        // Taxes are always refunded alongside the item they were charged on
+        for mut tax_item in self.find_tax_items(conn)? {
+            refund_amount_in_cents += tax_item.refund_one_unit(true, conn)?;
+        }
+
         diesel::update(order_items::table.filter(order_items::id.eq(self.id)))
             .set((
                 order_items::updated_at.eq(dsl::now),
@@ -156,6 +191,7 @@ impl OrderItem {
             || self.item_type == OrderItemTypes::EventFees
             || self.item_type == OrderItemTypes::Discount
             || self.item_type == OrderItemTypes::CreditCardFees
+            || self.item_type == OrderItemTypes::Tax
         {
             return Ok(());
         }
@@ -188,6 +224,7 @@ impl OrderItem {
                         company_fee_in_cents: 0,
                         client_fee_in_cents: 0,
                         parent_id: Some(self.id),
+                        currency: self.currency.clone(),
                     }
                     .commit(conn)?;
                 }
@@ -218,6 +255,7 @@ impl OrderItem {
                     company_fee_in_cents: 0,
                     client_fee_in_cents: 0,
                     parent_id: Some(self.id),
+                    currency: self.currency.clone(),
                 }
                 .commit(conn)?;
             }
@@ -289,6 +327,7 @@ impl OrderItem {
                             + ticket_type.additional_fee_in_cents,
                         quantity: self.quantity,
                         parent_id: Some(self.id),
+                        currency: self.currency.clone(),
                     }
                     .commit(conn)?;
 
@@ -303,6 +342,79 @@ impl OrderItem {
         }
     }
 
+    /// Creates, updates or removes the tax items charged on this item for the provided tax rules.
+    /// Ticket prices are always taxed, fees are only taxed when the rule applies to fees.
+    pub(crate) fn update_taxes(
+        &self,
+        order: &Order,
+        tax_rules: &[TaxRule],
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        let taxable_unit_price = match self.item_type {
+            OrderItemTypes::Tickets => match self.find_discount_item(conn)? {
+                Some(di) => self.unit_price_in_cents + di.unit_price_in_cents,
+                None => self.unit_price_in_cents,
+            },
+            OrderItemTypes::PerUnitFees | OrderItemTypes::EventFees => self.unit_price_in_cents,
+            _ => return Ok(()),
+        };
+
+        let tax_items = self.find_tax_items(conn)?;
+        for tax_item in tax_items.iter() {
+            if !tax_rules.iter().any(|r| Some(r.id) == tax_item.tax_rule_id) {
+                order.destroy_item(tax_item.id, conn)?;
+            }
+        }
+
+        for tax_rule in tax_rules {
+            let tax_item = tax_items.iter().find(|ti| ti.tax_rule_id == Some(tax_rule.id));
+            let tax_in_cents = if self.item_type == OrderItemTypes::Tickets || tax_rule.applies_to_fees {
+                tax_rule.tax_for(taxable_unit_price)
+            } else {
+                0
+            };
+
+            if tax_in_cents <= 0 {
+                if let Some(tax_item) = tax_item {
+                    order.destroy_item(tax_item.id, conn)?;
+                }
+                continue;
+            }
+
+            // Inclusive taxes are already part of the taxed item's price
+            let unit_price_in_cents = if tax_rule.inclusive { 0 } else { tax_in_cents };
+            match tax_item {
+                Some(tax_item) => {
+                    diesel::update(tax_item)
+                        .set((
+                            order_items::quantity.eq(self.quantity),
+                            order_items::unit_price_in_cents.eq(unit_price_in_cents),
+                            order_items::tax_in_cents.eq(tax_in_cents),
+                            order_items::updated_at.eq(dsl::now),
+                        ))
+                        .execute(conn)
+                        .to_db_error(ErrorCode::UpdateError, "Could not update tax order item")?;
+                }
+                None => {
+                    NewTaxOrderItem {
+                        order_id: self.order_id,
+                        item_type: OrderItemTypes::Tax,
+                        event_id: self.event_id,
+                        quantity: self.quantity,
+                        unit_price_in_cents,
+                        tax_in_cents,
+                        tax_rule_id: Some(tax_rule.id),
+                        parent_id: Some(self.id),
+                        currency: self.currency.clone(),
+                    }
+                    .commit(conn)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     pub(crate) fn update(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
         self.validate_record(conn)?;
         diesel::update(self)
@@ -477,6 +589,8 @@ impl OrderItem {
             cart_item_status: Option<CartItemStatus>,
             #[sql_type = "dUuid"]
             event_id: Uuid,
+            #[sql_type = "Nullable<dUuid>"]
+            bundle_id: Option<Uuid>,
             #[sql_type = "dUuid"]
             order_id: Uuid,
         }
@@ -497,12 +611,16 @@ impl OrderItem {
              WHEN item_type = 'EventFees' THEN 'Event Fees - ' || e.name
              WHEN item_type = 'Discount' THEN 'Discount'
              WHEN item_type = 'CreditCardFees' THEN 'Credit Card Fees'
+             WHEN item_type = 'Tax' THEN COALESCE(tr.name, 'Tax')
+             WHEN item_type = 'ResaleTickets' THEN 'Resale - ' || e.name
+             WHEN b.id IS NOT NULL THEN b.name || ' - ' || e.name || ' - ' || tt.name
              ELSE e.name || ' - ' || tt.name
            END AS description,
            COALESCE(h.redemption_code, c.redemption_code) as redemption_code,
            CASE
              -- Null prevents serialization
              WHEN o.status <> 'Draft' THEN null
+             WHEN item_type = 'ResaleTickets' AND l.status <> 'Published' THEN 'TicketNotReserved'
              WHEN item_type <> 'Tickets' THEN 'Valid'
              WHEN ti.status = 'Nullified' THEN 'TicketNullified'
              WHEN oit.count <> oi.quantity OR ti.reserved_until < now() THEN 'TicketNotReserved'
@@ -511,6 +629,7 @@ impl OrderItem {
              ELSE 'Valid'
            END AS cart_item_status,
            e.id AS event_id,
+           oi.bundle_id,
            oi.order_id
         FROM order_items oi
            JOIN orders o ON oi.order_id = o.id
@@ -531,6 +650,9 @@ impl OrderItem {
                LIMIT 1
            )
            LEFT JOIN codes c ON oi.code_id = c.id
+           LEFT JOIN tax_rules tr ON oi.tax_rule_id = tr.id
+           LEFT JOIN listings l ON oi.listing_id = l.id
+           LEFT JOIN bundles b ON oi.bundle_id = b.id
            LEFT JOIN (
                SELECT count(ti.id) as count, oi.id
                FROM order_items oi
@@ -579,6 +701,7 @@ impl OrderItem {
                     redemption_code: item.redemption_code,
                     cart_item_status: item.cart_item_status,
                     event_id: item.event_id,
+                    bundle_id: item.bundle_id,
                 });
             }
             order_items.insert(order_id, display_items);
@@ -629,6 +752,7 @@ pub(crate) struct NewTicketsOrderItem {
     pub ticket_pricing_id: Uuid,
     pub hold_id: Option<Uuid>,
     pub code_id: Option<Uuid>,
+    pub currency: String,
 }
 
 impl NewTicketsOrderItem {
@@ -666,6 +790,66 @@ impl NewTicketsOrderItem {
     }
 }
 
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug)]
+#[table_name = "order_items"]
+pub(crate) struct NewResaleOrderItem {
+    pub order_id: Uuid,
+    pub item_type: OrderItemTypes,
+    pub event_id: Option<Uuid>,
+    pub quantity: i64,
+    pub unit_price_in_cents: i64,
+    pub listing_id: Option<Uuid>,
+    pub currency: String,
+}
+
+impl NewResaleOrderItem {
+    pub(crate) fn commit(self, conn: &PgConnection) -> Result<OrderItem, DatabaseError> {
+        diesel::insert_into(order_items::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create order item")
+    }
+}
+
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug)]
+#[table_name = "order_items"]
+pub(crate) struct NewBundleOrderItem {
+    pub order_id: Uuid,
+    pub item_type: OrderItemTypes,
+    pub event_id: Option<Uuid>,
+    pub quantity: i64,
+    pub unit_price_in_cents: i64,
+    pub ticket_type_id: Uuid,
+    pub ticket_pricing_id: Uuid,
+    pub bundle_id: Option<Uuid>,
+    pub currency: String,
+}
+
+impl NewBundleOrderItem {
+    pub(crate) fn commit(self, conn: &PgConnection) -> Result<OrderItem, DatabaseError> {
+        self.validate_record(conn)?;
+        diesel::insert_into(order_items::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create order item")
+    }
+
+    pub fn validate_record(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        let validation_errors = validators::append_validation_error(
+            Ok(()),
+            "quantity",
+            OrderItem::quantity_valid_increment(
+                true,
+                self.item_type.clone(),
+                self.quantity,
+                Some(self.ticket_pricing_id),
+                conn,
+            )?,
+        );
+        Ok(validation_errors?)
+    }
+}
+
 #[derive(Insertable, Serialize, Deserialize, PartialEq, Debug)]
 #[table_name = "order_items"]
 pub(crate) struct NewFeesOrderItem {
@@ -678,6 +862,7 @@ pub(crate) struct NewFeesOrderItem {
     pub company_fee_in_cents: i64,
     pub client_fee_in_cents: i64,
     pub parent_id: Option<Uuid>,
+    pub currency: String,
 }
 
 impl NewFeesOrderItem {
@@ -700,6 +885,7 @@ pub(crate) struct NewDiscountOrderItem {
     pub company_fee_in_cents: i64,
     pub client_fee_in_cents: i64,
     pub parent_id: Option<Uuid>,
+    pub currency: String,
 }
 
 impl NewDiscountOrderItem {
@@ -711,6 +897,29 @@ impl NewDiscountOrderItem {
     }
 }
 
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug)]
+#[table_name = "order_items"]
+pub(crate) struct NewTaxOrderItem {
+    pub order_id: Uuid,
+    pub item_type: OrderItemTypes,
+    pub event_id: Option<Uuid>,
+    pub quantity: i64,
+    pub unit_price_in_cents: i64,
+    pub tax_in_cents: i64,
+    pub tax_rule_id: Option<Uuid>,
+    pub parent_id: Option<Uuid>,
+    pub currency: String,
+}
+
+impl NewTaxOrderItem {
+    pub(crate) fn commit(self, conn: &PgConnection) -> Result<OrderItem, DatabaseError> {
+        diesel::insert_into(order_items::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create order item")
+    }
+}
+
 #[derive(Deserialize, Queryable, QueryableByName, Serialize)]
 pub struct DisplayOrderItem {
     #[sql_type = "dUuid"]
@@ -739,4 +948,6 @@ pub struct DisplayOrderItem {
     pub cart_item_status: Option<CartItemStatus>,
     #[sql_type = "dUuid"]
     pub event_id: Uuid,
+    #[sql_type = "Nullable<dUuid>"]
+    pub bundle_id: Option<Uuid>,
 }
@@ -19,6 +19,7 @@ use schema::{
 use serde_json;
 use serde_json::Value;
 use std::borrow::Cow;
+use std::cmp;
 use std::collections::HashMap;
 use url::Url;
 use utils::dates::*;
@@ -358,12 +359,23 @@ impl Order {
             }
 
             let mut update_data = Vec::new();
+            let mut bundle_quantities: HashMap<Uuid, u32> = HashMap::new();
             let redemption_code = self.redemption_code(conn)?;
             for item in self.items(conn)? {
                 if item.item_type != OrderItemTypes::Tickets {
                     continue;
                 }
 
+                if let (Some(bundle_id), Some(ticket_type_id)) = (item.bundle_id, item.ticket_type_id) {
+                    if !bundle_quantities.contains_key(&bundle_id) {
+                        let bundle_ticket_type =
+                            BundleTicketType::find_by_bundle_id_ticket_type_id(bundle_id, ticket_type_id, conn)?;
+                        bundle_quantities
+                            .insert(bundle_id, (item.quantity / bundle_ticket_type.quantity as i64) as u32);
+                    }
+                    continue;
+                }
+
                 if let Some(ticket_type_id) = item.ticket_type_id {
                     update_data.push(UpdateOrderItem {
                         quantity: item.quantity as u32,
@@ -378,6 +390,12 @@ impl Order {
                 .map_err(|_err| {
                     DatabaseError::business_process_error::<()>("Order is invalid for duplication").unwrap_err()
                 })?;
+            for (bundle_id, quantity) in bundle_quantities {
+                cart.update_bundle_quantity(self.user_id, bundle_id, quantity, conn)
+                    .map_err(|_err| {
+                        DatabaseError::business_process_error::<()>("Order is invalid for duplication").unwrap_err()
+                    })?;
+            }
             return Ok(cart);
         } else {
             return DatabaseError::business_process_error("Order is invalid for duplication");
@@ -433,10 +451,49 @@ impl Order {
             "event_id",
             Order::order_contains_items_from_only_one_event(self.id, conn)?,
         );
+        let validation_errors = append_validation_error(
+            validation_errors,
+            "currency",
+            Order::order_contains_items_in_only_one_currency(self.id, conn)?,
+        );
 
         Ok(validation_errors?)
     }
 
+    /// Currency the order is charged in, orders without items have no currency
+    pub fn currency(&self, conn: &PgConnection) -> Result<Option<String>, DatabaseError> {
+        order_items::table
+            .filter(order_items::order_id.eq(self.id))
+            .select(order_items::currency)
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Could not load currency for order")
+    }
+
+    pub fn order_contains_items_in_only_one_currency(
+        id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Result<(), ValidationError>, DatabaseError> {
+        let currency_count = order_items::table
+            .filter(order_items::order_id.eq(id))
+            .select(sql::<BigInt>("count(distinct currency) AS currency_count"))
+            .get_result::<i64>(conn)
+            .to_db_error(
+                ErrorCode::QueryError,
+                "Could not get count of unique currencies in cart",
+            )?;
+
+        if currency_count > 1 {
+            let mut validation_error = create_validation_error(
+                "cart_currency_mismatch",
+                "Your cart contains tickets priced in another currency. Please clear your cart first to purchase these tickets.",
+            );
+            validation_error.add_param(Cow::from("order_id"), &id);
+            return Ok(Err(validation_error.into()));
+        }
+        Ok(Ok(()))
+    }
+
     pub fn order_contains_items_from_only_one_event(
         id: Uuid,
         conn: &PgConnection,
@@ -444,6 +501,8 @@ impl Order {
         let event_count = order_items::table
             .filter(order_items::order_id.eq(id))
             .filter(order_items::event_id.is_not_null())
+            // Bundles issue tickets for several events by design
+            .filter(order_items::bundle_id.is_null())
             .select(sql::<BigInt>("count(distinct event_id) AS event_count"))
             .get_result::<i64>(conn)
             .to_db_error(ErrorCode::QueryError, "Could not get count of unique events in cart")?;
@@ -498,7 +557,17 @@ impl Order {
             );
         }
 
-        // delete children order items
+        // delete children order items along with their own children (e.g. taxes charged on fees)
+        let child_ids: Vec<Uuid> = order_items::table
+            .filter(order_items::parent_id.eq(item_id))
+            .select(order_items::id)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load child order items")?;
+        diesel::delete(order_items::table.filter(order_items::parent_id.eq_any(child_ids)))
+            .execute(conn)
+            .map(|_| ())
+            .to_db_error(ErrorCode::DeleteError, "Could not delete child order item")?;
+
         diesel::delete(order_items::table.filter(order_items::parent_id.eq(item_id)))
             .execute(conn)
             .map(|_| ())
@@ -582,6 +651,10 @@ impl Order {
             let mut order_item = OrderItem::find(refund_datum.order_item_id, conn)?;
             if order_item.item_type == OrderItemTypes::Discount {
                 return DatabaseError::business_process_error("Discount order items can not be refunded");
+            } else if order_item.item_type == OrderItemTypes::Tax {
+                return DatabaseError::business_process_error("Tax order items can not be refunded directly");
+            } else if order_item.item_type == OrderItemTypes::ResaleTickets {
+                return DatabaseError::business_process_error("Resale order items can not be refunded");
             } else if order_item.order_id != self.id {
                 return DatabaseError::business_process_error("Order item id does not belong to this order");
             }
@@ -670,6 +743,9 @@ impl Order {
         if ticket_instance.status == TicketInstanceStatus::Purchased && order_item.item_type == OrderItemTypes::Tickets
         {
             ticket_instance.release(TicketInstanceStatus::Purchased, user_id, conn)?;
+            if let Some(ticket_type_id) = order_item.ticket_type_id {
+                WaitlistEntry::queue_processing(ticket_type_id, None, conn)?;
+            }
         }
 
         order_item.refund_one_unit(refund_fees, conn)
@@ -1017,6 +1093,14 @@ impl Order {
             .to_db_error(ErrorCode::QueryError, "Could not find order")
     }
 
+    pub(crate) fn find_for_update(id: Uuid, conn: &PgConnection) -> Result<Order, DatabaseError> {
+        orders::table
+            .filter(orders::id.eq(id))
+            .for_update()
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find order")
+    }
+
     pub fn search(
         event_id: Option<Uuid>,
         organization_id: Option<Uuid>,
@@ -1318,8 +1402,13 @@ impl Order {
     pub fn clear_cart(&mut self, user_id: Uuid, conn: &PgConnection) -> Result<(), DatabaseError> {
         jlog!(Level::Debug, "Clearing cart");
         self.lock_version(conn)?;
+        self.return_stored_value_for_cart_change(user_id, conn)?;
 
         for current_line in self.items(conn)? {
+            if current_line.item_type == OrderItemTypes::ResaleTickets {
+                self.destroy_item(current_line.id, conn)?;
+                continue;
+            }
             if current_line.item_type != OrderItemTypes::Tickets {
                 continue;
             }
@@ -1340,6 +1429,7 @@ impl Order {
         conn: &PgConnection,
     ) -> Result<(), DatabaseError> {
         self.lock_version(conn)?;
+        self.return_stored_value_for_cart_change(current_user_id, conn)?;
 
         jlog!(Debug, "Update order quantities", {"items": items,"remove_others":remove_others, "user_id": current_user_id, "box_office_pricing":box_office_pricing });
 
@@ -1401,7 +1491,8 @@ impl Order {
         }
 
         for mut current_line in current_items {
-            if current_line.item_type != OrderItemTypes::Tickets {
+            // Bundled tickets are managed through update_bundle_quantity
+            if current_line.item_type != OrderItemTypes::Tickets || current_line.bundle_id.is_some() {
                 continue;
             }
 
@@ -1436,49 +1527,19 @@ impl Order {
                         // Ticket pricing might have changed since we added the previous item.
                         // In future we may want to use the ticket pricing at the time the order was created.
 
-                        // TODO: Fetch the ticket type and pricing in one go.
-                        let ticket_type_id = current_line.ticket_type_id.unwrap();
-                        let ticket_pricing =
-                            TicketPricing::get_current_ticket_pricing(ticket_type_id, box_office_pricing, false, conn)?;
-                        let ticket_type = TicketType::find(ticket_type_id, conn)?;
+                        let ticket_type = TicketType::find(current_line.ticket_type_id.unwrap(), conn)?;
                         check_ticket_limits.append(&mut Order::check_ticket_limits(&ticket_type, &match_data));
 
-                        // TODO: Move this to an external processer
-                        if Some(ticket_pricing.id) != current_line.ticket_pricing_id {
-                            let price_in_cents = ticket_pricing.price_in_cents;
-
-                            let order_item = NewTicketsOrderItem {
-                                order_id: self.id,
-                                item_type: OrderItemTypes::Tickets,
-                                quantity: match_data.update_order_item.quantity as i64,
-                                ticket_type_id: ticket_type.id,
-                                ticket_pricing_id: ticket_pricing.id,
-                                event_id: Some(ticket_type.event_id),
-                                unit_price_in_cents: price_in_cents,
-                                hold_id: match_data.hold_id,
-                                code_id: match_data.code_id,
-                            }
-                            .commit(conn)?;
-                            TicketInstance::reserve_tickets(
-                                &order_item,
-                                self.expires_at,
-                                ticket_type_id,
-                                match_data.hold_id,
-                                match_data.update_order_item.quantity - current_line.quantity as u32,
-                                conn,
-                            )?;
-                        } else {
-                            TicketInstance::reserve_tickets(
-                                &current_line,
-                                self.expires_at,
-                                ticket_type_id,
-                                match_data.hold_id,
-                                match_data.update_order_item.quantity - current_line.quantity as u32,
-                                conn,
-                            )?;
-                            current_line.quantity = match_data.update_order_item.quantity as i64;
-                            current_line.update(conn)?;
-                        }
+                        let quantity = match_data.update_order_item.quantity - current_line.quantity as u32;
+                        self.add_tickets_at_current_pricing(
+                            &ticket_type,
+                            match_data,
+                            quantity,
+                            Some(&mut current_line),
+                            box_office_pricing,
+                            current_user_id,
+                            conn,
+                        )?;
                     }
                 } else if remove_others {
                     jlog!(Level::Debug, "Removing extra tickets because remove others was called.", { "order_item.id": current_line.id, "ticket_type_id": current_line.ticket_type_id});
@@ -1508,46 +1569,320 @@ impl Order {
             }
 
             jlog!(Level::Debug, "Adding new cart items");
-            let ticket_pricing = TicketPricing::get_current_ticket_pricing(
-                match_data.update_order_item.ticket_type_id,
+            let ticket_type = TicketType::find(match_data.update_order_item.ticket_type_id, conn)?;
+            check_ticket_limits.append(&mut Order::check_ticket_limits(&ticket_type, &match_data));
+
+            self.add_tickets_at_current_pricing(
+                &ticket_type,
+                &match_data,
+                match_data.update_order_item.quantity,
+                None,
                 box_office_pricing,
-                false,
+                current_user_id,
                 conn,
             )?;
+        }
 
-            let ticket_type = TicketType::find(match_data.update_order_item.ticket_type_id, conn)?;
-            check_ticket_limits.append(&mut Order::check_ticket_limits(&ticket_type, &match_data));
+        // if the cart is empty at this point, it is effectively a new cart, remove expiration
+        if self.items(conn)?.len() == 0 {
+            self.remove_expiry(current_user_id, conn)?;
+        }
+        self.validate_ticket_limits(check_ticket_limits, conn)?;
+        self.update_fees_and_discounts(conn)?;
+        self.validate_record(conn)?;
+        // Beware there could be multiple orders that meet this condition
+        for (ticket_type_id, remaining) in self.ticket_types(conn)? {
+            if remaining == 0 {
+                TicketType::find(ticket_type_id, conn)?.check_for_sold_out_triggers(Some(current_user_id), conn)?;
+            }
+        }
 
-            let price_in_cents = ticket_pricing.price_in_cents;
+        Ok(())
+    }
 
-            // TODO: Move this to an external processer
-            let order_item = NewTicketsOrderItem {
-                order_id: self.id,
-                item_type: OrderItemTypes::Tickets,
-                quantity: match_data.update_order_item.quantity as i64,
+    /// Adds tickets to the cart at the current pricing. A quantity crossing a tier's sold quantity cap
+    /// is split so only the tickets under the cap are sold at that tier's price and the rest at the
+    /// following tier's.
+    fn add_tickets_at_current_pricing(
+        &self,
+        ticket_type: &TicketType,
+        match_data: &MatchData,
+        quantity: u32,
+        mut current_line: Option<&mut OrderItem>,
+        box_office_pricing: bool,
+        current_user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        let mut remaining = quantity;
+        let mut sold_out_ticket_pricing_id = None;
+        while remaining > 0 {
+            // TODO: Fetch the ticket type and pricing in one go.
+            let ticket_pricing =
+                TicketPricing::get_current_ticket_pricing(ticket_type.id, box_office_pricing, false, conn)?;
+            let tier_quantity = ticket_pricing.quantity_available_for_sale(remaining, conn)?;
+            if tier_quantity == 0 {
+                // The cap was reached by another cart, the tier is skipped once it is ended below
+                if sold_out_ticket_pricing_id == Some(ticket_pricing.id) {
+                    return DatabaseError::business_process_error("Ticket pricing has reached its sold quantity cap");
+                }
+                sold_out_ticket_pricing_id = Some(ticket_pricing.id);
+            } else {
+                match current_line {
+                    Some(ref mut current_line) if current_line.ticket_pricing_id == Some(ticket_pricing.id) => {
+                        TicketInstance::reserve_tickets(
+                            current_line,
+                            self.expires_at,
+                            ticket_type.id,
+                            match_data.hold_id,
+                            tier_quantity,
+                            conn,
+                        )?;
+                        current_line.quantity += tier_quantity as i64;
+                        current_line.update(conn)?;
+                    }
+                    _ => {
+                        // TODO: Move this to an external processer
+                        let order_item = NewTicketsOrderItem {
+                            order_id: self.id,
+                            item_type: OrderItemTypes::Tickets,
+                            quantity: tier_quantity as i64,
+                            ticket_type_id: ticket_type.id,
+                            ticket_pricing_id: ticket_pricing.id,
+                            event_id: Some(ticket_type.event_id),
+                            unit_price_in_cents: ticket_pricing.price_in_cents,
+                            hold_id: match_data.hold_id,
+                            code_id: match_data.code_id,
+                            currency: ticket_pricing.currency.clone(),
+                        }
+                        .commit(conn)?;
+                        TicketInstance::reserve_tickets(
+                            &order_item,
+                            self.expires_at,
+                            ticket_type.id,
+                            match_data.hold_id,
+                            tier_quantity,
+                            conn,
+                        )?;
+                    }
+                }
+                remaining -= tier_quantity;
+            }
+            ticket_pricing.check_sold_quantity_cap(Some(current_user_id), conn)?;
+        }
+        Ok(())
+    }
+
+    /// Adds specific seats for a seated ticket type to the cart. The seated tickets are reserved
+    /// until the cart expires, after which they return to the available pool.
+    pub fn reserve_seats(
+        &mut self,
+        current_user_id: Uuid,
+        ticket_type_id: Uuid,
+        seat_ids: &[Uuid],
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        self.lock_version(conn)?;
+
+        jlog!(Debug, "Reserve seats", {"ticket_type_id": ticket_type_id, "seat_ids": seat_ids, "user_id": current_user_id});
+
+        if seat_ids.is_empty() {
+            return DatabaseError::validation_error("seat_ids", "At least one seat is required");
+        }
+
+        // Set cart expiration time if not currently set (empty carts have no expiration)
+        if self.expires_at.is_none() {
+            self.set_expiry(Some(current_user_id), None, false, conn)?;
+        }
+
+        let ticket_type = TicketType::find(ticket_type_id, conn)?;
+        let ticket_pricing =
+            TicketPricing::get_current_ticket_pricing(ticket_type.id, self.box_office_pricing, false, conn)?;
+
+        let order_item = NewTicketsOrderItem {
+            order_id: self.id,
+            item_type: OrderItemTypes::Tickets,
+            quantity: seat_ids.len() as i64,
+            ticket_type_id: ticket_type.id,
+            ticket_pricing_id: ticket_pricing.id,
+            event_id: Some(ticket_type.event_id),
+            unit_price_in_cents: ticket_pricing.price_in_cents,
+            hold_id: None,
+            code_id: None,
+            currency: ticket_pricing.currency.clone(),
+        }
+        .commit(conn)?;
+
+        TicketInstance::reserve_seats(&order_item, self.expires_at, ticket_type.id, seat_ids, conn)?;
+
+        self.validate_ticket_limits(
+            vec![LimitCheck {
                 ticket_type_id: ticket_type.id,
-                ticket_pricing_id: ticket_pricing.id,
-                event_id: Some(ticket_type.event_id),
-                unit_price_in_cents: price_in_cents,
-                hold_id: match_data.hold_id,
-                code_id: match_data.code_id,
+                hold_id: None,
+                code_id: None,
+                limit_per_person: ticket_type.limit_per_person as u32,
+                redemption_code: None,
+            }],
+            conn,
+        )?;
+        self.update_fees_and_discounts(conn)?;
+        self.validate_record(conn)?;
+        if ticket_type.valid_available_ticket_count(conn)? == 0 {
+            ticket_type.check_for_sold_out_triggers(Some(current_user_id), conn)?;
+        }
+
+        Ok(())
+    }
+
+    /// Adds a published resale listing to the cart. The listing is claimed by this cart until the
+    /// cart expires, after which it becomes available to other buyers again.
+    pub fn add_listing(
+        &mut self,
+        current_user_id: Uuid,
+        listing_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        self.lock_version(conn)?;
+        self.return_stored_value_for_cart_change(current_user_id, conn)?;
+
+        jlog!(Debug, "Add listing", {"listing_id": listing_id, "user_id": current_user_id});
+
+        if self.box_office_pricing {
+            return DatabaseError::business_process_error("Resale listings cannot be purchased through the box office");
+        }
+
+        let listing = Listing::find_for_update(listing_id, conn)?;
+        if listing.user_id == self.on_behalf_of_user_id.unwrap_or(self.user_id) {
+            return DatabaseError::validation_error("listing_id", "You cannot purchase your own listing");
+        }
+        if self.items(conn)?.iter().any(|i| i.listing_id == Some(listing.id)) {
+            return DatabaseError::validation_error("listing_id", "Listing is already in the cart");
+        }
+        let event_id = match listing.event_id {
+            Some(event_id) if listing.is_available(Some(self.id), conn)? => event_id,
+            _ => return DatabaseError::validation_error("listing_id", "Listing is no longer available"),
+        };
+
+        // Set cart expiration time if not currently set (empty carts have no expiration)
+        if self.expires_at.is_none() {
+            self.set_expiry(Some(current_user_id), None, false, conn)?;
+        }
+
+        let event = Event::find(event_id, conn)?;
+        NewResaleOrderItem {
+            order_id: self.id,
+            item_type: OrderItemTypes::ResaleTickets,
+            event_id: Some(event.id),
+            quantity: listing.tickets(conn)?.len() as i64,
+            unit_price_in_cents: listing.asking_price_in_cents,
+            listing_id: Some(listing.id),
+            currency: event.currency.clone(),
+        }
+        .commit(conn)?;
+
+        self.update_fees_and_discounts(conn)?;
+        self.validate_record(conn)?;
+
+        Ok(())
+    }
+
+    /// Sets the number of a bundle in the cart. Each ticket type in the bundle is added as its own
+    /// cart item against its event, priced at the revenue the bundle allocates to it.
+    pub fn update_bundle_quantity(
+        &mut self,
+        current_user_id: Uuid,
+        bundle_id: Uuid,
+        quantity: u32,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        self.lock_version(conn)?;
+        self.return_stored_value_for_cart_change(current_user_id, conn)?;
+
+        jlog!(Debug, "Update bundle quantity", {"bundle_id": bundle_id, "quantity": quantity, "user_id": current_user_id});
+
+        let bundle = Bundle::find(bundle_id, conn)?;
+        for current_line in self.items(conn)? {
+            if current_line.item_type != OrderItemTypes::Tickets || current_line.bundle_id != Some(bundle.id) {
+                continue;
+            }
+            let current_quantity = current_line.calculate_quantity(conn)?;
+            TicketInstance::release_tickets(&current_line, current_quantity as u32, Some(current_user_id), conn)?;
+            self.destroy_item(current_line.id, conn)?;
+        }
+
+        let mut check_ticket_limits: Vec<LimitCheck> = vec![];
+        if quantity > 0 {
+            if bundle.deleted_at.is_some() {
+                return DatabaseError::validation_error("bundle_id", "Bundle is no longer available");
+            }
+            let bundle_ticket_types = bundle.ticket_types(conn)?;
+            if bundle_ticket_types.is_empty() {
+                return DatabaseError::validation_error("bundle_id", "Bundle does not contain any tickets");
             }
-            .commit(conn);
 
-            TicketInstance::reserve_tickets(
-                &order_item?,
-                self.expires_at,
-                match_data.update_order_item.ticket_type_id,
-                match_data.hold_id,
-                match_data.update_order_item.quantity,
-                conn,
-            )?;
+            // Set cart expiration time if not currently set (empty carts have no expiration)
+            if self.expires_at.is_none() {
+                self.set_expiry(Some(current_user_id), None, false, conn)?;
+            }
+
+            for bundle_ticket_type in bundle_ticket_types {
+                let ticket_type = TicketType::find(bundle_ticket_type.ticket_type_id, conn)?;
+                let ticket_pricing =
+                    TicketPricing::get_current_ticket_pricing(ticket_type.id, self.box_office_pricing, false, conn)?;
+                let item_quantity = bundle_ticket_type.quantity as u32 * quantity;
+
+                let order_item = NewBundleOrderItem {
+                    order_id: self.id,
+                    item_type: OrderItemTypes::Tickets,
+                    event_id: Some(ticket_type.event_id),
+                    quantity: item_quantity as i64,
+                    unit_price_in_cents: bundle_ticket_type.unit_price_in_cents,
+                    ticket_type_id: ticket_type.id,
+                    ticket_pricing_id: ticket_pricing.id,
+                    bundle_id: Some(bundle.id),
+                    currency: bundle.currency.clone(),
+                }
+                .commit(conn)?;
+
+                TicketInstance::reserve_tickets(
+                    &order_item,
+                    self.expires_at,
+                    ticket_type.id,
+                    None,
+                    item_quantity,
+                    conn,
+                )?;
+
+                check_ticket_limits.push(LimitCheck {
+                    ticket_type_id: ticket_type.id,
+                    hold_id: None,
+                    code_id: None,
+                    limit_per_person: ticket_type.limit_per_person as u32,
+                    redemption_code: None,
+                });
+            }
         }
 
         // if the cart is empty at this point, it is effectively a new cart, remove expiration
         if self.items(conn)?.len() == 0 {
             self.remove_expiry(current_user_id, conn)?;
         }
+        self.validate_ticket_limits(check_ticket_limits, conn)?;
+        self.update_fees_and_discounts(conn)?;
+        self.validate_record(conn)?;
+        for (ticket_type_id, remaining) in self.ticket_types(conn)? {
+            if remaining == 0 {
+                TicketType::find(ticket_type_id, conn)?.check_for_sold_out_triggers(Some(current_user_id), conn)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_ticket_limits(
+        &self,
+        check_ticket_limits: Vec<LimitCheck>,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
         for limit_check in check_ticket_limits {
             let ordered_quantity = Order::quantity_for_user_for_ticket_type(
                 self.user_id,
@@ -1584,15 +1919,6 @@ impl Order {
                 return Err(errors.into());
             }
         }
-        self.update_fees_and_discounts(conn)?;
-        self.validate_record(conn)?;
-        // Beware there could be multiple orders that meet this condition
-        for (ticket_type_id, remaining) in self.ticket_types(conn)? {
-            if remaining == 0 {
-                TicketType::find(ticket_type_id, conn)?.check_for_sold_out_triggers(Some(current_user_id), conn)?;
-            }
-        }
-
         Ok(())
     }
 
@@ -1643,8 +1969,20 @@ impl Order {
             }
         }
 
-        // Box office purchased tickets do not have fees at this time
+        // Box office purchased tickets do not have fees at this time but are still taxed
         if self.box_office_pricing {
+            for (event_id, items) in self
+                .items(conn)?
+                .into_iter()
+                .filter(|i| i.item_type == OrderItemTypes::Tickets)
+                .filter_map(|i| i.event_id.map(|event_id| (event_id, i)))
+                .into_group_map()
+            {
+                let tax_rules = TaxRule::find_for_event(&Event::find(event_id, conn)?, conn)?;
+                for o in items {
+                    o.update_taxes(&self, &tax_rules, conn)?;
+                }
+            }
             return Ok(());
         }
 
@@ -1671,6 +2009,7 @@ impl Order {
 
             let event_id = event_id.unwrap();
             let event = Event::find(event_id, conn)?;
+            let tax_rules = TaxRule::find_for_event(&event, conn)?;
 
             let mut all_zero_price = true;
 
@@ -1685,6 +2024,10 @@ impl Order {
                         };
 
                         o.update_fees(&self, conn)?;
+                        o.update_taxes(&self, &tax_rules, conn)?;
+                        if let Some(fee_item) = o.find_fee_item(conn)? {
+                            fee_item.update_taxes(&self, &tax_rules, conn)?;
+                        }
                         if unit_price_with_discount > 0 {
                             all_zero_price = false;
                         }
@@ -1707,6 +2050,7 @@ impl Order {
                     client_fee_in_cents: 0,
                     quantity: 1,
                     parent_id: None,
+                    currency: event.currency.clone(),
                 };
 
                 // Credit card fees are set per organization
@@ -1719,7 +2063,7 @@ impl Order {
                     new_event_fee.company_fee_in_cents = company_fee_in_cents;
                     new_event_fee.client_fee_in_cents = client_fee_in_cents;
                     new_event_fee.unit_price_in_cents = client_fee_in_cents + company_fee_in_cents;
-                    new_event_fee.commit(conn)?;
+                    new_event_fee.commit(conn)?.update_taxes(&self, &tax_rules, conn)?;
                     per_event_fees_included.insert(event_id, true);
                 }
 
@@ -1735,6 +2079,7 @@ impl Order {
                         client_fee_in_cents: 0,
                         quantity: 1,
                         parent_id: None,
+                        currency: event.currency.clone(),
                     }
                     .commit(conn)?;
                 }
@@ -2224,6 +2569,11 @@ impl Order {
                         provider: PaymentProviders::Stripe,
                         display_name: "Card".to_string(),
                     }),
+                    PaymentProviders::Adyen | PaymentProviders::Mock => Some(AllowedPaymentMethod {
+                        method: "Card".to_string(),
+                        provider: p,
+                        display_name: "Card".to_string(),
+                    }),
                     PaymentProviders::Globee => Some(AllowedPaymentMethod {
                         method: "Provider".to_string(),
                         provider: PaymentProviders::Globee,
@@ -2487,6 +2837,231 @@ impl Order {
         self.add_payment(payment, Some(current_user_id), conn)
     }
 
+    /// Card payment authorized by the processor on condition of the shopper passing an issuer
+    /// challenge (e.g. 3D Secure). The cart is kept until the challenge is completed from the
+    /// payment callback.
+    pub fn add_challenged_credit_card_payment(
+        &mut self,
+        current_user_id: Uuid,
+        amount: i64,
+        provider: PaymentProviders,
+        external_reference: String,
+        url_nonce: String,
+        provider_data: serde_json::Value,
+        conn: &PgConnection,
+    ) -> Result<Payment, DatabaseError> {
+        let payment = Payment::create(
+            self.id,
+            Some(current_user_id),
+            PaymentStatus::Requested,
+            PaymentMethods::CreditCard,
+            provider,
+            Some(external_reference),
+            amount,
+            Some(provider_data),
+            Some(url_nonce),
+            None,
+        );
+
+        self.add_payment(payment, Some(current_user_id), conn)
+    }
+
+    /// Pays as much of the amount due as the gift card balance allows, the remainder can be paid
+    /// with further gift cards, account credit or a payment method
+    pub fn add_gift_card_payment(
+        &mut self,
+        gift_card: &GiftCard,
+        current_user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Payment, DatabaseError> {
+        if self.status != OrderStatus::Draft {
+            return DatabaseError::business_process_error("Gift cards can only be applied to draft orders");
+        }
+
+        let gift_card = GiftCard::find_for_update(gift_card.id, conn)?;
+        if gift_card.deactivated_at.is_some() {
+            return DatabaseError::validation_error("gift_card", "Gift card is no longer active");
+        } else if gift_card
+            .expires_at
+            .map(|e| e <= Utc::now().naive_utc())
+            .unwrap_or(false)
+        {
+            return DatabaseError::validation_error("gift_card", "Gift card has expired");
+        } else if gift_card.balance_in_cents == 0 {
+            return DatabaseError::validation_error("gift_card", "Gift card has no remaining balance");
+        }
+
+        // Redemptions are deducted from the settlement of the event they paid for
+        let events = self.events(conn)?;
+        if events.len() != 1 {
+            return DatabaseError::validation_error(
+                "gift_card",
+                "Gift cards can only be applied to orders for a single event",
+            );
+        } else if events[0].organization_id != gift_card.organization_id {
+            return DatabaseError::validation_error("gift_card", "Gift card is not valid for this event");
+        } else if self.currency(conn)?.as_ref() != Some(&gift_card.currency) {
+            return DatabaseError::validation_error("gift_card", "Gift card currency does not match the order");
+        }
+
+        let amount = cmp::min(gift_card.balance_in_cents, self.amount_due(conn)?);
+        if amount == 0 {
+            return DatabaseError::business_process_error("Order has no amount due");
+        }
+
+        let payment = Payment::create(
+            self.id,
+            Some(current_user_id),
+            PaymentStatus::Completed,
+            PaymentMethods::GiftCard,
+            PaymentProviders::External,
+            Some(gift_card.id.to_string()),
+            amount,
+            None,
+            None,
+            None,
+        );
+        let payment = self.add_payment(payment, Some(current_user_id), conn)?;
+        gift_card.change_balance(
+            StoredValueLedgerEntryTypes::Redeemed,
+            -amount,
+            Some(self.id),
+            Some(payment.id),
+            None,
+            Some(current_user_id),
+            conn,
+        )?;
+        self.schedule_stored_value_return(conn)?;
+
+        Ok(payment)
+    }
+
+    /// Pays as much of the amount due as the purchaser's account credit allows
+    pub fn add_account_credit_payment(
+        &mut self,
+        current_user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Payment, DatabaseError> {
+        if self.status != OrderStatus::Draft {
+            return DatabaseError::business_process_error("Account credit can only be applied to draft orders");
+        }
+
+        let account_credit = match self.currency(conn)? {
+            Some(currency) => AccountCredit::find_for_update(self.user_id, &currency, conn).optional()?,
+            None => None,
+        };
+        let account_credit = match account_credit {
+            Some(account_credit) if account_credit.balance_in_cents > 0 => account_credit,
+            _ => {
+                return DatabaseError::validation_error("account_credit", "No account credit is available");
+            }
+        };
+
+        let amount = cmp::min(account_credit.balance_in_cents, self.amount_due(conn)?);
+        if amount == 0 {
+            return DatabaseError::business_process_error("Order has no amount due");
+        }
+
+        let payment = Payment::create(
+            self.id,
+            Some(current_user_id),
+            PaymentStatus::Completed,
+            PaymentMethods::AccountCredit,
+            PaymentProviders::External,
+            Some(account_credit.id.to_string()),
+            amount,
+            None,
+            None,
+            None,
+        );
+        let payment = self.add_payment(payment, Some(current_user_id), conn)?;
+        account_credit.change_balance(
+            StoredValueLedgerEntryTypes::Redeemed,
+            -amount,
+            Some(self.id),
+            Some(payment.id),
+            None,
+            Some(current_user_id),
+            conn,
+        )?;
+        self.schedule_stored_value_return(conn)?;
+
+        Ok(payment)
+    }
+
+    /// Queues returning gift card and account credit payments to their balances if the order is
+    /// still unpaid when it expires, e.g. when the rest of the payment is abandoned at a card
+    /// challenge or payment page or fails after the stored value was applied
+    pub fn schedule_stored_value_return(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        if self.status == OrderStatus::Paid {
+            return Ok(());
+        }
+        // Carts that were extended need a new action after the new expiry
+        let scheduled_at = self.expires_at.unwrap_or_else(|| Utc::now().naive_utc());
+        let already_scheduled = DomainAction::find_by_resource(
+            Some(Tables::Orders),
+            Some(self.id),
+            DomainActionTypes::ReturnStoredValue,
+            DomainActionStatus::Pending,
+            conn,
+        )?
+        .iter()
+        // Stored times lose sub-microsecond precision
+        .any(|action| action.scheduled_at >= scheduled_at - Duration::seconds(1));
+        if already_scheduled {
+            return Ok(());
+        }
+
+        let mut action = DomainAction::create(
+            None,
+            DomainActionTypes::ReturnStoredValue,
+            None,
+            json!({}),
+            Some(Tables::Orders),
+            Some(self.id),
+        );
+        action.schedule_at(scheduled_at);
+        action.commit(conn)?;
+        Ok(())
+    }
+
+    /// Returns the gift card and account credit payments of an order that was never paid
+    pub fn return_stored_value(
+        &mut self,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Vec<StoredValueLedgerEntry>, DatabaseError> {
+        if self.status != OrderStatus::Draft && self.status != OrderStatus::PendingPayment {
+            return DatabaseError::business_process_error(
+                "Stored value can only be returned for orders that have not been paid",
+            );
+        }
+
+        let mut ledger_entries = vec![];
+        for payment in self.payments(conn)? {
+            if payment.status == PaymentStatus::Completed
+                && (payment.payment_method == PaymentMethods::GiftCard
+                    || payment.payment_method == PaymentMethods::AccountCredit)
+            {
+                ledger_entries.push(payment.return_to_stored_value(current_user_id, conn)?);
+            }
+        }
+        Ok(ledger_entries)
+    }
+
+    /// Stored value was checked against the items in the cart when it was applied, so it is given
+    /// back before the cart changes and has to be applied again at checkout
+    fn return_stored_value_for_cart_change(
+        &mut self,
+        current_user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        if self.status == OrderStatus::Draft || self.status == OrderStatus::PendingPayment {
+            self.return_stored_value(Some(current_user_id), conn)?;
+        }
+        Ok(())
+    }
+
     pub fn user(&self, conn: &PgConnection) -> Result<User, DatabaseError> {
         users::table
             .filter(users::id.eq(self.on_behalf_of_user_id.unwrap_or(self.user_id)))
@@ -2547,15 +3122,18 @@ impl Order {
             OrderStatus::Cancelled => {
                 DatabaseError::business_process_error("Cannot reset this order because it has been cancelled")
             }
+            OrderStatus::PartiallyPaid => {
+                DatabaseError::business_process_error("Cannot reset to draft, the order is being paid in installments")
+            }
 
             OrderStatus::Draft => Ok(()),
             OrderStatus::PendingPayment => self.update_status(current_user_id, OrderStatus::Draft, conn),
         }
     }
 
-    fn add_payment(
+    pub(crate) fn add_payment(
         &mut self,
-        payment: NewPayment,
+        mut payment: NewPayment,
         current_user_id: Option<Uuid>,
         conn: &PgConnection,
     ) -> Result<Payment, DatabaseError> {
@@ -2564,12 +3142,20 @@ impl Order {
             item.confirm_code_valid(conn)?;
         }
 
+        if payment.currency.is_none() {
+            payment.currency = self.currency(conn)?;
+        }
+
         let p = payment.commit(current_user_id, conn)?;
-        if p.status != PaymentStatus::Requested {
+        self.complete_if_fully_paid(current_user_id, conn)?;
+
+        // The cart is kept after gift card or account credit payments until the rest is paid
+        let is_stored_value =
+            p.payment_method == PaymentMethods::GiftCard || p.payment_method == PaymentMethods::AccountCredit;
+        if p.status != PaymentStatus::Requested && !(is_stored_value && self.status != OrderStatus::Paid) {
             self.clear_user_cart(conn)?;
         }
 
-        self.complete_if_fully_paid(current_user_id, conn)?;
         Ok(p)
     }
 
@@ -2585,16 +3171,30 @@ impl Order {
         let total_paid = self.total_paid(conn)?;
         let total_required = self.calculate_total(conn)?;
         if total_paid >= total_required {
+            // Orders paid in installments were issued their tickets with the first installment
+            let tickets_issued = self.status == OrderStatus::PartiallyPaid;
             self.update_status(current_user_id, OrderStatus::Paid, conn)?;
             //Mark tickets as Purchased
             let order_items = OrderItem::find_for_order(self.id, conn)?;
             for item in order_items
                 .iter()
-                .filter(|oi| oi.item_type == OrderItemTypes::Tickets)
+                .filter(|oi| !tickets_issued && oi.item_type == OrderItemTypes::Tickets)
                 .collect_vec()
             {
                 TicketInstance::mark_as_purchased(item, self.on_behalf_of_user_id.unwrap_or(self.user_id), conn)?;
             }
+            //Transfer resold tickets from their sellers
+            for item in order_items
+                .iter()
+                .filter(|oi| oi.item_type == OrderItemTypes::ResaleTickets)
+            {
+                Listing::complete_sale(
+                    item,
+                    self.on_behalf_of_user_id.unwrap_or(self.user_id),
+                    current_user_id,
+                    conn,
+                )?;
+            }
 
             let ticket_ids = TicketInstance::find_ids_for_order(self.id, conn)?;
             let domain_event = DomainEvent::create(
@@ -2626,7 +3226,7 @@ impl Order {
         }
     }
 
-    fn clear_user_cart(&mut self, conn: &PgConnection) -> Result<(), DatabaseError> {
+    pub(crate) fn clear_user_cart(&mut self, conn: &PgConnection) -> Result<(), DatabaseError> {
         let cart_user: Option<User> = users::table
             .filter(users::last_cart_id.eq(self.id))
             .get_result(conn)
@@ -2638,6 +3238,11 @@ impl Order {
         Ok(())
     }
 
+    /// Remainder of the order total not yet covered by completed payments
+    pub fn amount_due(&self, conn: &PgConnection) -> Result<i64, DatabaseError> {
+        Ok(cmp::max(self.calculate_total(conn)? - self.total_paid(conn)?, 0))
+    }
+
     pub fn total_paid(&self, conn: &PgConnection) -> Result<i64, DatabaseError> {
         #[derive(QueryableByName)]
         struct ResultForSum {
@@ -2666,7 +3271,14 @@ impl Order {
         self.lock_version(conn)?;
 
         let order_items = self.order_items_in_invalid_state(conn)?;
+        if !order_items.is_empty() {
+            self.return_stored_value_for_cart_change(user_id, conn)?;
+        }
         for item in order_items {
+            if item.item_type == OrderItemTypes::ResaleTickets {
+                self.destroy_item(item.id, conn)?;
+                continue;
+            }
             // Use calculated quantity as reserved may have been taken in the meantime
             let quantity = item.calculate_quantity(conn)?;
             TicketInstance::release_tickets(&item, quantity as u32, Some(user_id), conn)?;
@@ -0,0 +1,264 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::{organization_roles, organization_users};
+use std::str::FromStr;
+use utils::errors::*;
+use uuid::Uuid;
+
+/// Named set of scopes defined by an organization, assigned to members alongside their `Roles`
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(Organization)]
+#[table_name = "organization_roles"]
+pub struct OrganizationRole {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub scopes: Vec<String>,
+    pub deleted_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Deserialize, Insertable, Serialize)]
+#[table_name = "organization_roles"]
+pub struct NewOrganizationRole {
+    pub organization_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub scopes: Vec<String>,
+}
+
+#[derive(Clone, Default, Deserialize, Serialize)]
+pub struct OrganizationRoleEditableAttributes {
+    pub name: Option<String>,
+    #[serde(default, deserialize_with = "double_option_deserialize_unless_blank")]
+    pub description: Option<Option<String>>,
+    pub scopes: Option<Vec<Scopes>>,
+}
+
+impl NewOrganizationRole {
+    pub fn commit(self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<OrganizationRole, DatabaseError> {
+        OrganizationRole::validate_name(self.organization_id, None, &self.name, conn)?;
+        OrganizationRole::validate_scopes(&self.scopes)?;
+
+        let organization_role: OrganizationRole = diesel::insert_into(organization_roles::table)
+            .values(&self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create organization role")?;
+
+        DomainEvent::create(
+            DomainEventTypes::OrganizationRoleCreated,
+            format!("Organization role '{}' created", &organization_role.name),
+            Tables::OrganizationRoles,
+            Some(organization_role.id),
+            current_user_id,
+            Some(json!(self)),
+        )
+        .commit(conn)?;
+
+        Ok(organization_role)
+    }
+}
+
+impl OrganizationRole {
+    pub fn create(
+        organization_id: Uuid,
+        name: String,
+        description: Option<String>,
+        scopes: Vec<Scopes>,
+    ) -> NewOrganizationRole {
+        NewOrganizationRole {
+            organization_id,
+            name,
+            description,
+            scopes: scopes.into_iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<OrganizationRole, DatabaseError> {
+        organization_roles::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find organization role")
+    }
+
+    pub fn find_for_organization(
+        organization_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<OrganizationRole>, DatabaseError> {
+        organization_roles::table
+            .filter(organization_roles::organization_id.eq(organization_id))
+            .filter(organization_roles::deleted_at.is_null())
+            .order_by(organization_roles::name.asc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load organization roles")
+    }
+
+    /// Scopes granted to a user through the custom roles they hold in the organization
+    pub fn scopes_for_user(
+        organization_id: Uuid,
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<Scopes>, DatabaseError> {
+        let role_scopes: Vec<Vec<String>> = organization_roles::table
+            .inner_join(
+                organization_users::table.on(organization_users::organization_id
+                    .eq(organization_roles::organization_id)
+                    .and(organization_roles::id.eq(dsl::any(organization_users::organization_role_ids)))),
+            )
+            .filter(organization_users::organization_id.eq(organization_id))
+            .filter(organization_users::user_id.eq(user_id))
+            .filter(organization_roles::deleted_at.is_null())
+            .select(organization_roles::scopes)
+            .load(conn)
+            .to_db_error(
+                ErrorCode::QueryError,
+                "Could not load organization role scopes for user",
+            )?;
+
+        let mut scopes: Vec<Scopes> = role_scopes
+            .iter()
+            .flatten()
+            .filter_map(|s| Scopes::from_str(s).ok())
+            .collect();
+        scopes.sort();
+        scopes.dedup();
+        Ok(scopes)
+    }
+
+    pub fn organization(&self, conn: &PgConnection) -> Result<Organization, DatabaseError> {
+        Organization::find(self.organization_id, conn)
+    }
+
+    /// Changes to a role's definition apply immediately to every member holding it, the previous
+    /// definition is kept in the domain event for auditing
+    pub fn update(
+        &self,
+        attributes: OrganizationRoleEditableAttributes,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<OrganizationRole, DatabaseError> {
+        if self.deleted_at.is_some() {
+            return DatabaseError::business_process_error("Unable to change a deleted organization role");
+        }
+
+        let name = attributes.name.unwrap_or_else(|| self.name.clone());
+        let description = attributes.description.unwrap_or_else(|| self.description.clone());
+        let scopes: Vec<String> = match attributes.scopes {
+            Some(scopes) => scopes.into_iter().map(|s| s.to_string()).collect(),
+            None => self.scopes.clone(),
+        };
+        OrganizationRole::validate_name(self.organization_id, Some(self.id), &name, conn)?;
+        OrganizationRole::validate_scopes(&scopes)?;
+
+        let result: OrganizationRole = diesel::update(self)
+            .set((
+                organization_roles::name.eq(&name),
+                organization_roles::description.eq(&description),
+                organization_roles::scopes.eq(&scopes),
+                organization_roles::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update organization role")?;
+
+        DomainEvent::create(
+            DomainEventTypes::OrganizationRoleUpdated,
+            format!("Organization role '{}' updated", &result.name),
+            Tables::OrganizationRoles,
+            Some(self.id),
+            current_user_id,
+            Some(json!({
+                "previous": {
+                    "name": self.name,
+                    "description": self.description,
+                    "scopes": self.scopes
+                },
+                "current": {
+                    "name": result.name,
+                    "description": result.description,
+                    "scopes": result.scopes
+                }
+            })),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    /// Removes the role and unassigns it from all members of the organization
+    pub fn destroy(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<(), DatabaseError> {
+        diesel::update(self)
+            .set((
+                organization_roles::deleted_at.eq(dsl::now.nullable()),
+                organization_roles::updated_at.eq(dsl::now),
+            ))
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not delete organization role")?;
+
+        diesel::sql_query(
+            r#"
+            UPDATE organization_users
+            SET organization_role_ids = array_remove(organization_role_ids, $1), updated_at = now()
+            WHERE organization_id = $2 AND $1 = ANY(organization_role_ids);
+            "#,
+        )
+        .bind::<diesel::sql_types::Uuid, _>(self.id)
+        .bind::<diesel::sql_types::Uuid, _>(self.organization_id)
+        .execute(conn)
+        .to_db_error(ErrorCode::UpdateError, "Could not unassign organization role")?;
+
+        DomainEvent::create(
+            DomainEventTypes::OrganizationRoleDeleted,
+            format!("Organization role '{}' deleted", &self.name),
+            Tables::OrganizationRoles,
+            Some(self.id),
+            current_user_id,
+            Some(json!({ "scopes": self.scopes })),
+        )
+        .commit(conn)?;
+
+        Ok(())
+    }
+
+    fn validate_name(
+        organization_id: Uuid,
+        id: Option<Uuid>,
+        name: &str,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        if name.trim().is_empty() {
+            return DatabaseError::validation_error("name", "Name cannot be blank");
+        }
+
+        let name_taken = OrganizationRole::find_for_organization(organization_id, conn)?
+            .iter()
+            .any(|r| Some(r.id) != id && r.name.to_lowercase() == name.to_lowercase());
+        if name_taken {
+            return DatabaseError::validation_error("name", "An organization role with this name already exists");
+        }
+        Ok(())
+    }
+
+    /// Custom roles may only combine scopes an organization owner holds, never administrative scopes
+    fn validate_scopes(role_scopes: &[String]) -> Result<(), DatabaseError> {
+        if role_scopes.is_empty() {
+            return DatabaseError::validation_error("scopes", "At least one scope is required");
+        }
+
+        let grantable_scopes = scopes::get_scopes(vec![Roles::OrgOwner], None);
+        for scope in role_scopes {
+            match Scopes::from_str(scope) {
+                Ok(scope) if grantable_scopes.contains(&scope) => (),
+                Ok(_) => {
+                    return DatabaseError::validation_error("scopes", "Scope cannot be granted by an organization role")
+                }
+                Err(_) => return DatabaseError::validation_error("scopes", "Unknown scope"),
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,375 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::organization_sso_configurations;
+use serde_json;
+use std::collections::HashMap;
+use utils::encryption::*;
+use utils::errors::*;
+use uuid::Uuid;
+
+/// Roles that can be granted to staff provisioned through single sign-on, ownership and
+/// event limited promoter roles are always assigned by hand
+pub const SSO_PROVISIONABLE_ROLES: [Roles; 4] = [
+    Roles::OrgAdmin,
+    Roles::OrgMember,
+    Roles::OrgBoxOffice,
+    Roles::DoorPerson,
+];
+
+/// OpenID Connect provider staff of an organization sign in with
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(Organization)]
+#[table_name = "organization_sso_configurations"]
+pub struct OrganizationSsoConfiguration {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    /// Provider metadata is discovered from `{issuer_url}/.well-known/openid-configuration`
+    pub issuer_url: String,
+    pub client_id: String,
+    #[serde(default, skip_serializing)]
+    pub encrypted_client_secret: String,
+    /// Role given to new members when none of their role claim values are mapped
+    pub default_role: Roles,
+    /// ID token claim holding the groups or roles the provider assigns the user
+    pub role_claim: Option<String>,
+    /// Role claim values mapped to the role they grant
+    pub role_mappings: serde_json::Value,
+    pub enabled: bool,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+pub struct NewOrganizationSsoConfiguration {
+    pub organization_id: Uuid,
+    pub issuer_url: String,
+    pub client_id: String,
+    pub client_secret: String,
+    pub default_role: Roles,
+    pub role_claim: Option<String>,
+    pub role_mappings: HashMap<String, Roles>,
+}
+
+#[derive(Clone, Default, Deserialize, Serialize)]
+pub struct OrganizationSsoConfigurationEditableAttributes {
+    pub issuer_url: Option<String>,
+    pub client_id: Option<String>,
+    pub client_secret: Option<String>,
+    pub default_role: Option<Roles>,
+    #[serde(default, deserialize_with = "double_option_deserialize_unless_blank")]
+    pub role_claim: Option<Option<String>>,
+    pub role_mappings: Option<HashMap<String, Roles>>,
+    pub enabled: Option<bool>,
+}
+
+/// Identity asserted by the provider in a validated ID token
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct SsoIdentity {
+    pub subject: String,
+    pub email: Option<String>,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    /// Values of the configuration's role claim
+    pub role_claim_values: Vec<String>,
+}
+
+impl NewOrganizationSsoConfiguration {
+    pub fn commit(
+        self,
+        encryption_key: &str,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<OrganizationSsoConfiguration, DatabaseError> {
+        OrganizationSsoConfiguration::validate(
+            &self.issuer_url,
+            &self.client_id,
+            &self.client_secret,
+            self.default_role,
+            &self.role_mappings,
+        )?;
+        if OrganizationSsoConfiguration::find_for_organization(self.organization_id, conn)
+            .optional()?
+            .is_some()
+        {
+            return DatabaseError::validation_error(
+                "organization_id",
+                "Single sign-on is already configured for this organization",
+            );
+        }
+
+        let configuration: OrganizationSsoConfiguration = diesel::insert_into(organization_sso_configurations::table)
+            .values((
+                organization_sso_configurations::organization_id.eq(self.organization_id),
+                organization_sso_configurations::issuer_url.eq(self.issuer_url.trim_end_matches('/')),
+                organization_sso_configurations::client_id.eq(&self.client_id),
+                organization_sso_configurations::encrypted_client_secret
+                    .eq(encrypt(&self.client_secret, encryption_key)?),
+                organization_sso_configurations::default_role.eq(self.default_role),
+                organization_sso_configurations::role_claim.eq(&self.role_claim),
+                organization_sso_configurations::role_mappings.eq(json!(self.role_mappings)),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create single sign-on configuration")?;
+
+        DomainEvent::create(
+            DomainEventTypes::OrganizationSsoConfigurationCreated,
+            "Single sign-on configured".to_string(),
+            Tables::OrganizationSsoConfigurations,
+            Some(configuration.id),
+            current_user_id,
+            Some(configuration.audit_data()),
+        )
+        .commit(conn)?;
+
+        Ok(configuration)
+    }
+}
+
+impl OrganizationSsoConfiguration {
+    pub fn create(
+        organization_id: Uuid,
+        issuer_url: String,
+        client_id: String,
+        client_secret: String,
+        default_role: Roles,
+        role_claim: Option<String>,
+        role_mappings: HashMap<String, Roles>,
+    ) -> NewOrganizationSsoConfiguration {
+        NewOrganizationSsoConfiguration {
+            organization_id,
+            issuer_url,
+            client_id,
+            client_secret,
+            default_role,
+            role_claim,
+            role_mappings,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<OrganizationSsoConfiguration, DatabaseError> {
+        organization_sso_configurations::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find single sign-on configuration")
+    }
+
+    pub fn find_for_organization(
+        organization_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<OrganizationSsoConfiguration, DatabaseError> {
+        organization_sso_configurations::table
+            .filter(organization_sso_configurations::organization_id.eq(organization_id))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find single sign-on configuration")
+    }
+
+    pub fn organization(&self, conn: &PgConnection) -> Result<Organization, DatabaseError> {
+        Organization::find(self.organization_id, conn)
+    }
+
+    pub fn client_secret(&self, encryption_key: &str) -> Result<String, DatabaseError> {
+        decrypt(&self.encrypted_client_secret, encryption_key)
+    }
+
+    pub fn update(
+        &self,
+        attributes: OrganizationSsoConfigurationEditableAttributes,
+        encryption_key: &str,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<OrganizationSsoConfiguration, DatabaseError> {
+        let issuer_url = attributes.issuer_url.unwrap_or_else(|| self.issuer_url.clone());
+        let client_id = attributes.client_id.unwrap_or_else(|| self.client_id.clone());
+        let client_secret = match attributes.client_secret {
+            Some(client_secret) => client_secret,
+            None => self.client_secret(encryption_key)?,
+        };
+        let default_role = attributes.default_role.unwrap_or(self.default_role);
+        let role_claim = attributes.role_claim.unwrap_or_else(|| self.role_claim.clone());
+        let role_mappings = attributes.role_mappings.unwrap_or_else(|| self.role_mappings());
+        let enabled = attributes.enabled.unwrap_or(self.enabled);
+        OrganizationSsoConfiguration::validate(&issuer_url, &client_id, &client_secret, default_role, &role_mappings)?;
+
+        let result: OrganizationSsoConfiguration = diesel::update(self)
+            .set((
+                organization_sso_configurations::issuer_url.eq(issuer_url.trim_end_matches('/')),
+                organization_sso_configurations::client_id.eq(&client_id),
+                organization_sso_configurations::encrypted_client_secret.eq(encrypt(&client_secret, encryption_key)?),
+                organization_sso_configurations::default_role.eq(default_role),
+                organization_sso_configurations::role_claim.eq(&role_claim),
+                organization_sso_configurations::role_mappings.eq(json!(role_mappings)),
+                organization_sso_configurations::enabled.eq(enabled),
+                organization_sso_configurations::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update single sign-on configuration")?;
+
+        DomainEvent::create(
+            DomainEventTypes::OrganizationSsoConfigurationUpdated,
+            "Single sign-on configuration updated".to_string(),
+            Tables::OrganizationSsoConfigurations,
+            Some(self.id),
+            current_user_id,
+            Some(json!({
+                "previous": self.audit_data(),
+                "updated": result.audit_data()
+            })),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
+    pub fn role_mappings(&self) -> HashMap<String, Roles> {
+        serde_json::from_value(self.role_mappings.clone()).unwrap_or_default()
+    }
+
+    /// Role for a new member, the first mapped role claim value wins
+    pub fn role_for(&self, role_claim_values: &[String]) -> Roles {
+        let role_mappings = self.role_mappings();
+        role_claim_values
+            .iter()
+            .filter_map(|value| role_mappings.get(value))
+            .next()
+            .cloned()
+            .unwrap_or(self.default_role)
+    }
+
+    /// Provider subjects are only unique per issuer
+    pub fn external_user_id(&self, subject: &str) -> String {
+        format!("{}|{}", self.issuer_url, subject)
+    }
+
+    /// Finds or creates the user for the identity and makes them a member of the organization if
+    /// they are not already. Existing accounts are never linked by email alone as the provider
+    /// is controlled by the organization, the user has to sign in and link it themselves.
+    pub fn provision_user(
+        &self,
+        identity: &SsoIdentity,
+        access_token: String,
+        conn: &PgConnection,
+    ) -> Result<User, DatabaseError> {
+        let external_user_id = self.external_user_id(&identity.subject);
+        let user = match ExternalLogin::find_user(&external_user_id, OIDC_SITE, conn)? {
+            Some(external_login) => {
+                let user = User::find(external_login.user_id, conn)?;
+                if user.deleted_at.is_some() {
+                    return DatabaseError::business_process_error("This account has been deleted");
+                }
+                user
+            }
+            None => {
+                if let Some(ref email) = identity.email {
+                    if User::find_by_email(email, true, conn).optional()?.is_some() {
+                        return DatabaseError::business_process_error(
+                            "An account already exists for this email, sign in to link it to single sign-on",
+                        );
+                    }
+                }
+                User::create_from_external_login(
+                    external_user_id,
+                    identity.first_name.clone().unwrap_or_default(),
+                    identity.last_name.clone().unwrap_or_default(),
+                    identity.email.clone(),
+                    OIDC_SITE.to_string(),
+                    access_token,
+                    vec![],
+                    None,
+                    conn,
+                )?
+            }
+        };
+
+        self.provision_membership(&user, identity, conn)?;
+        Ok(user)
+    }
+
+    /// Links the identity to a signed in user and makes them a member of the organization
+    pub fn link_user(
+        &self,
+        user: &User,
+        identity: &SsoIdentity,
+        access_token: String,
+        conn: &PgConnection,
+    ) -> Result<User, DatabaseError> {
+        user.add_or_replace_external_login(
+            Some(user.id),
+            self.external_user_id(&identity.subject),
+            OIDC_SITE.to_string(),
+            access_token,
+            vec![],
+            conn,
+        )?;
+        self.provision_membership(user, identity, conn)?;
+        Ok(user.clone())
+    }
+
+    fn provision_membership(
+        &self,
+        user: &User,
+        identity: &SsoIdentity,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        let organization = self.organization(conn)?;
+        // Roles of existing members are managed within the organization
+        if organization.is_member(user, conn)? {
+            return Ok(());
+        }
+
+        let role = self.role_for(&identity.role_claim_values);
+        organization.add_user(user.id, vec![role], Vec::new(), conn)?;
+        DomainEvent::create(
+            DomainEventTypes::OrganizationUserSsoProvisioned,
+            "User added to organization through single sign-on".to_string(),
+            Tables::Organizations,
+            Some(organization.id),
+            Some(user.id),
+            Some(json!({ "user_id": user.id, "role": role, "role_claim_values": identity.role_claim_values })),
+        )
+        .commit(conn)?;
+        Ok(())
+    }
+
+    fn validate(
+        issuer_url: &str,
+        client_id: &str,
+        client_secret: &str,
+        default_role: Roles,
+        role_mappings: &HashMap<String, Roles>,
+    ) -> Result<(), DatabaseError> {
+        if !issuer_url.starts_with("https://") {
+            return DatabaseError::validation_error("issuer_url", "Issuer URL must use https");
+        }
+        if client_id.trim().is_empty() {
+            return DatabaseError::validation_error("client_id", "Client ID cannot be blank");
+        }
+        if client_secret.trim().is_empty() {
+            return DatabaseError::validation_error("client_secret", "Client secret cannot be blank");
+        }
+        if !SSO_PROVISIONABLE_ROLES.contains(&default_role) {
+            return DatabaseError::validation_error("default_role", "Role cannot be granted through single sign-on");
+        }
+        if role_mappings
+            .values()
+            .any(|role| !SSO_PROVISIONABLE_ROLES.contains(role))
+        {
+            return DatabaseError::validation_error("role_mappings", "Role cannot be granted through single sign-on");
+        }
+        Ok(())
+    }
+
+    fn audit_data(&self) -> serde_json::Value {
+        json!({
+            "organization_id": self.organization_id,
+            "issuer_url": self.issuer_url,
+            "client_id": self.client_id,
+            "default_role": self.default_role,
+            "role_claim": self.role_claim,
+            "role_mappings": self.role_mappings,
+            "enabled": self.enabled
+        })
+    }
+}
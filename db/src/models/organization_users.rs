@@ -3,7 +3,7 @@ use diesel;
 use diesel::dsl;
 use diesel::prelude::*;
 use models::enums::Roles;
-use models::{EventUser, Organization, Scopes, User};
+use models::{DomainEvent, DomainEventTypes, EventUser, Organization, OrganizationRole, Scopes, Tables, User};
 use schema::{event_users, events, organization_users};
 use serde_json;
 use serde_json::Value;
@@ -24,6 +24,7 @@ pub struct OrganizationUser {
     pub updated_at: NaiveDateTime,
     pub role: Vec<Roles>,
     pub additional_scopes: Option<serde_json::Value>,
+    pub organization_role_ids: Vec<Uuid>,
 }
 
 #[derive(Default, Clone, Debug, Serialize, PartialEq)]
@@ -168,6 +169,54 @@ impl OrganizationUser {
             .to_db_error(ErrorCode::UpdateError, "Could not update additional_scopes")
     }
 
+    /// Replaces the custom organization roles held by the member
+    pub fn set_organization_roles(
+        &self,
+        organization_role_ids: Vec<Uuid>,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<OrganizationUser, DatabaseError> {
+        let mut organization_role_ids = organization_role_ids;
+        organization_role_ids.sort();
+        organization_role_ids.dedup();
+
+        let organization_roles = OrganizationRole::find_for_organization(self.organization_id, conn)?;
+        if organization_role_ids
+            .iter()
+            .any(|id| !organization_roles.iter().any(|r| &r.id == id))
+        {
+            return DatabaseError::validation_error(
+                "organization_role_ids",
+                "Organization roles must belong to the member's organization",
+            );
+        }
+
+        let result: OrganizationUser =
+            diesel::update(organization_users::table.filter(organization_users::id.eq(self.id)))
+                .set((
+                    organization_users::organization_role_ids.eq(&organization_role_ids),
+                    organization_users::updated_at.eq(dsl::now),
+                ))
+                .get_result(conn)
+                .to_db_error(ErrorCode::UpdateError, "Could not update organization roles")?;
+
+        DomainEvent::create(
+            DomainEventTypes::OrganizationUserRolesUpdated,
+            "Organization roles updated for member".to_string(),
+            Tables::Organizations,
+            Some(self.organization_id),
+            current_user_id,
+            Some(json!({
+                "user_id": self.user_id,
+                "previous_organization_role_ids": self.organization_role_ids,
+                "organization_role_ids": organization_role_ids
+            })),
+        )
+        .commit(conn)?;
+
+        Ok(result)
+    }
+
     pub fn find_users_by_organization(
         organization_id: Uuid,
         conn: &PgConnection,
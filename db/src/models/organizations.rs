@@ -12,6 +12,7 @@ use schema::{
     assets, event_users, events, fee_schedules, order_items, orders, organization_users, organizations, ticket_types,
     users,
 };
+use serde_with::rust::double_option;
 use std::cmp;
 use std::collections::HashMap;
 use utils::encryption::*;
@@ -19,6 +20,8 @@ use utils::errors::*;
 use utils::pagination::Paginate;
 use utils::text;
 use uuid::Uuid;
+use validator::ValidationError;
+use validators;
 
 const DEFAULT_SETTLEMENT_TIMEZONE: &str = "America/Los_Angeles";
 
@@ -54,6 +57,13 @@ pub struct Organization {
     pub slug_id: Option<Uuid>,
     pub google_ads_conversion_id: Option<String>,
     pub google_ads_conversion_labels: Vec<String>,
+    pub currency: String,
+    pub transfer_expiry_hours: Option<i32>,
+    pub resale_fee_percent: f32,
+    /// Members must use two-factor authentication to log in
+    pub require_two_factor: bool,
+    /// Failed installment charges after which a payment plan is cancelled
+    pub payment_plan_max_failed_attempts: i32,
 }
 
 #[derive(Serialize)]
@@ -90,6 +100,10 @@ pub struct NewOrganization {
     pub globee_api_key: Option<String>,
     pub max_instances_per_ticket_type: Option<i64>,
     pub settlement_type: Option<SettlementTypes>,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub currency: Option<String>,
+    pub transfer_expiry_hours: Option<i32>,
+    pub resale_fee_percent: Option<f32>,
 }
 
 #[derive(Default, Serialize, Clone, Deserialize, Debug, PartialEq)]
@@ -108,6 +122,22 @@ impl NewOrganization {
         current_user_id: Option<Uuid>,
         conn: &PgConnection,
     ) -> Result<Organization, DatabaseError> {
+        if let Some(ref currency) = self.currency {
+            validators::append_validation_error(Ok(()), "currency", validators::validate_currency(currency))?;
+        }
+        validators::append_validation_error(
+            Ok(()),
+            "transfer_expiry_hours",
+            Organization::transfer_expiry_hours_valid(self.transfer_expiry_hours),
+        )?;
+        if let Some(resale_fee_percent) = self.resale_fee_percent {
+            validators::append_validation_error(
+                Ok(()),
+                "resale_fee_percent",
+                Organization::resale_fee_percent_valid(resale_fee_percent),
+            )?;
+        }
+
         let mut updated_organisation = self;
         if encryption_key.len() > 0 {
             if let Some(key) = updated_organisation.sendgrid_api_key.clone() {
@@ -214,6 +244,13 @@ pub struct OrganizationEditableAttributes {
     pub google_ads_conversion_id: Option<Option<String>>,
     #[serde(default)]
     pub google_ads_conversion_labels: Option<Vec<String>>,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub currency: Option<String>,
+    #[serde(default, deserialize_with = "double_option::deserialize")]
+    pub transfer_expiry_hours: Option<Option<i32>>,
+    pub resale_fee_percent: Option<f32>,
+    pub require_two_factor: Option<bool>,
+    pub payment_plan_max_failed_attempts: Option<i32>,
 }
 
 impl Organization {
@@ -343,6 +380,33 @@ impl Organization {
         }
     }
 
+    pub fn transfer_expiry_hours_valid(transfer_expiry_hours: Option<i32>) -> Result<(), ValidationError> {
+        match transfer_expiry_hours {
+            Some(transfer_expiry_hours) => validators::validate_greater_than_or_equal(
+                transfer_expiry_hours,
+                1,
+                "transfer_expiry_hours_must_be_positive",
+                "Transfer expiry must be at least 1 hour",
+            ),
+            None => Ok(()),
+        }
+    }
+
+    pub fn resale_fee_percent_valid(resale_fee_percent: f32) -> Result<(), ValidationError> {
+        if resale_fee_percent < 0f32 || resale_fee_percent > 100f32 {
+            return Err(validators::create_validation_error(
+                "resale_fee_percent_out_of_range",
+                "Resale fee must be between 0 and 100 percent",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Portion of a resale amount retained by the platform, the remainder is paid out to the seller
+    pub fn resale_fee_in_cents(&self, resale_amount_in_cents: i64) -> i64 {
+        (resale_amount_in_cents as f32 * (self.resale_fee_percent / 100f32)).round() as i64
+    }
+
     pub fn create(name: &str, fee_schedule_id: Uuid) -> NewOrganization {
         NewOrganization {
             name: name.into(),
@@ -358,6 +422,36 @@ impl Organization {
         encryption_key: &String,
         conn: &PgConnection,
     ) -> Result<Organization, DatabaseError> {
+        if let Some(ref currency) = attributes.currency {
+            validators::append_validation_error(Ok(()), "currency", validators::validate_currency(currency))?;
+        }
+        if let Some(transfer_expiry_hours) = attributes.transfer_expiry_hours {
+            validators::append_validation_error(
+                Ok(()),
+                "transfer_expiry_hours",
+                Organization::transfer_expiry_hours_valid(transfer_expiry_hours),
+            )?;
+        }
+        if let Some(resale_fee_percent) = attributes.resale_fee_percent {
+            validators::append_validation_error(
+                Ok(()),
+                "resale_fee_percent",
+                Organization::resale_fee_percent_valid(resale_fee_percent),
+            )?;
+        }
+        if let Some(payment_plan_max_failed_attempts) = attributes.payment_plan_max_failed_attempts {
+            validators::append_validation_error(
+                Ok(()),
+                "payment_plan_max_failed_attempts",
+                validators::validate_greater_than_or_equal(
+                    payment_plan_max_failed_attempts,
+                    1,
+                    "payment_plan_max_failed_attempts_lt_1",
+                    "Payment plans must allow at least one failed attempt",
+                ),
+            )?;
+        }
+
         if encryption_key.len() > 0 {
             if let Some(Some(key)) = attributes.sendgrid_api_key {
                 attributes.sendgrid_api_key = Some(Some(encrypt(&key, encryption_key)?));
@@ -550,6 +644,16 @@ impl Organization {
 
     pub fn get_scopes_for_user(&self, user: &User, conn: &PgConnection) -> Result<Vec<Scopes>, DatabaseError> {
         let (roles, additional_scopes) = self.get_roles_for_user(user, conn)?;
+        // Custom organization roles grant their scopes in addition to the fixed roles, explicitly
+        // revoked scopes still take precedence
+        let custom_role_scopes = OrganizationRole::scopes_for_user(self.id, user.id, conn)?;
+        let additional_scopes = if custom_role_scopes.is_empty() {
+            additional_scopes
+        } else {
+            let mut additional_scopes = additional_scopes.unwrap_or_default();
+            additional_scopes.additional.extend(custom_role_scopes);
+            Some(additional_scopes)
+        };
         let user_scopes = scopes::get_scopes(roles, additional_scopes);
 
         Ok(user_scopes)
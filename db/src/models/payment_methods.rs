@@ -1,14 +1,22 @@
 use chrono::prelude::*;
+use chrono::Duration;
 use diesel;
+use diesel::dsl::sql;
 use diesel::expression::dsl;
 use diesel::prelude::*;
-use diesel::sql_types::Text;
-use models::{DomainEvent, DomainEventTypes, ForDisplay, PaymentProviders, Tables};
+use diesel::sql_types::{Bool, Integer, Text};
+use models::{
+    DomainAction, DomainActionTypes, DomainEvent, DomainEventTypes, ForDisplay, PaymentPlanStatus, PaymentProviders,
+    Tables,
+};
 use schema::*;
 use serde_json;
 use utils::errors::*;
 use uuid::Uuid;
 
+/// Owners of default cards are notified once the card expires within this many months
+pub const PAYMENT_METHOD_EXPIRY_NOTICE_MONTHS: i32 = 1;
+
 #[derive(Clone, Debug, Identifiable, PartialEq, Queryable)]
 pub struct PaymentMethod {
     pub id: Uuid,
@@ -19,6 +27,11 @@ pub struct PaymentMethod {
     pub provider_data: serde_json::Value,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub card_brand: Option<String>,
+    pub card_last_four: Option<String>,
+    pub card_expiry_month: Option<i32>,
+    pub card_expiry_year: Option<i32>,
+    pub expiry_notification_sent_at: Option<NaiveDateTime>,
 }
 
 #[derive(AsChangeset, Default, Deserialize)]
@@ -43,9 +56,20 @@ impl PaymentMethod {
             is_default,
             provider,
             provider_data: data,
+            card_brand: None,
+            card_last_four: None,
+            card_expiry_month: None,
+            card_expiry_year: None,
         }
     }
 
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<PaymentMethod, DatabaseError> {
+        payment_methods::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load payment method")
+    }
+
     pub fn find_default_for_user(user_id: Uuid, conn: &PgConnection) -> Result<PaymentMethod, DatabaseError> {
         payment_methods::table
             .filter(payment_methods::user_id.eq(user_id))
@@ -68,7 +92,11 @@ impl PaymentMethod {
         }
 
         query
-            .order_by(payment_methods::name)
+            .order_by((
+                payment_methods::name,
+                payment_methods::is_default.desc(),
+                payment_methods::created_at.desc(),
+            ))
             .load(conn)
             .to_db_error(ErrorCode::QueryError, "Could not load payment methods for user")
     }
@@ -97,6 +125,160 @@ impl PaymentMethod {
             query.get_result(conn),
         )
     }
+
+    pub fn set_default(&self, current_user_id: Uuid, conn: &PgConnection) -> Result<PaymentMethod, DatabaseError> {
+        PaymentMethod::clear_default_for_user(self.user_id, conn)?;
+
+        let payment_method: PaymentMethod = diesel::update(self)
+            .set((
+                payment_methods::is_default.eq(true),
+                payment_methods::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not set default payment method")?;
+
+        DomainEvent::create(
+            DomainEventTypes::PaymentMethodDefaultChanged,
+            "Payment method was set as default".to_string(),
+            Tables::PaymentMethods,
+            Some(self.id),
+            Some(current_user_id),
+            None,
+        )
+        .commit(conn)?;
+
+        Ok(payment_method)
+    }
+
+    /// Removes the payment method, the most recently saved remaining method becomes the default
+    /// if this was the user's default
+    pub fn destroy(&self, current_user_id: Uuid, conn: &PgConnection) -> Result<(), DatabaseError> {
+        let active_payment_plans: i64 = payment_plans::table
+            .filter(payment_plans::payment_method_id.eq(self.id))
+            .filter(payment_plans::status.eq(PaymentPlanStatus::Active))
+            .select(dsl::count(payment_plans::id))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load payment plans for payment method")?;
+        if active_payment_plans > 0 {
+            return DatabaseError::business_process_error(
+                "Payment method is being used to pay for an order in installments and cannot be removed",
+            );
+        }
+
+        diesel::delete(self)
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not delete payment method")?;
+
+        DomainEvent::create(
+            DomainEventTypes::PaymentMethodDeleted,
+            "Payment method was deleted".to_string(),
+            Tables::PaymentMethods,
+            Some(self.id),
+            Some(current_user_id),
+            Some(self.provider_data.clone()),
+        )
+        .commit(conn)?;
+
+        if self.is_default {
+            let replacement: Option<PaymentMethod> = payment_methods::table
+                .filter(payment_methods::user_id.eq(self.user_id))
+                .order_by(payment_methods::created_at.desc())
+                .first(conn)
+                .optional()
+                .to_db_error(ErrorCode::QueryError, "Could not load payment methods for user")?;
+            if let Some(replacement) = replacement {
+                replacement.set_default(current_user_id, conn)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Default cards expiring within the notice period whose owners have not been notified yet
+    pub fn find_default_pending_expiry_notification(conn: &PgConnection) -> Result<Vec<PaymentMethod>, DatabaseError> {
+        let today = Utc::now().naive_utc().date();
+        let current_month = today.year() * 12 + today.month0() as i32;
+
+        payment_methods::table
+            .filter(payment_methods::is_default.eq(true))
+            .filter(payment_methods::expiry_notification_sent_at.is_null())
+            .filter(
+                sql::<Bool>("payment_methods.card_expiry_year * 12 + payment_methods.card_expiry_month - 1 BETWEEN ")
+                    .bind::<Integer, _>(current_month)
+                    .sql(" AND ")
+                    .bind::<Integer, _>(current_month + PAYMENT_METHOD_EXPIRY_NOTICE_MONTHS),
+            )
+            .order_by(payment_methods::created_at)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load expiring payment methods")
+    }
+
+    pub fn mark_expiry_notification_sent(&self, conn: &PgConnection) -> Result<PaymentMethod, DatabaseError> {
+        DomainEvent::create(
+            DomainEventTypes::PaymentMethodExpiryNotificationSent,
+            "Payment method expiry notification was sent".to_string(),
+            Tables::PaymentMethods,
+            Some(self.id),
+            None,
+            None,
+        )
+        .commit(conn)?;
+
+        diesel::update(self)
+            .set((
+                payment_methods::expiry_notification_sent_at.eq(dsl::now.nullable()),
+                payment_methods::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(
+                ErrorCode::UpdateError,
+                "Could not mark payment method expiry notification as sent",
+            )
+    }
+
+    pub fn create_next_expiry_notification_domain_action(conn: &PgConnection) -> Result<(), DatabaseError> {
+        let now = Utc::now().naive_utc();
+        if let Some(upcoming_domain_action) = DomainAction::upcoming_domain_action(
+            None,
+            None,
+            DomainActionTypes::SendPaymentMethodExpiryNotifications,
+            conn,
+        )? {
+            if upcoming_domain_action.scheduled_at > now {
+                return DatabaseError::already_scheduled_error(
+                    "Payment method expiry notification domain action is already pending",
+                );
+            }
+        }
+
+        let mut action = DomainAction::create(
+            None,
+            DomainActionTypes::SendPaymentMethodExpiryNotifications,
+            None,
+            json!({}),
+            None,
+            None,
+        );
+        action.schedule_at(now + Duration::days(1));
+        action.commit(conn)?;
+
+        Ok(())
+    }
+
+    fn clear_default_for_user(user_id: Uuid, conn: &PgConnection) -> Result<(), DatabaseError> {
+        diesel::update(
+            payment_methods::table
+                .filter(payment_methods::user_id.eq(user_id))
+                .filter(payment_methods::is_default.eq(true)),
+        )
+        .set((
+            payment_methods::is_default.eq(false),
+            payment_methods::updated_at.eq(dsl::now),
+        ))
+        .execute(conn)
+        .to_db_error(ErrorCode::UpdateError, "Could not clear default payment method")?;
+        Ok(())
+    }
 }
 
 impl ForDisplay<DisplayPaymentMethod> for PaymentMethod {
@@ -113,10 +295,27 @@ pub struct NewPaymentMethod {
     is_default: bool,
     provider: String,
     provider_data: serde_json::Value,
+    card_brand: Option<String>,
+    card_last_four: Option<String>,
+    card_expiry_month: Option<i32>,
+    card_expiry_year: Option<i32>,
 }
 
 impl NewPaymentMethod {
+    pub fn with_card(mut self, brand: String, last_four: String, expiry_month: i32, expiry_year: i32) -> Self {
+        self.card_brand = Some(brand);
+        self.card_last_four = Some(last_four);
+        self.card_expiry_month = Some(expiry_month);
+        self.card_expiry_year = Some(expiry_year);
+        self
+    }
+
+    /// Saves the payment method, a new default replaces the user's existing default
     pub fn commit(self, current_user_id: Uuid, conn: &PgConnection) -> Result<PaymentMethod, DatabaseError> {
+        if self.is_default {
+            PaymentMethod::clear_default_for_user(self.user_id, conn)?;
+        }
+
         let payment_method = diesel::insert_into(payment_methods::table)
             .values(self)
             .get_result::<PaymentMethod>(conn)
@@ -138,15 +337,25 @@ impl NewPaymentMethod {
 
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 pub struct DisplayPaymentMethod {
+    pub id: Uuid,
     pub name: PaymentProviders,
     pub is_default: bool,
+    pub card_brand: Option<String>,
+    pub card_last_four: Option<String>,
+    pub card_expiry_month: Option<i32>,
+    pub card_expiry_year: Option<i32>,
 }
 
 impl From<PaymentMethod> for DisplayPaymentMethod {
     fn from(payment_method: PaymentMethod) -> Self {
         DisplayPaymentMethod {
+            id: payment_method.id,
             name: payment_method.name,
             is_default: payment_method.is_default,
+            card_brand: payment_method.card_brand,
+            card_last_four: payment_method.card_last_four,
+            card_expiry_month: payment_method.card_expiry_month,
+            card_expiry_year: payment_method.card_expiry_year,
         }
     }
 }
@@ -0,0 +1,492 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use itertools::Itertools;
+use models::*;
+use schema::{payment_plan_installments, payment_plans};
+use serde_json;
+use std::cmp;
+use utils::dates::IntoDateBuilder;
+use utils::errors::*;
+use uuid::Uuid;
+
+/// Days to wait before retrying an installment after a failed charge
+pub const PAYMENT_PLAN_RETRY_INTERVAL_DAYS: i64 = 3;
+
+/// Schedule for paying an order in installments charged against a saved payment method.
+/// Tickets are issued with the first installment but cannot be transferred, listed or redeemed
+/// until the plan is completed.
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(Order)]
+#[table_name = "payment_plans"]
+pub struct PaymentPlan {
+    pub id: Uuid,
+    pub order_id: Uuid,
+    pub payment_method_id: Option<Uuid>,
+    pub status: PaymentPlanStatus,
+    pub installment_count: i32,
+    /// Copied from the organization when the plan is created
+    pub max_failed_attempts: i32,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "payment_plans"]
+struct NewPaymentPlan {
+    order_id: Uuid,
+    payment_method_id: Option<Uuid>,
+    status: PaymentPlanStatus,
+    installment_count: i32,
+    max_failed_attempts: i32,
+}
+
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(PaymentPlan)]
+#[table_name = "payment_plan_installments"]
+pub struct PaymentPlanInstallment {
+    pub id: Uuid,
+    pub payment_plan_id: Uuid,
+    pub installment_number: i32,
+    pub amount_in_cents: i64,
+    pub due_at: NaiveDateTime,
+    pub status: PaymentPlanInstallmentStatus,
+    pub failed_attempts: i32,
+    pub last_failure_reason: Option<String>,
+    pub payment_id: Option<Uuid>,
+    pub paid_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "payment_plan_installments"]
+struct NewPaymentPlanInstallment {
+    payment_plan_id: Uuid,
+    installment_number: i32,
+    amount_in_cents: i64,
+    due_at: NaiveDateTime,
+    status: PaymentPlanInstallmentStatus,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DisplayPaymentPlan {
+    pub id: Uuid,
+    pub order_id: Uuid,
+    pub status: PaymentPlanStatus,
+    pub installment_count: i32,
+    pub installments: Vec<PaymentPlanInstallment>,
+}
+
+impl PaymentPlan {
+    /// Creates a payment plan for a draft order. Every ticket in the order must be on a ticket type
+    /// offering installments, the smallest installment count and interval of those ticket types
+    /// are used. The first installment is due immediately and is collected at checkout.
+    pub fn create_for_order(
+        order: &Order,
+        payment_method: &PaymentMethod,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<PaymentPlan, DatabaseError> {
+        if order.status != OrderStatus::Draft {
+            return DatabaseError::business_process_error("Payment plans can only be created for draft orders");
+        }
+        if payment_method.user_id != order.user_id {
+            return DatabaseError::business_process_error("Payment method does not belong to the purchaser");
+        }
+
+        let items = order.items(conn)?;
+        if items.iter().any(|i| i.item_type == OrderItemTypes::ResaleTickets) {
+            return DatabaseError::validation_error("payment_plan", "Resale tickets cannot be paid in installments");
+        }
+        let ticket_type_ids: Vec<Uuid> = items
+            .iter()
+            .filter(|i| i.item_type == OrderItemTypes::Tickets)
+            .filter_map(|i| i.ticket_type_id)
+            .unique()
+            .collect();
+        if ticket_type_ids.is_empty() {
+            return DatabaseError::validation_error("payment_plan", "Order does not contain any tickets");
+        }
+
+        let mut installment_count = i32::max_value();
+        let mut installment_interval_days = i32::max_value();
+        for ticket_type in TicketType::find_by_ids(&ticket_type_ids, conn)? {
+            match ticket_type.installment_count {
+                Some(count) => {
+                    installment_count = cmp::min(installment_count, count);
+                    installment_interval_days =
+                        cmp::min(installment_interval_days, ticket_type.installment_interval_days);
+                }
+                None => {
+                    return DatabaseError::validation_error(
+                        "payment_plan",
+                        "Not all tickets in the order can be paid in installments",
+                    );
+                }
+            }
+        }
+
+        let mut organizations = order.organizations(conn)?;
+        if organizations.len() != 1 {
+            return DatabaseError::validation_error(
+                "payment_plan",
+                "Orders paid in installments must contain tickets from a single organization",
+            );
+        }
+        let organization = organizations.remove(0);
+
+        let now = Utc::now().naive_utc();
+        let interval = Duration::days(installment_interval_days as i64);
+        let final_due_at = now + interval * (installment_count - 1);
+        for event in order.events(conn)? {
+            if event
+                .event_start
+                .map(|event_start| final_due_at >= event_start)
+                .unwrap_or(false)
+            {
+                return DatabaseError::validation_error(
+                    "payment_plan",
+                    "The final installment would be due after the event starts",
+                );
+            }
+        }
+
+        if let Some(existing_plan) = PaymentPlan::find_for_order(order.id, conn).optional()? {
+            if existing_plan
+                .installments(conn)?
+                .iter()
+                .any(|i| i.status == PaymentPlanInstallmentStatus::Paid)
+            {
+                return DatabaseError::business_process_error("Order already has a payment plan in progress");
+            }
+            diesel::delete(&existing_plan)
+                .execute(conn)
+                .to_db_error(ErrorCode::DeleteError, "Could not remove previous payment plan")?;
+        }
+
+        let plan: PaymentPlan = diesel::insert_into(payment_plans::table)
+            .values(NewPaymentPlan {
+                order_id: order.id,
+                payment_method_id: Some(payment_method.id),
+                status: PaymentPlanStatus::Active,
+                installment_count,
+                max_failed_attempts: organization.payment_plan_max_failed_attempts,
+            })
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create payment plan")?;
+
+        // Any remainder from splitting the amount due (after gift cards or account credit) is
+        // collected with the first installment
+        let total = order.amount_due(conn)?;
+        let base_amount = total / installment_count as i64;
+        let first_amount = total - base_amount * (installment_count as i64 - 1);
+        let installments: Vec<NewPaymentPlanInstallment> = (0..installment_count)
+            .map(|i| NewPaymentPlanInstallment {
+                payment_plan_id: plan.id,
+                installment_number: i + 1,
+                amount_in_cents: if i == 0 { first_amount } else { base_amount },
+                due_at: now + interval * i,
+                status: PaymentPlanInstallmentStatus::Pending,
+            })
+            .collect();
+        diesel::insert_into(payment_plan_installments::table)
+            .values(&installments)
+            .execute(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create payment plan installments")?;
+
+        DomainEvent::create(
+            DomainEventTypes::PaymentPlanCreated,
+            "Payment plan created".to_string(),
+            Tables::PaymentPlans,
+            Some(plan.id),
+            current_user_id,
+            Some(json!({
+                "order_id": order.id,
+                "installment_count": installment_count,
+                "installment_interval_days": installment_interval_days,
+                "total_in_cents": total
+            })),
+        )
+        .commit(conn)?;
+
+        Ok(plan)
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<PaymentPlan, DatabaseError> {
+        payment_plans::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find payment plan")
+    }
+
+    pub fn find_for_order(order_id: Uuid, conn: &PgConnection) -> Result<PaymentPlan, DatabaseError> {
+        payment_plans::table
+            .filter(payment_plans::order_id.eq(order_id))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find payment plan for order")
+    }
+
+    pub fn installments(&self, conn: &PgConnection) -> Result<Vec<PaymentPlanInstallment>, DatabaseError> {
+        payment_plan_installments::table
+            .filter(payment_plan_installments::payment_plan_id.eq(self.id))
+            .order_by(payment_plan_installments::installment_number)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load payment plan installments")
+    }
+
+    pub fn payment_method(&self, conn: &PgConnection) -> Result<Option<PaymentMethod>, DatabaseError> {
+        match self.payment_method_id {
+            Some(payment_method_id) => Ok(Some(PaymentMethod::find(payment_method_id, conn)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn for_display(&self, conn: &PgConnection) -> Result<DisplayPaymentPlan, DatabaseError> {
+        Ok(DisplayPaymentPlan {
+            id: self.id,
+            order_id: self.order_id,
+            status: self.status,
+            installment_count: self.installment_count,
+            installments: self.installments(conn)?,
+        })
+    }
+
+    /// Cancels the plan after the final attempt to collect an installment failed. The order is
+    /// cancelled and its tickets are released back into inventory. Installments already collected
+    /// are not refunded automatically.
+    pub fn cancel(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<PaymentPlan, DatabaseError> {
+        if self.status != PaymentPlanStatus::Active {
+            return DatabaseError::business_process_error("Only active payment plans can be cancelled");
+        }
+
+        let plan = self.update_status(PaymentPlanStatus::Cancelled, conn)?;
+        diesel::update(
+            payment_plan_installments::table
+                .filter(payment_plan_installments::payment_plan_id.eq(self.id))
+                .filter(payment_plan_installments::status.eq(PaymentPlanInstallmentStatus::Pending)),
+        )
+        .set((
+            payment_plan_installments::status.eq(PaymentPlanInstallmentStatus::Cancelled),
+            payment_plan_installments::updated_at.eq(dsl::now),
+        ))
+        .execute(conn)
+        .to_db_error(ErrorCode::UpdateError, "Could not cancel payment plan installments")?;
+
+        let mut order = Order::find(self.order_id, conn)?;
+        for item in order
+            .items(conn)?
+            .iter()
+            .filter(|oi| oi.item_type == OrderItemTypes::Tickets)
+        {
+            for ticket in TicketInstance::find_for_order_item(item.id, conn)? {
+                if ticket.status == TicketInstanceStatus::Purchased {
+                    ticket.release(TicketInstanceStatus::Purchased, order.user_id, conn)?;
+                }
+            }
+            if let Some(ticket_type_id) = item.ticket_type_id {
+                WaitlistEntry::queue_processing(ticket_type_id, None, conn)?;
+            }
+        }
+        order.update_status(current_user_id, OrderStatus::Cancelled, conn)?;
+
+        DomainEvent::create(
+            DomainEventTypes::PaymentPlanCancelled,
+            "Payment plan cancelled".to_string(),
+            Tables::PaymentPlans,
+            Some(self.id),
+            current_user_id,
+            Some(json!({ "order_id": self.order_id })),
+        )
+        .commit(conn)?;
+
+        Ok(plan)
+    }
+
+    fn update_status(&self, status: PaymentPlanStatus, conn: &PgConnection) -> Result<PaymentPlan, DatabaseError> {
+        diesel::update(self)
+            .set((payment_plans::status.eq(status), payment_plans::updated_at.eq(dsl::now)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update payment plan status")
+    }
+}
+
+impl PaymentPlanInstallment {
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<PaymentPlanInstallment, DatabaseError> {
+        payment_plan_installments::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find payment plan installment")
+    }
+
+    pub fn payment_plan(&self, conn: &PgConnection) -> Result<PaymentPlan, DatabaseError> {
+        PaymentPlan::find(self.payment_plan_id, conn)
+    }
+
+    /// Records the collected installment against the order. Tickets are issued with the first
+    /// installment and the order is completed with the last, otherwise the next installment
+    /// is scheduled to be charged on its due date.
+    pub fn mark_paid(
+        &self,
+        provider: PaymentProviders,
+        external_reference: String,
+        provider_data: serde_json::Value,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Payment, DatabaseError> {
+        // Lock the installment so a scheduled charge and a manual payment cannot both be recorded
+        let current: PaymentPlanInstallment = payment_plan_installments::table
+            .find(self.id)
+            .for_update()
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find payment plan installment")?;
+        if current.status != PaymentPlanInstallmentStatus::Pending {
+            return DatabaseError::business_process_error("Installment is not pending payment");
+        }
+        let plan = self.payment_plan(conn)?;
+        if plan.status != PaymentPlanStatus::Active {
+            return DatabaseError::business_process_error("Payment plan is not active");
+        }
+
+        let mut order = Order::find(plan.order_id, conn)?;
+        let mut new_payment = Payment::create(
+            order.id,
+            current_user_id,
+            PaymentStatus::Completed,
+            PaymentMethods::CreditCard,
+            provider,
+            Some(external_reference),
+            self.amount_in_cents,
+            Some(provider_data),
+            None,
+            None,
+        );
+        // Codes only need to be valid when the order is placed, later installments are collected
+        // after the code may have ended
+        let payment = if order.status == OrderStatus::Draft {
+            order.add_payment(new_payment, current_user_id, conn)?
+        } else {
+            new_payment.currency = order.currency(conn)?;
+            let payment = new_payment.commit(current_user_id, conn)?;
+            order.complete_if_fully_paid(current_user_id, conn)?;
+            payment
+        };
+
+        diesel::update(self)
+            .set((
+                payment_plan_installments::status.eq(PaymentPlanInstallmentStatus::Paid),
+                payment_plan_installments::payment_id.eq(payment.id),
+                payment_plan_installments::paid_at.eq(dsl::now.nullable()),
+                payment_plan_installments::updated_at.eq(dsl::now),
+            ))
+            .execute(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not mark installment as paid")?;
+
+        DomainEvent::create(
+            DomainEventTypes::PaymentPlanInstallmentPaid,
+            format!("Installment {} paid", self.installment_number),
+            Tables::PaymentPlanInstallments,
+            Some(self.id),
+            current_user_id,
+            Some(json!({
+                "payment_plan_id": plan.id,
+                "payment_id": payment.id,
+                "amount_in_cents": self.amount_in_cents
+            })),
+        )
+        .commit(conn)?;
+
+        let next_installment = plan
+            .installments(conn)?
+            .into_iter()
+            .find(|i| i.status == PaymentPlanInstallmentStatus::Pending);
+        match next_installment {
+            Some(next_installment) => {
+                if order.status == OrderStatus::Draft {
+                    order.update_status(current_user_id, OrderStatus::PartiallyPaid, conn)?;
+                    for item in order
+                        .items(conn)?
+                        .iter()
+                        .filter(|oi| oi.item_type == OrderItemTypes::Tickets)
+                    {
+                        TicketInstance::mark_as_purchased(
+                            item,
+                            order.on_behalf_of_user_id.unwrap_or(order.user_id),
+                            conn,
+                        )?;
+                    }
+                }
+                next_installment.schedule_charge(next_installment.due_at, conn)?;
+            }
+            None => {
+                plan.update_status(PaymentPlanStatus::Completed, conn)?;
+                DomainEvent::create(
+                    DomainEventTypes::PaymentPlanCompleted,
+                    "Payment plan completed".to_string(),
+                    Tables::PaymentPlans,
+                    Some(plan.id),
+                    current_user_id,
+                    Some(json!({ "order_id": order.id })),
+                )
+                .commit(conn)?;
+            }
+        }
+
+        Ok(payment)
+    }
+
+    /// Records a failed attempt to charge the installment. The plan is cancelled once the
+    /// maximum number of attempts is reached, otherwise the charge is retried later.
+    pub fn record_failed_attempt(&self, reason: &str, conn: &PgConnection) -> Result<PaymentPlan, DatabaseError> {
+        let installment: PaymentPlanInstallment = diesel::update(self)
+            .set((
+                payment_plan_installments::failed_attempts.eq(payment_plan_installments::failed_attempts + 1),
+                payment_plan_installments::last_failure_reason.eq(reason),
+                payment_plan_installments::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not record failed installment attempt")?;
+
+        DomainEvent::create(
+            DomainEventTypes::PaymentPlanInstallmentFailed,
+            format!("Installment {} could not be charged", self.installment_number),
+            Tables::PaymentPlanInstallments,
+            Some(self.id),
+            None,
+            Some(json!({
+                "payment_plan_id": self.payment_plan_id,
+                "failed_attempts": installment.failed_attempts,
+                "reason": reason
+            })),
+        )
+        .commit(conn)?;
+
+        let plan = self.payment_plan(conn)?;
+        if installment.failed_attempts >= plan.max_failed_attempts {
+            plan.cancel(None, conn)
+        } else {
+            installment.schedule_charge(
+                Utc::now().naive_utc() + Duration::days(PAYMENT_PLAN_RETRY_INTERVAL_DAYS),
+                conn,
+            )?;
+            Ok(plan)
+        }
+    }
+
+    fn schedule_charge(&self, charge_at: NaiveDateTime, conn: &PgConnection) -> Result<(), DatabaseError> {
+        let mut action = DomainAction::create(
+            None,
+            DomainActionTypes::ChargePaymentPlanInstallment,
+            None,
+            json!({}),
+            Some(Tables::PaymentPlanInstallments),
+            Some(self.id),
+        );
+        action.schedule_at(charge_at);
+        action.expires_at = charge_at.into_builder().add_days(1).finish();
+        action.commit(conn)?;
+        Ok(())
+    }
+}
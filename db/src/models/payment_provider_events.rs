@@ -0,0 +1,100 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::payment_provider_events;
+use serde_json;
+use utils::dates::IntoDateBuilder;
+use utils::errors::*;
+use uuid::Uuid;
+
+/// Webhook event received from a payment provider, stored as delivered so it can be reprocessed
+#[derive(Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[table_name = "payment_provider_events"]
+pub struct PaymentProviderEvent {
+    pub id: Uuid,
+    pub provider: PaymentProviders,
+    /// Provider's id for the event, providers may deliver the same event more than once
+    pub external_id: String,
+    pub event_type: String,
+    pub payload: serde_json::Value,
+    pub processed_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Insertable)]
+#[table_name = "payment_provider_events"]
+pub struct NewPaymentProviderEvent {
+    pub provider: PaymentProviders,
+    pub external_id: String,
+    pub event_type: String,
+    pub payload: serde_json::Value,
+}
+
+impl NewPaymentProviderEvent {
+    /// Stores the event and queues it for processing. Returns `None` when an event with the same
+    /// external id has already been received from the provider.
+    pub fn commit(self, conn: &PgConnection) -> Result<Option<PaymentProviderEvent>, DatabaseError> {
+        let event: Option<PaymentProviderEvent> = diesel::insert_into(payment_provider_events::table)
+            .values(&self)
+            .on_conflict((payment_provider_events::provider, payment_provider_events::external_id))
+            .do_nothing()
+            .get_result(conn)
+            .optional()
+            .to_db_error(ErrorCode::InsertError, "Could not create payment provider event")?;
+
+        if let Some(ref event) = event {
+            let mut action = DomainAction::create(
+                None,
+                DomainActionTypes::ProcessPaymentProviderEvent,
+                None,
+                json!({}),
+                Some(Tables::PaymentProviderEvents),
+                Some(event.id),
+            );
+            action.expires_at = action.scheduled_at.into_builder().add_days(30).finish();
+            action.max_attempt_count = 5;
+            action.commit(conn)?;
+        }
+
+        Ok(event)
+    }
+}
+
+impl PaymentProviderEvent {
+    pub fn create(
+        provider: PaymentProviders,
+        external_id: String,
+        event_type: String,
+        payload: serde_json::Value,
+    ) -> NewPaymentProviderEvent {
+        NewPaymentProviderEvent {
+            provider,
+            external_id,
+            event_type,
+            payload,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<PaymentProviderEvent, DatabaseError> {
+        payment_provider_events::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find payment provider event")
+    }
+
+    pub fn mark_processed(&self, conn: &PgConnection) -> Result<PaymentProviderEvent, DatabaseError> {
+        diesel::update(self)
+            .set((
+                payment_provider_events::processed_at.eq(dsl::now.nullable()),
+                payment_provider_events::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(
+                ErrorCode::UpdateError,
+                "Could not mark payment provider event as processed",
+            )
+    }
+}
@@ -2,9 +2,12 @@ use chrono::Duration;
 use chrono::NaiveDateTime;
 use chrono::Utc;
 use diesel;
+use diesel::dsl::{exists, select};
 use diesel::expression::dsl;
+use diesel::expression::sql_literal::sql;
 use diesel::pg::PgConnection;
 use diesel::prelude::*;
+use diesel::sql_types::{Bool, Text};
 use models::*;
 use schema::{orders, payments};
 use serde_json;
@@ -27,6 +30,7 @@ pub struct Payment {
     updated_at: NaiveDateTime,
     pub url_nonce: Option<String>,
     pub refund_id: Option<Uuid>,
+    pub currency: String,
 }
 
 impl Payment {
@@ -53,6 +57,7 @@ impl Payment {
             raw_data,
             url_nonce,
             refund_id,
+            currency: None,
         }
     }
 
@@ -96,6 +101,21 @@ impl Payment {
             .expect_single()
     }
 
+    /// Original payment made through a provider, refunds logged against it share the reference
+    pub fn find_by_external_reference(
+        provider: PaymentProviders,
+        external_reference: &str,
+        conn: &PgConnection,
+    ) -> Result<Payment, DatabaseError> {
+        payments::table
+            .filter(payments::provider.eq(provider))
+            .filter(payments::external_reference.eq(external_reference))
+            .filter(payments::refund_id.is_null())
+            .order_by(payments::created_at)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find payment")
+    }
+
     pub fn log_refund(
         &self,
         current_user_id: Uuid,
@@ -104,7 +124,7 @@ impl Payment {
         refund_data: Option<serde_json::Value>,
         conn: &PgConnection,
     ) -> Result<Payment, DatabaseError> {
-        let refund_payment = Payment::create(
+        let mut refund_payment = Payment::create(
             self.order_id,
             self.created_by,
             PaymentStatus::Refunded,
@@ -115,8 +135,9 @@ impl Payment {
             refund_data.clone(),
             None,
             Some(refund.id),
-        )
-        .commit(Some(current_user_id), conn)?;
+        );
+        refund_payment.currency = Some(self.currency.clone());
+        let refund_payment = refund_payment.commit(Some(current_user_id), conn)?;
 
         DomainEvent::create(
             DomainEventTypes::PaymentRefund,
@@ -130,6 +151,256 @@ impl Payment {
         Ok(refund_payment)
     }
 
+    /// Returns the refunded amount to the gift card or account credit this payment was made with
+    pub fn refund_to_stored_value(
+        &self,
+        refund: &Refund,
+        refund_amount: i64,
+        current_user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<StoredValueLedgerEntry, DatabaseError> {
+        let stored_value_id = match self
+            .external_reference
+            .as_ref()
+            .and_then(|reference| Uuid::parse_str(reference).ok())
+        {
+            Some(id) => id,
+            None => return DatabaseError::business_process_error("Payment is not linked to a stored value balance"),
+        };
+
+        match self.payment_method {
+            PaymentMethods::GiftCard => {
+                let gift_card = GiftCard::find_for_update(stored_value_id, conn)?;
+                if gift_card.deactivated_at.is_some() {
+                    return DatabaseError::business_process_error(
+                        "Gift card has been deactivated, refund as account credit instead",
+                    );
+                }
+                gift_card.change_balance(
+                    StoredValueLedgerEntryTypes::Refunded,
+                    refund_amount,
+                    Some(self.order_id),
+                    Some(self.id),
+                    Some(refund.id),
+                    Some(current_user_id),
+                    conn,
+                )
+            }
+            PaymentMethods::AccountCredit => {
+                let account_credit = AccountCredit::find(stored_value_id, conn)?;
+                account_credit.change_balance(
+                    StoredValueLedgerEntryTypes::Refunded,
+                    refund_amount,
+                    Some(self.order_id),
+                    Some(self.id),
+                    Some(refund.id),
+                    Some(current_user_id),
+                    conn,
+                )
+            }
+            _ => DatabaseError::business_process_error("Payment was not made with stored value"),
+        }
+    }
+
+    /// Cancels a gift card or account credit payment on an order that was never completed and
+    /// puts the value back on the balance it was taken from
+    pub fn return_to_stored_value(
+        &self,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<StoredValueLedgerEntry, DatabaseError> {
+        if self.status != PaymentStatus::Completed {
+            return DatabaseError::business_process_error("Only completed payments can be returned");
+        }
+        let stored_value_id = match self
+            .external_reference
+            .as_ref()
+            .and_then(|reference| Uuid::parse_str(reference).ok())
+        {
+            Some(id) => id,
+            None => return DatabaseError::business_process_error("Payment is not linked to a stored value balance"),
+        };
+
+        let ledger_entry = match self.payment_method {
+            PaymentMethods::GiftCard => GiftCard::find_for_update(stored_value_id, conn)?.change_balance(
+                StoredValueLedgerEntryTypes::Returned,
+                self.amount,
+                Some(self.order_id),
+                Some(self.id),
+                None,
+                current_user_id,
+                conn,
+            )?,
+            PaymentMethods::AccountCredit => AccountCredit::find(stored_value_id, conn)?.change_balance(
+                StoredValueLedgerEntryTypes::Returned,
+                self.amount,
+                Some(self.order_id),
+                Some(self.id),
+                None,
+                current_user_id,
+                conn,
+            )?,
+            _ => return DatabaseError::business_process_error("Payment was not made with stored value"),
+        };
+
+        DomainEvent::create(
+            DomainEventTypes::PaymentCancelled,
+            "Stored value payment was returned".to_string(),
+            Tables::Payments,
+            Some(self.id),
+            current_user_id,
+            Some(json!({ "order_id": self.order_id, "amount": self.amount })),
+        )
+        .commit(conn)?;
+        self.update_status(PaymentStatus::Cancelled, current_user_id, conn)?;
+
+        Ok(ledger_entry)
+    }
+
+    /// Records a refund made directly with the payment provider, e.g. from its dashboard. Returns
+    /// `None` if a refund with this reference has already been logged against the order.
+    pub fn record_external_refund(
+        &self,
+        refund_reference: &str,
+        amount: i64,
+        reason: Option<String>,
+        raw_data: serde_json::Value,
+        conn: &PgConnection,
+    ) -> Result<Option<Refund>, DatabaseError> {
+        // Refunds made through the API hold the order lock until they are logged, waiting on it
+        // means a refund still being committed is seen below instead of being recorded twice
+        let order = Order::find_for_update(self.order_id, conn)?;
+
+        let already_recorded: bool = select(exists(
+            payments::table
+                .filter(payments::order_id.eq(self.order_id))
+                .filter(payments::refund_id.is_not_null())
+                .filter(sql::<Bool>("payments.raw_data ->> 'id' = ").bind::<Text, _>(refund_reference)),
+        ))
+        .get_result(conn)
+        .to_db_error(ErrorCode::QueryError, "Could not check if refund has been recorded")?;
+        if already_recorded || Refund::find_by_external_reference(refund_reference, conn)?.is_some() {
+            return Ok(None);
+        }
+
+        // The provider does not say who issued the refund so it is attributed to the purchaser.
+        // It also only knows the amount so the refund is flagged for the items to be refunded by hand.
+        let user_id = order.on_behalf_of_user_id.unwrap_or(order.user_id);
+        let mut refund = Refund::create(self.order_id, user_id, reason, true);
+        refund.external_reference = Some(refund_reference.to_string());
+        refund.requires_item_review = true;
+        let refund = refund.commit(conn)?;
+        self.log_refund(user_id, &refund, amount, Some(raw_data), conn)?;
+
+        Ok(Some(refund))
+    }
+
+    /// Records a dispute (chargeback) raised against the payment, deducting the disputed amount
+    /// from the organization's pending settlement. No adjustment is made when the organization has
+    /// no pending settlement, the dispute event is left for the adjustment to be entered by hand.
+    pub fn record_dispute(
+        &self,
+        dispute_reference: &str,
+        amount: i64,
+        raw_data: serde_json::Value,
+        conn: &PgConnection,
+    ) -> Result<Option<SettlementAdjustment>, DatabaseError> {
+        let adjustment =
+            self.create_dispute_adjustment(SettlementAdjustmentTypes::Chargeback, dispute_reference, amount, conn)?;
+
+        DomainEvent::create(
+            DomainEventTypes::PaymentDisputeCreated,
+            "Payment was disputed".to_string(),
+            Tables::Payments,
+            Some(self.id),
+            None,
+            Some(json!({
+                "dispute_reference": dispute_reference,
+                "amount": amount,
+                "settlement_adjustment_id": adjustment.as_ref().map(|a| a.id),
+                "raw_data": raw_data
+            })),
+        )
+        .commit(conn)?;
+
+        Ok(adjustment)
+    }
+
+    /// Records the outcome of a dispute, a won dispute credits back the chargeback made for it
+    pub fn record_dispute_closed(
+        &self,
+        dispute_reference: &str,
+        won: bool,
+        raw_data: serde_json::Value,
+        conn: &PgConnection,
+    ) -> Result<Option<SettlementAdjustment>, DatabaseError> {
+        let chargeback = SettlementAdjustment::find_by_external_reference(
+            SettlementAdjustmentTypes::Chargeback,
+            dispute_reference,
+            conn,
+        )?;
+        let adjustment = match chargeback {
+            Some(ref chargeback) if won => self.create_dispute_adjustment(
+                SettlementAdjustmentTypes::ManualCredit,
+                dispute_reference,
+                chargeback.amount_in_cents,
+                conn,
+            )?,
+            _ => None,
+        };
+
+        DomainEvent::create(
+            DomainEventTypes::PaymentDisputeClosed,
+            format!("Payment dispute was {}", if won { "won" } else { "lost" }),
+            Tables::Payments,
+            Some(self.id),
+            None,
+            Some(json!({
+                "dispute_reference": dispute_reference,
+                "won": won,
+                "settlement_adjustment_id": adjustment.as_ref().map(|a| a.id),
+                "raw_data": raw_data
+            })),
+        )
+        .commit(conn)?;
+
+        Ok(adjustment)
+    }
+
+    fn create_dispute_adjustment(
+        &self,
+        settlement_adjustment_type: SettlementAdjustmentTypes,
+        dispute_reference: &str,
+        amount: i64,
+        conn: &PgConnection,
+    ) -> Result<Option<SettlementAdjustment>, DatabaseError> {
+        if let Some(adjustment) =
+            SettlementAdjustment::find_by_external_reference(settlement_adjustment_type, dispute_reference, conn)?
+        {
+            return Ok(Some(adjustment));
+        }
+
+        let order = self.order(conn)?;
+        let organizations = order.organizations(conn)?;
+        if organizations.len() != 1 {
+            return Ok(None);
+        }
+        let settlement = match Settlement::find_pending_for_organization(organizations[0].id, conn)? {
+            Some(settlement) => settlement,
+            None => return Ok(None),
+        };
+
+        let mut adjustment = SettlementAdjustment::create(
+            settlement.id,
+            settlement_adjustment_type,
+            Some(format!("Dispute {} for order {}", dispute_reference, order.id)),
+            amount,
+        );
+        adjustment.payment_id = Some(self.id);
+        adjustment.external_reference = Some(dispute_reference.to_string());
+        Ok(Some(adjustment.commit(conn)?))
+    }
+
     pub fn add_ipn(
         &self,
         new_status: PaymentStatus,
@@ -225,6 +496,34 @@ impl Payment {
 
         Ok(())
     }
+    /// Records the processor authorizing a requested card payment once the shopper has passed the
+    /// issuer challenge, the charge still needs to be completed. Processors may only assign their
+    /// reference for the charge once it is authorized.
+    pub fn mark_authorized(
+        &self,
+        external_reference: &str,
+        raw_data: serde_json::Value,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Payment, DatabaseError> {
+        if self.status != PaymentStatus::Requested {
+            return DatabaseError::business_process_error(
+                "Could not mark payment as authorized because it is not awaiting authorization",
+            );
+        }
+        diesel::update(self)
+            .set((
+                payments::external_reference.eq(external_reference),
+                payments::updated_at.eq(dsl::now),
+            ))
+            .execute(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update payment reference")?;
+        self.add_ipn(PaymentStatus::Authorized, raw_data, current_user_id, conn)?;
+        self.order(conn)?.clear_user_cart(conn)?;
+
+        Payment::find(self.id, conn)
+    }
+
     pub fn mark_pending_ipn(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<(), DatabaseError> {
         if self.status != PaymentStatus::Completed {
             self.update_status(PaymentStatus::PendingIpn, current_user_id, conn)?;
@@ -289,6 +588,8 @@ pub struct NewPayment {
     raw_data: Option<serde_json::Value>,
     url_nonce: Option<String>,
     refund_id: Option<Uuid>,
+    /// Set from the order's currency when the payment is added to an order
+    pub currency: Option<String>,
 }
 
 impl NewPayment {
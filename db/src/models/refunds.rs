@@ -17,6 +17,10 @@ pub struct Refund {
     #[serde(skip_serializing)]
     pub settlement_id: Option<Uuid>,
     pub manual_override: bool,
+    /// Provider reference of a refund made with the payment provider directly
+    pub external_reference: Option<String>,
+    /// Set when the refunded items are not known and have to be refunded by hand
+    pub requires_item_review: bool,
 }
 
 impl Refund {
@@ -26,6 +30,8 @@ impl Refund {
             user_id,
             reason,
             manual_override,
+            external_reference: None,
+            requires_item_review: false,
         }
     }
 
@@ -36,6 +42,17 @@ impl Refund {
             .to_db_error(ErrorCode::QueryError, "Could not retrieve refund data")
     }
 
+    pub fn find_by_external_reference(
+        external_reference: &str,
+        conn: &PgConnection,
+    ) -> Result<Option<Refund>, DatabaseError> {
+        refunds::table
+            .filter(refunds::external_reference.eq(external_reference))
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Could not retrieve refund data")
+    }
+
     pub fn order(&self, conn: &PgConnection) -> Result<Order, DatabaseError> {
         Order::find(self.order_id, conn)
     }
@@ -55,6 +72,8 @@ pub struct NewRefund {
     pub user_id: Uuid,
     pub reason: Option<String>,
     pub manual_override: bool,
+    pub external_reference: Option<String>,
+    pub requires_item_review: bool,
 }
 
 impl NewRefund {
@@ -271,6 +271,12 @@ pub struct TransactionReportRow {
     #[serde(skip_serializing)]
     #[sql_type = "BigInt"]
     pub event_fee_client_in_cents_total: i64,
+    #[sql_type = "BigInt"]
+    pub tax_in_cents: i64,
+    #[sql_type = "BigInt"]
+    pub tax_in_cents_total: i64,
+    #[sql_type = "Text"]
+    pub currency: String,
     #[sql_type = "Nullable<dUuid>"]
     pub fee_range_id: Option<Uuid>,
     #[sql_type = "Text"]
@@ -328,6 +334,7 @@ pub struct TransactionReportRow {
 #[derive(Serialize, Deserialize)]
 pub struct EventSummarySalesResult {
     pub event_id: Uuid,
+    pub currency: String,
     pub sales: Vec<EventSummarySalesRow>,
     pub ticket_fees: Vec<EventSummaryFeesRow>,
     pub other_fees: Vec<EventSummaryOtherFees>,
@@ -337,6 +344,7 @@ impl Default for EventSummarySalesResult {
     fn default() -> Self {
         EventSummarySalesResult {
             event_id: Uuid::nil(),
+            currency: String::new(),
             sales: vec![],
             ticket_fees: vec![],
             other_fees: vec![],
@@ -356,6 +364,8 @@ pub struct BoxOfficeSalesSummaryReportRow {
     pub event_date: Option<NaiveDateTime>,
     #[sql_type = "Nullable<Text>"]
     pub external_payment_type: Option<ExternalPaymentType>,
+    #[sql_type = "Text"]
+    pub currency: String,
     #[sql_type = "BigInt"]
     pub number_of_tickets: i64,
     #[sql_type = "BigInt"]
@@ -375,6 +385,7 @@ pub struct BoxOfficeSalesSummaryReport {
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct BoxOfficeSalesSummaryPaymentRow {
     pub payment_type: ExternalPaymentType,
+    pub currency: String,
     pub quantity: u32,
     pub total_sales_in_cents: u32,
 }
@@ -391,6 +402,7 @@ pub struct BoxOfficeSalesSummaryOperatorRow {
 pub struct BoxOfficeSalesSummaryOperatorEventRow {
     pub event_name: Option<String>,
     pub event_date: Option<NaiveDateTime>,
+    pub currency: String,
     pub number_of_tickets: u32,
     pub face_value_in_cents: u32,
     pub revenue_share_value_in_cents: u32,
@@ -411,6 +423,8 @@ pub struct EventSummarySalesRow {
     pub ticket_name: String,
     #[sql_type = "Text"]
     pub pricing_name: String,
+    #[sql_type = "Text"]
+    pub currency: String,
     #[sql_type = "BigInt"]
     pub total_client_fee_in_cents: i64,
     #[sql_type = "BigInt"]
@@ -492,6 +506,7 @@ pub struct ScanCountReportRow {
 pub struct ReconciliationSummaryResult {
     pub payment_method: String,
     pub payment_provider: String,
+    pub currency: String,
     pub quantity: i64,
     pub unit_price_in_cents: i64,
     pub client_fee_in_cents: i64,
@@ -537,6 +552,7 @@ pub struct ReconciliationDetailEventResult {
     pub event_id: Uuid,
     pub event_name: String,
     pub event_start: Option<NaiveDateTime>,
+    pub currency: String,
     pub entries: Vec<ReconciliationDetailResult>,
 }
 
@@ -743,35 +759,39 @@ impl Report {
             .get_results(conn)
             .to_db_error(ErrorCode::QueryError, "Could not fetch report results")?;
 
-        let mut payment_totals: HashMap<ExternalPaymentType, BoxOfficeSalesSummaryPaymentRow> = HashMap::new();
+        // Payment totals are grouped by currency as well as payment type
+        let mut payment_totals: HashMap<(ExternalPaymentType, String), BoxOfficeSalesSummaryPaymentRow> =
+            HashMap::new();
         let mut operator_data: Vec<BoxOfficeSalesSummaryOperatorRow> = Vec::new();
         let mut operator_payments: HashMap<Uuid, Vec<BoxOfficeSalesSummaryPaymentRow>> = HashMap::new();
         for (operator_id, group) in &payment_box_office_summary_rows
             .into_iter()
             .group_by(|row| row.operator_id)
         {
-            let mut payments: HashMap<ExternalPaymentType, BoxOfficeSalesSummaryPaymentRow> = HashMap::new();
+            let mut payments: HashMap<(ExternalPaymentType, String), BoxOfficeSalesSummaryPaymentRow> = HashMap::new();
             for group_item in group {
                 if let Some(external_payment_type) = group_item.external_payment_type {
                     payment_totals
-                        .entry(external_payment_type)
+                        .entry((external_payment_type, group_item.currency.clone()))
                         .and_modify(|e| {
                             e.quantity += group_item.number_of_tickets as u32;
                             e.total_sales_in_cents += group_item.total_sales_in_cents as u32;
                         })
                         .or_insert_with(|| BoxOfficeSalesSummaryPaymentRow {
                             payment_type: external_payment_type,
+                            currency: group_item.currency.clone(),
                             quantity: group_item.number_of_tickets as u32,
                             total_sales_in_cents: group_item.total_sales_in_cents as u32,
                         });
                     payments
-                        .entry(external_payment_type)
+                        .entry((external_payment_type, group_item.currency.clone()))
                         .and_modify(|e| {
                             e.quantity += group_item.number_of_tickets as u32;
                             e.total_sales_in_cents += group_item.total_sales_in_cents as u32;
                         })
                         .or_insert_with(|| BoxOfficeSalesSummaryPaymentRow {
                             payment_type: external_payment_type,
+                            currency: group_item.currency.clone(),
                             quantity: group_item.number_of_tickets as u32,
                             total_sales_in_cents: group_item.total_sales_in_cents as u32,
                         });
@@ -782,7 +802,7 @@ impl Report {
                 .values()
                 .map(|v| (*v).clone())
                 .collect::<Vec<BoxOfficeSalesSummaryPaymentRow>>();
-            payments.sort_by_key(|p| p.payment_type.to_string());
+            payments.sort_by_key(|p| (p.payment_type.to_string(), p.currency.clone()));
             operator_payments.insert(operator_id, payments);
         }
 
@@ -797,6 +817,7 @@ impl Report {
                 events.push(BoxOfficeSalesSummaryOperatorEventRow {
                     event_name: group_item.event_name.clone(),
                     event_date: group_item.event_date,
+                    currency: group_item.currency.clone(),
                     number_of_tickets: group_item.number_of_tickets as u32,
                     face_value_in_cents: group_item.face_value_in_cents as u32,
                     revenue_share_value_in_cents: group_item.revenue_share_value_in_cents as u32,
@@ -816,7 +837,7 @@ impl Report {
             .values()
             .map(|v| (*v).clone())
             .collect::<Vec<BoxOfficeSalesSummaryPaymentRow>>();
-        payment_totals.sort_by_key(|p| p.payment_type.to_string());
+        payment_totals.sort_by_key(|p| (p.payment_type.to_string(), p.currency.clone()));
 
         Ok(BoxOfficeSalesSummaryReport {
             operators: operator_data,
@@ -898,6 +919,7 @@ impl Report {
             true => {
                 let mut event_summary = EventSummarySalesResult { ..Default::default() };
                 event_summary.event_id = event_id;
+                event_summary.currency = Event::find(event_id, conn)?.currency;
                 event_summary
             }
             false => results.pop().unwrap(),
@@ -986,9 +1008,12 @@ impl Report {
 
         // assume that an event must have sales in order to have other fees
         for (event_id, sales) in sales_rows.into_iter() {
+            let sales = sales.into_iter().collect_vec();
             result.push(EventSummarySalesResult {
                 event_id,
-                sales: sales.into_iter().collect_vec(),
+                // Events are sold in a single currency so each event's totals share it
+                currency: sales[0].currency.clone(),
+                sales,
                 ticket_fees: fees_hash.get(&event_id).unwrap_or(&vec![]).to_vec(),
                 other_fees: other_fees_hash.get(&event_id).unwrap_or(&vec![]).to_vec(),
             })
@@ -1092,14 +1117,17 @@ impl Report {
         let mut results: Vec<ReconciliationSummaryResult> = Vec::new();
         for row in transaction_rows {
             if row.payment_method.is_some() && row.payment_provider.is_some() {
+                // Totals are never summed across currencies
                 let entry_exists = results.iter().any(|r| {
                     r.payment_method == row.payment_method.clone().unwrap()
                         && r.payment_provider == row.payment_provider.clone().unwrap()
+                        && r.currency == row.currency
                 });
                 if entry_exists {
                     if let Some(entry) = results.iter_mut().find(|r| {
                         r.payment_method == row.payment_method.clone().unwrap()
                             && r.payment_provider == row.payment_provider.clone().unwrap()
+                            && r.currency == row.currency
                     }) {
                         let ticket_face = row.unit_price_in_cents * row.actual_quantity;
                         let client_fee = row.client_fee_in_cents * row.actual_quantity;
@@ -1133,6 +1161,7 @@ impl Report {
                     results.push(ReconciliationSummaryResult {
                         payment_method: row.payment_method.unwrap(),
                         payment_provider: row.payment_provider.unwrap(),
+                        currency: row.currency,
                         quantity: row.actual_quantity,
                         unit_price_in_cents: ticket_face,
                         client_fee_in_cents: client_fee,
@@ -1235,6 +1264,7 @@ impl Report {
                     event_id: row.event_id.clone(),
                     event_name: row.event_name.clone(),
                     event_start: row.event_start.clone(),
+                    currency: row.currency.clone(),
                     entries: Vec::new(),
                 });
             }
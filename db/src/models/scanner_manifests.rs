@@ -0,0 +1,120 @@
+use chrono::prelude::*;
+use diesel::PgConnection;
+use hex;
+use models::*;
+use ring::{digest, hmac};
+use serde_json;
+use utils::errors::*;
+use uuid::Uuid;
+
+/// Compact list of the tickets for an event allowing door staff to validate scans while offline
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ScannerManifest {
+    pub event_id: Uuid,
+    pub generated_at: NaiveDateTime,
+    pub changes_since: Option<NaiveDateTime>,
+    pub tickets: Vec<ScannerManifestTicket>,
+    pub signature: String,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ScannerManifestTicket {
+    pub id: Uuid,
+    pub ticket_type: String,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub status: TicketInstanceStatus,
+    /// Hidden until the event's redeem date the same as the guest list
+    pub redeem_key_hash: Option<String>,
+    pub redeemed_at: Option<NaiveDateTime>,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct OfflineRedemption {
+    pub ticket_id: Uuid,
+    pub redeem_key: String,
+    pub scanned_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct OfflineRedemptionResult {
+    pub ticket_id: Uuid,
+    pub status: OfflineRedemptionStatus,
+    /// The ticket was transferred after it was scanned at the door, the scan takes precedence
+    pub transferred_after_scan: bool,
+    pub redeemed_at: Option<NaiveDateTime>,
+    pub redeemed_by_user_id: Option<Uuid>,
+}
+
+impl ScannerManifest {
+    pub fn create(
+        event_id: Uuid,
+        changes_since: Option<NaiveDateTime>,
+        signing_secret: &str,
+        conn: &PgConnection,
+    ) -> Result<ScannerManifest, DatabaseError> {
+        let (redeemable_tickets, _) =
+            Event::guest_list_tickets(Some(event_id), None, None, &changes_since, None, conn)?;
+        let tickets = redeemable_tickets
+            .into_iter()
+            .map(|t| ScannerManifestTicket {
+                redeem_key_hash: t
+                    .redeem_key
+                    .as_ref()
+                    .map(|k| ScannerManifest::hash_redeem_key(t.id, k, signing_secret)),
+                id: t.id,
+                ticket_type: t.ticket_type,
+                first_name: t.first_name,
+                last_name: t.last_name,
+                status: t.status,
+                redeemed_at: t.redeemed_at,
+                updated_at: t.updated_at,
+            })
+            .collect();
+
+        let mut manifest = ScannerManifest {
+            event_id,
+            generated_at: Utc::now().naive_utc(),
+            changes_since,
+            tickets,
+            signature: "".to_string(),
+        };
+        manifest.signature = manifest.generate_signature(signing_secret)?;
+        Ok(manifest)
+    }
+
+    /// Redeem keys are hashed so that scanners can compare a scanned key without the manifest
+    /// containing keys that could be used to redeem the tickets online. The hash is keyed with the
+    /// manifest secret held by the scanners as redeem keys are short enough to brute force otherwise.
+    pub fn hash_redeem_key(ticket_id: Uuid, redeem_key: &str, signing_secret: &str) -> String {
+        let key = hmac::SigningKey::new(&digest::SHA256, signing_secret.as_bytes());
+        let data = format!("{}:{}", ticket_id, redeem_key);
+        hex::encode(hmac::sign(&key, data.as_bytes()).as_ref())
+    }
+
+    pub fn verify_signature(&self, signing_secret: &str) -> Result<bool, DatabaseError> {
+        let signature = match hex::decode(&self.signature) {
+            Ok(signature) => signature,
+            Err(_) => return Ok(false),
+        };
+        let key = hmac::SigningKey::new(&digest::SHA256, signing_secret.as_bytes());
+        Ok(hmac::verify_with_own_key(&key, self.signed_payload()?.as_bytes(), &signature).is_ok())
+    }
+
+    fn generate_signature(&self, signing_secret: &str) -> Result<String, DatabaseError> {
+        let key = hmac::SigningKey::new(&digest::SHA256, signing_secret.as_bytes());
+        Ok(hex::encode(
+            hmac::sign(&key, self.signed_payload()?.as_bytes()).as_ref(),
+        ))
+    }
+
+    fn signed_payload(&self) -> Result<String, DatabaseError> {
+        Ok(serde_json::to_string(&json!({
+            "event_id": self.event_id,
+            "generated_at": self.generated_at,
+            "changes_since": self.changes_since,
+            "tickets": self.tickets,
+        }))?)
+    }
+}
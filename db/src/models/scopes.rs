@@ -1,4 +1,7 @@
 use models::{AdditionalOrgMemberScopes, Roles};
+use serde::de;
+use serde::Deserialize;
+use serde::Deserializer;
 use serde::Serialize;
 use serde::Serializer;
 use std::fmt;
@@ -71,6 +74,7 @@ pub enum Scopes {
     SettlementRead,
     SettlementReadEarly,
     SettlementWrite,
+    TaxRuleWrite,
     TemporaryUserPromote,
     TransferCancel,
     TransferCancelAccepted,
@@ -100,6 +104,16 @@ impl Serialize for Scopes {
     }
 }
 
+impl<'de> Deserialize<'de> for Scopes {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Scopes::from_str(&s).map_err(de::Error::custom)
+    }
+}
+
 impl fmt::Display for Scopes {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let s = match self {
@@ -167,6 +181,7 @@ impl fmt::Display for Scopes {
             Scopes::SettlementRead => "settlement:read",
             Scopes::SettlementReadEarly => "settlement:read-early",
             Scopes::SettlementWrite => "settlement:write",
+            Scopes::TaxRuleWrite => "tax-rule:write",
             Scopes::TicketAdmin => "ticket:admin",
             Scopes::TicketRead => "ticket:read",
             Scopes::TicketWrite => "ticket:write",
@@ -259,6 +274,7 @@ impl FromStr for Scopes {
             "settlement:read" => Scopes::SettlementRead,
             "settlement:read-early" => Scopes::SettlementReadEarly,
             "settlement:write" => Scopes::SettlementWrite,
+            "tax-rule:write" => Scopes::TaxRuleWrite,
             "temporary-user:promote" => Scopes::TemporaryUserPromote,
             "ticket:admin" => Scopes::TicketAdmin,
             "ticket:read" => Scopes::TicketRead,
@@ -478,6 +494,7 @@ fn get_scopes_for_role(role: Roles) -> Vec<Scopes> {
                 Scopes::SettlementAdjustmentWrite,
                 Scopes::SettlementReadEarly,
                 Scopes::SettlementWrite,
+                Scopes::TaxRuleWrite,
                 Scopes::TransferCancelAccepted,
                 Scopes::UserDelete,
             ];
@@ -718,6 +735,7 @@ fn get_scopes_test() {
             "settlement:read",
             "settlement:read-early",
             "settlement:write",
+            "tax-rule:write",
             "ticket-type:read",
             "ticket-type:write",
             "ticket:admin",
@@ -809,6 +827,7 @@ fn get_scopes_test() {
             "settlement:read",
             "settlement:read-early",
             "settlement:write",
+            "tax-rule:write",
             "ticket-type:read",
             "ticket-type:write",
             "ticket:admin",
@@ -899,6 +918,7 @@ fn get_scopes_test() {
             "settlement:read",
             "settlement:read-early",
             "settlement:write",
+            "tax-rule:write",
             "ticket:admin",
             "ticket:read",
             "ticket:transfer",
@@ -1003,3 +1023,10 @@ fn from_str() {
     let s: Scopes = "ticket:read".parse().unwrap();
     assert_eq!(Scopes::TicketRead, s);
 }
+
+#[test]
+fn deserialize() {
+    let s: Scopes = ::serde_json::from_str("\"ticket:read\"").unwrap();
+    assert_eq!(Scopes::TicketRead, s);
+    assert!(::serde_json::from_str::<Scopes>("\"ticket:unknown\"").is_err());
+}
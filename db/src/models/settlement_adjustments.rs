@@ -18,6 +18,10 @@ pub struct SettlementAdjustment {
     pub settlement_adjustment_type: SettlementAdjustmentTypes,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    /// Payment the adjustment was made for when raised by the payment provider
+    pub payment_id: Option<Uuid>,
+    /// Provider's reference for the adjustment, e.g. the dispute behind a chargeback
+    pub external_reference: Option<String>,
 }
 
 impl SettlementAdjustment {
@@ -32,9 +36,24 @@ impl SettlementAdjustment {
             amount_in_cents,
             note,
             settlement_adjustment_type,
+            payment_id: None,
+            external_reference: None,
         }
     }
 
+    pub fn find_by_external_reference(
+        settlement_adjustment_type: SettlementAdjustmentTypes,
+        external_reference: &str,
+        conn: &PgConnection,
+    ) -> Result<Option<SettlementAdjustment>, DatabaseError> {
+        settlement_adjustments::table
+            .filter(settlement_adjustments::settlement_adjustment_type.eq(settlement_adjustment_type))
+            .filter(settlement_adjustments::external_reference.eq(external_reference))
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Could not load Settlement Adjustment")
+    }
+
     pub fn find(id: Uuid, conn: &PgConnection) -> Result<SettlementAdjustment, DatabaseError> {
         settlement_adjustments::table
             .filter(settlement_adjustments::id.eq(id))
@@ -56,6 +75,8 @@ pub struct NewSettlementAdjustment {
     pub amount_in_cents: i64,
     pub note: Option<String>,
     pub settlement_adjustment_type: SettlementAdjustmentTypes,
+    pub payment_id: Option<Uuid>,
+    pub external_reference: Option<String>,
 }
 impl NewSettlementAdjustment {
     pub fn commit(&self, conn: &PgConnection) -> Result<SettlementAdjustment, DatabaseError> {
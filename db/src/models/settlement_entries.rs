@@ -26,6 +26,8 @@ pub struct SettlementEntry {
     pub settlement_entry_type: SettlementEntryTypes,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub tax_in_cents: i64,
+    pub currency: String,
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Queryable, Serialize)]
@@ -43,6 +45,8 @@ pub struct DisplaySettlementEntry {
     pub settlement_entry_type: SettlementEntryTypes,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub tax_in_cents: i64,
+    pub currency: String,
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Queryable, Serialize)]
@@ -74,6 +78,8 @@ impl SettlementEntry {
                 settlement_entries::settlement_entry_type,
                 settlement_entries::created_at,
                 settlement_entries::updated_at,
+                settlement_entries::tax_in_cents,
+                settlement_entries::currency,
             ))
             .order_by(events::event_start)
             .then_order_by(settlement_entries::event_id)
@@ -119,6 +125,7 @@ impl SettlementEntry {
             settlement_entry_type,
             fee_sold_quantity,
             total_sales_in_cents,
+            currency: None,
         }
     }
 }
@@ -135,14 +142,22 @@ pub struct NewSettlementEntry {
     pub fee_sold_quantity: i64,
     pub total_sales_in_cents: i64,
     pub settlement_entry_type: SettlementEntryTypes,
+    /// Defaults to the currency of the entry's event
+    #[serde(default)]
+    pub currency: Option<String>,
 }
 impl NewSettlementEntry {
     pub fn commit(&self, conn: &PgConnection) -> Result<SettlementEntry, DatabaseError> {
+        let mut new_settlement_entry = self.clone();
+        if new_settlement_entry.currency.is_none() {
+            new_settlement_entry.currency = Some(Event::find(self.event_id, conn)?.currency);
+        }
+
         DatabaseError::wrap(
             ErrorCode::InsertError,
             "Could not create new settlement entry",
             diesel::insert_into(settlement_entries::table)
-                .values(self)
+                .values(&new_settlement_entry)
                 .get_result::<SettlementEntry>(conn),
         )
     }
@@ -30,9 +30,10 @@ pub struct Settlement {
     pub only_finished_events: bool,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub currency: String,
 }
 
-#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug)]
+#[derive(Clone, Insertable, Serialize, Deserialize, PartialEq, Debug)]
 #[table_name = "settlements"]
 pub struct NewSettlement {
     pub organization_id: Uuid,
@@ -41,6 +42,9 @@ pub struct NewSettlement {
     pub status: SettlementStatus,
     pub comment: Option<String>,
     pub only_finished_events: bool,
+    /// Defaults to the organization's currency, entries record the currency of their event
+    #[serde(default)]
+    pub currency: Option<String>,
 }
 
 #[derive(Debug, Deserialize, PartialEq, Serialize)]
@@ -54,11 +58,16 @@ impl NewSettlement {
     pub fn commit(&self, user: Option<User>, conn: &PgConnection) -> Result<Settlement, DatabaseError> {
         self.validate_record()?;
 
+        let mut new_settlement = self.clone();
+        if new_settlement.currency.is_none() {
+            new_settlement.currency = Some(Organization::find(self.organization_id, conn)?.currency);
+        }
+
         let settlement = DatabaseError::wrap(
             ErrorCode::InsertError,
             "Could not create new settlement",
             diesel::insert_into(settlements::table)
-                .values(self)
+                .values(&new_settlement)
                 .get_result::<Settlement>(conn),
         )?;
 
@@ -111,6 +120,7 @@ impl Settlement {
             status,
             comment,
             only_finished_events,
+            currency: None,
         }
     }
 
@@ -185,6 +195,20 @@ impl Settlement {
             .to_db_error(ErrorCode::QueryError, "Could not load settlement")
     }
 
+    /// Most recent settlement for the organization that has not been finalized yet
+    pub fn find_pending_for_organization(
+        organization_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Option<Settlement>, DatabaseError> {
+        settlements::table
+            .filter(settlements::organization_id.eq(organization_id))
+            .filter(settlements::status.eq(SettlementStatus::PendingSettlement))
+            .order_by(settlements::end_time.desc())
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Could not load settlement")
+    }
+
     pub fn process_settlement_for_organization(
         organization: &Organization,
         settlement_period_in_days: Option<u32>,
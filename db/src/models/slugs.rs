@@ -67,6 +67,10 @@ pub enum SlugContext {
         id: Uuid,
         name: String,
     },
+    EventSeries {
+        id: Uuid,
+        name: String,
+    },
 }
 
 impl Slug {
@@ -134,6 +138,12 @@ impl Slug {
                 .select(slugs::all_columns)
                 .load(conn)
                 .to_db_error(ErrorCode::QueryError, "Error loading slug"),
+            Tables::EventSeries => event_series::table
+                .inner_join(slugs::table.on(event_series::slug_id.eq(slugs::id.nullable())))
+                .filter(event_series::id.eq_any(main_table_ids))
+                .select(slugs::all_columns)
+                .load(conn)
+                .to_db_error(ErrorCode::QueryError, "Error loading slug"),
             _ => return DatabaseError::business_process_error("Unable to load primary slug"),
         }
     }
@@ -287,6 +297,11 @@ impl Slug {
                 main_table = Some(Tables::Genres);
                 slug_name = Some(name.clone())
             }
+            SlugContext::EventSeries { id, ref name } => {
+                main_table_id = Some(*id);
+                main_table = Some(Tables::EventSeries);
+                slug_name = Some(name.clone());
+            }
         }
 
         // Sanity check
@@ -0,0 +1,102 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::sso_login_requests;
+use utils::errors::*;
+use utils::oidc;
+use utils::rand::random_alpha_string;
+use uuid::Uuid;
+
+const SSO_LOGIN_REQUEST_EXPIRY_MINUTES: i64 = 10;
+
+/// Authorization request sent to an organization's provider, holds the values the callback is
+/// checked against and can only be used once
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(OrganizationSsoConfiguration)]
+#[table_name = "sso_login_requests"]
+pub struct SsoLoginRequest {
+    pub id: Uuid,
+    pub organization_sso_configuration_id: Uuid,
+    pub state: String,
+    pub nonce: String,
+    #[serde(default, skip_serializing)]
+    pub code_verifier: String,
+    pub redirect_uri: String,
+    pub expires_at: NaiveDateTime,
+    pub consumed_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    /// Signed in user who started the request, only they can link the identity to their account
+    pub user_id: Option<Uuid>,
+}
+
+#[derive(Clone, Deserialize, Insertable, Serialize)]
+#[table_name = "sso_login_requests"]
+pub struct NewSsoLoginRequest {
+    pub organization_sso_configuration_id: Uuid,
+    pub state: String,
+    pub nonce: String,
+    pub code_verifier: String,
+    pub redirect_uri: String,
+    pub expires_at: NaiveDateTime,
+    pub user_id: Option<Uuid>,
+}
+
+impl NewSsoLoginRequest {
+    pub fn commit(self, conn: &PgConnection) -> Result<SsoLoginRequest, DatabaseError> {
+        diesel::insert_into(sso_login_requests::table)
+            .values(&self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create single sign-on login request")
+    }
+}
+
+impl SsoLoginRequest {
+    pub fn create(
+        organization_sso_configuration_id: Uuid,
+        redirect_uri: String,
+        user_id: Option<Uuid>,
+    ) -> NewSsoLoginRequest {
+        NewSsoLoginRequest {
+            organization_sso_configuration_id,
+            state: random_alpha_string(32),
+            nonce: random_alpha_string(32),
+            code_verifier: random_alpha_string(64),
+            redirect_uri,
+            expires_at: Utc::now().naive_utc() + Duration::minutes(SSO_LOGIN_REQUEST_EXPIRY_MINUTES),
+            user_id,
+        }
+    }
+
+    /// Marks the request matching the callback state as used, `None` if it is unknown, expired or
+    /// has already been used
+    pub fn consume(state: &str, conn: &PgConnection) -> Result<Option<SsoLoginRequest>, DatabaseError> {
+        diesel::update(
+            sso_login_requests::table
+                .filter(sso_login_requests::state.eq(state))
+                .filter(sso_login_requests::consumed_at.is_null())
+                .filter(sso_login_requests::expires_at.gt(dsl::now)),
+        )
+        .set((
+            sso_login_requests::consumed_at.eq(dsl::now.nullable()),
+            sso_login_requests::updated_at.eq(dsl::now),
+        ))
+        .get_result(conn)
+        .optional()
+        .to_db_error(ErrorCode::UpdateError, "Could not consume single sign-on login request")
+    }
+
+    pub fn code_challenge(&self) -> String {
+        oidc::pkce_challenge(&self.code_verifier)
+    }
+
+    pub fn organization_sso_configuration(
+        &self,
+        conn: &PgConnection,
+    ) -> Result<OrganizationSsoConfiguration, DatabaseError> {
+        OrganizationSsoConfiguration::find(self.organization_sso_configuration_id, conn)
+    }
+}
@@ -0,0 +1,133 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::dsl::{exists, select};
+use diesel::prelude::*;
+use models::*;
+use schema::{stage_seats, stage_sections, ticket_instances};
+use utils::errors::ConvertToDatabaseError;
+use utils::errors::DatabaseError;
+use utils::errors::ErrorCode;
+use uuid::Uuid;
+
+#[derive(Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[belongs_to(StageSection)]
+#[table_name = "stage_seats"]
+pub struct StageSeat {
+    pub id: Uuid,
+    pub stage_section_id: Uuid,
+    pub row_name: String,
+    pub seat_number: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Default, Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "stage_seats"]
+pub struct NewStageSeat {
+    pub stage_section_id: Uuid,
+    pub row_name: String,
+    pub seat_number: String,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Queryable, Serialize)]
+pub struct DisplaySeat {
+    pub id: Uuid,
+    pub section_name: String,
+    pub row_name: String,
+    pub seat_number: String,
+}
+
+impl DisplaySeat {
+    /// Human readable seat location, e.g. "Balcony B-12"
+    pub fn label(&self) -> String {
+        format!("{} {}-{}", self.section_name, self.row_name, self.seat_number)
+    }
+}
+
+impl NewStageSeat {
+    pub fn commit(&self, connection: &PgConnection) -> Result<StageSeat, DatabaseError> {
+        diesel::insert_into(stage_seats::table)
+            .values(self)
+            .get_result(connection)
+            .to_db_error(ErrorCode::InsertError, "Could not create stage seat")
+    }
+}
+
+impl StageSeat {
+    pub fn create(stage_section_id: Uuid, row_name: String, seat_number: String) -> NewStageSeat {
+        NewStageSeat {
+            stage_section_id,
+            row_name,
+            seat_number,
+        }
+    }
+
+    pub fn create_multiple(new_seats: &[NewStageSeat], conn: &PgConnection) -> Result<Vec<StageSeat>, DatabaseError> {
+        diesel::insert_into(stage_seats::table)
+            .values(new_seats)
+            .get_results(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create stage seats")
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<StageSeat, DatabaseError> {
+        DatabaseError::wrap(
+            ErrorCode::QueryError,
+            "Error loading stage seat",
+            stage_seats::table.find(id).first::<StageSeat>(conn),
+        )
+    }
+
+    pub fn find_by_stage_section_id(
+        stage_section_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<StageSeat>, DatabaseError> {
+        stage_seats::table
+            .filter(stage_seats::stage_section_id.eq(stage_section_id))
+            .order_by(stage_seats::row_name)
+            .then_order_by(stage_seats::seat_number)
+            .select(stage_seats::all_columns)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load stage seats")
+    }
+
+    pub fn find_for_display(ids: &[Uuid], conn: &PgConnection) -> Result<Vec<DisplaySeat>, DatabaseError> {
+        stage_seats::table
+            .inner_join(stage_sections::table.on(stage_sections::id.eq(stage_seats::stage_section_id)))
+            .filter(stage_seats::id.eq_any(ids))
+            .order_by(stage_sections::name)
+            .then_order_by(stage_seats::row_name)
+            .then_order_by(stage_seats::seat_number)
+            .select((
+                stage_seats::id,
+                stage_sections::name,
+                stage_seats::row_name,
+                stage_seats::seat_number,
+            ))
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load seats for display")
+    }
+
+    pub fn section(&self, conn: &PgConnection) -> Result<StageSection, DatabaseError> {
+        StageSection::find(self.stage_section_id, conn)
+    }
+
+    pub fn is_assigned(&self, conn: &PgConnection) -> Result<bool, DatabaseError> {
+        select(exists(
+            ticket_instances::table.filter(ticket_instances::seat_id.eq(self.id)),
+        ))
+        .get_result(conn)
+        .to_db_error(ErrorCode::QueryError, "Could not check if seat is assigned to tickets")
+    }
+
+    pub fn destroy(&self, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        if self.is_assigned(conn)? {
+            return DatabaseError::business_process_error("Unable to delete a seat that has been assigned to tickets");
+        }
+
+        DatabaseError::wrap(
+            ErrorCode::DeleteError,
+            "Failed to delete stage seat",
+            diesel::delete(self).execute(conn),
+        )
+    }
+}
@@ -0,0 +1,121 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::{stage_sections, stages};
+use utils::errors::ConvertToDatabaseError;
+use utils::errors::DatabaseError;
+use utils::errors::ErrorCode;
+use uuid::Uuid;
+
+#[derive(Clone, Associations, Identifiable, Queryable, Serialize, Deserialize, PartialEq, Debug)]
+#[belongs_to(Stage)]
+#[table_name = "stage_sections"]
+pub struct StageSection {
+    pub id: Uuid,
+    pub stage_id: Uuid,
+    pub name: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(AsChangeset, Default, Deserialize)]
+#[table_name = "stage_sections"]
+pub struct StageSectionEditableAttributes {
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub name: Option<String>,
+}
+
+#[derive(Default, Insertable, Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[table_name = "stage_sections"]
+pub struct NewStageSection {
+    pub stage_id: Uuid,
+    pub name: String,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DisplayStageSection {
+    pub id: Uuid,
+    pub name: String,
+    pub seats: Vec<StageSeat>,
+}
+
+impl NewStageSection {
+    pub fn commit(&self, connection: &PgConnection) -> Result<StageSection, DatabaseError> {
+        diesel::insert_into(stage_sections::table)
+            .values(self)
+            .get_result(connection)
+            .to_db_error(ErrorCode::InsertError, "Could not create stage section")
+    }
+}
+
+impl StageSection {
+    pub fn create(stage_id: Uuid, name: String) -> NewStageSection {
+        NewStageSection { stage_id, name }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<StageSection, DatabaseError> {
+        DatabaseError::wrap(
+            ErrorCode::QueryError,
+            "Error loading stage section",
+            stage_sections::table.find(id).first::<StageSection>(conn),
+        )
+    }
+
+    pub fn find_by_stage_id(stage_id: Uuid, conn: &PgConnection) -> Result<Vec<StageSection>, DatabaseError> {
+        stage_sections::table
+            .filter(stage_sections::stage_id.eq(stage_id))
+            .order_by(stage_sections::name)
+            .select(stage_sections::all_columns)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load stage sections")
+    }
+
+    pub fn stage(&self, conn: &PgConnection) -> Result<Stage, DatabaseError> {
+        stages::table
+            .find(self.stage_id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Unable to load stage for stage section")
+    }
+
+    pub fn seats(&self, conn: &PgConnection) -> Result<Vec<StageSeat>, DatabaseError> {
+        StageSeat::find_by_stage_section_id(self.id, conn)
+    }
+
+    pub fn for_display(&self, conn: &PgConnection) -> Result<DisplayStageSection, DatabaseError> {
+        Ok(DisplayStageSection {
+            id: self.id,
+            name: self.name.clone(),
+            seats: self.seats(conn)?,
+        })
+    }
+
+    pub fn update(
+        &self,
+        attributes: StageSectionEditableAttributes,
+        conn: &PgConnection,
+    ) -> Result<StageSection, DatabaseError> {
+        DatabaseError::wrap(
+            ErrorCode::UpdateError,
+            "Could not update stage section",
+            diesel::update(self)
+                .set((attributes, stage_sections::updated_at.eq(dsl::now)))
+                .get_result(conn),
+        )
+    }
+
+    /// Removes the section along with its seats, provided none of its seats have been
+    /// assigned to tickets
+    pub fn destroy(&self, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        for seat in self.seats(conn)? {
+            seat.destroy(conn)?;
+        }
+
+        DatabaseError::wrap(
+            ErrorCode::DeleteError,
+            "Failed to delete stage section",
+            diesel::delete(self).execute(conn),
+        )
+    }
+}
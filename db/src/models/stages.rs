@@ -80,6 +80,17 @@ impl Stage {
             .to_db_error(ErrorCode::QueryError, "Unable to load all stages")
     }
 
+    pub fn sections(&self, conn: &PgConnection) -> Result<Vec<StageSection>, DatabaseError> {
+        StageSection::find_by_stage_id(self.id, conn)
+    }
+
+    pub fn seat_map(&self, conn: &PgConnection) -> Result<Vec<DisplayStageSection>, DatabaseError> {
+        self.sections(conn)?
+            .iter()
+            .map(|section| section.for_display(conn))
+            .collect()
+    }
+
     pub fn update(&self, attributes: StageEditableAttributes, conn: &PgConnection) -> Result<Stage, DatabaseError> {
         DatabaseError::wrap(
             ErrorCode::UpdateError,
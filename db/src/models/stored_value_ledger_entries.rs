@@ -0,0 +1,86 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::prelude::*;
+use models::*;
+use schema::stored_value_ledger_entries;
+use utils::errors::*;
+use uuid::Uuid;
+
+/// A single change to a gift card or account credit balance. Every issue, redemption, refund and
+/// deactivation is recorded so balances can be reconciled against their history.
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(AccountCredit)]
+#[belongs_to(GiftCard)]
+#[table_name = "stored_value_ledger_entries"]
+pub struct StoredValueLedgerEntry {
+    pub id: Uuid,
+    pub gift_card_id: Option<Uuid>,
+    pub account_credit_id: Option<Uuid>,
+    pub entry_type: StoredValueLedgerEntryTypes,
+    /// Positive when value is added to the balance, negative when it is spent
+    pub amount_in_cents: i64,
+    pub balance_after_in_cents: i64,
+    pub order_id: Option<Uuid>,
+    pub payment_id: Option<Uuid>,
+    pub refund_id: Option<Uuid>,
+    pub created_by: Option<Uuid>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "stored_value_ledger_entries"]
+pub(crate) struct NewStoredValueLedgerEntry {
+    pub gift_card_id: Option<Uuid>,
+    pub account_credit_id: Option<Uuid>,
+    pub entry_type: StoredValueLedgerEntryTypes,
+    pub amount_in_cents: i64,
+    pub balance_after_in_cents: i64,
+    pub order_id: Option<Uuid>,
+    pub payment_id: Option<Uuid>,
+    pub refund_id: Option<Uuid>,
+    pub created_by: Option<Uuid>,
+}
+
+impl NewStoredValueLedgerEntry {
+    pub(crate) fn commit(self, conn: &PgConnection) -> Result<StoredValueLedgerEntry, DatabaseError> {
+        diesel::insert_into(stored_value_ledger_entries::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create ledger entry")
+    }
+}
+
+impl StoredValueLedgerEntry {
+    pub fn find_for_gift_card(
+        gift_card_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<StoredValueLedgerEntry>, DatabaseError> {
+        stored_value_ledger_entries::table
+            .filter(stored_value_ledger_entries::gift_card_id.eq(gift_card_id))
+            .order_by(stored_value_ledger_entries::created_at)
+            .then_order_by(stored_value_ledger_entries::id)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load gift card ledger entries")
+    }
+
+    pub fn find_for_account_credit(
+        account_credit_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<StoredValueLedgerEntry>, DatabaseError> {
+        stored_value_ledger_entries::table
+            .filter(stored_value_ledger_entries::account_credit_id.eq(account_credit_id))
+            .order_by(stored_value_ledger_entries::created_at)
+            .then_order_by(stored_value_ledger_entries::id)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load account credit ledger entries")
+    }
+
+    pub fn find_for_order(order_id: Uuid, conn: &PgConnection) -> Result<Vec<StoredValueLedgerEntry>, DatabaseError> {
+        stored_value_ledger_entries::table
+            .filter(stored_value_ledger_entries::order_id.eq(order_id))
+            .order_by(stored_value_ledger_entries::created_at)
+            .then_order_by(stored_value_ledger_entries::id)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load order ledger entries")
+    }
+}
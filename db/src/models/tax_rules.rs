@@ -0,0 +1,183 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::tax_rules;
+use utils::errors::*;
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+use validators;
+
+#[derive(Clone, Deserialize, Identifiable, Queryable, Serialize, Debug, PartialEq)]
+pub struct TaxRule {
+    pub id: Uuid,
+    pub name: String,
+    pub venue_id: Option<Uuid>,
+    pub region_id: Option<Uuid>,
+    pub rate_percent: f32,
+    pub applies_to_fees: bool,
+    pub inclusive: bool,
+    pub deleted_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Default, Insertable, Serialize, Deserialize, PartialEq, Debug, Validate)]
+#[table_name = "tax_rules"]
+pub struct NewTaxRule {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    pub venue_id: Option<Uuid>,
+    pub region_id: Option<Uuid>,
+    pub rate_percent: f32,
+    #[serde(default)]
+    pub applies_to_fees: bool,
+    #[serde(default)]
+    pub inclusive: bool,
+}
+
+#[derive(AsChangeset, Default, Deserialize, Debug, Validate)]
+#[table_name = "tax_rules"]
+pub struct TaxRuleEditableAttributes {
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    #[validate(length(min = 1, max = 100))]
+    pub name: Option<String>,
+    pub rate_percent: Option<f32>,
+    pub applies_to_fees: Option<bool>,
+    pub inclusive: Option<bool>,
+}
+
+impl TaxRule {
+    pub fn create(
+        name: String,
+        venue_id: Option<Uuid>,
+        region_id: Option<Uuid>,
+        rate_percent: f32,
+        applies_to_fees: bool,
+        inclusive: bool,
+    ) -> NewTaxRule {
+        NewTaxRule {
+            name,
+            venue_id,
+            region_id,
+            rate_percent,
+            applies_to_fees,
+            inclusive,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<TaxRule, DatabaseError> {
+        tax_rules::table
+            .filter(tax_rules::id.eq(id))
+            .filter(tax_rules::deleted_at.is_null())
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load tax rule")
+    }
+
+    pub fn find_for_venue(venue_id: Uuid, conn: &PgConnection) -> Result<Vec<TaxRule>, DatabaseError> {
+        tax_rules::table
+            .filter(tax_rules::venue_id.eq(venue_id))
+            .filter(tax_rules::deleted_at.is_null())
+            .order_by(tax_rules::created_at.asc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load tax rules for venue")
+    }
+
+    pub fn find_for_region(region_id: Uuid, conn: &PgConnection) -> Result<Vec<TaxRule>, DatabaseError> {
+        tax_rules::table
+            .filter(tax_rules::region_id.eq(region_id))
+            .filter(tax_rules::venue_id.is_null())
+            .filter(tax_rules::deleted_at.is_null())
+            .order_by(tax_rules::created_at.asc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load tax rules for region")
+    }
+
+    /// Rules applying to an event, venue specific rules take precedence over those of the venue's region
+    pub fn find_for_event(event: &Event, conn: &PgConnection) -> Result<Vec<TaxRule>, DatabaseError> {
+        let venue = match event.venue(conn)? {
+            Some(venue) => venue,
+            None => return Ok(vec![]),
+        };
+
+        let rules = TaxRule::find_for_venue(venue.id, conn)?;
+        if !rules.is_empty() {
+            return Ok(rules);
+        }
+
+        match venue.region_id {
+            Some(region_id) => TaxRule::find_for_region(region_id, conn),
+            None => Ok(vec![]),
+        }
+    }
+
+    /// Tax portion of the provided amount. Inclusive rules treat the amount as already containing the tax.
+    pub fn tax_for(&self, amount_in_cents: i64) -> i64 {
+        let rate = self.rate_percent as f64 / 100f64;
+        if self.inclusive {
+            amount_in_cents - (amount_in_cents as f64 / (1f64 + rate)).round() as i64
+        } else {
+            (amount_in_cents as f64 * rate).round() as i64
+        }
+    }
+
+    pub fn update(&self, attributes: TaxRuleEditableAttributes, conn: &PgConnection) -> Result<TaxRule, DatabaseError> {
+        attributes.validate()?;
+        if let Some(rate_percent) = attributes.rate_percent {
+            validators::append_validation_error(Ok(()), "rate_percent", TaxRule::rate_percent_valid(rate_percent))?;
+        }
+
+        diesel::update(self)
+            .set((attributes, tax_rules::updated_at.eq(dsl::now)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update tax rule")
+    }
+
+    pub fn destroy(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        diesel::update(self)
+            .set((
+                tax_rules::deleted_at.eq(dsl::now.nullable()),
+                tax_rules::updated_at.eq(dsl::now),
+            ))
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not delete tax rule")?;
+        Ok(())
+    }
+
+    fn rate_percent_valid(rate_percent: f32) -> Result<(), ValidationError> {
+        if rate_percent < 0f32 || rate_percent >= 100f32 {
+            return Err(validators::create_validation_error(
+                "rate_percent_out_of_range",
+                "Rate percent must be at least 0 and less than 100",
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl NewTaxRule {
+    pub fn commit(self, conn: &PgConnection) -> Result<TaxRule, DatabaseError> {
+        let mut validation_errors = validators::append_validation_error(
+            self.validate(),
+            "rate_percent",
+            TaxRule::rate_percent_valid(self.rate_percent),
+        );
+        if self.venue_id.is_none() && self.region_id.is_none() {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "venue_id",
+                Err(validators::create_validation_error(
+                    "venue_or_region_required",
+                    "Tax rules must belong to a venue or a region",
+                )),
+            );
+        }
+        validation_errors?;
+
+        diesel::insert_into(tax_rules::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create tax rule")
+    }
+}
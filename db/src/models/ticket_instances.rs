@@ -13,7 +13,8 @@ use models::*;
 use rand;
 use rand::Rng;
 use schema::{
-    assets, events, order_items, orders, organizations, ticket_instances, ticket_types, transfers, users, wallets,
+    assets, events, order_items, orders, organizations, payment_plans, stage_seats, stage_sections, ticket_instances,
+    ticket_types, transfer_tickets, transfers, users, wallets,
 };
 use std::cmp;
 use tari_client::*;
@@ -44,6 +45,7 @@ pub struct TicketInstance {
     pub check_in_source: Option<CheckInSource>,
     parent_id: Option<Uuid>,
     pub listing_id: Option<Uuid>,
+    pub seat_id: Option<Uuid>,
 }
 
 #[derive(AsChangeset, Clone, Deserialize, Serialize)]
@@ -210,6 +212,8 @@ impl TicketInstance {
                     WHERE tt.ticket_instance_id = ticket_instances.id
                     AND t.status = 'Pending'
                 )")))
+            .left_join(stage_seats::table.on(ticket_instances::seat_id.eq(stage_seats::id.nullable())))
+            .left_join(stage_sections::table.on(stage_sections::id.eq(stage_seats::stage_section_id)))
             .filter(ticket_instances::id.eq(id))
             .select((
                 ticket_instances::id,
@@ -240,6 +244,10 @@ impl TicketInstance {
                 transfers::transfer_address.nullable(),
                 ticket_instances::check_in_source,
                 ticket_types::promo_image_url,
+                ticket_instances::seat_id,
+                stage_sections::name.nullable(),
+                stage_seats::row_name.nullable(),
+                stage_seats::seat_number.nullable(),
             ))
             .first::<DisplayTicketIntermediary>(conn)
             .to_db_error(ErrorCode::QueryError, "Unable to load ticket")?;
@@ -317,6 +325,8 @@ impl TicketInstance {
                         WHERE tt.ticket_instance_id = ticket_instances.id
                         AND t.status = 'Pending'
                     )")))
+            .left_join(stage_seats::table.on(ticket_instances::seat_id.eq(stage_seats::id.nullable())))
+            .left_join(stage_sections::table.on(stage_sections::id.eq(stage_seats::stage_section_id)))
             .filter(
                 events::event_end.ge(start_time.unwrap_or_else(|| NaiveDate::from_ymd(1970, 1, 1).and_hms(0, 0, 0))),
             )
@@ -358,6 +368,10 @@ impl TicketInstance {
                 transfers::transfer_address.nullable(),
                 ticket_instances::check_in_source,
                 ticket_types::promo_image_url,
+                ticket_instances::seat_id,
+                stage_sections::name.nullable(),
+                stage_seats::row_name.nullable(),
+                stage_seats::seat_number.nullable(),
             ))
             .order_by(events::event_start.asc())
             .then_order_by(events::name.asc())
@@ -499,6 +513,63 @@ impl TicketInstance {
         Ok(tickets)
     }
 
+    pub fn reserve_seats(
+        order_item: &OrderItem,
+        expires_at: Option<NaiveDateTime>,
+        ticket_type_id: Uuid,
+        seat_ids: &[Uuid],
+        conn: &PgConnection,
+    ) -> Result<Vec<TicketInstance>, DatabaseError> {
+        let order_expires_at = expires_at.ok_or(DatabaseError::new(
+            ErrorCode::BusinessProcessError,
+            Some("Expiration date was not set on cart prior to reserving tickets".to_string()),
+        ))?;
+
+        let query = include_str!("../queries/reserve_seated_tickets.sql");
+        let q = diesel::sql_query(query)
+            .bind::<sql_types::Uuid, _>(order_item.id)
+            .bind::<sql_types::Timestamp, _>(order_expires_at)
+            .bind::<sql_types::Uuid, _>(ticket_type_id)
+            .bind::<Array<sql_types::Uuid>, _>(seat_ids);
+        let tickets: Vec<TicketInstance> = q
+            .get_results(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not reserve seats")?;
+
+        if tickets.len() != seat_ids.len() {
+            jlog!(Debug, "Could not reserve all requested seats", {"ticket_type_id": ticket_type_id, "seat_ids": seat_ids, "reserved": tickets.len()});
+            return DatabaseError::validation_error(
+                "seat_ids",
+                "Could not reserve seats, one or more seats are not available",
+            );
+        }
+
+        Ok(tickets)
+    }
+
+    pub fn assign_seats(
+        ticket_type_id: Uuid,
+        seat_ids: &[Uuid],
+        conn: &PgConnection,
+    ) -> Result<Vec<TicketInstance>, DatabaseError> {
+        let query = include_str!("../queries/assign_seats_to_tickets.sql");
+        let q = diesel::sql_query(query)
+            .bind::<sql_types::Uuid, _>(ticket_type_id)
+            .bind::<Array<sql_types::Uuid>, _>(seat_ids)
+            .bind::<BigInt, _>(seat_ids.len() as i64);
+        let tickets: Vec<TicketInstance> = q
+            .get_results(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not assign seats to tickets")?;
+
+        if tickets.len() != seat_ids.len() {
+            return DatabaseError::validation_error(
+                "seat_ids",
+                "Could not assign seats, not enough unseated tickets are available",
+            );
+        }
+
+        Ok(tickets)
+    }
+
     fn validate_record(&self, update_attrs: &UpdateTicketInstanceAttributes) -> Result<(), DatabaseError> {
         let mut validation_errors = Ok(());
         let first_name = update_attrs
@@ -759,6 +830,13 @@ impl TicketInstance {
             }
         }
 
+        let ticket_ids: Vec<Uuid> = tickets.iter().map(|t| t.id).collect();
+        if TicketInstance::awaiting_payment_plan(&ticket_ids, conn)? {
+            return DatabaseError::business_process_error(
+                "Tickets on an unpaid payment plan cannot be listed for resale",
+            );
+        }
+
         for ticket in tickets.iter() {
             DomainEvent::create(
                 DomainEventTypes::TicketInstanceAddedToListing,
@@ -914,6 +992,20 @@ impl TicketInstance {
             .to_db_error(ErrorCode::QueryError, "Could not load Ticket Instances")
     }
 
+    /// Whether any of the tickets were purchased on a payment plan that has not been fully paid.
+    /// These tickets cannot be transferred, listed or redeemed until the final installment is collected.
+    pub fn awaiting_payment_plan(ticket_ids: &[Uuid], conn: &PgConnection) -> Result<bool, DatabaseError> {
+        let count: i64 = ticket_instances::table
+            .inner_join(order_items::table.on(ticket_instances::order_item_id.eq(order_items::id.nullable())))
+            .inner_join(payment_plans::table.on(payment_plans::order_id.eq(order_items::order_id)))
+            .filter(ticket_instances::id.eq_any(ticket_ids))
+            .filter(payment_plans::status.eq(PaymentPlanStatus::Active))
+            .select(dsl::count(ticket_instances::id))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not check payment plans for tickets")?;
+        Ok(count > 0)
+    }
+
     pub fn find_ids_for_order(order_id: Uuid, conn: &PgConnection) -> Result<Vec<Uuid>, DatabaseError> {
         ticket_instances::table
             .inner_join(order_items::table.on(ticket_instances::order_item_id.eq(order_items::id.nullable())))
@@ -1039,6 +1131,8 @@ impl TicketInstance {
             .to_db_error(ErrorCode::QueryError, "Unable to load ticket")?;
         if ticket.has_pending_transfer(conn)? {
             return Ok(RedeemResults::TicketTransferInProcess);
+        } else if TicketInstance::awaiting_payment_plan(&[ticket.id], conn)? {
+            return Ok(RedeemResults::TicketAwaitingPayment);
         } else if ticket.status == TicketInstanceStatus::Purchased
             && ticket.redeem_key.is_some()
             && ticket.redeem_key.clone().unwrap() == redeem_key
@@ -1071,6 +1165,130 @@ impl TicketInstance {
         Ok(RedeemResults::TicketRedeemSuccess)
     }
 
+    /// Replays redemptions scanned while the scanner was offline, oldest scan first. Tickets already
+    /// redeemed are reported as double scans leaving the original redemption in place. Transfers
+    /// made after the ticket was scanned at the door do not prevent the redemption.
+    pub fn redeem_offline_tickets(
+        event_id: Uuid,
+        redemptions: &[OfflineRedemption],
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<OfflineRedemptionResult>, DatabaseError> {
+        let mut redemptions = redemptions.to_vec();
+        redemptions.sort_by_key(|r| r.scanned_at);
+
+        let mut results = Vec::new();
+        for redemption in redemptions {
+            results.push(TicketInstance::redeem_offline_ticket(
+                event_id,
+                &redemption,
+                user_id,
+                conn,
+            )?);
+        }
+        Ok(results)
+    }
+
+    fn redeem_offline_ticket(
+        event_id: Uuid,
+        redemption: &OfflineRedemption,
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<OfflineRedemptionResult, DatabaseError> {
+        let mut result = OfflineRedemptionResult {
+            ticket_id: redemption.ticket_id,
+            status: OfflineRedemptionStatus::Invalid,
+            transferred_after_scan: false,
+            redeemed_at: None,
+            redeemed_by_user_id: None,
+        };
+
+        // Lock the ticket so simultaneous uploads from multiple scanners cannot both redeem it
+        let ticket: Option<TicketInstance> = ticket_instances::table
+            .find(redemption.ticket_id)
+            .for_update()
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Unable to load ticket")?;
+        let ticket = match ticket {
+            Some(ticket) => ticket,
+            None => return Ok(result),
+        };
+        if ticket.event(conn)?.id != event_id {
+            return Ok(result);
+        }
+
+        if ticket.status == TicketInstanceStatus::Redeemed {
+            result.status = OfflineRedemptionStatus::AlreadyRedeemed;
+            result.redeemed_at = ticket.redeemed_at;
+            result.redeemed_by_user_id = ticket.redeemed_by_user_id;
+            return Ok(result);
+        }
+
+        if ticket.status != TicketInstanceStatus::Purchased
+            || ticket.redeem_key.as_ref() != Some(&redemption.redeem_key)
+        {
+            return Ok(result);
+        }
+
+        if TicketInstance::awaiting_payment_plan(&[ticket.id], conn)? {
+            result.status = OfflineRedemptionStatus::AwaitingPayment;
+            return Ok(result);
+        }
+
+        // Scanner clocks may drift, a scan cannot be recorded as happening in the future
+        let scanned_at = cmp::min(redemption.scanned_at, Utc::now().naive_utc());
+        if let Some(transfer) = TransferTicket::pending_transfer(ticket.id, conn)? {
+            if transfer.created_at <= scanned_at {
+                result.status = OfflineRedemptionStatus::TransferInProcess;
+                return Ok(result);
+            }
+            result.transferred_after_scan = true;
+        }
+        let transfers_after_scan: i64 = transfers::table
+            .inner_join(transfer_tickets::table.on(transfer_tickets::transfer_id.eq(transfers::id)))
+            .filter(transfer_tickets::ticket_instance_id.eq(ticket.id))
+            .filter(transfers::status.eq(TransferStatus::Completed))
+            .filter(transfers::updated_at.gt(scanned_at))
+            .select(dsl::count(transfers::id))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load transfers for ticket")?;
+        if transfers_after_scan > 0 {
+            result.transferred_after_scan = true;
+        }
+
+        diesel::update(ticket_instances::table.filter(ticket_instances::id.eq(ticket.id)))
+            .set((
+                ticket_instances::status.eq(TicketInstanceStatus::Redeemed),
+                ticket_instances::redeemed_by_user_id.eq(user_id),
+                ticket_instances::redeemed_at.eq(scanned_at),
+                ticket_instances::check_in_source.eq(CheckInSource::Scanned),
+                ticket_instances::updated_at.eq(dsl::now),
+            ))
+            .execute(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not set ticket to Redeemed")?;
+
+        DomainEvent::create(
+            DomainEventTypes::TicketInstanceRedeemed,
+            "Ticket redeemed".to_string(),
+            Tables::TicketInstances,
+            Some(ticket.id),
+            Some(user_id),
+            Some(json!({
+                "check_in_source": CheckInSource::Scanned,
+                "offline": true,
+                "scanned_at": scanned_at,
+                "transferred_after_scan": result.transferred_after_scan
+            })),
+        )
+        .commit(conn)?;
+
+        result.status = OfflineRedemptionStatus::Redeemed;
+        result.redeemed_at = Some(scanned_at);
+        result.redeemed_by_user_id = Some(user_id);
+        Ok(result)
+    }
+
     pub fn show_redeemable_ticket(ticket_id: Uuid, conn: &PgConnection) -> Result<RedeemableTicket, DatabaseError> {
         let tickets_and_counts = Event::guest_list_tickets(None, Some(ticket_id), None, &None, None, conn)?;
 
@@ -1128,12 +1346,16 @@ impl TicketInstance {
         let mut ticket_ids_and_updated_at = vec![];
         let mut all_tickets_valid = true;
         let mut has_redeemed_tickets = false;
+        let mut has_listed_tickets = false;
         let mut wallet_id = Uuid::nil();
 
         for ti in ticket_ids {
             let mut found_and_purchased = false;
             for t in &tickets {
-                if t.id == *ti && t.status == TicketInstanceStatus::Purchased {
+                if t.id == *ti && t.listing_id.is_some() {
+                    has_listed_tickets = true;
+                    break;
+                } else if t.id == *ti && t.status == TicketInstanceStatus::Purchased {
                     found_and_purchased = true;
                     ticket_ids_and_updated_at.push((*ti, t.updated_at));
                     wallet_id = t.wallet_id;
@@ -1151,8 +1373,12 @@ impl TicketInstance {
 
         if has_redeemed_tickets {
             return DatabaseError::business_process_error("Redeemed tickets cannot be transferred");
+        } else if has_listed_tickets {
+            return DatabaseError::business_process_error("Tickets listed for resale cannot be transferred");
         } else if !all_tickets_valid || tickets.len() == 0 {
             return DatabaseError::business_process_error("User does not own all requested tickets");
+        } else if TicketInstance::awaiting_payment_plan(ticket_ids, conn)? {
+            return DatabaseError::business_process_error("Tickets on an unpaid payment plan cannot be transferred");
         }
 
         Ok((wallet_id, ticket_ids_and_updated_at))
@@ -1196,6 +1422,11 @@ impl TicketInstance {
         }
 
         transfer.update_associated_orders(conn)?;
+        let transfer = if direct {
+            transfer
+        } else {
+            transfer.schedule_expiry(conn)?
+        };
 
         // Log transfer event after associating transfer tickets
         DomainEvent::create(
@@ -1283,6 +1514,18 @@ impl TicketInstance {
                     Some("Cannot transfer ticket, event has ended.".to_string()),
                 ));
             }
+            TransferStatus::Declined => {
+                return Err(DatabaseError::new(
+                    ErrorCode::BusinessProcessError,
+                    Some("The transfer has been declined.".to_string()),
+                ));
+            }
+            TransferStatus::Expired => {
+                return Err(DatabaseError::new(
+                    ErrorCode::BusinessProcessError,
+                    Some("The transfer has expired.".to_string()),
+                ));
+            }
             _ => (),
         }
 
@@ -1401,6 +1644,7 @@ pub struct DisplayTicket {
     pub transfer_address: Option<String>,
     pub check_in_source: Option<CheckInSource>,
     pub promo_image_url: Option<String>,
+    pub seat: Option<DisplaySeat>,
 }
 
 #[derive(Queryable, QueryableByName)]
@@ -1445,6 +1689,14 @@ pub struct DisplayTicketIntermediary {
     pub check_in_source: Option<CheckInSource>,
     #[sql_type = "Nullable<Text>"]
     pub promo_image_url: Option<String>,
+    #[sql_type = "Nullable<dUuid>"]
+    pub seat_id: Option<Uuid>,
+    #[sql_type = "Nullable<Text>"]
+    pub section_name: Option<String>,
+    #[sql_type = "Nullable<Text>"]
+    pub row_name: Option<String>,
+    #[sql_type = "Nullable<Text>"]
+    pub seat_number: Option<String>,
 }
 
 impl From<DisplayTicketIntermediary> for DisplayTicket {
@@ -1472,6 +1724,21 @@ impl From<DisplayTicketIntermediary> for DisplayTicket {
             None
         };
 
+        let seat = match (
+            ticket_intermediary.seat_id,
+            ticket_intermediary.section_name,
+            ticket_intermediary.row_name,
+            ticket_intermediary.seat_number,
+        ) {
+            (Some(id), Some(section_name), Some(row_name), Some(seat_number)) => Some(DisplaySeat {
+                id,
+                section_name,
+                row_name,
+                seat_number,
+            }),
+            _ => None,
+        };
+
         DisplayTicket {
             id: ticket_intermediary.id,
             order_id: ticket_intermediary.order_id,
@@ -1488,6 +1755,7 @@ impl From<DisplayTicketIntermediary> for DisplayTicket {
             transfer_address: ticket_intermediary.transfer_address,
             check_in_source: ticket_intermediary.check_in_source,
             promo_image_url: ticket_intermediary.promo_image_url,
+            seat,
         }
     }
 }
@@ -1547,6 +1815,7 @@ pub enum RedeemResults {
     TicketAlreadyRedeemed,
     TicketInvalid,
     TicketTransferInProcess,
+    TicketAwaitingPayment,
 }
 
 fn generate_redeem_key(len: u32) -> String {
@@ -3,10 +3,12 @@ use dev::times;
 use diesel;
 use diesel::dsl::{self, exists, select, sql};
 use diesel::prelude::*;
-use diesel::sql_types::{Bool, Timestamp, Uuid as dUuid};
+use diesel::sql_types::{BigInt, Bool, Timestamp, Uuid as dUuid};
 use models::*;
 use schema::{order_items, orders, ticket_pricing};
+use serde_with::rust::double_option;
 use std::borrow::Cow;
+use std::cmp;
 use utils::errors::*;
 use uuid::Uuid;
 use validator::*;
@@ -29,6 +31,8 @@ pub struct TicketPricing {
     created_at: NaiveDateTime,
     updated_at: NaiveDateTime,
     pub previous_ticket_pricing_id: Option<Uuid>,
+    pub currency: String,
+    pub sold_quantity_cap: Option<i64>,
 }
 
 #[derive(AsChangeset, Clone, Default, Deserialize, Serialize)]
@@ -39,6 +43,8 @@ pub struct TicketPricingEditableAttributes {
     pub start_date: Option<NaiveDateTime>,
     pub end_date: Option<NaiveDateTime>,
     pub is_box_office_only: Option<bool>,
+    #[serde(default, deserialize_with = "double_option::deserialize")]
+    pub sold_quantity_cap: Option<Option<i64>>,
 }
 
 impl TicketPricing {
@@ -94,6 +100,8 @@ impl TicketPricing {
             price_in_cents,
             is_box_office_only,
             previous_ticket_pricing_id,
+            currency: None,
+            sold_quantity_cap: None,
         }
     }
 
@@ -106,16 +114,34 @@ impl TicketPricing {
                 attributes.end_date.unwrap_or(self.end_date),
             ),
         );
+        let validation_errors = validators::append_validation_error(
+            validation_errors,
+            "ticket_pricing.sold_quantity_cap",
+            TicketPricing::sold_quantity_cap_valid(attributes.sold_quantity_cap.unwrap_or(self.sold_quantity_cap)),
+        );
         Ok(validation_errors?)
     }
 
+    pub fn sold_quantity_cap_valid(sold_quantity_cap: Option<i64>) -> Result<(), ValidationError> {
+        match sold_quantity_cap {
+            Some(sold_quantity_cap) => validators::validate_greater_than_or_equal(
+                sold_quantity_cap,
+                1,
+                "sold_quantity_cap_must_be_positive",
+                "Sold quantity cap must be at least 1",
+            ),
+            None => Ok(()),
+        }
+    }
+
     pub fn has_changes(&self, attributes: &TicketPricingEditableAttributes) -> bool {
         !((attributes.name.is_none() || Some(self.name.clone()) == attributes.name)
             && (attributes.price_in_cents.is_none() || Some(self.price_in_cents) == attributes.price_in_cents)
             && (attributes.start_date.is_none() || Some(self.start_date) == attributes.start_date)
             && (attributes.end_date.is_none() || Some(self.end_date) == attributes.end_date)
             && (attributes.is_box_office_only.is_none()
-                || Some(self.is_box_office_only) == attributes.is_box_office_only))
+                || Some(self.is_box_office_only) == attributes.is_box_office_only)
+            && (attributes.sold_quantity_cap.is_none() || Some(self.sold_quantity_cap) == attributes.sold_quantity_cap))
     }
 
     pub fn update(
@@ -148,7 +174,7 @@ impl TicketPricing {
                 result
             } else {
                 // Orders affected, create new ticket pricing and delete old
-                let new_ticket_pricing = TicketPricing::create(
+                let mut new_ticket_pricing = TicketPricing::create(
                     self.ticket_type_id,
                     attributes.name.unwrap_or(self.name.clone()),
                     attributes.start_date.unwrap_or(self.start_date),
@@ -158,6 +184,7 @@ impl TicketPricing {
                     Some(self.status),
                     Some(self.id),
                 );
+                new_ticket_pricing.sold_quantity_cap = attributes.sold_quantity_cap.unwrap_or(self.sold_quantity_cap);
                 self.destroy(current_user_id, conn)?;
                 new_ticket_pricing.commit(current_user_id, conn)
             }
@@ -310,6 +337,102 @@ impl TicketPricing {
         Ok(())
     }
 
+    /// Quantity sold or currently reserved in carts at this pricing
+    pub fn sold_quantity(&self, conn: &PgConnection) -> Result<i64, DatabaseError> {
+        order_items::table
+            .inner_join(orders::table.on(orders::id.eq(order_items::order_id)))
+            .filter(order_items::ticket_pricing_id.eq(self.id))
+            .filter(order_items::item_type.eq(OrderItemTypes::Tickets))
+            .filter(orders::status.eq(OrderStatus::Paid).or(orders::expires_at.ge(dsl::now)))
+            .select(sql::<BigInt>(
+                "CAST(COALESCE(SUM(order_items.quantity - order_items.refunded_quantity), 0) AS BIGINT)",
+            ))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load sold quantity for ticket pricing")
+    }
+
+    /// The pricing period following this one for the same ticket type
+    pub fn next_ticket_pricing(&self, conn: &PgConnection) -> Result<Option<TicketPricing>, DatabaseError> {
+        ticket_pricing::table
+            .filter(ticket_pricing::ticket_type_id.eq(self.ticket_type_id))
+            .filter(ticket_pricing::id.ne(self.id))
+            .filter(ticket_pricing::status.eq(TicketPricingStatus::Published))
+            .filter(ticket_pricing::is_box_office_only.eq(self.is_box_office_only))
+            .filter(ticket_pricing::start_date.ge(self.start_date))
+            .order_by(ticket_pricing::start_date.asc())
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Could not load next ticket pricing")
+    }
+
+    /// True once the quantity sold or reserved at this pricing has reached its sold quantity cap
+    pub fn is_sold_out(&self, conn: &PgConnection) -> Result<bool, DatabaseError> {
+        match self.sold_quantity_cap {
+            Some(sold_quantity_cap) => Ok(self.sold_quantity(conn)? >= sold_quantity_cap),
+            None => Ok(false),
+        }
+    }
+
+    /// How much of the quantity can still be added to a cart before the sold quantity cap is
+    /// reached. The row is locked for the remainder of the transaction so concurrent carts crossing
+    /// the cap are serialized and the cap is not oversold.
+    pub fn quantity_available_for_sale(&self, quantity: u32, conn: &PgConnection) -> Result<u32, DatabaseError> {
+        let locked_ticket_pricing: TicketPricing = ticket_pricing::table
+            .find(self.id)
+            .for_update()
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not lock ticket pricing")?;
+        match locked_ticket_pricing.sold_quantity_cap {
+            Some(sold_quantity_cap) => {
+                let remaining = sold_quantity_cap - locked_ticket_pricing.sold_quantity(conn)?;
+                Ok(cmp::max(0, cmp::min(quantity as i64, remaining)) as u32)
+            }
+            None => Ok(quantity),
+        }
+    }
+
+    /// Checks the sold quantity cap after tickets are added to a cart, returning true if this
+    /// pricing has sold out. The first caller to find the cap reached ends this pricing period and
+    /// starts sales on the next one. The row is locked for the remainder of the transaction so only
+    /// one of the concurrent carts crossing the cap advances the tier.
+    pub fn check_sold_quantity_cap(
+        &self,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<bool, DatabaseError> {
+        let locked_ticket_pricing: TicketPricing = ticket_pricing::table
+            .find(self.id)
+            .for_update()
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not lock ticket pricing")?;
+        let sold_quantity_cap = match locked_ticket_pricing.sold_quantity_cap {
+            Some(sold_quantity_cap) => sold_quantity_cap,
+            None => return Ok(false),
+        };
+        if locked_ticket_pricing.sold_quantity(conn)? < sold_quantity_cap {
+            return Ok(false);
+        }
+
+        // Another transaction has already advanced to the next tier
+        let now = times::now();
+        if locked_ticket_pricing.end_date <= now {
+            return Ok(true);
+        }
+
+        diesel::update(&locked_ticket_pricing)
+            .set((
+                ticket_pricing::end_date.eq(now),
+                ticket_pricing::updated_at.eq(dsl::now),
+            ))
+            .execute(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update end date on Ticket Pricing")?;
+
+        if let Some(next_ticket_pricing) = locked_ticket_pricing.next_ticket_pricing(conn)? {
+            next_ticket_pricing.start_sales(current_user_id, conn)?;
+        }
+        Ok(true)
+    }
+
     pub fn get_default(ticket_type_id: Uuid, conn: &PgConnection) -> Result<TicketPricing, DatabaseError> {
         ticket_pricing::table
             .filter(ticket_pricing::ticket_type_id.eq(ticket_type_id))
@@ -370,10 +493,26 @@ impl TicketPricing {
             }
         }
 
-        price_points.pop().ok_or(DatabaseError::new(
+        let mut ticket_pricing = price_points.pop().ok_or(DatabaseError::new(
             ErrorCode::NoResults,
             Some("No ticket pricing found".to_string()),
-        ))
+        ))?;
+
+        // Skip past any tiers that have reached their sold quantity cap without writing, this is also used
+        // on read only connections. The tiers are ended when the sale reaching the cap is added to a cart.
+        // The next tier's start date may be later than this transaction's view of now so it is loaded
+        // directly rather than re-queried.
+        while ticket_pricing.is_sold_out(conn)? {
+            ticket_pricing = match ticket_pricing.next_ticket_pricing(conn)? {
+                Some(next_ticket_pricing) => next_ticket_pricing,
+                None if !get_default_pricing => {
+                    return TicketPricing::get_current_ticket_pricing(ticket_type_id, box_office_pricing, true, &conn)
+                }
+                None => break,
+            };
+        }
+
+        Ok(ticket_pricing)
     }
 }
 
@@ -388,6 +527,8 @@ pub struct NewTicketPricing {
     pub start_date: NaiveDateTime,
     pub end_date: NaiveDateTime,
     previous_ticket_pricing_id: Option<Uuid>,
+    currency: Option<String>,
+    pub sold_quantity_cap: Option<i64>,
 }
 
 impl NewTicketPricing {
@@ -409,11 +550,25 @@ impl NewTicketPricing {
             ),
         );
 
+        let validation_errors = validators::append_validation_error(
+            validation_errors,
+            "ticket_pricing.sold_quantity_cap",
+            TicketPricing::sold_quantity_cap_valid(self.sold_quantity_cap),
+        );
+
         Ok(validation_errors?)
     }
 
-    pub fn commit(self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<TicketPricing, DatabaseError> {
+    pub fn commit(
+        mut self,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<TicketPricing, DatabaseError> {
         self.validate_record()?;
+        if self.currency.is_none() {
+            // Pricing is charged in the currency of the event it belongs to
+            self.currency = Some(TicketType::find(self.ticket_type_id, conn)?.event(conn)?.currency);
+        }
         let result: TicketPricing = diesel::insert_into(ticket_pricing::table)
             .values(self)
             .get_result(conn)
@@ -8,7 +8,8 @@ use diesel::sql_types::{Nullable, Text, Uuid as dUuid};
 use itertools::Itertools;
 use models::*;
 use schema::{
-    assets, events, fee_schedules, organizations, ticket_instances, ticket_pricing, ticket_type_codes, ticket_types,
+    assets, events, fee_schedules, organizations, stage_seats, stage_sections, stages, ticket_instances,
+    ticket_pricing, ticket_type_codes, ticket_types,
 };
 use serde_with::rust::double_option;
 use std::cmp;
@@ -48,6 +49,10 @@ pub struct TicketType {
     pub ticket_type_type: TicketTypeType,
     pub promo_image_url: Option<String>,
     pub content_url: Option<String>,
+    pub resale_price_cap_percent: Option<i32>,
+    /// Number of installments the ticket type can be paid in, `None` when payment plans are not offered
+    pub installment_count: Option<i32>,
+    pub installment_interval_days: i32,
 }
 
 impl PartialOrd for TicketType {
@@ -79,6 +84,11 @@ pub struct TicketTypeEditableAttributes {
     pub box_office_sales_enabled: Option<bool>,
     pub app_sales_enabled: Option<bool>,
     pub rank: Option<i32>,
+    #[serde(default, deserialize_with = "double_option::deserialize")]
+    pub resale_price_cap_percent: Option<Option<i32>>,
+    #[serde(default, deserialize_with = "double_option::deserialize")]
+    pub installment_count: Option<Option<i32>>,
+    pub installment_interval_days: Option<i32>,
 }
 
 impl TicketType {
@@ -251,6 +261,7 @@ impl TicketType {
             result.end_date(conn)?,
             result.price_in_cents,
             false,
+            None,
             Some(TicketPricingStatus::Default),
             current_user_id,
             conn,
@@ -295,6 +306,60 @@ impl TicketType {
         Ok(())
     }
 
+    /// Binds the given seats to unsold tickets of this ticket type. Seats must belong to a stage
+    /// at the event's venue and can only be bound to one ticket per event.
+    pub fn assign_seats(
+        &self,
+        seat_ids: &[Uuid],
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Vec<TicketInstance>, DatabaseError> {
+        let seat_ids: Vec<Uuid> = seat_ids.iter().cloned().unique().collect();
+        let event = self.event(conn)?;
+
+        let venue_seat_count: i64 = stage_seats::table
+            .inner_join(stage_sections::table.on(stage_sections::id.eq(stage_seats::stage_section_id)))
+            .inner_join(stages::table.on(stages::id.eq(stage_sections::stage_id)))
+            .filter(stage_seats::id.eq_any(&seat_ids))
+            .filter(stages::venue_id.nullable().eq(event.venue_id))
+            .select(dsl::count(stage_seats::id))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load seats for venue")?;
+        if venue_seat_count != seat_ids.len() as i64 {
+            return DatabaseError::validation_error("seat_ids", "Seats must belong to a stage at the event's venue");
+        }
+
+        let already_assigned: bool = dsl::select(dsl::exists(
+            ticket_instances::table
+                .inner_join(assets::table.on(assets::id.eq(ticket_instances::asset_id)))
+                .inner_join(ticket_types::table.on(ticket_types::id.eq(assets::ticket_type_id)))
+                .filter(ticket_types::event_id.eq(event.id))
+                .filter(ticket_instances::seat_id.eq_any(&seat_ids)),
+        ))
+        .get_result(conn)
+        .to_db_error(ErrorCode::QueryError, "Could not check if seats are already assigned")?;
+        if already_assigned {
+            return DatabaseError::validation_error(
+                "seat_ids",
+                "One or more seats have already been assigned to tickets for this event",
+            );
+        }
+
+        let tickets = TicketInstance::assign_seats(self.id, &seat_ids, conn)?;
+
+        DomainEvent::create(
+            DomainEventTypes::TicketTypeUpdated,
+            format!("Seats assigned to ticket type '{}'", &self.name),
+            Tables::TicketTypes,
+            Some(self.id),
+            current_user_id,
+            Some(json!({ "seat_ids": seat_ids })),
+        )
+        .commit(conn)?;
+
+        Ok(tickets)
+    }
+
     pub fn parent(&self, conn: &PgConnection) -> Result<Option<TicketType>, DatabaseError> {
         match self.parent_id {
             Some(parent_id) => Ok(Some(TicketType::find(parent_id, conn)?)),
@@ -369,6 +434,45 @@ impl TicketType {
         attributes: &mut TicketTypeEditableAttributes,
         conn: &PgConnection,
     ) -> Result<(), DatabaseError> {
+        if let Some(Some(resale_price_cap_percent)) = attributes.resale_price_cap_percent {
+            validators::append_validation_error(
+                Ok(()),
+                "resale_price_cap_percent",
+                validators::validate_greater_than_or_equal(
+                    resale_price_cap_percent,
+                    0,
+                    "resale_price_cap_percent_lt_0",
+                    "Resale price cap cannot be negative",
+                ),
+            )?;
+        }
+
+        if let Some(Some(installment_count)) = attributes.installment_count {
+            validators::append_validation_error(
+                Ok(()),
+                "installment_count",
+                validators::validate_greater_than_or_equal(
+                    installment_count,
+                    2,
+                    "installment_count_lt_2",
+                    "Payment plans must have at least 2 installments",
+                ),
+            )?;
+        }
+
+        if let Some(installment_interval_days) = attributes.installment_interval_days {
+            validators::append_validation_error(
+                Ok(()),
+                "installment_interval_days",
+                validators::validate_greater_than_or_equal(
+                    installment_interval_days,
+                    1,
+                    "installment_interval_days_lt_1",
+                    "Installments must be at least 1 day apart",
+                ),
+            )?;
+        }
+
         if attributes.end_date_type.unwrap_or(self.end_date_type) == TicketTypeEndDateType::Manual
             && (attributes.end_date == Some(None) || (attributes.end_date.is_none() && self.end_date.is_none()))
         {
@@ -718,12 +822,13 @@ impl TicketType {
         end_date: NaiveDateTime,
         price_in_cents: i64,
         is_box_office_only: bool,
+        sold_quantity_cap: Option<i64>,
         status: Option<TicketPricingStatus>,
         current_user_id: Option<Uuid>,
         conn: &PgConnection,
     ) -> Result<TicketPricing, DatabaseError> {
         let desc = format!("Ticket pricing '{}' added", &name);
-        let result = TicketPricing::create(
+        let mut new_ticket_pricing = TicketPricing::create(
             self.id,
             name,
             start_date,
@@ -732,8 +837,9 @@ impl TicketType {
             is_box_office_only,
             status,
             None,
-        )
-        .commit(current_user_id, conn)?;
+        );
+        new_ticket_pricing.sold_quantity_cap = sold_quantity_cap;
+        let result = new_ticket_pricing.commit(current_user_id, conn)?;
 
         DomainEvent::create(
             DomainEventTypes::TicketPricingAdded,
@@ -842,6 +948,7 @@ impl NewTicketType {
             result.end_date(conn)?,
             result.price_in_cents,
             false,
+            None,
             Some(TicketPricingStatus::Default),
             current_user_id,
             conn,
@@ -1,4 +1,5 @@
 use chrono::prelude::*;
+use chrono::Duration;
 use diesel;
 use diesel::dsl::{count, exists, select, sql};
 use diesel::expression::dsl;
@@ -6,10 +7,11 @@ use diesel::prelude::*;
 use diesel::sql_types::{Array, Bool, Text, Uuid as dUuid};
 use models::*;
 use schema::{
-    assets, events, order_transfers, orders, organizations, ticket_instances, ticket_types, transfer_tickets, transfers,
+    assets, events, order_transfers, orders, organizations, stage_seats, stage_sections, ticket_instances,
+    ticket_types, transfer_tickets, transfers,
 };
 use serde_json::Value;
-use std::cmp::Ordering;
+use std::cmp::{self, Ordering};
 use tari_client::*;
 use utils::errors::ConvertToDatabaseError;
 use utils::errors::DatabaseError;
@@ -21,6 +23,7 @@ use validators::{self, *};
 
 pub static TRANSFER_DRIP_NOTIFICATION_DAYS_PRIOR_TO_EVENT: &'static [i64] = &[7, 1, 0];
 pub const TRANSFER_DRIP_NOTIFICATION_HOURS_PRIOR_TO_EVENT: i64 = 3;
+pub const TRANSFER_EXPIRY_REMINDER_HOURS_PRIOR_TO_EXPIRY: i64 = 24;
 
 #[derive(Insertable, Serialize, Deserialize, PartialEq, Debug)]
 #[table_name = "transfers"]
@@ -48,6 +51,7 @@ pub struct Transfer {
     pub cancelled_by_user_id: Option<Uuid>,
     pub direct: bool,
     pub destination_temporary_user_id: Option<Uuid>,
+    pub expires_at: Option<NaiveDateTime>,
 }
 
 #[derive(AsChangeset, Default, Deserialize)]
@@ -56,6 +60,7 @@ pub struct TransferEditableAttributes {
     pub status: Option<TransferStatus>,
     pub destination_user_id: Option<Uuid>,
     pub cancelled_by_user_id: Option<Uuid>,
+    pub expires_at: Option<NaiveDateTime>,
 }
 
 #[derive(Clone, Queryable, Deserialize, Serialize, PartialEq, Debug)]
@@ -72,12 +77,16 @@ pub struct DisplayTransfer {
     pub ticket_ids: Vec<Uuid>,
     pub event_ids: Vec<Uuid>,
     pub direct: bool,
+    pub seats: Vec<String>,
+    pub expires_at: Option<NaiveDateTime>,
 }
 
 #[derive(Debug, Deserialize, PartialEq, Serialize)]
 pub struct ProcessTransferDripPayload {
     pub source_or_destination: SourceOrDestination,
     pub event_id: Uuid,
+    #[serde(default)]
+    pub expiry_reminder: bool,
 }
 
 impl PartialOrd for Transfer {
@@ -239,6 +248,7 @@ impl Transfer {
                 json!(ProcessTransferDripPayload {
                     event_id: event.id,
                     source_or_destination,
+                    expiry_reminder: false,
                 }),
                 Some(Tables::Transfers),
                 Some(self.id),
@@ -326,6 +336,8 @@ impl Transfer {
             .inner_join(ticket_types::table.on(assets::ticket_type_id.eq(ticket_types::id)))
             .inner_join(events::table.on(events::id.eq(ticket_types::event_id)))
             .left_join(order_transfers::table.on(order_transfers::transfer_id.eq(transfers::id)))
+            .left_join(stage_seats::table.on(ticket_instances::seat_id.eq(stage_seats::id.nullable())))
+            .left_join(stage_sections::table.on(stage_sections::id.eq(stage_seats::stage_section_id)))
             .order_by(transfers::created_at.desc())
             .into_boxed();
 
@@ -380,6 +392,15 @@ impl Transfer {
                 ",
                 ),
                 transfers::direct,
+                sql::<Array<Text>>(
+                    "
+                    ARRAY_REMOVE(
+                        ARRAY_AGG(DISTINCT stage_sections.name || ' ' || stage_seats.row_name || '-' || stage_seats.seat_number),
+                        NULL
+                    )
+                ",
+                ),
+                transfers::expires_at,
             ))
             .group_by((
                 transfers::id,
@@ -391,6 +412,7 @@ impl Transfer {
                 transfers::transfer_message_type,
                 transfers::transfer_address,
                 transfers::direct,
+                transfers::expires_at,
             ))
             .paginate(page as i64)
             .per_page(limit as i64)
@@ -409,6 +431,7 @@ impl Transfer {
             .map(|tt| tt.ticket_instance_id)
             .collect();
         let event_ids = self.events(conn)?.iter().map(|e| e.id).collect();
+        let seats = self.seats(conn)?.iter().map(|s| s.label()).collect();
 
         Ok(DisplayTransfer {
             id: self.id,
@@ -423,9 +446,16 @@ impl Transfer {
             ticket_ids,
             event_ids,
             direct: self.direct,
+            seats,
+            expires_at: self.expires_at,
         })
     }
 
+    pub fn seats(&self, conn: &PgConnection) -> Result<Vec<DisplaySeat>, DatabaseError> {
+        let seat_ids: Vec<Uuid> = self.tickets(conn)?.iter().filter_map(|t| t.seat_id).collect();
+        StageSeat::find_for_display(&seat_ids, conn)
+    }
+
     pub fn find_pending_by_ticket_instance_ids(
         ticket_instance_ids: &[Uuid],
         conn: &PgConnection,
@@ -641,6 +671,189 @@ impl Transfer {
         Ok(transfer)
     }
 
+    pub fn decline(
+        &self,
+        ticket_ids: Option<Vec<Uuid>>,
+        user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<Transfer, DatabaseError> {
+        if self.status != TransferStatus::Pending {
+            return DatabaseError::business_process_error("Transfer cannot be declined as it is no longer pending");
+        }
+
+        let transfer_ticket_ids: Vec<Uuid> = self
+            .transfer_tickets(conn)?
+            .iter()
+            .map(|tt| tt.ticket_instance_id)
+            .collect();
+        let declined_ticket_ids = ticket_ids.unwrap_or_else(|| transfer_ticket_ids.clone());
+        if declined_ticket_ids.is_empty() || declined_ticket_ids.iter().any(|id| !transfer_ticket_ids.contains(id)) {
+            return DatabaseError::business_process_error("Declined tickets must be part of the transfer");
+        }
+
+        let declined_transfer = if declined_ticket_ids.len() == transfer_ticket_ids.len() {
+            self.update(
+                TransferEditableAttributes {
+                    status: Some(TransferStatus::Declined),
+                    ..Default::default()
+                },
+                conn,
+            )?
+        } else {
+            // Declined tickets are split into their own transfer leaving the remaining tickets pending acceptance
+            let declined_transfer = Transfer::create(
+                self.source_user_id,
+                Uuid::new_v4(),
+                self.transfer_message_type,
+                self.transfer_address.clone(),
+                self.direct,
+            )
+            .commit(conn)?;
+            diesel::update(
+                transfer_tickets::table
+                    .filter(transfer_tickets::transfer_id.eq(self.id))
+                    .filter(transfer_tickets::ticket_instance_id.eq_any(&declined_ticket_ids)),
+            )
+            .set((
+                transfer_tickets::transfer_id.eq(declined_transfer.id),
+                transfer_tickets::updated_at.eq(dsl::now),
+            ))
+            .execute(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not move declined transfer tickets")?;
+            declined_transfer.update_associated_orders(conn)?;
+            declined_transfer.update(
+                TransferEditableAttributes {
+                    status: Some(TransferStatus::Declined),
+                    ..Default::default()
+                },
+                conn,
+            )?
+        };
+
+        DomainEvent::create(
+            DomainEventTypes::TransferTicketDeclined,
+            "Ticket transfer was declined".to_string(),
+            Tables::Transfers,
+            Some(declined_transfer.id),
+            user_id,
+            Some(json!({
+                "transfer_key": self.transfer_key,
+                "declined_from_transfer_id": self.id,
+                "ticket_ids": declined_ticket_ids
+            })),
+        )
+        .commit(conn)?;
+
+        Ok(declined_transfer)
+    }
+
+    pub fn expire(&self, conn: &PgConnection) -> Result<Transfer, DatabaseError> {
+        if self.status != TransferStatus::Pending {
+            return DatabaseError::business_process_error("Transfer cannot be expired as it is no longer pending");
+        }
+
+        let transfer = self.update(
+            TransferEditableAttributes {
+                status: Some(TransferStatus::Expired),
+                ..Default::default()
+            },
+            conn,
+        )?;
+
+        DomainEvent::create(
+            DomainEventTypes::TransferTicketExpired,
+            "Ticket transfer expired".to_string(),
+            Tables::Transfers,
+            Some(self.id),
+            None,
+            Some(json!({"transfer_key": self.transfer_key, "expires_at": self.expires_at})),
+        )
+        .commit(conn)?;
+
+        Ok(transfer)
+    }
+
+    /// Shortest expiry configured across the organizations of the transferred tickets
+    pub fn expiry_hours(&self, conn: &PgConnection) -> Result<Option<i64>, DatabaseError> {
+        Ok(self
+            .organizations(conn)?
+            .iter()
+            .filter_map(|o| o.transfer_expiry_hours)
+            .min()
+            .map(|h| h as i64))
+    }
+
+    pub fn schedule_expiry(&self, conn: &PgConnection) -> Result<Transfer, DatabaseError> {
+        let expiry_hours = match self.expiry_hours(conn)? {
+            Some(expiry_hours) => expiry_hours,
+            None => return Ok(self.clone()),
+        };
+        let expires_at = self.created_at + Duration::hours(expiry_hours);
+        let transfer = self.update(
+            TransferEditableAttributes {
+                expires_at: Some(expires_at),
+                ..Default::default()
+            },
+            conn,
+        )?;
+
+        let mut action = DomainAction::create(
+            None,
+            DomainActionTypes::ExpireTransfer,
+            None,
+            json!({}),
+            Some(Tables::Transfers),
+            Some(self.id),
+        );
+        action.schedule_at(expires_at);
+        action.commit(conn)?;
+
+        // Remind the sender ahead of expiry, or halfway through the window for short expiry periods
+        if let Some(event) = self.events(conn)?.first() {
+            let reminder_minutes = cmp::min(TRANSFER_EXPIRY_REMINDER_HOURS_PRIOR_TO_EXPIRY * 60, expiry_hours * 30);
+            let mut action = DomainAction::create(
+                None,
+                DomainActionTypes::ProcessTransferDrip,
+                None,
+                json!(ProcessTransferDripPayload {
+                    event_id: event.id,
+                    source_or_destination: SourceOrDestination::Source,
+                    expiry_reminder: true,
+                }),
+                Some(Tables::Transfers),
+                Some(self.id),
+            );
+            action.schedule_at(expires_at - Duration::minutes(reminder_minutes));
+            action.commit(conn)?;
+        }
+
+        Ok(transfer)
+    }
+
+    pub fn expiry_drip_header(&self, include_links: bool) -> Result<String, DatabaseError> {
+        let (destination_address, expires_at) = match (self.transfer_address.clone(), self.expires_at) {
+            (Some(destination_address), Some(expires_at)) => (destination_address, expires_at),
+            _ => {
+                return DatabaseError::business_process_error(
+                    "Cannot build expiry header for transfer missing destination address or expiry",
+                );
+            }
+        };
+
+        let destination_address = if include_links && self.transfer_message_type == Some(TransferMessageType::Email) {
+            format!("<a href='mailto:{}'>{}</a>", destination_address, destination_address)
+        } else {
+            destination_address
+        };
+        let hours_until_expiry = cmp::max(expires_at.signed_duration_since(Utc::now().naive_utc()).num_hours(), 1);
+        Ok(format!(
+            "Those tickets you sent to {} still haven't been claimed and will be returned to you in {} hour{} if they are not accepted. Give them a nudge!",
+            destination_address,
+            hours_until_expiry,
+            if hours_until_expiry == 1 { "" } else { "s" }
+        ))
+    }
+
     fn transfer_key_unique(
         transfer_key: Uuid,
         conn: &PgConnection,
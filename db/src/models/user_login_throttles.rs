@@ -0,0 +1,99 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::user_login_throttles;
+use utils::errors::*;
+use uuid::Uuid;
+
+pub const MAX_FAILED_LOGIN_ATTEMPTS: i32 = 5;
+pub const LOGIN_LOCKOUT_MINUTES: i64 = 15;
+
+/// Failed login attempts for a user, too many in a row lock the account temporarily
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, QueryableByName, Serialize)]
+#[belongs_to(User)]
+#[table_name = "user_login_throttles"]
+pub struct UserLoginThrottle {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub failed_attempts: i32,
+    pub last_failed_at: Option<NaiveDateTime>,
+    pub locked_until: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl UserLoginThrottle {
+    pub fn find_by_user_id(user_id: Uuid, conn: &PgConnection) -> Result<Option<UserLoginThrottle>, DatabaseError> {
+        user_login_throttles::table
+            .filter(user_login_throttles::user_id.eq(user_id))
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Could not load login throttle")
+    }
+
+    /// Time until which logins are refused for the user, if they are currently locked out
+    pub fn locked_until(user_id: Uuid, conn: &PgConnection) -> Result<Option<NaiveDateTime>, DatabaseError> {
+        let now = Utc::now().naive_utc();
+        Ok(UserLoginThrottle::find_by_user_id(user_id, conn)?
+            .and_then(|throttle| throttle.locked_until)
+            .filter(|locked_until| *locked_until > now))
+    }
+
+    /// Counts a failed attempt, locking the user out once the limit is reached
+    pub fn record_failure(user_id: Uuid, conn: &PgConnection) -> Result<UserLoginThrottle, DatabaseError> {
+        let throttle: UserLoginThrottle = diesel::sql_query(
+            r#"
+            INSERT INTO user_login_throttles (user_id, failed_attempts, last_failed_at)
+            VALUES ($1, 1, now())
+            ON CONFLICT (user_id) DO UPDATE
+            SET failed_attempts = user_login_throttles.failed_attempts + 1, last_failed_at = now(), updated_at = now()
+            RETURNING *;
+            "#,
+        )
+        .bind::<diesel::sql_types::Uuid, _>(user_id)
+        .get_result(conn)
+        .to_db_error(ErrorCode::UpdateError, "Could not record failed login attempt")?;
+
+        if throttle.failed_attempts < MAX_FAILED_LOGIN_ATTEMPTS {
+            return Ok(throttle);
+        }
+
+        let locked_until = Utc::now().naive_utc() + Duration::minutes(LOGIN_LOCKOUT_MINUTES);
+        let throttle: UserLoginThrottle = diesel::update(&throttle)
+            .set((
+                user_login_throttles::failed_attempts.eq(0),
+                user_login_throttles::locked_until.eq(locked_until),
+                user_login_throttles::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not lock user login")?;
+
+        DomainEvent::create(
+            DomainEventTypes::UserLoginLocked,
+            "User login locked after repeated failed attempts".to_string(),
+            Tables::Users,
+            Some(user_id),
+            None,
+            Some(json!({ "locked_until": locked_until })),
+        )
+        .commit(conn)?;
+
+        Ok(throttle)
+    }
+
+    /// Clears failed attempts after a successful login
+    pub fn reset(user_id: Uuid, conn: &PgConnection) -> Result<(), DatabaseError> {
+        diesel::update(user_login_throttles::table.filter(user_login_throttles::user_id.eq(user_id)))
+            .set((
+                user_login_throttles::failed_attempts.eq(0),
+                user_login_throttles::locked_until.eq(None::<NaiveDateTime>),
+                user_login_throttles::updated_at.eq(dsl::now),
+            ))
+            .execute(conn)
+            .map(|_| ())
+            .to_db_error(ErrorCode::UpdateError, "Could not reset login throttle")
+    }
+}
@@ -0,0 +1,92 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use hex;
+use models::*;
+use ring::digest;
+use schema::user_recovery_codes;
+use utils::errors::*;
+use utils::rand::random_alpha_string;
+use uuid::Uuid;
+
+const RECOVERY_CODE_COUNT: usize = 10;
+const RECOVERY_CODE_LENGTH: usize = 10;
+
+/// Single use code allowing a user to log in without their authenticator app
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(User)]
+#[table_name = "user_recovery_codes"]
+pub struct UserRecoveryCode {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    #[serde(default, skip_serializing)]
+    pub code_hash: String,
+    pub used_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl UserRecoveryCode {
+    /// Replaces any existing recovery codes, the plain text codes are returned and not stored
+    pub fn generate_for_user(user_id: Uuid, conn: &PgConnection) -> Result<Vec<String>, DatabaseError> {
+        UserRecoveryCode::destroy_for_user(user_id, conn)?;
+
+        let codes: Vec<String> = (0..RECOVERY_CODE_COUNT)
+            .map(|_| random_alpha_string(RECOVERY_CODE_LENGTH).to_lowercase())
+            .collect();
+        let values: Vec<_> = codes
+            .iter()
+            .map(|code| {
+                (
+                    user_recovery_codes::user_id.eq(user_id),
+                    user_recovery_codes::code_hash.eq(UserRecoveryCode::hash_code(code)),
+                )
+            })
+            .collect();
+        diesel::insert_into(user_recovery_codes::table)
+            .values(values)
+            .execute(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create recovery codes")?;
+
+        Ok(codes)
+    }
+
+    pub fn find_unused_for_user(user_id: Uuid, conn: &PgConnection) -> Result<Vec<UserRecoveryCode>, DatabaseError> {
+        user_recovery_codes::table
+            .filter(user_recovery_codes::user_id.eq(user_id))
+            .filter(user_recovery_codes::used_at.is_null())
+            .order_by(user_recovery_codes::created_at.asc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load recovery codes")
+    }
+
+    /// Marks the matching unused code as used, returning false if there is no such code
+    pub fn redeem(user_id: Uuid, code: &str, conn: &PgConnection) -> Result<bool, DatabaseError> {
+        let updated = diesel::update(
+            user_recovery_codes::table
+                .filter(user_recovery_codes::user_id.eq(user_id))
+                .filter(user_recovery_codes::code_hash.eq(UserRecoveryCode::hash_code(code)))
+                .filter(user_recovery_codes::used_at.is_null()),
+        )
+        .set((
+            user_recovery_codes::used_at.eq(dsl::now.nullable()),
+            user_recovery_codes::updated_at.eq(dsl::now),
+        ))
+        .execute(conn)
+        .to_db_error(ErrorCode::UpdateError, "Could not redeem recovery code")?;
+        Ok(updated > 0)
+    }
+
+    pub fn destroy_for_user(user_id: Uuid, conn: &PgConnection) -> Result<(), DatabaseError> {
+        diesel::delete(user_recovery_codes::table.filter(user_recovery_codes::user_id.eq(user_id)))
+            .execute(conn)
+            .map(|_| ())
+            .to_db_error(ErrorCode::DeleteError, "Could not remove recovery codes")
+    }
+
+    fn hash_code(code: &str) -> String {
+        let code = code.trim().replace("-", "").replace(" ", "").to_lowercase();
+        hex::encode(digest::digest(&digest::SHA256, code.as_bytes()).as_ref())
+    }
+}
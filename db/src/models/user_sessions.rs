@@ -0,0 +1,205 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::dsl;
+use diesel::dsl::{exists, select};
+use diesel::prelude::*;
+use hex;
+use models::*;
+use ring::digest;
+use schema::user_sessions;
+use utils::errors::*;
+use uuid::Uuid;
+
+/// Server side record of a login, the refresh tokens issued for it are only honoured while it is active
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(User)]
+#[table_name = "user_sessions"]
+pub struct UserSession {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    /// Identifies the only refresh token currently valid for the session, replaced on every refresh
+    #[serde(default, skip_serializing)]
+    pub refresh_token_id: Uuid,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub last_seen_at: NaiveDateTime,
+    pub revoked_at: Option<NaiveDateTime>,
+    pub revoked_reason: Option<SessionRevocationReasons>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    /// Hash of the sessionless token (e.g. a magic link) the session was started with, each can
+    /// only start one session
+    #[serde(default, skip_serializing)]
+    pub started_by_token_hash: Option<String>,
+}
+
+#[derive(Clone, Deserialize, Insertable, Serialize)]
+#[table_name = "user_sessions"]
+pub struct NewUserSession {
+    pub user_id: Uuid,
+    pub refresh_token_id: Uuid,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub started_by_token_hash: Option<String>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct DisplayUserSession {
+    pub id: Uuid,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub last_seen_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+    /// Session the request listing the sessions was made with
+    pub current: bool,
+}
+
+impl NewUserSession {
+    pub fn started_by_token(mut self, token: &str) -> NewUserSession {
+        self.started_by_token_hash = Some(UserSession::hash_token(token));
+        self
+    }
+
+    pub fn commit(self, conn: &PgConnection) -> Result<UserSession, DatabaseError> {
+        diesel::insert_into(user_sessions::table)
+            .values(&self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create user session")
+    }
+}
+
+impl UserSession {
+    pub fn create(user_id: Uuid, user_agent: Option<String>, ip_address: Option<String>) -> NewUserSession {
+        NewUserSession {
+            user_id,
+            refresh_token_id: Uuid::new_v4(),
+            user_agent,
+            ip_address,
+            started_by_token_hash: None,
+        }
+    }
+
+    /// Whether the sessionless token has already been used to start a session
+    pub fn token_used(token: &str, conn: &PgConnection) -> Result<bool, DatabaseError> {
+        select(exists(user_sessions::table.filter(
+            user_sessions::started_by_token_hash.eq(UserSession::hash_token(token)),
+        )))
+        .get_result(conn)
+        .to_db_error(ErrorCode::QueryError, "Could not check if token was used")
+    }
+
+    fn hash_token(token: &str) -> String {
+        hex::encode(digest::digest(&digest::SHA256, token.as_bytes()).as_ref())
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<UserSession, DatabaseError> {
+        user_sessions::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find user session")
+    }
+
+    pub fn find_active_for_user(user_id: Uuid, conn: &PgConnection) -> Result<Vec<UserSession>, DatabaseError> {
+        user_sessions::table
+            .filter(user_sessions::user_id.eq(user_id))
+            .filter(user_sessions::revoked_at.is_null())
+            .order_by(user_sessions::last_seen_at.desc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load sessions for user")
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.revoked_at.is_none()
+    }
+
+    /// Issues a new refresh token id for the session. A refresh token that has already been
+    /// replaced can only be presented again if it was copied, in which case the whole session is
+    /// revoked and `None` is returned.
+    pub fn rotate_refresh_token(
+        &self,
+        refresh_token_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Option<UserSession>, DatabaseError> {
+        if !self.is_active() {
+            return Ok(None);
+        }
+
+        // Conditional on the presented token so concurrent refreshes with the same token can't both succeed
+        let session: Option<UserSession> = diesel::update(
+            user_sessions::table
+                .filter(user_sessions::id.eq(self.id))
+                .filter(user_sessions::refresh_token_id.eq(refresh_token_id))
+                .filter(user_sessions::revoked_at.is_null()),
+        )
+        .set((
+            user_sessions::refresh_token_id.eq(Uuid::new_v4()),
+            user_sessions::last_seen_at.eq(dsl::now),
+            user_sessions::updated_at.eq(dsl::now),
+        ))
+        .get_result(conn)
+        .optional()
+        .to_db_error(ErrorCode::UpdateError, "Could not refresh user session")?;
+
+        if session.is_none() {
+            self.revoke(SessionRevocationReasons::RefreshTokenReused, None, conn)?;
+        }
+        Ok(session)
+    }
+
+    pub fn revoke(
+        &self,
+        reason: SessionRevocationReasons,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<UserSession, DatabaseError> {
+        if !self.is_active() {
+            return DatabaseError::business_process_error("Session has already been revoked");
+        }
+
+        let session: UserSession = diesel::update(self)
+            .set((
+                user_sessions::revoked_at.eq(dsl::now.nullable()),
+                user_sessions::revoked_reason.eq(reason),
+                user_sessions::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not revoke user session")?;
+
+        DomainEvent::create(
+            DomainEventTypes::UserSessionRevoked,
+            "User session revoked".to_string(),
+            Tables::UserSessions,
+            Some(self.id),
+            current_user_id,
+            Some(json!({ "user_id": self.user_id, "reason": reason })),
+        )
+        .commit(conn)?;
+
+        Ok(session)
+    }
+
+    /// Signs the user out everywhere, returning the number of sessions revoked
+    pub fn revoke_all_for_user(
+        user_id: Uuid,
+        reason: SessionRevocationReasons,
+        current_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<usize, DatabaseError> {
+        let sessions = UserSession::find_active_for_user(user_id, conn)?;
+        for session in &sessions {
+            session.revoke(reason, current_user_id, conn)?;
+        }
+        Ok(sessions.len())
+    }
+
+    pub fn for_display(&self, current_session_id: Option<Uuid>) -> DisplayUserSession {
+        DisplayUserSession {
+            id: self.id,
+            user_agent: self.user_agent.clone(),
+            ip_address: self.ip_address.clone(),
+            last_seen_at: self.last_seen_at,
+            created_at: self.created_at,
+            current: current_session_id == Some(self.id),
+        }
+    }
+}
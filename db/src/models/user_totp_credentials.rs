@@ -0,0 +1,190 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::user_totp_credentials;
+use utils::encryption::*;
+use utils::errors::*;
+use utils::totp;
+use uuid::Uuid;
+
+const TOTP_ISSUER: &str = "Big Neon";
+
+/// Authenticator app secret used as a second factor when logging in
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(User)]
+#[table_name = "user_totp_credentials"]
+pub struct UserTotpCredential {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    #[serde(default, skip_serializing)]
+    pub encrypted_secret: String,
+    /// Set once the user has proven their authenticator app produces valid codes
+    pub confirmed_at: Option<NaiveDateTime>,
+    /// Time step of the last accepted code, codes cannot be used twice
+    pub last_used_time_step: Option<i64>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+/// Secret to load into an authenticator app, only available when enrolling
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct TotpEnrollment {
+    pub secret: String,
+    pub provisioning_uri: String,
+}
+
+impl UserTotpCredential {
+    /// Starts enrollment with a new secret, replacing any enrollment that was never confirmed
+    pub fn enroll(user: &User, encryption_key: &str, conn: &PgConnection) -> Result<TotpEnrollment, DatabaseError> {
+        if let Some(credential) = UserTotpCredential::find_by_user_id(user.id, conn).optional()? {
+            if credential.confirmed_at.is_some() {
+                return DatabaseError::business_process_error("Two-factor authentication is already enabled");
+            }
+            diesel::delete(&credential).execute(conn).to_db_error(
+                ErrorCode::DeleteError,
+                "Could not remove previous two-factor enrollment",
+            )?;
+        }
+
+        let secret = totp::generate_secret();
+        diesel::insert_into(user_totp_credentials::table)
+            .values((
+                user_totp_credentials::user_id.eq(user.id),
+                user_totp_credentials::encrypted_secret.eq(encrypt(&secret, encryption_key)?),
+            ))
+            .execute(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create two-factor enrollment")?;
+
+        let account_name = user.email.clone().unwrap_or_else(|| user.id.to_string());
+        Ok(TotpEnrollment {
+            provisioning_uri: totp::provisioning_uri(&secret, &account_name, TOTP_ISSUER),
+            secret,
+        })
+    }
+
+    pub fn find_by_user_id(user_id: Uuid, conn: &PgConnection) -> Result<UserTotpCredential, DatabaseError> {
+        user_totp_credentials::table
+            .filter(user_totp_credentials::user_id.eq(user_id))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find two-factor credential")
+    }
+
+    pub fn find_confirmed_for_user(
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Option<UserTotpCredential>, DatabaseError> {
+        user_totp_credentials::table
+            .filter(user_totp_credentials::user_id.eq(user_id))
+            .filter(user_totp_credentials::confirmed_at.is_not_null())
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Could not find two-factor credential")
+    }
+
+    /// Completes enrollment with a code from the authenticator app, returning the recovery codes
+    /// which are only shown this once
+    pub fn confirm(&self, code: &str, encryption_key: &str, conn: &PgConnection) -> Result<Vec<String>, DatabaseError> {
+        if self.confirmed_at.is_some() {
+            return DatabaseError::business_process_error("Two-factor authentication is already enabled");
+        }
+        if !self.verify(code, encryption_key, conn)? {
+            return DatabaseError::validation_error("code", "Two-factor code is invalid");
+        }
+
+        diesel::update(self)
+            .set((
+                user_totp_credentials::confirmed_at.eq(dsl::now.nullable()),
+                user_totp_credentials::updated_at.eq(dsl::now),
+            ))
+            .execute(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not confirm two-factor enrollment")?;
+        let recovery_codes = UserRecoveryCode::generate_for_user(self.user_id, conn)?;
+
+        DomainEvent::create(
+            DomainEventTypes::UserTwoFactorEnabled,
+            "Two-factor authentication enabled".to_string(),
+            Tables::Users,
+            Some(self.user_id),
+            Some(self.user_id),
+            None,
+        )
+        .commit(conn)?;
+
+        Ok(recovery_codes)
+    }
+
+    /// Checks a code from the authenticator app, a code is accepted at most once
+    pub fn verify(&self, code: &str, encryption_key: &str, conn: &PgConnection) -> Result<bool, DatabaseError> {
+        let secret = decrypt(&self.encrypted_secret, encryption_key)?;
+        let time_step = match totp::verify_code(&secret, code, Utc::now().timestamp()) {
+            Some(time_step) => time_step,
+            None => return Ok(false),
+        };
+        if self.last_used_time_step.map(|last| time_step <= last).unwrap_or(false) {
+            return Ok(false);
+        }
+
+        // Conditional update so concurrent logins cannot both use the same code
+        let updated = diesel::update(
+            user_totp_credentials::table
+                .filter(user_totp_credentials::id.eq(self.id))
+                .filter(
+                    user_totp_credentials::last_used_time_step
+                        .is_null()
+                        .or(user_totp_credentials::last_used_time_step.lt(time_step)),
+                ),
+        )
+        .set(user_totp_credentials::last_used_time_step.eq(time_step))
+        .execute(conn)
+        .to_db_error(ErrorCode::UpdateError, "Could not record two-factor code usage")?;
+        Ok(updated == 1)
+    }
+
+    /// Replaces the user's recovery codes, invalidating any they have not used yet
+    pub fn regenerate_recovery_codes(&self, conn: &PgConnection) -> Result<Vec<String>, DatabaseError> {
+        if self.confirmed_at.is_none() {
+            return DatabaseError::business_process_error("Two-factor authentication is not enabled");
+        }
+
+        let recovery_codes = UserRecoveryCode::generate_for_user(self.user_id, conn)?;
+        DomainEvent::create(
+            DomainEventTypes::UserTwoFactorRecoveryCodesRegenerated,
+            "Two-factor recovery codes regenerated".to_string(),
+            Tables::Users,
+            Some(self.user_id),
+            Some(self.user_id),
+            None,
+        )
+        .commit(conn)?;
+
+        Ok(recovery_codes)
+    }
+
+    /// Removes two-factor authentication along with the user's recovery codes
+    pub fn disable(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<(), DatabaseError> {
+        if User::find(self.user_id, conn)?.member_of_organization_requiring_two_factor(conn)? {
+            return DatabaseError::business_process_error(
+                "Two-factor authentication is required by an organization you are a member of",
+            );
+        }
+
+        UserRecoveryCode::destroy_for_user(self.user_id, conn)?;
+        diesel::delete(self)
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not disable two-factor authentication")?;
+
+        DomainEvent::create(
+            DomainEventTypes::UserTwoFactorDisabled,
+            "Two-factor authentication disabled".to_string(),
+            Tables::Users,
+            Some(self.user_id),
+            current_user_id,
+            None,
+        )
+        .commit(conn)?;
+
+        Ok(())
+    }
+}
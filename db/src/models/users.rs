@@ -349,6 +349,27 @@ impl User {
         Ok(MarketplaceAccount::find_by_user_id(self.id, conn)?.pop())
     }
 
+    pub fn member_of_organization_requiring_two_factor(&self, conn: &PgConnection) -> Result<bool, DatabaseError> {
+        select(exists(
+            organization_users::table
+                .inner_join(organizations::table)
+                .filter(organization_users::user_id.eq(self.id))
+                .filter(organizations::require_two_factor.eq(true)),
+        ))
+        .get_result(conn)
+        .to_db_error(
+            ErrorCode::QueryError,
+            "Could not check organization two-factor requirements for user",
+        )
+    }
+
+    /// True when an organization the user belongs to requires two-factor authentication and the
+    /// user has not yet enabled it
+    pub fn two_factor_enrollment_required(&self, conn: &PgConnection) -> Result<bool, DatabaseError> {
+        Ok(UserTotpCredential::find_confirmed_for_user(self.id, conn)?.is_none()
+            && self.member_of_organization_requiring_two_factor(conn)?)
+    }
+
     pub fn login_domain_event(&self, json: Value, conn: &PgConnection) -> Result<(), DatabaseError> {
         DomainEvent::create(
             DomainEventTypes::UserLogin,
@@ -541,6 +562,10 @@ impl User {
             facebook_event_id: Option<String>,
             #[sql_type = "Nullable<dUuid>"]
             cloned_from_event_id: Option<Uuid>,
+            #[sql_type = "Text"]
+            currency: String,
+            #[sql_type = "Nullable<dUuid>"]
+            event_series_id: Option<Uuid>,
         }
 
         let mut query = sql_query(
@@ -631,6 +656,8 @@ impl User {
             slug_id: Some(event.slug_id),
             facebook_event_id: event.facebook_event_id,
             cloned_from_event_id: event.cloned_from_event_id,
+            currency: event.currency,
+            event_series_id: event.event_series_id,
         });
 
         let mut result: Vec<ActivitySummary> = Vec::new();
@@ -1270,6 +1297,13 @@ impl User {
             external_login.delete(current_user.map(|u| u.id), conn)?
         }
 
+        UserSession::revoke_all_for_user(
+            self.id,
+            SessionRevocationReasons::UserDisabled,
+            current_user.map(|u| u.id),
+            conn,
+        )?;
+
         Ok(result)
     }
 }
@@ -0,0 +1,386 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::{assets, ticket_instances, waitlist_entries};
+use utils::errors::*;
+use utils::pagination::*;
+use utils::rand::random_alpha_string;
+use uuid::Uuid;
+
+/// How long a waitlisted user has to claim the tickets offered to them before
+/// the offer lapses and the tickets move on to the next person in line
+pub const WAITLIST_OFFER_EXPIRY_MINUTES: i64 = 60;
+
+#[derive(Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[table_name = "waitlist_entries"]
+pub struct WaitlistEntry {
+    pub id: Uuid,
+    pub ticket_type_id: Uuid,
+    pub user_id: Uuid,
+    pub quantity: i64,
+    pub status: WaitlistEntryStatus,
+    pub hold_id: Option<Uuid>,
+    pub offer_expires_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "waitlist_entries"]
+pub struct NewWaitlistEntry {
+    pub ticket_type_id: Uuid,
+    pub user_id: Uuid,
+    pub quantity: i64,
+}
+
+impl NewWaitlistEntry {
+    pub fn commit(self, conn: &PgConnection) -> Result<WaitlistEntry, DatabaseError> {
+        if self.quantity <= 0 {
+            return DatabaseError::validation_error("quantity", "Quantity must be greater than zero");
+        }
+
+        let ticket_type = TicketType::find(self.ticket_type_id, conn)?;
+        if ticket_type.status == TicketTypeStatus::Cancelled || ticket_type.status == TicketTypeStatus::Deleted {
+            return DatabaseError::business_process_error("Ticket type is no longer available");
+        }
+        if ticket_type.valid_available_ticket_count(conn)? > 0 {
+            return DatabaseError::validation_error(
+                "ticket_type_id",
+                "Tickets are still available for this ticket type",
+            );
+        }
+        if ticket_type.limit_per_person > 0 && self.quantity > ticket_type.limit_per_person as i64 {
+            return DatabaseError::validation_error("quantity", "Quantity exceeds the limit per person");
+        }
+        if WaitlistEntry::find_open_for_user(self.ticket_type_id, self.user_id, conn)?.is_some() {
+            return DatabaseError::validation_error("user_id", "User is already on the waitlist for this ticket type");
+        }
+
+        let entry: WaitlistEntry = diesel::insert_into(waitlist_entries::table)
+            .values(&self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not join waitlist")?;
+
+        DomainEvent::create(
+            DomainEventTypes::WaitlistEntryCreated,
+            "User joined waitlist".to_string(),
+            Tables::WaitlistEntries,
+            Some(entry.id),
+            Some(entry.user_id),
+            Some(json!({ "ticket_type_id": entry.ticket_type_id, "quantity": entry.quantity })),
+        )
+        .commit(conn)?;
+
+        // Tickets sitting in unpaid carts are the first to come free
+        if let Some(next_expiry) = WaitlistEntry::next_reservation_expiry(entry.ticket_type_id, conn)? {
+            WaitlistEntry::queue_processing(entry.ticket_type_id, Some(next_expiry), conn)?;
+        }
+
+        Ok(entry)
+    }
+}
+
+impl WaitlistEntry {
+    pub fn create(ticket_type_id: Uuid, user_id: Uuid, quantity: u32) -> NewWaitlistEntry {
+        NewWaitlistEntry {
+            ticket_type_id,
+            user_id,
+            quantity: quantity as i64,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<WaitlistEntry, DatabaseError> {
+        waitlist_entries::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load waitlist entry")
+    }
+
+    pub fn find_open_for_user(
+        ticket_type_id: Uuid,
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Option<WaitlistEntry>, DatabaseError> {
+        waitlist_entries::table
+            .filter(waitlist_entries::ticket_type_id.eq(ticket_type_id))
+            .filter(waitlist_entries::user_id.eq(user_id))
+            .filter(waitlist_entries::status.eq_any(vec![WaitlistEntryStatus::Waiting, WaitlistEntryStatus::Offered]))
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Could not load waitlist entry")
+    }
+
+    /// Entries for the ticket type in the order they joined the waitlist
+    pub fn find_for_ticket_type(
+        ticket_type_id: Uuid,
+        status: Option<WaitlistEntryStatus>,
+        page: u32,
+        limit: u32,
+        conn: &PgConnection,
+    ) -> Result<Payload<WaitlistEntry>, DatabaseError> {
+        let mut query = waitlist_entries::table
+            .filter(waitlist_entries::ticket_type_id.eq(ticket_type_id))
+            .into_boxed();
+
+        if let Some(status) = status {
+            query = query.filter(waitlist_entries::status.eq(status));
+        }
+
+        let (entries, record_count): (Vec<WaitlistEntry>, i64) = query
+            .order_by(waitlist_entries::created_at.asc())
+            .paginate(page as i64)
+            .per_page(limit as i64)
+            .load_and_count_pages(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load waitlist entries")?;
+
+        Ok(Payload::from_data(entries, page, limit, Some(record_count as u64)))
+    }
+
+    fn find_by_status(
+        ticket_type_id: Uuid,
+        status: WaitlistEntryStatus,
+        conn: &PgConnection,
+    ) -> Result<Vec<WaitlistEntry>, DatabaseError> {
+        waitlist_entries::table
+            .filter(waitlist_entries::ticket_type_id.eq(ticket_type_id))
+            .filter(waitlist_entries::status.eq(status))
+            .order_by(waitlist_entries::created_at.asc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load waitlist entries")
+    }
+
+    pub fn has_open_entries(ticket_type_id: Uuid, conn: &PgConnection) -> Result<bool, DatabaseError> {
+        diesel::select(dsl::exists(
+            waitlist_entries::table
+                .filter(waitlist_entries::ticket_type_id.eq(ticket_type_id))
+                .filter(
+                    waitlist_entries::status.eq_any(vec![WaitlistEntryStatus::Waiting, WaitlistEntryStatus::Offered]),
+                ),
+        ))
+        .get_result(conn)
+        .to_db_error(ErrorCode::QueryError, "Could not check waitlist for ticket type")
+    }
+
+    pub fn ticket_type(&self, conn: &PgConnection) -> Result<TicketType, DatabaseError> {
+        TicketType::find(self.ticket_type_id, conn)
+    }
+
+    pub fn user(&self, conn: &PgConnection) -> Result<User, DatabaseError> {
+        User::find(self.user_id, conn)
+    }
+
+    pub fn hold(&self, conn: &PgConnection) -> Result<Option<Hold>, DatabaseError> {
+        self.hold_id.map(|hold_id| Hold::find(hold_id, conn)).transpose()
+    }
+
+    /// Schedules the waitlist for the ticket type to be processed at `run_at`. Only one future
+    /// action is kept per ticket type, if one already exists it is brought forward when needed.
+    /// Actions that are already due may be running so they are left alone.
+    pub fn queue_processing(
+        ticket_type_id: Uuid,
+        run_at: Option<NaiveDateTime>,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        if !WaitlistEntry::has_open_entries(ticket_type_id, conn)? {
+            return Ok(());
+        }
+
+        let now = Utc::now().naive_utc();
+        let run_at = match run_at {
+            Some(run_at) if run_at > now => run_at,
+            _ => now,
+        };
+
+        let scheduled_action = DomainAction::find_by_resource(
+            Some(Tables::TicketTypes),
+            Some(ticket_type_id),
+            DomainActionTypes::ProcessWaitlist,
+            DomainActionStatus::Pending,
+            conn,
+        )?
+        .into_iter()
+        .filter(|a| a.scheduled_at > now)
+        .min_by_key(|a| a.scheduled_at);
+
+        match scheduled_action {
+            Some(action) => {
+                if action.scheduled_at > run_at {
+                    action.set_scheduled_at(run_at, conn)?;
+                }
+            }
+            None => {
+                let mut action = DomainAction::create(
+                    None,
+                    DomainActionTypes::ProcessWaitlist,
+                    None,
+                    json!({}),
+                    Some(Tables::TicketTypes),
+                    Some(ticket_type_id),
+                );
+                action.schedule_at(run_at);
+                action.commit(conn)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Resolves lapsed offers and offers any free inventory to the users waiting in line.
+    /// Returns the entries that received an offer along with the hold reserving their tickets.
+    pub fn process_ticket_type(
+        ticket_type_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<(WaitlistEntry, Hold)>, DatabaseError> {
+        let now = Utc::now().naive_utc();
+        for entry in WaitlistEntry::find_by_status(ticket_type_id, WaitlistEntryStatus::Offered, conn)? {
+            if entry.offer_expires_at.map(|e| e <= now).unwrap_or(true) {
+                entry.close_offer(conn)?;
+            }
+        }
+
+        let ticket_type = TicketType::find(ticket_type_id, conn)?;
+        let mut offers = Vec::new();
+        let waiting = WaitlistEntry::find_by_status(ticket_type_id, WaitlistEntryStatus::Waiting, conn)?;
+        let mut available = ticket_type.valid_available_ticket_count(conn)? as i64;
+        for entry in waiting.iter() {
+            // Inventory is offered strictly in the order users joined the waitlist
+            if available < entry.quantity {
+                break;
+            }
+            let (entry, hold) = entry.offer(&ticket_type, conn)?;
+            available -= entry.quantity;
+            offers.push((entry, hold));
+        }
+
+        // Tickets sitting in unpaid carts are released lazily, so check back when the
+        // next reservation lapses if there are still users waiting in line
+        if waiting.len() > offers.len() {
+            if let Some(next_expiry) = WaitlistEntry::next_reservation_expiry(ticket_type_id, conn)? {
+                WaitlistEntry::queue_processing(ticket_type_id, Some(next_expiry), conn)?;
+            }
+        }
+
+        Ok(offers)
+    }
+
+    fn next_reservation_expiry(
+        ticket_type_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Option<NaiveDateTime>, DatabaseError> {
+        ticket_instances::table
+            .inner_join(assets::table)
+            .filter(assets::ticket_type_id.eq(ticket_type_id))
+            .filter(ticket_instances::status.eq(TicketInstanceStatus::Reserved))
+            .filter(ticket_instances::hold_id.is_null())
+            .filter(ticket_instances::reserved_until.gt(dsl::now))
+            .select(dsl::min(ticket_instances::reserved_until))
+            .first(conn)
+            .to_db_error(
+                ErrorCode::QueryError,
+                "Could not load reservation expiry for ticket type",
+            )
+    }
+
+    /// Reserves the entry's tickets in a hold that only lasts until the offer expires
+    fn offer(&self, ticket_type: &TicketType, conn: &PgConnection) -> Result<(WaitlistEntry, Hold), DatabaseError> {
+        let user = self.user(conn)?;
+        let offer_expires_at = Utc::now().naive_utc() + chrono::Duration::minutes(WAITLIST_OFFER_EXPIRY_MINUTES);
+
+        let mut new_hold = Hold::create_hold(
+            format!("Waitlist {}", self.id),
+            ticket_type.event_id,
+            Some(random_alpha_string(10)),
+            Some(0),
+            Some(offer_expires_at),
+            Some(self.quantity as u32),
+            HoldTypes::Discount,
+            ticket_type.id,
+        );
+        new_hold.email = user.email.clone();
+        let hold = new_hold.commit(None, conn)?;
+        hold.set_quantity(None, self.quantity as u32, conn)?;
+
+        let entry: WaitlistEntry = diesel::update(
+            waitlist_entries::table
+                .filter(waitlist_entries::id.eq(self.id))
+                .filter(waitlist_entries::updated_at.eq(self.updated_at)),
+        )
+        .set((
+            waitlist_entries::status.eq(WaitlistEntryStatus::Offered),
+            waitlist_entries::hold_id.eq(hold.id),
+            waitlist_entries::offer_expires_at.eq(offer_expires_at),
+            waitlist_entries::updated_at.eq(dsl::now),
+        ))
+        .get_result(conn)
+        .to_db_error(ErrorCode::UpdateError, "Could not update waitlist entry")?;
+
+        DomainEvent::create(
+            DomainEventTypes::WaitlistOfferSent,
+            format!("Waitlist offer of {} tickets sent", self.quantity),
+            Tables::WaitlistEntries,
+            Some(self.id),
+            None,
+            Some(json!({ "hold_id": hold.id, "offer_expires_at": offer_expires_at })),
+        )
+        .commit(conn)?;
+
+        Ok((entry, hold))
+    }
+
+    /// Marks a lapsed offer as claimed if any of the offered tickets were purchased
+    fn close_offer(&self, conn: &PgConnection) -> Result<WaitlistEntry, DatabaseError> {
+        let status = match self.hold(conn)? {
+            Some(hold) => {
+                let (total, remaining) = hold.quantity(conn)?;
+                if total > remaining {
+                    WaitlistEntryStatus::Claimed
+                } else {
+                    WaitlistEntryStatus::Expired
+                }
+            }
+            None => WaitlistEntryStatus::Expired,
+        };
+
+        self.set_status(status, conn)
+    }
+
+    pub fn cancel(&self, current_user_id: Uuid, conn: &PgConnection) -> Result<WaitlistEntry, DatabaseError> {
+        if self.status != WaitlistEntryStatus::Waiting && self.status != WaitlistEntryStatus::Offered {
+            return DatabaseError::business_process_error("Waitlist entry is no longer open");
+        }
+
+        let entry = self.set_status(WaitlistEntryStatus::Cancelled, conn)?;
+        if let Some(hold) = self.hold(conn)? {
+            hold.remove_available_quantity(Some(current_user_id), conn)?;
+            WaitlistEntry::queue_processing(self.ticket_type_id, None, conn)?;
+        }
+
+        DomainEvent::create(
+            DomainEventTypes::WaitlistEntryCancelled,
+            "User left waitlist".to_string(),
+            Tables::WaitlistEntries,
+            Some(self.id),
+            Some(current_user_id),
+            None,
+        )
+        .commit(conn)?;
+
+        Ok(entry)
+    }
+
+    fn set_status(&self, status: WaitlistEntryStatus, conn: &PgConnection) -> Result<WaitlistEntry, DatabaseError> {
+        diesel::update(
+            waitlist_entries::table
+                .filter(waitlist_entries::id.eq(self.id))
+                .filter(waitlist_entries::updated_at.eq(self.updated_at)),
+        )
+        .set((
+            waitlist_entries::status.eq(status),
+            waitlist_entries::updated_at.eq(dsl::now),
+        ))
+        .get_result(conn)
+        .to_db_error(ErrorCode::UpdateError, "Could not update waitlist entry")
+    }
+}
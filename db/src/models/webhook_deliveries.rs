@@ -0,0 +1,291 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::{webhook_deliveries, webhook_delivery_attempts};
+use utils::errors::*;
+use utils::pagination::*;
+use uuid::Uuid;
+
+/// Attempts made before a delivery is given up on, roughly a day with exponential backoff
+pub const WEBHOOK_DELIVERY_MAX_ATTEMPTS: i64 = 10;
+const RETRY_BASE_DELAY_SECONDS: i64 = 30;
+const RETRY_MAX_DELAY_SECONDS: i64 = 6 * 60 * 60;
+const RESPONSE_BODY_EXCERPT_LENGTH: usize = 256;
+
+/// A domain event sent to an organization's webhook endpoint, retried until the endpoint responds
+/// with a success status or the attempts are exhausted
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(DomainEventPublisher)]
+#[table_name = "webhook_deliveries"]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub domain_event_publisher_id: Uuid,
+    pub domain_event_id: Uuid,
+    pub status: WebhookDeliveryStatus,
+    pub payload: String,
+    pub attempt_count: i64,
+    pub last_attempted_at: Option<NaiveDateTime>,
+    pub next_attempt_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[belongs_to(WebhookDelivery)]
+#[table_name = "webhook_delivery_attempts"]
+pub struct WebhookDeliveryAttempt {
+    pub id: Uuid,
+    pub webhook_delivery_id: Uuid,
+    pub response_status: Option<i32>,
+    pub latency_ms: i64,
+    pub response_body_excerpt: Option<String>,
+    pub error: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Deserialize, Insertable, Serialize)]
+#[table_name = "webhook_deliveries"]
+pub struct NewWebhookDelivery {
+    pub domain_event_publisher_id: Uuid,
+    pub domain_event_id: Uuid,
+    pub status: WebhookDeliveryStatus,
+    pub payload: String,
+}
+
+#[derive(Insertable)]
+#[table_name = "webhook_delivery_attempts"]
+struct NewWebhookDeliveryAttempt {
+    webhook_delivery_id: Uuid,
+    response_status: Option<i32>,
+    latency_ms: i64,
+    response_body_excerpt: Option<String>,
+    error: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DisplayWebhookDelivery {
+    pub id: Uuid,
+    pub domain_event_publisher_id: Uuid,
+    pub domain_event_id: Uuid,
+    pub status: WebhookDeliveryStatus,
+    pub payload: String,
+    pub attempt_count: i64,
+    pub last_attempted_at: Option<NaiveDateTime>,
+    pub next_attempt_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub attempts: Vec<WebhookDeliveryAttempt>,
+}
+
+impl NewWebhookDelivery {
+    pub fn commit(self, conn: &PgConnection) -> Result<WebhookDelivery, DatabaseError> {
+        let delivery: WebhookDelivery = diesel::insert_into(webhook_deliveries::table)
+            .values(&self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create webhook delivery")?;
+        delivery.schedule_attempt(Utc::now().naive_utc(), conn)
+    }
+}
+
+impl WebhookDelivery {
+    pub fn create(domain_event_publisher_id: Uuid, domain_event_id: Uuid, payload: String) -> NewWebhookDelivery {
+        NewWebhookDelivery {
+            domain_event_publisher_id,
+            domain_event_id,
+            status: WebhookDeliveryStatus::Pending,
+            payload,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<WebhookDelivery, DatabaseError> {
+        webhook_deliveries::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load webhook delivery")
+    }
+
+    pub fn find_for_publisher(
+        domain_event_publisher_id: Uuid,
+        page: u32,
+        limit: u32,
+        conn: &PgConnection,
+    ) -> Result<Payload<DisplayWebhookDelivery>, DatabaseError> {
+        let (deliveries, record_count): (Vec<WebhookDelivery>, i64) = webhook_deliveries::table
+            .filter(webhook_deliveries::domain_event_publisher_id.eq(domain_event_publisher_id))
+            .order_by(webhook_deliveries::created_at.desc())
+            .paginate(page as i64)
+            .per_page(limit as i64)
+            .load_and_count_pages(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load webhook deliveries")?;
+
+        let attempts = WebhookDeliveryAttempt::belonging_to(&deliveries)
+            .order_by(webhook_delivery_attempts::created_at.asc())
+            .load::<WebhookDeliveryAttempt>(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load webhook delivery attempts")?
+            .grouped_by(&deliveries);
+        let deliveries = deliveries
+            .into_iter()
+            .zip(attempts)
+            .map(|(delivery, attempts)| delivery.into_display(attempts))
+            .collect();
+
+        Ok(Payload::from_data(deliveries, page, limit, Some(record_count as u64)))
+    }
+
+    pub fn domain_event_publisher(&self, conn: &PgConnection) -> Result<DomainEventPublisher, DatabaseError> {
+        DomainEventPublisher::find(self.domain_event_publisher_id, conn)
+    }
+
+    pub fn attempts(&self, conn: &PgConnection) -> Result<Vec<WebhookDeliveryAttempt>, DatabaseError> {
+        WebhookDeliveryAttempt::belonging_to(self)
+            .order_by(webhook_delivery_attempts::created_at.asc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load webhook delivery attempts")
+    }
+
+    pub fn for_display(&self, conn: &PgConnection) -> Result<DisplayWebhookDelivery, DatabaseError> {
+        let attempts = self.attempts(conn)?;
+        Ok(self.clone().into_display(attempts))
+    }
+
+    /// Delay before the next attempt after the given number of failed attempts, doubling each time
+    pub fn retry_delay(attempt_count: i64) -> Duration {
+        let exponent = (attempt_count - 1).max(0).min(20) as u32;
+        Duration::seconds((RETRY_BASE_DELAY_SECONDS * 2i64.pow(exponent)).min(RETRY_MAX_DELAY_SECONDS))
+    }
+
+    /// Logs the outcome of an attempt. Responses outside of the 2xx range and requests that could
+    /// not be completed are retried with exponential backoff.
+    pub fn record_attempt(
+        &self,
+        response_status: Option<i32>,
+        latency_ms: i64,
+        response_body: Option<&str>,
+        error: Option<String>,
+        conn: &PgConnection,
+    ) -> Result<WebhookDelivery, DatabaseError> {
+        if self.status != WebhookDeliveryStatus::Pending {
+            return DatabaseError::business_process_error("Webhook delivery is not awaiting an attempt");
+        }
+
+        diesel::insert_into(webhook_delivery_attempts::table)
+            .values(NewWebhookDeliveryAttempt {
+                webhook_delivery_id: self.id,
+                response_status,
+                latency_ms,
+                response_body_excerpt: response_body
+                    .map(|body| body.chars().take(RESPONSE_BODY_EXCERPT_LENGTH).collect()),
+                error,
+            })
+            .execute(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not log webhook delivery attempt")?;
+
+        let attempt_count = self.attempt_count + 1;
+        let succeeded = response_status.map(|s| s >= 200 && s < 300).unwrap_or(false);
+        let (status, next_attempt_at) = if succeeded {
+            (WebhookDeliveryStatus::Succeeded, None)
+        } else if attempt_count >= WEBHOOK_DELIVERY_MAX_ATTEMPTS {
+            (WebhookDeliveryStatus::Failed, None)
+        } else {
+            (
+                WebhookDeliveryStatus::Pending,
+                Some(Utc::now().naive_utc() + WebhookDelivery::retry_delay(attempt_count)),
+            )
+        };
+
+        let delivery: WebhookDelivery = diesel::update(self)
+            .set((
+                webhook_deliveries::status.eq(status),
+                webhook_deliveries::attempt_count.eq(attempt_count),
+                webhook_deliveries::last_attempted_at.eq(dsl::now.nullable()),
+                webhook_deliveries::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update webhook delivery")?;
+
+        match next_attempt_at {
+            Some(next_attempt_at) => delivery.schedule_attempt(next_attempt_at, conn),
+            None => delivery.clear_next_attempt(conn),
+        }
+    }
+
+    /// Sends a failed delivery again, starting a new round of attempts
+    pub fn replay(&self, current_user_id: Option<Uuid>, conn: &PgConnection) -> Result<WebhookDelivery, DatabaseError> {
+        if self.status != WebhookDeliveryStatus::Failed {
+            return DatabaseError::business_process_error("Only failed webhook deliveries can be replayed");
+        }
+
+        let delivery: WebhookDelivery = diesel::update(self)
+            .set((
+                webhook_deliveries::status.eq(WebhookDeliveryStatus::Pending),
+                webhook_deliveries::attempt_count.eq(0),
+                webhook_deliveries::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not replay webhook delivery")?;
+
+        DomainEvent::create(
+            DomainEventTypes::WebhookDeliveryReplayed,
+            "Webhook delivery replayed".to_string(),
+            Tables::WebhookDeliveries,
+            Some(self.id),
+            current_user_id,
+            Some(json!({ "domain_event_publisher_id": self.domain_event_publisher_id })),
+        )
+        .commit(conn)?;
+
+        delivery.schedule_attempt(Utc::now().naive_utc(), conn)
+    }
+
+    fn schedule_attempt(
+        &self,
+        attempt_at: NaiveDateTime,
+        conn: &PgConnection,
+    ) -> Result<WebhookDelivery, DatabaseError> {
+        let mut action = DomainAction::create(
+            None,
+            DomainActionTypes::DeliverWebhook,
+            None,
+            json!({}),
+            Some(Tables::WebhookDeliveries),
+            Some(self.id),
+        );
+        action.schedule_at(attempt_at);
+        action.commit(conn)?;
+
+        diesel::update(self)
+            .set((
+                webhook_deliveries::next_attempt_at.eq(attempt_at),
+                webhook_deliveries::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update webhook delivery")
+    }
+
+    fn clear_next_attempt(&self, conn: &PgConnection) -> Result<WebhookDelivery, DatabaseError> {
+        diesel::update(self)
+            .set((
+                webhook_deliveries::next_attempt_at.eq(None::<NaiveDateTime>),
+                webhook_deliveries::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update webhook delivery")
+    }
+
+    fn into_display(self, attempts: Vec<WebhookDeliveryAttempt>) -> DisplayWebhookDelivery {
+        DisplayWebhookDelivery {
+            id: self.id,
+            domain_event_publisher_id: self.domain_event_publisher_id,
+            domain_event_id: self.domain_event_id,
+            status: self.status,
+            payload: self.payload,
+            attempt_count: self.attempt_count,
+            last_attempted_at: self.last_attempted_at,
+            next_attempt_at: self.next_attempt_at,
+            created_at: self.created_at,
+            attempts,
+        }
+    }
+}
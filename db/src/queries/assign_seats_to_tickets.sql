@@ -0,0 +1,21 @@
+-- Pairs each requested seat with an unseated, unsold ticket of the ticket type
+WITH locked AS (SELECT t.id
+                FROM ticket_instances AS t
+                         INNER JOIN assets AS a ON t.asset_id = a.id
+                WHERE a.ticket_type_id = $1
+                  AND t.status = 'Available'
+                  AND t.seat_id IS NULL
+                  AND t.hold_id IS NULL
+                  AND t.parent_id IS NULL
+                ORDER BY t.token_id
+                LIMIT $3 FOR UPDATE OF t SKIP LOCKED),
+     tickets AS (SELECT id, row_number() OVER (ORDER BY id) AS position FROM locked),
+     seats AS (SELECT s.id, row_number() OVER (ORDER BY s.id) AS position FROM unnest($2::uuid[]) AS s(id))
+
+UPDATE ticket_instances
+
+SET seat_id    = seats.id,
+    updated_at = now()
+FROM tickets
+         INNER JOIN seats ON seats.position = tickets.position
+WHERE ticket_instances.id = tickets.id RETURNING ticket_instances.*;
@@ -0,0 +1,20 @@
+SELECT l.id,
+       l.title,
+       l.event_id,
+       l.asking_price_in_cents,
+       CAST(COUNT(ti.id) AS BIGINT) AS quantity
+FROM listings l
+         INNER JOIN ticket_instances ti ON ti.listing_id = l.id
+WHERE l.event_id = $1
+  AND l.status = 'Published'
+  AND l.deleted_at IS NULL
+  -- Exclude listings claimed by another buyer's cart
+  AND NOT EXISTS(
+        SELECT 1
+        FROM order_items oi
+                 INNER JOIN orders o ON oi.order_id = o.id
+        WHERE oi.listing_id = l.id
+          AND (o.status IN ('Paid', 'PendingPayment') OR (o.status = 'Draft' AND o.expires_at > now()))
+    )
+GROUP BY l.id, l.title, l.event_id, l.asking_price_in_cents
+ORDER BY l.asking_price_in_cents, l.created_at;
@@ -3,6 +3,7 @@ FROM order_items oi
 LEFT JOIN holds h ON oi.hold_id = h.id
 LEFT JOIN ticket_instances ti ON ti.order_item_id = oi.id
 LEFT JOIN codes c ON oi.code_id = c.id
+LEFT JOIN listings l ON oi.listing_id = l.id
 LEFT JOIN refunded_tickets rt ON oi.id = rt.order_item_id
 LEFT JOIN (
     SELECT count(ti.id) as count, oi.id
@@ -12,11 +13,30 @@ LEFT JOIN (
     GROUP BY oi.id
 ) oit on oit.id = oi.id
 WHERE oi.order_id = $1
-AND item_type = 'Tickets'
 AND (
-    ti.status = 'Nullified'
-    OR ti.reserved_until < now()
-    OR c.end_date < now()
-    OR h.end_at < now()
-    OR oit.count <> oi.quantity
+    (
+        item_type = 'Tickets'
+        AND (
+            ti.status = 'Nullified'
+            OR ti.reserved_until < now()
+            OR c.end_date < now()
+            OR h.end_at < now()
+            OR oit.count <> oi.quantity
+        )
+    )
+    OR (
+        item_type = 'ResaleTickets'
+        AND (
+            l.status <> 'Published'
+            -- Listing has been claimed by another cart
+            OR EXISTS (
+                SELECT 1
+                FROM order_items loi
+                JOIN orders lo ON loi.order_id = lo.id
+                WHERE loi.listing_id = oi.listing_id
+                AND lo.id <> oi.order_id
+                AND (lo.status IN ('Paid', 'PendingPayment') OR (lo.status = 'Draft' AND lo.expires_at > now()))
+            )
+        )
+    )
 )
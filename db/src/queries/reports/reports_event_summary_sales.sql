@@ -12,6 +12,7 @@ SELECT event_id,
        total_client_fee_in_cents,
        pricing_name,
        ticket_name,
+       currency,
        CAST(total_net_income + total_company_fee_in_cents +
             total_client_fee_in_cents AS BIGINT) AS total_gross_income_in_cents
 FROM (
@@ -44,7 +45,8 @@ FROM (
                     ((COALESCE(oi_promo_code.quantity, 0) - COALESCE(oi_promo_code.refunded_quantity, 0)) *
                      COALESCE(oi_promo_code.unit_price_in_cents, 0))),
                               0) AS BIGINT)            AS total_net_income,
-                tp.name                                AS pricing_name,
+                COALESCE(b.name, tp.name)              AS pricing_name,
+                oi.currency,
                 CASE WHEN tt.status = 'Cancelled' THEN concat(tt.name, ' (Cancelled)') ELSE tt.name END AS ticket_name
          FROM orders
                   LEFT JOIN order_items oi ON orders.id = oi.order_id
@@ -54,6 +56,7 @@ FROM (
                   LEFT JOIN codes c ON oi.code_id = c.id
                   LEFT JOIN ticket_types tt ON (oi.ticket_type_id = tt.id)
                   LEFT JOIN ticket_pricing tp ON (oi.ticket_pricing_id = tp.id)
+                  LEFT JOIN bundles b ON (oi.bundle_id = b.id)
                   LEFT JOIN holds h ON oi.hold_id = h.id
                   LEFT JOIN events e ON oi.event_id = e.id
          WHERE orders.status = 'Paid'
@@ -62,6 +65,6 @@ FROM (
            AND oi.item_type = 'Tickets'
            AND ($3 IS NULL OR orders.paid_at >= $3)
            AND ($4 IS NULL OR orders.paid_at <= $4)
-         GROUP BY oi.event_id, oi.ticket_type_id, tt.name, tt.status, tp.name, oi.unit_price_in_cents,
-                  oi_promo_code.unit_price_in_cents, h.id, h.name, c.id
+         GROUP BY oi.event_id, oi.ticket_type_id, tt.name, tt.status, tp.name, b.name, oi.unit_price_in_cents,
+                  oi_promo_code.unit_price_in_cents, h.id, h.name, c.id, oi.currency
      ) AS report_data;
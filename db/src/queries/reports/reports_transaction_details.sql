@@ -24,6 +24,11 @@ SELECT COUNT(*) OVER ()
            COALESCE(oi_event_fees.client_fee_in_cents, 0) *
            (COALESCE(oi_event_fees.quantity, 0) -
             COALESCE(oi_event_fees.refunded_quantity, 0)) AS BIGINT)                                  AS event_fee_client_in_cents_total,
+    -- taxes charged on the ticket and its per unit fees
+    CAST(COALESCE(oi_taxes.tax_in_cents, 0) + COALESCE(oi_fee_taxes.tax_in_cents, 0) AS BIGINT)        AS tax_in_cents,
+    CAST(COALESCE(oi_taxes.tax_in_cents_total, 0)
+    + COALESCE(oi_fee_taxes.tax_in_cents_total, 0) AS BIGINT)                                          AS tax_in_cents_total,
+    oi.currency,
     oi_fees.fee_schedule_range_id                                                                      AS fee_range_id,
     o.paid_at                                                                                          AS transaction_date,
     o.order_type,
@@ -58,6 +63,18 @@ FROM orders o
         ON (oi_event_fees.item_type = 'EventFees' AND o.id = oi_event_fees.order_id)
     LEFT JOIN order_items oi_promo_code
         ON (oi_promo_code.item_type = 'Discount' AND oi.id = oi_promo_code.parent_id)
+    LEFT JOIN (SELECT parent_id,
+        SUM(tax_in_cents)                                    AS tax_in_cents,
+        SUM(tax_in_cents * (quantity - refunded_quantity))   AS tax_in_cents_total
+        FROM order_items
+        WHERE item_type = 'Tax'
+        GROUP BY parent_id) AS oi_taxes ON oi_taxes.parent_id = oi.id
+    LEFT JOIN (SELECT parent_id,
+        SUM(tax_in_cents)                                    AS tax_in_cents,
+        SUM(tax_in_cents * (quantity - refunded_quantity))   AS tax_in_cents_total
+        FROM order_items
+        WHERE item_type = 'Tax'
+        GROUP BY parent_id) AS oi_fee_taxes ON oi_fee_taxes.parent_id = oi_fees.id
     LEFT JOIN codes c ON oi.code_id = c.id
     LEFT JOIN ticket_types tt ON (oi.ticket_type_id = tt.id)
     LEFT JOIN (SELECT order_id,
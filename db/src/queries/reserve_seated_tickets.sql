@@ -0,0 +1,18 @@
+WITH r AS (SELECT t.id
+           FROM ticket_instances AS t
+                    INNER JOIN assets AS a ON t.asset_id = a.id
+           WHERE ((t.reserved_until < now() AND t.status = 'Reserved') OR t.status = 'Available')
+             AND a.ticket_type_id = $3
+             AND t.parent_id IS NULL
+             AND t.hold_id IS NULL
+             AND t.seat_id = ANY ($4)
+           FOR UPDATE OF t SKIP LOCKED)
+
+UPDATE ticket_instances
+
+SET order_item_id  = $1,
+    reserved_until = $2,
+    status         = 'Reserved',
+    updated_at     = now()
+FROM r
+WHERE ticket_instances.id = r.id RETURNING ticket_instances.*;
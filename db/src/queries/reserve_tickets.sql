@@ -4,6 +4,8 @@ WITH r AS (SELECT t.id
            WHERE ((t.reserved_until < now() AND t.status = 'Reserved') OR t.status = 'Available')
              AND a.ticket_type_id = $3
              and t.parent_id is null
+             -- Seats are only reserved by choosing them, see reserve_seated_tickets.sql
+             AND t.seat_id IS NULL
              AND coalesce($4, 'a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11') =
                  coalesce(t.hold_id, 'a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11') -- dummy guid
            LIMIT $5 FOR UPDATE OF t SKIP LOCKED)
@@ -1,3 +1,14 @@
+table! {
+    account_credits (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        currency -> Text,
+        balance_in_cents -> Int8,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     analytics_page_views (id) {
         id -> Uuid,
@@ -44,6 +55,23 @@ table! {
     }
 }
 
+table! {
+    api_keys (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        user_id -> Uuid,
+        name -> Text,
+        key_prefix -> Text,
+        key_hash -> Text,
+        scopes -> Array<Text>,
+        last_used_at -> Nullable<Timestamp>,
+        expires_at -> Nullable<Timestamp>,
+        revoked_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     artist_genres (id) {
         id -> Uuid,
@@ -111,6 +139,30 @@ table! {
     }
 }
 
+table! {
+    bundle_ticket_types (id) {
+        id -> Uuid,
+        bundle_id -> Uuid,
+        ticket_type_id -> Uuid,
+        quantity -> Int4,
+        unit_price_in_cents -> Int8,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    bundles (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        name -> Text,
+        currency -> Text,
+        deleted_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     codes (id) {
         id -> Uuid,
@@ -152,6 +204,32 @@ table! {
     }
 }
 
+table! {
+    communication_template_versions (id) {
+        id -> Uuid,
+        communication_template_id -> Uuid,
+        version -> Int4,
+        subject -> Text,
+        body -> Text,
+        created_by -> Nullable<Uuid>,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    communication_templates (id) {
+        id -> Uuid,
+        organization_id -> Nullable<Uuid>,
+        template_name -> Text,
+        communication_type -> Text,
+        subject -> Text,
+        body -> Text,
+        version -> Int4,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     domain_actions (id) {
         id -> Uuid,
@@ -171,6 +249,7 @@ table! {
         blocked_until -> Timestamp,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        priority -> Int4,
     }
 }
 
@@ -197,6 +276,7 @@ table! {
         adapter -> Nullable<Varchar>,
         adapter_config -> Nullable<Jsonb>,
         blocked_until -> Timestamp,
+        encrypted_signing_secret -> Nullable<Text>,
     }
 }
 
@@ -309,6 +389,24 @@ table! {
         facebook_event_id -> Nullable<Text>,
         settled_at -> Nullable<Timestamp>,
         cloned_from_event_id -> Nullable<Uuid>,
+        currency -> Text,
+        event_series_id -> Nullable<Uuid>,
+    }
+}
+
+table! {
+    event_series (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        template_event_id -> Uuid,
+        name -> Text,
+        recurrence_type -> Text,
+        recurrence_interval -> Int4,
+        recurrence_until -> Nullable<Timestamp>,
+        custom_dates -> Array<Timestamp>,
+        slug_id -> Nullable<Uuid>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
     }
 }
 
@@ -359,6 +457,22 @@ table! {
     }
 }
 
+table! {
+    gift_cards (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        code -> Text,
+        initial_balance_in_cents -> Int8,
+        balance_in_cents -> Int8,
+        currency -> Text,
+        expires_at -> Nullable<Timestamp>,
+        deactivated_at -> Nullable<Timestamp>,
+        created_by -> Uuid,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     holds (id) {
         id -> Uuid,
@@ -390,6 +504,12 @@ table! {
         created_at -> Timestamp,
         updated_at -> Timestamp,
         deleted_at -> Nullable<Timestamp>,
+        event_id -> Nullable<Uuid>,
+        buyer_user_id -> Nullable<Uuid>,
+        order_id -> Nullable<Uuid>,
+        resale_fee_in_cents -> Nullable<Int8>,
+        payout_in_cents -> Nullable<Int8>,
+        sold_at -> Nullable<Timestamp>,
     }
 }
 
@@ -454,6 +574,11 @@ table! {
         company_fee_in_cents -> Int8,
         client_fee_in_cents -> Int8,
         refunded_quantity -> Int8,
+        tax_rule_id -> Nullable<Uuid>,
+        tax_in_cents -> Int8,
+        currency -> Text,
+        listing_id -> Nullable<Uuid>,
+        bundle_id -> Nullable<Uuid>,
     }
 }
 
@@ -528,6 +653,35 @@ table! {
     }
 }
 
+table! {
+    organization_roles (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        name -> Text,
+        description -> Nullable<Text>,
+        scopes -> Array<Text>,
+        deleted_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    organization_sso_configurations (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        issuer_url -> Text,
+        client_id -> Text,
+        encrypted_client_secret -> Text,
+        default_role -> Text,
+        role_claim -> Nullable<Text>,
+        role_mappings -> Jsonb,
+        enabled -> Bool,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     organization_users (id) {
         id -> Uuid,
@@ -537,6 +691,7 @@ table! {
         updated_at -> Timestamp,
         role -> Array<Text>,
         additional_scopes -> Nullable<Jsonb>,
+        organization_role_ids -> Array<Uuid>,
     }
 }
 
@@ -579,6 +734,11 @@ table! {
         slug_id -> Nullable<Uuid>,
         google_ads_conversion_id -> Nullable<Text>,
         google_ads_conversion_labels -> Array<Text>,
+        currency -> Text,
+        transfer_expiry_hours -> Nullable<Int4>,
+        resale_fee_percent -> Float4,
+        require_two_factor -> Bool,
+        payment_plan_max_failed_attempts -> Int4,
     }
 }
 
@@ -592,6 +752,54 @@ table! {
         provider_data -> Json,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        card_brand -> Nullable<Text>,
+        card_last_four -> Nullable<Text>,
+        card_expiry_month -> Nullable<Int4>,
+        card_expiry_year -> Nullable<Int4>,
+        expiry_notification_sent_at -> Nullable<Timestamp>,
+    }
+}
+
+table! {
+    payment_plan_installments (id) {
+        id -> Uuid,
+        payment_plan_id -> Uuid,
+        installment_number -> Int4,
+        amount_in_cents -> Int8,
+        due_at -> Timestamp,
+        status -> Text,
+        failed_attempts -> Int4,
+        last_failure_reason -> Nullable<Text>,
+        payment_id -> Nullable<Uuid>,
+        paid_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    payment_plans (id) {
+        id -> Uuid,
+        order_id -> Uuid,
+        payment_method_id -> Nullable<Uuid>,
+        status -> Text,
+        installment_count -> Int4,
+        max_failed_attempts -> Int4,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    payment_provider_events (id) {
+        id -> Uuid,
+        provider -> Text,
+        external_id -> Text,
+        event_type -> Text,
+        payload -> Jsonb,
+        processed_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
     }
 }
 
@@ -610,6 +818,7 @@ table! {
         updated_at -> Timestamp,
         url_nonce -> Nullable<Text>,
         refund_id -> Nullable<Uuid>,
+        currency -> Text,
     }
 }
 
@@ -670,6 +879,8 @@ table! {
         reason -> Nullable<Text>,
         settlement_id -> Nullable<Uuid>,
         manual_override -> Bool,
+        external_reference -> Nullable<Text>,
+        requires_item_review -> Bool,
     }
 }
 
@@ -691,6 +902,8 @@ table! {
         settlement_adjustment_type -> Text,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        payment_id -> Nullable<Uuid>,
+        external_reference -> Nullable<Text>,
     }
 }
 
@@ -708,6 +921,8 @@ table! {
         settlement_entry_type -> Text,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        tax_in_cents -> Int8,
+        currency -> Text,
     }
 }
 
@@ -722,6 +937,7 @@ table! {
         only_finished_events -> Bool,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        currency -> Text,
     }
 }
 
@@ -749,6 +965,42 @@ table! {
     }
 }
 
+table! {
+    sso_login_requests (id) {
+        id -> Uuid,
+        organization_sso_configuration_id -> Uuid,
+        state -> Text,
+        nonce -> Text,
+        code_verifier -> Text,
+        redirect_uri -> Text,
+        expires_at -> Timestamp,
+        consumed_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,        user_id -> Nullable<Uuid>,
+    }
+}
+
+table! {
+    stage_seats (id) {
+        id -> Uuid,
+        stage_section_id -> Uuid,
+        row_name -> Text,
+        seat_number -> Text,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    stage_sections (id) {
+        id -> Uuid,
+        stage_id -> Uuid,
+        name -> Text,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     stages (id) {
         id -> Uuid,
@@ -761,6 +1013,37 @@ table! {
     }
 }
 
+table! {
+    stored_value_ledger_entries (id) {
+        id -> Uuid,
+        gift_card_id -> Nullable<Uuid>,
+        account_credit_id -> Nullable<Uuid>,
+        entry_type -> Text,
+        amount_in_cents -> Int8,
+        balance_after_in_cents -> Int8,
+        order_id -> Nullable<Uuid>,
+        payment_id -> Nullable<Uuid>,
+        refund_id -> Nullable<Uuid>,
+        created_by -> Nullable<Uuid>,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    tax_rules (id) {
+        id -> Uuid,
+        name -> Text,
+        venue_id -> Nullable<Uuid>,
+        region_id -> Nullable<Uuid>,
+        rate_percent -> Float4,
+        applies_to_fees -> Bool,
+        inclusive -> Bool,
+        deleted_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     temporary_user_links (temporary_user_id, user_id) {
         temporary_user_id -> Uuid,
@@ -800,6 +1083,7 @@ table! {
         check_in_source -> Nullable<Text>,
         parent_id -> Nullable<Uuid>,
         listing_id -> Nullable<Uuid>,
+        seat_id -> Nullable<Uuid>,
     }
 }
 
@@ -816,6 +1100,8 @@ table! {
         created_at -> Timestamp,
         updated_at -> Timestamp,
         previous_ticket_pricing_id -> Nullable<Uuid>,
+        currency -> Text,
+        sold_quantity_cap -> Nullable<Int8>,
     }
 }
 
@@ -857,6 +1143,9 @@ table! {
         ticket_type_type -> Varchar,
         promo_image_url -> Nullable<Text>,
         content_url -> Nullable<Text>,
+        resale_price_cap_percent -> Nullable<Int4>,
+        installment_count -> Nullable<Int4>,
+        installment_interval_days -> Int4,
     }
 }
 
@@ -884,6 +1173,7 @@ table! {
         cancelled_by_user_id -> Nullable<Uuid>,
         direct -> Bool,
         destination_temporary_user_id -> Nullable<Uuid>,
+        expires_at -> Nullable<Timestamp>,
     }
 }
 
@@ -897,6 +1187,57 @@ table! {
     }
 }
 
+table! {
+    user_login_throttles (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        failed_attempts -> Int4,
+        last_failed_at -> Nullable<Timestamp>,
+        locked_until -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    user_recovery_codes (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        code_hash -> Text,
+        used_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    user_sessions (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        refresh_token_id -> Uuid,
+        user_agent -> Nullable<Text>,
+        ip_address -> Nullable<Text>,
+        last_seen_at -> Timestamp,
+        revoked_at -> Nullable<Timestamp>,
+        revoked_reason -> Nullable<Text>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        started_by_token_hash -> Nullable<Text>,
+    }
+}
+
+table! {
+    user_totp_credentials (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        encrypted_secret -> Text,
+        confirmed_at -> Nullable<Timestamp>,
+        last_used_time_step -> Nullable<Int8>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     users (id) {
         id -> Uuid,
@@ -946,6 +1287,20 @@ table! {
     }
 }
 
+table! {
+    waitlist_entries (id) {
+        id -> Uuid,
+        ticket_type_id -> Uuid,
+        user_id -> Uuid,
+        quantity -> Int8,
+        status -> Text,
+        hold_id -> Nullable<Uuid>,
+        offer_expires_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     wallets (id) {
         id -> Uuid,
@@ -960,20 +1315,56 @@ table! {
     }
 }
 
+table! {
+    webhook_deliveries (id) {
+        id -> Uuid,
+        domain_event_publisher_id -> Uuid,
+        domain_event_id -> Uuid,
+        status -> Text,
+        payload -> Text,
+        attempt_count -> Int8,
+        last_attempted_at -> Nullable<Timestamp>,
+        next_attempt_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    webhook_delivery_attempts (id) {
+        id -> Uuid,
+        webhook_delivery_id -> Uuid,
+        response_status -> Nullable<Int4>,
+        latency_ms -> Int8,
+        response_body_excerpt -> Nullable<Text>,
+        error -> Nullable<Text>,
+        created_at -> Timestamp,
+    }
+}
+
+joinable!(account_credits -> users (user_id));
 joinable!(announcement_engagements -> announcements (announcement_id));
 joinable!(announcement_engagements -> users (user_id));
 joinable!(announcements -> organizations (organization_id));
+joinable!(api_keys -> organizations (organization_id));
+joinable!(api_keys -> users (user_id));
 joinable!(artist_genres -> artists (artist_id));
 joinable!(artist_genres -> genres (genre_id));
 joinable!(artists -> genres (main_genre_id));
 joinable!(artists -> organizations (organization_id));
 joinable!(assets -> ticket_types (ticket_type_id));
 joinable!(broadcasts -> events (event_id));
+joinable!(bundle_ticket_types -> bundles (bundle_id));
+joinable!(bundle_ticket_types -> ticket_types (ticket_type_id));
+joinable!(bundles -> organizations (organization_id));
 joinable!(codes -> events (event_id));
 joinable!(collection_items -> collections (collection_id));
 joinable!(collection_items -> ticket_types (collectible_id));
 joinable!(collections -> ticket_types (featured_collectible_id));
 joinable!(collections -> users (user_id));
+joinable!(communication_template_versions -> communication_templates (communication_template_id));
+joinable!(communication_template_versions -> users (created_by));
+joinable!(communication_templates -> organizations (organization_id));
 joinable!(domain_actions -> domain_events (domain_event_id));
 joinable!(domain_event_published -> domain_event_publishers (domain_event_publisher_id));
 joinable!(domain_event_published -> domain_events (domain_event_id));
@@ -990,20 +1381,27 @@ joinable!(event_interest -> users (user_id));
 joinable!(event_report_subscribers -> events (event_id));
 joinable!(event_users -> events (event_id));
 joinable!(event_users -> users (user_id));
+joinable!(event_series -> organizations (organization_id));
+joinable!(events -> event_series (event_series_id));
 joinable!(events -> organizations (organization_id));
 joinable!(events -> venues (venue_id));
 joinable!(external_logins -> users (user_id));
 joinable!(fee_schedule_ranges -> fee_schedules (fee_schedule_id));
+joinable!(gift_cards -> organizations (organization_id));
 joinable!(holds -> events (event_id));
 joinable!(holds -> ticket_types (ticket_type_id));
-joinable!(listings -> users (user_id));
+joinable!(listings -> events (event_id));
+joinable!(listings -> orders (order_id));
 joinable!(loot_box_contents -> events (content_event_id));
 joinable!(marketplace_accounts -> users (user_id));
+joinable!(order_items -> bundles (bundle_id));
 joinable!(order_items -> codes (code_id));
 joinable!(order_items -> events (event_id));
 joinable!(order_items -> fee_schedule_ranges (fee_schedule_range_id));
 joinable!(order_items -> holds (hold_id));
+joinable!(order_items -> listings (listing_id));
 joinable!(order_items -> orders (order_id));
+joinable!(order_items -> tax_rules (tax_rule_id));
 joinable!(order_items -> ticket_pricing (ticket_pricing_id));
 joinable!(order_items -> ticket_types (ticket_type_id));
 joinable!(order_transfers -> orders (order_id));
@@ -1012,12 +1410,18 @@ joinable!(orders -> settlements (settlement_id));
 joinable!(organization_interactions -> organizations (organization_id));
 joinable!(organization_interactions -> users (user_id));
 joinable!(organization_invites -> organizations (organization_id));
+joinable!(organization_roles -> organizations (organization_id));
+joinable!(organization_sso_configurations -> organizations (organization_id));
 joinable!(organization_users -> organizations (organization_id));
 joinable!(organization_users -> users (user_id));
 joinable!(organization_venues -> organizations (organization_id));
 joinable!(organization_venues -> venues (venue_id));
 joinable!(organizations -> fee_schedules (fee_schedule_id));
 joinable!(payment_methods -> users (user_id));
+joinable!(payment_plan_installments -> payment_plans (payment_plan_id));
+joinable!(payment_plan_installments -> payments (payment_id));
+joinable!(payment_plans -> orders (order_id));
+joinable!(payment_plans -> payment_methods (payment_method_id));
 joinable!(payments -> orders (order_id));
 joinable!(payments -> refunds (refund_id));
 joinable!(payments -> users (created_by));
@@ -1030,17 +1434,30 @@ joinable!(refunded_tickets -> ticket_instances (ticket_instance_id));
 joinable!(refunds -> orders (order_id));
 joinable!(refunds -> settlements (settlement_id));
 joinable!(refunds -> users (user_id));
+joinable!(settlement_adjustments -> payments (payment_id));
 joinable!(settlement_adjustments -> settlements (settlement_id));
 joinable!(settlement_entries -> events (event_id));
 joinable!(settlement_entries -> settlements (settlement_id));
 joinable!(settlement_entries -> ticket_types (ticket_type_id));
 joinable!(settlements -> organizations (organization_id));
+joinable!(sso_login_requests -> organization_sso_configurations (organization_sso_configuration_id));
+joinable!(sso_login_requests -> users (user_id));
+joinable!(stage_seats -> stage_sections (stage_section_id));
+joinable!(stage_sections -> stages (stage_id));
+joinable!(stored_value_ledger_entries -> account_credits (account_credit_id));
+joinable!(stored_value_ledger_entries -> gift_cards (gift_card_id));
+joinable!(stored_value_ledger_entries -> orders (order_id));
+joinable!(stored_value_ledger_entries -> payments (payment_id));
+joinable!(stored_value_ledger_entries -> refunds (refund_id));
+joinable!(tax_rules -> regions (region_id));
+joinable!(tax_rules -> venues (venue_id));
 joinable!(temporary_user_links -> temporary_users (temporary_user_id));
 joinable!(temporary_user_links -> users (user_id));
 joinable!(ticket_instances -> assets (asset_id));
 joinable!(ticket_instances -> holds (hold_id));
 joinable!(ticket_instances -> listings (listing_id));
 joinable!(ticket_instances -> order_items (order_item_id));
+joinable!(ticket_instances -> stage_seats (seat_id));
 joinable!(ticket_instances -> wallets (wallet_id));
 joinable!(ticket_pricing -> ticket_types (ticket_type_id));
 joinable!(ticket_type_codes -> codes (code_id));
@@ -1051,21 +1468,37 @@ joinable!(transfer_tickets -> ticket_instances (ticket_instance_id));
 joinable!(transfer_tickets -> transfers (transfer_id));
 joinable!(user_genres -> genres (genre_id));
 joinable!(user_genres -> users (user_id));
+joinable!(user_login_throttles -> users (user_id));
+joinable!(user_recovery_codes -> users (user_id));
+joinable!(user_sessions -> users (user_id));
+joinable!(user_totp_credentials -> users (user_id));
 joinable!(venues -> regions (region_id));
+joinable!(waitlist_entries -> holds (hold_id));
+joinable!(waitlist_entries -> ticket_types (ticket_type_id));
+joinable!(waitlist_entries -> users (user_id));
 joinable!(wallets -> organizations (organization_id));
 joinable!(wallets -> users (user_id));
+joinable!(webhook_deliveries -> domain_event_publishers (domain_event_publisher_id));
+joinable!(webhook_deliveries -> domain_events (domain_event_id));
+joinable!(webhook_delivery_attempts -> webhook_deliveries (webhook_delivery_id));
 
 allow_tables_to_appear_in_same_query!(
+    account_credits,
     analytics_page_views,
     announcement_engagements,
     announcements,
+    api_keys,
     artist_genres,
     artists,
     assets,
     broadcasts,
+    bundle_ticket_types,
+    bundles,
     codes,
     collection_items,
     collections,
+    communication_template_versions,
+    communication_templates,
     domain_actions,
     domain_event_published,
     domain_event_publishers,
@@ -1074,12 +1507,14 @@ allow_tables_to_appear_in_same_query!(
     event_genres,
     event_interest,
     event_report_subscribers,
+    event_series,
     event_users,
     events,
     external_logins,
     fee_schedule_ranges,
     fee_schedules,
     genres,
+    gift_cards,
     holds,
     listings,
     loot_box_contents,
@@ -1090,10 +1525,15 @@ allow_tables_to_appear_in_same_query!(
     orders,
     organization_interactions,
     organization_invites,
+    organization_roles,
+    organization_sso_configurations,
     organization_users,
     organization_venues,
     organizations,
     payment_methods,
+    payment_plan_installments,
+    payment_plans,
+    payment_provider_events,
     payments,
     push_notification_tokens,
     rarities,
@@ -1106,7 +1546,12 @@ allow_tables_to_appear_in_same_query!(
     settlements,
     slugs,
     source_aliases,
+    sso_login_requests,
+    stage_seats,
+    stage_sections,
     stages,
+    stored_value_ledger_entries,
+    tax_rules,
     temporary_user_links,
     temporary_users,
     ticket_instances,
@@ -1116,7 +1561,14 @@ allow_tables_to_appear_in_same_query!(
     transfer_tickets,
     transfers,
     user_genres,
+    user_login_throttles,
+    user_recovery_codes,
+    user_sessions,
+    user_totp_credentials,
     users,
     venues,
+    waitlist_entries,
     wallets,
+    webhook_deliveries,
+    webhook_delivery_attempts,
 );
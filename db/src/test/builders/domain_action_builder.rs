@@ -16,6 +16,7 @@ pub struct DomainActionBuilder<'a> {
     blocked_until: Option<NaiveDateTime>,
     max_attempt_count: Option<i64>,
     attempt_count: Option<i64>,
+    priority: Option<i32>,
 
     connection: &'a PgConnection,
 }
@@ -35,6 +36,7 @@ impl<'a> DomainActionBuilder<'a> {
             blocked_until: None,
             attempt_count: None,
             max_attempt_count: None,
+            priority: None,
 
             connection,
         }
@@ -90,6 +92,11 @@ impl<'a> DomainActionBuilder<'a> {
         self
     }
 
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
     pub fn finish(self) -> DomainAction {
         let mut action = DomainAction::create(
             self.domain_event_id,
@@ -124,6 +131,10 @@ impl<'a> DomainActionBuilder<'a> {
             action.max_attempt_count = self.max_attempt_count.unwrap();
         }
 
+        if self.priority.is_some() {
+            action.priority = self.priority.unwrap();
+        }
+
         action.commit(self.connection).unwrap()
     }
 }
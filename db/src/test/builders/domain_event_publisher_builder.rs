@@ -1,11 +1,13 @@
 use diesel::prelude::*;
 use models::*;
+use test::builders::*;
 use uuid::Uuid;
 
 pub struct DomainEventPublisherBuilder<'a> {
     organization_id: Option<Uuid>,
     event_types: Option<Vec<DomainEventTypes>>,
     webhook_url: Option<String>,
+    signing_secret: Option<String>,
     connection: &'a PgConnection,
 }
 
@@ -15,6 +17,7 @@ impl<'a> DomainEventPublisherBuilder<'a> {
             organization_id: None,
             event_types: None,
             webhook_url: None,
+            signing_secret: None,
             connection,
         }
     }
@@ -34,7 +37,27 @@ impl<'a> DomainEventPublisherBuilder<'a> {
         self
     }
 
+    /// Creates an organization webhook signed with the secret
+    pub fn with_signing_secret(mut self, signing_secret: &str) -> Self {
+        self.signing_secret = Some(signing_secret.to_string());
+        self
+    }
+
     pub fn finish(self) -> DomainEventPublisher {
+        if let Some(signing_secret) = self.signing_secret {
+            return DomainEventPublisher::create_for_organization(
+                self.organization_id
+                    .unwrap_or_else(|| OrganizationBuilder::new(self.connection).finish().id),
+                self.event_types.unwrap_or(vec![DomainEventTypes::OrderCompleted]),
+                self.webhook_url.unwrap_or("https://www.tari.com".to_string()),
+                &signing_secret,
+                "encryption_key",
+            )
+            .unwrap()
+            .commit(self.connection)
+            .unwrap();
+        }
+
         DomainEventPublisher::create(
             self.organization_id,
             self.event_types.unwrap_or(vec![DomainEventTypes::OrderCreated]),
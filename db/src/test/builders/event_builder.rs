@@ -270,6 +270,7 @@ impl<'a> EventBuilder<'a> {
                             false,
                             None,
                             None,
+                            None,
                             self.connection,
                         )
                         .unwrap();
@@ -283,6 +284,7 @@ impl<'a> EventBuilder<'a> {
                             false,
                             None,
                             None,
+                            None,
                             self.connection,
                         )
                         .unwrap();
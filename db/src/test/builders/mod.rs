@@ -26,6 +26,7 @@ pub use self::settlement_builder::*;
 pub use self::settlement_entry_builder::*;
 pub use self::slug_builder::*;
 pub use self::stage_builder::*;
+pub use self::stage_section_builder::*;
 pub use self::ticket_type_builder::*;
 pub use self::user_builder::*;
 pub use self::venue_builder::*;
@@ -58,6 +59,7 @@ mod settlement_builder;
 mod settlement_entry_builder;
 mod slug_builder;
 mod stage_builder;
+mod stage_section_builder;
 mod ticket_type_builder;
 mod user_builder;
 mod venue_builder;
@@ -18,6 +18,11 @@ pub struct OrganizationBuilder<'a> {
     additional_fee: i64,
     timezone: Option<String>,
     settlement_type: Option<SettlementTypes>,
+    currency: Option<String>,
+    transfer_expiry_hours: Option<i32>,
+    resale_fee_percent: Option<f32>,
+    require_two_factor: bool,
+    allowed_payment_providers: Option<Vec<PaymentProviders>>,
 }
 
 impl<'a> OrganizationBuilder<'a> {
@@ -36,6 +41,11 @@ impl<'a> OrganizationBuilder<'a> {
             additional_fee: 0,
             timezone: None,
             settlement_type: None,
+            currency: None,
+            transfer_expiry_hours: None,
+            resale_fee_percent: None,
+            require_two_factor: false,
+            allowed_payment_providers: None,
         }
     }
 
@@ -59,6 +69,31 @@ impl<'a> OrganizationBuilder<'a> {
         self
     }
 
+    pub fn with_currency(mut self, currency: &str) -> Self {
+        self.currency = Some(currency.to_string());
+        self
+    }
+
+    pub fn with_allowed_payment_providers(mut self, allowed_payment_providers: Vec<PaymentProviders>) -> Self {
+        self.allowed_payment_providers = Some(allowed_payment_providers);
+        self
+    }
+
+    pub fn with_transfer_expiry_hours(mut self, transfer_expiry_hours: i32) -> Self {
+        self.transfer_expiry_hours = Some(transfer_expiry_hours);
+        self
+    }
+
+    pub fn with_resale_fee(mut self, resale_fee_percent: f32) -> Self {
+        self.resale_fee_percent = Some(resale_fee_percent);
+        self
+    }
+
+    pub fn with_two_factor_required(mut self) -> Self {
+        self.require_two_factor = true;
+        self
+    }
+
     pub fn with_name(mut self, name: String) -> Self {
         self.name = name;
         self
@@ -107,6 +142,9 @@ impl<'a> OrganizationBuilder<'a> {
 
         let mut organization = Organization::create(&self.name, self.fee_schedule.unwrap().id);
         organization.settlement_type = self.settlement_type;
+        organization.currency = self.currency;
+        organization.transfer_expiry_hours = self.transfer_expiry_hours;
+        organization.resale_fee_percent = self.resale_fee_percent;
         let mut organization = organization
             .commit(None, "encryption_key", None, self.connection)
             .unwrap();
@@ -117,6 +155,8 @@ impl<'a> OrganizationBuilder<'a> {
             cc_fee_percent: self.cc_fee_percent,
             max_additional_fee_in_cents: Some(self.additional_fee),
             timezone: self.timezone,
+            require_two_factor: Some(self.require_two_factor),
+            allowed_payment_providers: self.allowed_payment_providers,
             ..Default::default()
         };
 
@@ -7,6 +7,7 @@ pub struct PaymentMethodBuilder<'a> {
     name: PaymentProviders,
     user_id: Option<Uuid>,
     is_default: bool,
+    card_expiry: Option<(i32, i32)>,
     connection: &'a PgConnection,
 }
 
@@ -16,6 +17,7 @@ impl<'a> PaymentMethodBuilder<'a> {
             name: PaymentProviders::Stripe,
             user_id: None,
             is_default: false,
+            card_expiry: None,
             connection,
         }
     }
@@ -30,6 +32,11 @@ impl<'a> PaymentMethodBuilder<'a> {
         self
     }
 
+    pub fn with_card_expiry(mut self, month: i32, year: i32) -> Self {
+        self.card_expiry = Some((month, year));
+        self
+    }
+
     pub fn with_name(mut self, name: PaymentProviders) -> Self {
         self.name = name;
         self
@@ -43,8 +50,11 @@ impl<'a> PaymentMethodBuilder<'a> {
 
         let user_id = self.user_id.unwrap();
 
-        PaymentMethod::create(user_id, self.name, self.is_default, "cus_example".into(), "abc".into())
-            .commit(user_id, self.connection)
-            .unwrap()
+        let mut payment_method =
+            PaymentMethod::create(user_id, self.name, self.is_default, "cus_example".into(), "abc".into());
+        if let Some((month, year)) = self.card_expiry {
+            payment_method = payment_method.with_card("Visa".to_string(), "4242".to_string(), month, year);
+        }
+        payment_method.commit(user_id, self.connection).unwrap()
     }
 }
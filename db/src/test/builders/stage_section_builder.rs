@@ -0,0 +1,54 @@
+use diesel::prelude::*;
+use models::*;
+use uuid::Uuid;
+
+pub struct StageSectionBuilder<'a> {
+    name: String,
+    stage_id: Uuid,
+    seat_count: u32,
+    connection: &'a PgConnection,
+}
+
+impl<'a> StageSectionBuilder<'a> {
+    pub fn new(connection: &PgConnection) -> StageSectionBuilder {
+        let x: u32 = rand::random();
+
+        StageSectionBuilder {
+            connection,
+            name: format!("Section {}", x).into(),
+            stage_id: Uuid::nil(),
+            seat_count: 0,
+        }
+    }
+
+    pub fn with_name(mut self, name: String) -> Self {
+        self.name = name;
+        self
+    }
+
+    pub fn with_stage_id(mut self, stage_id: Uuid) -> Self {
+        self.stage_id = stage_id;
+        self
+    }
+
+    /// Adds the given number of seats to row "A" of the section
+    pub fn with_seats(mut self, seat_count: u32) -> Self {
+        self.seat_count = seat_count;
+        self
+    }
+
+    pub fn finish(self) -> StageSection {
+        let section = StageSection::create(self.stage_id, self.name)
+            .commit(self.connection)
+            .unwrap();
+
+        if self.seat_count > 0 {
+            let new_seats: Vec<NewStageSeat> = (1..=self.seat_count)
+                .map(|seat_number| StageSeat::create(section.id, "A".to_string(), seat_number.to_string()))
+                .collect();
+            StageSeat::create_multiple(&new_seats, self.connection).unwrap();
+        }
+
+        section
+    }
+}
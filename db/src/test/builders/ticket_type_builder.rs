@@ -145,6 +145,7 @@ impl<'a> TicketPricingBuilder<'a> {
                 false,
                 None,
                 None,
+                None,
                 connection,
             )
             .unwrap();
@@ -147,6 +147,10 @@ impl TestProject {
         StageBuilder::new(&self.connection)
     }
 
+    pub fn create_stage_section(&self) -> StageSectionBuilder {
+        StageSectionBuilder::new(&self.connection)
+    }
+
     pub fn create_event_artist(&self) -> EventArtistBuilder {
         EventArtistBuilder::new(&self.connection)
     }
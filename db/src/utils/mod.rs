@@ -5,9 +5,14 @@ pub mod hash;
 pub mod iterators;
 mod math;
 pub mod migration;
+pub mod oidc;
 pub mod pagination;
 pub mod passwords;
 pub mod rand;
 pub mod regexes;
+pub mod templates;
 pub mod text;
+pub mod totp;
+pub mod webhook_signatures;
+pub mod webhook_urls;
 pub use self::math::*;
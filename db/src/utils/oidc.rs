@@ -0,0 +1,104 @@
+use ring::digest;
+
+const BASE64URL_ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// RFC 7636 S256 code challenge sent with the authorization request
+pub fn pkce_challenge(code_verifier: &str) -> String {
+    base64url_encode(digest::digest(&digest::SHA256, code_verifier.as_bytes()).as_ref())
+}
+
+/// DER encoded RSAPublicKey built from the base64url modulus and exponent of a JSON web key,
+/// the form expected when verifying RS256 signatures
+pub fn rsa_public_key_der(modulus: &str, exponent: &str) -> Option<Vec<u8>> {
+    let mut body = der_integer(&base64url_decode(modulus)?);
+    body.extend(der_integer(&base64url_decode(exponent)?));
+    let mut result = vec![0x30];
+    result.extend(der_length(body.len()));
+    result.extend(body);
+    Some(result)
+}
+
+/// Unpadded base64url encoding as used throughout JOSE
+pub fn base64url_encode(data: &[u8]) -> String {
+    let mut result = String::new();
+    for chunk in data.chunks(3) {
+        let buffer = chunk.iter().fold(0u32, |buffer, byte| (buffer << 8) | *byte as u32) << (8 * (3 - chunk.len()));
+        for i in 0..=chunk.len() {
+            result.push(BASE64URL_ALPHABET[((buffer >> (18 - 6 * i)) & 0x3f) as usize] as char);
+        }
+    }
+    result
+}
+
+pub fn base64url_decode(data: &str) -> Option<Vec<u8>> {
+    let mut result = Vec::new();
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for c in data.trim_end_matches('=').bytes() {
+        let value = BASE64URL_ALPHABET.iter().position(|a| *a == c)? as u32;
+        buffer = (buffer << 6) | value;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            result.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Some(result)
+}
+
+fn der_integer(value: &[u8]) -> Vec<u8> {
+    let mut value: Vec<u8> = value.iter().cloned().skip_while(|b| *b == 0).collect();
+    // Integers are signed so a leading high bit needs a zero byte to stay positive
+    if value.first().map(|b| b & 0x80 != 0).unwrap_or(true) {
+        value.insert(0, 0);
+    }
+    let mut result = vec![0x02];
+    result.extend(der_length(value.len()));
+    result.extend(value);
+    result
+}
+
+fn der_length(length: usize) -> Vec<u8> {
+    if length < 0x80 {
+        return vec![length as u8];
+    }
+    let bytes: Vec<u8> = (0..8)
+        .rev()
+        .map(|i| (length >> (8 * i)) as u8)
+        .skip_while(|b| *b == 0)
+        .collect();
+    let mut result = vec![0x80 | bytes.len() as u8];
+    result.extend(bytes);
+    result
+}
+
+#[test]
+fn base64url_round_trip() {
+    assert_eq!(base64url_encode(b"f"), "Zg");
+    assert_eq!(base64url_encode(b"fo"), "Zm8");
+    assert_eq!(base64url_encode(b"foo"), "Zm9v");
+    assert_eq!(base64url_encode(&[0xfb, 0xff]), "-_8");
+    assert_eq!(base64url_decode("Zm9vYg"), Some(b"foob".to_vec()));
+    assert_eq!(base64url_decode("-_8"), Some(vec![0xfb, 0xff]));
+    assert_eq!(base64url_decode("Zm9v!"), None);
+}
+
+#[test]
+fn pkce_challenge_matches_rfc_example() {
+    assert_eq!(
+        pkce_challenge("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"),
+        "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+    );
+}
+
+#[test]
+fn rsa_public_key_der_encoding() {
+    // Modulus with its high bit set gains a leading zero, 65537 is encoded as is
+    assert_eq!(
+        rsa_public_key_der("gQ", "AQAB"),
+        Some(vec![0x30, 0x09, 0x02, 0x02, 0x00, 0x81, 0x02, 0x03, 0x01, 0x00, 0x01])
+    );
+    assert_eq!(der_length(0x7f), vec![0x7f]);
+    assert_eq!(der_length(0x100), vec![0x82, 0x01, 0x00]);
+}
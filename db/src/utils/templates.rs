@@ -0,0 +1,71 @@
+use regex::{Captures, Regex};
+use std::collections::HashMap;
+
+/// Matches `{{variable}}` and the unescaped `{{{variable}}}` form used by mail providers
+fn variable_pattern() -> Regex {
+    Regex::new(r#"\{\{(\{)?\s*([a-zA-Z0-9_]+)\s*\}?\}\}"#).unwrap()
+}
+
+/// Returns the variables referenced by a template in the order they first appear
+pub fn variables(template: &str) -> Vec<String> {
+    let mut result: Vec<String> = Vec::new();
+    for captures in variable_pattern().captures_iter(template) {
+        let name = captures[2].to_string();
+        if !result.contains(&name) {
+            result.push(name);
+        }
+    }
+    result
+}
+
+/// Replaces variables with their values, missing variables render as empty strings. When
+/// `escape_html` is set values in `{{variable}}` are escaped while `{{{variable}}}` is inserted as is.
+pub fn render(template: &str, data: &HashMap<String, String>, escape_html: bool) -> String {
+    variable_pattern()
+        .replace_all(template, |captures: &Captures| {
+            let value = data.get(&captures[2]).map(|v| v.as_str()).unwrap_or("");
+            if escape_html && captures.get(1).is_none() {
+                html_escape(value)
+            } else {
+                value.to_string()
+            }
+        })
+        .to_string()
+}
+
+fn html_escape(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&#39;"),
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+#[test]
+fn test_variables() {
+    assert_eq!(
+        variables("Hi {{name}}, {{ org }} {{{item_breakdown}}} {{name}}"),
+        vec!["name".to_string(), "org".to_string(), "item_breakdown".to_string()]
+    );
+    assert!(variables("No variables {name}").is_empty());
+}
+
+#[test]
+fn test_render() {
+    let mut data = HashMap::new();
+    data.insert("name".to_string(), "<b>Sam</b>".to_string());
+    data.insert("item_breakdown".to_string(), "<table></table>".to_string());
+
+    assert_eq!(
+        render("Hi {{ name }} {{{item_breakdown}}}{{missing}}", &data, true),
+        "Hi &lt;b&gt;Sam&lt;/b&gt; <table></table>"
+    );
+    assert_eq!(render("Hi {{name}}", &data, false), "Hi <b>Sam</b>");
+}
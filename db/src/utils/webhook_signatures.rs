@@ -0,0 +1,56 @@
+use hex;
+use rand::{thread_rng, Rng};
+use ring::{digest, hmac};
+
+const SECRET_LENGTH: usize = 32;
+const SECRET_PREFIX: &str = "whsec_";
+pub const SIGNATURE_SCHEME: &str = "v1";
+
+/// Random secret shared with the receiver of a webhook to verify its signatures
+pub fn generate_secret() -> String {
+    let mut secret = [0u8; SECRET_LENGTH];
+    thread_rng().fill(&mut secret);
+    format!("{}{}", SECRET_PREFIX, hex::encode(secret))
+}
+
+/// HMAC-SHA256 of `<timestamp>.<payload>`, hex encoded. Including the timestamp in the signed
+/// content lets receivers reject replayed requests.
+pub fn sign(payload: &str, secret: &str, timestamp: i64) -> String {
+    let key = hmac::SigningKey::new(&digest::SHA256, secret.as_bytes());
+    let signature = hmac::sign(&key, format!("{}.{}", timestamp, payload).as_bytes());
+    hex::encode(signature.as_ref())
+}
+
+/// Signature header value in the form `t=<timestamp>,v1=<signature>`
+pub fn signature_header(payload: &str, secret: &str, timestamp: i64) -> String {
+    format!(
+        "t={},{}={}",
+        timestamp,
+        SIGNATURE_SCHEME,
+        sign(payload, secret, timestamp)
+    )
+}
+
+#[test]
+fn sign_payload() {
+    assert_eq!(
+        sign(r#"{"id":1}"#, "whsec_test", 1_586_000_000),
+        "3eacccc8b8f4b713a3dc697576f2744688c999a5fcefd5db7a7461c719102c34"
+    );
+    assert_ne!(
+        sign(r#"{"id":1}"#, "whsec_test", 1_586_000_001),
+        sign(r#"{"id":1}"#, "whsec_test", 1_586_000_000)
+    );
+    assert_eq!(
+        signature_header(r#"{"id":1}"#, "whsec_test", 1_586_000_000),
+        "t=1586000000,v1=3eacccc8b8f4b713a3dc697576f2744688c999a5fcefd5db7a7461c719102c34"
+    );
+}
+
+#[test]
+fn generate_unique_secrets() {
+    let secret = generate_secret();
+    assert!(secret.starts_with(SECRET_PREFIX));
+    assert_eq!(secret.len(), SECRET_PREFIX.len() + SECRET_LENGTH * 2);
+    assert_ne!(secret, generate_secret());
+}
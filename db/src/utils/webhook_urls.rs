@@ -0,0 +1,157 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs};
+use url::{Host, Url};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WebhookUrlError {
+    Invalid,
+    InsecureScheme,
+    InternalAddress,
+}
+
+impl WebhookUrlError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            WebhookUrlError::Invalid => "url",
+            WebhookUrlError::InsecureScheme => "https_required",
+            WebhookUrlError::InternalAddress => "internal_address",
+        }
+    }
+
+    pub fn message(&self) -> &'static str {
+        match self {
+            WebhookUrlError::Invalid => "Webhook URL is invalid",
+            WebhookUrlError::InsecureScheme => "Webhook URL must use https",
+            WebhookUrlError::InternalAddress => "Webhook URL must not point to an internal address",
+        }
+    }
+}
+
+/// Checks a self-service webhook URL uses https and does not name an internal host. Host names
+/// can resolve to anything so deliveries check them again with `resolve_public_addresses`.
+pub fn check(webhook_url: &str) -> Result<Url, WebhookUrlError> {
+    let url = Url::parse(webhook_url).map_err(|_| WebhookUrlError::Invalid)?;
+    if url.scheme() != "https" {
+        return Err(WebhookUrlError::InsecureScheme);
+    }
+    let is_public = match url.host() {
+        Some(Host::Domain(domain)) => {
+            let domain = domain.trim_end_matches('.').to_lowercase();
+            domain != "localhost" && !domain.ends_with(".localhost")
+        }
+        Some(Host::Ipv4(ip)) => is_public_ip(IpAddr::V4(ip)),
+        Some(Host::Ipv6(ip)) => is_public_ip(IpAddr::V6(ip)),
+        None => return Err(WebhookUrlError::Invalid),
+    };
+    if !is_public {
+        return Err(WebhookUrlError::InternalAddress);
+    }
+    Ok(url)
+}
+
+/// Resolves the webhook host, failing if any address it resolves to is internal
+pub fn resolve_public_addresses(url: &Url) -> Result<Vec<SocketAddr>, WebhookUrlError> {
+    let host = url.host_str().ok_or(WebhookUrlError::Invalid)?;
+    let port = url.port_or_known_default().ok_or(WebhookUrlError::Invalid)?;
+    // Bracketed IPv6 hosts are resolved without their brackets
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let addresses: Vec<SocketAddr> = (host, port)
+        .to_socket_addrs()
+        .map_err(|_| WebhookUrlError::Invalid)?
+        .collect();
+    if addresses.is_empty() {
+        return Err(WebhookUrlError::Invalid);
+    }
+    if addresses.iter().any(|address| !is_public_ip(address.ip())) {
+        return Err(WebhookUrlError::InternalAddress);
+    }
+    Ok(addresses)
+}
+
+/// Whether the address is publicly routable, loopback, link-local (including cloud metadata
+/// services), private, shared and reserved ranges are not
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => is_public_ipv6(ip),
+    }
+}
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let octets = ip.octets();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        // 0.0.0.0/8
+        || octets[0] == 0
+        // Shared address space 100.64.0.0/10
+        || (octets[0] == 100 && octets[1] & 0b1100_0000 == 64)
+        // Reserved 240.0.0.0/4
+        || octets[0] >= 240)
+}
+
+fn is_public_ipv6(ip: Ipv6Addr) -> bool {
+    if let Some(ipv4) = ip.to_ipv4() {
+        if ip.segments()[..5].iter().all(|s| *s == 0) {
+            return is_public_ipv4(ipv4);
+        }
+    }
+    let first_segment = ip.segments()[0];
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        // Unique local fc00::/7
+        || first_segment & 0xfe00 == 0xfc00
+        // Link-local fe80::/10
+        || first_segment & 0xffc0 == 0xfe80)
+}
+
+#[test]
+fn test_check() {
+    assert!(check("https://example.com/webhooks").is_ok());
+    assert!(check("https://8.8.8.8/webhooks").is_ok());
+    assert_eq!(check("not a url").unwrap_err(), WebhookUrlError::Invalid);
+    assert_eq!(
+        check("http://example.com/webhooks").unwrap_err(),
+        WebhookUrlError::InsecureScheme
+    );
+    for webhook_url in vec![
+        "https://localhost/webhooks",
+        "https://api.localhost/webhooks",
+        "https://127.0.0.1/webhooks",
+        "https://169.254.169.254/latest/meta-data",
+        "https://10.0.0.5/webhooks",
+        "https://172.16.0.1/webhooks",
+        "https://192.168.1.1/webhooks",
+        "https://100.64.0.1/webhooks",
+        "https://0.0.0.0/webhooks",
+        "https://[::1]/webhooks",
+        "https://[fd00::1]/webhooks",
+        "https://[fe80::1]/webhooks",
+        "https://[::ffff:127.0.0.1]/webhooks",
+    ] {
+        assert_eq!(
+            check(webhook_url).unwrap_err(),
+            WebhookUrlError::InternalAddress,
+            "{}",
+            webhook_url
+        );
+    }
+}
+
+#[test]
+fn test_resolve_public_addresses() {
+    let url = Url::parse("https://127.0.0.1:8443/webhooks").unwrap();
+    assert_eq!(
+        resolve_public_addresses(&url).unwrap_err(),
+        WebhookUrlError::InternalAddress
+    );
+    let url = Url::parse("https://8.8.8.8/webhooks").unwrap();
+    assert_eq!(
+        resolve_public_addresses(&url).unwrap(),
+        vec!["8.8.8.8:443".parse::<SocketAddr>().unwrap()]
+    );
+}
@@ -0,0 +1,127 @@
+use db::dev::TestProject;
+use db::prelude::*;
+use db::utils::errors::ErrorCode::ValidationError;
+
+#[test]
+fn issue() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let admin = project.create_user().finish();
+
+    let account_credit = AccountCredit::issue(user.id, "usd", 1000, None, None, Some(admin.id), connection).unwrap();
+    assert_eq!(account_credit.user_id, user.id);
+    assert_eq!(account_credit.currency, "USD");
+    assert_eq!(account_credit.balance_in_cents, 1000);
+
+    // Further credit in the same currency is added to the existing balance
+    let account_credit2 = AccountCredit::issue(user.id, "USD", 500, None, None, Some(admin.id), connection).unwrap();
+    assert_eq!(account_credit2.id, account_credit.id);
+    assert_eq!(account_credit2.balance_in_cents, 1500);
+    AccountCredit::issue(user.id, "CAD", 200, None, None, Some(admin.id), connection).unwrap();
+    assert_eq!(AccountCredit::find_for_user(user.id, connection).unwrap().len(), 2);
+
+    let ledger_entries = account_credit2.ledger_entries(connection).unwrap();
+    assert_eq!(ledger_entries.len(), 2);
+    assert!(ledger_entries
+        .iter()
+        .all(|e| e.entry_type == StoredValueLedgerEntryTypes::Issued));
+    assert_eq!(ledger_entries[1].balance_after_in_cents, 1500);
+
+    let domain_events = DomainEvent::find(
+        Tables::AccountCredits,
+        Some(account_credit.id),
+        Some(DomainEventTypes::AccountCreditIssued),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(2, domain_events.len());
+
+    assert!(AccountCredit::issue(user.id, "USD", 0, None, None, Some(admin.id), connection).is_err());
+}
+
+#[test]
+fn issue_for_refund_and_redeem() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let admin = project.create_user().finish();
+    let user = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let mut order = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(2)
+        .is_paid()
+        .finish();
+
+    let items = order.items(connection).unwrap();
+    let order_item = items.iter().find(|i| i.item_type == OrderItemTypes::Tickets).unwrap();
+    let ticket = &TicketInstance::find_for_order_item(order_item.id, connection).unwrap()[0];
+    let refund_items = vec![RefundItemRequest {
+        order_item_id: order_item.id,
+        ticket_instance_id: Some(ticket.id),
+    }];
+    let (refund, amount) = order.refund(&refund_items, admin.id, None, false, connection).unwrap();
+    let account_credit = AccountCredit::issue_for_refund(&order, &refund, amount, admin.id, connection).unwrap();
+    assert_eq!(account_credit.user_id, user.id);
+    assert_eq!(account_credit.balance_in_cents, amount);
+    assert_eq!(
+        Some(account_credit.currency.clone()),
+        order.currency(connection).unwrap()
+    );
+    let ledger_entries = account_credit.ledger_entries(connection).unwrap();
+    assert_eq!(ledger_entries[0].refund_id, Some(refund.id));
+    assert_eq!(ledger_entries[0].order_id, Some(order.id));
+
+    // Credit is spent on the next order, leaving the rest to be paid another way
+    let mut cart = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(3)
+        .finish();
+    let total = cart.calculate_total(connection).unwrap();
+    let payment = cart.add_account_credit_payment(user.id, connection).unwrap();
+    assert_eq!(payment.amount, amount);
+    assert_eq!(payment.payment_method, PaymentMethods::AccountCredit);
+    assert_eq!(cart.status, OrderStatus::Draft);
+    assert_eq!(cart.amount_due(connection).unwrap(), total - amount);
+    let account_credit = AccountCredit::find(account_credit.id, connection).unwrap();
+    assert_eq!(account_credit.balance_in_cents, 0);
+    let ledger_entries = account_credit.ledger_entries(connection).unwrap();
+    assert_eq!(ledger_entries.len(), 2);
+    assert_eq!(ledger_entries[1].entry_type, StoredValueLedgerEntryTypes::Redeemed);
+    assert_eq!(ledger_entries[1].amount_in_cents, -amount);
+    assert_eq!(ledger_entries[1].payment_id, Some(payment.id));
+
+    let result = cart.add_account_credit_payment(user.id, connection);
+    match result {
+        Ok(_) => {
+            panic!("Expected validation error");
+        }
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert_eq!(
+                    errors["account_credit"][0].message.clone().unwrap().into_owned(),
+                    "No account credit is available"
+                );
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    // Refunding the credit payment returns it to the balance
+    let refund = Refund::create(cart.id, admin.id, None, false)
+        .commit(connection)
+        .unwrap();
+    payment
+        .refund_to_stored_value(&refund, amount, admin.id, connection)
+        .unwrap();
+    assert_eq!(
+        AccountCredit::find(account_credit.id, connection)
+            .unwrap()
+            .balance_in_cents,
+        amount
+    );
+}
@@ -0,0 +1,265 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use db::dev::TestProject;
+use db::prelude::*;
+use db::utils::errors::ErrorCode::ValidationError;
+use diesel;
+use diesel::sql_types;
+use diesel::RunQueryDsl;
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project
+        .create_organization()
+        .with_member(&user, Roles::OrgOwner)
+        .finish();
+
+    let issued_api_key = ApiKey::create(
+        organization.id,
+        user.id,
+        "Box office sync".to_string(),
+        vec![Scopes::EventWrite, Scopes::OrderRead],
+        None,
+    )
+    .commit(connection)
+    .unwrap();
+    let api_key = issued_api_key.api_key;
+    assert_eq!(api_key.organization_id, organization.id);
+    assert_eq!(api_key.user_id, user.id);
+    assert_eq!(
+        api_key.scopes,
+        vec!["event:write".to_string(), "order:read".to_string()]
+    );
+    assert!(issued_api_key.key.starts_with(API_KEY_PREFIX));
+    assert!(issued_api_key.key.starts_with(&api_key.key_prefix));
+    // Only the hash of the key is stored
+    assert_ne!(api_key.key_hash, issued_api_key.key);
+    assert!(api_key.is_active());
+
+    let domain_events = DomainEvent::find(
+        Tables::ApiKeys,
+        Some(api_key.id),
+        Some(DomainEventTypes::ApiKeyCreated),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+}
+
+#[test]
+fn commit_with_validation_errors() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project
+        .create_organization()
+        .with_member(&user, Roles::OrgMember)
+        .finish();
+
+    // Scopes the issuer does not hold for the organization cannot be granted
+    let result = ApiKey::create(
+        organization.id,
+        user.id,
+        "Box office sync".to_string(),
+        vec![Scopes::OrgAdminUsers],
+        None,
+    )
+    .commit(connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("scopes"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    let result = ApiKey::create(
+        organization.id,
+        user.id,
+        " ".to_string(),
+        vec![],
+        Some(Utc::now().naive_utc() - Duration::days(1)),
+    )
+    .commit(connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("name"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    let result = ApiKey::create(organization.id, user.id, "Sync".to_string(), vec![], None).commit(connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("scopes"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    let result = ApiKey::create(
+        organization.id,
+        user.id,
+        "Sync".to_string(),
+        vec![Scopes::EventWrite],
+        Some(Utc::now().naive_utc() - Duration::days(1)),
+    )
+    .commit(connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("expires_at"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn find_active_by_key() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project
+        .create_organization()
+        .with_member(&user, Roles::OrgOwner)
+        .finish();
+    let issued_api_key = ApiKey::create(
+        organization.id,
+        user.id,
+        "Box office sync".to_string(),
+        vec![Scopes::EventWrite],
+        Some(Utc::now().naive_utc() + Duration::days(1)),
+    )
+    .commit(connection)
+    .unwrap();
+
+    let api_key = ApiKey::find_active_by_key(&issued_api_key.key, connection).unwrap();
+    assert_eq!(api_key, issued_api_key.api_key);
+    assert!(ApiKey::find_active_by_key("bnk_invalid", connection).is_err());
+
+    // Expired keys are no longer found
+    diesel::sql_query(
+        r#"
+        UPDATE api_keys
+        SET expires_at = $1
+        WHERE id = $2;
+        "#,
+    )
+    .bind::<sql_types::Timestamp, _>(dates::now().add_minutes(-1).finish())
+    .bind::<sql_types::Uuid, _>(api_key.id)
+    .execute(connection)
+    .unwrap();
+    assert!(ApiKey::find_active_by_key(&issued_api_key.key, connection).is_err());
+}
+
+#[test]
+fn record_usage() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project
+        .create_organization()
+        .with_member(&user, Roles::OrgOwner)
+        .finish();
+    let api_key = ApiKey::create(
+        organization.id,
+        user.id,
+        "Box office sync".to_string(),
+        vec![Scopes::EventWrite],
+        None,
+    )
+    .commit(connection)
+    .unwrap()
+    .api_key;
+    assert!(api_key.last_used_at.is_none());
+
+    let api_key = api_key.record_usage(connection).unwrap();
+    assert!(api_key.last_used_at.is_some());
+}
+
+#[test]
+fn rotate() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project
+        .create_organization()
+        .with_member(&user, Roles::OrgOwner)
+        .finish();
+    let issued_api_key = ApiKey::create(
+        organization.id,
+        user.id,
+        "Box office sync".to_string(),
+        vec![Scopes::EventWrite],
+        None,
+    )
+    .commit(connection)
+    .unwrap();
+
+    let rotated_api_key = issued_api_key.api_key.rotate(Some(user.id), connection).unwrap();
+    assert_eq!(rotated_api_key.api_key.id, issued_api_key.api_key.id);
+    assert_ne!(rotated_api_key.key, issued_api_key.key);
+    assert!(ApiKey::find_active_by_key(&issued_api_key.key, connection).is_err());
+    assert_eq!(
+        ApiKey::find_active_by_key(&rotated_api_key.key, connection).unwrap().id,
+        issued_api_key.api_key.id
+    );
+
+    let domain_events = DomainEvent::find(
+        Tables::ApiKeys,
+        Some(issued_api_key.api_key.id),
+        Some(DomainEventTypes::ApiKeyRotated),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+}
+
+#[test]
+fn revoke() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project
+        .create_organization()
+        .with_member(&user, Roles::OrgOwner)
+        .finish();
+    let issued_api_key = ApiKey::create(
+        organization.id,
+        user.id,
+        "Box office sync".to_string(),
+        vec![Scopes::EventWrite],
+        None,
+    )
+    .commit(connection)
+    .unwrap();
+    assert_eq!(
+        ApiKey::find_for_organization(organization.id, connection).unwrap(),
+        vec![issued_api_key.api_key.clone()]
+    );
+
+    let api_key = issued_api_key.api_key.revoke(Some(user.id), connection).unwrap();
+    assert!(api_key.revoked_at.is_some());
+    assert!(!api_key.is_active());
+    assert!(ApiKey::find_active_by_key(&issued_api_key.key, connection).is_err());
+    assert!(api_key.revoke(Some(user.id), connection).is_err());
+    assert!(api_key.rotate(Some(user.id), connection).is_err());
+
+    // Revoked keys remain listed for auditing
+    assert_eq!(
+        ApiKey::find_for_organization(organization.id, connection).unwrap(),
+        vec![api_key]
+    );
+}
@@ -0,0 +1,256 @@
+use db::dev::TestProject;
+use db::models::*;
+use db::utils::errors::ErrorCode::ValidationError;
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+
+    let bundle = Bundle::create(organization.id, "Festival Pass".to_string(), "USD".to_string())
+        .commit(Some(user.id), connection)
+        .unwrap();
+    assert_eq!(bundle.organization_id, organization.id);
+    assert_eq!(bundle.name, "Festival Pass");
+    assert_eq!(bundle.currency, "USD");
+    assert!(bundle.deleted_at.is_none());
+
+    let domain_events = DomainEvent::find(
+        Tables::Bundles,
+        Some(bundle.id),
+        Some(DomainEventTypes::BundleCreated),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+
+    let result = Bundle::create(organization.id, " ".to_string(), "USD".to_string()).commit(Some(user.id), connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("name"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn add_ticket_type() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let event2 = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let ticket_type2 = &event2.ticket_types(true, None, connection).unwrap()[0];
+    let bundle = Bundle::create(organization.id, "Festival Pass".to_string(), "USD".to_string())
+        .commit(Some(user.id), connection)
+        .unwrap();
+
+    bundle
+        .add_ticket_type(ticket_type.id, 1, 1500, Some(user.id), connection)
+        .unwrap();
+    bundle
+        .add_ticket_type(ticket_type2.id, 2, 1000, Some(user.id), connection)
+        .unwrap();
+    assert_eq!(bundle.ticket_types(connection).unwrap().len(), 2);
+    assert_eq!(bundle.price_in_cents(connection).unwrap(), 3500);
+
+    // Adding an existing ticket type changes its allocation
+    let bundle_ticket_type = bundle
+        .add_ticket_type(ticket_type2.id, 1, 2000, Some(user.id), connection)
+        .unwrap();
+    assert_eq!(bundle_ticket_type.quantity, 1);
+    assert_eq!(bundle_ticket_type.unit_price_in_cents, 2000);
+    assert_eq!(bundle.ticket_types(connection).unwrap().len(), 2);
+    assert_eq!(bundle.price_in_cents(connection).unwrap(), 3500);
+
+    let domain_events = DomainEvent::find(
+        Tables::Bundles,
+        Some(bundle.id),
+        Some(DomainEventTypes::BundleUpdated),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 3);
+
+    // Quantity must be positive
+    let result = bundle.add_ticket_type(ticket_type.id, 0, 1500, Some(user.id), connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("quantity"));
+                assert_eq!(errors["quantity"][0].code, "quantity_must_be_positive");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    // Ticket types from other organizations cannot be bundled
+    let other_event = project.create_event().with_tickets().with_ticket_pricing().finish();
+    let other_ticket_type = &other_event.ticket_types(true, None, connection).unwrap()[0];
+    let result = bundle.add_ticket_type(other_ticket_type.id, 1, 1500, Some(user.id), connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("ticket_type_id"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    // Ticket types must be priced in the bundle's currency
+    let cad_bundle = Bundle::create(organization.id, "CAD Pass".to_string(), "CAD".to_string())
+        .commit(Some(user.id), connection)
+        .unwrap();
+    let result = cad_bundle.add_ticket_type(ticket_type.id, 1, 1500, Some(user.id), connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("ticket_type_id"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn remove_ticket_type() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let bundle = Bundle::create(organization.id, "Festival Pass".to_string(), "USD".to_string())
+        .commit(Some(user.id), connection)
+        .unwrap();
+    bundle
+        .add_ticket_type(ticket_type.id, 1, 1500, Some(user.id), connection)
+        .unwrap();
+
+    bundle
+        .remove_ticket_type(ticket_type.id, Some(user.id), connection)
+        .unwrap();
+    assert!(bundle.ticket_types(connection).unwrap().is_empty());
+    assert!(bundle
+        .remove_ticket_type(ticket_type.id, Some(user.id), connection)
+        .is_err());
+}
+
+#[test]
+fn update() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let bundle = Bundle::create(organization.id, "Festival Pass".to_string(), "USD".to_string())
+        .commit(Some(user.id), connection)
+        .unwrap();
+
+    let bundle = bundle
+        .update(
+            BundleEditableAttributes {
+                name: Some("Weekend Pass".to_string()),
+            },
+            Some(user.id),
+            connection,
+        )
+        .unwrap();
+    assert_eq!(bundle.name, "Weekend Pass");
+    assert_eq!(Bundle::find(bundle.id, connection).unwrap().name, "Weekend Pass");
+}
+
+#[test]
+fn destroy() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let bundle = Bundle::create(organization.id, "Festival Pass".to_string(), "USD".to_string())
+        .commit(Some(user.id), connection)
+        .unwrap();
+    assert_eq!(
+        Bundle::find_for_organization(organization.id, connection).unwrap(),
+        vec![bundle.clone()]
+    );
+
+    bundle.destroy(Some(user.id), connection).unwrap();
+    assert!(Bundle::find_for_organization(organization.id, connection)
+        .unwrap()
+        .is_empty());
+    let bundle = Bundle::find(bundle.id, connection).unwrap();
+    assert!(bundle.deleted_at.is_some());
+    assert!(bundle
+        .add_ticket_type(ticket_type.id, 1, 1500, Some(user.id), connection)
+        .is_err());
+}
+
+#[test]
+fn for_display() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_name("Day 1".to_string())
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let bundle = Bundle::create(organization.id, "Festival Pass".to_string(), "USD".to_string())
+        .commit(Some(user.id), connection)
+        .unwrap();
+    bundle
+        .add_ticket_type(ticket_type.id, 2, 1500, Some(user.id), connection)
+        .unwrap();
+
+    let display_bundle = bundle.for_display(connection).unwrap();
+    assert_eq!(display_bundle.id, bundle.id);
+    assert_eq!(display_bundle.name, "Festival Pass");
+    assert_eq!(display_bundle.price_in_cents, 3000);
+    assert_eq!(
+        display_bundle.ticket_types,
+        vec![DisplayBundleTicketType {
+            ticket_type_id: ticket_type.id,
+            ticket_type_name: ticket_type.name.clone(),
+            event_id: event.id,
+            event_name: "Day 1".to_string(),
+            event_start: event.event_start,
+            quantity: 2,
+            unit_price_in_cents: 1500,
+        }]
+    );
+}
@@ -0,0 +1,333 @@
+use db::dev::TestProject;
+use db::prelude::*;
+use db::utils::errors::ErrorCode::ValidationError;
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let user = project.create_user().finish();
+
+    let template = CommunicationTemplate::create(
+        Some(organization.id),
+        CommunicationTemplateNames::OrgInvite,
+        CommunicationType::EmailTemplate,
+        "Join {{org}}".to_string(),
+        "Hi {{name}}, <a href=\"{{invite_link_accept}}\">accept</a>".to_string(),
+    )
+    .commit(Some(user.id), connection)
+    .unwrap();
+    assert_eq!(template.organization_id, Some(organization.id));
+    assert_eq!(template.version, 1);
+
+    let versions = template.versions(connection).unwrap();
+    assert_eq!(versions.len(), 1);
+    assert_eq!(versions[0].version, 1);
+    assert_eq!(versions[0].subject, template.subject);
+    assert_eq!(versions[0].created_by, Some(user.id));
+
+    let domain_events = DomainEvent::find(
+        Tables::CommunicationTemplates,
+        Some(template.id),
+        Some(DomainEventTypes::CommunicationTemplateUpdated),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+    assert_eq!(domain_events[0].organization_id, Some(organization.id));
+}
+
+#[test]
+fn commit_new_version() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+
+    let template = CommunicationTemplate::create(
+        Some(organization.id),
+        CommunicationTemplateNames::UserRegistered,
+        CommunicationType::EmailTemplate,
+        "Welcome".to_string(),
+        "Hi {{name}}".to_string(),
+    )
+    .commit(None, connection)
+    .unwrap();
+
+    let updated = CommunicationTemplate::create(
+        Some(organization.id),
+        CommunicationTemplateNames::UserRegistered,
+        CommunicationType::EmailTemplate,
+        "Welcome".to_string(),
+        "Hello {{name}}".to_string(),
+    )
+    .commit(None, connection)
+    .unwrap();
+    assert_eq!(updated.id, template.id);
+    assert_eq!(updated.version, 2);
+    assert_eq!(updated.body, "Hello {{name}}");
+
+    // Saving unchanged content does not create a version
+    let unchanged = CommunicationTemplate::create(
+        Some(organization.id),
+        CommunicationTemplateNames::UserRegistered,
+        CommunicationType::EmailTemplate,
+        "Welcome".to_string(),
+        "Hello {{name}}".to_string(),
+    )
+    .commit(None, connection)
+    .unwrap();
+    assert_eq!(unchanged, updated);
+
+    let versions = updated.versions(connection).unwrap();
+    assert_eq!(versions.iter().map(|v| v.version).collect::<Vec<i32>>(), vec![2, 1]);
+
+    // Other communication types are separate templates
+    let sms_template = CommunicationTemplate::create(
+        Some(organization.id),
+        CommunicationTemplateNames::UserRegistered,
+        CommunicationType::Sms,
+        "".to_string(),
+        "Welcome {{name}}".to_string(),
+    )
+    .commit(None, connection)
+    .unwrap();
+    assert_ne!(sms_template.id, template.id);
+    assert_eq!(sms_template.version, 1);
+}
+
+#[test]
+fn commit_with_validation_errors() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+
+    let result = CommunicationTemplate::create(
+        None,
+        CommunicationTemplateNames::PasswordReset,
+        CommunicationType::EmailTemplate,
+        "".to_string(),
+        "Reset with {{password_reset_link}} before {{expires_at}}".to_string(),
+    )
+    .commit(None, connection);
+    match result {
+        Ok(_) => {
+            panic!("Expected validation error");
+        }
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("subject"));
+                assert_eq!(errors["body"][0].code, "unknown_variables");
+                assert_eq!(errors["body"][0].params["unknown_variables"], json!(["expires_at"]));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    let result = CommunicationTemplate::create(
+        None,
+        CommunicationTemplateNames::PasswordReset,
+        CommunicationType::Sms,
+        "".to_string(),
+        " ".to_string(),
+    )
+    .commit(None, connection);
+    match result {
+        Ok(_) => {
+            panic!("Expected validation error");
+        }
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("body"));
+                assert!(!errors.contains_key("subject"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn find_active() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let organization2 = project.create_organization().finish();
+
+    assert!(CommunicationTemplate::find_active(
+        Some(organization.id),
+        CommunicationTemplateNames::WaitlistOffer,
+        CommunicationType::EmailTemplate,
+        connection
+    )
+    .unwrap()
+    .is_none());
+
+    let default_template = CommunicationTemplate::create(
+        None,
+        CommunicationTemplateNames::WaitlistOffer,
+        CommunicationType::EmailTemplate,
+        "Tickets for {{event_name}}".to_string(),
+        "{{claim_link}}".to_string(),
+    )
+    .commit(None, connection)
+    .unwrap();
+    let organization_template = CommunicationTemplate::create(
+        Some(organization.id),
+        CommunicationTemplateNames::WaitlistOffer,
+        CommunicationType::EmailTemplate,
+        "{{organization_name}} has tickets for {{event_name}}".to_string(),
+        "{{claim_link}}".to_string(),
+    )
+    .commit(None, connection)
+    .unwrap();
+
+    assert_eq!(
+        CommunicationTemplate::find_active(
+            Some(organization.id),
+            CommunicationTemplateNames::WaitlistOffer,
+            CommunicationType::EmailTemplate,
+            connection
+        )
+        .unwrap(),
+        Some(organization_template.clone())
+    );
+    assert_eq!(
+        CommunicationTemplate::find_active(
+            Some(organization2.id),
+            CommunicationTemplateNames::WaitlistOffer,
+            CommunicationType::EmailTemplate,
+            connection
+        )
+        .unwrap(),
+        Some(default_template.clone())
+    );
+    assert_eq!(
+        CommunicationTemplate::find_active(
+            None,
+            CommunicationTemplateNames::WaitlistOffer,
+            CommunicationType::EmailTemplate,
+            connection
+        )
+        .unwrap(),
+        Some(default_template.clone())
+    );
+
+    assert_eq!(
+        CommunicationTemplate::find_for_organization(Some(organization.id), connection).unwrap(),
+        vec![organization_template]
+    );
+    assert_eq!(
+        CommunicationTemplate::find_for_organization(None, connection).unwrap(),
+        vec![default_template]
+    );
+}
+
+#[test]
+fn rollback() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+
+    CommunicationTemplate::create(
+        None,
+        CommunicationTemplateNames::Refund,
+        CommunicationType::EmailTemplate,
+        "Refund".to_string(),
+        "Refunded {{amount_refunded}}".to_string(),
+    )
+    .commit(None, connection)
+    .unwrap();
+    let template = CommunicationTemplate::create(
+        None,
+        CommunicationTemplateNames::Refund,
+        CommunicationType::EmailTemplate,
+        "Your refund".to_string(),
+        "We refunded {{amount_refunded}}".to_string(),
+    )
+    .commit(None, connection)
+    .unwrap();
+    assert_eq!(template.version, 2);
+
+    let template = template.rollback(1, Some(user.id), connection).unwrap();
+    assert_eq!(template.version, 3);
+    assert_eq!(template.subject, "Refund");
+    assert_eq!(template.body, "Refunded {{amount_refunded}}");
+
+    let versions = template.versions(connection).unwrap();
+    assert_eq!(versions.len(), 3);
+    assert_eq!(versions[0].version, 3);
+    assert_eq!(versions[0].body, "Refunded {{amount_refunded}}");
+    assert_eq!(versions[0].created_by, Some(user.id));
+
+    assert!(template.rollback(3, None, connection).is_err());
+    assert!(template.rollback(10, None, connection).is_err());
+}
+
+#[test]
+fn destroy() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+
+    let template = CommunicationTemplate::create(
+        Some(organization.id),
+        CommunicationTemplateNames::UserInvite,
+        CommunicationType::EmailTemplate,
+        "Invite".to_string(),
+        "{{invite_link}}".to_string(),
+    )
+    .commit(None, connection)
+    .unwrap();
+    template.destroy(None, connection).unwrap();
+
+    assert!(CommunicationTemplate::find(template.id, connection).is_err());
+    let domain_events = DomainEvent::find(
+        Tables::CommunicationTemplates,
+        Some(template.id),
+        Some(DomainEventTypes::CommunicationTemplateDeleted),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+    assert_eq!(domain_events[0].organization_id, Some(organization.id));
+}
+
+#[test]
+fn render() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+
+    let template = CommunicationTemplate::create(
+        None,
+        CommunicationTemplateNames::PurchaseCompleted,
+        CommunicationType::EmailTemplate,
+        "Thanks {{name}}".to_string(),
+        "Hi {{name}} {{{item_breakdown}}}".to_string(),
+    )
+    .commit(None, connection)
+    .unwrap();
+
+    let mut template_data = TemplateData::new();
+    template_data.insert("name".to_string(), "A & B".to_string());
+    template_data.insert("item_breakdown".to_string(), "<table></table>".to_string());
+    assert_eq!(
+        template.render(&template_data),
+        RenderedCommunicationTemplate {
+            subject: "Thanks A & B".to_string(),
+            body: "Hi A &amp; B <table></table>".to_string(),
+        }
+    );
+}
+
+#[test]
+fn sample_data() {
+    let sample_data = CommunicationTemplateNames::WaitlistOffer.sample_data();
+    assert_eq!(
+        sample_data.len(),
+        CommunicationTemplateNames::WaitlistOffer.variables().len()
+    );
+    assert!(sample_data.contains_key("claim_link"));
+    assert!(sample_data.contains_key("event_name"));
+    assert!(!CommunicationTemplateNames::UserRegistered
+        .sample_data()
+        .contains_key("event_name"));
+}
@@ -140,6 +140,195 @@ fn find_pending() {
     assert_eq!(pending_example.id, pending_actions[0].id);
 }
 
+#[test]
+fn find_pending_by_priority() {
+    let project = TestProject::new();
+    let conn = project.get_connection();
+
+    let report_action = project
+        .create_domain_action()
+        .with_domain_action_type(DomainActionTypes::SendAutomaticReportEmails)
+        .with_scheduled_at(Utc::now().naive_utc() - Duration::minutes(10))
+        .finish();
+    let webhook_action = project
+        .create_domain_action()
+        .with_domain_action_type(DomainActionTypes::DeliverWebhook)
+        .with_scheduled_at(Utc::now().naive_utc() - Duration::minutes(5))
+        .finish();
+    let communication_action = project
+        .create_domain_action()
+        .with_domain_action_type(DomainActionTypes::Communication)
+        .finish();
+    let older_communication_action = project
+        .create_domain_action()
+        .with_domain_action_type(DomainActionTypes::Communication)
+        .with_scheduled_at(Utc::now().naive_utc() - Duration::minutes(1))
+        .finish();
+    assert_eq!(report_action.priority, DOMAIN_ACTION_PRIORITY_LOW);
+    assert_eq!(webhook_action.priority, DOMAIN_ACTION_PRIORITY_NORMAL);
+    assert_eq!(communication_action.priority, DOMAIN_ACTION_PRIORITY_HIGH);
+
+    let pending_actions = DomainAction::find_pending(None, conn).unwrap();
+    assert_eq!(
+        pending_actions.iter().map(|a| a.id).collect::<Vec<Uuid>>(),
+        vec![
+            older_communication_action.id,
+            communication_action.id,
+            webhook_action.id,
+            report_action.id
+        ]
+    );
+
+    // Priority can be set per action
+    let urgent_report_action = project
+        .create_domain_action()
+        .with_domain_action_type(DomainActionTypes::SendAutomaticReportEmails)
+        .with_priority(DOMAIN_ACTION_PRIORITY_HIGH + 1)
+        .finish();
+    let pending_actions = DomainAction::find_pending(None, conn).unwrap();
+    assert_eq!(pending_actions[0].id, urgent_report_action.id);
+}
+
+#[test]
+fn find_dead_letters() {
+    let project = TestProject::new();
+    let conn = project.get_connection();
+
+    project
+        .create_domain_action()
+        .with_status(DomainActionStatus::Pending)
+        .finish();
+    project
+        .create_domain_action()
+        .with_status(DomainActionStatus::Success)
+        .finish();
+    let errored = project
+        .create_domain_action()
+        .with_domain_action_type(DomainActionTypes::SendAutomaticReportEmails)
+        .with_status(DomainActionStatus::Errored)
+        .finish();
+    let retries_exceeded = project
+        .create_domain_action()
+        .with_status(DomainActionStatus::RetriesExceeded)
+        .finish();
+
+    let dead_letters = DomainAction::find_dead_letters(None, 0, 100, conn).unwrap();
+    assert_eq!(dead_letters.paging.total, 2);
+    let mut ids = dead_letters.data.iter().map(|a| a.id).collect::<Vec<Uuid>>();
+    ids.sort();
+    let mut expected_ids = vec![errored.id, retries_exceeded.id];
+    expected_ids.sort();
+    assert_eq!(ids, expected_ids);
+
+    let dead_letters =
+        DomainAction::find_dead_letters(Some(DomainActionTypes::SendAutomaticReportEmails), 0, 100, conn).unwrap();
+    assert_eq!(dead_letters.data.len(), 1);
+    assert_eq!(dead_letters.data[0].id, errored.id);
+}
+
+#[test]
+fn requeue() {
+    let project = TestProject::new();
+    let conn = project.get_connection();
+
+    let pending = project
+        .create_domain_action()
+        .with_status(DomainActionStatus::Pending)
+        .finish();
+    assert!(pending.requeue(conn).is_err());
+
+    let failed = project
+        .create_domain_action()
+        .with_status(DomainActionStatus::RetriesExceeded)
+        .with_attempt_count(3)
+        .with_scheduled_at(Utc::now().naive_utc() - Duration::days(2))
+        .finish();
+    assert!(failed.is_dead_letter());
+    assert!(DomainAction::find_pending(None, conn)
+        .unwrap()
+        .iter()
+        .all(|a| a.id != failed.id));
+
+    let requeued = failed.requeue(conn).unwrap();
+    assert_eq!(requeued.status, DomainActionStatus::Pending);
+    assert_eq!(requeued.attempt_count, 0);
+    assert!(requeued.expires_at > Utc::now().naive_utc());
+    assert!(DomainAction::find_pending(None, conn)
+        .unwrap()
+        .iter()
+        .any(|a| a.id == failed.id));
+}
+
+#[test]
+fn cancel() {
+    let project = TestProject::new();
+    let conn = project.get_connection();
+
+    let errored = project
+        .create_domain_action()
+        .with_status(DomainActionStatus::Errored)
+        .finish();
+    assert_eq!(errored.cancel(conn).unwrap().status, DomainActionStatus::Cancelled);
+
+    let pending = project
+        .create_domain_action()
+        .with_status(DomainActionStatus::Pending)
+        .finish();
+    assert_eq!(pending.cancel(conn).unwrap().status, DomainActionStatus::Cancelled);
+
+    let done = project
+        .create_domain_action()
+        .with_status(DomainActionStatus::Success)
+        .finish();
+    assert!(done.cancel(conn).is_err());
+}
+
+#[test]
+fn queue_metrics() {
+    let project = TestProject::new();
+    let conn = project.get_connection();
+
+    let due = project
+        .create_domain_action()
+        .with_domain_action_type(DomainActionTypes::Communication)
+        .with_scheduled_at(Utc::now().naive_utc() - Duration::minutes(2))
+        .finish();
+    project
+        .create_domain_action()
+        .with_domain_action_type(DomainActionTypes::Communication)
+        .with_scheduled_at(Utc::now().naive_utc() + Duration::minutes(2))
+        .finish();
+    let busy = project
+        .create_domain_action()
+        .with_domain_action_type(DomainActionTypes::Communication)
+        .finish();
+    busy.set_busy(60, conn).unwrap();
+    project
+        .create_domain_action()
+        .with_domain_action_type(DomainActionTypes::SendAutomaticReportEmails)
+        .with_status(DomainActionStatus::RetriesExceeded)
+        .finish();
+
+    let metrics = DomainAction::queue_metrics(conn).unwrap();
+    assert_eq!(metrics.len(), 2);
+
+    let communication = &metrics[0];
+    assert_eq!(communication.domain_action_type, DomainActionTypes::Communication);
+    assert_eq!(communication.pending_count, 3);
+    assert_eq!(communication.due_count, 1);
+    assert_eq!(communication.in_progress_count, 1);
+    assert_eq!(communication.dead_letter_count, 0);
+    assert_eq!(communication.oldest_due_at, Some(due.scheduled_at));
+    assert!(communication.average_start_latency_seconds.is_some());
+
+    let reports = &metrics[1];
+    assert_eq!(reports.domain_action_type, DomainActionTypes::SendAutomaticReportEmails);
+    assert_eq!(reports.pending_count, 0);
+    assert_eq!(reports.dead_letter_count, 1);
+    assert_eq!(reports.oldest_due_at, None);
+    assert_eq!(reports.average_start_latency_seconds, None);
+}
+
 #[test]
 fn has_pending_action() {
     let project = TestProject::new();
@@ -192,6 +381,7 @@ fn set_busy() {
     let updated = DomainAction::find(example.id, conn).unwrap();
 
     assert!(updated.blocked_until > Utc::now().naive_utc());
+    assert!(updated.last_attempted_at.is_some());
 
     let err = updated.set_busy(9999, conn).err().unwrap();
     assert_eq!(err.error_code, ErrorCode::ConcurrencyError);
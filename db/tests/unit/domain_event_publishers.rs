@@ -146,6 +146,7 @@ fn update() {
     let parameters = DomainEventPublisherEditableAttributes {
         webhook_url: Some(new_webhook_url.clone()),
         import_historic_events: Some(false),
+        event_types: None,
     };
     let domain_event_publisher = domain_event_publisher.update(&parameters, connection).unwrap();
 
@@ -216,3 +217,209 @@ fn renew_lock() {
 
     assert!(domain_event_publisher.renew_lock(60, connection).is_ok());
 }
+
+#[test]
+fn create_for_organization() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let webhook = DomainEventPublisher::create_for_organization(
+        organization.id,
+        vec![DomainEventTypes::OrderCompleted],
+        "https://example.com/webhook".to_string(),
+        "whsec_test",
+        "encryption_key",
+    )
+    .unwrap()
+    .commit(connection)
+    .unwrap();
+
+    assert_eq!(webhook.organization_id, Some(organization.id));
+    assert!(webhook.is_signed());
+    assert_ne!(webhook.encrypted_signing_secret, Some("whsec_test".to_string()));
+    assert_eq!(
+        webhook.signing_secret("encryption_key").unwrap(),
+        Some("whsec_test".to_string())
+    );
+    assert_eq!(
+        DomainEvent::find(
+            Tables::DomainEventPublishers,
+            Some(webhook.id),
+            Some(DomainEventTypes::WebhookCreated),
+            connection,
+        )
+        .unwrap()
+        .len(),
+        1
+    );
+
+    // Unsigned publishers have no secret
+    let domain_event_publisher = project.create_domain_event_publisher().finish();
+    assert!(!domain_event_publisher.is_signed());
+    assert_eq!(domain_event_publisher.signing_secret("encryption_key").unwrap(), None);
+}
+
+#[test]
+fn create_for_organization_with_validation_errors() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+
+    let result = DomainEventPublisher::create_for_organization(
+        organization.id,
+        vec![],
+        "not a url".to_string(),
+        "whsec_test",
+        "encryption_key",
+    )
+    .unwrap()
+    .commit(connection);
+    match result.unwrap_err().error_code {
+        ErrorCode::ValidationError { errors } => {
+            assert_eq!(errors["webhook_url"][0].code, "url");
+            assert_eq!(errors["event_types"][0].code, "event_types_empty");
+        }
+        _ => panic!("Expected validation error"),
+    }
+
+    let result = DomainEventPublisher::create_for_organization(
+        organization.id,
+        vec![DomainEventTypes::OrderCompleted],
+        "http://example.com/webhook".to_string(),
+        "whsec_test",
+        "encryption_key",
+    )
+    .unwrap()
+    .commit(connection);
+    match result.unwrap_err().error_code {
+        ErrorCode::ValidationError { errors } => {
+            assert_eq!(errors["webhook_url"][0].code, "https_required");
+        }
+        _ => panic!("Expected validation error"),
+    }
+
+    // Webhooks cannot be used to reach internal services
+    for webhook_url in vec![
+        "https://localhost/webhook",
+        "https://127.0.0.1/webhook",
+        "https://169.254.169.254/latest/meta-data",
+        "https://10.1.2.3/webhook",
+    ] {
+        let result = DomainEventPublisher::create_for_organization(
+            organization.id,
+            vec![DomainEventTypes::OrderCompleted],
+            webhook_url.to_string(),
+            "whsec_test",
+            "encryption_key",
+        )
+        .unwrap()
+        .commit(connection);
+        match result.unwrap_err().error_code {
+            ErrorCode::ValidationError { errors } => {
+                assert_eq!(errors["webhook_url"][0].code, "internal_address");
+            }
+            _ => panic!("Expected validation error"),
+        }
+    }
+
+    let result = DomainEventPublisher::create_for_organization(
+        organization.id,
+        vec![DomainEventTypes::PaymentCreated],
+        "https://example.com/webhook".to_string(),
+        "whsec_test",
+        "encryption_key",
+    )
+    .unwrap()
+    .commit(connection);
+    match result.unwrap_err().error_code {
+        ErrorCode::ValidationError { errors } => {
+            assert!(!errors.contains_key("webhook_url"));
+            assert_eq!(errors["event_types"][0].code, "event_types_unsupported");
+        }
+        _ => panic!("Expected validation error"),
+    }
+}
+
+#[test]
+fn find_for_organization() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let webhook = project
+        .create_domain_event_publisher()
+        .with_organization(&organization)
+        .with_signing_secret("whsec_test")
+        .finish();
+    let webhook2 = project
+        .create_domain_event_publisher()
+        .with_organization(&organization)
+        .with_signing_secret("whsec_test2")
+        .finish();
+    // Integrations set up by an administrator are not listed
+    project
+        .create_domain_event_publisher()
+        .with_organization(&organization)
+        .finish();
+    // Other organizations' webhooks are not listed
+    project
+        .create_domain_event_publisher()
+        .with_signing_secret("whsec_test3")
+        .finish();
+
+    assert_eq!(
+        DomainEventPublisher::find_for_organization(organization.id, connection).unwrap(),
+        vec![webhook, webhook2.clone()]
+    );
+
+    webhook2.delete(connection).unwrap();
+    assert_eq!(
+        DomainEventPublisher::find_for_organization(organization.id, connection)
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn update_signed() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let webhook = project
+        .create_domain_event_publisher()
+        .with_signing_secret("whsec_test")
+        .finish();
+
+    let parameters = DomainEventPublisherEditableAttributes {
+        webhook_url: Some("invalid".to_string()),
+        import_historic_events: None,
+        event_types: Some(vec![DomainEventTypes::PaymentCreated]),
+    };
+    let result = webhook.update(&parameters, connection);
+    match result.unwrap_err().error_code {
+        ErrorCode::ValidationError { errors } => {
+            assert_eq!(errors["webhook_url"][0].code, "url");
+            assert_eq!(errors["event_types"][0].code, "event_types_unsupported");
+        }
+        _ => panic!("Expected validation error"),
+    }
+
+    let parameters = DomainEventPublisherEditableAttributes {
+        webhook_url: Some("https://example.com/updated".to_string()),
+        import_historic_events: None,
+        event_types: Some(vec![DomainEventTypes::OrderRefund]),
+    };
+    let webhook = webhook.update(&parameters, connection).unwrap();
+    assert_eq!(webhook.webhook_url, "https://example.com/updated".to_string());
+    assert_eq!(webhook.event_types, vec![DomainEventTypes::OrderRefund]);
+    assert_eq!(
+        DomainEvent::find(
+            Tables::DomainEventPublishers,
+            Some(webhook.id),
+            Some(DomainEventTypes::WebhookUpdated),
+            connection,
+        )
+        .unwrap()
+        .len(),
+        1
+    );
+}
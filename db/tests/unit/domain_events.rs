@@ -1,6 +1,9 @@
-use chrono::Utc;
+use chrono::{Duration, Utc};
 use db::dev::TestProject;
 use db::prelude::*;
+use db::schema::domain_events;
+use diesel;
+use diesel::prelude::*;
 use serde_json::Value;
 use std::str::FromStr;
 use uuid::Uuid;
@@ -254,3 +257,230 @@ fn find() {
         [domain_event2]
     );
 }
+
+#[test]
+fn organization_id_populated_on_insert() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project.create_event().with_organization(&organization).finish();
+
+    let domain_event = DomainEvent::create(
+        DomainEventTypes::EventUpdated,
+        "Event updated".to_string(),
+        Tables::Events,
+        Some(event.id),
+        None,
+        None,
+    )
+    .commit(connection)
+    .unwrap();
+    assert_eq!(domain_event.organization_id, Some(organization.id));
+
+    let domain_event = DomainEvent::create(
+        DomainEventTypes::OrganizationCreated,
+        "Organization created".to_string(),
+        Tables::Organizations,
+        Some(organization.id),
+        None,
+        None,
+    )
+    .commit(connection)
+    .unwrap();
+    assert_eq!(domain_event.organization_id, Some(organization.id));
+
+    // Records outside of an organization are left unset
+    let user = project.create_user().finish();
+    let domain_event = DomainEvent::create(
+        DomainEventTypes::UserCreated,
+        "User created".to_string(),
+        Tables::Users,
+        Some(user.id),
+        None,
+        None,
+    )
+    .commit(connection)
+    .unwrap();
+    assert_eq!(domain_event.organization_id, None);
+}
+
+#[test]
+fn backpopulate_organization_ids() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project.create_event().with_organization(&organization).finish();
+    let user = project.create_user().finish();
+
+    let domain_event = DomainEvent::create(
+        DomainEventTypes::EventUpdated,
+        "Event updated".to_string(),
+        Tables::Events,
+        Some(event.id),
+        None,
+        None,
+    )
+    .commit(connection)
+    .unwrap();
+    let domain_event2 = DomainEvent::create(
+        DomainEventTypes::UserCreated,
+        "User created".to_string(),
+        Tables::Users,
+        Some(user.id),
+        None,
+        None,
+    )
+    .commit(connection)
+    .unwrap();
+    let domain_event3 = DomainEvent::create(
+        DomainEventTypes::OrganizationCreated,
+        "Organization created".to_string(),
+        Tables::Organizations,
+        Some(organization.id),
+        None,
+        None,
+    )
+    .commit(connection)
+    .unwrap();
+
+    // Events recorded before organizations were set on insert
+    diesel::update(domain_events::table.filter(domain_events::id.eq_any(vec![domain_event.id, domain_event3.id])))
+        .set(domain_events::organization_id.eq(None::<Uuid>))
+        .execute(connection)
+        .unwrap();
+    let after_seq = domain_event.seq - 1;
+
+    assert_eq!(
+        DomainEvent::backpopulate_organization_ids(after_seq, 2, connection).unwrap(),
+        Some(domain_event2.seq)
+    );
+    assert_eq!(
+        DomainEvent::find_by_ids(vec![domain_event.id], connection).unwrap()[0].organization_id,
+        Some(organization.id)
+    );
+    assert_eq!(
+        DomainEvent::find_by_ids(vec![domain_event3.id], connection).unwrap()[0].organization_id,
+        None
+    );
+
+    assert_eq!(
+        DomainEvent::backpopulate_organization_ids(domain_event2.seq, 2, connection).unwrap(),
+        Some(domain_event3.seq)
+    );
+    assert_eq!(
+        DomainEvent::find_by_ids(vec![domain_event3.id], connection).unwrap()[0].organization_id,
+        Some(organization.id)
+    );
+    assert_eq!(
+        DomainEvent::backpopulate_organization_ids(domain_event3.seq, 2, connection).unwrap(),
+        None
+    );
+}
+
+#[test]
+fn find_for_organization_after_seq() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let organization2 = project.create_organization().finish();
+    let event = project.create_event().with_organization(&organization).finish();
+    let settled_at = Utc::now().naive_utc() - Duration::minutes(1);
+
+    let mut new_domain_event = DomainEvent::create(
+        DomainEventTypes::OrganizationCreated,
+        "Organization created".to_string(),
+        Tables::Organizations,
+        Some(organization.id),
+        None,
+        None,
+    );
+    new_domain_event.created_at = Some(settled_at);
+    let domain_event = new_domain_event.commit(connection).unwrap();
+
+    let mut new_domain_event = DomainEvent::create(
+        DomainEventTypes::EventUpdated,
+        "Event updated".to_string(),
+        Tables::Events,
+        Some(event.id),
+        None,
+        None,
+    );
+    new_domain_event.created_at = Some(settled_at);
+    let domain_event2 = new_domain_event.commit(connection).unwrap();
+
+    let mut new_domain_event = DomainEvent::create(
+        DomainEventTypes::OrganizationCreated,
+        "Organization created".to_string(),
+        Tables::Organizations,
+        Some(organization2.id),
+        None,
+        None,
+    );
+    new_domain_event.created_at = Some(settled_at);
+    let other_domain_event = new_domain_event.commit(connection).unwrap();
+
+    // Recent events are held back until they have settled
+    let unsettled_domain_event = DomainEvent::create(
+        DomainEventTypes::EventUpdated,
+        "Event updated".to_string(),
+        Tables::Events,
+        Some(event.id),
+        None,
+        None,
+    )
+    .commit(connection)
+    .unwrap();
+
+    let filter = DomainEventStreamFilter::default();
+    let page = DomainEvent::find_for_organization_after_seq(organization.id, 0, &filter, 10, connection).unwrap();
+    assert_eq!(page.data, vec![domain_event.clone(), domain_event2.clone()]);
+    assert_eq!(page.next_seq, domain_event2.seq);
+    assert!(!page.has_more);
+    assert!(!page.data.contains(&unsettled_domain_event));
+
+    // Paging with the cursor
+    let page = DomainEvent::find_for_organization_after_seq(organization.id, 0, &filter, 1, connection).unwrap();
+    assert_eq!(page.data, vec![domain_event.clone()]);
+    assert_eq!(page.next_seq, domain_event.seq);
+    assert!(page.has_more);
+    let page =
+        DomainEvent::find_for_organization_after_seq(organization.id, page.next_seq, &filter, 1, connection).unwrap();
+    assert_eq!(page.data, vec![domain_event2.clone()]);
+    assert!(!page.has_more);
+    let page =
+        DomainEvent::find_for_organization_after_seq(organization.id, page.next_seq, &filter, 1, connection).unwrap();
+    assert!(page.data.is_empty());
+    assert_eq!(page.next_seq, domain_event2.seq);
+
+    // Filtered by type, table and date
+    let filter = DomainEventStreamFilter {
+        event_types: Some(vec![DomainEventTypes::EventUpdated]),
+        ..Default::default()
+    };
+    let page = DomainEvent::find_for_organization_after_seq(organization.id, 0, &filter, 10, connection).unwrap();
+    assert_eq!(page.data, vec![domain_event2.clone()]);
+
+    let filter = DomainEventStreamFilter {
+        main_table: Some(Tables::Organizations),
+        ..Default::default()
+    };
+    let page = DomainEvent::find_for_organization_after_seq(organization.id, 0, &filter, 10, connection).unwrap();
+    assert_eq!(page.data, vec![domain_event.clone()]);
+
+    let filter = DomainEventStreamFilter {
+        start_utc: Some(settled_at + Duration::seconds(1)),
+        ..Default::default()
+    };
+    let page = DomainEvent::find_for_organization_after_seq(organization.id, 0, &filter, 10, connection).unwrap();
+    assert!(page.data.is_empty());
+
+    let page = DomainEvent::find_for_organization_after_seq(
+        organization2.id,
+        0,
+        &DomainEventStreamFilter::default(),
+        10,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(page.data, vec![other_domain_event]);
+}
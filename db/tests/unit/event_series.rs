@@ -0,0 +1,403 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use db::dev::TestProject;
+use db::models::*;
+use db::utils::dates;
+use db::utils::errors::DatabaseError;
+use db::utils::errors::ErrorCode::ValidationError;
+
+fn whole_seconds(date: NaiveDateTime) -> NaiveDateTime {
+    NaiveDateTime::from_timestamp(date.timestamp(), 0)
+}
+
+#[test]
+fn occurrence_dates() {
+    let template_start = NaiveDate::from_ymd(2020, 1, 31).and_hms(20, 0, 0);
+
+    // Weekly
+    let dates = EventSeries::occurrence_dates(
+        RecurrenceTypes::Weekly,
+        1,
+        Some(template_start + Duration::weeks(3)),
+        &[],
+        template_start,
+    );
+    assert_eq!(
+        dates,
+        vec![
+            template_start + Duration::weeks(1),
+            template_start + Duration::weeks(2),
+            template_start + Duration::weeks(3),
+        ]
+    );
+
+    // Every second week
+    let dates = EventSeries::occurrence_dates(
+        RecurrenceTypes::Weekly,
+        2,
+        Some(template_start + Duration::weeks(5)),
+        &[],
+        template_start,
+    );
+    assert_eq!(
+        dates,
+        vec![template_start + Duration::weeks(2), template_start + Duration::weeks(4)]
+    );
+
+    // Monthly skips months without the 31st
+    let dates = EventSeries::occurrence_dates(
+        RecurrenceTypes::Monthly,
+        1,
+        Some(NaiveDate::from_ymd(2020, 6, 1).and_hms(0, 0, 0)),
+        &[],
+        template_start,
+    );
+    assert_eq!(
+        dates,
+        vec![
+            NaiveDate::from_ymd(2020, 3, 31).and_hms(20, 0, 0),
+            NaiveDate::from_ymd(2020, 5, 31).and_hms(20, 0, 0),
+        ]
+    );
+
+    // Monthly without an end date has no occurrences
+    assert!(EventSeries::occurrence_dates(RecurrenceTypes::Monthly, 1, None, &[], template_start).is_empty());
+
+    // Custom dates are sorted, deduplicated and exclude the template's own start
+    let first = template_start + Duration::days(3);
+    let second = template_start + Duration::days(10);
+    let dates = EventSeries::occurrence_dates(
+        RecurrenceTypes::Custom,
+        1,
+        None,
+        &[second, first, template_start, second],
+        template_start,
+    );
+    assert_eq!(dates, vec![first, second]);
+}
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event_start = whole_seconds(dates::now().add_days(7).finish());
+    let event = project.create_event().with_event_start(event_start).finish();
+
+    let event_series = EventSeries::create(
+        event.organization_id,
+        event.id,
+        "Friday Night Comedy".to_string(),
+        RecurrenceTypes::Weekly,
+        1,
+        Some(event_start + Duration::weeks(4)),
+        vec![],
+    )
+    .commit(None, connection)
+    .unwrap();
+
+    assert_eq!(event_series.template_event_id, event.id);
+    let slug = Slug::primary_slug(event_series.id, Tables::EventSeries, connection).unwrap();
+    assert_eq!(slug.slug, "friday-night-comedy");
+    assert_eq!(slug.slug_type, SlugTypes::EventSeries);
+    let event = Event::find(event.id, connection).unwrap();
+    assert_eq!(event.event_series_id, Some(event_series.id));
+
+    let domain_events = DomainEvent::find(
+        Tables::EventSeries,
+        Some(event_series.id),
+        Some(DomainEventTypes::EventSeriesCreated),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+
+    // Template event can only belong to one series
+    let result = EventSeries::create(
+        event.organization_id,
+        event.id,
+        "Another series".to_string(),
+        RecurrenceTypes::Weekly,
+        1,
+        Some(event_start + Duration::weeks(4)),
+        vec![],
+    )
+    .commit(None, connection);
+    assert_eq!(
+        result,
+        DatabaseError::business_process_error("Template event already belongs to an event series")
+    );
+}
+
+#[test]
+fn commit_with_validation_errors() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event_start = whole_seconds(dates::now().add_days(7).finish());
+    let event = project.create_event().with_event_start(event_start).finish();
+
+    let result = EventSeries::create(
+        event.organization_id,
+        event.id,
+        "Series".to_string(),
+        RecurrenceTypes::Weekly,
+        0,
+        None,
+        vec![],
+    )
+    .commit(None, connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("recurrence_interval"));
+                assert_eq!(
+                    errors["recurrence_interval"][0].code,
+                    "recurrence_interval_must_be_positive"
+                );
+                assert!(errors.contains_key("recurrence_until"));
+                assert_eq!(errors["recurrence_until"][0].code, "required");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    let result = EventSeries::create(
+        event.organization_id,
+        event.id,
+        "Series".to_string(),
+        RecurrenceTypes::Custom,
+        1,
+        None,
+        vec![],
+    )
+    .commit(None, connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("custom_dates"));
+                assert_eq!(errors["custom_dates"][0].code, "required");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    let result = EventSeries::create(
+        event.organization_id,
+        event.id,
+        "Series".to_string(),
+        RecurrenceTypes::Weekly,
+        1,
+        Some(event_start + Duration::weeks(400)),
+        vec![],
+    )
+    .commit(None, connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("recurrence_until"));
+                assert_eq!(errors["recurrence_until"][0].code, "too_many_occurrences");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn generate_occurrences() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let artist = project.create_artist().finish();
+    let event_start = whole_seconds(dates::now().add_days(7).finish());
+    let event = project
+        .create_event()
+        .with_event_start(event_start)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    project
+        .create_event_artist()
+        .with_event(&event)
+        .with_artist(&artist)
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let hold = project
+        .create_hold()
+        .with_ticket_type_id(ticket_type.id)
+        .with_quantity(5)
+        .finish();
+
+    let event_series = EventSeries::create(
+        event.organization_id,
+        event.id,
+        "Weekly show".to_string(),
+        RecurrenceTypes::Weekly,
+        1,
+        Some(event_start + Duration::weeks(2)),
+        vec![],
+    )
+    .commit(None, connection)
+    .unwrap();
+
+    let occurrences = event_series.generate_occurrences(None, connection).unwrap();
+    assert_eq!(occurrences.len(), 2);
+    assert_eq!(event_series.events(connection).unwrap().len(), 3);
+
+    let occurrence = &occurrences[0];
+    let offset = Duration::weeks(1);
+    assert_eq!(occurrence.event_series_id, Some(event_series.id));
+    assert_eq!(occurrence.cloned_from_event_id, Some(event.id));
+    assert_eq!(occurrence.name, event.name);
+    assert_eq!(occurrence.event_start, Some(event_start + offset));
+    assert_eq!(occurrence.event_end, event.event_end.map(|e| e + offset));
+    assert_eq!(occurrence.status, EventStatus::Draft);
+
+    let artists = occurrence.artists(connection).unwrap();
+    assert_eq!(artists.len(), 1);
+    assert_eq!(artists[0].artist.id, artist.id);
+
+    // Pricing tiers are shifted along with the occurrence
+    let new_ticket_type = &occurrence.ticket_types(true, None, connection).unwrap()[0];
+    assert_eq!(new_ticket_type.name, ticket_type.name);
+    assert_eq!(new_ticket_type.start_date, ticket_type.start_date.map(|d| d + offset));
+    let ticket_pricing = ticket_type.valid_ticket_pricing(false, connection).unwrap();
+    let new_ticket_pricing = new_ticket_type.valid_ticket_pricing(false, connection).unwrap();
+    assert_eq!(new_ticket_pricing.len(), ticket_pricing.len());
+    for (pricing, new_pricing) in ticket_pricing.iter().zip(new_ticket_pricing.iter()) {
+        assert_eq!(new_pricing.name, pricing.name);
+        assert_eq!(new_pricing.price_in_cents, pricing.price_in_cents);
+        assert_eq!(new_pricing.start_date, pricing.start_date + offset);
+        assert_eq!(new_pricing.end_date, pricing.end_date + offset);
+    }
+
+    // Holds keep their quantity with a new redemption code
+    let holds = Hold::find_for_event(occurrence.id, false, connection).unwrap();
+    assert_eq!(holds.len(), 1);
+    assert_eq!(holds[0].name, hold.name);
+    assert_eq!(holds[0].ticket_type_id, new_ticket_type.id);
+    assert!(holds[0].redemption_code.is_some());
+    assert_ne!(holds[0].redemption_code, hold.redemption_code);
+    assert_eq!(holds[0].quantity(connection).unwrap().0, 5);
+
+    // Existing occurrences are not generated again
+    assert!(event_series.generate_occurrences(None, connection).unwrap().is_empty());
+
+    // Extending the series only generates the new dates
+    let event_series = event_series
+        .update(
+            EventSeriesEditableAttributes {
+                recurrence_until: Some(Some(event_start + Duration::weeks(3))),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+    let occurrences = event_series.generate_occurrences(None, connection).unwrap();
+    assert_eq!(occurrences.len(), 1);
+    assert_eq!(occurrences[0].event_start, Some(event_start + Duration::weeks(3)));
+}
+
+#[test]
+fn update_future_occurrences() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event_start = whole_seconds(dates::now().add_days(7).finish());
+    let event = project.create_event().with_event_start(event_start).finish();
+    let event_series = EventSeries::create(
+        event.organization_id,
+        event.id,
+        "Weekly show".to_string(),
+        RecurrenceTypes::Weekly,
+        1,
+        Some(event_start + Duration::weeks(2)),
+        vec![],
+    )
+    .commit(None, connection)
+    .unwrap();
+    event_series.generate_occurrences(None, connection).unwrap();
+
+    // Move the template into the past, it should no longer receive changes
+    let event = Event::find(event.id, connection).unwrap();
+    let past_start = whole_seconds(dates::now().add_days(-7).finish());
+    event
+        .update(
+            None,
+            EventEditableAttributes {
+                event_start: Some(past_start),
+                event_end: Some(past_start + Duration::hours(3)),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+
+    let updated_events = event_series
+        .update_future_occurrences(
+            EventEditableAttributes {
+                additional_info: Some(Some("Two drink minimum".to_string())),
+                event_start: Some(event_start + Duration::days(100)),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+    assert_eq!(updated_events.len(), 2);
+    for updated_event in &updated_events {
+        assert_eq!(updated_event.additional_info, Some("Two drink minimum".to_string()));
+        assert_ne!(updated_event.event_start, Some(event_start + Duration::days(100)));
+    }
+
+    let event = Event::find(event.id, connection).unwrap();
+    assert_ne!(event.additional_info, Some("Two drink minimum".to_string()));
+}
+
+#[test]
+fn for_display() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let event_start = whole_seconds(dates::now().add_days(7).finish());
+    let event = project
+        .create_event()
+        .with_venue(&venue)
+        .with_event_start(event_start)
+        .with_status(EventStatus::Published)
+        .with_publish_date(dates::now().add_days(-1).finish())
+        .finish();
+    let event_series = EventSeries::create(
+        event.organization_id,
+        event.id,
+        "Weekly show".to_string(),
+        RecurrenceTypes::Weekly,
+        1,
+        Some(event_start + Duration::weeks(1)),
+        vec![],
+    )
+    .commit(None, connection)
+    .unwrap();
+    let occurrences = event_series.generate_occurrences(None, connection).unwrap();
+    assert_eq!(occurrences.len(), 1);
+
+    // Generated occurrences are drafts and not listed until published
+    let display_event_series = event_series.for_display(connection).unwrap();
+    assert_eq!(display_event_series.name, event_series.name);
+    assert_eq!(display_event_series.slug, Some("weekly-show".to_string()));
+    assert_eq!(display_event_series.upcoming_dates.len(), 1);
+    assert_eq!(display_event_series.upcoming_dates[0].event_id, event.id);
+
+    occurrences[0].publish(None, connection).unwrap();
+    let display_event_series = event_series.for_display(connection).unwrap();
+    assert_eq!(
+        display_event_series
+            .upcoming_dates
+            .iter()
+            .map(|d| d.event_id)
+            .collect::<Vec<_>>(),
+        vec![event.id, occurrences[0].id]
+    );
+    let event_slug = Slug::primary_slug(event.id, Tables::Events, connection).unwrap();
+    assert_eq!(display_event_series.upcoming_dates[0].slug, Some(event_slug.slug));
+}
@@ -658,7 +658,8 @@ fn create_next_transfer_drip_action() {
         payload,
         ProcessTransferDripPayload {
             event_id: event.id,
-            source_or_destination: SourceOrDestination::Destination
+            source_or_destination: SourceOrDestination::Destination,
+            expiry_reminder: false
         }
     );
     // Drip day is 1 days from the event start
@@ -753,7 +754,8 @@ fn create_next_transfer_drip_action_staging() {
         payload,
         ProcessTransferDripPayload {
             event_id: event.id,
-            source_or_destination: SourceOrDestination::Destination
+            source_or_destination: SourceOrDestination::Destination,
+            expiry_reminder: false
         }
     );
     // Drip day is 1 minutes from the event start
@@ -1875,6 +1877,78 @@ fn guest_list() {
     assert_eq!(1, guest_list.0.len());
 }
 
+#[test]
+fn update_currency() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_currency("EUR").finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    assert_eq!(event.currency, "EUR".to_string());
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let ticket_pricing = ticket_type.current_ticket_pricing(false, connection).unwrap();
+    assert_eq!(ticket_pricing.currency, "EUR".to_string());
+
+    // Invalid currency
+    let result = event.update(
+        None,
+        EventEditableAttributes {
+            currency: Some("euro".to_string()),
+            ..Default::default()
+        },
+        connection,
+    );
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("event.currency"));
+                assert_eq!(errors["event.currency"][0].code, "currency");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    // Currency changes are carried through to pricing while there are no sales
+    let event = event
+        .update(
+            None,
+            EventEditableAttributes {
+                currency: Some("GBP".to_string()),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+    assert_eq!(event.currency, "GBP".to_string());
+    let ticket_pricing = ticket_type.current_ticket_pricing(false, connection).unwrap();
+    assert_eq!(ticket_pricing.currency, "GBP".to_string());
+
+    project.create_order().for_event(&event).quantity(1).is_paid().finish();
+    let result = event.update(
+        None,
+        EventEditableAttributes {
+            currency: Some("USD".to_string()),
+            ..Default::default()
+        },
+        connection,
+    );
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("event.currency"));
+                assert_eq!(errors["event.currency"][0].code, "cannot_change_currency_with_sales");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
 #[test]
 fn update_fails_to_move_event_into_past() {
     let project = TestProject::new();
@@ -4110,6 +4184,7 @@ fn current_ticket_pricing_range() {
             false,
             None,
             None,
+            None,
             connection,
         )
         .unwrap();
@@ -4128,6 +4203,7 @@ fn current_ticket_pricing_range() {
             false,
             None,
             None,
+            None,
             connection,
         )
         .unwrap();
@@ -4145,6 +4221,7 @@ fn current_ticket_pricing_range() {
             false,
             None,
             None,
+            None,
             connection,
         )
         .unwrap();
@@ -4163,6 +4240,7 @@ fn current_ticket_pricing_range() {
             true,
             None,
             None,
+            None,
             connection,
         )
         .unwrap();
@@ -4177,6 +4255,7 @@ fn current_ticket_pricing_range() {
             true,
             None,
             None,
+            None,
             connection,
         )
         .unwrap();
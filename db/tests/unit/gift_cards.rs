@@ -0,0 +1,444 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use db::dev::TestProject;
+use db::prelude::*;
+use db::utils::errors::ErrorCode::ValidationError;
+
+#[test]
+fn create() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let user = project.create_user().finish();
+
+    let gift_card = GiftCard::create(
+        organization.id,
+        None,
+        5000,
+        organization.currency.clone(),
+        None,
+        user.id,
+    )
+    .commit(connection)
+    .unwrap();
+    assert_eq!(gift_card.balance_in_cents, 5000);
+    assert_eq!(gift_card.initial_balance_in_cents, 5000);
+    assert_eq!(gift_card.code.len(), 16);
+    assert_eq!(gift_card.code, gift_card.code.to_uppercase());
+    assert!(gift_card.is_redeemable());
+
+    let ledger_entries = gift_card.ledger_entries(connection).unwrap();
+    assert_eq!(ledger_entries.len(), 1);
+    assert_eq!(ledger_entries[0].entry_type, StoredValueLedgerEntryTypes::Issued);
+    assert_eq!(ledger_entries[0].amount_in_cents, 5000);
+    assert_eq!(ledger_entries[0].balance_after_in_cents, 5000);
+
+    let domain_events = DomainEvent::find(
+        Tables::GiftCards,
+        Some(gift_card.id),
+        Some(DomainEventTypes::GiftCardCreated),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(1, domain_events.len());
+
+    // Codes are case insensitive
+    let gift_card = GiftCard::create(
+        organization.id,
+        Some("holiday2020".to_string()),
+        1000,
+        organization.currency.clone(),
+        None,
+        user.id,
+    )
+    .commit(connection)
+    .unwrap();
+    assert_eq!(gift_card.code, "HOLIDAY2020");
+    assert_eq!(GiftCard::find_by_code("Holiday2020", connection).unwrap(), gift_card);
+}
+
+#[test]
+fn create_with_validation_errors() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let user = project.create_user().finish();
+    GiftCard::create(
+        organization.id,
+        Some("HOLIDAY2020".to_string()),
+        1000,
+        organization.currency.clone(),
+        None,
+        user.id,
+    )
+    .commit(connection)
+    .unwrap();
+
+    let result = GiftCard::create(
+        organization.id,
+        Some("holiday2020".to_string()),
+        0,
+        organization.currency.clone(),
+        Some(Utc::now().naive_utc() - Duration::days(1)),
+        user.id,
+    )
+    .commit(connection);
+    match result {
+        Ok(_) => {
+            panic!("Expected validation error");
+        }
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("initial_balance_in_cents"));
+                assert_eq!(
+                    errors["code"][0].message.clone().unwrap().into_owned(),
+                    "Gift card code is already in use"
+                );
+                assert!(errors.contains_key("expires_at"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn add_gift_card_payment() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let user = project.create_user().finish();
+    let mut order = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(2)
+        .finish();
+    let total = order.calculate_total(connection).unwrap();
+    let gift_card = GiftCard::create(
+        organization.id,
+        None,
+        total / 2,
+        organization.currency.clone(),
+        None,
+        user.id,
+    )
+    .commit(connection)
+    .unwrap();
+    let gift_card2 = GiftCard::create(
+        organization.id,
+        None,
+        total,
+        organization.currency.clone(),
+        None,
+        user.id,
+    )
+    .commit(connection)
+    .unwrap();
+
+    // Partial redemption leaves the remainder due
+    let payment = order.add_gift_card_payment(&gift_card, user.id, connection).unwrap();
+    assert_eq!(payment.amount, total / 2);
+    assert_eq!(payment.payment_method, PaymentMethods::GiftCard);
+    assert_eq!(order.status, OrderStatus::Draft);
+    assert_eq!(order.amount_due(connection).unwrap(), total - total / 2);
+    let gift_card = GiftCard::find(gift_card.id, connection).unwrap();
+    assert_eq!(gift_card.balance_in_cents, 0);
+    assert!(!gift_card.is_redeemable());
+    assert!(order.add_gift_card_payment(&gift_card, user.id, connection).is_err());
+
+    // Second card covers what is left and keeps its remaining balance
+    let payment2 = order.add_gift_card_payment(&gift_card2, user.id, connection).unwrap();
+    assert_eq!(payment2.amount, total - total / 2);
+    assert_eq!(order.status, OrderStatus::Paid);
+    assert_eq!(order.amount_due(connection).unwrap(), 0);
+    let gift_card2 = GiftCard::find(gift_card2.id, connection).unwrap();
+    assert_eq!(gift_card2.balance_in_cents, total / 2);
+
+    let ledger_entries = gift_card2.ledger_entries(connection).unwrap();
+    assert_eq!(ledger_entries.len(), 2);
+    assert_eq!(ledger_entries[1].entry_type, StoredValueLedgerEntryTypes::Redeemed);
+    assert_eq!(ledger_entries[1].amount_in_cents, -payment2.amount);
+    assert_eq!(ledger_entries[1].balance_after_in_cents, total / 2);
+    assert_eq!(ledger_entries[1].order_id, Some(order.id));
+    assert_eq!(ledger_entries[1].payment_id, Some(payment2.id));
+    assert_eq!(
+        StoredValueLedgerEntry::find_for_order(order.id, connection)
+            .unwrap()
+            .len(),
+        2
+    );
+
+    // Gift cards are only valid for the issuing organization's events
+    let other_event = project.create_event().with_ticket_pricing().finish();
+    let mut other_order = project.create_order().for_event(&other_event).for_user(&user).finish();
+    let result = other_order.add_gift_card_payment(&gift_card2, user.id, connection);
+    match result {
+        Ok(_) => {
+            panic!("Expected validation error");
+        }
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert_eq!(
+                    errors["gift_card"][0].message.clone().unwrap().into_owned(),
+                    "Gift card is not valid for this event"
+                );
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn deactivate() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let user = project.create_user().finish();
+    let gift_card = GiftCard::create(
+        organization.id,
+        None,
+        1000,
+        organization.currency.clone(),
+        None,
+        user.id,
+    )
+    .commit(connection)
+    .unwrap();
+
+    let gift_card = gift_card.deactivate(user.id, connection).unwrap();
+    assert!(gift_card.deactivated_at.is_some());
+    assert_eq!(gift_card.balance_in_cents, 0);
+    let ledger_entries = gift_card.ledger_entries(connection).unwrap();
+    assert_eq!(ledger_entries.len(), 2);
+    assert_eq!(ledger_entries[1].entry_type, StoredValueLedgerEntryTypes::Deactivated);
+    assert_eq!(ledger_entries[1].amount_in_cents, -1000);
+    assert!(gift_card.deactivate(user.id, connection).is_err());
+
+    let mut order = project.create_order().for_event(&event).for_user(&user).finish();
+    assert!(order.add_gift_card_payment(&gift_card, user.id, connection).is_err());
+}
+
+#[test]
+fn refund_to_stored_value() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let user = project.create_user().finish();
+    let mut order = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(2)
+        .finish();
+    let total = order.calculate_total(connection).unwrap();
+    let gift_card = GiftCard::create(
+        organization.id,
+        None,
+        total,
+        organization.currency.clone(),
+        None,
+        user.id,
+    )
+    .commit(connection)
+    .unwrap();
+    let payment = order.add_gift_card_payment(&gift_card, user.id, connection).unwrap();
+
+    let items = order.items(connection).unwrap();
+    let order_item = items.iter().find(|i| i.item_type == OrderItemTypes::Tickets).unwrap();
+    let ticket = &TicketInstance::find_for_order_item(order_item.id, connection).unwrap()[0];
+    let refund_items = vec![RefundItemRequest {
+        order_item_id: order_item.id,
+        ticket_instance_id: Some(ticket.id),
+    }];
+    let (refund, amount) = order.refund(&refund_items, user.id, None, false, connection).unwrap();
+
+    let ledger_entry = payment
+        .refund_to_stored_value(&refund, amount, user.id, connection)
+        .unwrap();
+    assert_eq!(ledger_entry.entry_type, StoredValueLedgerEntryTypes::Refunded);
+    assert_eq!(ledger_entry.amount_in_cents, amount);
+    assert_eq!(ledger_entry.refund_id, Some(refund.id));
+    assert_eq!(
+        GiftCard::find(gift_card.id, connection).unwrap().balance_in_cents,
+        amount
+    );
+
+    // Only stored value payments can be refunded to a balance
+    let mut other_order = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .is_paid()
+        .finish();
+    let other_payment = other_order.payments(connection).unwrap().remove(0);
+    assert!(other_payment
+        .refund_to_stored_value(&refund, 100, user.id, connection)
+        .is_err());
+    assert!(other_order
+        .add_gift_card_payment(&gift_card, user.id, connection)
+        .is_err());
+}
+
+#[test]
+fn return_stored_value() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let user = project.create_user().finish();
+    let mut order = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(2)
+        .finish();
+    let total = order.calculate_total(connection).unwrap();
+    let gift_card = GiftCard::create(
+        organization.id,
+        None,
+        total / 2,
+        organization.currency.clone(),
+        None,
+        user.id,
+    )
+    .commit(connection)
+    .unwrap();
+
+    let payment = order.add_gift_card_payment(&gift_card, user.id, connection).unwrap();
+    assert_eq!(order.status, OrderStatus::Draft);
+    let domain_action = DomainAction::upcoming_domain_action(
+        Some(Tables::Orders),
+        Some(order.id),
+        DomainActionTypes::ReturnStoredValue,
+        connection,
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(
+        Some(domain_action.scheduled_at.timestamp()),
+        order.expires_at.map(|e| e.timestamp())
+    );
+
+    // Unpaid orders give the value back to the card
+    let ledger_entries = order.return_stored_value(None, connection).unwrap();
+    assert_eq!(ledger_entries.len(), 1);
+    assert_eq!(ledger_entries[0].entry_type, StoredValueLedgerEntryTypes::Returned);
+    assert_eq!(ledger_entries[0].amount_in_cents, payment.amount);
+    assert_eq!(ledger_entries[0].payment_id, Some(payment.id));
+    assert_eq!(
+        GiftCard::find(gift_card.id, connection).unwrap().balance_in_cents,
+        total / 2
+    );
+    assert_eq!(
+        Payment::find(payment.id, connection).unwrap().status,
+        PaymentStatus::Cancelled
+    );
+    assert_eq!(order.amount_due(connection).unwrap(), total);
+    assert!(order.return_stored_value(None, connection).unwrap().is_empty());
+
+    // Paid orders keep it
+    let gift_card2 = GiftCard::create(
+        organization.id,
+        None,
+        total,
+        organization.currency.clone(),
+        None,
+        user.id,
+    )
+    .commit(connection)
+    .unwrap();
+    order.add_gift_card_payment(&gift_card2, user.id, connection).unwrap();
+    assert_eq!(order.status, OrderStatus::Paid);
+    assert!(order.return_stored_value(None, connection).is_err());
+}
+
+#[test]
+fn return_stored_value_when_cart_changes() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let user = project.create_user().finish();
+    let mut order = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(2)
+        .finish();
+    let total = order.calculate_total(connection).unwrap();
+    let gift_card = GiftCard::create(
+        organization.id,
+        None,
+        total / 4,
+        organization.currency.clone(),
+        None,
+        user.id,
+    )
+    .commit(connection)
+    .unwrap();
+
+    // Changing the cart gives the value back so it is checked again against the new items
+    let payment = order.add_gift_card_payment(&gift_card, user.id, connection).unwrap();
+    assert_eq!(GiftCard::find(gift_card.id, connection).unwrap().balance_in_cents, 0);
+    order
+        .update_quantities(
+            user.id,
+            &[UpdateOrderItem {
+                ticket_type_id: ticket_type.id,
+                quantity: 1,
+                redemption_code: None,
+            }],
+            false,
+            true,
+            connection,
+        )
+        .unwrap();
+    assert_eq!(
+        Payment::find(payment.id, connection).unwrap().status,
+        PaymentStatus::Cancelled
+    );
+    assert_eq!(
+        GiftCard::find(gift_card.id, connection).unwrap().balance_in_cents,
+        total / 4
+    );
+    assert_eq!(
+        order.amount_due(connection).unwrap(),
+        order.calculate_total(connection).unwrap()
+    );
+
+    // Clearing the cart does not strand the balance
+    let payment = order.add_gift_card_payment(&gift_card, user.id, connection).unwrap();
+    order.clear_cart(user.id, connection).unwrap();
+    assert_eq!(
+        Payment::find(payment.id, connection).unwrap().status,
+        PaymentStatus::Cancelled
+    );
+    assert_eq!(
+        GiftCard::find(gift_card.id, connection).unwrap().balance_in_cents,
+        total / 4
+    );
+}
@@ -0,0 +1,368 @@
+use db::dev::TestProject;
+use db::models::*;
+use db::utils::errors::DatabaseError;
+use uuid::Uuid;
+
+#[test]
+fn add_tickets() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let event2 = project.create_event().with_ticket_pricing().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(2)
+        .is_paid()
+        .finish();
+    project
+        .create_order()
+        .for_event(&event2)
+        .for_user(&user)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let ticket_type2 = &event2.ticket_types(true, None, connection).unwrap()[0];
+    let wallet = user.default_wallet(connection).unwrap();
+
+    let mut listing = Listing::create("Listing".to_string(), user.id, 100)
+        .commit(connection)
+        .unwrap();
+    assert_eq!(listing.event_id, None);
+    let tickets = listing
+        .add_tickets(Some(user.id), wallet.id, ticket_type.id, 2, connection)
+        .unwrap();
+    assert_eq!(tickets.len(), 2);
+    assert_eq!(listing.event_id, Some(event.id));
+    assert_eq!(listing.tickets(connection).unwrap().len(), 2);
+
+    // Tickets for another event cannot be added to the listing
+    let result = listing.add_tickets(Some(user.id), wallet.id, ticket_type2.id, 1, connection);
+    assert_eq!(
+        result,
+        DatabaseError::validation_error("ticket_type_id", "Listings can only contain tickets for a single event")
+    );
+}
+
+#[test]
+fn validate_asking_price() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let order = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(2)
+        .is_paid()
+        .finish();
+    let face_value_in_cents = order
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .find(|i| i.item_type == OrderItemTypes::Tickets)
+        .unwrap()
+        .unit_price_in_cents;
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let ticket_type = ticket_type
+        .update(
+            TicketTypeEditableAttributes {
+                resale_price_cap_percent: Some(Some(110)),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+    assert_eq!(ticket_type.resale_price_cap_percent, Some(110));
+    let wallet = user.default_wallet(connection).unwrap();
+
+    // Asking price above the cap
+    let mut listing = Listing::create("Listing".to_string(), user.id, face_value_in_cents * 2)
+        .commit(connection)
+        .unwrap();
+    let result = listing.add_tickets(Some(user.id), wallet.id, ticket_type.id, 1, connection);
+    assert_eq!(
+        result,
+        DatabaseError::validation_error(
+            "asking_price_in_cents",
+            "Asking price exceeds the resale price cap set by the organizer"
+        )
+    );
+
+    // Asking price at the cap
+    let mut listing = Listing::create("Listing".to_string(), user.id, face_value_in_cents * 110 / 100)
+        .commit(connection)
+        .unwrap();
+    assert!(listing
+        .add_tickets(Some(user.id), wallet.id, ticket_type.id, 1, connection)
+        .is_ok());
+    assert!(listing.validate_asking_price(connection).is_ok());
+}
+
+#[test]
+fn publish() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let wallet = user.default_wallet(connection).unwrap();
+
+    // Listings without tickets cannot be published
+    let listing = Listing::create("Listing".to_string(), user.id, 100)
+        .commit(connection)
+        .unwrap();
+    assert_eq!(
+        listing.publish(Some(user.id), connection),
+        DatabaseError::business_process_error("Cannot publish a listing without tickets")
+    );
+
+    let mut listing = Listing::create("Listing".to_string(), user.id, 100)
+        .commit(connection)
+        .unwrap();
+    listing
+        .add_tickets(Some(user.id), wallet.id, ticket_type.id, 1, connection)
+        .unwrap();
+    assert!(!listing.is_available(None, connection).unwrap());
+    let listing = listing.publish(Some(user.id), connection).unwrap();
+    assert_eq!(listing.status, ListingStatus::Published);
+    assert!(listing.is_available(None, connection).unwrap());
+
+    let listings = Listing::find_published_for_event(event.id, connection).unwrap();
+    assert_eq!(
+        listings,
+        vec![DisplayListing {
+            id: listing.id,
+            title: "Listing".to_string(),
+            event_id: Some(event.id),
+            asking_price_in_cents: 100,
+            quantity: 1,
+        }]
+    );
+
+    let domain_events = DomainEvent::find(
+        Tables::Listings,
+        Some(listing.id),
+        Some(DomainEventTypes::ListingPublished),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(1, domain_events.len());
+}
+
+#[test]
+fn cancel() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let wallet = user.default_wallet(connection).unwrap();
+    let mut listing = Listing::create("Listing".to_string(), user.id, 100)
+        .commit(connection)
+        .unwrap();
+    let tickets = listing
+        .add_tickets(Some(user.id), wallet.id, ticket_type.id, 1, connection)
+        .unwrap();
+    let listing = listing.publish(Some(user.id), connection).unwrap();
+
+    // Listed tickets cannot be transferred
+    let result = TicketInstance::create_transfer(
+        &user,
+        &[tickets[0].id],
+        Some("example@tari.com"),
+        Some(TransferMessageType::Email),
+        false,
+        connection,
+    );
+    assert_eq!(
+        result,
+        DatabaseError::business_process_error("Tickets listed for resale cannot be transferred")
+    );
+
+    let listing = listing.cancel(Some(user.id), connection).unwrap();
+    assert_eq!(listing.status, ListingStatus::Cancelled);
+    assert!(listing.tickets(connection).unwrap().is_empty());
+    assert_eq!(
+        TicketInstance::find(tickets[0].id, connection).unwrap().listing_id,
+        None
+    );
+    assert!(Listing::find_published_for_event(event.id, connection)
+        .unwrap()
+        .is_empty());
+    assert_eq!(
+        listing.cancel(Some(user.id), connection),
+        DatabaseError::business_process_error("Listing can no longer be cancelled")
+    );
+}
+
+#[test]
+fn purchase() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_resale_fee(10f32).finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let seller = project.create_user().finish();
+    let buyer = project.create_user().finish();
+    let other_buyer = project.create_user().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&seller)
+        .quantity(2)
+        .is_paid()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let wallet = seller.default_wallet(connection).unwrap();
+    let mut listing = Listing::create("Listing".to_string(), seller.id, 1000)
+        .commit(connection)
+        .unwrap();
+    let tickets = listing
+        .add_tickets(Some(seller.id), wallet.id, ticket_type.id, 2, connection)
+        .unwrap();
+    let ticket_ids: Vec<Uuid> = tickets.iter().map(|t| t.id).collect();
+    let listing = listing.publish(Some(seller.id), connection).unwrap();
+
+    // Sellers cannot purchase their own listing
+    let mut seller_cart = Order::find_or_create_cart(&seller, connection).unwrap();
+    assert_eq!(
+        seller_cart.add_listing(seller.id, listing.id, connection),
+        DatabaseError::validation_error("listing_id", "You cannot purchase your own listing")
+    );
+
+    let mut cart = Order::find_or_create_cart(&buyer, connection).unwrap();
+    cart.add_listing(buyer.id, listing.id, connection).unwrap();
+    let items = cart.items(connection).unwrap();
+    let resale_item = items
+        .iter()
+        .find(|i| i.item_type == OrderItemTypes::ResaleTickets)
+        .unwrap();
+    assert_eq!(resale_item.listing_id, Some(listing.id));
+    assert_eq!(resale_item.quantity, 2);
+    assert_eq!(resale_item.unit_price_in_cents, 1000);
+    assert!(cart.items_valid_for_purchase(connection).unwrap());
+
+    // Listing is claimed by the buyer's cart
+    assert!(!listing.is_available(None, connection).unwrap());
+    assert!(listing.is_available(Some(cart.id), connection).unwrap());
+    assert!(Listing::find_published_for_event(event.id, connection)
+        .unwrap()
+        .is_empty());
+    let mut other_cart = Order::find_or_create_cart(&other_buyer, connection).unwrap();
+    assert_eq!(
+        other_cart.add_listing(other_buyer.id, listing.id, connection),
+        DatabaseError::validation_error("listing_id", "Listing is no longer available")
+    );
+
+    let total = cart.calculate_total(connection).unwrap();
+    assert_eq!(total, 2000);
+    cart.add_external_payment(
+        Some("Test".to_string()),
+        ExternalPaymentType::CreditCard,
+        buyer.id,
+        total,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(cart.status, OrderStatus::Paid);
+
+    let listing = Listing::find(listing.id, connection).unwrap();
+    assert_eq!(listing.status, ListingStatus::Sold);
+    assert_eq!(listing.buyer_user_id, Some(buyer.id));
+    assert_eq!(listing.order_id, Some(cart.id));
+    assert_eq!(listing.resale_fee_in_cents, Some(200));
+    assert_eq!(listing.payout_in_cents, Some(1800));
+    assert!(listing.sold_at.is_some());
+
+    let mut buyer_ticket_ids: Vec<Uuid> = TicketInstance::find_for_user(buyer.id, connection)
+        .unwrap()
+        .iter()
+        .map(|t| t.id)
+        .collect();
+    buyer_ticket_ids.sort();
+    let mut expected_ticket_ids = ticket_ids.clone();
+    expected_ticket_ids.sort();
+    assert_eq!(buyer_ticket_ids, expected_ticket_ids);
+    assert!(TicketInstance::find_for_user(seller.id, connection).unwrap().is_empty());
+
+    let domain_events = DomainEvent::find(
+        Tables::Listings,
+        Some(listing.id),
+        Some(DomainEventTypes::ListingSold),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(1, domain_events.len());
+
+    // Resale items cannot be refunded
+    let resale_item = cart
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .find(|i| i.item_type == OrderItemTypes::ResaleTickets)
+        .unwrap();
+    let refund_items = vec![RefundItemRequest {
+        order_item_id: resale_item.id,
+        ticket_instance_id: None,
+    }];
+    assert_eq!(
+        cart.refund(&refund_items, buyer.id, None, false, connection),
+        DatabaseError::business_process_error("Resale order items can not be refunded")
+    );
+}
+
+#[test]
+fn clear_cart() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let seller = project.create_user().finish();
+    let buyer = project.create_user().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&seller)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let wallet = seller.default_wallet(connection).unwrap();
+    let mut listing = Listing::create("Listing".to_string(), seller.id, 1000)
+        .commit(connection)
+        .unwrap();
+    listing
+        .add_tickets(Some(seller.id), wallet.id, ticket_type.id, 1, connection)
+        .unwrap();
+    let listing = listing.publish(Some(seller.id), connection).unwrap();
+
+    let mut cart = Order::find_or_create_cart(&buyer, connection).unwrap();
+    cart.add_listing(buyer.id, listing.id, connection).unwrap();
+    assert!(!listing.is_available(None, connection).unwrap());
+
+    cart.clear_cart(buyer.id, connection).unwrap();
+    assert!(cart.items(connection).unwrap().is_empty());
+    assert!(listing.is_available(None, connection).unwrap());
+}
@@ -1,13 +1,17 @@
+pub mod account_credits;
 pub mod activities;
 pub mod announcement_engagements;
 pub mod announcements;
+pub mod api_keys;
 pub mod artists;
 pub mod assets;
 pub mod broadcasts;
+pub mod bundles;
 pub mod codes;
 pub mod collection_items;
 pub mod collections;
 pub mod communication;
+pub mod communication_templates;
 pub mod comps;
 pub mod concerns;
 pub mod domain_actions;
@@ -16,24 +20,31 @@ pub mod domain_events;
 pub mod event_artists;
 pub mod event_interest;
 pub mod event_report_subscribers;
+pub mod event_series;
 pub mod event_users;
 pub mod events;
 pub mod external_logins;
 pub mod fee_schedule_ranges;
 pub mod fee_schedules;
 pub mod genres;
+pub mod gift_cards;
 pub mod global;
 pub mod holds;
+pub mod listings;
 pub mod notes;
 pub mod order_items;
 pub mod orders;
 pub mod organization_interactions;
 pub mod organization_invites;
+pub mod organization_roles;
+pub mod organization_sso_configurations;
 pub mod organization_users;
 pub mod organization_venues;
 pub mod organizations;
 pub mod paging;
 pub mod payment_methods;
+pub mod payment_plans;
+pub mod payment_provider_events;
 pub mod payments;
 pub mod push_notification_tokens;
 pub mod refund_items;
@@ -41,12 +52,17 @@ pub mod refunded_tickets;
 pub mod refunds;
 pub mod regions;
 pub mod reports;
+pub mod scanner_manifests;
 pub mod services;
 pub mod settlement_adjustments;
 pub mod settlement_entries;
 pub mod settlements;
 pub mod slugs;
+pub mod sso_login_requests;
+pub mod stage_seats;
+pub mod stage_sections;
 pub mod stages;
+pub mod tax_rules;
 pub mod temporary_users;
 pub mod ticket_instances;
 pub mod ticket_pricing;
@@ -54,5 +70,11 @@ pub mod ticket_type_codes;
 pub mod ticket_types;
 pub mod transfer_tickets;
 pub mod transfers;
+pub mod user_login_throttles;
+pub mod user_recovery_codes;
+pub mod user_sessions;
+pub mod user_totp_credentials;
 pub mod users;
 pub mod venues;
+pub mod waitlist_entries;
+pub mod webhook_deliveries;
@@ -130,6 +130,7 @@ fn valid_for_duplicating() {
             false,
             None,
             None,
+            None,
             connection,
         )
         .unwrap();
@@ -1505,6 +1506,197 @@ fn update_fees() {
         .is_none());
 }
 
+#[test]
+fn update_fees_and_discounts_with_taxes() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_event_fee().with_fees().finish();
+    let venue = project.create_venue().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_venue(&venue)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    let sales_tax = TaxRule::create("Sales Tax".to_string(), Some(venue.id), None, 10.0, false, false)
+        .commit(connection)
+        .unwrap();
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 2,
+            redemption_code: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+
+    // Exclusive tax is only charged on the ticket price
+    let items = cart.items(connection).unwrap();
+    let order_item = items.iter().find(|i| i.ticket_type_id == Some(ticket_type.id)).unwrap();
+    let fee_item = order_item.find_fee_item(connection).unwrap().unwrap();
+    let event_fee_item = items.iter().find(|i| i.item_type == OrderItemTypes::EventFees).unwrap();
+    let tax_items = order_item.find_tax_items(connection).unwrap();
+    assert_eq!(tax_items.len(), 1);
+    let tax_item = &tax_items[0];
+    assert_eq!(tax_item.tax_rule_id, Some(sales_tax.id));
+    assert_eq!(tax_item.quantity, 2);
+    assert_eq!(tax_item.tax_in_cents, sales_tax.tax_for(order_item.unit_price_in_cents));
+    assert_eq!(tax_item.unit_price_in_cents, tax_item.tax_in_cents);
+    assert_eq!(tax_item.description(connection).unwrap(), "Sales Tax".to_string());
+    assert!(fee_item.find_tax_items(connection).unwrap().is_empty());
+    assert!(event_fee_item.find_tax_items(connection).unwrap().is_empty());
+    assert_eq!(
+        cart.calculate_total(connection).unwrap(),
+        2 * (order_item.unit_price_in_cents + fee_item.unit_price_in_cents + tax_item.unit_price_in_cents)
+            + event_fee_item.unit_price_in_cents
+    );
+
+    // Applying the rule to fees taxes both the per unit and per event fees
+    sales_tax
+        .update(
+            TaxRuleEditableAttributes {
+                applies_to_fees: Some(true),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+    cart.update_fees_and_discounts(connection).unwrap();
+    let items = cart.items(connection).unwrap();
+    let event_fee_item = items.iter().find(|i| i.item_type == OrderItemTypes::EventFees).unwrap();
+    let fee_tax_items = fee_item.find_tax_items(connection).unwrap();
+    assert_eq!(fee_tax_items.len(), 1);
+    assert_eq!(fee_tax_items[0].quantity, 2);
+    assert_eq!(
+        fee_tax_items[0].tax_in_cents,
+        sales_tax.tax_for(fee_item.unit_price_in_cents)
+    );
+    let event_fee_tax_items = event_fee_item.find_tax_items(connection).unwrap();
+    assert_eq!(event_fee_tax_items.len(), 1);
+    assert_eq!(
+        event_fee_tax_items[0].tax_in_cents,
+        sales_tax.tax_for(event_fee_item.unit_price_in_cents)
+    );
+
+    // Inclusive taxes are recorded but do not change the total
+    sales_tax.destroy(connection).unwrap();
+    let vat = TaxRule::create("VAT".to_string(), Some(venue.id), None, 20.0, false, true)
+        .commit(connection)
+        .unwrap();
+    cart.update_fees_and_discounts(connection).unwrap();
+    let items = cart.items(connection).unwrap();
+    let event_fee_item = items.iter().find(|i| i.item_type == OrderItemTypes::EventFees).unwrap();
+    let tax_items: Vec<&OrderItem> = items.iter().filter(|i| i.item_type == OrderItemTypes::Tax).collect();
+    assert_eq!(tax_items.len(), 1);
+    let tax_item = tax_items[0];
+    assert_eq!(tax_item.parent_id, Some(order_item.id));
+    assert_eq!(tax_item.tax_rule_id, Some(vat.id));
+    assert_eq!(tax_item.tax_in_cents, vat.tax_for(order_item.unit_price_in_cents));
+    assert_eq!(tax_item.unit_price_in_cents, 0);
+    assert_eq!(
+        cart.calculate_total(connection).unwrap(),
+        2 * (order_item.unit_price_in_cents + fee_item.unit_price_in_cents) + event_fee_item.unit_price_in_cents
+    );
+
+    // Removing the tickets removes their taxes
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 0,
+            redemption_code: None,
+        }],
+        false,
+        true,
+        connection,
+    )
+    .unwrap();
+    assert!(cart
+        .items(connection)
+        .unwrap()
+        .iter()
+        .find(|i| i.item_type == OrderItemTypes::Tax)
+        .is_none());
+}
+
+#[test]
+fn refund_with_taxes() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_fees().finish();
+    let venue = project.create_venue().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_venue(&venue)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    TaxRule::create("Sales Tax".to_string(), Some(venue.id), None, 10.0, true, false)
+        .commit(connection)
+        .unwrap();
+    let user = project.create_user().finish();
+    let mut order = project
+        .create_order()
+        .for_event(&event)
+        .for_tickets(ticket_type.id)
+        .quantity(2)
+        .is_paid()
+        .for_user(&user)
+        .finish();
+    let items = order.items(connection).unwrap();
+    let order_item = items.iter().find(|i| i.ticket_type_id == Some(ticket_type.id)).unwrap();
+    let fee_item = order_item.find_fee_item(connection).unwrap().unwrap();
+    let tax_item = order_item.find_tax_items(connection).unwrap().remove(0);
+    let fee_tax_item = fee_item.find_tax_items(connection).unwrap().remove(0);
+    let ticket = &TicketInstance::find_for_order_item(order_item.id, connection).unwrap()[0];
+
+    // Tax items can not be refunded on their own
+    let refund_items = vec![RefundItemRequest {
+        order_item_id: tax_item.id,
+        ticket_instance_id: None,
+    }];
+    assert_eq!(
+        DatabaseError::business_process_error("Tax order items can not be refunded directly"),
+        order.refund(&refund_items, user.id, None, false, connection)
+    );
+
+    // Refunding a ticket refunds the taxes charged on it and its fees
+    let refund_items = vec![RefundItemRequest {
+        order_item_id: order_item.id,
+        ticket_instance_id: Some(ticket.id),
+    }];
+    let (refund, amount) = order.refund(&refund_items, user.id, None, false, connection).unwrap();
+    assert_eq!(
+        amount,
+        order_item.unit_price_in_cents
+            + fee_item.unit_price_in_cents
+            + tax_item.unit_price_in_cents
+            + fee_tax_item.unit_price_in_cents
+    );
+    let refund_items = refund.items(connection).unwrap();
+    assert_eq!(refund_items.len(), 4);
+    let found_tax_item = refund_items.iter().find(|ri| ri.order_item_id == tax_item.id).unwrap();
+    assert_eq!(found_tax_item.quantity, 1);
+    assert_eq!(found_tax_item.amount, tax_item.unit_price_in_cents);
+    let found_fee_tax_item = refund_items
+        .iter()
+        .find(|ri| ri.order_item_id == fee_tax_item.id)
+        .unwrap();
+    assert_eq!(found_fee_tax_item.quantity, 1);
+    assert_eq!(found_fee_tax_item.amount, fee_tax_item.unit_price_in_cents);
+    assert_eq!(OrderItem::find(tax_item.id, connection).unwrap().refunded_quantity, 1);
+}
+
 #[test]
 fn refund_can_refund_previously_refunded_and_repurchased_tickets() {
     let project = TestProject::new();
@@ -3107,6 +3299,300 @@ fn add_tickets() {
     assert_eq!(order_item.calculate_quantity(connection), Ok(15));
 }
 
+#[test]
+fn reserve_seats() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let stage = project.create_stage().with_venue_id(venue.id).finish();
+    let section = project
+        .create_stage_section()
+        .with_stage_id(stage.id)
+        .with_seats(3)
+        .finish();
+    let seat_ids: Vec<Uuid> = section.seats(connection).unwrap().iter().map(|s| s.id).collect();
+    let event = project
+        .create_event()
+        .with_venue(&venue)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    ticket_type.assign_seats(&seat_ids, None, connection).unwrap();
+
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.reserve_seats(user.id, ticket_type.id, &seat_ids[0..2], connection)
+        .unwrap();
+
+    let items = cart.items(connection).unwrap();
+    let order_item = items.iter().find(|i| i.ticket_type_id == Some(ticket_type.id)).unwrap();
+    assert_eq!(order_item.quantity, 2);
+    let tickets = TicketInstance::find_for_order_item(order_item.id, connection).unwrap();
+    let mut reserved_seat_ids: Vec<Uuid> = tickets.iter().filter_map(|t| t.seat_id).collect();
+    reserved_seat_ids.sort();
+    let mut expected_seat_ids = seat_ids[0..2].to_vec();
+    expected_seat_ids.sort();
+    assert_eq!(reserved_seat_ids, expected_seat_ids);
+    assert!(tickets
+        .iter()
+        .all(|t| t.reserved_until.map(|r| r.timestamp()) == cart.expires_at.map(|e| e.timestamp())));
+
+    // Seats already in another cart cannot be reserved
+    let user2 = project.create_user().finish();
+    let mut cart2 = Order::find_or_create_cart(&user2, connection).unwrap();
+    assert!(cart2
+        .reserve_seats(user2.id, ticket_type.id, &seat_ids[1..3], connection)
+        .is_err());
+
+    // Seat reservations follow the cart expiry
+    let new_expiry = dates::now().add_minutes(30).finish();
+    cart.set_expiry(Some(user.id), Some(new_expiry), false, connection)
+        .unwrap();
+    let tickets = TicketInstance::find_for_order_item(order_item.id, connection).unwrap();
+    assert!(tickets
+        .iter()
+        .all(|t| t.reserved_until.map(|r| r.timestamp()) == Some(new_expiry.timestamp())));
+}
+
+#[test]
+fn update_quantities_does_not_reserve_seats() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let stage = project.create_stage().with_venue_id(venue.id).finish();
+    let section = project
+        .create_stage_section()
+        .with_stage_id(stage.id)
+        .with_seats(3)
+        .finish();
+    let seat_ids: Vec<Uuid> = section.seats(connection).unwrap().iter().map(|s| s.id).collect();
+    let event = project
+        .create_event()
+        .with_venue(&venue)
+        .with_a_specific_number_of_tickets(5)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    ticket_type.assign_seats(&seat_ids, None, connection).unwrap();
+
+    // Only the two tickets without a seat can be added by quantity
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 2,
+            redemption_code: None,
+        }],
+        false,
+        true,
+        connection,
+    )
+    .unwrap();
+
+    let items = cart.items(connection).unwrap();
+    let order_item = items.iter().find(|i| i.ticket_type_id == Some(ticket_type.id)).unwrap();
+    let tickets = TicketInstance::find_for_order_item(order_item.id, connection).unwrap();
+    assert_eq!(tickets.len(), 2);
+    assert!(tickets.iter().all(|t| t.seat_id.is_none()));
+
+    assert!(cart
+        .update_quantities(
+            user.id,
+            &[UpdateOrderItem {
+                ticket_type_id: ticket_type.id,
+                quantity: 3,
+                redemption_code: None,
+            }],
+            false,
+            true,
+            connection,
+        )
+        .is_err());
+}
+
+#[test]
+fn update_bundle_quantity() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let creator = project.create_user().finish();
+    let organization = project.create_organization().with_fees().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let event2 = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let ticket_type2 = &event2.ticket_types(true, None, connection).unwrap()[0];
+    let bundle = Bundle::create(organization.id, "Festival Pass".to_string(), "USD".to_string())
+        .commit(Some(creator.id), connection)
+        .unwrap();
+    bundle
+        .add_ticket_type(ticket_type.id, 1, 1500, Some(creator.id), connection)
+        .unwrap();
+    bundle
+        .add_ticket_type(ticket_type2.id, 2, 1000, Some(creator.id), connection)
+        .unwrap();
+
+    // Bundles issue tickets across events without tripping the single event cart limit
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_bundle_quantity(user.id, bundle.id, 2, connection).unwrap();
+    assert!(cart.expires_at.is_some());
+    let items = cart.items(connection).unwrap();
+    let order_item = items.iter().find(|i| i.ticket_type_id == Some(ticket_type.id)).unwrap();
+    assert_eq!(order_item.bundle_id, Some(bundle.id));
+    assert_eq!(order_item.event_id, Some(event.id));
+    assert_eq!(order_item.quantity, 2);
+    assert_eq!(order_item.unit_price_in_cents, 1500);
+    assert_eq!(order_item.calculate_quantity(connection).unwrap(), 2);
+    let order_item2 = items
+        .iter()
+        .find(|i| i.ticket_type_id == Some(ticket_type2.id))
+        .unwrap();
+    assert_eq!(order_item2.bundle_id, Some(bundle.id));
+    assert_eq!(order_item2.event_id, Some(event2.id));
+    assert_eq!(order_item2.quantity, 4);
+    assert_eq!(order_item2.unit_price_in_cents, 1000);
+    assert!(order_item.find_fee_item(connection).unwrap().is_some());
+
+    // Regular ticket updates leave bundled items alone
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 1,
+            redemption_code: None,
+        }],
+        false,
+        true,
+        connection,
+    )
+    .unwrap();
+    let items = cart.items(connection).unwrap();
+    let tickets: Vec<&OrderItem> = items
+        .iter()
+        .filter(|i| i.item_type == OrderItemTypes::Tickets)
+        .collect();
+    assert_eq!(tickets.len(), 3);
+    cart.update_quantities(user.id, &[], false, true, connection).unwrap();
+
+    // Reducing the quantity replaces the bundled items
+    cart.update_bundle_quantity(user.id, bundle.id, 1, connection).unwrap();
+    let items = cart.items(connection).unwrap();
+    let order_item = items.iter().find(|i| i.ticket_type_id == Some(ticket_type.id)).unwrap();
+    assert_eq!(order_item.quantity, 1);
+    let order_item2 = items
+        .iter()
+        .find(|i| i.ticket_type_id == Some(ticket_type2.id))
+        .unwrap();
+    assert_eq!(order_item2.quantity, 2);
+
+    // Removing the bundle empties the cart
+    cart.update_bundle_quantity(user.id, bundle.id, 0, connection).unwrap();
+    assert!(cart
+        .items(connection)
+        .unwrap()
+        .iter()
+        .all(|i| i.item_type != OrderItemTypes::Tickets));
+    assert!(cart.expires_at.is_none());
+
+    // Deleted bundles can no longer be added
+    cart.update_bundle_quantity(user.id, bundle.id, 1, connection).unwrap();
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(
+        Some("Test".to_string()),
+        ExternalPaymentType::CreditCard,
+        user.id,
+        total,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(cart.status, OrderStatus::Paid);
+    bundle.destroy(Some(creator.id), connection).unwrap();
+    let mut cart2 = Order::find_or_create_cart(&user, connection).unwrap();
+    let result = cart2.update_bundle_quantity(user.id, bundle.id, 1, connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("bundle_id"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn duplicate_order_with_bundle() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let creator = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let event2 = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let ticket_type2 = &event2.ticket_types(true, None, connection).unwrap()[0];
+    let bundle = Bundle::create(organization.id, "Festival Pass".to_string(), "USD".to_string())
+        .commit(Some(creator.id), connection)
+        .unwrap();
+    bundle
+        .add_ticket_type(ticket_type.id, 1, 1500, Some(creator.id), connection)
+        .unwrap();
+    bundle
+        .add_ticket_type(ticket_type2.id, 2, 1000, Some(creator.id), connection)
+        .unwrap();
+
+    let user = project.create_user().finish();
+    let mut order = Order::find_or_create_cart(&user, connection).unwrap();
+    order.update_bundle_quantity(user.id, bundle.id, 2, connection).unwrap();
+    let total = order.calculate_total(connection).unwrap();
+    order
+        .add_external_payment(
+            Some("Test".to_string()),
+            ExternalPaymentType::CreditCard,
+            user.id,
+            total,
+            connection,
+        )
+        .unwrap();
+
+    let dupe_order = order.duplicate_order(connection).unwrap();
+    assert_ne!(dupe_order.id, order.id);
+    let dupe_items = dupe_order.items(connection).unwrap();
+    let order_item = dupe_items
+        .iter()
+        .find(|i| i.ticket_type_id == Some(ticket_type.id))
+        .unwrap();
+    assert_eq!(order_item.bundle_id, Some(bundle.id));
+    assert_eq!(order_item.quantity, 2);
+    let order_item2 = dupe_items
+        .iter()
+        .find(|i| i.ticket_type_id == Some(ticket_type2.id))
+        .unwrap();
+    assert_eq!(order_item2.bundle_id, Some(bundle.id));
+    assert_eq!(order_item2.quantity, 4);
+}
+
 #[test]
 fn add_tickets_below_min_fee() {
     let project = TestProject::new();
@@ -4470,6 +4956,7 @@ fn replace_tickets_for_box_office() {
             true,
             None,
             None,
+            None,
             connection,
         )
         .unwrap();
@@ -5759,6 +6246,61 @@ fn validate_record() {
     }
 }
 
+#[test]
+fn validate_record_with_mixed_currencies() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().with_currency("CAD").finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let event2 = project.create_event().with_tickets().with_ticket_pricing().finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let ticket_type2 = &event2.ticket_types(true, None, connection).unwrap()[0];
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 1,
+            redemption_code: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(cart.currency(connection).unwrap(), Some("CAD".to_string()));
+
+    let result = cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type2.id,
+            quantity: 1,
+            redemption_code: None,
+        }],
+        false,
+        false,
+        connection,
+    );
+    match result {
+        Ok(_) => {
+            panic!("Expected validation error");
+        }
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("currency"));
+                assert_eq!(errors["currency"][0].code, "cart_currency_mismatch");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
 #[test]
 fn for_display_with_organization_id_and_event_id_filters() {
     let project = TestProject::new();
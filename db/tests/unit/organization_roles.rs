@@ -0,0 +1,235 @@
+use db::dev::TestProject;
+use db::prelude::*;
+use db::utils::errors::ErrorCode::ValidationError;
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+
+    let organization_role = OrganizationRole::create(
+        organization.id,
+        "Finance read-only".to_string(),
+        Some("Settlements and financial reports".to_string()),
+        vec![Scopes::SettlementRead, Scopes::EventFinancialReports],
+    )
+    .commit(Some(user.id), connection)
+    .unwrap();
+    assert_eq!(organization_role.organization_id, organization.id);
+    assert_eq!(organization_role.name, "Finance read-only");
+    assert_eq!(
+        organization_role.scopes,
+        vec!["settlement:read".to_string(), "event:financial-reports".to_string()]
+    );
+
+    let domain_events = DomainEvent::find(
+        Tables::OrganizationRoles,
+        Some(organization_role.id),
+        Some(DomainEventTypes::OrganizationRoleCreated),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+
+    // Names are unique within an organization
+    let result = OrganizationRole::create(
+        organization.id,
+        "FINANCE READ-ONLY".to_string(),
+        None,
+        vec![Scopes::SettlementRead],
+    )
+    .commit(Some(user.id), connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("name"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    // Administrative scopes cannot be granted
+    let result = OrganizationRole::create(organization.id, "Admin".to_string(), None, vec![Scopes::OrgAdmin])
+        .commit(Some(user.id), connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("scopes"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    let result =
+        OrganizationRole::create(organization.id, "Empty".to_string(), None, vec![]).commit(Some(user.id), connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("scopes"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn update() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let organization_role =
+        OrganizationRole::create(organization.id, "Marketing".to_string(), None, vec![Scopes::OrgFans])
+            .commit(Some(user.id), connection)
+            .unwrap();
+
+    let updated_role = organization_role
+        .update(
+            OrganizationRoleEditableAttributes {
+                description: Some(Some("Fan outreach".to_string())),
+                scopes: Some(vec![Scopes::OrgFans, Scopes::EventBroadcast]),
+                ..Default::default()
+            },
+            Some(user.id),
+            connection,
+        )
+        .unwrap();
+    assert_eq!(updated_role.name, "Marketing");
+    assert_eq!(updated_role.description, Some("Fan outreach".to_string()));
+    assert_eq!(
+        updated_role.scopes,
+        vec!["org:fans".to_string(), "event:broadcast".to_string()]
+    );
+
+    // The previous definition is kept for auditing
+    let domain_events = DomainEvent::find(
+        Tables::OrganizationRoles,
+        Some(organization_role.id),
+        Some(DomainEventTypes::OrganizationRoleUpdated),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+    assert_eq!(
+        domain_events[0].event_data,
+        Some(json!({
+            "previous": {
+                "name": "Marketing",
+                "description": null,
+                "scopes": ["org:fans"]
+            },
+            "current": {
+                "name": "Marketing",
+                "description": "Fan outreach",
+                "scopes": ["org:fans", "event:broadcast"]
+            }
+        }))
+    );
+}
+
+#[test]
+fn destroy() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let member = project.create_user().finish();
+    let organization = project
+        .create_organization()
+        .with_member(&member, Roles::OrgMember)
+        .finish();
+    let organization_role =
+        OrganizationRole::create(organization.id, "Marketing".to_string(), None, vec![Scopes::OrgReports])
+            .commit(Some(user.id), connection)
+            .unwrap();
+    OrganizationUser::find_by_user_id(member.id, organization.id, connection)
+        .unwrap()
+        .set_organization_roles(vec![organization_role.id], Some(user.id), connection)
+        .unwrap();
+    assert_eq!(
+        OrganizationRole::find_for_organization(organization.id, connection).unwrap(),
+        vec![organization_role.clone()]
+    );
+
+    organization_role.destroy(Some(user.id), connection).unwrap();
+    assert!(OrganizationRole::find_for_organization(organization.id, connection)
+        .unwrap()
+        .is_empty());
+    assert!(OrganizationRole::find(organization_role.id, connection)
+        .unwrap()
+        .deleted_at
+        .is_some());
+    assert!(
+        OrganizationUser::find_by_user_id(member.id, organization.id, connection)
+            .unwrap()
+            .organization_role_ids
+            .is_empty()
+    );
+    assert!(!organization
+        .get_scopes_for_user(&member, connection)
+        .unwrap()
+        .contains(&Scopes::OrgReports));
+
+    let domain_events = DomainEvent::find(
+        Tables::OrganizationRoles,
+        Some(organization_role.id),
+        Some(DomainEventTypes::OrganizationRoleDeleted),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+}
+
+#[test]
+fn scopes_for_user() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let member = project.create_user().finish();
+    let organization = project
+        .create_organization()
+        .with_member(&member, Roles::OrgMember)
+        .finish();
+    let other_organization = project
+        .create_organization()
+        .with_member(&member, Roles::OrgMember)
+        .finish();
+    let marketing = OrganizationRole::create(
+        organization.id,
+        "Marketing".to_string(),
+        None,
+        vec![Scopes::OrgFans, Scopes::EventBroadcast],
+    )
+    .commit(None, connection)
+    .unwrap();
+    let finance = OrganizationRole::create(
+        organization.id,
+        "Finance read-only".to_string(),
+        None,
+        vec![Scopes::SettlementRead, Scopes::OrgFans],
+    )
+    .commit(None, connection)
+    .unwrap();
+    assert!(
+        OrganizationRole::scopes_for_user(organization.id, member.id, connection)
+            .unwrap()
+            .is_empty()
+    );
+
+    OrganizationUser::find_by_user_id(member.id, organization.id, connection)
+        .unwrap()
+        .set_organization_roles(vec![marketing.id, finance.id], None, connection)
+        .unwrap();
+    assert_eq!(
+        OrganizationRole::scopes_for_user(organization.id, member.id, connection).unwrap(),
+        vec![Scopes::EventBroadcast, Scopes::OrgFans, Scopes::SettlementRead]
+    );
+    assert!(
+        OrganizationRole::scopes_for_user(other_organization.id, member.id, connection)
+            .unwrap()
+            .is_empty()
+    );
+}
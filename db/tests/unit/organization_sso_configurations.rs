@@ -0,0 +1,256 @@
+use db::dev::TestProject;
+use db::prelude::*;
+use db::utils::errors::ErrorCode::ValidationError;
+use std::collections::HashMap;
+
+const ENCRYPTION_KEY: &str = "encryption_key";
+
+fn new_configuration(organization: &Organization) -> NewOrganizationSsoConfiguration {
+    let mut role_mappings = HashMap::new();
+    role_mappings.insert("box-office".to_string(), Roles::OrgBoxOffice);
+    role_mappings.insert("admins".to_string(), Roles::OrgAdmin);
+    OrganizationSsoConfiguration::create(
+        organization.id,
+        "https://login.example.com/".to_string(),
+        "client-id".to_string(),
+        "client-secret".to_string(),
+        Roles::OrgMember,
+        Some("groups".to_string()),
+        role_mappings,
+    )
+}
+
+fn sso_identity(subject: &str, email: Option<&str>, role_claim_values: Vec<&str>) -> SsoIdentity {
+    SsoIdentity {
+        subject: subject.to_string(),
+        email: email.map(|e| e.to_string()),
+        first_name: Some("Staff".to_string()),
+        last_name: Some("Member".to_string()),
+        role_claim_values: role_claim_values.into_iter().map(|v| v.to_string()).collect(),
+    }
+}
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+
+    let configuration = new_configuration(&organization)
+        .commit(ENCRYPTION_KEY, Some(user.id), connection)
+        .unwrap();
+    assert_eq!(configuration.organization_id, organization.id);
+    assert_eq!(configuration.issuer_url, "https://login.example.com");
+    assert_ne!(configuration.encrypted_client_secret, "client-secret");
+    assert_eq!(configuration.client_secret(ENCRYPTION_KEY).unwrap(), "client-secret");
+    assert_eq!(configuration.role_mappings().get("admins"), Some(&Roles::OrgAdmin));
+    assert!(configuration.enabled);
+    assert_eq!(
+        OrganizationSsoConfiguration::find_for_organization(organization.id, connection).unwrap(),
+        configuration
+    );
+
+    let domain_events = DomainEvent::find(
+        Tables::OrganizationSsoConfigurations,
+        Some(configuration.id),
+        Some(DomainEventTypes::OrganizationSsoConfigurationCreated),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(1, domain_events.len());
+
+    // Only one provider per organization
+    assert!(new_configuration(&organization)
+        .commit(ENCRYPTION_KEY, Some(user.id), connection)
+        .is_err());
+}
+
+#[test]
+fn commit_with_validation_errors() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+
+    let mut configuration = new_configuration(&organization);
+    configuration.issuer_url = "http://login.example.com".to_string();
+    match configuration.commit(ENCRYPTION_KEY, None, connection) {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("issuer_url"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    // Ownership cannot be granted through the provider
+    let mut configuration = new_configuration(&organization);
+    configuration.default_role = Roles::OrgOwner;
+    match configuration.commit(ENCRYPTION_KEY, None, connection) {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("default_role"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    let mut configuration = new_configuration(&organization);
+    configuration
+        .role_mappings
+        .insert("owners".to_string(), Roles::OrgOwner);
+    match configuration.commit(ENCRYPTION_KEY, None, connection) {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("role_mappings"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn update() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let configuration = new_configuration(&organization)
+        .commit(ENCRYPTION_KEY, Some(user.id), connection)
+        .unwrap();
+
+    let attributes = OrganizationSsoConfigurationEditableAttributes {
+        client_id: Some("new-client-id".to_string()),
+        enabled: Some(false),
+        ..Default::default()
+    };
+    let updated = configuration
+        .update(attributes, ENCRYPTION_KEY, Some(user.id), connection)
+        .unwrap();
+    assert_eq!(updated.client_id, "new-client-id");
+    assert!(!updated.enabled);
+    // Secret is kept when not supplied
+    assert_eq!(updated.client_secret(ENCRYPTION_KEY).unwrap(), "client-secret");
+    assert_eq!(updated.role_mappings, configuration.role_mappings);
+
+    let domain_events = DomainEvent::find(
+        Tables::OrganizationSsoConfigurations,
+        Some(configuration.id),
+        Some(DomainEventTypes::OrganizationSsoConfigurationUpdated),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(1, domain_events.len());
+}
+
+#[test]
+fn role_for() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let configuration = new_configuration(&organization)
+        .commit(ENCRYPTION_KEY, None, connection)
+        .unwrap();
+
+    assert_eq!(configuration.role_for(&[]), Roles::OrgMember);
+    assert_eq!(configuration.role_for(&["unmapped".to_string()]), Roles::OrgMember);
+    assert_eq!(
+        configuration.role_for(&["unmapped".to_string(), "box-office".to_string()]),
+        Roles::OrgBoxOffice
+    );
+}
+
+#[test]
+fn provision_user() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let configuration = new_configuration(&organization)
+        .commit(ENCRYPTION_KEY, None, connection)
+        .unwrap();
+
+    let identity = sso_identity("subject-1", Some("Staff@Example.com"), vec!["box-office"]);
+    let user = configuration
+        .provision_user(&identity, "access-token".to_string(), connection)
+        .unwrap();
+    assert_eq!(user.email, Some("staff@example.com".to_string()));
+    assert_eq!(user.first_name, Some("Staff".to_string()));
+    assert_eq!(
+        organization.get_roles_for_user(&user, connection).unwrap().0,
+        vec![Roles::OrgBoxOffice]
+    );
+    let external_login = user.find_external_login(OIDC_SITE, connection).unwrap();
+    assert_eq!(external_login.external_user_id, "https://login.example.com|subject-1");
+
+    let domain_events = DomainEvent::find(
+        Tables::Organizations,
+        Some(organization.id),
+        Some(DomainEventTypes::OrganizationUserSsoProvisioned),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(1, domain_events.len());
+
+    // Subsequent logins find the same user and leave their membership alone
+    let identity = sso_identity("subject-1", Some("staff@example.com"), vec!["admins"]);
+    let same_user = configuration
+        .provision_user(&identity, "access-token".to_string(), connection)
+        .unwrap();
+    assert_eq!(same_user.id, user.id);
+    assert_eq!(
+        organization.get_roles_for_user(&user, connection).unwrap().0,
+        vec![Roles::OrgBoxOffice]
+    );
+
+    // Existing accounts are not linked by email
+    let existing_user = project.create_user().finish();
+    let identity = sso_identity("subject-2", existing_user.email.as_ref().map(|e| e.as_str()), vec![]);
+    assert_eq!(
+        configuration.provision_user(&identity, "access-token".to_string(), connection),
+        Err(DatabaseError::new(
+            ErrorCode::BusinessProcessError,
+            Some("An account already exists for this email, sign in to link it to single sign-on".to_string()),
+        ))
+    );
+
+    // Disabled users cannot sign in
+    user.disable(None, connection).unwrap();
+    let identity = sso_identity("subject-1", None, vec![]);
+    assert_eq!(
+        configuration.provision_user(&identity, "access-token".to_string(), connection),
+        Err(DatabaseError::new(
+            ErrorCode::BusinessProcessError,
+            Some("This account has been deleted".to_string()),
+        ))
+    );
+}
+
+#[test]
+fn link_user() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let user = project.create_user().finish();
+    let configuration = new_configuration(&organization)
+        .commit(ENCRYPTION_KEY, None, connection)
+        .unwrap();
+
+    let identity = sso_identity("subject-1", user.email.as_ref().map(|e| e.as_str()), vec![]);
+    configuration
+        .link_user(&user, &identity, "access-token".to_string(), connection)
+        .unwrap();
+    assert!(organization.is_member(&user, connection).unwrap());
+    assert_eq!(
+        organization.get_roles_for_user(&user, connection).unwrap().0,
+        vec![Roles::OrgMember]
+    );
+
+    // Later logins through the provider find the linked user
+    let linked_user = configuration
+        .provision_user(&identity, "access-token".to_string(), connection)
+        .unwrap();
+    assert_eq!(linked_user.id, user.id);
+}
@@ -1,5 +1,6 @@
 use db::dev::TestProject;
-use db::models::{OrganizationUser, Roles};
+use db::models::*;
+use db::utils::errors::ErrorCode::ValidationError;
 
 #[test]
 fn is_event_user() {
@@ -113,3 +114,86 @@ fn update() {
     assert_eq!(vec![Roles::OrgOwner], organization_user.role);
     assert_eq!(organization_user_id, organization_user.id);
 }
+
+#[test]
+fn set_organization_roles() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project
+        .create_organization()
+        .with_member(&user, Roles::OrgMember)
+        .finish();
+    let other_organization = project.create_organization().finish();
+    let organization_role = OrganizationRole::create(
+        organization.id,
+        "Finance read-only".to_string(),
+        None,
+        vec![Scopes::SettlementRead],
+    )
+    .commit(None, connection)
+    .unwrap();
+    let other_organization_role = OrganizationRole::create(
+        other_organization.id,
+        "Finance read-only".to_string(),
+        None,
+        vec![Scopes::SettlementRead],
+    )
+    .commit(None, connection)
+    .unwrap();
+    let organization_user = OrganizationUser::find_by_user_id(user.id, organization.id, connection).unwrap();
+    assert!(organization_user.organization_role_ids.is_empty());
+    assert!(!organization
+        .get_scopes_for_user(&user, connection)
+        .unwrap()
+        .contains(&Scopes::SettlementRead));
+
+    let organization_user = organization_user
+        .set_organization_roles(vec![organization_role.id], None, connection)
+        .unwrap();
+    assert_eq!(organization_user.organization_role_ids, vec![organization_role.id]);
+    assert!(organization
+        .get_scopes_for_user(&user, connection)
+        .unwrap()
+        .contains(&Scopes::SettlementRead));
+    assert_eq!(
+        user.get_scopes_by_organization(connection).unwrap()[&organization.id],
+        organization.get_scopes_for_user(&user, connection).unwrap()
+    );
+
+    let domain_events = DomainEvent::find(
+        Tables::Organizations,
+        Some(organization.id),
+        Some(DomainEventTypes::OrganizationUserRolesUpdated),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+
+    // Revoked scopes take precedence over those granted by organization roles
+    organization_user
+        .set_additional_scopes(
+            AdditionalOrgMemberScopes {
+                additional: vec![],
+                revoked: vec![Scopes::SettlementRead],
+            },
+            connection,
+        )
+        .unwrap();
+    assert!(!organization
+        .get_scopes_for_user(&user, connection)
+        .unwrap()
+        .contains(&Scopes::SettlementRead));
+
+    // Roles of other organizations cannot be assigned
+    let result = organization_user.set_organization_roles(vec![other_organization_role.id], None, connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("organization_role_ids"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
@@ -597,6 +597,96 @@ fn has_fan() {
     assert!(organization.has_fan(&user, connection).unwrap());
 }
 
+#[test]
+fn create_with_currency() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let fee_schedule = project.create_fee_schedule().finish(None);
+
+    let organization = Organization::create("Organization", fee_schedule.id)
+        .commit(None, &"encryption_key".to_string(), None, connection)
+        .unwrap();
+    assert_eq!(organization.currency, "USD".to_string());
+
+    let mut new_organization = Organization::create("Organization", fee_schedule.id);
+    new_organization.currency = Some("CAD".to_string());
+    let organization = new_organization
+        .commit(None, &"encryption_key".to_string(), None, connection)
+        .unwrap();
+    assert_eq!(organization.currency, "CAD".to_string());
+
+    let mut new_organization = Organization::create("Organization", fee_schedule.id);
+    new_organization.currency = Some("Dollars".to_string());
+    let result = new_organization.commit(None, &"encryption_key".to_string(), None, connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ErrorCode::ValidationError { errors } => {
+                assert!(errors.contains_key("currency"));
+                assert_eq!(errors["currency"][0].code, "currency");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn update_transfer_expiry_hours() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    assert_eq!(organization.transfer_expiry_hours, None);
+
+    let organization = organization
+        .update(
+            OrganizationEditableAttributes {
+                transfer_expiry_hours: Some(Some(72)),
+                ..Default::default()
+            },
+            None,
+            &"encryption_key".to_string(),
+            connection,
+        )
+        .unwrap();
+    assert_eq!(organization.transfer_expiry_hours, Some(72));
+
+    let result = organization.update(
+        OrganizationEditableAttributes {
+            transfer_expiry_hours: Some(Some(0)),
+            ..Default::default()
+        },
+        None,
+        &"encryption_key".to_string(),
+        connection,
+    );
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ErrorCode::ValidationError { errors } => {
+                assert!(errors.contains_key("transfer_expiry_hours"));
+                assert_eq!(
+                    errors["transfer_expiry_hours"][0].code,
+                    "transfer_expiry_hours_must_be_positive"
+                );
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    let organization = organization
+        .update(
+            OrganizationEditableAttributes {
+                transfer_expiry_hours: Some(None),
+                ..Default::default()
+            },
+            None,
+            &"encryption_key".to_string(),
+            connection,
+        )
+        .unwrap();
+    assert_eq!(organization.transfer_expiry_hours, None);
+}
+
 #[test]
 fn update() {
     let project = TestProject::new();
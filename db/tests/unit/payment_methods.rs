@@ -1,5 +1,8 @@
+use chrono::prelude::*;
 use db::dev::TestProject;
 use db::models::*;
+use db::utils::errors::ErrorCode;
+use uuid::Uuid;
 
 #[test]
 fn for_display() {
@@ -20,8 +23,13 @@ fn for_display() {
     assert_eq!(
         payment_method.clone().for_display(),
         Ok(DisplayPaymentMethod {
+            id: payment_method.id,
             name: payment_method.name,
             is_default: payment_method.is_default,
+            card_brand: None,
+            card_last_four: None,
+            card_expiry_month: None,
+            card_expiry_year: None,
         })
     )
 }
@@ -194,3 +202,211 @@ fn find_for_user() {
         PaymentMethod::find_for_user(user3.id, Some(PaymentProviders::Stripe), &connection).unwrap();
     assert!(found_payment_methods.is_empty());
 }
+
+#[test]
+fn create_with_card() {
+    let project = TestProject::new();
+    let user = project.create_user().finish();
+    let connection = project.get_connection();
+
+    let payment_method = PaymentMethod::create(
+        user.id,
+        PaymentProviders::Stripe,
+        true,
+        "cus_example".into(),
+        "abc".into(),
+    )
+    .with_card("Visa".to_string(), "4242".to_string(), 8, 2024)
+    .commit(user.id, connection)
+    .unwrap();
+
+    let display_payment_method = payment_method.for_display().unwrap();
+    assert_eq!(display_payment_method.card_brand, Some("Visa".to_string()));
+    assert_eq!(display_payment_method.card_last_four, Some("4242".to_string()));
+    assert_eq!(display_payment_method.card_expiry_month, Some(8));
+    assert_eq!(display_payment_method.card_expiry_year, Some(2024));
+}
+
+#[test]
+fn create_default_replaces_existing_default() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let payment_method = project.create_payment_method().with_user(&user).make_default().finish();
+    let payment_method2 = project.create_payment_method().with_user(&user).make_default().finish();
+
+    assert!(!PaymentMethod::find(payment_method.id, connection).unwrap().is_default);
+    assert_eq!(
+        PaymentMethod::find_default_for_user(user.id, connection).unwrap(),
+        payment_method2
+    );
+    // Both cards are kept for the provider
+    assert_eq!(
+        PaymentMethod::find_for_user(user.id, Some(PaymentProviders::Stripe), connection)
+            .unwrap()
+            .len(),
+        2
+    );
+}
+
+#[test]
+fn find() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let payment_method = project.create_payment_method().finish();
+
+    assert_eq!(
+        PaymentMethod::find(payment_method.id, connection).unwrap(),
+        payment_method
+    );
+    assert!(PaymentMethod::find(Uuid::new_v4(), connection).is_err());
+}
+
+#[test]
+fn set_default() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let payment_method = project.create_payment_method().with_user(&user).make_default().finish();
+    let payment_method2 = project.create_payment_method().with_user(&user).finish();
+    let other_user_payment_method = project.create_payment_method().make_default().finish();
+
+    let payment_method2 = payment_method2.set_default(user.id, connection).unwrap();
+    assert!(payment_method2.is_default);
+    assert!(!PaymentMethod::find(payment_method.id, connection).unwrap().is_default);
+    assert!(
+        PaymentMethod::find(other_user_payment_method.id, connection)
+            .unwrap()
+            .is_default
+    );
+
+    let domain_events = DomainEvent::find(
+        Tables::PaymentMethods,
+        Some(payment_method2.id),
+        Some(DomainEventTypes::PaymentMethodDefaultChanged),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+}
+
+#[test]
+fn destroy() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let payment_method = project.create_payment_method().with_user(&user).finish();
+    let payment_method2 = project.create_payment_method().with_user(&user).finish();
+    let payment_method3 = project.create_payment_method().with_user(&user).make_default().finish();
+
+    // Removing a method that is not the default leaves the default in place
+    payment_method.destroy(user.id, connection).unwrap();
+    assert!(PaymentMethod::find(payment_method.id, connection).is_err());
+    assert_eq!(
+        PaymentMethod::find_default_for_user(user.id, connection).unwrap().id,
+        payment_method3.id
+    );
+    let domain_events = DomainEvent::find(
+        Tables::PaymentMethods,
+        Some(payment_method.id),
+        Some(DomainEventTypes::PaymentMethodDeleted),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+
+    // Removing the default promotes the remaining method
+    payment_method3.destroy(user.id, connection).unwrap();
+    assert_eq!(
+        PaymentMethod::find_default_for_user(user.id, connection).unwrap().id,
+        payment_method2.id
+    );
+
+    PaymentMethod::find(payment_method2.id, connection)
+        .unwrap()
+        .destroy(user.id, connection)
+        .unwrap();
+    assert!(user.payment_methods(connection).unwrap().is_empty());
+}
+
+#[test]
+fn find_default_pending_expiry_notification() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let today = Utc::now().naive_utc().date();
+    let next_month = today.with_day(1).unwrap() + chrono::Duration::days(32);
+    let in_three_months = today.with_day(1).unwrap() + chrono::Duration::days(93);
+    let last_month = today.with_day(1).unwrap() - chrono::Duration::days(1);
+
+    let expiring_this_month = project
+        .create_payment_method()
+        .with_card_expiry(today.month() as i32, today.year())
+        .make_default()
+        .finish();
+    let expiring_next_month = project
+        .create_payment_method()
+        .with_card_expiry(next_month.month() as i32, next_month.year())
+        .make_default()
+        .finish();
+    // Not the default card
+    project
+        .create_payment_method()
+        .with_card_expiry(today.month() as i32, today.year())
+        .finish();
+    // Expires later
+    project
+        .create_payment_method()
+        .with_card_expiry(in_three_months.month() as i32, in_three_months.year())
+        .make_default()
+        .finish();
+    // Already expired
+    project
+        .create_payment_method()
+        .with_card_expiry(last_month.month() as i32, last_month.year())
+        .make_default()
+        .finish();
+    // No card details
+    project.create_payment_method().make_default().finish();
+
+    assert_equiv!(
+        PaymentMethod::find_default_pending_expiry_notification(connection).unwrap(),
+        vec![expiring_this_month.clone(), expiring_next_month.clone()]
+    );
+
+    let expiring_this_month = expiring_this_month.mark_expiry_notification_sent(connection).unwrap();
+    assert!(expiring_this_month.expiry_notification_sent_at.is_some());
+    let domain_events = DomainEvent::find(
+        Tables::PaymentMethods,
+        Some(expiring_this_month.id),
+        Some(DomainEventTypes::PaymentMethodExpiryNotificationSent),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+    assert_eq!(
+        PaymentMethod::find_default_pending_expiry_notification(connection).unwrap(),
+        vec![expiring_next_month]
+    );
+}
+
+#[test]
+fn create_next_expiry_notification_domain_action() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+
+    PaymentMethod::create_next_expiry_notification_domain_action(connection).unwrap();
+    let domain_actions = DomainAction::find_by_resource(
+        None,
+        None,
+        DomainActionTypes::SendPaymentMethodExpiryNotifications,
+        DomainActionStatus::Pending,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_actions.len(), 1);
+    assert!(domain_actions[0].scheduled_at > Utc::now().naive_utc());
+
+    // Already scheduled
+    let result = PaymentMethod::create_next_expiry_notification_domain_action(connection);
+    assert_eq!(result.unwrap_err().error_code, ErrorCode::AlreadyScheduledError);
+}
@@ -0,0 +1,322 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use db::dev::TestProject;
+use db::prelude::*;
+use db::utils::errors::ErrorCode::ValidationError;
+
+fn create_payment_plan_order(project: &TestProject, organization: &Organization) -> (User, Order, PaymentMethod) {
+    let connection = project.get_connection();
+    let event = project
+        .create_event()
+        .with_organization(organization)
+        .with_event_start(Utc::now().naive_utc() + Duration::days(60))
+        .with_sales_starting(Utc::now().naive_utc() - Duration::days(1))
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    ticket_type
+        .update(
+            TicketTypeEditableAttributes {
+                installment_count: Some(Some(3)),
+                installment_interval_days: Some(10),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+
+    let user = project.create_user().finish();
+    let order = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(2)
+        .finish();
+    let payment_method = project.create_payment_method().with_user(&user).finish();
+    (user, order, payment_method)
+}
+
+#[test]
+fn create_for_order() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_fees().finish();
+    let (user, order, payment_method) = create_payment_plan_order(&project, &organization);
+
+    let plan = PaymentPlan::create_for_order(&order, &payment_method, Some(user.id), connection).unwrap();
+    assert_eq!(plan.status, PaymentPlanStatus::Active);
+    assert_eq!(plan.installment_count, 3);
+    assert_eq!(plan.max_failed_attempts, organization.payment_plan_max_failed_attempts);
+    assert_eq!(plan.payment_method_id, Some(payment_method.id));
+
+    let total = order.calculate_total(connection).unwrap();
+    let installments = plan.installments(connection).unwrap();
+    assert_eq!(installments.len(), 3);
+    assert_eq!(installments.iter().map(|i| i.amount_in_cents).sum::<i64>(), total);
+    assert_eq!(installments[1].amount_in_cents, total / 3);
+    assert_eq!(installments[2].due_at - installments[1].due_at, Duration::days(10));
+    assert!(installments[0].due_at <= Utc::now().naive_utc());
+    assert!(installments
+        .iter()
+        .all(|i| i.status == PaymentPlanInstallmentStatus::Pending));
+
+    let domain_events = DomainEvent::find(
+        Tables::PaymentPlans,
+        Some(plan.id),
+        Some(DomainEventTypes::PaymentPlanCreated),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(1, domain_events.len());
+
+    // Recreating the plan before any installments are paid replaces it
+    let new_plan = PaymentPlan::create_for_order(&order, &payment_method, Some(user.id), connection).unwrap();
+    assert_ne!(plan.id, new_plan.id);
+    assert_eq!(
+        PaymentPlan::find_for_order(order.id, connection).unwrap().id,
+        new_plan.id
+    );
+}
+
+#[test]
+fn create_for_order_ineligible() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let payment_method = project.create_payment_method().with_user(&user).finish();
+
+    // Ticket type does not offer installments
+    let event = project.create_event().with_ticket_pricing().finish();
+    let order = project.create_order().for_event(&event).for_user(&user).finish();
+    let result = PaymentPlan::create_for_order(&order, &payment_method, Some(user.id), connection);
+    match result {
+        Ok(_) => {
+            panic!("Expected validation error");
+        }
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("payment_plan"));
+                assert_eq!(
+                    &errors["payment_plan"][0].message.clone().unwrap().into_owned(),
+                    "Not all tickets in the order can be paid in installments"
+                );
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    // Final installment would be due after the event starts
+    let event = project.create_event().with_ticket_pricing().finish();
+    let ticket_type = event.ticket_types(true, None, connection).unwrap().remove(0);
+    ticket_type
+        .update(
+            TicketTypeEditableAttributes {
+                installment_count: Some(Some(2)),
+                ..Default::default()
+            },
+            None,
+            connection,
+        )
+        .unwrap();
+    let order = project.create_order().for_event(&event).for_user(&user).finish();
+    assert!(PaymentPlan::create_for_order(&order, &payment_method, Some(user.id), connection).is_err());
+
+    // Payment method belongs to another user
+    let organization = project.create_organization().finish();
+    let (_, order, _) = create_payment_plan_order(&project, &organization);
+    assert!(PaymentPlan::create_for_order(&order, &payment_method, Some(user.id), connection).is_err());
+}
+
+#[test]
+fn mark_paid() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_fees().finish();
+    let (user, order, payment_method) = create_payment_plan_order(&project, &organization);
+    let plan = PaymentPlan::create_for_order(&order, &payment_method, Some(user.id), connection).unwrap();
+    let installments = plan.installments(connection).unwrap();
+
+    let payment = installments[0]
+        .mark_paid(
+            PaymentProviders::Stripe,
+            "ch_1".to_string(),
+            json!({}),
+            Some(user.id),
+            connection,
+        )
+        .unwrap();
+    assert_eq!(payment.amount, installments[0].amount_in_cents);
+    assert_eq!(payment.status, PaymentStatus::Completed);
+
+    // Tickets are issued but held until the plan is paid
+    let order = Order::find(order.id, connection).unwrap();
+    assert_eq!(order.status, OrderStatus::PartiallyPaid);
+    let ticket_ids = TicketInstance::find_ids_for_order(order.id, connection).unwrap();
+    assert_eq!(ticket_ids.len(), 2);
+    let tickets = TicketInstance::find_for_user(user.id, connection).unwrap();
+    assert_eq!(tickets.len(), 2);
+    assert!(tickets.iter().all(|t| t.status == TicketInstanceStatus::Purchased));
+    assert!(TicketInstance::awaiting_payment_plan(&ticket_ids, connection).unwrap());
+    assert!(TicketInstance::create_transfer(&user, &ticket_ids, None, None, false, connection).is_err());
+    assert_eq!(
+        TicketInstance::redeem_ticket(
+            tickets[0].id,
+            tickets[0].redeem_key.clone().unwrap(),
+            user.id,
+            CheckInSource::GuestList,
+            connection,
+        )
+        .unwrap(),
+        RedeemResults::TicketAwaitingPayment
+    );
+    assert!(payment_method.destroy(user.id, connection).is_err());
+
+    let installment = PaymentPlanInstallment::find(installments[0].id, connection).unwrap();
+    assert_eq!(installment.status, PaymentPlanInstallmentStatus::Paid);
+    assert_eq!(installment.payment_id, Some(payment.id));
+    let actions = DomainAction::find_by_resource(
+        Some(Tables::PaymentPlanInstallments),
+        Some(installments[1].id),
+        DomainActionTypes::ChargePaymentPlanInstallment,
+        DomainActionStatus::Pending,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(actions.len(), 1);
+    assert_eq!(actions[0].scheduled_at, installments[1].due_at);
+
+    // Paying an installment twice is not allowed
+    assert!(installments[0]
+        .mark_paid(
+            PaymentProviders::Stripe,
+            "ch_1".to_string(),
+            json!({}),
+            Some(user.id),
+            connection,
+        )
+        .is_err());
+
+    for installment in &installments[1..] {
+        installment
+            .mark_paid(
+                PaymentProviders::Stripe,
+                format!("ch_{}", installment.installment_number),
+                json!({}),
+                None,
+                connection,
+            )
+            .unwrap();
+    }
+
+    let order = Order::find(order.id, connection).unwrap();
+    assert_eq!(order.status, OrderStatus::Paid);
+    assert_eq!(
+        order.total_paid(connection).unwrap(),
+        order.calculate_total(connection).unwrap()
+    );
+    let plan = PaymentPlan::find(plan.id, connection).unwrap();
+    assert_eq!(plan.status, PaymentPlanStatus::Completed);
+    assert!(!TicketInstance::awaiting_payment_plan(&ticket_ids, connection).unwrap());
+
+    // Tickets keep the redeem keys issued with the first installment
+    let reloaded_tickets = TicketInstance::find_for_user(user.id, connection).unwrap();
+    assert_equiv!(
+        reloaded_tickets
+            .iter()
+            .map(|t| t.redeem_key.clone())
+            .collect::<Vec<Option<String>>>(),
+        tickets
+            .iter()
+            .map(|t| t.redeem_key.clone())
+            .collect::<Vec<Option<String>>>()
+    );
+    assert!(TicketInstance::create_transfer(&user, &ticket_ids, None, None, false, connection).is_ok());
+}
+
+#[test]
+fn record_failed_attempt() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project
+        .create_organization()
+        .finish()
+        .update(
+            OrganizationEditableAttributes {
+                payment_plan_max_failed_attempts: Some(2),
+                ..Default::default()
+            },
+            None,
+            &"encryption_key".to_string(),
+            connection,
+        )
+        .unwrap();
+    let (user, order, payment_method) = create_payment_plan_order(&project, &organization);
+    let plan = PaymentPlan::create_for_order(&order, &payment_method, Some(user.id), connection).unwrap();
+    assert_eq!(plan.max_failed_attempts, 2);
+    let installments = plan.installments(connection).unwrap();
+    installments[0]
+        .mark_paid(
+            PaymentProviders::Stripe,
+            "ch_1".to_string(),
+            json!({}),
+            Some(user.id),
+            connection,
+        )
+        .unwrap();
+    let ticket_ids = TicketInstance::find_ids_for_order(order.id, connection).unwrap();
+
+    // First failure schedules a retry
+    let plan = installments[1]
+        .record_failed_attempt("Your card was declined.", connection)
+        .unwrap();
+    assert_eq!(plan.status, PaymentPlanStatus::Active);
+    let installment = PaymentPlanInstallment::find(installments[1].id, connection).unwrap();
+    assert_eq!(installment.failed_attempts, 1);
+    assert_eq!(
+        installment.last_failure_reason,
+        Some("Your card was declined.".to_string())
+    );
+    let actions = DomainAction::find_by_resource(
+        Some(Tables::PaymentPlanInstallments),
+        Some(installment.id),
+        DomainActionTypes::ChargePaymentPlanInstallment,
+        DomainActionStatus::Pending,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(actions.len(), 2);
+
+    // Final failure cancels the plan and releases the tickets
+    let plan = installment
+        .record_failed_attempt("Your card was declined.", connection)
+        .unwrap();
+    assert_eq!(plan.status, PaymentPlanStatus::Cancelled);
+    let order = Order::find(order.id, connection).unwrap();
+    assert_eq!(order.status, OrderStatus::Cancelled);
+    assert!(TicketInstance::find_ids_for_order(order.id, connection)
+        .unwrap()
+        .is_empty());
+    for ticket in TicketInstance::find_by_ids(&ticket_ids, connection).unwrap() {
+        assert_eq!(ticket.status, TicketInstanceStatus::Available);
+        assert!(ticket.redeem_key.is_none());
+    }
+    let installments = plan.installments(connection).unwrap();
+    assert_eq!(installments[0].status, PaymentPlanInstallmentStatus::Paid);
+    assert_eq!(installments[1].status, PaymentPlanInstallmentStatus::Cancelled);
+    assert_eq!(installments[2].status, PaymentPlanInstallmentStatus::Cancelled);
+
+    let domain_events = DomainEvent::find(
+        Tables::PaymentPlans,
+        Some(plan.id),
+        Some(DomainEventTypes::PaymentPlanCancelled),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(1, domain_events.len());
+
+    // Collected installments are kept
+    assert_eq!(order.total_paid(connection).unwrap(), installments[0].amount_in_cents);
+    assert!(payment_method.destroy(user.id, connection).is_ok());
+}
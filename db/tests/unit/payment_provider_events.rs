@@ -0,0 +1,77 @@
+use db::dev::TestProject;
+use db::models::*;
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let payload = json!({"id": "evt_1", "type": "charge.refunded"});
+
+    let event = PaymentProviderEvent::create(
+        PaymentProviders::Stripe,
+        "evt_1".to_string(),
+        "charge.refunded".to_string(),
+        payload.clone(),
+    )
+    .commit(connection)
+    .unwrap()
+    .unwrap();
+    assert_eq!(event.provider, PaymentProviders::Stripe);
+    assert_eq!(event.external_id, "evt_1");
+    assert_eq!(event.payload, payload);
+    assert!(event.processed_at.is_none());
+    assert_eq!(PaymentProviderEvent::find(event.id, connection).unwrap(), event);
+
+    let domain_actions = DomainAction::find_by_resource(
+        Some(Tables::PaymentProviderEvents),
+        Some(event.id),
+        DomainActionTypes::ProcessPaymentProviderEvent,
+        DomainActionStatus::Pending,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_actions.len(), 1);
+
+    // Repeated deliveries of the same event are ignored
+    assert!(PaymentProviderEvent::create(
+        PaymentProviders::Stripe,
+        "evt_1".to_string(),
+        "charge.refunded".to_string(),
+        payload.clone(),
+    )
+    .commit(connection)
+    .unwrap()
+    .is_none());
+    let domain_actions =
+        DomainAction::find_pending(Some(DomainActionTypes::ProcessPaymentProviderEvent), connection).unwrap();
+    assert_eq!(domain_actions.len(), 1);
+
+    // Event ids are only unique per provider
+    assert!(PaymentProviderEvent::create(
+        PaymentProviders::Globee,
+        "evt_1".to_string(),
+        "charge.refunded".to_string(),
+        payload,
+    )
+    .commit(connection)
+    .unwrap()
+    .is_some());
+}
+
+#[test]
+fn mark_processed() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = PaymentProviderEvent::create(
+        PaymentProviders::Stripe,
+        "evt_1".to_string(),
+        "charge.refunded".to_string(),
+        json!({}),
+    )
+    .commit(connection)
+    .unwrap()
+    .unwrap();
+
+    let event = event.mark_processed(connection).unwrap();
+    assert!(event.processed_at.is_some());
+}
@@ -184,6 +184,38 @@ fn mark_pending_ipn() {
     assert!(payment.mark_pending_ipn(Some(user.id), connection).is_ok(), true)
 }
 
+#[test]
+fn mark_authorized() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_event_fee().with_fees().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let user = project.create_user().finish();
+    let payment = project
+        .create_payment()
+        .with_user(&user)
+        .with_organization(&organization)
+        .with_event(&event)
+        .with_status(PaymentStatus::Requested)
+        .finish();
+
+    let payment = payment
+        .mark_authorized("charge-1", json!(null), Some(user.id), connection)
+        .unwrap();
+    assert_eq!(payment.status, PaymentStatus::Authorized);
+    assert_eq!(payment.external_reference, Some("charge-1".to_string()));
+
+    // Only requested payments are awaiting authorization
+    assert!(payment
+        .mark_authorized("charge-2", json!(null), Some(user.id), connection)
+        .is_err());
+}
+
 #[test]
 fn mark_cancelled() {
     let project = TestProject::new();
@@ -208,3 +240,218 @@ fn mark_cancelled() {
         true
     )
 }
+
+#[test]
+fn find_by_external_reference() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_event_fee().with_fees().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let user = project.create_user().finish();
+    let payment = project
+        .create_payment()
+        .with_user(&user)
+        .with_organization(&organization)
+        .with_event(&event)
+        .finish();
+    let refund = project.create_refund().finish();
+    payment.log_refund(user.id, &refund, 100, None, connection).unwrap();
+
+    // Refunds share the external reference of the payment they were made against
+    let found_payment = Payment::find_by_external_reference(PaymentProviders::External, "Test", connection).unwrap();
+    assert_eq!(payment, found_payment);
+    assert!(Payment::find_by_external_reference(PaymentProviders::Stripe, "Test", connection).is_err());
+}
+
+#[test]
+fn record_external_refund() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_event_fee().with_fees().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let user = project.create_user().finish();
+    let payment = project
+        .create_payment()
+        .with_user(&user)
+        .with_organization(&organization)
+        .with_event(&event)
+        .finish();
+
+    let refund = payment
+        .record_external_refund(
+            "re_1",
+            100,
+            Some("requested_by_customer".to_string()),
+            json!({"id": "re_1", "amount": 100}),
+            connection,
+        )
+        .unwrap()
+        .unwrap();
+    assert_eq!(refund.order_id, payment.order_id);
+    assert_eq!(refund.user_id, user.id);
+    assert_eq!(refund.reason, Some("requested_by_customer".to_string()));
+    assert!(refund.manual_override);
+    assert_eq!(refund.external_reference, Some("re_1".to_string()));
+    // The provider does not say which items were refunded
+    assert!(refund.requires_item_review);
+    assert!(refund.items(connection).unwrap().is_empty());
+
+    let order = Order::find(payment.order_id, connection).unwrap();
+    let refund_payments: Vec<Payment> = order
+        .payments(connection)
+        .unwrap()
+        .into_iter()
+        .filter(|p| p.refund_id == Some(refund.id))
+        .collect();
+    assert_eq!(refund_payments.len(), 1);
+    assert_eq!(refund_payments[0].amount, -100);
+    assert_eq!(refund_payments[0].status, PaymentStatus::Refunded);
+
+    // Already recorded
+    assert!(payment
+        .record_external_refund("re_1", 100, None, json!({"id": "re_1", "amount": 100}), connection)
+        .unwrap()
+        .is_none());
+    assert!(payment
+        .record_external_refund("re_1", 100, None, json!({"amount": 100}), connection)
+        .unwrap()
+        .is_none());
+
+    // Refunds made through the API are logged with the provider refund
+    let api_refund = project.create_refund().with_order(&order).for_user(&user).finish();
+    payment
+        .log_refund(
+            user.id,
+            &api_refund,
+            50,
+            Some(json!({"id": "re_2", "amount": 50})),
+            connection,
+        )
+        .unwrap();
+    assert!(payment
+        .record_external_refund("re_2", 50, None, json!({"id": "re_2", "amount": 50}), connection)
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn record_dispute() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_event_fee().with_fees().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let user = project.create_user().finish();
+    let payment = project
+        .create_payment()
+        .with_user(&user)
+        .with_organization(&organization)
+        .with_event(&event)
+        .finish();
+
+    // Without a pending settlement the dispute is recorded without an adjustment
+    assert!(payment
+        .record_dispute("dp_1", 500, json!({"id": "dp_1"}), connection)
+        .unwrap()
+        .is_none());
+    let domain_events = DomainEvent::find(
+        Tables::Payments,
+        Some(payment.id),
+        Some(DomainEventTypes::PaymentDisputeCreated),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+
+    let settlement = project.create_settlement().with_organization(&organization).finish();
+    let adjustment = payment
+        .record_dispute("dp_2", 500, json!({"id": "dp_2"}), connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(adjustment.settlement_id, settlement.id);
+    assert_eq!(
+        adjustment.settlement_adjustment_type,
+        SettlementAdjustmentTypes::Chargeback
+    );
+    assert_eq!(adjustment.amount_in_cents, 500);
+    assert_eq!(adjustment.payment_id, Some(payment.id));
+    assert_eq!(adjustment.external_reference, Some("dp_2".to_string()));
+
+    // A dispute only results in one chargeback
+    let adjustment2 = payment
+        .record_dispute("dp_2", 500, json!({"id": "dp_2"}), connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(adjustment, adjustment2);
+    assert_eq!(settlement.adjustments(connection).unwrap(), vec![adjustment]);
+}
+
+#[test]
+fn record_dispute_closed() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_event_fee().with_fees().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let user = project.create_user().finish();
+    let payment = project
+        .create_payment()
+        .with_user(&user)
+        .with_organization(&organization)
+        .with_event(&event)
+        .finish();
+    let settlement = project.create_settlement().with_organization(&organization).finish();
+    let chargeback = payment
+        .record_dispute("dp_1", 500, json!({"id": "dp_1"}), connection)
+        .unwrap()
+        .unwrap();
+    payment
+        .record_dispute("dp_2", 700, json!({"id": "dp_2"}), connection)
+        .unwrap()
+        .unwrap();
+
+    // Lost disputes keep the chargeback
+    assert!(payment
+        .record_dispute_closed("dp_2", false, json!({"id": "dp_2"}), connection)
+        .unwrap()
+        .is_none());
+
+    let credit = payment
+        .record_dispute_closed("dp_1", true, json!({"id": "dp_1"}), connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(credit.settlement_id, settlement.id);
+    assert_eq!(
+        credit.settlement_adjustment_type,
+        SettlementAdjustmentTypes::ManualCredit
+    );
+    assert_eq!(credit.amount_in_cents, chargeback.amount_in_cents);
+    assert_eq!(credit.external_reference, Some("dp_1".to_string()));
+
+    let domain_events = DomainEvent::find(
+        Tables::Payments,
+        Some(payment.id),
+        Some(DomainEventTypes::PaymentDisputeClosed),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 2);
+}
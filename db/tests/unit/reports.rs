@@ -144,6 +144,7 @@ fn find_event_reports_for_processing() {
             false,
             None,
             None,
+            None,
             connection,
         )
         .unwrap();
@@ -157,6 +158,7 @@ fn find_event_reports_for_processing() {
             false,
             None,
             None,
+            None,
             connection,
         )
         .unwrap();
@@ -170,6 +172,7 @@ fn find_event_reports_for_processing() {
             false,
             None,
             None,
+            None,
             connection,
         )
         .unwrap();
@@ -1716,6 +1719,7 @@ fn box_office_sales_summary_report() {
                     BoxOfficeSalesSummaryOperatorEventRow {
                         event_name: Some("Event1".to_string()),
                         event_date: Some(NaiveDate::from_ymd(2016, 7, 8).and_hms(9, 10, 11)),
+                        currency: "USD".to_string(),
                         number_of_tickets: 2,
                         face_value_in_cents: 150,
                         revenue_share_value_in_cents: 0,
@@ -1724,6 +1728,7 @@ fn box_office_sales_summary_report() {
                     BoxOfficeSalesSummaryOperatorEventRow {
                         event_name: Some("Event2".to_string()),
                         event_date: Some(NaiveDate::from_ymd(2017, 7, 8).and_hms(9, 10, 11)),
+                        currency: "USD".to_string(),
                         number_of_tickets: 2,
                         face_value_in_cents: 150,
                         revenue_share_value_in_cents: 0,
@@ -1733,16 +1738,19 @@ fn box_office_sales_summary_report() {
                 payments: vec![
                     BoxOfficeSalesSummaryPaymentRow {
                         payment_type: ExternalPaymentType::Cash,
+                        currency: "USD".to_string(),
                         quantity: 1,
                         total_sales_in_cents: 150,
                     },
                     BoxOfficeSalesSummaryPaymentRow {
                         payment_type: ExternalPaymentType::CreditCard,
+                        currency: "USD".to_string(),
                         quantity: 1,
                         total_sales_in_cents: 150,
                     },
                     BoxOfficeSalesSummaryPaymentRow {
                         payment_type: ExternalPaymentType::Voucher,
+                        currency: "USD".to_string(),
                         quantity: 2,
                         total_sales_in_cents: 300,
                     },
@@ -1755,6 +1763,7 @@ fn box_office_sales_summary_report() {
                     BoxOfficeSalesSummaryOperatorEventRow {
                         event_name: Some("Event1".to_string()),
                         event_date: Some(NaiveDate::from_ymd(2016, 7, 8).and_hms(9, 10, 11)),
+                        currency: "USD".to_string(),
                         number_of_tickets: 1,
                         face_value_in_cents: 140,
                         revenue_share_value_in_cents: 0,
@@ -1763,6 +1772,7 @@ fn box_office_sales_summary_report() {
                     BoxOfficeSalesSummaryOperatorEventRow {
                         event_name: Some("Event1".to_string()),
                         event_date: Some(NaiveDate::from_ymd(2016, 7, 8).and_hms(9, 10, 11)),
+                        currency: "USD".to_string(),
                         number_of_tickets: 2,
                         face_value_in_cents: 150,
                         revenue_share_value_in_cents: 0,
@@ -1771,6 +1781,7 @@ fn box_office_sales_summary_report() {
                 ],
                 payments: vec![BoxOfficeSalesSummaryPaymentRow {
                     payment_type: ExternalPaymentType::Cash,
+                    currency: "USD".to_string(),
                     quantity: 3,
                     total_sales_in_cents: 440,
                 }],
@@ -1779,16 +1790,19 @@ fn box_office_sales_summary_report() {
         payments: vec![
             BoxOfficeSalesSummaryPaymentRow {
                 payment_type: ExternalPaymentType::Cash,
+                currency: "USD".to_string(),
                 quantity: 4,
                 total_sales_in_cents: 590,
             },
             BoxOfficeSalesSummaryPaymentRow {
                 payment_type: ExternalPaymentType::CreditCard,
+                currency: "USD".to_string(),
                 quantity: 1,
                 total_sales_in_cents: 150,
             },
             BoxOfficeSalesSummaryPaymentRow {
                 payment_type: ExternalPaymentType::Voucher,
+                currency: "USD".to_string(),
                 quantity: 2,
                 total_sales_in_cents: 300,
             },
@@ -1825,6 +1839,9 @@ fn build_transaction_report_row(
         client_fee_in_cents_total: fee_schedule_range.client_fee_in_cents * quantity,
         event_fee_client_in_cents: organization.client_event_fee_in_cents,
         event_fee_client_in_cents_total: organization.client_event_fee_in_cents,
+        tax_in_cents: 0,
+        tax_in_cents_total: 0,
+        currency: "USD".to_string(),
         fee_range_id: Some(fee_schedule_range.id),
         order_type: OrderTypes::Cart,
         payment_method: Some(PaymentMethods::CreditCard.to_string()),
@@ -0,0 +1,68 @@
+use db::dev::TestProject;
+use db::prelude::*;
+
+#[test]
+fn create() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let user = project.create_user().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(2)
+        .is_paid()
+        .finish();
+    let tickets = TicketInstance::find_for_user(user.id, connection).unwrap();
+
+    let manifest = ScannerManifest::create(event.id, None, "secret", connection).unwrap();
+    assert_eq!(manifest.event_id, event.id);
+    assert_eq!(manifest.tickets.len(), 2);
+    for ticket in tickets {
+        let manifest_ticket = manifest.tickets.iter().find(|t| t.id == ticket.id).unwrap();
+        assert_eq!(
+            manifest_ticket.redeem_key_hash,
+            Some(ScannerManifest::hash_redeem_key(
+                ticket.id,
+                ticket.redeem_key.as_ref().unwrap(),
+                "secret"
+            ))
+        );
+        // Scanners holding a different secret cannot match the key
+        assert_ne!(
+            manifest_ticket.redeem_key_hash,
+            Some(ScannerManifest::hash_redeem_key(
+                ticket.id,
+                ticket.redeem_key.as_ref().unwrap(),
+                "other-secret"
+            ))
+        );
+    }
+}
+
+#[test]
+fn verify_signature() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let user = project.create_user().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(1)
+        .is_paid()
+        .finish();
+
+    let mut manifest = ScannerManifest::create(event.id, None, "secret", connection).unwrap();
+    assert!(manifest.verify_signature("secret").unwrap());
+    assert!(!manifest.verify_signature("other-secret").unwrap());
+
+    // Tampering with the ticket list invalidates the signature
+    manifest.tickets[0].status = TicketInstanceStatus::Redeemed;
+    assert!(!manifest.verify_signature("secret").unwrap());
+
+    manifest.signature = "not-hex".to_string();
+    assert!(!manifest.verify_signature("secret").unwrap());
+}
@@ -101,6 +101,27 @@ fn find_last_settlement_for_organization() {
     );
 }
 
+#[test]
+fn find_pending_for_organization() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    project
+        .create_settlement()
+        .with_organization(&organization)
+        .finalized()
+        .finish();
+    assert!(Settlement::find_pending_for_organization(organization.id, connection)
+        .unwrap()
+        .is_none());
+
+    let settlement = project.create_settlement().with_organization(&organization).finish();
+    assert_eq!(
+        Settlement::find_pending_for_organization(organization.id, connection).unwrap(),
+        Some(settlement)
+    );
+}
+
 #[test]
 fn process_settlement_for_organization() {
     let project = TestProject::new();
@@ -1352,3 +1373,145 @@ fn find_for_organization() {
     assert_eq!(settlements.len(), 1);
     assert_eq!(settlements[0].id, settlement.id);
 }
+
+#[test]
+fn settlement_gift_card_redemptions() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let mut order = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(2)
+        .finish();
+    let total = order.calculate_total(connection).unwrap();
+    let gift_card = GiftCard::create(
+        organization.id,
+        None,
+        total - 100,
+        organization.currency.clone(),
+        None,
+        user.id,
+    )
+    .commit(connection)
+    .unwrap();
+    order.add_gift_card_payment(&gift_card, user.id, connection).unwrap();
+    order
+        .add_external_payment(
+            Some("Test".to_string()),
+            ExternalPaymentType::CreditCard,
+            user.id,
+            100,
+            connection,
+        )
+        .unwrap();
+    assert_eq!(order.status, OrderStatus::Paid);
+
+    let settlement = Settlement::create(
+        organization.id,
+        dates::now().add_days(-5).finish(),
+        dates::now().add_days(2).finish(),
+        SettlementStatus::PendingSettlement,
+        None,
+        false,
+    )
+    .commit(None, connection)
+    .unwrap();
+
+    // Tickets are recognised as revenue while the redeemed gift card value is deducted
+    let display_settlement = settlement.for_display(connection).unwrap();
+    let event_entries = &display_settlement.event_entries[0].entries;
+    assert!(event_entries
+        .iter()
+        .any(|e| e.settlement_entry_type == SettlementEntryTypes::TicketType && e.online_sold_quantity == 2));
+    let gift_card_entry = event_entries
+        .iter()
+        .find(|e| e.settlement_entry_type == SettlementEntryTypes::GiftCardRedemptions)
+        .unwrap();
+    assert_eq!(gift_card_entry.event_id, event.id);
+    assert_eq!(gift_card_entry.ticket_type_id, None);
+    assert_eq!(gift_card_entry.total_sales_in_cents, -(total - 100));
+    assert_eq!(gift_card_entry.currency, gift_card.currency);
+}
+
+#[test]
+fn settlement_resale() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_resale_fee(10f32).finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let seller = project.create_user().finish();
+    let buyer = project.create_user().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&seller)
+        .quantity(2)
+        .is_paid()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let wallet = seller.default_wallet(connection).unwrap();
+    let mut listing = Listing::create("Listing".to_string(), seller.id, 1000)
+        .commit(connection)
+        .unwrap();
+    listing
+        .add_tickets(Some(seller.id), wallet.id, ticket_type.id, 2, connection)
+        .unwrap();
+    let listing = listing.publish(Some(seller.id), connection).unwrap();
+    let mut cart = Order::find_or_create_cart(&buyer, connection).unwrap();
+    cart.add_listing(buyer.id, listing.id, connection).unwrap();
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(
+        Some("Test".to_string()),
+        ExternalPaymentType::CreditCard,
+        buyer.id,
+        total,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(cart.status, OrderStatus::Paid);
+
+    let settlement = Settlement::create(
+        organization.id,
+        dates::now().add_days(-5).finish(),
+        dates::now().add_days(2).finish(),
+        SettlementStatus::PendingSettlement,
+        None,
+        false,
+    )
+    .commit(None, connection)
+    .unwrap();
+
+    // Tickets are settled at face value once, the resale only settles the organization's resale fee
+    let display_settlement = settlement.for_display(connection).unwrap();
+    let event_entries = &display_settlement.event_entries[0].entries;
+    let ticket_type_entries: Vec<&DisplaySettlementEntry> = event_entries
+        .iter()
+        .filter(|e| e.settlement_entry_type == SettlementEntryTypes::TicketType)
+        .collect();
+    assert_eq!(ticket_type_entries.len(), 1);
+    assert_eq!(ticket_type_entries[0].online_sold_quantity, 2);
+    assert_eq!(ticket_type_entries[0].ticket_type_id, Some(ticket_type.id));
+    let resale_entry = event_entries
+        .iter()
+        .find(|e| e.settlement_entry_type == SettlementEntryTypes::ResaleFees)
+        .unwrap();
+    assert_eq!(resale_entry.event_id, event.id);
+    assert_eq!(resale_entry.ticket_type_id, None);
+    assert_eq!(resale_entry.total_sales_in_cents, 200);
+    assert_eq!(
+        Order::find(cart.id, connection).unwrap().settlement_id,
+        Some(settlement.id)
+    );
+}
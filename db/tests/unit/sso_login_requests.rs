@@ -0,0 +1,91 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use db::dev::TestProject;
+use db::prelude::*;
+use db::schema::sso_login_requests;
+use db::utils::oidc;
+use diesel;
+use diesel::prelude::*;
+use std::collections::HashMap;
+
+fn create_configuration(project: &TestProject) -> OrganizationSsoConfiguration {
+    let organization = project.create_organization().finish();
+    OrganizationSsoConfiguration::create(
+        organization.id,
+        "https://login.example.com".to_string(),
+        "client-id".to_string(),
+        "client-secret".to_string(),
+        Roles::OrgMember,
+        None,
+        HashMap::new(),
+    )
+    .commit("encryption_key", None, project.get_connection())
+    .unwrap()
+}
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let configuration = create_configuration(&project);
+    let user = project.create_user().finish();
+
+    let login_request = SsoLoginRequest::create(configuration.id, "https://example.com/sso".to_string(), Some(user.id))
+        .commit(connection)
+        .unwrap();
+    assert_eq!(login_request.organization_sso_configuration_id, configuration.id);
+    assert_eq!(login_request.user_id, Some(user.id));
+    assert_eq!(login_request.redirect_uri, "https://example.com/sso");
+    assert!(login_request.consumed_at.is_none());
+    assert!(login_request.expires_at > Utc::now().naive_utc());
+    assert_ne!(login_request.state, login_request.nonce);
+    assert_eq!(
+        login_request.code_challenge(),
+        oidc::pkce_challenge(&login_request.code_verifier)
+    );
+    assert_eq!(
+        login_request.organization_sso_configuration(connection).unwrap(),
+        configuration
+    );
+}
+
+#[test]
+fn consume() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let configuration = create_configuration(&project);
+    let login_request = SsoLoginRequest::create(configuration.id, "https://example.com/sso".to_string(), None)
+        .commit(connection)
+        .unwrap();
+
+    assert!(SsoLoginRequest::consume("unknown", connection).unwrap().is_none());
+
+    let consumed = SsoLoginRequest::consume(&login_request.state, connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(consumed.id, login_request.id);
+    assert!(consumed.consumed_at.is_some());
+
+    // Requests can only be used once
+    assert!(SsoLoginRequest::consume(&login_request.state, connection)
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn consume_expired() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let configuration = create_configuration(&project);
+    let login_request = SsoLoginRequest::create(configuration.id, "https://example.com/sso".to_string(), None)
+        .commit(connection)
+        .unwrap();
+    diesel::update(sso_login_requests::table.filter(sso_login_requests::id.eq(login_request.id)))
+        .set(sso_login_requests::expires_at.eq(Utc::now().naive_utc() - Duration::minutes(1)))
+        .execute(connection)
+        .unwrap();
+
+    assert!(SsoLoginRequest::consume(&login_request.state, connection)
+        .unwrap()
+        .is_none());
+}
@@ -0,0 +1,80 @@
+use db::dev::TestProject;
+use db::prelude::*;
+
+#[test]
+fn create_multiple() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let stage = project.create_stage().with_venue_id(venue.id).finish();
+    let section = project.create_stage_section().with_stage_id(stage.id).finish();
+
+    let seats = StageSeat::create_multiple(
+        &[
+            StageSeat::create(section.id, "B".to_string(), "1".to_string()),
+            StageSeat::create(section.id, "B".to_string(), "2".to_string()),
+        ],
+        connection,
+    )
+    .unwrap();
+
+    assert_eq!(seats.len(), 2);
+    assert_eq!(
+        StageSeat::find_by_stage_section_id(section.id, connection).unwrap(),
+        seats
+    );
+
+    // Seats must be unique per row in a section
+    let result = StageSeat::create(section.id, "B".to_string(), "1".to_string()).commit(connection);
+    assert!(result.is_err());
+}
+
+#[test]
+fn find_for_display() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let stage = project.create_stage().with_venue_id(venue.id).finish();
+    let section = project
+        .create_stage_section()
+        .with_name("Balcony".to_string())
+        .with_stage_id(stage.id)
+        .with_seats(2)
+        .finish();
+    let seats = section.seats(connection).unwrap();
+
+    let display_seats = StageSeat::find_for_display(&[seats[1].id], connection).unwrap();
+    assert_eq!(
+        display_seats,
+        vec![DisplaySeat {
+            id: seats[1].id,
+            section_name: "Balcony".to_string(),
+            row_name: "A".to_string(),
+            seat_number: "2".to_string(),
+        }]
+    );
+    assert_eq!(display_seats[0].label(), "Balcony A-2".to_string());
+}
+
+#[test]
+fn destroy() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let stage = project.create_stage().with_venue_id(venue.id).finish();
+    let section = project
+        .create_stage_section()
+        .with_stage_id(stage.id)
+        .with_seats(2)
+        .finish();
+    let seats = section.seats(connection).unwrap();
+    let event = project.create_event().with_venue(&venue).with_ticket_pricing().finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    ticket_type.assign_seats(&[seats[0].id], None, connection).unwrap();
+
+    assert!(seats[0].is_assigned(connection).unwrap());
+    assert!(seats[0].destroy(connection).is_err());
+
+    assert!(!seats[1].is_assigned(connection).unwrap());
+    assert_eq!(seats[1].destroy(connection).unwrap(), 1);
+}
@@ -0,0 +1,105 @@
+use db::dev::TestProject;
+use db::prelude::*;
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let stage = project.create_stage().with_venue_id(venue.id).finish();
+    let section = StageSection::create(stage.id, "Balcony".to_string())
+        .commit(connection)
+        .unwrap();
+
+    assert_eq!(section.name, "Balcony".to_string());
+    assert_eq!(section.stage_id, stage.id);
+    assert_eq!(section.id.to_string().is_empty(), false);
+}
+
+#[test]
+fn update() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let stage = project.create_stage().with_venue_id(venue.id).finish();
+    let section = project.create_stage_section().with_stage_id(stage.id).finish();
+
+    let parameters = StageSectionEditableAttributes {
+        name: Some("Mezzanine".to_string()),
+    };
+    let section = section.update(parameters, connection).unwrap();
+    assert_eq!(section.name, "Mezzanine".to_string());
+}
+
+#[test]
+fn find_by_stage_id() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let stage = project.create_stage().with_venue_id(venue.id).finish();
+    let stage2 = project.create_stage().with_venue_id(venue.id).finish();
+    let section = project
+        .create_stage_section()
+        .with_name("Balcony".to_string())
+        .with_stage_id(stage.id)
+        .finish();
+    let section2 = project
+        .create_stage_section()
+        .with_name("Floor".to_string())
+        .with_stage_id(stage.id)
+        .finish();
+    project.create_stage_section().with_stage_id(stage2.id).finish();
+
+    assert_eq!(
+        StageSection::find_by_stage_id(stage.id, connection).unwrap(),
+        vec![section, section2]
+    );
+}
+
+#[test]
+fn seat_map() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let stage = project.create_stage().with_venue_id(venue.id).finish();
+    let section = project
+        .create_stage_section()
+        .with_stage_id(stage.id)
+        .with_seats(3)
+        .finish();
+
+    let seat_map = stage.seat_map(connection).unwrap();
+    assert_eq!(seat_map.len(), 1);
+    assert_eq!(seat_map[0].id, section.id);
+    assert_eq!(
+        seat_map[0]
+            .seats
+            .iter()
+            .map(|s| (s.row_name.clone(), s.seat_number.clone()))
+            .collect::<Vec<(String, String)>>(),
+        vec![
+            ("A".to_string(), "1".to_string()),
+            ("A".to_string(), "2".to_string()),
+            ("A".to_string(), "3".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn destroy() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let stage = project.create_stage().with_venue_id(venue.id).finish();
+    let section = project
+        .create_stage_section()
+        .with_stage_id(stage.id)
+        .with_seats(2)
+        .finish();
+
+    assert!(section.destroy(connection).unwrap() > 0);
+    assert!(StageSection::find(section.id, connection).is_err());
+    assert!(StageSeat::find_by_stage_section_id(section.id, connection)
+        .unwrap()
+        .is_empty());
+}
@@ -0,0 +1,156 @@
+use db::dev::TestProject;
+use db::models::*;
+use db::utils::errors::ErrorCode::ValidationError;
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+
+    let tax_rule = TaxRule::create("Sales Tax".to_string(), Some(venue.id), None, 8.5, true, false)
+        .commit(connection)
+        .unwrap();
+    assert_eq!(tax_rule.name, "Sales Tax".to_string());
+    assert_eq!(tax_rule.venue_id, Some(venue.id));
+    assert_eq!(tax_rule.region_id, None);
+    assert_eq!(tax_rule.rate_percent, 8.5);
+    assert!(tax_rule.applies_to_fees);
+    assert!(!tax_rule.inclusive);
+
+    // Rules require a venue or region
+    let result = TaxRule::create("Sales Tax".to_string(), None, None, 8.5, false, false).commit(connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("venue_id"));
+                assert_eq!(errors["venue_id"][0].code, "venue_or_region_required");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    // Rate must be within range
+    let result = TaxRule::create("Sales Tax".to_string(), Some(venue.id), None, -1.0, false, false).commit(connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("rate_percent"));
+                assert_eq!(errors["rate_percent"][0].code, "rate_percent_out_of_range");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn update() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let region = project.create_region().finish();
+    let tax_rule = TaxRule::create("VAT".to_string(), None, Some(region.id), 20.0, false, true)
+        .commit(connection)
+        .unwrap();
+
+    let tax_rule = tax_rule
+        .update(
+            TaxRuleEditableAttributes {
+                rate_percent: Some(15.0),
+                applies_to_fees: Some(true),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+    assert_eq!(tax_rule.rate_percent, 15.0);
+    assert!(tax_rule.applies_to_fees);
+    assert!(tax_rule.inclusive);
+
+    let result = tax_rule.update(
+        TaxRuleEditableAttributes {
+            rate_percent: Some(100.0),
+            ..Default::default()
+        },
+        connection,
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn destroy() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let tax_rule = TaxRule::create("Sales Tax".to_string(), Some(venue.id), None, 8.5, false, false)
+        .commit(connection)
+        .unwrap();
+    assert_eq!(
+        TaxRule::find_for_venue(venue.id, connection).unwrap(),
+        vec![tax_rule.clone()]
+    );
+
+    tax_rule.destroy(connection).unwrap();
+    assert!(TaxRule::find_for_venue(venue.id, connection).unwrap().is_empty());
+    assert!(TaxRule::find(tax_rule.id, connection).is_err());
+}
+
+#[test]
+fn find_for_event() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let region = project.create_region().finish();
+    let venue = project.create_venue().with_region(&region).finish();
+    let event = project.create_event().with_venue(&venue).finish();
+    let event_without_venue = project.create_event().finish();
+
+    assert!(TaxRule::find_for_event(&event, connection).unwrap().is_empty());
+
+    // Region rules apply when the venue has none of its own
+    let region_tax_rule = TaxRule::create("State Tax".to_string(), None, Some(region.id), 6.0, false, false)
+        .commit(connection)
+        .unwrap();
+    assert_eq!(
+        TaxRule::find_for_event(&event, connection).unwrap(),
+        vec![region_tax_rule.clone()]
+    );
+    assert_eq!(
+        TaxRule::find_for_region(region.id, connection).unwrap(),
+        vec![region_tax_rule]
+    );
+
+    // Venue rules take precedence
+    let venue_tax_rule = TaxRule::create("City Tax".to_string(), Some(venue.id), None, 2.0, false, false)
+        .commit(connection)
+        .unwrap();
+    assert_eq!(
+        TaxRule::find_for_event(&event, connection).unwrap(),
+        vec![venue_tax_rule]
+    );
+
+    assert!(TaxRule::find_for_event(&event_without_venue, connection)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn tax_for() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+
+    let tax_rule = TaxRule::create("Sales Tax".to_string(), Some(venue.id), None, 10.0, false, false)
+        .commit(connection)
+        .unwrap();
+    assert_eq!(tax_rule.tax_for(1000), 100);
+    assert_eq!(tax_rule.tax_for(155), 16);
+    assert_eq!(tax_rule.tax_for(0), 0);
+
+    let tax_rule = TaxRule::create("VAT".to_string(), Some(venue.id), None, 25.0, false, true)
+        .commit(connection)
+        .unwrap();
+    assert_eq!(tax_rule.tax_for(1250), 250);
+    assert_eq!(tax_rule.tax_for(1000), 200);
+    assert_eq!(tax_rule.tax_for(0), 0);
+}
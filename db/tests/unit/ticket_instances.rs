@@ -906,6 +906,7 @@ fn find() {
         transfer_address: None,
         check_in_source: None,
         promo_image_url: None,
+        seat: None,
     };
     assert_eq!(
         (display_event, None, expected_ticket),
@@ -977,6 +978,7 @@ fn find_show_no_token() {
         transfer_address: None,
         check_in_source: None,
         promo_image_url: None,
+        seat: None,
     };
     let (found_event, found_user, found_ticket) = TicketInstance::find_for_display(ticket.id, connection).unwrap();
     assert_eq!(
@@ -1318,6 +1320,102 @@ fn redeem_ticket() {
     assert_eq!(result, RedeemResults::TicketRedeemSuccess);
 }
 
+#[test]
+fn redeem_offline_tickets() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let door_person = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let other_event = project.create_event().with_ticket_pricing().finish();
+    let user = project.create_user().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(4)
+        .is_paid()
+        .finish();
+    let mut tickets = TicketInstance::find_for_user(user.id, connection).unwrap();
+    let ticket = tickets.remove(0);
+    let ticket2 = tickets.remove(0);
+    let ticket3 = tickets.remove(0);
+    let ticket4 = tickets.remove(0);
+
+    // Ticket 3 has a transfer pending from before it was scanned
+    TicketInstance::create_transfer(&user, &[ticket3.id], None, None, false, connection).unwrap();
+
+    let scanned_at = NaiveDateTime::from_timestamp((Utc::now() - Duration::minutes(5)).timestamp(), 0);
+    let redemptions = vec![
+        OfflineRedemption {
+            ticket_id: ticket.id,
+            redeem_key: ticket.redeem_key.clone().unwrap(),
+            scanned_at,
+        },
+        OfflineRedemption {
+            ticket_id: ticket2.id,
+            redeem_key: "WrongKey".to_string(),
+            scanned_at,
+        },
+        OfflineRedemption {
+            ticket_id: ticket3.id,
+            redeem_key: ticket3.redeem_key.clone().unwrap(),
+            scanned_at: Utc::now().naive_utc(),
+        },
+        // Scanned a second time by another scanner a minute later
+        OfflineRedemption {
+            ticket_id: ticket.id,
+            redeem_key: ticket.redeem_key.clone().unwrap(),
+            scanned_at: scanned_at + Duration::minutes(1),
+        },
+    ];
+    let results = TicketInstance::redeem_offline_tickets(event.id, &redemptions, door_person.id, connection).unwrap();
+    assert_eq!(results.len(), 4);
+    assert_eq!(results[0].ticket_id, ticket.id);
+    assert_eq!(results[0].status, OfflineRedemptionStatus::Redeemed);
+    assert_eq!(results[0].redeemed_at, Some(scanned_at));
+    assert_eq!(results[0].redeemed_by_user_id, Some(door_person.id));
+    assert!(!results[0].transferred_after_scan);
+    assert_eq!(results[1].ticket_id, ticket2.id);
+    assert_eq!(results[1].status, OfflineRedemptionStatus::Invalid);
+    assert_eq!(results[2].ticket_id, ticket.id);
+    assert_eq!(results[2].status, OfflineRedemptionStatus::AlreadyRedeemed);
+    assert_eq!(results[2].redeemed_at, Some(scanned_at));
+    assert_eq!(results[3].ticket_id, ticket3.id);
+    assert_eq!(results[3].status, OfflineRedemptionStatus::TransferInProcess);
+
+    let ticket = TicketInstance::find(ticket.id, connection).unwrap();
+    assert_eq!(ticket.status, TicketInstanceStatus::Redeemed);
+    assert_eq!(ticket.redeemed_at, Some(scanned_at));
+    assert_eq!(ticket.check_in_source, Some(CheckInSource::Scanned));
+    let ticket2 = TicketInstance::find(ticket2.id, connection).unwrap();
+    assert_eq!(ticket2.status, TicketInstanceStatus::Purchased);
+    let ticket3 = TicketInstance::find(ticket3.id, connection).unwrap();
+    assert_eq!(ticket3.status, TicketInstanceStatus::Purchased);
+
+    let domain_events = DomainEvent::find(
+        Tables::TicketInstances,
+        Some(ticket.id),
+        Some(DomainEventTypes::TicketInstanceRedeemed),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(1, domain_events.len());
+
+    // Ticket does not belong to the event being scanned
+    let results = TicketInstance::redeem_offline_tickets(
+        other_event.id,
+        &[OfflineRedemption {
+            ticket_id: ticket4.id,
+            redeem_key: ticket4.redeem_key.clone().unwrap(),
+            scanned_at,
+        }],
+        door_person.id,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(results[0].status, OfflineRedemptionStatus::Invalid);
+}
+
 #[test]
 fn organization() {
     let project = TestProject::new();
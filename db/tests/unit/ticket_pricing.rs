@@ -21,6 +21,7 @@ fn has_changes() {
         start_date: None,
         end_date: None,
         is_box_office_only: None,
+        sold_quantity_cap: None,
     }));
 
     assert!(!ticket_pricing.has_changes(&TicketPricingEditableAttributes {
@@ -29,6 +30,7 @@ fn has_changes() {
         start_date: Some(ticket_pricing.start_date),
         end_date: Some(ticket_pricing.end_date),
         is_box_office_only: Some(ticket_pricing.is_box_office_only),
+        sold_quantity_cap: Some(ticket_pricing.sold_quantity_cap),
     }));
 
     assert!(ticket_pricing.has_changes(&TicketPricingEditableAttributes {
@@ -37,6 +39,7 @@ fn has_changes() {
         start_date: None,
         end_date: None,
         is_box_office_only: None,
+        sold_quantity_cap: None,
     }));
 
     assert!(ticket_pricing.has_changes(&TicketPricingEditableAttributes {
@@ -45,6 +48,7 @@ fn has_changes() {
         start_date: None,
         end_date: None,
         is_box_office_only: None,
+        sold_quantity_cap: None,
     }));
 
     assert!(ticket_pricing.has_changes(&TicketPricingEditableAttributes {
@@ -53,6 +57,7 @@ fn has_changes() {
         start_date: Some(dates::now().add_days(-1).finish()),
         end_date: None,
         is_box_office_only: None,
+        sold_quantity_cap: None,
     }));
 
     assert!(ticket_pricing.has_changes(&TicketPricingEditableAttributes {
@@ -61,6 +66,7 @@ fn has_changes() {
         start_date: None,
         end_date: Some(dates::now().add_days(11).finish()),
         is_box_office_only: None,
+        sold_quantity_cap: None,
     }));
 
     assert!(ticket_pricing.has_changes(&TicketPricingEditableAttributes {
@@ -69,6 +75,7 @@ fn has_changes() {
         start_date: None,
         end_date: None,
         is_box_office_only: Some(true),
+        sold_quantity_cap: None,
     }));
 }
 
@@ -462,6 +469,7 @@ fn update() {
         start_date: Some(update_start_date),
         end_date: Some(update_end_date),
         is_box_office_only: Some(false),
+        sold_quantity_cap: None,
     };
     let updated_ticket_pricing = ticket_pricing.update(update_parameters, None, connection).unwrap();
     assert_eq!(updated_ticket_pricing.id, ticket_pricing.id);
@@ -519,6 +527,7 @@ fn update_with_affected_orders() {
         start_date: Some(ticket_pricing.start_date),
         end_date: Some(ticket_pricing.end_date),
         is_box_office_only: Some(false),
+        sold_quantity_cap: None,
     };
     let updated_ticket_pricing = ticket_pricing.update(update_parameters, None, connection).unwrap();
     assert_eq!(updated_ticket_pricing.id, ticket_pricing.id);
@@ -541,6 +550,7 @@ fn update_with_affected_orders() {
         start_date: Some(update_start_date),
         end_date: Some(update_end_date),
         is_box_office_only: Some(false),
+        sold_quantity_cap: None,
     };
     let updated_ticket_pricing = ticket_pricing.update(update_parameters, None, connection).unwrap();
 
@@ -702,3 +712,226 @@ fn get_current_ticket_capacity() {
     let ticket_capacity = ticket_types[0].valid_ticket_count(project.get_connection()).unwrap();
     assert_eq!(ticket_capacity, 100);
 }
+
+#[test]
+fn get_current_ticket_pricing_with_sold_quantity_cap() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_tickets().finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let early_bird = ticket_type
+        .add_ticket_pricing(
+            "Early bird".into(),
+            dates::now().add_days(-1).finish(),
+            dates::now().add_days(1).finish(),
+            100,
+            false,
+            Some(2),
+            None,
+            None,
+            connection,
+        )
+        .unwrap();
+    let standard = ticket_type
+        .add_ticket_pricing(
+            "Standard".into(),
+            dates::now().add_days(1).finish(),
+            dates::now().add_days(2).finish(),
+            150,
+            false,
+            None,
+            None,
+            None,
+            connection,
+        )
+        .unwrap();
+    let ticket_pricing = TicketPricing::get_current_ticket_pricing(ticket_type.id, false, false, connection).unwrap();
+    assert_eq!(ticket_pricing.id, early_bird.id);
+    assert_eq!(early_bird.sold_quantity(connection).unwrap(), 0);
+
+    project
+        .create_order()
+        .for_event(&event)
+        .for_tickets(ticket_type.id)
+        .quantity(2)
+        .is_paid()
+        .finish();
+    assert_eq!(early_bird.sold_quantity(connection).unwrap(), 2);
+
+    // The sale reaching the cap advanced sales to the next tier
+    let ticket_pricing = TicketPricing::get_current_ticket_pricing(ticket_type.id, false, false, connection).unwrap();
+    assert_eq!(ticket_pricing.id, standard.id);
+    assert!(ticket_pricing.start_date < standard.start_date);
+    let early_bird = TicketPricing::find(early_bird.id, connection).unwrap();
+    assert!(early_bird.end_date <= ticket_pricing.start_date);
+    let domain_events = DomainEvent::find(
+        Tables::TicketPricing,
+        Some(standard.id),
+        Some(DomainEventTypes::TicketPricingSalesStarted),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(1, domain_events.len());
+
+    // Tier has already advanced, subsequent lookups do not start sales again
+    let ticket_pricing = TicketPricing::get_current_ticket_pricing(ticket_type.id, false, false, connection).unwrap();
+    assert_eq!(ticket_pricing.id, standard.id);
+    assert!(early_bird.check_sold_quantity_cap(None, connection).unwrap());
+    let domain_events = DomainEvent::find(
+        Tables::TicketPricing,
+        Some(standard.id),
+        Some(DomainEventTypes::TicketPricingSalesStarted),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(1, domain_events.len());
+}
+
+#[test]
+fn sold_quantity_cap_validation() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_tickets().finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let result = ticket_type.add_ticket_pricing(
+        "Early bird".into(),
+        dates::now().add_days(-1).finish(),
+        dates::now().add_days(1).finish(),
+        100,
+        false,
+        Some(0),
+        None,
+        None,
+        connection,
+    );
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("ticket_pricing.sold_quantity_cap"));
+                assert_eq!(
+                    errors["ticket_pricing.sold_quantity_cap"][0].code,
+                    "sold_quantity_cap_must_be_positive"
+                );
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn get_current_ticket_pricing_with_sold_quantity_cap_is_read_only() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_tickets().finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let early_bird = ticket_type
+        .add_ticket_pricing(
+            "Early bird".into(),
+            dates::now().add_days(-1).finish(),
+            dates::now().add_days(1).finish(),
+            100,
+            false,
+            Some(2),
+            None,
+            None,
+            connection,
+        )
+        .unwrap();
+    let standard = ticket_type
+        .add_ticket_pricing(
+            "Standard".into(),
+            dates::now().add_days(1).finish(),
+            dates::now().add_days(2).finish(),
+            150,
+            false,
+            None,
+            None,
+            None,
+            connection,
+        )
+        .unwrap();
+    let order = project
+        .create_order()
+        .for_event(&event)
+        .for_tickets(ticket_type.id)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    // Reach the cap without going through the cart
+    diesel::sql_query("UPDATE order_items SET quantity = 2 WHERE order_id = $1 AND ticket_pricing_id = $2")
+        .bind::<sql_types::Uuid, _>(order.id)
+        .bind::<sql_types::Uuid, _>(early_bird.id)
+        .execute(connection)
+        .unwrap();
+
+    // Lookups skip the sold out tier without ending it
+    let ticket_pricing = TicketPricing::get_current_ticket_pricing(ticket_type.id, false, false, connection).unwrap();
+    assert_eq!(ticket_pricing.id, standard.id);
+    assert_eq!(
+        TicketPricing::find(early_bird.id, connection).unwrap().end_date,
+        early_bird.end_date
+    );
+    assert_eq!(
+        TicketPricing::find(standard.id, connection).unwrap().start_date,
+        standard.start_date
+    );
+}
+
+#[test]
+fn update_quantities_splits_quantity_crossing_sold_quantity_cap() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().with_tickets().finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let early_bird = ticket_type
+        .add_ticket_pricing(
+            "Early bird".into(),
+            dates::now().add_days(-1).finish(),
+            dates::now().add_days(1).finish(),
+            100,
+            false,
+            Some(2),
+            None,
+            None,
+            connection,
+        )
+        .unwrap();
+    let standard = ticket_type
+        .add_ticket_pricing(
+            "Standard".into(),
+            dates::now().add_days(1).finish(),
+            dates::now().add_days(2).finish(),
+            150,
+            false,
+            None,
+            None,
+            None,
+            connection,
+        )
+        .unwrap();
+    let order = project
+        .create_order()
+        .for_event(&event)
+        .for_tickets(ticket_type.id)
+        .quantity(3)
+        .finish();
+
+    // Only the tickets under the cap are sold at the capped tier's price
+    let mut items: Vec<OrderItem> = order
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .filter(|i| i.item_type == OrderItemTypes::Tickets)
+        .collect();
+    items.sort_by_key(|i| i.unit_price_in_cents);
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[0].ticket_pricing_id, Some(early_bird.id));
+    assert_eq!(items[0].quantity, 2);
+    assert_eq!(items[0].unit_price_in_cents, 100);
+    assert_eq!(items[1].ticket_pricing_id, Some(standard.id));
+    assert_eq!(items[1].quantity, 1);
+    assert_eq!(items[1].unit_price_in_cents, 150);
+    assert_eq!(early_bird.sold_quantity(connection).unwrap(), 2);
+    assert!(TicketPricing::find(early_bird.id, connection).unwrap().end_date <= dates::now().finish());
+}
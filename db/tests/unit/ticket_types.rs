@@ -38,6 +38,7 @@ fn status() {
             false,
             None,
             None,
+            None,
             connection,
         )
         .unwrap();
@@ -57,6 +58,7 @@ fn status() {
             false,
             None,
             None,
+            None,
             connection,
         )
         .unwrap();
@@ -75,6 +77,7 @@ fn status() {
             false,
             None,
             None,
+            None,
             connection,
         )
         .unwrap();
@@ -372,6 +375,68 @@ pub fn create_with_validation_errors() {
     }
 }
 
+#[test]
+fn assign_seats() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let venue = project.create_venue().finish();
+    let other_venue = project.create_venue().finish();
+    let stage = project.create_stage().with_venue_id(venue.id).finish();
+    let other_stage = project.create_stage().with_venue_id(other_venue.id).finish();
+    let section = project
+        .create_stage_section()
+        .with_stage_id(stage.id)
+        .with_seats(3)
+        .finish();
+    let other_section = project
+        .create_stage_section()
+        .with_stage_id(other_stage.id)
+        .with_seats(1)
+        .finish();
+    let seat_ids: Vec<Uuid> = section.seats(connection).unwrap().iter().map(|s| s.id).collect();
+    let other_seat_ids: Vec<Uuid> = other_section.seats(connection).unwrap().iter().map(|s| s.id).collect();
+    let event = project
+        .create_event()
+        .with_venue(&venue)
+        .with_ticket_type_count(2)
+        .with_a_specific_number_of_tickets(10)
+        .finish();
+    let ticket_types = event.ticket_types(true, None, connection).unwrap();
+
+    // Seats from another venue cannot be used
+    let result = ticket_types[0].assign_seats(&other_seat_ids, None, connection);
+    assert!(result.is_err());
+
+    let tickets = ticket_types[0].assign_seats(&seat_ids[0..2], None, connection).unwrap();
+    assert_eq!(tickets.len(), 2);
+    let mut assigned_seat_ids: Vec<Uuid> = tickets.iter().filter_map(|t| t.seat_id).collect();
+    assigned_seat_ids.sort();
+    let mut expected_seat_ids = seat_ids[0..2].to_vec();
+    expected_seat_ids.sort();
+    assert_eq!(assigned_seat_ids, expected_seat_ids);
+
+    // A seat can only be bound to one ticket per event
+    let result = ticket_types[1].assign_seats(&seat_ids[1..3], None, connection);
+    match result {
+        Ok(_) => {
+            panic!("Expected validation error");
+        }
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("seat_ids"));
+                assert_eq!(
+                    &errors["seat_ids"][0].message.clone().unwrap().into_owned(),
+                    "One or more seats have already been assigned to tickets for this event"
+                );
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    let tickets = ticket_types[1].assign_seats(&seat_ids[2..3], None, connection).unwrap();
+    assert_eq!(tickets[0].seat_id, Some(seat_ids[2]));
+}
+
 #[test]
 fn find_by_ids() {
     let project = TestProject::new();
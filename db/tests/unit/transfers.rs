@@ -1906,6 +1906,248 @@ fn complete() {
     );
 }
 
+#[test]
+fn decline() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    project.create_order().for_user(&user).quantity(3).is_paid().finish();
+    let user_tickets = TicketInstance::find_for_user(user.id, connection).unwrap();
+    let ticket = &user_tickets[0];
+    let ticket2 = &user_tickets[1];
+    let ticket3 = &user_tickets[2];
+    let transfer = TicketInstance::create_transfer(
+        &user,
+        &[ticket.id, ticket2.id],
+        Some("example@tari.com"),
+        Some(TransferMessageType::Email),
+        false,
+        connection,
+    )
+    .unwrap();
+
+    // Tickets not part of the transfer cannot be declined
+    let result = transfer.decline(Some(vec![ticket3.id]), None, connection);
+    assert_eq!(
+        result,
+        DatabaseError::business_process_error("Declined tickets must be part of the transfer",)
+    );
+
+    // Partially declined transfer splits off the declined ticket
+    let declined_transfer = transfer.decline(Some(vec![ticket2.id]), None, connection).unwrap();
+    assert_ne!(declined_transfer.id, transfer.id);
+    assert_eq!(declined_transfer.status, TransferStatus::Declined);
+    assert_eq!(declined_transfer.source_user_id, user.id);
+    assert_eq!(declined_transfer.transfer_address, transfer.transfer_address);
+    assert_eq!(
+        declined_transfer
+            .tickets(connection)
+            .unwrap()
+            .iter()
+            .map(|t| t.id)
+            .collect::<Vec<Uuid>>(),
+        vec![ticket2.id]
+    );
+    let transfer = Transfer::find(transfer.id, connection).unwrap();
+    assert_eq!(transfer.status, TransferStatus::Pending);
+    assert_eq!(
+        transfer
+            .tickets(connection)
+            .unwrap()
+            .iter()
+            .map(|t| t.id)
+            .collect::<Vec<Uuid>>(),
+        vec![ticket.id]
+    );
+    let domain_events = DomainEvent::find(
+        Tables::Transfers,
+        Some(declined_transfer.id),
+        Some(DomainEventTypes::TransferTicketDeclined),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(1, domain_events.len());
+
+    // Remaining tickets can still be accepted with the updated authorization
+    let user2 = project.create_user().finish();
+    let sender_wallet = Wallet::find_default_for_user(user.id, connection).unwrap();
+    let receiver_wallet = Wallet::find_default_for_user(user2.id, connection).unwrap();
+    TicketInstance::receive_ticket_transfer(
+        transfer.into_authorization(connection).unwrap(),
+        &sender_wallet,
+        user2.id,
+        receiver_wallet.id,
+        connection,
+    )
+    .unwrap();
+    let ticket2 = TicketInstance::find(ticket2.id, connection).unwrap();
+    assert_eq!(ticket2.wallet_id, sender_wallet.id);
+
+    // Declining the whole transfer keeps the existing transfer
+    let transfer =
+        TicketInstance::create_transfer(&user, &[ticket2.id, ticket3.id], None, None, false, connection).unwrap();
+    let declined_transfer = transfer.decline(None, Some(user2.id), connection).unwrap();
+    assert_eq!(declined_transfer.id, transfer.id);
+    assert_eq!(declined_transfer.status, TransferStatus::Declined);
+    let domain_events = DomainEvent::find(
+        Tables::Transfers,
+        Some(transfer.id),
+        Some(DomainEventTypes::TransferTicketDeclined),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(1, domain_events.len());
+    assert_eq!(domain_events[0].user_id, Some(user2.id));
+
+    // Declined transfers can no longer be accepted
+    let result = TicketInstance::receive_ticket_transfer(
+        declined_transfer.into_authorization(connection).unwrap(),
+        &sender_wallet,
+        user2.id,
+        receiver_wallet.id,
+        connection,
+    );
+    assert_eq!(
+        result,
+        Err(DatabaseError::new(
+            BusinessProcessError,
+            Some("The transfer has been declined.".to_string()),
+        ))
+    );
+
+    let result = declined_transfer.decline(None, None, connection);
+    assert_eq!(
+        result,
+        DatabaseError::business_process_error("Transfer cannot be declined as it is no longer pending",)
+    );
+}
+
+#[test]
+fn expire() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    project.create_order().for_user(&user).quantity(1).is_paid().finish();
+    let ticket = TicketInstance::find_for_user(user.id, connection).unwrap().remove(0);
+    let transfer = TicketInstance::create_transfer(&user, &[ticket.id], None, None, false, connection).unwrap();
+
+    let transfer = transfer.expire(connection).unwrap();
+    assert_eq!(transfer.status, TransferStatus::Expired);
+    let domain_events = DomainEvent::find(
+        Tables::Transfers,
+        Some(transfer.id),
+        Some(DomainEventTypes::TransferTicketExpired),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(1, domain_events.len());
+
+    // Tickets are free to be transferred again
+    assert!(TicketInstance::create_transfer(&user, &[ticket.id], None, None, false, connection).is_ok());
+
+    let result = transfer.expire(connection);
+    assert_eq!(
+        result,
+        DatabaseError::business_process_error("Transfer cannot be expired as it is no longer pending",)
+    );
+}
+
+#[test]
+fn schedule_expiry() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().with_transfer_expiry_hours(48).finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let event2 = project.create_event().with_ticket_pricing().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    project
+        .create_order()
+        .for_event(&event2)
+        .for_user(&user)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let user_tickets = TicketInstance::find_for_user(user.id, connection).unwrap();
+    let (tickets, tickets2): (Vec<TicketInstance>, Vec<TicketInstance>) = user_tickets
+        .into_iter()
+        .partition(|t| t.event(connection).unwrap().id == event.id);
+    let ticket = &tickets[0];
+    let ticket2 = &tickets2[0];
+
+    // Organization without an expiry leaves the transfer pending until the event ends
+    let transfer = TicketInstance::create_transfer(&user, &[ticket2.id], None, None, false, connection).unwrap();
+    assert_eq!(transfer.expires_at, None);
+    assert_eq!(transfer.expiry_hours(connection).unwrap(), None);
+    assert!(DomainAction::find_by_resource(
+        Some(Tables::Transfers),
+        Some(transfer.id),
+        DomainActionTypes::ExpireTransfer,
+        DomainActionStatus::Pending,
+        connection,
+    )
+    .unwrap()
+    .is_empty());
+
+    let transfer = TicketInstance::create_transfer(
+        &user,
+        &[ticket.id],
+        Some("example@tari.com"),
+        Some(TransferMessageType::Email),
+        false,
+        connection,
+    )
+    .unwrap();
+    let expires_at = transfer.created_at + Duration::hours(48);
+    assert_eq!(transfer.expiry_hours(connection).unwrap(), Some(48));
+    assert_eq!(transfer.expires_at, Some(expires_at));
+
+    let expire_actions = DomainAction::find_by_resource(
+        Some(Tables::Transfers),
+        Some(transfer.id),
+        DomainActionTypes::ExpireTransfer,
+        DomainActionStatus::Pending,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(expire_actions.len(), 1);
+    assert_eq!(expire_actions[0].scheduled_at, expires_at);
+
+    let reminder_actions = DomainAction::find_by_resource(
+        Some(Tables::Transfers),
+        Some(transfer.id),
+        DomainActionTypes::ProcessTransferDrip,
+        DomainActionStatus::Pending,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(reminder_actions.len(), 1);
+    assert_eq!(
+        reminder_actions[0].scheduled_at,
+        expires_at - Duration::hours(TRANSFER_EXPIRY_REMINDER_HOURS_PRIOR_TO_EXPIRY)
+    );
+    let payload: ProcessTransferDripPayload = serde_json::from_value(reminder_actions[0].payload.clone()).unwrap();
+    assert_eq!(
+        payload,
+        ProcessTransferDripPayload {
+            event_id: event.id,
+            source_or_destination: SourceOrDestination::Source,
+            expiry_reminder: true
+        }
+    );
+    assert!(transfer.expiry_drip_header(false).unwrap().contains("example@tari.com"));
+}
+
 #[test]
 fn create_commit() {
     let project = TestProject::new();
@@ -0,0 +1,60 @@
+use db::dev::TestProject;
+use db::prelude::*;
+
+#[test]
+fn record_failure() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+
+    for attempt in 1..MAX_FAILED_LOGIN_ATTEMPTS {
+        let throttle = UserLoginThrottle::record_failure(user.id, connection).unwrap();
+        assert_eq!(throttle.failed_attempts, attempt);
+        assert!(throttle.locked_until.is_none());
+        assert!(UserLoginThrottle::locked_until(user.id, connection).unwrap().is_none());
+    }
+
+    // Reaching the limit locks the user out and starts counting again
+    let throttle = UserLoginThrottle::record_failure(user.id, connection).unwrap();
+    assert_eq!(throttle.failed_attempts, 0);
+    assert!(throttle.locked_until.is_some());
+    assert_eq!(
+        UserLoginThrottle::locked_until(user.id, connection).unwrap(),
+        throttle.locked_until
+    );
+
+    let domain_events = DomainEvent::find(
+        Tables::Users,
+        Some(user.id),
+        Some(DomainEventTypes::UserLoginLocked),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(1, domain_events.len());
+}
+
+#[test]
+fn reset() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+
+    // Nothing to reset for a user without failed attempts
+    UserLoginThrottle::reset(user.id, connection).unwrap();
+    assert!(UserLoginThrottle::find_by_user_id(user.id, connection)
+        .unwrap()
+        .is_none());
+
+    for _ in 0..MAX_FAILED_LOGIN_ATTEMPTS {
+        UserLoginThrottle::record_failure(user.id, connection).unwrap();
+    }
+    assert!(UserLoginThrottle::locked_until(user.id, connection).unwrap().is_some());
+
+    UserLoginThrottle::reset(user.id, connection).unwrap();
+    let throttle = UserLoginThrottle::find_by_user_id(user.id, connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(throttle.failed_attempts, 0);
+    assert!(throttle.locked_until.is_none());
+    assert!(UserLoginThrottle::locked_until(user.id, connection).unwrap().is_none());
+}
@@ -0,0 +1,52 @@
+use db::dev::TestProject;
+use db::prelude::*;
+
+#[test]
+fn generate_for_user() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+
+    let codes = UserRecoveryCode::generate_for_user(user.id, connection).unwrap();
+    assert_eq!(codes.len(), 10);
+    let recovery_codes = UserRecoveryCode::find_unused_for_user(user.id, connection).unwrap();
+    assert_eq!(recovery_codes.len(), 10);
+    // Only hashes of the codes are stored
+    assert!(recovery_codes.iter().all(|r| !codes.contains(&r.code_hash)));
+
+    // Generating again replaces the existing codes
+    let new_codes = UserRecoveryCode::generate_for_user(user.id, connection).unwrap();
+    assert_eq!(
+        UserRecoveryCode::find_unused_for_user(user.id, connection)
+            .unwrap()
+            .len(),
+        10
+    );
+    assert!(!UserRecoveryCode::redeem(user.id, &codes[0], connection).unwrap());
+    assert!(UserRecoveryCode::redeem(user.id, &new_codes[0], connection).unwrap());
+}
+
+#[test]
+fn redeem() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    let codes = UserRecoveryCode::generate_for_user(user.id, connection).unwrap();
+
+    assert!(!UserRecoveryCode::redeem(user.id, "invalid", connection).unwrap());
+    // Codes belong to a single user
+    assert!(!UserRecoveryCode::redeem(user2.id, &codes[0], connection).unwrap());
+
+    // Codes are accepted regardless of case and surrounding whitespace
+    assert!(UserRecoveryCode::redeem(user.id, &format!(" {} ", codes[0].to_uppercase()), connection).unwrap());
+    assert_eq!(
+        UserRecoveryCode::find_unused_for_user(user.id, connection)
+            .unwrap()
+            .len(),
+        9
+    );
+
+    // Each code can only be used once
+    assert!(!UserRecoveryCode::redeem(user.id, &codes[0], connection).unwrap());
+}
@@ -0,0 +1,202 @@
+use db::dev::TestProject;
+use db::models::concerns::users::password_resetable::PasswordResetable;
+use db::prelude::*;
+use uuid::Uuid;
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+
+    let session = UserSession::create(user.id, Some("Mozilla/5.0".to_string()), Some("127.0.0.1".to_string()))
+        .commit(connection)
+        .unwrap();
+    assert_eq!(session.user_id, user.id);
+    assert_eq!(session.user_agent, Some("Mozilla/5.0".to_string()));
+    assert_eq!(session.ip_address, Some("127.0.0.1".to_string()));
+    assert!(session.is_active());
+    assert_eq!(UserSession::find(session.id, connection).unwrap(), session);
+}
+
+#[test]
+fn started_by_token() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+
+    assert!(!UserSession::token_used("magic-link-token", connection).unwrap());
+    let session = UserSession::create(user.id, None, None)
+        .started_by_token("magic-link-token")
+        .commit(connection)
+        .unwrap();
+    assert!(session.started_by_token_hash.is_some());
+    assert_ne!(session.started_by_token_hash, Some("magic-link-token".to_string()));
+    assert!(UserSession::token_used("magic-link-token", connection).unwrap());
+    assert!(!UserSession::token_used("other-token", connection).unwrap());
+
+    // Each token can only start one session
+    assert!(UserSession::create(user.id, None, None)
+        .started_by_token("magic-link-token")
+        .commit(connection)
+        .is_err());
+}
+
+#[test]
+fn find_active_for_user() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    let session = UserSession::create(user.id, None, None).commit(connection).unwrap();
+    let session2 = UserSession::create(user.id, None, None).commit(connection).unwrap();
+    UserSession::create(user2.id, None, None).commit(connection).unwrap();
+
+    let sessions = UserSession::find_active_for_user(user.id, connection).unwrap();
+    assert_eq!(sessions.len(), 2);
+    assert!(sessions.contains(&session));
+    assert!(sessions.contains(&session2));
+
+    session
+        .revoke(SessionRevocationReasons::SignedOut, Some(user.id), connection)
+        .unwrap();
+    assert_eq!(
+        UserSession::find_active_for_user(user.id, connection).unwrap(),
+        vec![session2]
+    );
+}
+
+#[test]
+fn rotate_refresh_token() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let session = UserSession::create(user.id, None, None).commit(connection).unwrap();
+    let original_refresh_token_id = session.refresh_token_id;
+
+    let rotated = session
+        .rotate_refresh_token(original_refresh_token_id, connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(rotated.id, session.id);
+    assert_ne!(rotated.refresh_token_id, original_refresh_token_id);
+    assert!(rotated.is_active());
+
+    // Presenting the replaced refresh token again revokes the session
+    assert!(rotated
+        .rotate_refresh_token(original_refresh_token_id, connection)
+        .unwrap()
+        .is_none());
+    let session = UserSession::find(session.id, connection).unwrap();
+    assert!(!session.is_active());
+    assert_eq!(
+        session.revoked_reason,
+        Some(SessionRevocationReasons::RefreshTokenReused)
+    );
+    let domain_events = DomainEvent::find(
+        Tables::UserSessions,
+        Some(session.id),
+        Some(DomainEventTypes::UserSessionRevoked),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(1, domain_events.len());
+
+    // Revoked sessions can no longer be refreshed with their latest token either
+    assert!(session
+        .rotate_refresh_token(rotated.refresh_token_id, connection)
+        .unwrap()
+        .is_none());
+    assert!(session
+        .rotate_refresh_token(Uuid::new_v4(), connection)
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn revoke() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let session = UserSession::create(user.id, None, None).commit(connection).unwrap();
+
+    let session = session
+        .revoke(SessionRevocationReasons::SignedOut, Some(user.id), connection)
+        .unwrap();
+    assert!(!session.is_active());
+    assert_eq!(session.revoked_reason, Some(SessionRevocationReasons::SignedOut));
+
+    let domain_events = DomainEvent::find(
+        Tables::UserSessions,
+        Some(session.id),
+        Some(DomainEventTypes::UserSessionRevoked),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(1, domain_events.len());
+    assert_eq!(domain_events[0].user_id, Some(user.id));
+
+    assert_eq!(
+        session.revoke(SessionRevocationReasons::SignedOut, Some(user.id), connection),
+        Err(DatabaseError::new(
+            ErrorCode::BusinessProcessError,
+            Some("Session has already been revoked".to_string()),
+        ))
+    );
+}
+
+#[test]
+fn revoke_all_for_user() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    UserSession::create(user.id, None, None).commit(connection).unwrap();
+    UserSession::create(user.id, None, None).commit(connection).unwrap();
+    let other_session = UserSession::create(user2.id, None, None).commit(connection).unwrap();
+
+    assert_eq!(
+        UserSession::revoke_all_for_user(user.id, SessionRevocationReasons::SignedOut, Some(user.id), connection)
+            .unwrap(),
+        2
+    );
+    assert!(UserSession::find_active_for_user(user.id, connection)
+        .unwrap()
+        .is_empty());
+    assert!(UserSession::find(other_session.id, connection).unwrap().is_active());
+
+    // Nothing left to revoke
+    assert_eq!(
+        UserSession::revoke_all_for_user(user.id, SessionRevocationReasons::SignedOut, Some(user.id), connection)
+            .unwrap(),
+        0
+    );
+}
+
+#[test]
+fn revoked_when_user_disabled() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let admin = project.create_user().finish();
+    let session = UserSession::create(user.id, None, None).commit(connection).unwrap();
+
+    user.disable(Some(&admin), connection).unwrap();
+    let session = UserSession::find(session.id, connection).unwrap();
+    assert!(!session.is_active());
+    assert_eq!(session.revoked_reason, Some(SessionRevocationReasons::UserDisabled));
+}
+
+#[test]
+fn revoked_when_password_reset() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let session = UserSession::create(user.id, None, None).commit(connection).unwrap();
+
+    let user = user.create_password_reset_token(connection).unwrap();
+    User::consume_password_reset_token(&user.password_reset_token.unwrap(), "newPassword", connection).unwrap();
+    let session = UserSession::find(session.id, connection).unwrap();
+    assert!(!session.is_active());
+    assert_eq!(session.revoked_reason, Some(SessionRevocationReasons::PasswordReset));
+}
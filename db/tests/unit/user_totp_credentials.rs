@@ -0,0 +1,199 @@
+use chrono::prelude::*;
+use db::dev::TestProject;
+use db::prelude::*;
+use db::utils::errors::ErrorCode::ValidationError;
+use db::utils::totp;
+
+const ENCRYPTION_KEY: &str = "encryption_key";
+
+fn current_code(secret: &str, offset: i64) -> String {
+    totp::generate_code(secret, totp::time_step(Utc::now().timestamp()) + offset).unwrap()
+}
+
+#[test]
+fn enroll() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+
+    let enrollment = UserTotpCredential::enroll(&user, ENCRYPTION_KEY, connection).unwrap();
+    assert!(enrollment.provisioning_uri.starts_with("otpauth://totp/"));
+    assert!(enrollment.provisioning_uri.contains(&enrollment.secret));
+    let credential = UserTotpCredential::find_by_user_id(user.id, connection).unwrap();
+    assert!(credential.confirmed_at.is_none());
+    // Only the encrypted secret is stored
+    assert_ne!(credential.encrypted_secret, enrollment.secret);
+    assert!(UserTotpCredential::find_confirmed_for_user(user.id, connection)
+        .unwrap()
+        .is_none());
+
+    // Enrolling again before confirming replaces the secret
+    let new_enrollment = UserTotpCredential::enroll(&user, ENCRYPTION_KEY, connection).unwrap();
+    assert_ne!(new_enrollment.secret, enrollment.secret);
+    let new_credential = UserTotpCredential::find_by_user_id(user.id, connection).unwrap();
+    assert_ne!(new_credential.id, credential.id);
+
+    // Once confirmed enrollment cannot be restarted
+    new_credential
+        .confirm(&current_code(&new_enrollment.secret, 0), ENCRYPTION_KEY, connection)
+        .unwrap();
+    let result = UserTotpCredential::enroll(&user, ENCRYPTION_KEY, connection);
+    assert_eq!(
+        result,
+        Err(DatabaseError::new(
+            ErrorCode::BusinessProcessError,
+            Some("Two-factor authentication is already enabled".to_string()),
+        ))
+    );
+}
+
+#[test]
+fn confirm() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let enrollment = UserTotpCredential::enroll(&user, ENCRYPTION_KEY, connection).unwrap();
+    let credential = UserTotpCredential::find_by_user_id(user.id, connection).unwrap();
+
+    let result = credential.confirm("000000", ENCRYPTION_KEY, connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("code"));
+                assert_eq!(errors["code"].len(), 1);
+                assert_eq!(errors["code"][0].code, "Two-factor code is invalid");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    let recovery_codes = credential
+        .confirm(&current_code(&enrollment.secret, 0), ENCRYPTION_KEY, connection)
+        .unwrap();
+    assert_eq!(recovery_codes.len(), 10);
+    assert_eq!(
+        UserRecoveryCode::find_unused_for_user(user.id, connection)
+            .unwrap()
+            .len(),
+        10
+    );
+    let credential = UserTotpCredential::find_confirmed_for_user(user.id, connection)
+        .unwrap()
+        .unwrap();
+    assert!(credential.confirmed_at.is_some());
+
+    let domain_events = DomainEvent::find(
+        Tables::Users,
+        Some(user.id),
+        Some(DomainEventTypes::UserTwoFactorEnabled),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(1, domain_events.len());
+}
+
+#[test]
+fn verify() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let enrollment = UserTotpCredential::enroll(&user, ENCRYPTION_KEY, connection).unwrap();
+    let credential = UserTotpCredential::find_by_user_id(user.id, connection).unwrap();
+
+    assert!(!credential.verify("000000", ENCRYPTION_KEY, connection).unwrap());
+    let code = current_code(&enrollment.secret, 0);
+    assert!(credential.verify(&code, ENCRYPTION_KEY, connection).unwrap());
+
+    // Codes cannot be replayed
+    let credential = UserTotpCredential::find_by_user_id(user.id, connection).unwrap();
+    assert!(!credential.verify(&code, ENCRYPTION_KEY, connection).unwrap());
+
+    // A later code is still accepted
+    assert!(credential
+        .verify(&current_code(&enrollment.secret, 1), ENCRYPTION_KEY, connection)
+        .unwrap());
+}
+
+#[test]
+fn regenerate_recovery_codes() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let enrollment = UserTotpCredential::enroll(&user, ENCRYPTION_KEY, connection).unwrap();
+    let credential = UserTotpCredential::find_by_user_id(user.id, connection).unwrap();
+
+    // Not available until enrollment is confirmed
+    let result = credential.regenerate_recovery_codes(connection);
+    assert_eq!(
+        result,
+        Err(DatabaseError::new(
+            ErrorCode::BusinessProcessError,
+            Some("Two-factor authentication is not enabled".to_string()),
+        ))
+    );
+
+    let recovery_codes = credential
+        .confirm(&current_code(&enrollment.secret, 0), ENCRYPTION_KEY, connection)
+        .unwrap();
+    let credential = UserTotpCredential::find_by_user_id(user.id, connection).unwrap();
+    let new_recovery_codes = credential.regenerate_recovery_codes(connection).unwrap();
+    assert_eq!(new_recovery_codes.len(), 10);
+    assert!(!UserRecoveryCode::redeem(user.id, &recovery_codes[0], connection).unwrap());
+    assert!(UserRecoveryCode::redeem(user.id, &new_recovery_codes[0], connection).unwrap());
+}
+
+#[test]
+fn disable() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let enrollment = UserTotpCredential::enroll(&user, ENCRYPTION_KEY, connection).unwrap();
+    let credential = UserTotpCredential::find_by_user_id(user.id, connection).unwrap();
+    credential
+        .confirm(&current_code(&enrollment.secret, 0), ENCRYPTION_KEY, connection)
+        .unwrap();
+
+    credential.disable(Some(user.id), connection).unwrap();
+    assert!(UserTotpCredential::find_by_user_id(user.id, connection).is_err());
+    assert!(UserRecoveryCode::find_unused_for_user(user.id, connection)
+        .unwrap()
+        .is_empty());
+    let domain_events = DomainEvent::find(
+        Tables::Users,
+        Some(user.id),
+        Some(DomainEventTypes::UserTwoFactorDisabled),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(1, domain_events.len());
+}
+
+#[test]
+fn disable_when_required_by_organization() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    project
+        .create_organization()
+        .with_member(&user, Roles::OrgMember)
+        .with_two_factor_required()
+        .finish();
+    let enrollment = UserTotpCredential::enroll(&user, ENCRYPTION_KEY, connection).unwrap();
+    let credential = UserTotpCredential::find_by_user_id(user.id, connection).unwrap();
+    credential
+        .confirm(&current_code(&enrollment.secret, 0), ENCRYPTION_KEY, connection)
+        .unwrap();
+
+    let result = credential.disable(Some(user.id), connection);
+    assert_eq!(
+        result,
+        Err(DatabaseError::new(
+            ErrorCode::BusinessProcessError,
+            Some("Two-factor authentication is required by an organization you are a member of".to_string()),
+        ))
+    );
+    assert!(UserTotpCredential::find_confirmed_for_user(user.id, connection)
+        .unwrap()
+        .is_some());
+}
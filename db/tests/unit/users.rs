@@ -2318,6 +2318,7 @@ fn get_global_scopes() {
             "settlement:read",
             "settlement:read-early",
             "settlement:write",
+            "tax-rule:write",
             "ticket:admin",
             "ticket:read",
             "ticket:transfer",
@@ -2407,6 +2408,7 @@ fn get_global_scopes() {
             "settlement:read",
             "settlement:read-early",
             "settlement:write",
+            "tax-rule:write",
             "ticket:admin",
             "ticket:read",
             "ticket:transfer",
@@ -0,0 +1,301 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use db::dev::TestProject;
+use db::models::*;
+use db::schema::waitlist_entries;
+use db::utils::errors::ErrorCode::ValidationError;
+use diesel;
+use diesel::prelude::*;
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project
+        .create_event()
+        .with_a_specific_number_of_tickets(2)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let user = project.create_user().finish();
+
+    // Tickets are still available
+    let result = WaitlistEntry::create(ticket_type.id, user.id, 1).commit(connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("ticket_type_id"));
+                assert_eq!(
+                    errors["ticket_type_id"][0].code,
+                    "Tickets are still available for this ticket type"
+                );
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    project
+        .create_order()
+        .for_event(&event)
+        .for_tickets(ticket_type.id)
+        .quantity(2)
+        .is_paid()
+        .finish();
+
+    // Quantity must be positive
+    let result = WaitlistEntry::create(ticket_type.id, user.id, 0).commit(connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("quantity"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    let entry = WaitlistEntry::create(ticket_type.id, user.id, 2)
+        .commit(connection)
+        .unwrap();
+    assert_eq!(entry.ticket_type_id, ticket_type.id);
+    assert_eq!(entry.user_id, user.id);
+    assert_eq!(entry.quantity, 2);
+    assert_eq!(entry.status, WaitlistEntryStatus::Waiting);
+    assert!(entry.hold_id.is_none());
+
+    let domain_events = DomainEvent::find(
+        Tables::WaitlistEntries,
+        Some(entry.id),
+        Some(DomainEventTypes::WaitlistEntryCreated),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(1, domain_events.len());
+
+    // Users can only be in line once
+    let result = WaitlistEntry::create(ticket_type.id, user.id, 1).commit(connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("user_id"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn find_for_ticket_type() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project
+        .create_event()
+        .with_a_specific_number_of_tickets(1)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    project
+        .create_order()
+        .for_event(&event)
+        .for_tickets(ticket_type.id)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    let entry = WaitlistEntry::create(ticket_type.id, user.id, 1)
+        .commit(connection)
+        .unwrap();
+    let entry2 = WaitlistEntry::create(ticket_type.id, user2.id, 1)
+        .commit(connection)
+        .unwrap();
+
+    let payload = WaitlistEntry::find_for_ticket_type(ticket_type.id, None, 0, 100, connection).unwrap();
+    assert_eq!(payload.data, vec![entry.clone(), entry2.clone()]);
+    assert_eq!(payload.paging.total, 2);
+
+    let entry = entry.cancel(user.id, connection).unwrap();
+    let payload =
+        WaitlistEntry::find_for_ticket_type(ticket_type.id, Some(WaitlistEntryStatus::Waiting), 0, 100, connection)
+            .unwrap();
+    assert_eq!(payload.data, vec![entry2]);
+    let payload =
+        WaitlistEntry::find_for_ticket_type(ticket_type.id, Some(WaitlistEntryStatus::Cancelled), 0, 100, connection)
+            .unwrap();
+    assert_eq!(payload.data, vec![entry]);
+}
+
+#[test]
+fn queue_processing() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project
+        .create_event()
+        .with_a_specific_number_of_tickets(1)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let cart = project
+        .create_order()
+        .for_event(&event)
+        .for_tickets(ticket_type.id)
+        .quantity(1)
+        .finish();
+    let user = project.create_user().finish();
+
+    // No open entries so nothing is scheduled
+    WaitlistEntry::queue_processing(ticket_type.id, None, connection).unwrap();
+    let domain_action = DomainAction::upcoming_domain_action(
+        Some(Tables::TicketTypes),
+        Some(ticket_type.id),
+        DomainActionTypes::ProcessWaitlist,
+        connection,
+    )
+    .unwrap();
+    assert!(domain_action.is_none());
+
+    // Joining schedules processing for when the cart reservation lapses
+    WaitlistEntry::create(ticket_type.id, user.id, 1)
+        .commit(connection)
+        .unwrap();
+    let domain_action = DomainAction::upcoming_domain_action(
+        Some(Tables::TicketTypes),
+        Some(ticket_type.id),
+        DomainActionTypes::ProcessWaitlist,
+        connection,
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(
+        domain_action.scheduled_at.timestamp(),
+        cart.expires_at.unwrap().timestamp()
+    );
+
+    // Queuing again brings the existing action forward instead of adding another
+    let run_at = Utc::now().naive_utc() + Duration::minutes(1);
+    WaitlistEntry::queue_processing(ticket_type.id, Some(run_at), connection).unwrap();
+    let domain_actions = DomainAction::find_by_resource(
+        Some(Tables::TicketTypes),
+        Some(ticket_type.id),
+        DomainActionTypes::ProcessWaitlist,
+        DomainActionStatus::Pending,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(1, domain_actions.len());
+    assert_eq!(domain_actions[0].scheduled_at.timestamp(), run_at.timestamp());
+}
+
+#[test]
+fn process_ticket_type() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project
+        .create_event()
+        .with_a_specific_number_of_tickets(2)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let mut cart = project
+        .create_order()
+        .for_event(&event)
+        .for_tickets(ticket_type.id)
+        .quantity(2)
+        .finish();
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    let entry = WaitlistEntry::create(ticket_type.id, user.id, 1)
+        .commit(connection)
+        .unwrap();
+    let entry2 = WaitlistEntry::create(ticket_type.id, user2.id, 2)
+        .commit(connection)
+        .unwrap();
+
+    // Nothing has been released yet
+    let offers = WaitlistEntry::process_ticket_type(ticket_type.id, connection).unwrap();
+    assert!(offers.is_empty());
+
+    // Cart expires releasing both tickets, first in line is offered their ticket
+    // but the second entry wants more than what is left
+    let past_expiry = Utc::now().naive_utc() - Duration::minutes(5);
+    cart.set_expiry(None, Some(past_expiry), true, connection).unwrap();
+    let offers = WaitlistEntry::process_ticket_type(ticket_type.id, connection).unwrap();
+    assert_eq!(offers.len(), 1);
+    let (offered_entry, hold) = offers[0].clone();
+    assert_eq!(offered_entry.id, entry.id);
+    assert_eq!(offered_entry.status, WaitlistEntryStatus::Offered);
+    assert_eq!(offered_entry.hold_id, Some(hold.id));
+    assert!(offered_entry.offer_expires_at.is_some());
+    assert_eq!(hold.end_at, offered_entry.offer_expires_at);
+    assert_eq!(hold.max_per_user, Some(1));
+    assert_eq!(hold.email, user.email);
+    assert_eq!(hold.quantity(connection).unwrap(), (1, 1));
+    assert_eq!(ticket_type.valid_available_ticket_count(connection).unwrap(), 1);
+    assert_eq!(
+        WaitlistEntry::find(entry2.id, connection).unwrap().status,
+        WaitlistEntryStatus::Waiting
+    );
+
+    // Offer lapses without a purchase, its tickets are released and the entry expires
+    diesel::update(waitlist_entries::table.filter(waitlist_entries::id.eq(entry.id)))
+        .set(waitlist_entries::offer_expires_at.eq(past_expiry))
+        .execute(connection)
+        .unwrap();
+    hold.set_quantity(None, 0, connection).unwrap();
+    let offers = WaitlistEntry::process_ticket_type(ticket_type.id, connection).unwrap();
+    assert_eq!(
+        WaitlistEntry::find(entry.id, connection).unwrap().status,
+        WaitlistEntryStatus::Expired
+    );
+    assert_eq!(offers.len(), 1);
+    let (offered_entry, hold) = offers[0].clone();
+    assert_eq!(offered_entry.id, entry2.id);
+    assert_eq!(hold.quantity(connection).unwrap(), (2, 2));
+}
+
+#[test]
+fn cancel() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project
+        .create_event()
+        .with_a_specific_number_of_tickets(1)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let mut cart = project
+        .create_order()
+        .for_event(&event)
+        .for_tickets(ticket_type.id)
+        .quantity(1)
+        .finish();
+    let user = project.create_user().finish();
+    let entry = WaitlistEntry::create(ticket_type.id, user.id, 1)
+        .commit(connection)
+        .unwrap();
+    cart.set_expiry(
+        None,
+        Some(Utc::now().naive_utc() - Duration::minutes(5)),
+        true,
+        connection,
+    )
+    .unwrap();
+    let (entry, hold) = WaitlistEntry::process_ticket_type(ticket_type.id, connection)
+        .unwrap()
+        .remove(0);
+    assert_eq!(ticket_type.valid_available_ticket_count(connection).unwrap(), 0);
+
+    // Cancelling an offer returns its tickets to the pool
+    let entry = entry.cancel(user.id, connection).unwrap();
+    assert_eq!(entry.status, WaitlistEntryStatus::Cancelled);
+    assert_eq!(hold.quantity(connection).unwrap(), (0, 0));
+    assert_eq!(ticket_type.valid_available_ticket_count(connection).unwrap(), 1);
+    assert!(WaitlistEntry::find_open_for_user(ticket_type.id, user.id, connection)
+        .unwrap()
+        .is_none());
+
+    // Entry is no longer open
+    assert!(entry.cancel(user.id, connection).is_err());
+}
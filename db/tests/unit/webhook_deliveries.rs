@@ -0,0 +1,168 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use db::dev::TestProject;
+use db::prelude::*;
+
+fn create_delivery(project: &TestProject) -> WebhookDelivery {
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let webhook = project
+        .create_domain_event_publisher()
+        .with_organization(&organization)
+        .with_signing_secret("whsec_test")
+        .finish();
+    let user = project.create_user().finish();
+    let domain_event = DomainEvent::find_by_type(DomainEventTypes::UserCreated, connection)
+        .unwrap()
+        .into_iter()
+        .find(|e| e.main_id == Some(user.id))
+        .unwrap();
+    WebhookDelivery::create(webhook.id, domain_event.id, json!({"id": domain_event.id}).to_string())
+        .commit(connection)
+        .unwrap()
+}
+
+#[test]
+fn commit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let delivery = create_delivery(&project);
+
+    assert_eq!(delivery.status, WebhookDeliveryStatus::Pending);
+    assert_eq!(delivery.attempt_count, 0);
+    assert!(delivery.next_attempt_at.is_some());
+    let domain_action = DomainAction::upcoming_domain_action(
+        Some(Tables::WebhookDeliveries),
+        Some(delivery.id),
+        DomainActionTypes::DeliverWebhook,
+        connection,
+    )
+    .unwrap();
+    assert!(domain_action.is_some());
+}
+
+#[test]
+fn retry_delay() {
+    assert_eq!(WebhookDelivery::retry_delay(1), Duration::seconds(30));
+    assert_eq!(WebhookDelivery::retry_delay(2), Duration::seconds(60));
+    assert_eq!(WebhookDelivery::retry_delay(5), Duration::seconds(480));
+    // Capped at six hours
+    assert_eq!(WebhookDelivery::retry_delay(15), Duration::hours(6));
+}
+
+#[test]
+fn record_attempt_succeeded() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let delivery = create_delivery(&project);
+
+    let delivery = delivery
+        .record_attempt(Some(204), 120, Some(""), None, connection)
+        .unwrap();
+    assert_eq!(delivery.status, WebhookDeliveryStatus::Succeeded);
+    assert_eq!(delivery.attempt_count, 1);
+    assert!(delivery.last_attempted_at.is_some());
+    assert_eq!(delivery.next_attempt_at, None);
+
+    let attempts = delivery.attempts(connection).unwrap();
+    assert_eq!(attempts.len(), 1);
+    assert_eq!(attempts[0].response_status, Some(204));
+    assert_eq!(attempts[0].latency_ms, 120);
+
+    // Completed deliveries are not attempted again
+    assert!(delivery.record_attempt(Some(200), 120, None, None, connection).is_err());
+}
+
+#[test]
+fn record_attempt_failed() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let mut delivery = create_delivery(&project);
+
+    let long_body = "x".repeat(5000);
+    delivery = delivery
+        .record_attempt(Some(500), 80, Some(&long_body), None, connection)
+        .unwrap();
+    assert_eq!(delivery.status, WebhookDeliveryStatus::Pending);
+    assert_eq!(delivery.attempt_count, 1);
+    let next_attempt_at = delivery.next_attempt_at.unwrap();
+    assert!(next_attempt_at > Utc::now().naive_utc() + Duration::seconds(20));
+    assert!(next_attempt_at <= Utc::now().naive_utc() + Duration::seconds(30));
+    let attempts = delivery.attempts(connection).unwrap();
+    assert_eq!(attempts[0].response_body_excerpt.as_ref().unwrap().len(), 256);
+
+    // Requests that could not be made are retried as well
+    delivery = delivery
+        .record_attempt(None, 10_000, None, Some("timed out".to_string()), connection)
+        .unwrap();
+    assert_eq!(delivery.status, WebhookDeliveryStatus::Pending);
+    assert_eq!(delivery.attempt_count, 2);
+    let attempts = delivery.attempts(connection).unwrap();
+    assert_eq!(attempts[1].error, Some("timed out".to_string()));
+
+    for _ in 2..WEBHOOK_DELIVERY_MAX_ATTEMPTS {
+        delivery = delivery.record_attempt(Some(503), 50, None, None, connection).unwrap();
+    }
+    assert_eq!(delivery.status, WebhookDeliveryStatus::Failed);
+    assert_eq!(delivery.attempt_count, WEBHOOK_DELIVERY_MAX_ATTEMPTS);
+    assert_eq!(delivery.next_attempt_at, None);
+}
+
+#[test]
+fn replay() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let mut delivery = create_delivery(&project);
+
+    // Pending deliveries are already scheduled
+    assert!(delivery.replay(Some(user.id), connection).is_err());
+
+    for _ in 0..WEBHOOK_DELIVERY_MAX_ATTEMPTS {
+        delivery = delivery.record_attempt(Some(410), 50, None, None, connection).unwrap();
+    }
+    assert_eq!(delivery.status, WebhookDeliveryStatus::Failed);
+
+    let delivery = delivery.replay(Some(user.id), connection).unwrap();
+    assert_eq!(delivery.status, WebhookDeliveryStatus::Pending);
+    assert_eq!(delivery.attempt_count, 0);
+    assert!(delivery.next_attempt_at.is_some());
+    // Earlier attempts are kept in the log
+    assert_eq!(
+        delivery.attempts(connection).unwrap().len(),
+        WEBHOOK_DELIVERY_MAX_ATTEMPTS as usize
+    );
+    assert_eq!(
+        DomainEvent::find(
+            Tables::WebhookDeliveries,
+            Some(delivery.id),
+            Some(DomainEventTypes::WebhookDeliveryReplayed),
+            connection,
+        )
+        .unwrap()
+        .len(),
+        1
+    );
+}
+
+#[test]
+fn find_for_publisher() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let delivery = create_delivery(&project);
+    delivery
+        .record_attempt(Some(500), 80, Some("error"), None, connection)
+        .unwrap();
+    create_delivery(&project);
+
+    let deliveries =
+        WebhookDelivery::find_for_publisher(delivery.domain_event_publisher_id, 0, 100, connection).unwrap();
+    assert_eq!(deliveries.data.len(), 1);
+    assert_eq!(deliveries.paging.total, 1);
+    assert_eq!(deliveries.data[0].id, delivery.id);
+    assert_eq!(deliveries.data[0].attempts.len(), 1);
+    assert_eq!(
+        deliveries.data[0].attempts[0].response_body_excerpt,
+        Some("error".to_string())
+    );
+}
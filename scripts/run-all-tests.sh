@@ -8,6 +8,7 @@ export TARI_URL="TEST"
 export COMMUNICATION_DEFAULT_SOURCE_EMAIL="noreply@bigneon.com"
 export COMMUNICATION_DEFAULT_SOURCE_PHONE="0112223333"
 export TOKEN_SECRET=travis_secret
+export SCANNER_MANIFEST_SECRET=travis_scanner_manifest_secret
 export TOKEN_ISSUER=bg-on-travis
 export STRIPE_SECRET_KEY="sk_test_iGn9c6EJyuF3Gx0QH6uitQlb"
 export SENDGRID_API_KEY=" "
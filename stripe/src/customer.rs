@@ -7,10 +7,52 @@ pub struct Customer {
     pub raw_data: String,
 }
 
+/// Card stored as a customer's payment source
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Card {
+    pub id: String,
+    pub brand: String,
+    pub last4: String,
+    pub exp_month: u32,
+    pub exp_year: u32,
+}
+
 impl Customer {
     pub fn to_json(&self) -> String {
         self.raw_data.clone()
     }
+
+    /// The card charged when the customer is used as the source of a charge
+    pub fn default_card(&self) -> Result<Option<Card>, StripeError> {
+        #[derive(Deserialize)]
+        struct Source {
+            id: String,
+            object: String,
+        }
+        #[derive(Deserialize)]
+        struct Sources {
+            data: Vec<serde_json::Value>,
+        }
+        #[derive(Deserialize)]
+        struct R {
+            default_source: Option<String>,
+            sources: Option<Sources>,
+        }
+        let result: R = serde_json::from_str(&self.raw_data)?;
+        let default_source = match result.default_source {
+            Some(default_source) => default_source,
+            None => return Ok(None),
+        };
+
+        for data in result.sources.map(|s| s.data).unwrap_or_default() {
+            let source: Source = serde_json::from_value(data.clone())?;
+            if source.id == default_source && source.object == "card" {
+                return Ok(Some(serde_json::from_value(data)?));
+            }
+        }
+        Ok(None)
+    }
+
     pub async fn from_response(resp: reqwest::Response) -> Result<Customer, StripeError> {
         let raw_data: String = resp.text().await?;
         #[derive(Deserialize)]
@@ -24,3 +66,33 @@ impl Customer {
         })
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn default_card() {
+        let customer = Customer {
+            id: "cus_H2Zk4Qe6Yw1Ltk".to_string(),
+            raw_data: include_str!("../fixtures/customer.json").to_string(),
+        };
+        assert_eq!(
+            customer.default_card().unwrap(),
+            Some(Card {
+                id: "card_1GUTwVHb0zBq6YkKq7RjQm2b".to_string(),
+                brand: "Visa".to_string(),
+                last4: "4242".to_string(),
+                exp_month: 8,
+                exp_year: 2024,
+            })
+        );
+
+        // Customer without a source
+        let customer = Customer {
+            id: "cus_H2Zk4Qe6Yw1Ltk".to_string(),
+            raw_data: r#"{"id": "cus_H2Zk4Qe6Yw1Ltk", "default_source": null, "sources": {"data": []}}"#.to_string(),
+        };
+        assert_eq!(customer.default_card().unwrap(), None);
+    }
+}
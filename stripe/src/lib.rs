@@ -3,7 +3,9 @@
 #![deny(unused_imports)]
 // Unused results is more often than not an error
 #![deny(unused_must_use)]
+extern crate hex;
 extern crate reqwest;
+extern crate ring;
 extern crate serde;
 extern crate serde_json;
 #[macro_use]
@@ -14,9 +16,11 @@ pub use self::customer::*;
 pub use self::refund_result::RefundResult;
 pub use self::stripe_client::StripeClient;
 pub use self::stripe_error::StripeError;
+pub use self::webhook::*;
 
 mod charge_result;
 mod customer;
 mod refund_result;
 mod stripe_client;
 mod stripe_error;
+mod webhook;
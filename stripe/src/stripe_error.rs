@@ -32,6 +32,14 @@ impl fmt::Display for StripeError {
 }
 
 impl StripeError {
+    pub fn webhook(description: &str) -> StripeError {
+        StripeError {
+            description: description.to_string(),
+            cause: None,
+            error_code: None,
+        }
+    }
+
     pub async fn from_response(response: reqwest::Response) -> StripeError {
         let status = response.status();
         let response_text = response
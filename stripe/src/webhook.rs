@@ -0,0 +1,200 @@
+use crate::StripeError;
+use hex;
+use ring::{digest, hmac};
+use serde::de::DeserializeOwned;
+use serde_json;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Maximum age of a signed payload, Stripe's libraries use the same default
+pub const DEFAULT_TOLERANCE_SECONDS: i64 = 300;
+
+const SIGNATURE_SCHEME: &str = "v1";
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct WebhookEvent {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub created: i64,
+    #[serde(default)]
+    pub livemode: bool,
+    pub data: WebhookEventData,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct WebhookEventData {
+    pub object: serde_json::Value,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Charge {
+    pub id: String,
+    pub amount: i64,
+    #[serde(default)]
+    pub amount_refunded: i64,
+    #[serde(default)]
+    pub captured: bool,
+    pub currency: String,
+    pub status: String,
+    pub failure_message: Option<String>,
+    #[serde(default)]
+    pub refunds: RefundList,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct RefundList {
+    pub data: Vec<Refund>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Refund {
+    pub id: String,
+    pub amount: i64,
+    pub charge: Option<String>,
+    pub currency: String,
+    pub reason: Option<String>,
+    pub status: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Dispute {
+    pub id: String,
+    pub amount: i64,
+    pub charge: String,
+    pub currency: String,
+    pub reason: String,
+    pub status: String,
+}
+
+impl WebhookEvent {
+    /// Verifies the `Stripe-Signature` header sent with a webhook before parsing the payload
+    pub fn construct(payload: &str, signature_header: &str, secret: &str) -> Result<WebhookEvent, StripeError> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+        WebhookEvent::construct_at(payload, signature_header, secret, now, DEFAULT_TOLERANCE_SECONDS)
+    }
+
+    pub fn construct_at(
+        payload: &str,
+        signature_header: &str,
+        secret: &str,
+        now: i64,
+        tolerance_seconds: i64,
+    ) -> Result<WebhookEvent, StripeError> {
+        let mut timestamp = None;
+        let mut signatures = Vec::new();
+        for element in signature_header.split(',') {
+            let mut parts = element.trim().splitn(2, '=');
+            match (parts.next(), parts.next()) {
+                (Some("t"), Some(value)) => timestamp = value.parse::<i64>().ok(),
+                (Some(SIGNATURE_SCHEME), Some(value)) => signatures.push(value),
+                _ => (),
+            }
+        }
+
+        let timestamp = timestamp.ok_or_else(|| StripeError::webhook("Webhook signature has no timestamp"))?;
+        let key = hmac::SigningKey::new(&digest::SHA256, secret.as_bytes());
+        let signed_payload = format!("{}.{}", timestamp, payload);
+        let signature_matches = signatures.iter().any(|signature| match hex::decode(signature) {
+            Ok(signature) => hmac::verify_with_own_key(&key, signed_payload.as_bytes(), &signature).is_ok(),
+            Err(_) => false,
+        });
+        if !signature_matches {
+            return Err(StripeError::webhook("Webhook signature does not match the payload"));
+        }
+        if (now - timestamp).abs() > tolerance_seconds {
+            return Err(StripeError::webhook("Webhook timestamp is outside the tolerance zone"));
+        }
+
+        Ok(serde_json::from_str(payload)?)
+    }
+
+    pub fn charge(&self) -> Result<Charge, StripeError> {
+        self.object()
+    }
+
+    pub fn dispute(&self) -> Result<Dispute, StripeError> {
+        self.object()
+    }
+
+    fn object<T: DeserializeOwned>(&self) -> Result<T, StripeError> {
+        Ok(serde_json::from_value(self.data.object.clone())?)
+    }
+}
+
+/// `Stripe-Signature` header for a payload, used to sign fixtures when testing webhooks
+pub fn signature_header(payload: &str, secret: &str, timestamp: i64) -> String {
+    let key = hmac::SigningKey::new(&digest::SHA256, secret.as_bytes());
+    let signature = hmac::sign(&key, format!("{}.{}", timestamp, payload).as_bytes());
+    format!(
+        "t={},{}={}",
+        timestamp,
+        SIGNATURE_SCHEME,
+        hex::encode(signature.as_ref())
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SECRET: &str = "whsec_test";
+    const TIMESTAMP: i64 = 1_586_000_000;
+
+    #[test]
+    fn construct_at_verifies_signature() {
+        let payload = include_str!("../fixtures/webhooks/charge.dispute.created.json");
+        let header = signature_header(payload, SECRET, TIMESTAMP);
+
+        let event =
+            WebhookEvent::construct_at(payload, &header, SECRET, TIMESTAMP + 10, DEFAULT_TOLERANCE_SECONDS).unwrap();
+        assert_eq!(event.id, "evt_1GU3Y2Hb0zBq6YkKJdGZ3Kqx");
+        assert_eq!(event.event_type, "charge.dispute.created");
+        let dispute = event.dispute().unwrap();
+        assert_eq!(dispute.charge, "ch_1GU3XbHb0zBq6YkKwL8kZt2n");
+        assert_eq!(dispute.amount, 5000);
+
+        // Signed with another secret
+        let header = signature_header(payload, "whsec_other", TIMESTAMP);
+        assert!(WebhookEvent::construct_at(payload, &header, SECRET, TIMESTAMP, DEFAULT_TOLERANCE_SECONDS).is_err());
+
+        // Payload altered after signing
+        let header = signature_header(payload, SECRET, TIMESTAMP);
+        let altered = payload.replace("5000", "50");
+        assert!(WebhookEvent::construct_at(&altered, &header, SECRET, TIMESTAMP, DEFAULT_TOLERANCE_SECONDS).is_err());
+
+        // Replayed outside of the tolerance
+        assert!(WebhookEvent::construct_at(
+            payload,
+            &header,
+            SECRET,
+            TIMESTAMP + DEFAULT_TOLERANCE_SECONDS + 1,
+            DEFAULT_TOLERANCE_SECONDS
+        )
+        .is_err());
+
+        assert!(WebhookEvent::construct_at(payload, "v1=abc", SECRET, TIMESTAMP, DEFAULT_TOLERANCE_SECONDS).is_err());
+    }
+
+    #[test]
+    fn construct_at_accepts_any_v1_signature() {
+        let payload = include_str!("../fixtures/webhooks/charge.refunded.json");
+        let header = format!(
+            "t={},v1=deadbeef,{},v0=ignored",
+            TIMESTAMP,
+            signature_header(payload, SECRET, TIMESTAMP)
+                .splitn(2, ',')
+                .nth(1)
+                .unwrap()
+        );
+
+        let event = WebhookEvent::construct_at(payload, &header, SECRET, TIMESTAMP, DEFAULT_TOLERANCE_SECONDS).unwrap();
+        let charge = event.charge().unwrap();
+        assert_eq!(charge.id, "ch_1GU3XbHb0zBq6YkKwL8kZt2n");
+        assert_eq!(charge.amount_refunded, 2500);
+        assert_eq!(charge.refunds.data.len(), 1);
+        assert_eq!(charge.refunds.data[0].id, "re_1GU3ZkHb0zBq6YkK9mUuVw2c");
+    }
+}